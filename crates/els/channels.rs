@@ -4,17 +4,23 @@ use erg_compiler::artifact::BuildRunnable;
 use erg_compiler::erg_parser::parse::Parsable;
 
 use lsp_types::request::{
-    CodeActionRequest, CodeActionResolveRequest, CodeLensRequest, Completion, ExecuteCommand,
-    GotoDefinition, HoverRequest, InlayHintRequest, References, ResolveCompletionItem,
-    SemanticTokensFullRequest, SignatureHelpRequest, WillRenameFiles,
+    CodeActionRequest, CodeActionResolveRequest, CodeLensRequest, Completion,
+    DocumentHighlightRequest, DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest,
+    GotoDefinition, HoverRequest, InlayHintRequest, OnTypeFormatting, References,
+    ResolveCompletionItem, SelectionRangeRequest, SemanticTokensFullDeltaRequest,
+    SemanticTokensFullRequest, SignatureHelpRequest, WillRenameFiles, WorkspaceSymbol,
 };
 use lsp_types::{
     CodeAction, CodeActionParams, CodeLensParams, CompletionItem, CompletionParams,
-    ExecuteCommandParams, GotoDefinitionParams, HoverParams, InlayHintParams, ReferenceParams,
-    RenameFilesParams, SemanticTokensParams, SignatureHelpParams,
+    DocumentHighlightParams, DocumentOnTypeFormattingParams, DocumentSymbolParams,
+    ExecuteCommandParams, FoldingRangeParams, GotoDefinitionParams, HoverParams, InlayHintParams,
+    ReferenceParams, RenameFilesParams, SelectionRangeParams, SemanticTokensDeltaParams,
+    SemanticTokensParams, SignatureHelpParams, WorkspaceSymbolParams,
 };
 
+use crate::desugared::{ViewDesugared, ViewDesugaredParams};
 use crate::server::Server;
+use crate::test_runner::{ListTests, ListTestsParams};
 
 #[derive(Debug, Clone)]
 pub struct SendChannels {
@@ -22,15 +28,24 @@ pub struct SendChannels {
     resolve_completion: mpsc::Sender<(i64, CompletionItem)>,
     goto_definition: mpsc::Sender<(i64, GotoDefinitionParams)>,
     semantic_tokens_full: mpsc::Sender<(i64, SemanticTokensParams)>,
+    semantic_tokens_full_delta: mpsc::Sender<(i64, SemanticTokensDeltaParams)>,
     inlay_hint: mpsc::Sender<(i64, InlayHintParams)>,
     hover: mpsc::Sender<(i64, HoverParams)>,
     references: mpsc::Sender<(i64, ReferenceParams)>,
+    document_highlight: mpsc::Sender<(i64, DocumentHighlightParams)>,
     code_lens: mpsc::Sender<(i64, CodeLensParams)>,
     code_action: mpsc::Sender<(i64, CodeActionParams)>,
     code_action_resolve: mpsc::Sender<(i64, CodeAction)>,
     signature_help: mpsc::Sender<(i64, SignatureHelpParams)>,
     will_rename_files: mpsc::Sender<(i64, RenameFilesParams)>,
     execute_command: mpsc::Sender<(i64, ExecuteCommandParams)>,
+    workspace_symbol: mpsc::Sender<(i64, WorkspaceSymbolParams)>,
+    on_type_formatting: mpsc::Sender<(i64, DocumentOnTypeFormattingParams)>,
+    selection_range: mpsc::Sender<(i64, SelectionRangeParams)>,
+    folding_range: mpsc::Sender<(i64, FoldingRangeParams)>,
+    document_symbol: mpsc::Sender<(i64, DocumentSymbolParams)>,
+    view_desugared: mpsc::Sender<(i64, ViewDesugaredParams)>,
+    list_tests: mpsc::Sender<(i64, ListTestsParams)>,
 }
 
 impl SendChannels {
@@ -39,45 +54,72 @@ impl SendChannels {
         let (tx_resolve_completion, rx_resolve_completion) = mpsc::channel();
         let (tx_goto_definition, rx_goto_definition) = mpsc::channel();
         let (tx_semantic_tokens_full, rx_semantic_tokens_full) = mpsc::channel();
+        let (tx_semantic_tokens_full_delta, rx_semantic_tokens_full_delta) = mpsc::channel();
         let (tx_inlay_hint, rx_inlay_hint) = mpsc::channel();
         let (tx_hover, rx_hover) = mpsc::channel();
         let (tx_references, rx_references) = mpsc::channel();
+        let (tx_document_highlight, rx_document_highlight) = mpsc::channel();
         let (tx_code_lens, rx_code_lens) = mpsc::channel();
         let (tx_code_action, rx_code_action) = mpsc::channel();
         let (tx_code_action_resolve, rx_code_action_resolve) = mpsc::channel();
         let (tx_sig_help, rx_sig_help) = mpsc::channel();
         let (tx_will_rename_files, rx_will_rename_files) = mpsc::channel();
         let (tx_execute_command, rx_execute_command) = mpsc::channel();
+        let (tx_workspace_symbol, rx_workspace_symbol) = mpsc::channel();
+        let (tx_on_type_formatting, rx_on_type_formatting) = mpsc::channel();
+        let (tx_selection_range, rx_selection_range) = mpsc::channel();
+        let (tx_folding_range, rx_folding_range) = mpsc::channel();
+        let (tx_document_symbol, rx_document_symbol) = mpsc::channel();
+        let (tx_view_desugared, rx_view_desugared) = mpsc::channel();
+        let (tx_list_tests, rx_list_tests) = mpsc::channel();
         (
             Self {
                 completion: tx_completion,
                 resolve_completion: tx_resolve_completion,
                 goto_definition: tx_goto_definition,
                 semantic_tokens_full: tx_semantic_tokens_full,
+                semantic_tokens_full_delta: tx_semantic_tokens_full_delta,
                 inlay_hint: tx_inlay_hint,
                 hover: tx_hover,
                 references: tx_references,
+                document_highlight: tx_document_highlight,
                 code_lens: tx_code_lens,
                 code_action: tx_code_action,
                 code_action_resolve: tx_code_action_resolve,
                 signature_help: tx_sig_help,
                 will_rename_files: tx_will_rename_files,
                 execute_command: tx_execute_command,
+                workspace_symbol: tx_workspace_symbol,
+                on_type_formatting: tx_on_type_formatting,
+                selection_range: tx_selection_range,
+                folding_range: tx_folding_range,
+                document_symbol: tx_document_symbol,
+                view_desugared: tx_view_desugared,
+                list_tests: tx_list_tests,
             },
             ReceiveChannels {
                 completion: rx_completion,
                 resolve_completion: rx_resolve_completion,
                 goto_definition: rx_goto_definition,
                 semantic_tokens_full: rx_semantic_tokens_full,
+                semantic_tokens_full_delta: rx_semantic_tokens_full_delta,
                 inlay_hint: rx_inlay_hint,
                 hover: rx_hover,
                 references: rx_references,
+                document_highlight: rx_document_highlight,
                 code_lens: rx_code_lens,
                 code_action: rx_code_action,
                 code_action_resolve: rx_code_action_resolve,
                 signature_help: rx_sig_help,
                 will_rename_files: rx_will_rename_files,
                 execute_command: rx_execute_command,
+                workspace_symbol: rx_workspace_symbol,
+                on_type_formatting: rx_on_type_formatting,
+                selection_range: rx_selection_range,
+                folding_range: rx_folding_range,
+                document_symbol: rx_document_symbol,
+                view_desugared: rx_view_desugared,
+                list_tests: rx_list_tests,
             },
         )
     }
@@ -89,15 +131,24 @@ pub struct ReceiveChannels {
     pub(crate) resolve_completion: mpsc::Receiver<(i64, CompletionItem)>,
     pub(crate) goto_definition: mpsc::Receiver<(i64, GotoDefinitionParams)>,
     pub(crate) semantic_tokens_full: mpsc::Receiver<(i64, SemanticTokensParams)>,
+    pub(crate) semantic_tokens_full_delta: mpsc::Receiver<(i64, SemanticTokensDeltaParams)>,
     pub(crate) inlay_hint: mpsc::Receiver<(i64, InlayHintParams)>,
     pub(crate) hover: mpsc::Receiver<(i64, HoverParams)>,
     pub(crate) references: mpsc::Receiver<(i64, ReferenceParams)>,
+    pub(crate) document_highlight: mpsc::Receiver<(i64, DocumentHighlightParams)>,
     pub(crate) code_lens: mpsc::Receiver<(i64, CodeLensParams)>,
     pub(crate) code_action: mpsc::Receiver<(i64, CodeActionParams)>,
     pub(crate) code_action_resolve: mpsc::Receiver<(i64, CodeAction)>,
     pub(crate) signature_help: mpsc::Receiver<(i64, SignatureHelpParams)>,
     pub(crate) will_rename_files: mpsc::Receiver<(i64, RenameFilesParams)>,
     pub(crate) execute_command: mpsc::Receiver<(i64, ExecuteCommandParams)>,
+    pub(crate) workspace_symbol: mpsc::Receiver<(i64, WorkspaceSymbolParams)>,
+    pub(crate) on_type_formatting: mpsc::Receiver<(i64, DocumentOnTypeFormattingParams)>,
+    pub(crate) selection_range: mpsc::Receiver<(i64, SelectionRangeParams)>,
+    pub(crate) folding_range: mpsc::Receiver<(i64, FoldingRangeParams)>,
+    pub(crate) document_symbol: mpsc::Receiver<(i64, DocumentSymbolParams)>,
+    pub(crate) view_desugared: mpsc::Receiver<(i64, ViewDesugaredParams)>,
+    pub(crate) list_tests: mpsc::Receiver<(i64, ListTestsParams)>,
 }
 
 pub trait Sendable<R: lsp_types::request::Request + 'static> {
@@ -129,12 +180,33 @@ impl_sendable!(
     SemanticTokensParams,
     semantic_tokens_full
 );
+impl_sendable!(
+    SemanticTokensFullDeltaRequest,
+    SemanticTokensDeltaParams,
+    semantic_tokens_full_delta
+);
 impl_sendable!(InlayHintRequest, InlayHintParams, inlay_hint);
 impl_sendable!(HoverRequest, HoverParams, hover);
 impl_sendable!(References, ReferenceParams, references);
+impl_sendable!(
+    DocumentHighlightRequest,
+    DocumentHighlightParams,
+    document_highlight
+);
 impl_sendable!(CodeLensRequest, CodeLensParams, code_lens);
 impl_sendable!(CodeActionRequest, CodeActionParams, code_action);
 impl_sendable!(CodeActionResolveRequest, CodeAction, code_action_resolve);
 impl_sendable!(SignatureHelpRequest, SignatureHelpParams, signature_help);
 impl_sendable!(WillRenameFiles, RenameFilesParams, will_rename_files);
 impl_sendable!(ExecuteCommand, ExecuteCommandParams, execute_command);
+impl_sendable!(WorkspaceSymbol, WorkspaceSymbolParams, workspace_symbol);
+impl_sendable!(
+    OnTypeFormatting,
+    DocumentOnTypeFormattingParams,
+    on_type_formatting
+);
+impl_sendable!(SelectionRangeRequest, SelectionRangeParams, selection_range);
+impl_sendable!(FoldingRangeRequest, FoldingRangeParams, folding_range);
+impl_sendable!(DocumentSymbolRequest, DocumentSymbolParams, document_symbol);
+impl_sendable!(ViewDesugared, ViewDesugaredParams, view_desugared);
+impl_sendable!(ListTests, ListTestsParams, list_tests);
@@ -1,19 +1,23 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use erg_common::consts::{ERG_MODE, PYTHON_MODE};
 use erg_common::deepen_indent;
-use erg_common::traits::{Locational, Stream};
+use erg_common::traits::{DequeStream, Locational, Stream};
+use erg_common::Str;
 use erg_compiler::artifact::BuildRunnable;
 use erg_compiler::erg_parser::parse::Parsable;
-use erg_compiler::erg_parser::token::{Token, TokenKind};
+use erg_compiler::erg_parser::token::{Token, TokenKind, TokenStream};
 use erg_compiler::hir::Expr;
-use erg_compiler::ty::HasType;
+use erg_compiler::ty::{HasType, Type};
 
 use lsp_types::{
     CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse,
-    Position, Range, TextEdit, Url, WorkspaceEdit,
+    Diagnostic, Position, Range, TextEdit, Url, WorkspaceEdit,
 };
 
+use crate::diagnostics::SuggestedEdit;
+use crate::hir_visitor::HIRVisitor;
 use crate::server::{send_log, ELSResult, Server};
 use crate::util::{self, NormalizedUrl};
 
@@ -32,6 +36,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             send_log("visitor not found")?;
             return Ok(None);
         };
+        let result_uri = uri.clone();
         let Some(result) = self.analysis_result.get(&uri) else {
             send_log("artifact not found")?;
             return Ok(None);
@@ -56,6 +61,14 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                         send_log("range not found")?;
                         continue;
                     };
+                    // the trailing comment is a part of the definition
+                    let trailing = (uri == result_uri)
+                        .then(|| result.ast.trivia().trailing_comment(&def.loc()))
+                        .flatten()
+                        .and_then(|comment| util::loc_to_range(comment.loc));
+                    if let Some(comment_range) = trailing {
+                        range.end = comment_range.end;
+                    }
                     let next = lsp_types::Range {
                         start: lsp_types::Position {
                             line: range.end.line,
@@ -138,6 +151,50 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         Some(action)
     }
 
+    /// Applies the edits suggested by the compiler (e.g. `x` is private variable -> define `x` as `.x`).
+    /// An edit to another file is also offered in that file, at the edited position.
+    fn gen_suggested_actions(&self, params: &CodeActionParams) -> Vec<CodeAction> {
+        let mut actions = vec![];
+        let mut seen = vec![];
+        for diag in params.context.diagnostics.iter() {
+            let Some(edits) = diag
+                .data
+                .clone()
+                .and_then(|data| serde_json::from_value::<Vec<SuggestedEdit>>(data).ok())
+            else {
+                continue;
+            };
+            for edit in edits {
+                actions.push(Self::suggested_edit_to_action(
+                    edit.clone(),
+                    Some(diag.clone()),
+                ));
+                seen.push(edit);
+            }
+        }
+        let uri = NormalizedUrl::new(params.text_document.uri.clone());
+        for edit in self.analysis_result.get_suggested_edits_to(&uri) {
+            let pos = edit.edit.range.start;
+            if params.range.start <= pos && pos <= params.range.end && !seen.contains(&edit) {
+                actions.push(Self::suggested_edit_to_action(edit.clone(), None));
+                seen.push(edit);
+            }
+        }
+        actions
+    }
+
+    fn suggested_edit_to_action(edit: SuggestedEdit, diag: Option<Diagnostic>) -> CodeAction {
+        let mut map = HashMap::new();
+        map.insert(edit.uri, vec![edit.edit]);
+        CodeAction {
+            title: edit.title,
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: diag.map(|diag| vec![diag]),
+            edit: Some(WorkspaceEdit::new(map)),
+            ..Default::default()
+        }
+    }
+
     fn gen_extract_action(&self, params: &CodeActionParams) -> Vec<CodeAction> {
         let mut actions = vec![];
         if params.range.start.line == params.range.end.line {
@@ -213,7 +270,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     }
 
     fn send_quick_fix(&self, params: &CodeActionParams) -> ELSResult<Vec<CodeAction>> {
-        let mut result: Vec<CodeAction> = vec![];
+        let mut result = self.gen_suggested_actions(params);
         let diags = &params.context.diagnostics;
         if diags.is_empty() {
             return Ok(result);
@@ -268,6 +325,32 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         }
     }
 
+    fn collect_free_vars(&self, uri: &NormalizedUrl, range: Range) -> (Vec<(Str, Type)>, bool) {
+        let (Some(visitor), Some(stream), Some(mod_ctx)) = (
+            self.get_visitor(uri),
+            self.file_cache.get_token_stream(uri),
+            self.modules.get(uri),
+        ) else {
+            return (vec![], false);
+        };
+        let path = util::uri_to_path(uri);
+        collect_free_vars(&visitor, &stream, &mod_ctx.context.name, &path, range)
+    }
+
+    /// The first line of the top-level statement containing `line` (both are 0-origin).
+    /// `None` if `line` is the first line of a top-level statement.
+    fn top_level_stmt_line(&self, uri: &NormalizedUrl, line: u32) -> Option<u32> {
+        let hir = self.analysis_result.get_hir(uri)?;
+        let line = line + 1;
+        let stmt = hir.module.iter().find(|expr| {
+            let (Some(ln_begin), Some(ln_end)) = (expr.ln_begin(), expr.ln_end()) else {
+                return false;
+            };
+            ln_begin < line && line <= ln_end
+        })?;
+        stmt.ln_begin().map(|ln| ln.saturating_sub(1))
+    }
+
     fn resolve_extract_action(&self, mut action: CodeAction) -> ELSResult<CodeAction> {
         let params = action
             .data
@@ -292,9 +375,33 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         } else {
             code
         };
+        // A function extracted from a local scope is defined at the top level,
+        // and the local variables it refers to are passed as arguments.
+        let (free_vars, is_proc) = if extract_function {
+            self.collect_free_vars(&uri, params.range)
+        } else {
+            (vec![], false)
+        };
+        let top_level_line = if extract_function && indent_len > 0 {
+            self.top_level_stmt_line(&uri, params.range.start.line)
+        } else {
+            None
+        };
+        let code = if top_level_line.is_some() {
+            code.lines()
+                .map(|line| {
+                    let dedent = line.chars().take(indent_len).take_while(|c| *c == ' ');
+                    &line[dedent.count()..]
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            code
+        };
         let body = if extract_function {
             let mut code = deepen_indent(code);
-            let should_insert_indent = code.lines().count() == 1 && !diff_is_indent;
+            let should_insert_indent =
+                code.lines().count() == 1 && !diff_is_indent && top_level_line.is_none();
             if should_insert_indent {
                 code = format!("{}{code}", " ".repeat(diff_indent_len));
             }
@@ -313,24 +420,49 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         } else {
             code.trim_start().to_string()
         };
+        // procedures must be named with `!` in Erg
+        let func_name = if ERG_MODE && is_proc {
+            "new_proc!"
+        } else {
+            "new_func"
+        };
+        let args = free_vars
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let params = free_vars
+            .iter()
+            .map(|(name, t)| {
+                if ERG_MODE && t.has_no_unbound_var() && !t.has_qvar() {
+                    format!("{name}: {t}")
+                } else {
+                    name.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
         let sig = match (ERG_MODE, extract_function) {
-            (true, true) => "new_func() =\n",
-            (true, false) => "new_var = ",
-            (false, true) => "def new_func():\n",
-            (false, false) => "new_var = ",
+            (true, true) => format!("{func_name}({params}) =\n"),
+            (false, true) => format!("def {func_name}({params}):\n"),
+            (_, false) => "new_var = ".to_string(),
         };
         let expanded = if extract_function {
-            "new_func()"
+            format!("{func_name}({args})")
         } else {
-            "new_var"
+            "new_var".to_string()
         };
         let expanded = if range.start.character == 0 {
             format!("{}{expanded}", " ".repeat(indent_len))
         } else {
-            expanded.to_string()
+            expanded
+        };
+        let (def_line, def_indent_len) = match top_level_line {
+            Some(line) => (line, 0),
+            None => (range.start.line, indent_len),
         };
-        let extracted = format!("{}{sig}{body}\n\n", " ".repeat(indent_len));
-        let start = Position::new(range.start.line, 0);
+        let extracted = format!("{}{sig}{body}\n\n", " ".repeat(def_indent_len));
+        let start = Position::new(def_line, 0);
         let edit1 = TextEdit::new(Range::new(start, start), extracted);
         let edit2 = TextEdit::new(Range::new(range.start, range.end), expanded);
         let mut changes = HashMap::new();
@@ -400,3 +532,47 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         changes
     }
 }
+
+/// Collects the variables referenced in `range` and defined in the enclosing local scopes (not in the module scope),
+/// which should be passed to the extracted function.
+/// Also returns whether the code has side effects (i.e. the extracted subroutine should be a procedure).
+pub(crate) fn collect_free_vars(
+    visitor: &HIRVisitor,
+    stream: &TokenStream,
+    module_name: &str,
+    path: &Path,
+    range: Range,
+) -> (Vec<(Str, Type)>, bool) {
+    let mut free_vars: Vec<(Str, Type)> = vec![];
+    let mut is_proc = false;
+    let in_range = |pos: Position| range.start <= pos && pos < range.end;
+    let mut prev_is_dot = false;
+    for token in stream.iter() {
+        let is_attr = prev_is_dot;
+        prev_is_dot = token.is(TokenKind::Dot);
+        let pos = Position::new(token.lineno.saturating_sub(1), token.col_begin);
+        if !token.is(TokenKind::Symbol) || !in_range(pos) {
+            continue;
+        }
+        let vi = visitor.get_info(token);
+        if token.content.ends_with('!') || vi.as_ref().is_some_and(|vi| vi.t.is_procedure()) {
+            is_proc = true;
+        }
+        let Some(vi) = vi else {
+            continue;
+        };
+        let is_local =
+            vi.def_loc.module.as_deref() == Some(path) && &vi.vis.def_namespace[..] != module_name;
+        let defined_inside =
+            util::loc_to_range(vi.def_loc.loc).is_some_and(|def_range| in_range(def_range.start));
+        if is_attr
+            || !is_local
+            || defined_inside
+            || free_vars.iter().any(|(name, _)| name == &token.content)
+        {
+            continue;
+        }
+        free_vars.push((token.content.clone(), vi.t.derefine()));
+    }
+    (free_vars, is_proc)
+}
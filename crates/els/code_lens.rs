@@ -1,9 +1,14 @@
+use erg_common::consts::PYTHON_MODE;
+use erg_common::traits::Locational;
+
 use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::context::Context;
 use erg_compiler::erg_parser::parse::Parsable;
 use erg_compiler::hir::Expr;
 
-use lsp_types::{CodeLens, CodeLensParams};
+use lsp_types::{CodeLens, CodeLensParams, Location, Range};
 
+use crate::references::abs_loc_to_lsp_loc;
 use crate::server::{send_log, ELSResult, Server};
 use crate::util::{self, NormalizedUrl};
 
@@ -16,6 +21,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         let uri = NormalizedUrl::new(params.text_document.uri);
         // TODO: parallelize
         let result = [
+            self.send_run_lens(&uri)?,
             self.send_trait_impls_lens(&uri)?,
             self.send_class_inherits_lens(&uri)?,
         ]
@@ -23,8 +29,45 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         Ok(Some(result))
     }
 
+    /// "Run" and "Check" on the first statement of a module
+    fn send_run_lens(&mut self, uri: &NormalizedUrl) -> ELSResult<Vec<CodeLens>> {
+        let path = util::uri_to_path(uri);
+        // declaration files cannot be run
+        if path.to_string_lossy().ends_with(".d.er") {
+            return Ok(vec![]);
+        }
+        let Some(hir) = self.analysis_result.get_hir(uri) else {
+            return Ok(vec![]);
+        };
+        let range = hir
+            .module
+            .iter()
+            .next()
+            .and_then(|chunk| util::loc_to_range(chunk.loc()))
+            .map_or(Range::default(), |range| {
+                Range::new(range.start, range.start)
+            });
+        let mut result = vec![];
+        if !PYTHON_MODE {
+            result.push(CodeLens {
+                range,
+                command: Some(self.gen_run_command(uri)?),
+                data: None,
+            });
+        }
+        result.push(CodeLens {
+            range,
+            command: Some(self.gen_check_command(uri)?),
+            data: None,
+        });
+        Ok(result)
+    }
+
     fn send_trait_impls_lens(&mut self, uri: &NormalizedUrl) -> ELSResult<Vec<CodeLens>> {
         let mut result = vec![];
+        let Some(module) = self.modules.get(uri) else {
+            return Ok(result);
+        };
         if let Some(hir) = self.analysis_result.get_hir(uri) {
             for chunk in hir.module.iter() {
                 match chunk {
@@ -33,7 +76,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                         let Some(range) = util::loc_to_range(trait_loc.loc) else {
                             continue;
                         };
-                        let command = self.gen_show_trait_impls_command(trait_loc.clone())?;
+                        let impls = get_trait_impls(&module.context, def.sig.ident().inspect());
+                        let command =
+                            self.gen_show_trait_impls_command(trait_loc.clone(), impls)?;
                         let lens = CodeLens {
                             range,
                             command,
@@ -52,3 +97,31 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         Ok(vec![])
     }
 }
+
+/// The definitions of the types implementing the trait `trait_name` (registered in `SharedTraitImpls`)
+pub fn get_trait_impls(ctx: &Context, trait_name: &str) -> Vec<Location> {
+    let Some(trait_ctx) = ctx.get_receiver_ctx(trait_name) else {
+        return vec![];
+    };
+    let Some(impls) = ctx.trait_impls().get(&trait_ctx.name) else {
+        return vec![];
+    };
+    let mut locs = impls
+        .iter()
+        .filter_map(|imp| {
+            // the types of the module itself are not found by the namespace (`<module>`)
+            ctx.get_type_info(&imp.sub_type)
+                .or_else(|| ctx.get_var_info(&imp.sub_type.local_name()))
+        })
+        .filter_map(|(_, vi)| abs_loc_to_lsp_loc(&vi.def_loc))
+        .collect::<Vec<_>>();
+    locs.sort_by_key(|loc| {
+        (
+            loc.uri.to_string(),
+            loc.range.start.line,
+            loc.range.start.character,
+        )
+    });
+    locs.dedup();
+    locs
+}
@@ -1,46 +1,105 @@
+use std::path::PathBuf;
+use std::process;
+
 use erg_compiler::erg_parser::parse::Parsable;
 use erg_compiler::varinfo::AbsLocation;
+use serde_json::json;
 use serde_json::Value;
 
 use erg_compiler::artifact::BuildRunnable;
-use erg_compiler::hir::Expr;
 
 use lsp_types::{Command, ExecuteCommandParams, Location, Url};
 
 use crate::_log;
-use crate::server::{ELSResult, Server};
+use crate::message::LogMessage;
+use crate::server::{send, send_error_info, send_info, ELSResult, Server};
 use crate::util::{self, NormalizedUrl};
 
+/// The `erg` executable used to run scripts.
+/// The language server is usually launched by `erg --language-server`, otherwise `erg` is searched in `PATH`.
+pub(crate) fn erg_command() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .filter(|exe| exe.file_stem().is_some_and(|stem| stem == "erg"))
+        .unwrap_or_else(|| PathBuf::from("erg"))
+}
+
 impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     pub(crate) fn handle_execute_command(
         &mut self,
         params: ExecuteCommandParams,
     ) -> ELSResult<Option<Value>> {
         _log!("command requested: {}", params.command);
-        #[allow(clippy::match_single_binding)]
-        match &params.command[..] {
-            other => {
-                _log!("unknown command {other}: {params:?}");
+        let command = params
+            .command
+            .strip_prefix(self.mode())
+            .and_then(|command| command.strip_prefix('.'));
+        let uri = params
+            .arguments
+            .first()
+            .and_then(|arg| serde_json::from_value::<Url>(arg.clone()).ok())
+            .map(NormalizedUrl::new);
+        match (command, uri) {
+            (Some("run"), Some(uri)) => self.run_module(&uri),
+            (Some("runTest"), Some(uri)) => {
+                let Some(name) = params.arguments.get(1).and_then(|name| name.as_str()) else {
+                    _log!("the test name is not passed: {params:?}");
+                    return Ok(None);
+                };
+                self.run_test(&uri, name)
+            }
+            (Some("check"), Some(uri)) => {
+                let code = self.file_cache.get_entire_code(&uri)?;
+                self.check_file(uri, code)?;
                 Ok(None)
             }
+            _ => {
+                _log!("unknown command {}: {params:?}", params.command);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Runs the module with `erg` and reports the output as a log message.
+    fn run_module(&self, uri: &NormalizedUrl) -> ELSResult<Option<Value>> {
+        let path = util::uri_to_path(uri);
+        let output = process::Command::new(erg_command()).arg(&path).output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        send(&LogMessage::new(format!("{stdout}{stderr}")))?;
+        if output.status.success() {
+            send_info(format!("{} exited successfully", path.display()))?;
+        } else {
+            send_error_info(format!("{} exited with {}", path.display(), output.status))?;
         }
+        Ok(Some(json!({
+            "status": output.status.code(),
+            "stdout": stdout,
+            "stderr": stderr,
+        })))
+    }
+
+    pub(crate) fn gen_run_command(&self, uri: &NormalizedUrl) -> ELSResult<Command> {
+        Ok(Command {
+            title: "Run".to_string(),
+            command: format!("{}.run", self.mode()),
+            arguments: Some(vec![serde_json::to_value(uri.clone().raw())?]),
+        })
+    }
+
+    pub(crate) fn gen_check_command(&self, uri: &NormalizedUrl) -> ELSResult<Command> {
+        Ok(Command {
+            title: "Check".to_string(),
+            command: format!("{}.check", self.mode()),
+            arguments: Some(vec![serde_json::to_value(uri.clone().raw())?]),
+        })
     }
 
     pub(crate) fn gen_show_trait_impls_command(
         &self,
         trait_loc: AbsLocation,
+        impls: Vec<Location>,
     ) -> ELSResult<Option<Command>> {
-        let refs = self.get_refs_from_abs_loc(&trait_loc);
-        let filter = |loc: Location| {
-            let uri = NormalizedUrl::new(loc.uri.clone());
-            let token = self.file_cache.get_token(&uri, loc.range.start)?;
-            let opt_visitor = self.get_visitor(&uri);
-            let min_expr = opt_visitor
-                .as_ref()
-                .and_then(|visitor| visitor.get_min_expr(&token))?;
-            matches!(min_expr, Expr::ClassDef(_)).then_some(loc)
-        };
-        let impls = refs.into_iter().filter_map(filter).collect::<Vec<_>>();
         let impl_len = impls.len();
         let locations = serde_json::to_value(impls)?;
         let Ok(uri) = trait_loc.module.ok_or(()).and_then(Url::from_file_path) else {
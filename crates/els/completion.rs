@@ -21,14 +21,16 @@ use erg_compiler::context::Context;
 use erg_compiler::erg_parser::token::TokenKind;
 use erg_compiler::hir::Expr;
 use erg_compiler::module::SharedCompilerResource;
+use erg_compiler::ty::constructors::{poly, ty_tp};
 use erg_compiler::ty::{HasType, ParamTy, Type};
 use erg_compiler::varinfo::{AbsLocation, VarInfo};
 use erg_compiler::HIRBuilder;
 use TokenKind::*;
 
 use lsp_types::{
-    CompletionItem, CompletionItemKind, CompletionParams, Documentation, MarkedString,
-    MarkupContent, MarkupKind, Position, Range, TextEdit,
+    CompletionItem, CompletionItemKind, CompletionParams, CompletionTextEdit, Documentation,
+    InsertTextFormat, InsertTextMode, MarkedString, MarkupContent, MarkupKind, Position, Range,
+    TextEdit,
 };
 
 use crate::server::{send_log, ELSResult, Server};
@@ -309,6 +311,59 @@ fn module_completions() -> Vec<CompletionItem> {
     comps
 }
 
+/// The postfix templates applicable to a receiver of type `receiver_t`, as (label, snippet).
+/// `{}` in the snippet is replaced with the receiver.
+fn postfix_templates(receiver_t: &Type, mod_ctx: &Context) -> Vec<(&'static str, &'static str)> {
+    let mut templates = vec![("match", "match {}:\n    ${1:_} -> $0")];
+    // `Never` is a subtype of any type
+    if !matches!(receiver_t, Type::Never | Type::Failure) {
+        if mod_ctx.subtype_of(receiver_t, &Type::Bool) {
+            templates.push(("if", "if {}:\n    $0"));
+        }
+        if mod_ctx.subtype_of(receiver_t, &poly("Iterable", vec![ty_tp(Type::Obj)])) {
+            templates.push(("for!", "for! {}, ${1:i} =>\n    $0"));
+        }
+    }
+    templates.push(("print!", "print! {}"));
+    templates
+}
+
+/// The postfix completion items for `receiver` (its source code is `code`) followed by the cursor at `pos`.
+pub(crate) fn postfix_items(
+    receiver: &Expr,
+    code: &str,
+    pos: Position,
+    mod_ctx: &Context,
+) -> Vec<CompletionItem> {
+    let Some(receiver_range) = util::loc_to_range(receiver.loc()) else {
+        return vec![];
+    };
+    // the text edit of a completion item must be in the line of the cursor
+    if receiver_range.start.line != pos.line {
+        return vec![];
+    }
+    let range = Range::new(receiver_range.start, pos);
+    let mut comps = vec![];
+    for (label, snippet) in postfix_templates(&receiver.t(), mod_ctx) {
+        let mut item = CompletionItem::new_simple(label.to_string(), "postfix".to_string());
+        item.kind = Some(CompletionItemKind::SNIPPET);
+        item.sort_text = Some(format!("{}_{}", CompletionOrder::STD_ITEM, item.label));
+        item.filter_text = Some(format!("{code}.{label}"));
+        item.insert_text_format = Some(InsertTextFormat::SNIPPET);
+        item.insert_text_mode = Some(InsertTextMode::ADJUST_INDENTATION);
+        let new_text = snippet.replace("{}", &escape_snippet(code));
+        item.text_edit = Some(CompletionTextEdit::Edit(TextEdit::new(range, new_text)));
+        comps.push(item);
+    }
+    comps
+}
+
+fn escape_snippet(code: &str) -> String {
+    code.replace('\\', "\\\\")
+        .replace('$', "\\$")
+        .replace('}', "\\}")
+}
+
 fn load_modules<'a>(
     cfg: ErgConfig,
     cache: Cache,
@@ -460,6 +515,28 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         comps
     }
 
+    /// Returns postfix completions (e.g. `x.match`), which rewrite the receiver `x.` into the construct
+    fn postfix_completion(
+        &self,
+        uri: &NormalizedUrl,
+        pos: Position,
+        receiver: &Expr,
+        mod_ctx: &Context,
+    ) -> Vec<CompletionItem> {
+        let Some(receiver_range) = util::loc_to_range(receiver.loc()) else {
+            return vec![];
+        };
+        // the other completion items are still returned if the receiver cannot be read
+        match self.file_cache.get_ranged(uri, receiver_range) {
+            Ok(Some(code)) => postfix_items(receiver, &code, pos, mod_ctx),
+            Ok(None) => vec![],
+            Err(err) => {
+                crate::_log!("failed to get the receiver: {err}");
+                vec![]
+            }
+        }
+    }
+
     pub(crate) fn handle_completion(
         &mut self,
         params: CompletionParams,
@@ -549,11 +626,12 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                 }
                 _ => None,
             });
-        let receiver_t = comp_kind
+        let receiver = comp_kind
             .should_be_method()
             .then(|| self.get_min_expr(&uri, pos, -2))
             .flatten()
-            .map(|(_, expr)| expr.t());
+            .map(|(_, expr)| expr);
+        let receiver_t = receiver.as_ref().map(|expr| expr.t());
         let Some(mod_ctx) = self.modules.get(&uri).map(|m| &m.context) else {
             return Ok(None);
         };
@@ -603,6 +681,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             }
             result.extend(self.neighbor_completion(&uri, arg_pt));
         }
+        if let Some(receiver) = receiver.as_ref().filter(|_| trigger == Some(".")) {
+            result.extend(self.postfix_completion(&uri, pos, receiver, mod_ctx));
+        }
         send_log(format!("completion items: {}", result.len()))?;
         Ok(Some(CompletionResponse::Array(result)))
     }
@@ -1,19 +1,133 @@
+use std::path::{Path, PathBuf};
+
 use erg_common::consts::PYTHON_MODE;
 use erg_common::traits::{Locational, Stream};
 use erg_compiler::artifact::BuildRunnable;
 use erg_compiler::context::register::PylyzerStatus;
 use erg_compiler::erg_parser::parse::Parsable;
 use erg_compiler::erg_parser::token::{Token, TokenCategory};
-use erg_compiler::hir::Expr;
+use erg_compiler::hir::{Accessor, Expr};
 use erg_compiler::ty::HasType;
 use erg_compiler::varinfo::VarInfo;
 
-use lsp_types::{GotoDefinitionParams, GotoDefinitionResponse, Url};
+use lsp_types::{GotoDefinitionParams, GotoDefinitionResponse, Position, Range, Url};
 
 use crate::server::{send_log, ELSResult, Server};
 use crate::util::{self, NormalizedUrl};
 
+fn indent_len(line: &str) -> usize {
+    line.chars().take_while(|c| c.is_whitespace()).count()
+}
+
+/// If `line` defines `name` (`def name`, `class name`, `name = ...` or `name: T`), returns the column of `name`
+fn py_def_col(line: &str, name: &str) -> Option<usize> {
+    let indent = indent_len(line);
+    let code = line.trim_start();
+    let (keyword, rest) = ["async def ", "def ", "class ", ""]
+        .into_iter()
+        .find_map(|keyword| Some((keyword, code.strip_prefix(keyword)?)))?;
+    let rest = rest.strip_prefix(name)?.trim_start();
+    let defined = if keyword.is_empty() {
+        (rest.starts_with('=') && !rest.starts_with("==")) || rest.starts_with(':')
+    } else {
+        rest.starts_with(['(', ':', '['])
+    };
+    defined.then_some(indent + keyword.len())
+}
+
+/// Finds the definition of `name` in the Python source (`.py` or `.pyi`) and returns the range of the name.
+/// If `class` is defined at the top level of `code`, the definition is searched in the class body.
+/// Otherwise, the top-level definitions are preferred (but the ones in `if` blocks, common in stubs, are also found).
+pub fn find_py_def(code: &str, name: &str, class: Option<&str>) -> Option<Range> {
+    let lines = code.lines().collect::<Vec<_>>();
+    let range = |ln: usize, col: usize| {
+        let col = col as u32;
+        Range::new(
+            Position::new(ln as u32, col),
+            Position::new(ln as u32, col + name.chars().count() as u32),
+        )
+    };
+    let class_body = class.and_then(|class| {
+        let class_ln = lines
+            .iter()
+            .position(|line| line.starts_with("class ") && py_def_col(line, class).is_some())?;
+        let body = lines
+            .iter()
+            .enumerate()
+            .skip(class_ln + 1)
+            .take_while(|(_, line)| line.trim().is_empty() || indent_len(line) > 0)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .collect::<Vec<_>>();
+        let body_indent = indent_len(body.first()?.1);
+        Some(
+            body.into_iter()
+                .filter(|(_, line)| indent_len(line) == body_indent)
+                .collect::<Vec<_>>(),
+        )
+    });
+    if let Some(body) = class_body {
+        return body
+            .into_iter()
+            .find_map(|(ln, line)| py_def_col(line, name).map(|col| range(ln, col)));
+    }
+    let defs = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(ln, line)| py_def_col(line, name).map(|col| (ln, col, indent_len(line))))
+        .collect::<Vec<_>>();
+    defs.iter()
+        .find(|(_, _, indent)| *indent == 0)
+        .or_else(|| defs.first())
+        .map(|(ln, col, _)| range(*ln, *col))
+}
+
 impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    /// The Python source of the declaration file `path`:
+    /// `path` itself if it is a stub (`.pyi`), or the file the declarations were generated from (by pylyzer)
+    pub(crate) fn py_source_of(&self, path: &Path) -> Option<PathBuf> {
+        if path.extension().is_some_and(|ext| ext == "pyi") {
+            return Some(path.to_path_buf());
+        }
+        if !path.to_string_lossy().ends_with(".d.er") {
+            return None;
+        }
+        let uri = NormalizedUrl::from_file_path(path).ok()?;
+        let header = self.file_cache.get_line(&uri, 0)?;
+        header.parse::<PylyzerStatus>().ok().map(|stat| stat.file)
+    }
+
+    /// The location of the definition of `vi` (named `name`).
+    /// The declarations of Python modules are mapped into the Python sources if possible,
+    /// because their locations are in the generated declaration files (or in the declarations converted from the stubs).
+    /// The Python name (`vi.py_name`, e.g. `foo` for `foo!`) is searched for there.
+    pub(crate) fn def_location(&self, vi: &VarInfo, name: &str) -> Option<lsp_types::Location> {
+        let path = vi.def_loc.module.as_ref()?;
+        let range = util::loc_to_range(vi.def_loc.loc);
+        if let Some(py_file) = self.py_source_of(path) {
+            let py_name = vi.py_name.as_deref().unwrap_or(name.trim_end_matches('!'));
+            // e.g. `foo.Bar` for the methods of `Bar`
+            let class = vi.vis.def_namespace.rsplit(['.', ':']).next();
+            let py_uri = Url::from_file_path(&py_file).ok()?;
+            let py_range = self
+                .file_cache
+                .get_entire_code(&NormalizedUrl::new(py_uri.clone()))
+                .ok()
+                .and_then(|code| find_py_def(&code, py_name, class));
+            if let Some(py_range) = py_range {
+                return Some(lsp_types::Location::new(py_uri, py_range));
+            }
+            // the locations in a converted stub are meaningless
+            if py_file == *path {
+                return Some(lsp_types::Location::new(py_uri, Range::default()));
+            }
+            if PYTHON_MODE {
+                return Some(lsp_types::Location::new(py_uri, range?));
+            }
+        }
+        let def_uri = Url::from_file_path(path).ok()?;
+        Some(lsp_types::Location::new(def_uri, range?))
+    }
+
     pub(crate) fn get_definition(
         &self,
         uri: &NormalizedUrl,
@@ -53,6 +167,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                                     .first()
                                     .and_then(|ctx| ctx.get_path_with_mod_t(&vi.t))
                                 {
+                                    let path = self.py_source_of(&path).unwrap_or(path);
                                     let mod_uri = Url::from_file_path(path).unwrap();
                                     let resp = GotoDefinitionResponse::Array(vec![
                                         lsp_types::Location::new(
@@ -71,62 +186,31 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                                     .and_then(|ctx| ctx.get_mod_with_t(mod_t))
                                     .and_then(|mod_ctx| mod_ctx.get_var_info(token.inspect()))
                                 {
-                                    let Some(path) = vi.def_loc.module.as_ref() else {
-                                        return Ok(GotoDefinitionResponse::Array(vec![]));
-                                    };
-                                    let def_uri = Url::from_file_path(path).unwrap();
-                                    let resp = GotoDefinitionResponse::Array(vec![
-                                        lsp_types::Location::new(
-                                            def_uri,
-                                            util::loc_to_range(vi.def_loc.loc).unwrap(),
-                                        ),
-                                    ]);
-                                    return Ok(resp);
+                                    let locs = self.def_location(vi, token.inspect());
+                                    return Ok(GotoDefinitionResponse::Array(
+                                        locs.into_iter().collect(),
+                                    ));
                                 }
                             }
                         } else if let Expr::Accessor(acc) = def.body.block.last().unwrap() {
-                            let vi = acc.var_info();
-                            match (&vi.def_loc.module, util::loc_to_range(vi.def_loc.loc)) {
-                                (Some(path), Some(range)) => {
-                                    let def_uri = NormalizedUrl::try_from(path.as_path()).unwrap();
-                                    let def_file = if PYTHON_MODE {
-                                        let header = self
-                                            .file_cache
-                                            .get_line(&def_uri, 0)
-                                            .unwrap_or_default();
-                                        let py_file = header
-                                            .parse::<PylyzerStatus>()
-                                            .ok()
-                                            .map(|stat| stat.file);
-                                        py_file.unwrap_or(path.clone())
-                                    } else {
-                                        path.clone()
-                                    };
-                                    let def_uri = Url::from_file_path(def_file).unwrap();
-                                    let resp = GotoDefinitionResponse::Array(vec![
-                                        lsp_types::Location::new(def_uri, range),
-                                    ]);
-                                    return Ok(resp);
-                                }
-                                _ => {
-                                    send_log("not found (maybe builtin)")?;
-                                    return Ok(GotoDefinitionResponse::Array(vec![]));
-                                }
+                            let name = match acc {
+                                Accessor::Ident(ident) => ident.inspect(),
+                                Accessor::Attr(attr) => attr.ident.inspect(),
+                            };
+                            if let Some(loc) = self.def_location(acc.var_info(), name) {
+                                return Ok(GotoDefinitionResponse::Array(vec![loc]));
+                            } else {
+                                send_log("not found (maybe builtin)")?;
+                                return Ok(GotoDefinitionResponse::Array(vec![]));
                             }
                         }
                     }
                 }
-                match (vi.def_loc.module, util::loc_to_range(vi.def_loc.loc)) {
-                    (Some(path), Some(range)) => {
-                        let def_uri = Url::from_file_path(path).unwrap();
-                        Ok(GotoDefinitionResponse::Array(vec![
-                            lsp_types::Location::new(def_uri, range),
-                        ]))
-                    }
-                    _ => {
-                        send_log("not found (maybe builtin)")?;
-                        Ok(GotoDefinitionResponse::Array(vec![]))
-                    }
+                if let Some(loc) = self.def_location(&vi, token.inspect()) {
+                    Ok(GotoDefinitionResponse::Array(vec![loc]))
+                } else {
+                    send_log("not found (maybe builtin)")?;
+                    Ok(GotoDefinitionResponse::Array(vec![]))
                 }
             } else {
                 Ok(GotoDefinitionResponse::Array(vec![]))
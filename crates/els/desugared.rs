@@ -0,0 +1,130 @@
+//! `erg/viewDesugared`: a custom request to show what the selected code is desugared into
+//! (e.g. pipelines, pattern parameters, and `match` arms), as the AST or as the HIR (with the types).
+
+use erg_common::traits::{Locational, Stream};
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::erg_parser::ast::{Expr, Module};
+use erg_compiler::erg_parser::parse::Parsable;
+use erg_compiler::hir::HIR;
+
+use lsp_types::request::Request;
+use lsp_types::{Range, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+
+use crate::server::{send_log, ELSResult, Server};
+use crate::util::{self, ASTNode, NormalizedUrl};
+
+#[derive(Debug)]
+pub enum ViewDesugared {}
+
+impl Request for ViewDesugared {
+    type Params = ViewDesugaredParams;
+    type Result = Option<String>;
+    const METHOD: &'static str = "erg/viewDesugared";
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DesugaredKind {
+    #[default]
+    Ast,
+    /// The HIR of the last check, so this is not available for the code that cannot be checked
+    Hir,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewDesugaredParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The whole module is shown if omitted
+    pub range: Option<Range>,
+    #[serde(default)]
+    pub kind: DesugaredKind,
+}
+
+fn overlaps(range: &Range, other: &Range) -> bool {
+    range.start <= other.end && other.start <= range.end
+}
+
+fn overlaps_loc(loc: &impl Locational, range: Option<Range>) -> bool {
+    range
+        .is_none_or(|range| util::loc_to_range(loc.loc()).is_some_and(|loc| overlaps(&loc, &range)))
+}
+
+/// The innermost node including the whole selection `range`.
+/// Desugared nodes may have unordered locations (e.g. `x |> f()` is `f(x)`), so this does not compare the ranges of the nodes,
+/// but descends while `range` overlaps only one child (and not the signature of a definition).
+fn inner_node<'a>(node: ASTNode<'a>, range: Range) -> ASTNode<'a> {
+    if let ASTNode::Def(def) | ASTNode::Expr(Expr::Def(def)) = node {
+        if overlaps_loc(&def.sig, Some(range)) {
+            return node;
+        }
+    }
+    let children = node
+        .children()
+        .into_iter()
+        .filter(|child| overlaps_loc(child, Some(range)))
+        .collect::<Vec<_>>();
+    match &children[..] {
+        [child] if !matches!(child, ASTNode::Leaf(_)) => inner_node(*child, range),
+        _ => node,
+    }
+}
+
+fn node_to_string(node: ASTNode) -> String {
+    match node {
+        ASTNode::Expr(expr) => expr.to_string(),
+        ASTNode::Def(def) => def.to_string(),
+        ASTNode::Methods(methods) => methods.to_string(),
+        ASTNode::Leaf(_) => String::new(),
+    }
+}
+
+/// Returns the desugared AST in `range` (the whole module if `None`).
+/// If `range` is a selection in a chunk, the innermost node including the selection is shown.
+/// Otherwise (e.g. `range` is just a cursor position), the chunks overlapping `range` are shown.
+pub fn desugared_ast(module: &Module, range: Option<Range>) -> String {
+    let chunks = module
+        .iter()
+        .filter(|chunk| overlaps_loc(*chunk, range))
+        .collect::<Vec<_>>();
+    match (&chunks[..], range) {
+        ([chunk], Some(range)) if range.start != range.end => {
+            node_to_string(inner_node(ASTNode::Expr(chunk), range))
+        }
+        _ => chunks
+            .iter()
+            .map(|chunk| chunk.to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Returns the chunks of `hir` overlapping `range` (the whole module if `None`)
+pub fn desugared_hir(hir: &HIR, range: Option<Range>) -> String {
+    hir.module
+        .iter()
+        .filter(|chunk| overlaps_loc(*chunk, range))
+        .map(|chunk| chunk.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn handle_view_desugared(
+        &mut self,
+        params: ViewDesugaredParams,
+    ) -> ELSResult<Option<String>> {
+        send_log(format!("view desugared requested: {params:?}"))?;
+        let uri = NormalizedUrl::new(params.text_document.uri);
+        match params.kind {
+            DesugaredKind::Ast => Ok(self
+                .get_partial_ast(&uri)
+                .map(|module| desugared_ast(&module, params.range))),
+            DesugaredKind::Hir => Ok(self
+                .analysis_result
+                .get_hir(&uri)
+                .map(|hir| desugared_hir(&hir, params.range))),
+        }
+    }
+}
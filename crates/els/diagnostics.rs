@@ -1,25 +1,136 @@
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use erg_common::consts::PYTHON_MODE;
 use erg_common::dict::Dict;
-use erg_common::fn_name;
+use erg_common::error::Suggestion;
+use erg_common::set::Set;
 use erg_common::spawn::spawn_new_thread;
 use erg_common::style::*;
+use erg_common::traits::Locational;
 use erg_common::traits::Stream;
+use erg_common::{fn_name, set, Str};
 use erg_compiler::artifact::BuildRunnable;
-use erg_compiler::erg_parser::ast::Module;
+use erg_compiler::erg_parser::ast::{Accessor, Def, Expr, Module};
 use erg_compiler::erg_parser::parse::Parsable;
-use erg_compiler::error::CompileErrors;
+use erg_compiler::error::{CompileError, CompileErrors};
+use erg_compiler::hir;
+use erg_compiler::hir::HIR;
 
 use lsp_types::{
-    Diagnostic, DiagnosticSeverity, NumberOrString, Position, PublishDiagnosticsParams, Range, Url,
+    Diagnostic, DiagnosticSeverity, NumberOrString, Position, PublishDiagnosticsParams, Range,
+    TextEdit, Url,
 };
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::diff::{ASTDiff, HIRDiff};
 use crate::server::{send, send_log, AnalysisResult, DefaultFeatures, ELSResult, Server};
-use crate::util::{self, NormalizedUrl};
+use crate::util::{self, ASTNode, NormalizedUrl};
+
+/// How long to wait after the last edit before checking the file automatically
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// A [`Suggestion`] of the compiler, converted for LSP.
+/// This is passed to the client as `Diagnostic.data` and comes back with the code action request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SuggestedEdit {
+    pub title: String,
+    pub uri: Url,
+    pub edit: TextEdit,
+}
+
+impl SuggestedEdit {
+    /// `uri`: the file where the error occurred
+    pub(crate) fn new(suggestion: &Suggestion, uri: &Url) -> Option<Self> {
+        let uri = if let Some(path) = &suggestion.path {
+            NormalizedUrl::from_file_path(path).ok()?.raw()
+        } else {
+            uri.clone()
+        };
+        let range = util::loc_to_range(suggestion.loc)?;
+        Some(Self {
+            title: suggestion.title.clone(),
+            uri,
+            edit: TextEdit::new(range, suggestion.replacement.clone()),
+        })
+    }
+}
+
+/// Whether the top-level chunk can be lowered again on its own.
+/// Type definitions and methods are excluded because they are linked with each other before lowering,
+/// and so are declarations because they are checked against the whole module.
+fn is_relowerable(chunk: &Expr) -> bool {
+    match chunk {
+        Expr::Def(def) => {
+            def.sig.ident().is_some() && def.def_kind().is_other() && !def.body.is_enum()
+        }
+        Expr::Methods(_) | Expr::ClassDef(_) | Expr::PatchDef(_) | Expr::TypeAscription(_) => false,
+        _ => true,
+    }
+}
+
+/// The names referred to in `chunk` (including the ones shadowed by local names)
+pub fn referred_names(chunk: &Expr) -> Set<Str> {
+    fn collect(node: ASTNode, names: &mut Set<Str>) {
+        if let ASTNode::Expr(Expr::Accessor(Accessor::Ident(ident))) = node {
+            names.insert(ident.inspect().clone());
+        }
+        for child in node.children() {
+            collect(child, names);
+        }
+    }
+    let mut names = set! {};
+    collect(ASTNode::Expr(chunk), &mut names);
+    names
+}
+
+/// Returns the index of the only top-level definition edited from `old` to `new`,
+/// if the other chunks are not changed at all (including their positions) and the definition keeps its name and lines.
+/// `Some(None)` means that nothing has been changed.
+pub fn edited_def(old: &Module, new: &Module) -> Option<Option<usize>> {
+    if old.len() != new.len() {
+        return None;
+    }
+    let mut edited = None;
+    for (i, (old, new)) in old.iter().zip(new.iter()).enumerate() {
+        if old == new && old.loc() == new.loc() {
+            continue;
+        }
+        let (Expr::Def(old), Expr::Def(new)) = (old, new) else {
+            return None;
+        };
+        let name_of = |def: &Def| {
+            def.sig
+                .ident()
+                .map(|ident| (ident.inspect().clone(), ident.vis.is_public()))
+        };
+        let same_name = name_of(old) == name_of(new);
+        let same_lines = old.ln_begin() == new.ln_begin() && old.ln_end() == new.ln_end();
+        if edited.is_some() || !same_name || !same_lines {
+            return None;
+        }
+        edited = Some(i);
+    }
+    Some(edited)
+}
+
+fn lines_of(chunk: &Expr) -> Option<(u32, u32)> {
+    Some((chunk.ln_begin()?, chunk.ln_end()?))
+}
+
+/// Replaces the chunks of `hir` in the lines `lines` with `chunk`
+fn patch_chunk(hir: &mut HIR, (begin, end): (u32, u32), chunk: Option<hir::Expr>) {
+    let chunks = hir.module.ref_mut_payload();
+    chunks.retain(|chunk| !chunk.ln_begin().is_some_and(|ln| begin <= ln && ln <= end));
+    if let Some(chunk) = chunk {
+        let idx = chunks
+            .iter()
+            .position(|chunk| chunk.ln_begin().is_some_and(|ln| ln > end))
+            .unwrap_or(chunks.len());
+        chunks.insert(idx, chunk);
+    }
+}
 
 impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     pub(crate) fn get_ast(&self, uri: &NormalizedUrl) -> Option<Module> {
@@ -27,6 +138,15 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         Parser::parse(code).ok().map(|artifact| artifact.ast)
     }
 
+    /// Unlike `get_ast`, returns the AST recovered by the parser even if the code has syntax errors
+    pub(crate) fn get_partial_ast(&self, uri: &NormalizedUrl) -> Option<Module> {
+        let code = self.file_cache.get_entire_code(uri).ok()?;
+        match Parser::parse(code) {
+            Ok(artifact) => Some(artifact.ast),
+            Err(iart) => iart.ast,
+        }
+    }
+
     pub(crate) fn check_file<S: Into<String>>(
         &mut self,
         uri: NormalizedUrl,
@@ -40,7 +160,11 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             "exec"
         };
         let mut checker = self.get_checker(path.clone());
-        let artifact = match checker.build(code.into(), mode) {
+        self.set_progress_listener(&checker);
+        self.progress.begin_check();
+        let result = checker.build(code.into(), mode);
+        self.progress.end_check()?;
+        let artifact = match result {
             Ok(artifact) => {
                 send_log(format!(
                     "checking {uri} passed, found warns: {}",
@@ -77,13 +201,14 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         };
         if let Some(shared) = self.get_shared() {
             if mode == "declare" {
+                shared.graph.clear_dirty(&path);
                 shared.py_mod_cache.register(
                     path,
                     artifact.object.clone(),
                     checker.get_context().unwrap().clone(),
                 );
             } else {
-                shared.mod_cache.register(
+                shared.register_module(
                     path,
                     artifact.object.clone(),
                     checker.get_context().unwrap().clone(),
@@ -130,6 +255,138 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         Ok(())
     }
 
+    /// Re-checks only the top-level definition edited since the last check, against the module context of that check.
+    /// If the type of the definition has changed, the chunks referring to it (transitively) are re-checked too.
+    /// The warnings about the whole module (e.g. unused variables) are updated at the next full check (e.g. on save).
+    ///
+    /// Returns `false` if the edit cannot be checked partially, then the whole module should be checked.
+    pub(crate) fn recheck_edited_def(&mut self, uri: &NormalizedUrl) -> ELSResult<bool> {
+        let path = util::uri_to_path(uri);
+        if path.to_string_lossy().ends_with(".d.er") || self.analysis_result.get_hir(uri).is_none()
+        {
+            return Ok(false);
+        }
+        let Some(new) = self.get_ast(uri) else {
+            return Ok(false);
+        };
+        let Some(old) = self.analysis_result.get_ast(uri).map(|ast| ast.clone()) else {
+            return Ok(false);
+        };
+        let idx = match edited_def(&old, &new) {
+            Some(Some(idx)) if new.get(idx).is_some_and(is_relowerable) => idx,
+            Some(None) => return Ok(true),
+            _ => return Ok(false),
+        };
+        let Some(mut lowerer) = self.steal_lowerer(uri) else {
+            return Ok(false);
+        };
+        let mut relowered = vec![];
+        let mut errors = CompileErrors::empty();
+        let mut warns = CompileErrors::empty();
+        let mut changed = set! {};
+        for (i, chunk) in new.iter().enumerate() {
+            let refers_changed = || {
+                referred_names(chunk)
+                    .iter()
+                    .any(|name| changed.contains(name))
+            };
+            if i != idx && !refers_changed() {
+                continue;
+            }
+            if !is_relowerable(chunk) {
+                self.restore_lowerer(uri.clone(), lowerer);
+                return Ok(false);
+            }
+            let name = match chunk {
+                Expr::Def(def) => def.sig.name_as_str().cloned(),
+                _ => None,
+            };
+            let old_t = name
+                .as_ref()
+                .and_then(|name| lowerer.get_var_info(name))
+                .map(|(_, vi)| vi.t.clone());
+            let (hir_chunk, errs, ws) = lowerer.relower_chunk(chunk.clone());
+            relowered.push((lines_of(chunk).unwrap_or_default(), hir_chunk));
+            errors.extend(errs);
+            warns.extend(ws);
+            let Some(name) = name else {
+                continue;
+            };
+            let Some((_, vi)) = lowerer.get_var_info(&name) else {
+                self.restore_lowerer(uri.clone(), lowerer);
+                return Ok(false);
+            };
+            if old_t.as_ref() == Some(&vi.t) {
+                continue;
+            }
+            // the dependents in the other modules are checked by the full check
+            let exported = vi.vis.is_public() && !self.dependents_of(uri).is_empty();
+            // the chunks before this one cannot be re-checked in order
+            let referred_before = new
+                .iter()
+                .take(i)
+                .any(|chunk| referred_names(chunk).contains(&name));
+            if exported || referred_before {
+                self.restore_lowerer(uri.clone(), lowerer);
+                return Ok(false);
+            }
+            changed.insert(name);
+        }
+        let ranges = relowered
+            .iter()
+            .map(|(lines, _)| *lines)
+            .collect::<Vec<_>>();
+        let in_ranges = |err: &CompileError| {
+            let loc = err.core.get_loc_with_fallback();
+            let in_file = err.input.path().is_none_or(|path| {
+                NormalizedUrl::from_file_path(path).is_ok_and(|url| &url == uri)
+            });
+            in_file
+                && loc
+                    .ln_begin()
+                    .is_some_and(|ln| ranges.iter().any(|(begin, end)| *begin <= ln && ln <= *end))
+        };
+        let diags = if let Some(mut result) = self.analysis_result.get_mut(uri) {
+            result.ast = new;
+            let artifact = &mut result.artifact;
+            artifact
+                .errors
+                .ref_mut_payload()
+                .retain(|err| !in_ranges(err));
+            artifact
+                .warns
+                .ref_mut_payload()
+                .retain(|err| !in_ranges(err));
+            artifact.errors.extend(errors);
+            artifact.warns.extend(warns);
+            if let Some(hir) = artifact.object.as_mut() {
+                for (lines, chunk) in relowered {
+                    patch_chunk(hir, lines, chunk);
+                }
+                if let Some(shared) = self.get_shared() {
+                    shared.register_module(path, Some(hir.clone()), lowerer.get_mod_ctx().clone());
+                }
+            }
+            artifact
+                .errors
+                .clone()
+                .into_iter()
+                .chain(artifact.warns.clone())
+                .collect()
+        } else {
+            CompileErrors::empty()
+        };
+        self.restore_lowerer(uri.clone(), lowerer);
+        send_log(format!("re-checked the definition at #{idx} of {uri}"))?;
+        let uri_and_diags = self.make_uri_and_diags(uri.clone(), diags);
+        // clear previous diagnostics
+        self.send_diagnostics(uri.clone().raw(), vec![])?;
+        for (uri, diags) in uri_and_diags.into_iter() {
+            self.send_diagnostics(uri, diags)?;
+        }
+        Ok(true)
+    }
+
     fn make_uri_and_diags(
         &mut self,
         uri: NormalizedUrl,
@@ -147,6 +404,12 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                 crate::_log!("failed to get uri: {}", err.input.path().unwrap().display());
                 continue;
             };
+            let edits = err
+                .core
+                .suggestions
+                .iter()
+                .filter_map(|sugg| SuggestedEdit::new(sugg, &err_uri))
+                .collect::<Vec<_>>();
             let mut message = remove_style(&err.core.main_message);
             for sub in err.core.sub_messages {
                 for msg in sub.get_msg() {
@@ -170,7 +433,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                 DiagnosticSeverity::ERROR
             };
             let source = if PYTHON_MODE { "pylyzer" } else { "els" };
-            let diag = Diagnostic::new(
+            let mut diag = Diagnostic::new(
                 Range::new(start, end),
                 Some(severity),
                 Some(NumberOrString::String(format!("E{}", err.core.errno))),
@@ -179,6 +442,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                 None,
                 None,
             );
+            if !edits.is_empty() {
+                diag.data = Some(json!(edits));
+            }
             if let Some((_, diags)) = uri_and_diags.iter_mut().find(|x| x.0 == err_uri) {
                 diags.push(diag);
             } else {
@@ -189,10 +455,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     }
 
     fn send_diagnostics(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> ELSResult<()> {
-        if self
-            .disabled_features
-            .contains(&DefaultFeatures::Diagnostics)
-        {
+        if self.is_disabled(DefaultFeatures::Diagnostics) {
             return Ok(());
         }
         let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
@@ -216,23 +479,43 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
 
     /// Periodically send diagnostics without a request from the server.
     /// This is necessary to perform reactive error highlighting in editors such as Vim, where no action is taken until the buffer is saved.
+    /// A file is checked once it has not been edited for [`DEBOUNCE`], and only the edited definition is re-checked if possible.
     pub(crate) fn start_auto_diagnostics(&mut self) {
         let mut _self = self.clone();
         spawn_new_thread(
             move || {
                 let mut file_vers = Dict::<NormalizedUrl, i32>::new();
+                // the versions not checked yet and since when they have been the latest
+                let mut pending = Dict::<NormalizedUrl, (i32, Instant)>::new();
                 loop {
                     for uri in _self.file_cache.entries() {
-                        let latest_ver = _self.file_cache.get_ver(&uri);
-                        let ver = file_vers.get(&uri);
-                        if latest_ver.as_ref() != ver {
-                            if let Ok(code) = _self.file_cache.get_entire_code(&uri) {
-                                let _ = _self.check_file(uri.clone(), code);
-                                file_vers.insert(uri, latest_ver.unwrap());
+                        let Some(latest_ver) = _self.file_cache.get_ver(&uri) else {
+                            continue;
+                        };
+                        if file_vers.get(&uri) == Some(&latest_ver) {
+                            continue;
+                        }
+                        // wait until the user stops typing
+                        match pending.get(&uri) {
+                            Some((ver, since)) if *ver == latest_ver => {
+                                if since.elapsed() < DEBOUNCE {
+                                    continue;
+                                }
                             }
+                            _ => {
+                                pending.insert(uri, (latest_ver, Instant::now()));
+                                continue;
+                            }
+                        }
+                        pending.remove(&uri);
+                        if _self.recheck_edited_def(&uri).unwrap_or(false) {
+                            file_vers.insert(uri, latest_ver);
+                        } else if let Ok(code) = _self.file_cache.get_entire_code(&uri) {
+                            let _ = _self.check_file(uri.clone(), code);
+                            file_vers.insert(uri, latest_ver);
                         }
                     }
-                    sleep(Duration::from_millis(500));
+                    sleep(Duration::from_millis(100));
                 }
             },
             fn_name!(),
@@ -0,0 +1,213 @@
+//! `textDocument/documentSymbol`: lists the names defined in the module hierarchically.
+//! Types contain their attributes and methods (traits contain their declarations), and subroutines contain their local names.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use erg_common::error::Location;
+use erg_common::traits::{Locational, Stream};
+use erg_common::Str;
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::context::{Context, ModuleContext};
+use erg_compiler::erg_parser::ast::{Def, Expr, Module, Record, RecordAttrOrIdent, VarName};
+use erg_compiler::erg_parser::parse::Parsable;
+use erg_compiler::varinfo::{VarInfo, VarKind};
+
+use lsp_types::{DocumentSymbol, DocumentSymbolParams, DocumentSymbolResponse, Range};
+
+use crate::server::{send_log, ELSResult, Server};
+use crate::symbol::symbol_kind;
+use crate::util::{self, ASTNode, NormalizedUrl};
+
+fn start(loc: &impl Locational) -> Option<(u32, u32)> {
+    Some((loc.ln_begin()?, loc.col_begin()?))
+}
+
+/// The names and locations of the fields of the record passed to a type definition (e.g. `C = Class { .x = Int }`).
+/// The context does not know the locations of them.
+fn record_fields(def: &Def) -> Vec<(Str, Location)> {
+    let Some(Expr::Call(call)) = def.body.block.first() else {
+        return vec![];
+    };
+    match call.args.pos_args().first().map(|arg| &arg.expr) {
+        Some(Expr::Record(Record::Normal(record))) => record
+            .attrs
+            .iter()
+            .filter_map(|attr| attr.sig.ident())
+            .map(|ident| (ident.inspect().clone(), ident.loc()))
+            .collect(),
+        Some(Expr::Record(Record::Mixed(record))) => record
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                RecordAttrOrIdent::Attr(def) => def.sig.ident(),
+                RecordAttrOrIdent::Ident(ident) => Some(ident),
+            })
+            .map(|ident| (ident.inspect().clone(), ident.loc()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+#[derive(Default)]
+struct DefLocs {
+    /// The locations of the definitions (including the bodies), keyed by their start positions.
+    /// The name of a definition starts at the same position as the definition.
+    defs: HashMap<(u32, u32), Location>,
+    /// The fields of the types, keyed by the start positions of the type definitions
+    fields: HashMap<(u32, u32), Vec<(Str, Location)>>,
+}
+
+impl DefLocs {
+    fn collect(&mut self, node: ASTNode) {
+        let def = match node {
+            ASTNode::Def(def) | ASTNode::Expr(Expr::Def(def)) => Some(def),
+            ASTNode::Expr(Expr::ClassDef(class_def)) => Some(&class_def.def),
+            ASTNode::Expr(Expr::PatchDef(patch_def)) => Some(&patch_def.def),
+            _ => None,
+        };
+        if let Some((def, key)) = def.zip(start(&node)) {
+            // the outermost one (e.g. `ClassDef` rather than its `Def`) is registered first
+            self.defs.entry(key).or_insert_with(|| node.loc());
+            let fields = record_fields(def);
+            if !fields.is_empty() {
+                self.fields.insert(key, fields);
+            }
+        }
+        for child in node.children() {
+            self.collect(child);
+        }
+    }
+}
+
+fn cover(range: Range, children: &[DocumentSymbol]) -> Range {
+    children.iter().fold(range, |range, child| {
+        Range::new(
+            range.start.min(child.range.start),
+            range.end.max(child.range.end),
+        )
+    })
+}
+
+struct SymbolCollector<'a> {
+    path: &'a Path,
+    mod_ctx: &'a ModuleContext,
+    locs: DefLocs,
+}
+
+impl SymbolCollector<'_> {
+    /// The location of the name of `vi` if it is defined in this module
+    fn name_loc(&self, vi: &VarInfo) -> Option<Location> {
+        (vi.def_loc.module.as_deref() == Some(self.path)).then_some(vi.def_loc.loc)
+    }
+
+    fn symbol(
+        &self,
+        name: &VarName,
+        vi: &VarInfo,
+        name_loc: Location,
+        is_attr: bool,
+        children: Vec<DocumentSymbol>,
+    ) -> Option<DocumentSymbol> {
+        let selection_range = util::loc_to_range(name_loc)?;
+        let loc = start(&name_loc)
+            .and_then(|key| self.locs.defs.get(&key))
+            .copied()
+            .unwrap_or(name_loc);
+        let range = util::loc_to_range(loc).unwrap_or(selection_range);
+        #[allow(deprecated)]
+        Some(DocumentSymbol {
+            name: name.inspect().to_string(),
+            detail: Some(vi.t.to_string()),
+            kind: symbol_kind(vi, is_attr),
+            tags: None,
+            deprecated: None,
+            range: cover(range, &children),
+            selection_range,
+            children: (!children.is_empty()).then_some(children),
+        })
+    }
+
+    /// The context of the subroutine `name` defined in `ctx`
+    fn nested_ctx(&self, ctx: &Context, name: &VarName, vi: &VarInfo) -> Option<&Context> {
+        let sep = if vi.vis.is_public() { "." } else { "::" };
+        self.mod_ctx
+            .scope
+            .get(&format!("{}{sep}{}", ctx.name, name.inspect())[..])
+    }
+
+    fn collect(&self, ctx: &Context) -> Vec<DocumentSymbol> {
+        let types = ctx.local_type_members();
+        let mut symbols = vec![];
+        for (name, vi) in ctx.local_vars() {
+            if matches!(vi.kind, VarKind::Parameter { .. })
+                || name.inspect().starts_with(['%', '<'])
+            {
+                continue;
+            }
+            let Some(name_loc) = self.name_loc(vi) else {
+                continue;
+            };
+            let children = if let Some((_, attrs)) = types.iter().find(|(ty, _)| *ty == name) {
+                let fields = start(&name_loc)
+                    .and_then(|key| self.locs.fields.get(&key))
+                    .map_or(&[][..], |fields| &fields[..]);
+                let mut attrs = attrs
+                    .iter()
+                    .filter_map(|(attr, vi)| {
+                        let attr_loc = self.name_loc(vi).or_else(|| {
+                            fields
+                                .iter()
+                                .find(|(field, _)| field == attr.inspect())
+                                .map(|(_, loc)| *loc)
+                        })?;
+                        self.symbol(attr, vi, attr_loc, true, vec![])
+                    })
+                    .collect::<Vec<_>>();
+                attrs.sort_by_key(|symbol| symbol.range.start);
+                attrs
+            } else if vi.t.is_subr() {
+                self.nested_ctx(ctx, name, vi)
+                    .map_or(vec![], |nested| self.collect(nested))
+            } else {
+                vec![]
+            };
+            symbols.extend(self.symbol(name, vi, name_loc, false, children));
+        }
+        symbols.sort_by_key(|symbol| symbol.range.start);
+        symbols
+    }
+}
+
+/// Returns the symbols defined in the module at `path`, nested by the types and the subroutines they belong to.
+/// `ast` is used to get the ranges of the definitions (including their bodies).
+pub fn document_symbols(mod_ctx: &ModuleContext, ast: &Module, path: &Path) -> Vec<DocumentSymbol> {
+    let mut locs = DefLocs::default();
+    for expr in ast.iter() {
+        locs.collect(ASTNode::Expr(expr));
+    }
+    let collector = SymbolCollector {
+        path,
+        mod_ctx,
+        locs,
+    };
+    collector.collect(&mod_ctx.context)
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn handle_document_symbol(
+        &mut self,
+        params: DocumentSymbolParams,
+    ) -> ELSResult<Option<DocumentSymbolResponse>> {
+        send_log(format!("document symbol requested: {params:?}"))?;
+        let uri = NormalizedUrl::new(params.text_document.uri);
+        let (Some(mod_ctx), Some(ast)) =
+            (self.modules.get(&uri), self.analysis_result.get_ast(&uri))
+        else {
+            return Ok(None);
+        };
+        let path = util::uri_to_path(&uri);
+        let symbols = document_symbols(mod_ctx, &ast, &path);
+        Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+    }
+}
@@ -12,6 +12,7 @@ use lsp_types::{
 use erg_common::dict::Dict;
 use erg_common::shared::Shared;
 use erg_common::traits::DequeStream;
+use erg_compiler::erg_parser::incremental::{relex, LineEdit};
 use erg_compiler::erg_parser::lex::Lexer;
 use erg_compiler::erg_parser::token::{Token, TokenStream};
 
@@ -258,6 +259,7 @@ impl FileCache {
             return;
         }
         let mut code = entry.code.clone();
+        let mut token_stream = entry.token_stream.take();
         for change in params.content_changes {
             let Some(range) = change.range else {
                 continue;
@@ -265,8 +267,10 @@ impl FileCache {
             let start = util::pos_to_byte_index(&code, range.start);
             let end = util::pos_to_byte_index(&code, range.end);
             code.replace_range(start..end, &change.text);
+            let edit = LineEdit::from_replacement(range.start.line, range.end.line, &change.text);
+            token_stream = token_stream.and_then(|tokens| relex(&tokens, &code, edit));
         }
-        let token_stream = Lexer::from_str(code.clone()).lex().ok();
+        let token_stream = token_stream.or_else(|| Lexer::from_str(code.clone()).lex().ok());
         entry.code = code;
         entry.ver = params.text_document.version;
         entry.token_stream = token_stream;
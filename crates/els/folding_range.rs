@@ -0,0 +1,119 @@
+//! `textDocument/foldingRange`: folds the multi-line blocks, class bodies, calls and comments.
+//! Only the parser's location data is used, so this works for the code that does not type-check.
+
+use erg_common::traits::{Locational, Stream};
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::erg_parser::ast::{Expr, Module};
+use erg_compiler::erg_parser::parse::Parsable;
+use erg_compiler::erg_parser::trivia::CommentKind;
+
+use lsp_types::{FoldingRange, FoldingRangeKind, FoldingRangeParams};
+
+use crate::server::{send_log, ELSResult, Server};
+use crate::util::{ASTNode, NormalizedUrl};
+
+fn is_foldable(node: &ASTNode) -> bool {
+    match node {
+        ASTNode::Def(_) | ASTNode::Methods(_) => true,
+        ASTNode::Expr(expr) => matches!(
+            expr,
+            Expr::Def(_)
+                | Expr::Lambda(_)
+                | Expr::Methods(_)
+                | Expr::ClassDef(_)
+                | Expr::PatchDef(_)
+                | Expr::Call(_)
+        ),
+        ASTNode::Leaf(_) => false,
+    }
+}
+
+/// (0-origin) lines of the node if it spans multiple lines
+fn lines_of(loc: &impl Locational) -> Option<(u32, u32)> {
+    let (begin, end) = (loc.ln_begin()?, loc.ln_end()?);
+    (begin < end).then(|| (begin.saturating_sub(1), end.saturating_sub(1)))
+}
+
+fn collect_node_ranges(node: ASTNode, ranges: &mut Vec<FoldingRange>) {
+    if is_foldable(&node) {
+        if let Some((start_line, end_line)) = lines_of(&node) {
+            ranges.push(FoldingRange {
+                start_line,
+                start_character: None,
+                end_line,
+                end_character: None,
+                kind: None,
+            });
+        }
+    }
+    for child in node.children() {
+        collect_node_ranges(child, ranges);
+    }
+}
+
+fn comment_range(start_line: u32, end_line: u32) -> FoldingRange {
+    FoldingRange {
+        start_line,
+        start_character: None,
+        end_line,
+        end_character: None,
+        kind: Some(FoldingRangeKind::Comment),
+    }
+}
+
+/// Consecutive line comments are folded together
+fn collect_comment_ranges(module: &Module, ranges: &mut Vec<FoldingRange>) {
+    let mut group: Option<(u32, u32)> = None;
+    for comment in module.trivia().comments() {
+        let (Some(begin), Some(end)) = (comment.ln_begin(), comment.ln_end()) else {
+            continue;
+        };
+        let (begin, end) = (begin.saturating_sub(1), end.saturating_sub(1));
+        match comment.kind {
+            CommentKind::Line => match &mut group {
+                Some((_, last)) if *last + 1 == begin => *last = end,
+                _ => {
+                    if let Some((start, last)) = group.replace((begin, end)) {
+                        if start < last {
+                            ranges.push(comment_range(start, last));
+                        }
+                    }
+                }
+            },
+            CommentKind::Block if begin < end => ranges.push(comment_range(begin, end)),
+            CommentKind::Block => {}
+        }
+    }
+    if let Some((start, last)) = group {
+        if start < last {
+            ranges.push(comment_range(start, last));
+        }
+    }
+}
+
+/// Returns the folding ranges of `module`, sorted by the start line.
+/// If several ranges start at the same line, only the outermost one is returned.
+pub fn folding_ranges(module: &Module) -> Vec<FoldingRange> {
+    let mut ranges = vec![];
+    for expr in module.iter() {
+        collect_node_ranges(ASTNode::Expr(expr), &mut ranges);
+    }
+    collect_comment_ranges(module, &mut ranges);
+    ranges.sort_by_key(|range| (range.start_line, std::cmp::Reverse(range.end_line)));
+    ranges.dedup_by_key(|range| range.start_line);
+    ranges
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn handle_folding_range(
+        &mut self,
+        params: FoldingRangeParams,
+    ) -> ELSResult<Option<Vec<FoldingRange>>> {
+        send_log(format!("folding range requested: {params:?}"))?;
+        let uri = NormalizedUrl::new(params.text_document.uri);
+        let Some(module) = self.get_partial_ast(&uri) else {
+            return Ok(None);
+        };
+        Ok(Some(folding_ranges(&module)))
+    }
+}
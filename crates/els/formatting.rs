@@ -0,0 +1,70 @@
+//! `textDocument/onTypeFormatting`: indents a new line following the block rules of the REPL.
+
+use erg_common::traits::{expect_block, BlockKind};
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::erg_parser::parse::Parsable;
+
+use lsp_types::{DocumentOnTypeFormattingParams, Position, Range, TextEdit};
+
+use crate::server::{send_log, ELSResult, Server};
+use crate::util::NormalizedUrl;
+
+const INDENT: usize = 4;
+
+fn indent_len(line: &str) -> usize {
+    line.chars().take_while(|c| *c == ' ').count()
+}
+
+/// The line without the indentation and the comment
+fn code_of(line: &str) -> &str {
+    let code = line.find('#').map_or(line, |comment| &line[..comment]);
+    code.trim()
+}
+
+/// Returns the indentation length of the line `line` (0-origin) of `code`, judging from the lines before it.
+/// As in the REPL, a line expecting a block (e.g. `f x =`, `C.`, `do!:`) deepens the indentation, and a blank line ends the block.
+///
+/// Returns `None` if the indentation should not be changed (e.g. the line is in a multi-line string).
+pub fn next_line_indent(code: &str, line: u32) -> Option<usize> {
+    let lines = code.lines().take(line as usize).collect::<Vec<_>>();
+    let prev = lines.last()?;
+    if code_of(prev).is_empty() {
+        // a blank line ends the block of the last non-blank line
+        let last = lines.iter().rev().find(|line| !code_of(line).is_empty())?;
+        return if expect_block(code_of(last)).is_block() {
+            Some(indent_len(last))
+        } else {
+            Some(indent_len(last).saturating_sub(INDENT))
+        };
+    }
+    match expect_block(code_of(prev)) {
+        BlockKind::MultiLineStr => None,
+        bk if bk.is_block() => Some(indent_len(prev) + INDENT),
+        _ => Some(indent_len(prev)),
+    }
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn handle_on_type_formatting(
+        &mut self,
+        params: DocumentOnTypeFormattingParams,
+    ) -> ELSResult<Option<Vec<TextEdit>>> {
+        send_log(format!("on type formatting requested: {params:?}"))?;
+        let uri = NormalizedUrl::new(params.text_document_position.text_document.uri);
+        let pos = params.text_document_position.position;
+        let code = self.file_cache.get_entire_code(&uri)?;
+        let Some(indent) = next_line_indent(&code, pos.line) else {
+            return Ok(None);
+        };
+        let current = code.lines().nth(pos.line as usize).unwrap_or_default();
+        let current_indent = indent_len(current);
+        if current_indent == indent {
+            return Ok(None);
+        }
+        let range = Range::new(
+            Position::new(pos.line, 0),
+            Position::new(pos.line, current_indent as u32),
+        );
+        Ok(Some(vec![TextEdit::new(range, " ".repeat(indent))]))
+    }
+}
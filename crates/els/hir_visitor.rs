@@ -2,6 +2,7 @@ use erg_common::consts::ERG_MODE;
 use erg_common::shared::MappedRwLockReadGuard;
 use erg_common::traits::Locational;
 use erg_common::Str;
+use erg_compiler::erg_parser::ast::OperationKind;
 use erg_compiler::erg_parser::token::Token;
 use erg_compiler::hir::*;
 use erg_compiler::varinfo::VarInfo;
@@ -260,7 +261,13 @@ impl<'a> HIRVisitor<'a> {
                     .and_then(|attr| self.return_expr_if_same(expr, attr.raw.name.token(), token))
             })
             .or_else(|| self.get_expr(&call.obj, token))
-            .or_else(|| self.get_expr_from_args(&call.args, token))
+            .or_else(|| {
+                // the code embedded by `py_inline` is opaque
+                if call.additional_operation() == Some(OperationKind::PyInline) {
+                    return None;
+                }
+                self.get_expr_from_args(&call.args, token)
+            })
             .or_else(|| call.loc().contains(token.loc()).then_some(expr))
     }
 
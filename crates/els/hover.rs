@@ -1,10 +1,12 @@
 use erg_common::consts::PYTHON_MODE;
 use erg_common::lang::LanguageCode;
-use erg_common::trim_eliminate_top_indent;
+use erg_common::{trim_eliminate_top_indent, Str};
 use erg_compiler::artifact::BuildRunnable;
 use erg_compiler::erg_parser::parse::Parsable;
 use erg_compiler::erg_parser::token::{Token, TokenCategory, TokenKind};
-use erg_compiler::ty::HasType;
+use erg_compiler::erg_parser::trivia::CommentKind;
+use erg_compiler::hir::Expr;
+use erg_compiler::ty::{HasType, Type};
 use erg_compiler::varinfo::{AbsLocation, VarInfo};
 
 use lsp_types::{Hover, HoverContents, HoverParams, MarkedString, Url};
@@ -53,6 +55,39 @@ fn sort_hovers(mut contents: Vec<MarkedString>) -> Vec<MarkedString> {
     contents
 }
 
+/// `lang` is the language code of the first line of `doc`
+fn doc_to_marked(lang: LanguageCode, doc: &str) -> MarkedString {
+    let code_block = trim_eliminate_top_indent(doc.trim_start_matches(lang.as_str()).to_string());
+    match lang {
+        LanguageCode::Erg => MarkedString::from_language_code("erg".into(), code_block),
+        LanguageCode::Python | LanguageCode::ErgOrPython => {
+            MarkedString::from_language_code("python".into(), code_block)
+        }
+        _ => MarkedString::from_markdown(code_block),
+    }
+}
+
+/// Renders the doc comments stored in `VarInfo` (the nearest one first) like `show_doc_comment`:
+/// code examples are always shown, and the English doc is shown if there is no doc in the language of the user.
+fn render_docs(docs: &[Str], contents: &mut Vec<MarkedString>) {
+    let mut default_doc = None;
+    for doc in docs.iter() {
+        let lang = lang_code(doc);
+        if lang.matches_feature() {
+            contents.push(doc_to_marked(lang, doc));
+            if !lang.is_pl() {
+                return;
+            }
+        } else if lang.is_en() {
+            default_doc = Some(doc);
+        }
+    }
+    if let Some(doc) = default_doc {
+        let code_block = trim_eliminate_top_indent(doc.to_string());
+        contents.push(MarkedString::from_markdown(code_block));
+    }
+}
+
 macro_rules! next {
     ($def_pos: ident, $default_code_block: ident, $contents: ident, $prev_token: ident, $token: ident) => {
         if $def_pos.line == 0 {
@@ -155,13 +190,17 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                             MarkedString::from_language_code(PROG_LANG.into(), code_block);
                         contents.push(definition);
                     }
-                    let typ = MarkedString::from_language_code(
-                        ERG_LANG.into(),
-                        format!("{}: {}", token.content, vi.t),
-                    );
-                    contents.push(typ);
+                    let mut typ = format!("{}: {}", token.content, vi.t);
+                    if let Some(inst_t) = self.get_instantiated_t(&uri, &token, &vi) {
+                        typ += &format!("\n# instantiated here\n{}: {inst_t}", token.content);
+                    }
+                    contents.push(MarkedString::from_language_code(ERG_LANG.into(), typ));
                     self.show_type_defs(&vi, &mut contents)?;
-                    self.show_doc_comment(Some(token), &mut contents, &vi.def_loc)?;
+                    if vi.docs.is_empty() {
+                        self.show_doc_comment(Some(token), &mut contents, &vi.def_loc)?;
+                    } else {
+                        render_docs(&vi.docs, &mut contents);
+                    }
                 }
                 // not found or not symbol, etc.
                 None => {
@@ -185,6 +224,19 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         }))
     }
 
+    /// The type of a polymorphic variable at the hover site, e.g. `id: (x: Int) -> Int` of `id 1`
+    fn get_instantiated_t(&self, uri: &NormalizedUrl, token: &Token, vi: &VarInfo) -> Option<Type> {
+        if !vi.t.is_quantified_subr() {
+            return None;
+        }
+        let visitor = self.get_visitor(uri)?;
+        let Expr::Accessor(acc) = visitor.get_min_expr(token)? else {
+            return None;
+        };
+        let t = acc.ref_t();
+        (t != &vi.t && !t.is_quantified_subr()).then(|| t.clone())
+    }
+
     fn show_type_defs(&mut self, vi: &VarInfo, contents: &mut Vec<MarkedString>) -> ELSResult<()> {
         let mut defs = "".to_string();
         for inner_t in vi.t.inner_ts() {
@@ -240,6 +292,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             let Some(stream) = self.file_cache.get_token_stream(&def_uri) else {
                 return Ok(());
             };
+            let n_contents = contents.len();
             let mut prev_token = Token::DUMMY;
             loop {
                 let Some(token) = util::get_token_from_stream(&stream, def_pos)? else {
@@ -256,19 +309,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                         .to_string();
                     let lang = lang_code(&code_block);
                     if lang.matches_feature() {
-                        let code_block = trim_eliminate_top_indent(
-                            code_block.trim_start_matches(lang.as_str()).to_string(),
-                        );
-                        let marked = match lang {
-                            LanguageCode::Erg => {
-                                MarkedString::from_language_code("erg".into(), code_block)
-                            }
-                            LanguageCode::Python | LanguageCode::ErgOrPython => {
-                                MarkedString::from_language_code("python".into(), code_block)
-                            }
-                            _ => MarkedString::from_markdown(code_block),
-                        };
-                        contents.push(marked);
+                        contents.push(doc_to_marked(lang, &code_block));
                         if lang.is_pl() {
                             next!(def_pos, default_code_block, contents, prev_token, token);
                         } else {
@@ -294,6 +335,21 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                     break;
                 }
             }
+            // no doc comment, use the line comments just before the definition instead
+            if contents.len() == n_contents {
+                if let Some(ast) = self.analysis_result.get_ast(&def_uri) {
+                    let comments = ast
+                        .trivia()
+                        .leading_comments(&def_loc.loc)
+                        .iter()
+                        .filter(|comment| comment.kind == CommentKind::Line)
+                        .map(|comment| comment.text())
+                        .collect::<Vec<_>>();
+                    if !comments.is_empty() {
+                        contents.push(MarkedString::from_markdown(comments.join("\n")));
+                    }
+                }
+            }
         }
         Ok(())
     }
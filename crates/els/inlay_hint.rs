@@ -10,11 +10,12 @@ use erg_common::error::Location;
 use erg_common::traits::NoTypeDisplay;
 use erg_common::traits::{Locational, Runnable, Stream};
 use erg_compiler::artifact::{BuildRunnable, IncompleteArtifact};
+use erg_compiler::erg_parser::ast::OperationKind;
 use erg_compiler::hir::{Block, Call, ClassDef, Def, Expr, Lambda, Params, PatchDef, Signature};
 use erg_compiler::ty::HasType;
 use lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, InlayHintParams, Position};
 
-use crate::server::{send, send_log, ELSResult, Server};
+use crate::server::{send, send_log, DefaultFeatures, ELSResult, Server};
 use crate::util::{self, loc_to_range, NormalizedUrl};
 
 fn anot(ln: u32, col: u32, cont: String) -> InlayHint {
@@ -94,6 +95,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         send_log(format!("inlay hint request: {params:?}"))?;
         let uri = NormalizedUrl::new(params.text_document.uri);
         let mut result = vec![];
+        if self.is_disabled(DefaultFeatures::InlayHint) {
+            return Ok(Some(result));
+        }
         if let Some(IncompleteArtifact {
             object: Some(hir), ..
         }) = self.analysis_result.get_artifact(&uri).as_deref()
@@ -119,6 +123,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
 
     fn get_param_hint(&self, params: &Params) -> Vec<InlayHint> {
         let mut result = vec![];
+        if !self.inlay_hint_settings().parameter_types {
+            return result;
+        }
         for nd_param in params.non_defaults.iter() {
             if nd_param.raw.t_spec.is_some() {
                 continue;
@@ -155,7 +162,8 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         let mut result = vec![];
         result.extend(self.get_block_hint(&def.body.block));
         let Signature::Subr(subr) = &def.sig else { unreachable!() };
-        if subr.ref_t().is_quantified_subr() && subr.bounds.is_empty() {
+        let settings = self.inlay_hint_settings();
+        if settings.type_bounds && subr.ref_t().is_quantified_subr() && subr.bounds.is_empty() {
             let subr = subr.ref_t().to_string();
             let ty_bounds = format!("|{}|", subr.split('|').nth(1).unwrap_or(""));
             let ident = def.sig.ident();
@@ -165,7 +173,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             }
         }
         result.extend(self.get_param_hint(&subr.params));
-        if def.sig.t_spec().is_none() {
+        if settings.return_types && def.sig.t_spec().is_none() {
             let Some(return_t) = subr.ref_t().return_t() else {
                 return result;
             };
@@ -186,7 +194,10 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     fn get_var_def_hint(&self, def: &Def) -> Vec<InlayHint> {
         let mut result = self.get_block_hint(&def.body.block);
         // don't show hints for compiler internal variables
-        if def.sig.t_spec().is_none() && !def.sig.ident().inspect().starts_with(['%']) {
+        if self.inlay_hint_settings().variable_types
+            && def.sig.t_spec().is_none()
+            && !def.sig.ident().inspect().starts_with(['%'])
+        {
             if let Some((ln, col)) = def.sig.ln_begin().zip(def.sig.col_end()) {
                 let hint = type_anot(ln, col, def.sig.ident().ref_t(), false);
                 result.push(hint);
@@ -199,6 +210,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         let mut result = vec![];
         result.extend(self.get_block_hint(&lambda.body));
         result.extend(self.get_param_hint(&lambda.params));
+        if !self.inlay_hint_settings().return_types {
+            return result;
+        }
         if lambda.params.parens.is_none() {
             if let Some((ln, col)) = lambda.params.ln_begin().zip(lambda.params.col_begin()) {
                 let hint = anot(ln, col, "(".to_string());
@@ -234,6 +248,10 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     }
 
     fn get_call_hint(&self, call: &Call) -> Vec<InlayHint> {
+        // the code embedded by `py_inline` is opaque
+        if call.additional_operation() == Some(OperationKind::PyInline) {
+            return vec![];
+        }
         let mut result = vec![];
         let Some(call_t) = call.signature_t() else {
             return vec![];
@@ -242,9 +260,13 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             return vec![];
         };
         let is_method = call.is_method_call();
+        let show_names = self.inlay_hint_settings().parameter_names;
         for (i, pos_arg) in call.args.pos_args.iter().enumerate() {
             let arg_is_lambda = matches!(&pos_arg.expr, Expr::Lambda(_));
             result.extend(self.get_expr_hint(&pos_arg.expr));
+            if !show_names {
+                continue;
+            }
             let index = if is_method { i + 1 } else { i };
             if let Some(name) = param_ts.clone().nth(index).and_then(|pt| pt.name()) {
                 if name.starts_with(['%']) {
@@ -4,17 +4,44 @@ mod code_lens;
 mod command;
 mod completion;
 mod definition;
+mod desugared;
 mod diagnostics;
 mod diff;
+mod document_symbol;
 mod file_cache;
+mod folding_range;
+mod formatting;
 mod hir_visitor;
 mod hover;
 mod inlay_hint;
 mod message;
+mod progress;
 mod references;
 mod rename;
+mod selection_range;
 mod semantic;
 mod server;
+mod settings;
 mod sig_help;
+mod standalone;
+mod symbol;
+mod test_runner;
+mod type_at;
 mod util;
+pub use code_lens::get_trait_impls;
+pub use definition::find_py_def;
+pub use desugared::{
+    desugared_ast, desugared_hir, DesugaredKind, ViewDesugared, ViewDesugaredParams,
+};
+pub use diagnostics::{edited_def, referred_names};
+pub use document_symbol::document_symbols;
+pub use folding_range::folding_ranges;
+pub use formatting::next_line_indent;
+pub use selection_range::selection_ranges;
+pub use semantic::{diff_semantic_tokens, TOKEN_MODIFIERS, TOKEN_TYPES};
 pub use server::*;
+pub use settings::{CheckingMode, InlayHintSettings, ServerSettings};
+pub use standalone::*;
+pub use symbol::search_symbols;
+pub use test_runner::{test_functions, test_script, ListTests, ListTestsParams, TestItem};
+pub use type_at::*;
@@ -4,18 +4,27 @@ mod code_lens;
 mod command;
 mod completion;
 mod definition;
+mod desugared;
 mod diagnostics;
 mod diff;
+mod document_symbol;
 mod file_cache;
+mod folding_range;
+mod formatting;
 mod hir_visitor;
 mod hover;
 mod inlay_hint;
 mod message;
+mod progress;
 mod references;
 mod rename;
+mod selection_range;
 mod semantic;
 mod server;
+mod settings;
 mod sig_help;
+mod symbol;
+mod test_runner;
 mod util;
 
 use erg_common::config::ErgConfig;
@@ -0,0 +1,199 @@
+//! `$/progress`: reports the progress of the analyses of the imported modules (started on the threads of `SharedPromises`),
+//! so that the client shows a progress bar while a large module graph is built.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use erg_common::shared::Shared;
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::erg_parser::parse::Parsable;
+use erg_compiler::module::{PromiseEvent, PromiseListener};
+
+use lsp_types::notification::{Notification, Progress};
+use lsp_types::request::{Request, WorkDoneProgressCreate};
+use lsp_types::{
+    NumberOrString, ProgressParams, ProgressParamsValue, WorkDoneProgress, WorkDoneProgressBegin,
+    WorkDoneProgressCreateParams, WorkDoneProgressEnd, WorkDoneProgressReport,
+};
+use serde_json::json;
+
+use crate::server::{send, ELSResult, Server};
+
+/// The IDs of the requests sent by the server
+static REQUEST_ID: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    /// The token created by the client, which can be used for the next analysis
+    token: Option<NumberOrString>,
+    /// The ID of the `window/workDoneProgress/create` request waiting for the response
+    pending: Option<(i64, NumberOrString)>,
+    generation: u32,
+    /// The number of the running checks (of the files opened in the client)
+    checking: usize,
+    /// The token used for the current analysis (`None` if the analysis does not start any thread)
+    current: Option<NumberOrString>,
+    started: usize,
+    finished: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+    enabled: bool,
+    state: Shared<ProgressState>,
+}
+
+fn send_progress(token: NumberOrString, progress: WorkDoneProgress) -> ELSResult<()> {
+    let params = ProgressParams {
+        token,
+        value: ProgressParamsValue::WorkDone(progress),
+    };
+    send(&json!({
+        "jsonrpc": "2.0",
+        "method": Progress::METHOD,
+        "params": params,
+    }))
+}
+
+fn module_name(path: &std::path::Path) -> String {
+    path.file_stem()
+        .map_or(String::new(), |stem| stem.to_string_lossy().to_string())
+}
+
+impl ProgressReporter {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            state: Shared::default(),
+        }
+    }
+
+    /// Requests the client to create a token for the next analysis.
+    /// A token can only be used for one analysis, and it is available after the client responds.
+    pub(crate) fn create_token(&self) -> ELSResult<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let mut state = self.state.borrow_mut();
+        if state.token.is_some() || state.pending.is_some() {
+            return Ok(());
+        }
+        state.generation += 1;
+        let token = NumberOrString::String(format!("els/analysis/{}", state.generation));
+        let id = REQUEST_ID.fetch_add(1, Ordering::SeqCst);
+        state.pending = Some((id, token.clone()));
+        drop(state);
+        send(&json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": WorkDoneProgressCreate::METHOD,
+            "params": WorkDoneProgressCreateParams { token },
+        }))
+    }
+
+    /// Called when the client responds to a request of the server.
+    /// The token cannot be used if the client returns an error.
+    pub(crate) fn on_response(&self, id: i64, is_error: bool) {
+        let mut state = self.state.borrow_mut();
+        if state
+            .pending
+            .as_ref()
+            .is_some_and(|(pending, _)| *pending == id)
+        {
+            let (_, token) = state.pending.take().unwrap();
+            if !is_error {
+                state.token = Some(token);
+            }
+        }
+    }
+
+    pub(crate) fn begin_check(&self) {
+        self.state.borrow_mut().checking += 1;
+    }
+
+    /// Ends the progress if all the checks are finished.
+    pub(crate) fn end_check(&self) -> ELSResult<()> {
+        let mut state = self.state.borrow_mut();
+        state.checking = state.checking.saturating_sub(1);
+        if state.checking > 0 {
+            return Ok(());
+        }
+        state.started = 0;
+        state.finished = 0;
+        let Some(token) = state.current.take() else {
+            return Ok(());
+        };
+        drop(state);
+        send_progress(
+            token,
+            WorkDoneProgress::End(WorkDoneProgressEnd { message: None }),
+        )?;
+        self.create_token()
+    }
+
+    fn on_event(&self, event: &PromiseEvent) -> ELSResult<()> {
+        let mut state = self.state.borrow_mut();
+        if state.checking == 0 {
+            return Ok(());
+        }
+        let (message, begin) = match event {
+            PromiseEvent::Started(path) => {
+                state.started += 1;
+                let begin = state.current.is_none();
+                if begin {
+                    let Some(token) = state.token.take() else {
+                        return Ok(());
+                    };
+                    state.current = Some(token);
+                }
+                (format!("analyzing {}", module_name(path)), begin)
+            }
+            PromiseEvent::Finished(path) => {
+                state.finished += 1;
+                (format!("analyzed {}", module_name(path)), false)
+            }
+        };
+        let Some(token) = state.current.clone() else {
+            return Ok(());
+        };
+        let percentage = (state.finished * 100 / state.started.max(1)).min(100) as u32;
+        let message = format!("{message} ({}/{})", state.finished, state.started);
+        drop(state);
+        let progress = if begin {
+            WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: "Analyzing modules".to_string(),
+                cancellable: Some(false),
+                message: Some(message),
+                percentage: Some(percentage),
+            })
+        } else {
+            WorkDoneProgress::Report(WorkDoneProgressReport {
+                cancellable: Some(false),
+                message: Some(message),
+                percentage: Some(percentage),
+            })
+        };
+        send_progress(token, progress)
+    }
+
+    pub(crate) fn listener(&self) -> PromiseListener {
+        let reporter = self.clone();
+        PromiseListener::new(move |event| {
+            let _ = reporter.on_event(event);
+        })
+    }
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn set_progress_listener(&self, checker: &Checker) {
+        if !self.progress.enabled {
+            return;
+        }
+        if let Some(module) = checker.get_context() {
+            module
+                .context
+                .shared()
+                .promises
+                .set_listener(self.progress.listener());
+        }
+    }
+}
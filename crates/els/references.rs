@@ -1,8 +1,12 @@
+use erg_common::traits::Locational;
 use erg_compiler::artifact::BuildRunnable;
 use erg_compiler::erg_parser::parse::Parsable;
 use erg_compiler::varinfo::AbsLocation;
 
-use lsp_types::{Location, Position, ReferenceParams, Url};
+use lsp_types::{
+    DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams, Location, Position,
+    ReferenceParams, Url,
+};
 
 use crate::server::{ELSResult, Server};
 use crate::util::{self, NormalizedUrl};
@@ -14,20 +18,68 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     ) -> ELSResult<Option<Vec<Location>>> {
         let uri = NormalizedUrl::new(params.text_document_position.text_document.uri);
         let pos = params.text_document_position.position;
-        let result = self.show_refs_inner(&uri, pos);
+        let Some(referee) = self.get_referee(&uri, pos) else {
+            return Ok(Some(vec![]));
+        };
+        let mut result = self.get_refs_from_abs_loc(&referee);
+        if params.context.include_declaration {
+            if let Some(def) = abs_loc_to_lsp_loc(&referee) {
+                result.insert(0, def);
+            }
+        }
         Ok(Some(result))
     }
 
-    fn show_refs_inner(&self, uri: &NormalizedUrl, pos: Position) -> Vec<lsp_types::Location> {
-        if let Some(tok) = self.file_cache.get_token(uri, pos) {
-            // send_log(format!("token: {tok}"))?;
-            if let Some(visitor) = self.get_visitor(uri) {
-                if let Some(vi) = visitor.get_info(&tok) {
-                    return self.get_refs_from_abs_loc(&vi.def_loc);
+    /// Highlights the definition (as `WRITE`) and the references (as `READ`) of the name in the document.
+    pub(crate) fn handle_document_highlight(
+        &mut self,
+        params: DocumentHighlightParams,
+    ) -> ELSResult<Option<Vec<DocumentHighlight>>> {
+        let uri = NormalizedUrl::new(params.text_document_position_params.text_document.uri);
+        let pos = params.text_document_position_params.position;
+        let Some(referee) = self.get_referee(&uri, pos) else {
+            return Ok(None);
+        };
+        let mut highlights = vec![];
+        let path = util::uri_to_path(&uri);
+        if referee.module.as_ref() == Some(&path) {
+            if let Some(range) = util::loc_to_range(referee.loc) {
+                highlights.push(DocumentHighlight {
+                    range,
+                    kind: Some(DocumentHighlightKind::WRITE),
+                });
+            }
+        }
+        if let Some(value) = self.get_index().and_then(|ind| ind.get_refs(&referee)) {
+            for referrer in value.referrers.iter() {
+                if referrer.module.as_ref() != Some(&path) {
+                    continue;
+                }
+                if let Some(range) = util::loc_to_range(referrer.loc) {
+                    highlights.push(DocumentHighlight {
+                        range,
+                        kind: Some(DocumentHighlightKind::READ),
+                    });
                 }
             }
         }
-        vec![]
+        Ok(Some(highlights))
+    }
+
+    /// Returns the definition of the name at `pos`.
+    /// The reference index built in the lowering process is looked up first, and the HIR is searched only if not found.
+    fn get_referee(&self, uri: &NormalizedUrl, pos: Position) -> Option<AbsLocation> {
+        let tok = self.file_cache.get_token(uri, pos)?;
+        let path = util::uri_to_path(uri);
+        if let Some(referee) = self
+            .get_index()
+            .and_then(|ind| ind.get_referee(&path, tok.ln_end()?, tok.col_end()?))
+        {
+            return Some(referee);
+        }
+        let visitor = self.get_visitor(uri)?;
+        let vi = visitor.get_info(&tok)?;
+        Some(vi.def_loc)
     }
 
     pub(crate) fn get_refs_from_abs_loc(&self, referee: &AbsLocation) -> Vec<lsp_types::Location> {
@@ -37,16 +89,18 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                 return vec![];
             }
             for referrer in value.referrers.iter() {
-                if let (Some(path), Some(range)) =
-                    (&referrer.module, util::loc_to_range(referrer.loc))
-                {
-                    let Ok(ref_uri) = Url::from_file_path(path) else {
-                        continue;
-                    };
-                    refs.push(lsp_types::Location::new(ref_uri, range));
+                if let Some(loc) = abs_loc_to_lsp_loc(referrer) {
+                    refs.push(loc);
                 }
             }
         }
         refs
     }
 }
+
+pub(crate) fn abs_loc_to_lsp_loc(loc: &AbsLocation) -> Option<lsp_types::Location> {
+    let path = loc.module.as_ref()?;
+    let range = util::loc_to_range(loc.loc)?;
+    let uri = Url::from_file_path(path).ok()?;
+    Some(lsp_types::Location::new(uri, range))
+}
@@ -0,0 +1,66 @@
+//! `textDocument/selectionRange`: expands the selection along the ancestors of the AST node at the cursor.
+//! Only the parser's location data is used, so this works for the code that does not type-check.
+
+use erg_common::traits::{Locational, Stream};
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::erg_parser::ast::Module;
+use erg_compiler::erg_parser::parse::Parsable;
+
+use lsp_types::{Position, Range, SelectionRange, SelectionRangeParams};
+
+use crate::server::{send_log, ELSResult, Server};
+use crate::util::{self, ASTNode, NormalizedUrl};
+
+fn contains(range: &Range, pos: Position) -> bool {
+    range.start <= pos && pos <= range.end
+}
+
+/// Returns the selection range of each position in `positions`.
+/// The innermost range is the AST node at the position, and the parents are the ancestors of the node.
+pub fn selection_ranges(module: &Module, positions: &[Position]) -> Vec<SelectionRange> {
+    positions
+        .iter()
+        .map(|pos| selection_range(module, *pos))
+        .collect()
+}
+
+fn selection_range(module: &Module, pos: Position) -> SelectionRange {
+    let mut ancestors: Vec<Range> = vec![];
+    let mut nodes = module.iter().map(ASTNode::Expr).collect::<Vec<_>>();
+    while let Some((node, range)) = nodes.iter().copied().find_map(|node| {
+        util::loc_to_range(node.loc())
+            .filter(|range| contains(range, pos))
+            .map(|range| (node, range))
+    }) {
+        // desugared nodes may share the location with their children
+        if ancestors.last() != Some(&range) {
+            ancestors.push(range);
+        }
+        nodes = node.children();
+    }
+    let mut selection = None;
+    for range in ancestors {
+        selection = Some(SelectionRange {
+            range,
+            parent: selection.map(Box::new),
+        });
+    }
+    selection.unwrap_or(SelectionRange {
+        range: Range::new(pos, pos),
+        parent: None,
+    })
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn handle_selection_range(
+        &mut self,
+        params: SelectionRangeParams,
+    ) -> ELSResult<Option<Vec<SelectionRange>>> {
+        send_log(format!("selection range requested: {params:?}"))?;
+        let uri = NormalizedUrl::new(params.text_document.uri);
+        let Some(module) = self.get_partial_ast(&uri) else {
+            return Ok(None);
+        };
+        Ok(Some(selection_ranges(&module, &params.positions)))
+    }
+}
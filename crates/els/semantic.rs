@@ -1,306 +1,319 @@
+//! `textDocument/semanticTokens/full` and `textDocument/semanticTokens/full/delta`
+//!
+//! Tokens are classified by the lexer, and names are further classified by their `VarInfo` (found by `HIRVisitor`).
+//! The last tokens of each document are cached with the document version as the result id, so that a delta request is answered with the edits from them.
+
 use erg_common::dict::Dict;
-use erg_common::error::Location;
-use erg_common::traits::{Locational, Runnable};
+use erg_common::shared::Shared;
+use erg_common::traits::{DequeStream, Locational};
 
 use erg_compiler::artifact::BuildRunnable;
-use erg_compiler::erg_parser::ast::{
-    Accessor, Args, BinOp, Block, Call, ClassAttr, Def, DefKind, Expr, Identifier, Methods, Params,
-    PolyTypeSpec, PreDeclTypeSpec, TypeSpec, UnaryOp, AST,
-};
 use erg_compiler::erg_parser::parse::Parsable;
-use erg_compiler::erg_parser::token::TokenKind;
-use erg_compiler::ASTBuilder;
+use erg_compiler::erg_parser::token::{Token, TokenCategory, TokenKind, TokenStream};
+use erg_compiler::ty::Type;
+use erg_compiler::varinfo::{AbsLocation, VarInfo};
 
 use lsp_types::{
-    SemanticToken, SemanticTokenType, SemanticTokens, SemanticTokensParams, SemanticTokensResult,
+    SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens, SemanticTokensDelta,
+    SemanticTokensDeltaParams, SemanticTokensEdit, SemanticTokensFullDeltaResult,
+    SemanticTokensParams, SemanticTokensResult,
 };
 
+use crate::hir_visitor::HIRVisitor;
 use crate::server::{send_log, ELSResult, Server};
-use crate::util::{self, NormalizedUrl};
+use crate::util::NormalizedUrl;
+
+/// The legend of the token types. The index is the value of `SemanticToken::token_type`.
+pub const TOKEN_TYPES: [SemanticTokenType; 13] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::TYPE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::INTERFACE,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::OPERATOR,
+];
+
+/// a variable of a mutable type (`T!`)
+pub const MUTABLE: SemanticTokenModifier = SemanticTokenModifier::new("mutable");
+/// a procedure (`f!`) or a procedural method
+pub const PROCEDURE: SemanticTokenModifier = SemanticTokenModifier::new("procedure");
+
+/// The legend of the token modifiers. The `i`-th bit of `SemanticToken::token_modifiers_bitset` is the `i`-th modifier.
+pub const TOKEN_MODIFIERS: [SemanticTokenModifier; 5] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+    MUTABLE,
+    PROCEDURE,
+];
 
-#[derive(Debug)]
-struct ASTSemanticState {
+fn type_index(typ: &SemanticTokenType) -> u32 {
+    TOKEN_TYPES
+        .iter()
+        .position(|t| t == typ)
+        .unwrap_or(6 /* variable */) as u32
+}
+
+fn modifier_bit(modifier: &SemanticTokenModifier) -> u32 {
+    TOKEN_MODIFIERS
+        .iter()
+        .position(|m| m == modifier)
+        .map_or(0, |i| 1 << i)
+}
+
+/// Classifies a name by the type and the kind of the variable.
+/// `is_attr` means that the name is accessed as an attribute (`x.name`).
+fn classify_var(
+    vi: &VarInfo,
+    is_attr: bool,
+    is_decl: bool,
+) -> (SemanticTokenType, Vec<SemanticTokenModifier>) {
+    let typ = match &vi.t {
+        t if t.is_module() => SemanticTokenType::NAMESPACE,
+        t if t.is_class_type() => SemanticTokenType::CLASS,
+        Type::TraitType => SemanticTokenType::INTERFACE,
+        Type::Type => SemanticTokenType::TYPE,
+        _ if vi.kind.is_parameter() => SemanticTokenType::PARAMETER,
+        t if t.is_method() => SemanticTokenType::METHOD,
+        t if t.is_subr() => SemanticTokenType::FUNCTION,
+        _ if is_attr || vi.kind.is_instance_attr() => SemanticTokenType::PROPERTY,
+        _ => SemanticTokenType::VARIABLE,
+    };
+    let mut modifiers = vec![];
+    if is_decl {
+        modifiers.push(SemanticTokenModifier::DECLARATION);
+    }
+    if vi.kind.is_builtin() {
+        modifiers.push(SemanticTokenModifier::DEFAULT_LIBRARY);
+    }
+    if vi.t.is_procedure() {
+        modifiers.push(PROCEDURE);
+    } else if vi.t.is_mut_type() {
+        modifiers.push(MUTABLE);
+    } else if [
+        SemanticTokenType::PARAMETER,
+        SemanticTokenType::PROPERTY,
+        SemanticTokenType::VARIABLE,
+    ]
+    .contains(&typ)
+    {
+        // variables are immutable unless they are of mutable types
+        modifiers.push(SemanticTokenModifier::READONLY);
+    }
+    (typ, modifiers)
+}
+
+fn is_def_site(def_loc: &AbsLocation, uri: &NormalizedUrl, token: &Token) -> bool {
+    def_loc.loc.ln_begin() == token.ln_begin()
+        && def_loc.loc.col_begin() == token.col_begin()
+        && def_loc
+            .module
+            .as_ref()
+            .and_then(|path| path.canonicalize().ok())
+            .and_then(|path| NormalizedUrl::from_file_path(path).ok())
+            .is_some_and(|def_uri| &def_uri == uri)
+}
+
+pub(crate) struct SemanticTokenGenerator<'a> {
+    uri: &'a NormalizedUrl,
+    visitor: Option<&'a HIRVisitor<'a>>,
     prev_line: u32,
     prev_col: u32,
-    namespaces: Vec<Dict<String, SemanticTokenType>>,
     tokens: Vec<SemanticToken>,
 }
 
-impl ASTSemanticState {
-    fn new() -> Self {
+impl<'a> SemanticTokenGenerator<'a> {
+    pub(crate) fn new(uri: &'a NormalizedUrl, visitor: Option<&'a HIRVisitor<'a>>) -> Self {
         Self {
+            uri,
+            visitor,
             prev_line: 1,
             prev_col: 0,
-            namespaces: vec![Dict::new()],
-            tokens: Vec::new(),
-        }
-    }
-
-    fn get_type(&self, key: &str) -> Option<SemanticTokenType> {
-        for namespace in self.namespaces.iter().rev() {
-            if let Some(typ) = namespace.get(key) {
-                return Some(typ.clone());
-            }
-        }
-        None
-    }
-
-    fn get_variable_type(&self, key: &str) -> SemanticTokenType {
-        self.get_type(key).unwrap_or(SemanticTokenType::VARIABLE)
-    }
-
-    fn push_current_namespace(&mut self, key: String, typ: SemanticTokenType) {
-        let current = self.namespaces.last_mut().unwrap();
-        current.insert(key, typ);
-    }
-
-    fn enumerate_tokens(&mut self, ast: AST) -> SemanticTokens {
-        for expr in ast.module.into_iter() {
-            let tokens = self.gen_from_expr(expr);
-            self.tokens.extend(tokens);
-        }
-        SemanticTokens {
-            result_id: None,
-            data: std::mem::take(&mut self.tokens),
+            tokens: vec![],
         }
     }
 
-    fn token_type_as_u32(token_type: SemanticTokenType) -> u32 {
-        match token_type.as_str() {
-            "namespace" => 0,
-            "type" => 1,
-            "class" => 2,
-            "enum" => 3,
-            "interface" => 4,
-            "struct" => 5,
-            "typeParameter" => 6,
-            "parameter" => 7,
-            "variable" => 8,
-            "property" => 9,
-            "enumMember" => 10,
-            "event" => 11,
-            "function" => 12,
-            "method" => 13,
-            "macro" => 14,
-            "keyword" => 15,
-            "modifier" => 16,
-            "comment" => 17,
-            "string" => 18,
-            "number" => 19,
-            "regexp" => 20,
-            "operator" => 21,
-            _ => 8,
+    fn push(&mut self, token: &Token, typ: SemanticTokenType, modifiers: &[SemanticTokenModifier]) {
+        let (Some(line), Some(col)) = (token.ln_begin(), token.col_begin()) else {
+            return;
+        };
+        // multi-line tokens (e.g. doc comments) are not supported by all clients
+        if token.ln_end() != Some(line) {
+            return;
         }
-    }
-
-    fn gen_token(&mut self, loc: Location, token_type: SemanticTokenType) -> SemanticToken {
-        let delta_line = loc.ln_begin().unwrap_or(1).saturating_sub(self.prev_line);
+        let delta_line = line - self.prev_line;
         let delta_start = if delta_line == 0 {
-            loc.col_begin().unwrap_or(0).saturating_sub(self.prev_col)
+            col - self.prev_col
         } else {
-            loc.col_begin().unwrap_or(0)
+            col
         };
-        let token = SemanticToken {
+        self.tokens.push(SemanticToken {
             delta_line,
             delta_start,
-            length: loc.length().unwrap_or(1),
-            token_type: Self::token_type_as_u32(token_type),
-            token_modifiers_bitset: 0,
-        };
-        self.prev_line = loc.ln_begin().unwrap_or(self.prev_line);
-        self.prev_col = loc.col_begin().unwrap_or(self.prev_col);
-        token
+            length: token.loc().length().unwrap_or(1),
+            token_type: type_index(&typ),
+            token_modifiers_bitset: modifiers.iter().map(modifier_bit).sum(),
+        });
+        self.prev_line = line;
+        self.prev_col = col;
     }
 
-    fn gen_from_typespec(&mut self, t_spec: TypeSpec) -> Vec<SemanticToken> {
-        match t_spec {
-            TypeSpec::PreDeclTy(predecl) => match predecl {
-                PreDeclTypeSpec::Mono(ident) => self.gen_from_ident(ident),
-                PreDeclTypeSpec::Poly(poly) => self.gen_from_poly_typespec(poly),
-                PreDeclTypeSpec::Attr { namespace, t } => {
-                    let mut tokens = self.gen_from_expr(*namespace);
-                    let ts = self.gen_from_ident(t);
-                    tokens.extend(ts);
-                    tokens
+    pub(crate) fn gen(mut self, stream: &TokenStream) -> Vec<SemanticToken> {
+        let stream = stream.iter().collect::<Vec<_>>();
+        for (i, token) in stream.iter().enumerate() {
+            match token.category() {
+                TokenCategory::Symbol => {
+                    // `x.name` (`.name` at the beginning of a line is a public name)
+                    let is_attr = i >= 2
+                        && stream[i - 1].is(TokenKind::Dot)
+                        && !matches!(
+                            stream[i - 2].category(),
+                            TokenCategory::Separator
+                                | TokenCategory::LEnclosure
+                                | TokenCategory::REnclosure
+                                | TokenCategory::BOF
+                        );
+                    let vi = self.visitor.and_then(|visitor| visitor.get_info(token));
+                    let (typ, modifiers) = match vi {
+                        Some(vi) => {
+                            let is_decl = is_def_site(&vi.def_loc, self.uri, token);
+                            classify_var(&vi, is_attr, is_decl)
+                        }
+                        None if is_attr => (SemanticTokenType::PROPERTY, vec![]),
+                        None => (SemanticTokenType::VARIABLE, vec![]),
+                    };
+                    self.push(token, typ, &modifiers);
                 }
-                _ => vec![],
-            },
-            _ => vec![],
-        }
-    }
-
-    fn gen_from_poly_typespec(&mut self, t_spec: PolyTypeSpec) -> Vec<SemanticToken> {
-        let mut tokens = vec![];
-        let token = self.gen_token(t_spec.acc.loc(), SemanticTokenType::TYPE);
-        tokens.push(token);
-        tokens
-    }
-
-    fn gen_from_expr(&mut self, expr: Expr) -> Vec<SemanticToken> {
-        match expr {
-            Expr::Literal(lit) => {
-                let typ = match lit.token.kind {
-                    TokenKind::StrLit => SemanticTokenType::STRING,
+                TokenCategory::Literal
+                | TokenCategory::StrInterpLeft
+                | TokenCategory::StrInterpMid
+                | TokenCategory::StrInterpRight => match token.kind {
+                    TokenKind::StrLit
+                    | TokenKind::DocComment
+                    | TokenKind::StrInterpLeft
+                    | TokenKind::StrInterpMid
+                    | TokenKind::StrInterpRight => {
+                        self.push(token, SemanticTokenType::STRING, &[]);
+                    }
                     TokenKind::NatLit | TokenKind::IntLit | TokenKind::RatioLit => {
-                        SemanticTokenType::NUMBER
+                        self.push(token, SemanticTokenType::NUMBER, &[]);
                     }
-                    _ => SemanticTokenType::VARIABLE,
-                };
-                let token = self.gen_token(lit.loc(), typ);
-                vec![token]
-            }
-            Expr::Def(def) => self.gen_from_def(def),
-            Expr::Lambda(lambda) => self.gen_from_block(Some(lambda.sig.params), lambda.body),
-            Expr::Methods(methods) => self.gen_from_methods(methods),
-            Expr::Accessor(acc) => self.gen_from_acc(acc),
-            Expr::Call(call) => self.gen_from_call(call),
-            Expr::BinOp(bin) => self.gen_from_bin(bin),
-            Expr::UnaryOp(unary) => self.gen_from_unary(unary),
-            _ => vec![],
-        }
-    }
-
-    fn gen_from_def(&mut self, def: Def) -> Vec<SemanticToken> {
-        let mut tokens = vec![];
-        let (_loc, name) = def
-            .sig
-            .ident()
-            .map(|id| (Some(id.name.loc()), id.name.to_string()))
-            .unwrap_or_else(|| (None, "_".to_string()));
-        let typ = match def.def_kind() {
-            DefKind::Class => SemanticTokenType::CLASS,
-            DefKind::Trait => SemanticTokenType::INTERFACE,
-            _ if def.is_subr() => SemanticTokenType::FUNCTION,
-            _ => SemanticTokenType::VARIABLE,
-        };
-        self.push_current_namespace(name, typ);
-        if let Some(decos) = def.sig.decorators() {
-            for deco in decos.iter() {
-                tokens.extend(self.gen_from_expr(deco.expr().clone()));
-            }
-        }
-        // HACK: the cause is unknown, but pushing _loc will break the order of tokens
-        /*if let Some(loc) = loc {
-            tokens.push(self.gen_token(loc, typ));
-        }
-        if let Some(t_spec) = def.sig.t_spec() {
-            tokens.extend(self.gen_from_typespec(t_spec.clone()));
-        }*/
-        let params = def.sig.params();
-        tokens.extend(self.gen_from_block(params, def.body.block));
-        tokens
-    }
-
-    fn gen_from_methods(&mut self, methods: Methods) -> Vec<SemanticToken> {
-        let mut tokens = vec![];
-        tokens.extend(self.gen_from_typespec(methods.class));
-        for attr in methods.attrs.into_iter() {
-            #[allow(clippy::single_match)]
-            match attr {
-                ClassAttr::Def(def) => tokens.extend(self.gen_from_def(def)),
+                    _ => {}
+                },
+                TokenCategory::BinOp | TokenCategory::UnaryOp => {
+                    self.push(token, SemanticTokenType::OPERATOR, &[]);
+                }
                 _ => {}
             }
         }
-        tokens
-    }
-
-    fn gen_from_ident(&mut self, ident: Identifier) -> Vec<SemanticToken> {
-        let typ = self.get_variable_type(ident.inspect());
-        vec![self.gen_token(ident.name.loc(), typ)]
-    }
-
-    fn gen_from_acc(&mut self, acc: Accessor) -> Vec<SemanticToken> {
-        match acc {
-            Accessor::Ident(ident) => self.gen_from_ident(ident),
-            Accessor::Attr(attr) => {
-                let mut tokens = self.gen_from_expr(*attr.obj);
-                tokens.push(self.gen_token(attr.ident.name.loc(), SemanticTokenType::PROPERTY));
-                tokens
-            }
-            _ => vec![],
-        }
+        self.tokens
     }
+}
 
-    fn gen_from_call(&mut self, call: Call) -> Vec<SemanticToken> {
-        let mut tokens = self.gen_from_expr(*call.obj);
-        tokens.extend(self.gen_from_args(call.args));
-        tokens
+/// The edits that transform `old` into `new`: the tokens between the common prefix and suffix are replaced.
+/// `start` and `delete_count` are counted in the integers of the encoded tokens (5 per token).
+pub fn diff_semantic_tokens(
+    old: &[SemanticToken],
+    new: &[SemanticToken],
+) -> Vec<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(l, r)| l == r).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(l, r)| l == r)
+        .count();
+    let deleted = old.len() - prefix - suffix;
+    let inserted = &new[prefix..new.len() - suffix];
+    if deleted == 0 && inserted.is_empty() {
+        return vec![];
     }
+    vec![SemanticTokensEdit {
+        start: prefix as u32 * 5,
+        delete_count: deleted as u32 * 5,
+        data: (!inserted.is_empty()).then(|| inserted.to_vec()),
+    }]
+}
 
-    fn gen_from_args(&mut self, args: Args) -> Vec<SemanticToken> {
-        let mut tokens = vec![];
-        let (pos_args, var_args, kw_args, ..) = args.deconstruct();
-        for arg in pos_args {
-            tokens.extend(self.gen_from_expr(arg.expr));
-        }
-        if let Some(var_args) = var_args {
-            tokens.extend(self.gen_from_expr(var_args.expr));
-        }
-        for arg in kw_args {
-            tokens.extend(self.gen_from_expr(arg.expr));
-        }
-        tokens
-    }
+/// The last tokens of the documents (keyed by the result id, i.e. the document version)
+#[derive(Debug, Clone, Default)]
+pub struct SemanticTokensCache(Shared<Dict<NormalizedUrl, (String, Vec<SemanticToken>)>>);
 
-    fn gen_from_bin(&mut self, bin: BinOp) -> Vec<SemanticToken> {
-        let mut args = bin.args.into_iter();
-        let mut tokens = self.gen_from_expr(*args.next().unwrap());
-        tokens.push(self.gen_token(bin.op.loc(), SemanticTokenType::OPERATOR));
-        tokens.extend(self.gen_from_expr(*args.next().unwrap()));
-        tokens
+impl SemanticTokensCache {
+    pub fn new() -> Self {
+        Self(Shared::new(Dict::new()))
     }
 
-    fn gen_from_unary(&mut self, unary: UnaryOp) -> Vec<SemanticToken> {
-        let mut tokens = vec![self.gen_token(unary.op.loc(), SemanticTokenType::OPERATOR)];
-        let mut args = unary.args.into_iter();
-        tokens.extend(self.gen_from_expr(*args.next().unwrap()));
-        tokens
+    /// Returns the cached tokens if they are of `result_id`
+    fn get(&self, uri: &NormalizedUrl, result_id: &str) -> Option<Vec<SemanticToken>> {
+        self.0
+            .borrow()
+            .get(uri)
+            .filter(|(id, _)| id == result_id)
+            .map(|(_, tokens)| tokens.clone())
     }
 
-    fn gen_from_block(&mut self, params: Option<Params>, block: Block) -> Vec<SemanticToken> {
-        self.namespaces.push(Dict::new());
-        let mut tokens = vec![];
-        if let Some(params) = params {
-            let (nd_params, var_params, d_params, ..) = params.deconstruct();
-            for param in nd_params.into_iter() {
-                let typ = SemanticTokenType::PARAMETER;
-                tokens.push(self.gen_token(param.loc(), typ));
-            }
-            if let Some(var_param) = var_params {
-                let typ = SemanticTokenType::PARAMETER;
-                tokens.push(self.gen_token(var_param.loc(), typ));
-            }
-            for param in d_params.into_iter() {
-                let typ = SemanticTokenType::PARAMETER;
-                tokens.push(self.gen_token(param.loc(), typ));
-            }
-        }
-        for expr in block.into_iter() {
-            tokens.extend(self.gen_from_expr(expr));
-        }
-        self.namespaces.pop();
-        tokens
+    fn insert(&self, uri: NormalizedUrl, result_id: String, tokens: Vec<SemanticToken>) {
+        self.0.borrow_mut().insert(uri, (result_id, tokens));
     }
 }
 
 impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    /// Returns the result id (the document version) and the tokens
+    fn gen_semantic_tokens(&self, uri: &NormalizedUrl) -> Option<(String, Vec<SemanticToken>)> {
+        let stream = self.file_cache.get_token_stream(uri)?;
+        let ver = self.file_cache.get_ver(uri)?;
+        let visitor = self.get_visitor(uri);
+        let data = SemanticTokenGenerator::new(uri, visitor.as_ref()).gen(&stream);
+        let result_id = ver.to_string();
+        self.sem_tokens_cache
+            .insert(uri.clone(), result_id.clone(), data.clone());
+        Some((result_id, data))
+    }
+
     pub(crate) fn handle_semantic_tokens_full(
         &mut self,
         params: SemanticTokensParams,
     ) -> ELSResult<Option<SemanticTokensResult>> {
         send_log(format!("full semantic tokens request: {params:?}"))?;
         let uri = NormalizedUrl::new(params.text_document.uri);
-        let path = util::uri_to_path(&uri);
-        let src = self.file_cache.get_entire_code(&uri)?;
-        let mut builder = ASTBuilder::new(self.cfg.inherit(path));
-        let result = match builder.build_without_desugaring(src) {
-            Ok(artifact) => {
-                let mut state = ASTSemanticState::new();
-                let tokens = state.enumerate_tokens(artifact.ast);
-                Some(SemanticTokensResult::Tokens(tokens))
-            }
-            Err(_) => None,
-        };
+        let result = self.gen_semantic_tokens(&uri).map(|(result_id, data)| {
+            SemanticTokensResult::Tokens(SemanticTokens {
+                result_id: Some(result_id),
+                data,
+            })
+        });
         Ok(result)
     }
+
+    pub(crate) fn handle_semantic_tokens_full_delta(
+        &mut self,
+        params: SemanticTokensDeltaParams,
+    ) -> ELSResult<Option<SemanticTokensFullDeltaResult>> {
+        send_log(format!("delta semantic tokens request: {params:?}"))?;
+        let uri = NormalizedUrl::new(params.text_document.uri);
+        let prev = self.sem_tokens_cache.get(&uri, &params.previous_result_id);
+        let Some((result_id, data)) = self.gen_semantic_tokens(&uri) else {
+            return Ok(None);
+        };
+        let result = match prev {
+            Some(prev) => SemanticTokensFullDeltaResult::TokensDelta(SemanticTokensDelta {
+                edits: diff_semantic_tokens(&prev, &data),
+                result_id: Some(result_id),
+            }),
+            // the previous tokens are unknown (or outdated), so all tokens are returned
+            None => SemanticTokensFullDeltaResult::Tokens(SemanticTokens {
+                result_id: Some(result_id),
+                data,
+            }),
+        };
+        Ok(Some(result))
+    }
 }
@@ -26,17 +26,20 @@ use erg_compiler::module::{SharedCompilerResource, SharedModuleIndex};
 use erg_compiler::ty::HasType;
 
 use lsp_types::request::{
-    CodeActionRequest, CodeActionResolveRequest, CodeLensRequest, Completion, ExecuteCommand,
-    GotoDefinition, HoverRequest, InlayHintRequest, References, Rename, Request,
-    ResolveCompletionItem, SemanticTokensFullRequest, SignatureHelpRequest, WillRenameFiles,
+    CodeActionRequest, CodeActionResolveRequest, CodeLensRequest, Completion,
+    DocumentHighlightRequest, DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest,
+    GotoDefinition, HoverRequest, InlayHintRequest, OnTypeFormatting, References, Rename, Request,
+    ResolveCompletionItem, SelectionRangeRequest, SemanticTokensFullDeltaRequest,
+    SemanticTokensFullRequest, SignatureHelpRequest, WillRenameFiles, WorkspaceSymbol,
 };
 use lsp_types::{
     ClientCapabilities, CodeActionKind, CodeActionOptions, CodeActionProviderCapability,
-    CodeLensOptions, CompletionOptions, DidChangeTextDocumentParams, DidOpenTextDocumentParams,
-    ExecuteCommandOptions, HoverProviderCapability, InitializeResult, OneOf, Position,
-    SemanticTokenType, SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions,
-    SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelpOptions,
-    WorkDoneProgressOptions,
+    CodeLensOptions, CompletionOptions, DidChangeConfigurationParams, DidChangeTextDocumentParams,
+    DidOpenTextDocumentParams, DocumentOnTypeFormattingOptions, ExecuteCommandOptions,
+    FoldingRangeProviderCapability, HoverProviderCapability, InitializeResult, OneOf, Position,
+    SelectionRangeProviderCapability, SemanticTokensFullOptions, SemanticTokensLegend,
+    SemanticTokensOptions, SemanticTokensServerCapabilities, ServerCapabilities,
+    SignatureHelpOptions, WorkDoneProgressOptions,
 };
 
 use serde::{Deserialize, Serialize};
@@ -45,9 +48,15 @@ use serde_json::Value;
 
 use crate::channels::{SendChannels, Sendable};
 use crate::completion::CompletionCache;
+use crate::desugared::ViewDesugared;
+use crate::diagnostics::SuggestedEdit;
 use crate::file_cache::FileCache;
 use crate::hir_visitor::HIRVisitor;
 use crate::message::{ErrorMessage, LSPResult, LogMessage, ShowMessage};
+use crate::progress::ProgressReporter;
+use crate::semantic::{SemanticTokensCache, TOKEN_MODIFIERS, TOKEN_TYPES};
+use crate::settings::ServerSettings;
+use crate::test_runner::ListTests;
 use crate::util::{self, NormalizedUrl};
 
 pub type ELSResult<T> = Result<T, Box<dyn std::error::Error>>;
@@ -63,13 +72,19 @@ pub enum DefaultFeatures {
     CodeLens,
     Completion,
     Diagnostics,
+    DocumentHighlight,
+    DocumentSymbol,
     FindReferences,
+    FoldingRange,
     GotoDefinition,
     Hover,
     InlayHint,
+    OnTypeFormatting,
     Rename,
+    SelectionRange,
     SemanticTokens,
     SignatureHelp,
+    WorkspaceSymbol,
     /* ELS specific features */
     SmartCompletion,
     DeepCompletion,
@@ -77,11 +92,23 @@ pub enum DefaultFeatures {
 
 impl From<&str> for DefaultFeatures {
     fn from(s: &str) -> Self {
-        match s {
+        Self::parse(s).unwrap_or_else(|| panic!("unknown feature: {s}"))
+    }
+}
+
+impl DefaultFeatures {
+    pub fn parse(s: &str) -> Option<Self> {
+        let feature = match s {
             "codeaction" | "codeAction" | "code-action" => DefaultFeatures::CodeAction,
             "codelens" | "codeLens" | "code-lens" => DefaultFeatures::CodeLens,
             "completion" => DefaultFeatures::Completion,
             "diagnostic" | "diagnostics" => DefaultFeatures::Diagnostics,
+            "documenthighlight" | "documentHighlight" | "document-highlight" => {
+                DefaultFeatures::DocumentHighlight
+            }
+            "documentsymbol" | "documentSymbol" | "document-symbol" => {
+                DefaultFeatures::DocumentSymbol
+            }
             "hover" => DefaultFeatures::Hover,
             "semantictoken" | "semantictokens" | "semanticToken" | "semanticTokens"
             | "semantic-tokens" => DefaultFeatures::SemanticTokens,
@@ -91,18 +118,29 @@ impl From<&str> for DefaultFeatures {
             "findreferences" | "findReferences" | "find-references" => {
                 DefaultFeatures::FindReferences
             }
+            "foldingrange" | "foldingRange" | "folding-range" => DefaultFeatures::FoldingRange,
             "gotodefinition" | "gotoDefinition" | "goto-completion" => {
                 DefaultFeatures::GotoDefinition
             }
+            "ontypeformatting" | "onTypeFormatting" | "on-type-formatting" => {
+                DefaultFeatures::OnTypeFormatting
+            }
+            "selectionrange" | "selectionRange" | "selection-range" => {
+                DefaultFeatures::SelectionRange
+            }
             "signaturehelp" | "signatureHelp" | "signature-help" => DefaultFeatures::SignatureHelp,
+            "workspacesymbol" | "workspaceSymbol" | "workspace-symbol" => {
+                DefaultFeatures::WorkspaceSymbol
+            }
             "smartcompletion" | "smartCompletion" | "smart-completion" => {
                 DefaultFeatures::SmartCompletion
             }
             "deepcompletion" | "deepCompletion" | "deep-completion" => {
                 DefaultFeatures::DeepCompletion
             }
-            _ => panic!("unknown feature: {s}"),
-        }
+            _ => return None,
+        };
+        Some(feature)
     }
 }
 
@@ -113,9 +151,15 @@ pub enum OptionalFeatures {
 
 impl From<&str> for OptionalFeatures {
     fn from(s: &str) -> Self {
+        Self::parse(s).unwrap_or_else(|| panic!("unknown feature: {s}"))
+    }
+}
+
+impl OptionalFeatures {
+    pub fn parse(s: &str) -> Option<Self> {
         match s {
-            "checkontype" | "checkOnType" | "check-on-type" => OptionalFeatures::CheckOnType,
-            _ => panic!("unknown feature: {s}"),
+            "checkontype" | "checkOnType" | "check-on-type" => Some(OptionalFeatures::CheckOnType),
+            _ => None,
         }
     }
 }
@@ -266,6 +310,23 @@ impl AnalysisResultCache {
     pub fn remove(&self, uri: &NormalizedUrl) -> Option<AnalysisResult> {
         self.0.borrow_mut().remove(uri)
     }
+
+    /// The edits to `uri` suggested by the errors of all files (including `uri` itself)
+    pub(crate) fn get_suggested_edits_to(&self, uri: &NormalizedUrl) -> Vec<SuggestedEdit> {
+        let mut edits = vec![];
+        for (err_uri, result) in self.0.borrow().iter() {
+            for err in result.artifact.errors.iter() {
+                edits.extend(
+                    err.core
+                        .suggestions
+                        .iter()
+                        .filter_map(|sugg| SuggestedEdit::new(sugg, err_uri))
+                        .filter(|edit| &NormalizedUrl::new(edit.uri.clone()) == uri),
+                );
+            }
+        }
+        edits
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -312,8 +373,11 @@ pub struct Server<Checker: BuildRunnable = HIRBuilder, Parser: Parsable = Simple
     pub(crate) client_capas: ClientCapabilities,
     pub(crate) disabled_features: Vec<DefaultFeatures>,
     pub(crate) opt_features: Vec<OptionalFeatures>,
+    pub(crate) settings: Shared<ServerSettings>,
+    pub(crate) progress: ProgressReporter,
     pub(crate) file_cache: FileCache,
     pub(crate) comp_cache: CompletionCache,
+    pub(crate) sem_tokens_cache: SemanticTokensCache,
     // TODO: remove modules, analysis_result, and add `shared: SharedCompilerResource`
     pub(crate) modules: ModuleCache,
     pub(crate) analysis_result: AnalysisResultCache,
@@ -332,8 +396,11 @@ impl<C: BuildRunnable, P: Parsable> Clone for Server<C, P> {
             client_capas: self.client_capas.clone(),
             disabled_features: self.disabled_features.clone(),
             opt_features: self.opt_features.clone(),
+            settings: self.settings.clone(),
+            progress: self.progress.clone(),
             file_cache: self.file_cache.clone(),
             comp_cache: self.comp_cache.clone(),
+            sem_tokens_cache: self.sem_tokens_cache.clone(),
             modules: self.modules.clone(),
             analysis_result: self.analysis_result.clone(),
             current_sig: self.current_sig.clone(),
@@ -354,7 +421,10 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             client_capas: ClientCapabilities::default(),
             disabled_features: vec![],
             opt_features: vec![],
+            settings: Shared::new(ServerSettings::default()),
+            progress: ProgressReporter::default(),
             file_cache: FileCache::new(),
+            sem_tokens_cache: SemanticTokensCache::new(),
             modules: ModuleCache::new(),
             analysis_result: AnalysisResultCache::new(),
             current_sig: None,
@@ -390,6 +460,13 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             self.client_capas = ClientCapabilities::deserialize(&msg["params"]["capabilities"])?;
             // send_log(format!("set client capabilities: {:?}", self.client_capas))?;
         }
+        let work_done_progress = self
+            .client_capas
+            .window
+            .as_ref()
+            .and_then(|window| window.work_done_progress)
+            .unwrap_or(false);
+        self.progress = ProgressReporter::new(work_done_progress);
         let mut args = self.cfg.runtime_args.iter();
         while let Some(&arg) = args.next() {
             if arg == "--disable" {
@@ -402,6 +479,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                 }
             }
         }
+        if let Some(options) = msg["params"].get("initializationOptions") {
+            self.update_settings(options)?;
+        }
         let mut result = InitializeResult::default();
         result.capabilities = ServerCapabilities::default();
         self.file_cache.set_capabilities(&mut result.capabilities);
@@ -411,48 +491,37 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         result.capabilities.completion_provider = Some(comp_options);
         result.capabilities.rename_provider = Some(OneOf::Left(true));
         result.capabilities.references_provider = Some(OneOf::Left(true));
+        result.capabilities.document_highlight_provider = self
+            .is_disabled(DefaultFeatures::DocumentHighlight)
+            .not()
+            .then_some(OneOf::Left(true));
+        result.capabilities.workspace_symbol_provider = self
+            .is_disabled(DefaultFeatures::WorkspaceSymbol)
+            .not()
+            .then_some(OneOf::Left(true));
         result.capabilities.definition_provider = Some(OneOf::Left(true));
         result.capabilities.hover_provider = self
-            .disabled_features
-            .contains(&DefaultFeatures::Hover)
+            .is_disabled(DefaultFeatures::Hover)
             .not()
             .then_some(HoverProviderCapability::Simple(true));
         result.capabilities.inlay_hint_provider = self
-            .disabled_features
-            .contains(&DefaultFeatures::InlayHint)
+            .is_disabled(DefaultFeatures::InlayHint)
             .not()
             .then_some(OneOf::Left(true));
         let mut sema_options = SemanticTokensOptions::default();
         sema_options.range = Some(false);
-        sema_options.full = Some(SemanticTokensFullOptions::Bool(true));
+        sema_options.full = Some(SemanticTokensFullOptions::Delta { delta: Some(true) });
         sema_options.legend = SemanticTokensLegend {
-            token_types: vec![
-                SemanticTokenType::NAMESPACE,
-                SemanticTokenType::TYPE,
-                SemanticTokenType::CLASS,
-                SemanticTokenType::INTERFACE,
-                SemanticTokenType::TYPE_PARAMETER,
-                SemanticTokenType::PARAMETER,
-                SemanticTokenType::VARIABLE,
-                SemanticTokenType::PROPERTY,
-                SemanticTokenType::FUNCTION,
-                SemanticTokenType::METHOD,
-                SemanticTokenType::STRING,
-                SemanticTokenType::NUMBER,
-                SemanticTokenType::OPERATOR,
-            ],
-            token_modifiers: vec![],
+            token_types: TOKEN_TYPES.to_vec(),
+            token_modifiers: TOKEN_MODIFIERS.to_vec(),
         };
         result.capabilities.semantic_tokens_provider = self
-            .disabled_features
-            .contains(&DefaultFeatures::SemanticTokens)
+            .is_disabled(DefaultFeatures::SemanticTokens)
             .not()
             .then_some(SemanticTokensServerCapabilities::SemanticTokensOptions(
                 sema_options,
             ));
-        result.capabilities.code_action_provider = if self
-            .disabled_features
-            .contains(&DefaultFeatures::CodeAction)
+        result.capabilities.code_action_provider = if self.is_disabled(DefaultFeatures::CodeAction)
         {
             None
         } else {
@@ -464,12 +533,16 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             Some(options)
         };
         result.capabilities.execute_command_provider = Some(ExecuteCommandOptions {
-            commands: vec![format!("{}.eliminate_unused_vars", self.mode())],
+            commands: vec![
+                format!("{}.eliminate_unused_vars", self.mode()),
+                format!("{}.run", self.mode()),
+                format!("{}.check", self.mode()),
+                format!("{}.runTest", self.mode()),
+            ],
             work_done_progress_options: WorkDoneProgressOptions::default(),
         });
         result.capabilities.signature_help_provider = self
-            .disabled_features
-            .contains(&DefaultFeatures::SignatureHelp)
+            .is_disabled(DefaultFeatures::SignatureHelp)
             .not()
             .then_some(SignatureHelpOptions {
                 trigger_characters: Some(vec!["(".to_string(), ",".to_string(), "|".to_string()]),
@@ -478,6 +551,25 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                     work_done_progress: None,
                 },
             });
+        result.capabilities.document_on_type_formatting_provider = self
+            .is_disabled(DefaultFeatures::OnTypeFormatting)
+            .not()
+            .then_some(DocumentOnTypeFormattingOptions {
+                first_trigger_character: "\n".to_string(),
+                more_trigger_character: None,
+            });
+        result.capabilities.selection_range_provider = self
+            .is_disabled(DefaultFeatures::SelectionRange)
+            .not()
+            .then_some(SelectionRangeProviderCapability::Simple(true));
+        result.capabilities.folding_range_provider = self
+            .is_disabled(DefaultFeatures::FoldingRange)
+            .not()
+            .then_some(FoldingRangeProviderCapability::Simple(true));
+        result.capabilities.document_symbol_provider = self
+            .is_disabled(DefaultFeatures::DocumentSymbol)
+            .not()
+            .then_some(OneOf::Left(true));
         result.capabilities.code_lens_provider = Some(CodeLensOptions {
             resolve_provider: Some(false),
         });
@@ -505,9 +597,17 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             receivers.semantic_tokens_full,
             Self::handle_semantic_tokens_full,
         );
+        self.start_service::<SemanticTokensFullDeltaRequest>(
+            receivers.semantic_tokens_full_delta,
+            Self::handle_semantic_tokens_full_delta,
+        );
         self.start_service::<InlayHintRequest>(receivers.inlay_hint, Self::handle_inlay_hint);
         self.start_service::<HoverRequest>(receivers.hover, Self::handle_hover);
         self.start_service::<References>(receivers.references, Self::handle_references);
+        self.start_service::<DocumentHighlightRequest>(
+            receivers.document_highlight,
+            Self::handle_document_highlight,
+        );
         self.start_service::<CodeLensRequest>(receivers.code_lens, Self::handle_code_lens);
         self.start_service::<CodeActionRequest>(receivers.code_action, Self::handle_code_action);
         self.start_service::<CodeActionResolveRequest>(
@@ -526,6 +626,28 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             receivers.execute_command,
             Self::handle_execute_command,
         );
+        self.start_service::<WorkspaceSymbol>(
+            receivers.workspace_symbol,
+            Self::handle_workspace_symbol,
+        );
+        self.start_service::<OnTypeFormatting>(
+            receivers.on_type_formatting,
+            Self::handle_on_type_formatting,
+        );
+        self.start_service::<SelectionRangeRequest>(
+            receivers.selection_range,
+            Self::handle_selection_range,
+        );
+        self.start_service::<FoldingRangeRequest>(
+            receivers.folding_range,
+            Self::handle_folding_range,
+        );
+        self.start_service::<DocumentSymbolRequest>(
+            receivers.document_symbol,
+            Self::handle_document_symbol,
+        );
+        self.start_service::<ViewDesugared>(receivers.view_desugared, Self::handle_view_desugared);
+        self.start_service::<ListTests>(receivers.list_tests, Self::handle_list_tests);
         self.start_auto_diagnostics();
     }
 
@@ -616,8 +738,9 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             msg.get("method").and_then(|m| m.as_str()),
         ) {
             (Some(id), Some(method)) => self.handle_request(&msg, id, method),
-            (Some(_id), None) => {
-                // ignore at this time
+            (Some(id), None) => {
+                // a response to a request of the server
+                self.progress.on_response(id, msg.get("error").is_some());
                 Ok(())
             }
             (None, Some(notification)) => self.handle_notification(&msg, notification),
@@ -665,9 +788,15 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             GotoDefinition::METHOD => self.parse_send::<GotoDefinition>(id, msg),
             HoverRequest::METHOD => self.parse_send::<HoverRequest>(id, msg),
             References::METHOD => self.parse_send::<References>(id, msg),
+            DocumentHighlightRequest::METHOD => {
+                self.parse_send::<DocumentHighlightRequest>(id, msg)
+            }
             SemanticTokensFullRequest::METHOD => {
                 self.parse_send::<SemanticTokensFullRequest>(id, msg)
             }
+            SemanticTokensFullDeltaRequest::METHOD => {
+                self.parse_send::<SemanticTokensFullDeltaRequest>(id, msg)
+            }
             InlayHintRequest::METHOD => self.parse_send::<InlayHintRequest>(id, msg),
             CodeActionRequest::METHOD => self.parse_send::<CodeActionRequest>(id, msg),
             CodeActionResolveRequest::METHOD => {
@@ -677,13 +806,23 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             CodeLensRequest::METHOD => self.parse_send::<CodeLensRequest>(id, msg),
             WillRenameFiles::METHOD => self.parse_send::<WillRenameFiles>(id, msg),
             ExecuteCommand::METHOD => self.parse_send::<ExecuteCommand>(id, msg),
+            WorkspaceSymbol::METHOD => self.parse_send::<WorkspaceSymbol>(id, msg),
+            OnTypeFormatting::METHOD => self.parse_send::<OnTypeFormatting>(id, msg),
+            SelectionRangeRequest::METHOD => self.parse_send::<SelectionRangeRequest>(id, msg),
+            FoldingRangeRequest::METHOD => self.parse_send::<FoldingRangeRequest>(id, msg),
+            DocumentSymbolRequest::METHOD => self.parse_send::<DocumentSymbolRequest>(id, msg),
+            ViewDesugared::METHOD => self.parse_send::<ViewDesugared>(id, msg),
+            ListTests::METHOD => self.parse_send::<ListTests>(id, msg),
             other => send_error(Some(id), -32600, format!("{other} is not supported")),
         }
     }
 
     fn handle_notification(&mut self, msg: &Value, method: &str) -> ELSResult<()> {
         match method {
-            "initialized" => send_log("successfully bound"),
+            "initialized" => {
+                send_log("successfully bound")?;
+                self.progress.create_token()
+            }
             "exit" => self.exit(),
             "textDocument/didOpen" => {
                 let params = DidOpenTextDocumentParams::deserialize(msg["params"].clone())?;
@@ -718,6 +857,10 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                 self.file_cache.incremental_update(params);
                 Ok(())
             }
+            "workspace/didChangeConfiguration" => {
+                let params = DidChangeConfigurationParams::deserialize(msg["params"].clone())?;
+                self.handle_did_change_configuration(params)
+            }
             _ => send_log(format!("received notification: {method}")),
         }
     }
@@ -726,12 +869,19 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         if let Some(shared) = self.get_shared() {
             let shared = shared.clone();
             shared.clear(&path);
-            Checker::inherit(self.cfg.inherit(path), shared)
+            Checker::inherit(self.checker_cfg(path), shared)
         } else {
-            Checker::new(self.cfg.inherit(path))
+            Checker::new(self.checker_cfg(path))
         }
     }
 
+    /// The configuration of the command line with the settings of the client applied
+    fn checker_cfg(&self, path: PathBuf) -> ErgConfig {
+        let mut cfg = self.cfg.inherit(path);
+        self.settings.borrow().apply(&mut cfg);
+        cfg
+    }
+
     pub(crate) fn steal_lowerer(&mut self, uri: &NormalizedUrl) -> Option<ASTLowerer> {
         let module = self.modules.remove(uri)?;
         Some(ASTLowerer::new_with_ctx(module))
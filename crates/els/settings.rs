@@ -0,0 +1,196 @@
+//! The settings given by the client (`initializationOptions` and `workspace/didChangeConfiguration`).
+//! They are applied to the configuration of the subsequent checks without restarting the server.
+
+use erg_common::config::ErgConfig;
+use erg_common::python_util::{detect_magic_number, get_python_version, PythonVersion};
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::erg_parser::parse::Parsable;
+
+use lsp_types::DidChangeConfigurationParams;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::server::{send_log, DefaultFeatures, ELSResult, OptionalFeatures, Server};
+use crate::util::NormalizedUrl;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckingMode {
+    Strict,
+    /// Lenient checking for Python-compatible code (same as the `py_compat` feature)
+    PyCompat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InlayHintSettings {
+    pub variable_types: bool,
+    pub parameter_types: bool,
+    pub return_types: bool,
+    pub type_bounds: bool,
+    pub parameter_names: bool,
+}
+
+impl Default for InlayHintSettings {
+    fn default() -> Self {
+        Self {
+            variable_types: true,
+            parameter_types: true,
+            return_types: true,
+            type_bounds: true,
+            parameter_names: true,
+        }
+    }
+}
+
+/// The Python interpreter specified by `pythonPath`, resolved once when the settings are changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PythonEnv {
+    pub command: &'static str,
+    pub magic_num: u32,
+    pub version: PythonVersion,
+}
+
+impl PythonEnv {
+    fn new(command: &str) -> Self {
+        Self {
+            command: Box::leak(command.to_string().into_boxed_str()),
+            magic_num: detect_magic_number(command),
+            version: get_python_version(command),
+        }
+    }
+}
+
+/// The unset items keep the values given by the command line.
+///
+/// ```json
+/// {
+///     "erg": {
+///         "checkingMode": "pyCompat",
+///         "inlayHints": { "parameterNames": false },
+///         "pythonPath": "/usr/bin/python3.11",
+///         "disabledFeatures": ["semanticTokens"]
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServerSettings {
+    pub checking_mode: Option<CheckingMode>,
+    pub inlay_hints: InlayHintSettings,
+    pub python_path: Option<String>,
+    /// The same names as the `--disable` option
+    pub disabled_features: Vec<String>,
+    /// The same names as the `--enable` option
+    pub enabled_features: Vec<String>,
+    #[serde(skip)]
+    pub python: Option<PythonEnv>,
+}
+
+impl ServerSettings {
+    /// Parses the section named `mode` (`"erg"` or `"pylyzer"`) of `value`, or `value` itself if there is no such section.
+    pub fn parse(value: &Value, mode: &str) -> ELSResult<Self> {
+        let section = value.get(mode).unwrap_or(value);
+        if section.is_null() {
+            return Ok(Self::default());
+        }
+        Ok(Self::deserialize(section)?)
+    }
+
+    /// Whether the checking results depend on the difference between `self` and `other`
+    pub fn affects_checking(&self, other: &Self) -> bool {
+        self.checking_mode != other.checking_mode || self.python_path != other.python_path
+    }
+
+    pub fn apply(&self, cfg: &mut ErgConfig) {
+        if let Some(mode) = self.checking_mode {
+            cfg.py_compat = mode == CheckingMode::PyCompat;
+        }
+        if let Some(python) = &self.python {
+            cfg.py_command = Some(python.command);
+            cfg.py_magic_num = Some(python.magic_num);
+            cfg.target_version = Some(python.version);
+        }
+    }
+
+    /// Unknown feature names are ignored (unlike the command line options, they should not stop the server)
+    pub fn disabled_features(&self) -> impl Iterator<Item = DefaultFeatures> + '_ {
+        self.disabled_features
+            .iter()
+            .filter_map(|name| DefaultFeatures::parse(name))
+    }
+
+    pub fn enabled_features(&self) -> impl Iterator<Item = OptionalFeatures> + '_ {
+        self.enabled_features
+            .iter()
+            .filter_map(|name| OptionalFeatures::parse(name))
+    }
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn is_disabled(&self, feature: DefaultFeatures) -> bool {
+        self.disabled_features.contains(&feature)
+            || self
+                .settings
+                .borrow()
+                .disabled_features()
+                .any(|f| f == feature)
+    }
+
+    #[allow(unused)]
+    pub(crate) fn is_enabled(&self, feature: OptionalFeatures) -> bool {
+        self.opt_features.contains(&feature)
+            || self
+                .settings
+                .borrow()
+                .enabled_features()
+                .any(|f| f == feature)
+    }
+
+    pub(crate) fn inlay_hint_settings(&self) -> InlayHintSettings {
+        self.settings.borrow().inlay_hints
+    }
+
+    pub(crate) fn update_settings(&mut self, value: &Value) -> ELSResult<()> {
+        let mut settings = ServerSettings::parse(value, self.mode())?;
+        send_log(format!("settings updated: {settings:?}"))?;
+        let old = self.settings.borrow().clone();
+        settings.python = if settings.python_path == old.python_path {
+            old.python
+        } else {
+            settings.python_path.as_deref().map(PythonEnv::new)
+        };
+        let affects_checking = settings.affects_checking(&old);
+        *self.settings.borrow_mut() = settings;
+        if affects_checking {
+            self.recheck_all()?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn handle_did_change_configuration(
+        &mut self,
+        params: DidChangeConfigurationParams,
+    ) -> ELSResult<()> {
+        self.update_settings(&params.settings)
+    }
+
+    /// Re-checks all the checked files with the new configuration.
+    /// All the caches are cleared first, so that the modules are not shared with the old configuration.
+    fn recheck_all(&mut self) -> ELSResult<()> {
+        let uris = self
+            .file_cache
+            .entries()
+            .into_iter()
+            .filter(|uri| self.analysis_result.get(uri).is_some())
+            .collect::<Vec<NormalizedUrl>>();
+        for uri in uris.iter() {
+            self.clear_cache(uri);
+        }
+        for uri in uris {
+            let code = self.file_cache.get_entire_code(&uri)?;
+            self.check_file(uri, code)?;
+        }
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use erg_common::traits::{DequeStream, Locational, NoTypeDisplay};
+use erg_common::Str;
 use erg_compiler::artifact::BuildRunnable;
 use erg_compiler::erg_parser::parse::Parsable;
-use erg_compiler::erg_parser::token::{Token, TokenKind};
-use erg_compiler::hir::Expr;
-use erg_compiler::ty::{HasType, ParamTy};
+use erg_compiler::erg_parser::token::{Token, TokenCategory, TokenKind, TokenStream};
+use erg_compiler::hir::{Args, Call, Expr};
+use erg_compiler::ty::free::Constraint;
+use erg_compiler::ty::{HasType, ParamTy, Type};
+use erg_compiler::varinfo::VarInfo;
 
 use lsp_types::{
     ParameterInformation, ParameterLabel, Position, SignatureHelp, SignatureHelpContext,
@@ -58,8 +61,11 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             .map(Trigger::from);
         let result = match trigger {
             Some(Trigger::Paren) => self.get_first_help(&uri, pos),
-            Some(Trigger::Comma) => self.get_continuous_help(&uri, pos),
-            Some(Trigger::VBar) | None => None,
+            Some(Trigger::Comma) => self
+                .get_continuous_help(&uri, pos)
+                .or_else(|| self.get_type_param_help(&uri, pos)),
+            Some(Trigger::VBar) => self.get_type_param_help(&uri, pos),
+            None => None,
         };
         Ok(result)
     }
@@ -118,7 +124,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
                     return None;
                 }
                 let nth = self.nth(uri, call.args.loc(), &token) as u32;
-                return self.make_sig_help(call.obj.as_ref(), nth);
+                return self.make_call_sig_help(uri, call, nth);
             }
         } else {
             crate::_log!("failed to get the token");
@@ -128,7 +134,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
 
     fn get_first_help(&mut self, uri: &NormalizedUrl, pos: Position) -> Option<SignatureHelp> {
         if let Some((_token, Expr::Accessor(acc))) = self.get_min_expr(uri, pos, -2) {
-            return self.make_sig_help(&acc, 0);
+            return self.make_sig_help(uri, &acc, Some(acc.var_info()), None, 0);
         } else {
             crate::_log!("lex error occurred");
         }
@@ -138,7 +144,7 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
     fn get_continuous_help(&mut self, uri: &NormalizedUrl, pos: Position) -> Option<SignatureHelp> {
         if let Some((comma, Expr::Call(call))) = self.get_min_expr(uri, pos, -1) {
             let nth = self.nth(uri, call.args.loc(), &comma) as u32 + 1;
-            let help = self.make_sig_help(call.obj.as_ref(), nth);
+            let help = self.make_call_sig_help(uri, &call, nth);
             self.current_sig = Some(Expr::Call(call));
             return help;
         } else {
@@ -147,14 +153,111 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
         None
     }
 
+    fn get_type_param_help(&self, uri: &NormalizedUrl, pos: Position) -> Option<SignatureHelp> {
+        let stream = self.file_cache.get_token_stream(uri)?;
+        let (callee, nth, keyword) = type_args_at(&stream, pos)?;
+        let t = self
+            .get_visitor(uri)
+            .and_then(|visitor| visitor.get_info(&callee))
+            .map(|vi| vi.t)
+            .or_else(|| {
+                self.get_local_ctx(uri, pos)
+                    .into_iter()
+                    .find_map(|ctx| ctx.get_var_info(&callee.content))
+                    .map(|(_, vi)| vi.t.clone())
+            })?;
+        type_param_help(&callee.content, &t, nth, keyword)
+    }
+
+    /// The type of the callee as declared.
+    /// The callee in the HIR has the overload resolved by the arguments, so the original overloads are looked up in the defining module.
+    fn declared_type(&self, sig_t: &Type, vi: Option<&VarInfo>) -> Type {
+        let declared = vi.and_then(|vi| {
+            let shared = self.get_shared()?;
+            let path = vi.def_loc.module.as_ref()?;
+            let entry = shared
+                .mod_cache
+                .get(path)
+                .or_else(|| shared.py_mod_cache.get(path))?;
+            let (_, _, decl) = entry
+                .module
+                .context
+                .local_members()
+                .into_iter()
+                .find(|(_, _, decl)| decl.def_loc == vi.def_loc)?;
+            (decl.t.intersection_types().len() > 1).then(|| decl.t.clone())
+        });
+        declared.unwrap_or_else(|| sig_t.clone())
+    }
+
+    fn make_call_sig_help(
+        &self,
+        uri: &NormalizedUrl,
+        call: &Call,
+        nth: u32,
+    ) -> Option<SignatureHelp> {
+        match &call.attr_name {
+            Some(attr) => self.make_sig_help(uri, attr, Some(&attr.vi), Some(&call.args), nth),
+            None => self.make_sig_help(
+                uri,
+                call.obj.as_ref(),
+                call.obj.var_info(),
+                Some(&call.args),
+                nth,
+            ),
+        }
+    }
+
     fn make_sig_help<S: HasType + NoTypeDisplay>(
         &self,
+        uri: &NormalizedUrl,
         sig: &S,
+        vi: Option<&VarInfo>,
+        args: Option<&Args>,
         nth: u32,
     ) -> Option<SignatureHelp> {
-        let sig_t = sig.ref_t();
-        let mut parameters = vec![];
+        let sig_t = self.declared_type(sig.ref_t(), vi);
         let sig = sig.to_string_notype();
+        let overloads = sig_t.intersection_types();
+        let signatures = overloads
+            .iter()
+            .filter_map(|overload| Self::make_sig_info(&sig, overload, nth))
+            .collect::<Vec<_>>();
+        if signatures.is_empty() {
+            return None;
+        }
+        // select the overload that accepts the arguments before the current one
+        let active_signature = if overloads.len() > 1 {
+            let pos_args = args.map_or(vec![], |args| {
+                args.pos_args
+                    .iter()
+                    .take(nth as usize)
+                    .map(|arg| arg.expr.t())
+                    .collect()
+            });
+            let kw_args = args.map_or(vec![], |args| {
+                args.kw_args
+                    .iter()
+                    .map(|arg| (arg.keyword.content.clone(), arg.expr.t()))
+                    .collect()
+            });
+            let ctx = &self.modules.get(uri)?.context;
+            Some(
+                ctx.resolve_partial_overload(&sig_t, &pos_args, &kw_args)
+                    .unwrap_or(0) as u32,
+            )
+        } else {
+            None
+        };
+        Some(SignatureHelp {
+            signatures,
+            active_parameter: None,
+            active_signature,
+        })
+    }
+
+    fn make_sig_info(sig: &str, sig_t: &Type, nth: u32) -> Option<SignatureInformation> {
+        let mut parameters = vec![];
         let label = format!("{sig}: {sig_t}");
         let mut end = sig.len() + 1; // +1: (
         for nd_param in sig_t.non_default_params()? {
@@ -175,17 +278,136 @@ impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
             };
             parameters.push(param_info);
         }
-        let nth = (parameters.len() as u32 - 1).min(nth);
-        let info = SignatureInformation {
+        let nth = (parameters.len() as u32).saturating_sub(1).min(nth);
+        Some(SignatureInformation {
             label,
             documentation: None,
             parameters: Some(parameters),
             active_parameter: Some(nth),
-        };
-        Some(SignatureHelp {
-            signatures: vec![info],
-            active_parameter: None,
-            active_signature: None,
         })
     }
 }
+
+/// Finds the type argument list containing `pos` (e.g. `id|T := Int, |`),
+/// and returns the callee token, the index of the type argument at `pos`,
+/// and the name of the type parameter if the argument is passed by keyword (e.g. `U` of `id|T := Int, U := |`).
+pub(crate) fn type_args_at(
+    stream: &TokenStream,
+    pos: Position,
+) -> Option<(Token, u32, Option<Str>)> {
+    let tokens = stream
+        .iter()
+        .take_while(|tk| Position::new(tk.lineno.saturating_sub(1), tk.col_begin) < pos)
+        .collect::<Vec<_>>();
+    let mut nth = 0;
+    let mut keyword = None;
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().rev() {
+        let is_sep = match token.category() {
+            TokenCategory::REnclosure => {
+                depth += 1;
+                false
+            }
+            TokenCategory::LEnclosure if depth > 0 => {
+                depth -= 1;
+                false
+            }
+            TokenCategory::LEnclosure | TokenCategory::Separator => {
+                return None;
+            }
+            _ if depth > 0 => false,
+            _ => token.is(TokenKind::Comma) || token.is(TokenKind::VBar),
+        };
+        if !is_sep {
+            continue;
+        }
+        // the current argument is the one after the last separator
+        if nth == 0 && keyword.is_none() {
+            if let (Some(name), Some(walrus)) = (tokens.get(i + 1), tokens.get(i + 2)) {
+                if name.is(TokenKind::Symbol) && walrus.is(TokenKind::Walrus) {
+                    keyword = Some(name.content.clone());
+                }
+            }
+        }
+        if token.is(TokenKind::Comma) {
+            nth += 1;
+            continue;
+        }
+        // the opening `|` immediately follows the callee
+        let callee = tokens.get(i.checked_sub(1)?)?;
+        let adjacent = callee.is(TokenKind::Symbol)
+            && callee.ln_end() == token.ln_begin()
+            && callee.col_end() == token.col_begin();
+        return adjacent.then(|| ((*callee).clone(), nth, keyword));
+    }
+    None
+}
+
+/// The type parameters of `quantified` in the order of appearance
+fn type_params_of(quantified: &Type) -> Vec<(Str, Constraint)> {
+    let qvars = quantified.qvars();
+    let types = quantified
+        .non_default_params()
+        .into_iter()
+        .flatten()
+        .chain(quantified.var_params())
+        .chain(quantified.default_params().into_iter().flatten())
+        .map(|pt| pt.typ())
+        .chain(quantified.return_t());
+    let mut params: Vec<(Str, Constraint)> = vec![];
+    for t in types {
+        let mut appeared = t.qvars().into_iter().collect::<Vec<_>>();
+        appeared.sort_by(|(l, _), (r, _)| l.cmp(r));
+        for (name, _) in appeared {
+            if params.iter().all(|(param, _)| param != &name) {
+                if let Some(qvar) = qvars.iter().find(|(qvar, _)| qvar == &name) {
+                    params.push(qvar.clone());
+                }
+            }
+        }
+    }
+    params
+}
+
+/// The help for the `nth` type argument (or the type argument named `keyword`) passed to `callee: t`.
+/// `None` if `t` is not generic.
+pub(crate) fn type_param_help(
+    callee: &str,
+    t: &Type,
+    nth: u32,
+    keyword: Option<Str>,
+) -> Option<SignatureHelp> {
+    let Type::Quantified(quantified) = t else {
+        return None;
+    };
+    let type_params = type_params_of(quantified);
+    let mut label = format!("{callee}|");
+    let mut parameters = vec![];
+    for (i, (name, constraint)) in type_params.iter().enumerate() {
+        if i != 0 {
+            label.push_str(", ");
+        }
+        let start = label.len();
+        label.push_str(&constraint.to_string_with_name(name));
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start as u32, label.len() as u32]),
+            documentation: None,
+        });
+    }
+    label.push_str(&format!("|{quantified}"));
+    let nth = keyword
+        .and_then(|keyword| type_params.iter().position(|(name, _)| name == &keyword))
+        .map_or(nth, |idx| idx as u32);
+    let nth = (parameters.len() as u32).saturating_sub(1).min(nth);
+    let info = SignatureInformation {
+        label,
+        documentation: None,
+        parameters: Some(parameters),
+        active_parameter: Some(nth),
+    };
+    Some(SignatureHelp {
+        signatures: vec![info],
+        active_parameter: None,
+        active_signature: None,
+    })
+}
@@ -0,0 +1,156 @@
+//! Language features queried without a language client (used by `els::tests`).
+//! The providers in the other modules share their implementations with these functions.
+
+use std::path::PathBuf;
+
+use erg_common::config::ErgConfig;
+use erg_common::shared::{RwLockReadGuard, Shared};
+use erg_common::traits::{Locational, Runnable};
+use erg_common::Str;
+
+use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::context::ModuleContext;
+use erg_compiler::hir::HIR;
+use erg_compiler::ty::Type;
+
+use lsp_types::{CompletionItem, Position, Range, SemanticTokens, SignatureHelp};
+
+use crate::code_action::collect_free_vars;
+use crate::completion::postfix_items;
+use crate::file_cache::FileCache;
+use crate::hir_visitor::HIRVisitor;
+use crate::semantic::SemanticTokenGenerator;
+use crate::server::ELSResult;
+use crate::sig_help::{type_args_at, type_param_help};
+use crate::util::{self, NormalizedUrl};
+
+/// A module analyzed as the server does. The module does not have to pass the check.
+struct Analyzed {
+    path: PathBuf,
+    uri: NormalizedUrl,
+    hir: Shared<HIR>,
+    mod_ctx: ModuleContext,
+    file_cache: FileCache,
+}
+
+impl Analyzed {
+    fn new(mut cfg: ErgConfig) -> ELSResult<Self> {
+        let path = cfg
+            .input
+            .path()
+            .ok_or("a file is required")?
+            .canonicalize()?;
+        let uri = NormalizedUrl::from_file_path(&path)?;
+        let code = cfg.input.read();
+        let mut builder = HIRBuilder::new(cfg);
+        let hir = match builder.build(code.clone(), "exec") {
+            Ok(artifact) => artifact.object,
+            Err(artifact) => artifact.object.ok_or("failed to build the module")?,
+        };
+        let mod_ctx = builder.pop_mod_ctx().ok_or("failed to build the module")?;
+        let file_cache = FileCache::new();
+        file_cache.update(&uri, code, None);
+        Ok(Self {
+            path,
+            uri,
+            hir: Shared::new(hir),
+            mod_ctx,
+            file_cache,
+        })
+    }
+
+    fn visitor(&self) -> HIRVisitor<'_> {
+        HIRVisitor::new(
+            RwLockReadGuard::map(self.hir.borrow(), |hir| hir),
+            &self.file_cache,
+            self.uri.clone(),
+        )
+    }
+}
+
+/// Returns the semantic tokens of `cfg.input` without a language client.
+pub fn semantic_tokens(cfg: ErgConfig) -> ELSResult<SemanticTokens> {
+    let analyzed = Analyzed::new(cfg)?;
+    let stream = analyzed
+        .file_cache
+        .get_token_stream(&analyzed.uri)
+        .unwrap_or_default();
+    let visitor = analyzed.visitor();
+    let data = SemanticTokenGenerator::new(&analyzed.uri, Some(&visitor)).gen(&stream);
+    Ok(SemanticTokens {
+        result_id: None,
+        data,
+    })
+}
+
+/// Returns the local variables that the code in `range` refers to (the parameters of the function extracted from it),
+/// and whether the extracted function should be a procedure.
+pub fn free_variables(cfg: ErgConfig, range: Range) -> ELSResult<(Vec<(Str, Type)>, bool)> {
+    let analyzed = Analyzed::new(cfg)?;
+    let stream = analyzed
+        .file_cache
+        .get_token_stream(&analyzed.uri)
+        .unwrap_or_default();
+    let visitor = analyzed.visitor();
+    Ok(collect_free_vars(
+        &visitor,
+        &stream,
+        &analyzed.mod_ctx.context.name,
+        &analyzed.path,
+        range,
+    ))
+}
+
+/// Returns the signature help for the type argument list at `pos` (e.g. `id|T := Int|`).
+pub fn type_param_signature_help(
+    cfg: ErgConfig,
+    pos: Position,
+) -> ELSResult<Option<SignatureHelp>> {
+    let analyzed = Analyzed::new(cfg)?;
+    let stream = analyzed
+        .file_cache
+        .get_token_stream(&analyzed.uri)
+        .unwrap_or_default();
+    let Some((callee, nth, keyword)) = type_args_at(&stream, pos) else {
+        return Ok(None);
+    };
+    let t = analyzed
+        .visitor()
+        .get_info(&callee)
+        .map(|vi| vi.t)
+        .or_else(|| {
+            analyzed
+                .mod_ctx
+                .context
+                .get_var_info(&callee.content)
+                .map(|(_, vi)| vi.t.clone())
+        });
+    Ok(t.and_then(|t| type_param_help(&callee.content, &t, nth, keyword)))
+}
+
+/// Returns the postfix completion items (e.g. `x.match`) for the receiver followed by `.` at `pos`.
+pub fn postfix_completions(cfg: ErgConfig, pos: Position) -> ELSResult<Vec<CompletionItem>> {
+    let analyzed = Analyzed::new(cfg)?;
+    let Some(token) = analyzed
+        .file_cache
+        .get_token_relatively(&analyzed.uri, pos, -2)
+    else {
+        return Ok(vec![]);
+    };
+    let visitor = analyzed.visitor();
+    let Some(receiver) = visitor.get_min_expr(&token) else {
+        return Ok(vec![]);
+    };
+    let Some(range) = util::loc_to_range(receiver.loc()) else {
+        return Ok(vec![]);
+    };
+    let Some(code) = analyzed.file_cache.get_ranged(&analyzed.uri, range)? else {
+        return Ok(vec![]);
+    };
+    Ok(postfix_items(
+        receiver,
+        &code,
+        pos,
+        &analyzed.mod_ctx.context,
+    ))
+}
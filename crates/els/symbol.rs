@@ -0,0 +1,111 @@
+//! `workspace/symbol`: searches the public names of all the modules in the module cache.
+
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::erg_parser::parse::Parsable;
+use erg_compiler::module::SharedModuleCache;
+use erg_compiler::varinfo::VarInfo;
+
+use lsp_types::{SymbolInformation, SymbolKind, Url, WorkspaceSymbolParams};
+
+use crate::server::{send_log, ELSResult, Server};
+use crate::util;
+
+/// The maximum number of symbols returned for a query
+const MAX_SYMBOLS: usize = 128;
+
+/// Matches `query` as a case-insensitive subsequence of `name`.
+/// A smaller score is better: matches near the start and consecutive matches are preferred.
+fn fuzzy_score(query: &str, name: &str) -> Option<usize> {
+    let mut name_chars = name.chars().flat_map(char::to_lowercase).enumerate();
+    let mut score = 0;
+    let mut prev = None;
+    for q in query.chars().flat_map(char::to_lowercase) {
+        let (i, _) = name_chars.find(|(_, c)| *c == q)?;
+        score += match prev {
+            Some(prev) => i - prev - 1,
+            None => i,
+        };
+        prev = Some(i);
+    }
+    Some(score)
+}
+
+pub(crate) fn symbol_kind(vi: &VarInfo, is_attr: bool) -> SymbolKind {
+    match &vi.t {
+        t if t.is_class_type() => SymbolKind::CLASS,
+        t if t.is_trait_type() => SymbolKind::INTERFACE,
+        t if t.is_module() => SymbolKind::MODULE,
+        t if t.is_subr() && is_attr => SymbolKind::METHOD,
+        t if t.is_subr() => SymbolKind::FUNCTION,
+        _ if vi.muty.is_const() => SymbolKind::CONSTANT,
+        _ if is_attr => SymbolKind::FIELD,
+        _ => SymbolKind::VARIABLE,
+    }
+}
+
+/// Searches the public names (including the attributes of the types) of the modules in `mod_cache` fuzzily.
+/// The results are sorted by relevance.
+pub fn search_symbols(mod_cache: &SharedModuleCache, query: &str) -> Vec<SymbolInformation> {
+    let mut found = vec![];
+    for (path, entry) in mod_cache.ref_inner().iter() {
+        let mod_name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().trim_end_matches(".d").to_string())
+            .unwrap_or_default();
+        for (type_name, name, vi) in entry.module.context.local_members() {
+            if !vi.vis.is_public() || name.inspect().starts_with(['%', '<']) {
+                continue;
+            }
+            let Some(score) = fuzzy_score(query, name.inspect()) else {
+                continue;
+            };
+            let (Some(def_path), Some(range)) = (
+                vi.def_loc.module.as_ref(),
+                util::loc_to_range(vi.def_loc.loc),
+            ) else {
+                continue;
+            };
+            let Ok(uri) = Url::from_file_path(def_path) else {
+                continue;
+            };
+            let container_name = match type_name {
+                Some(type_name) => format!("{mod_name}.{type_name}"),
+                None => mod_name.clone(),
+            };
+            #[allow(deprecated)]
+            let symbol = SymbolInformation {
+                name: name.inspect().to_string(),
+                kind: symbol_kind(vi, type_name.is_some()),
+                tags: None,
+                deprecated: None,
+                location: lsp_types::Location::new(uri, range),
+                container_name: Some(container_name),
+            };
+            found.push((score, symbol));
+        }
+    }
+    found.sort_by(|(l_score, l), (r_score, r)| {
+        l_score
+            .cmp(r_score)
+            .then_with(|| l.name.len().cmp(&r.name.len()))
+            .then_with(|| l.name.cmp(&r.name))
+    });
+    found
+        .into_iter()
+        .take(MAX_SYMBOLS)
+        .map(|(_, symbol)| symbol)
+        .collect()
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn handle_workspace_symbol(
+        &mut self,
+        params: WorkspaceSymbolParams,
+    ) -> ELSResult<Option<Vec<SymbolInformation>>> {
+        send_log(format!("workspace symbol requested: {}", params.query))?;
+        let Some(shared) = self.get_shared() else {
+            return Ok(None);
+        };
+        Ok(Some(search_symbols(&shared.mod_cache, &params.query)))
+    }
+}
@@ -0,0 +1,128 @@
+//! `erg/tests`: a custom request to list the test functions of a module (see `VarInfo::is_test`),
+//! and the `runTest` command to run one of them.
+
+use std::process;
+
+use erg_compiler::artifact::BuildRunnable;
+use erg_compiler::context::ModuleContext;
+use erg_compiler::erg_parser::parse::Parsable;
+
+use lsp_types::request::Request;
+use lsp_types::{Command, Location, TextDocumentIdentifier};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::command::erg_command;
+use crate::message::LogMessage;
+use crate::server::{send, send_error_info, send_info, send_log, ELSResult, Server};
+use crate::util::{self, NormalizedUrl};
+
+#[derive(Debug)]
+pub enum ListTests {}
+
+impl Request for ListTests {
+    type Params = ListTestsParams;
+    type Result = Option<Vec<TestItem>>;
+    const METHOD: &'static str = "erg/tests";
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListTestsParams {
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestItem {
+    pub name: String,
+    /// The location of the name of the test function
+    pub location: Location,
+    /// The `runTest` command to run the test
+    pub command: Command,
+}
+
+/// The names and the ranges (of the names) of the test functions defined in `module`
+pub fn test_functions(module: &ModuleContext) -> Vec<(String, lsp_types::Range)> {
+    module
+        .context
+        .test_functions()
+        .into_iter()
+        .filter_map(|(name, vi)| {
+            let range = util::loc_to_range(vi.def_loc.loc)?;
+            Some((name.inspect().to_string(), range))
+        })
+        .collect()
+}
+
+/// The code that defines everything in `code` and calls the test function `name`.
+/// The test function is private, so it cannot be called by importing the module.
+pub fn test_script(code: &str, name: &str) -> String {
+    format!("{}\n{name}()\n", code.trim_end())
+}
+
+impl<Checker: BuildRunnable, Parser: Parsable> Server<Checker, Parser> {
+    pub(crate) fn handle_list_tests(
+        &mut self,
+        params: ListTestsParams,
+    ) -> ELSResult<Option<Vec<TestItem>>> {
+        send_log(format!("tests requested: {params:?}"))?;
+        let uri = NormalizedUrl::new(params.text_document.uri);
+        let Some(module) = self.modules.get(&uri) else {
+            return Ok(None);
+        };
+        let mut items = vec![];
+        for (name, range) in test_functions(module) {
+            items.push(TestItem {
+                command: self.gen_run_test_command(&uri, &name)?,
+                location: Location::new(uri.clone().raw(), range),
+                name,
+            });
+        }
+        Ok(Some(items))
+    }
+
+    pub(crate) fn gen_run_test_command(
+        &self,
+        uri: &NormalizedUrl,
+        name: &str,
+    ) -> ELSResult<Command> {
+        Ok(Command {
+            title: format!("Run {name}"),
+            command: format!("{}.runTest", self.mode()),
+            arguments: Some(vec![
+                serde_json::to_value(uri.clone().raw())?,
+                Value::String(name.to_string()),
+            ]),
+        })
+    }
+
+    /// Runs the test function `name` of the module with `erg` (in the directory of the module, so that the imports are resolved),
+    /// and reports the result. The test passes if it exits successfully.
+    pub(crate) fn run_test(&self, uri: &NormalizedUrl, name: &str) -> ELSResult<Option<Value>> {
+        let code = self.file_cache.get_entire_code(uri)?;
+        let path = util::uri_to_path(uri);
+        let mut command = process::Command::new(erg_command());
+        command.arg("-c").arg(test_script(&code, name));
+        if let Some(dir) = path.parent() {
+            command.current_dir(dir);
+        }
+        let output = command.output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        send(&LogMessage::new(format!("{stdout}{stderr}")))?;
+        let passed = output.status.success();
+        if passed {
+            send_info(format!("{name} passed"))?;
+        } else {
+            send_error_info(format!("{name} failed ({})", output.status))?;
+        }
+        Ok(Some(json!({
+            "name": name,
+            "uri": uri.clone().raw(),
+            "passed": passed,
+            "status": output.status.code(),
+            "stdout": stdout,
+            "stderr": stderr,
+        })))
+    }
+}
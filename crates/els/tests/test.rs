@@ -0,0 +1,516 @@
+use std::path::PathBuf;
+
+use els::{
+    desugared_ast, diff_semantic_tokens, document_symbols, edited_def, find_py_def, folding_ranges,
+    free_variables, get_trait_impls, next_line_indent, postfix_completions, referred_names,
+    search_symbols, selection_ranges, semantic_tokens, test_functions, test_script, type_at,
+    type_param_signature_help, CheckingMode, DefaultFeatures, ServerSettings, TOKEN_MODIFIERS,
+    TOKEN_TYPES,
+};
+use erg_common::config::{ErgConfig, ErgMode};
+use erg_common::spawn::exec_new_thread;
+use erg_common::traits::{Runnable, Stream};
+use erg_compiler::artifact::Buildable;
+use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::erg_parser::ast::Module;
+use erg_compiler::erg_parser::parse::{Parsable, SimpleParser};
+use erg_compiler::module::SharedCompilerResource;
+use lsp_types::{DocumentSymbol, FoldingRangeKind, ParameterLabel, Position, Range, SymbolKind};
+
+#[test]
+fn test_type_at() -> Result<(), ()> {
+    exec_new_thread(_test_type_at, "test_type_at")
+}
+
+fn _test_type_at() -> Result<(), ()> {
+    let cfg = ErgConfig::with_main_path("tests/type_at.er".into());
+    // `x` of `x + 1`
+    let info = type_at(cfg.copy(), 3, 13).unwrap().unwrap();
+    assert_eq!(&info.token.content[..], "x");
+    assert_eq!(info.t.to_string(), "Int");
+    assert_eq!(info.narrowed_from.unwrap().to_string(), "Int or Str");
+    // `1` of `f 1`
+    let info = type_at(cfg.copy(), 5, 7).unwrap().unwrap();
+    assert_eq!(info.t.to_string(), "{1}");
+    assert_eq!(info.expected_t.unwrap().to_string(), "Int or Str");
+    assert!(info.narrowed_from.is_none());
+    // `f` of the definition
+    let info = type_at(cfg.copy(), 1, 1).unwrap().unwrap();
+    assert_eq!(info.t.to_string(), "(x: Int or Str) -> Int");
+    assert!(type_at(cfg, 4, 1).unwrap().is_none());
+    Ok(())
+}
+
+#[test]
+fn test_search_symbols() -> Result<(), ()> {
+    exec_new_thread(_test_search_symbols, "test_search_symbols")
+}
+
+fn _test_search_symbols() -> Result<(), ()> {
+    let path = PathBuf::from("tests/symbols.er").canonicalize().unwrap();
+    let mut cfg = ErgConfig::with_main_path(path.clone());
+    let shared = SharedCompilerResource::new(cfg.copy());
+    let mut checker = HIRBuilder::inherit(cfg.copy(), shared.clone());
+    let artifact = checker.build(cfg.input.read(), "exec").map_err(|_| ())?;
+    let ctx = checker.pop_mod_ctx().ok_or(())?;
+    shared.mod_cache.register(path, Some(artifact.object), ctx);
+    let found = search_symbols(&shared.mod_cache, "nrm");
+    assert_eq!(found[0].name, "norm");
+    assert_eq!(found[0].kind, SymbolKind::METHOD);
+    assert_eq!(found[0].container_name.as_deref(), Some("symbols.Point"));
+    let found = search_symbols(&shared.mod_cache, "point");
+    assert_eq!(found[0].kind, SymbolKind::CLASS);
+    // private names are not listed
+    assert!(search_symbols(&shared.mod_cache, "private").is_empty());
+    assert!(search_symbols(&shared.mod_cache, "").len() >= 3);
+    Ok(())
+}
+
+#[test]
+fn test_semantic_tokens() -> Result<(), ()> {
+    exec_new_thread(_test_semantic_tokens, "test_semantic_tokens")
+}
+
+fn _test_semantic_tokens() -> Result<(), ()> {
+    let path = PathBuf::from("tests/semantic.er").canonicalize().unwrap();
+    let cfg = ErgConfig::with_main_path(path);
+    let tokens = semantic_tokens(cfg).map_err(|_| ())?.data;
+    // (line, col, type, modifiers)
+    let (mut line, mut col) = (1, 0);
+    let mut decoded: Vec<(u32, u32, String, Vec<String>)> = vec![];
+    for token in tokens.iter() {
+        line += token.delta_line;
+        col = if token.delta_line == 0 {
+            col + token.delta_start
+        } else {
+            token.delta_start
+        };
+        let typ = TOKEN_TYPES[token.token_type as usize].as_str().to_string();
+        let mods = TOKEN_MODIFIERS
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| token.token_modifiers_bitset & (1 << i) != 0)
+            .map(|(_, m)| m.as_str().to_string())
+            .collect::<Vec<_>>();
+        decoded.push((line, col, typ, mods));
+    }
+    let find = |ln: u32, col: u32| {
+        decoded
+            .iter()
+            .find(|(l, c, ..)| (*l, *c) == (ln, col))
+            .map(|(_, _, typ, mods)| (typ.as_str(), mods.clone()))
+    };
+    assert_eq!(find(1, 0), Some(("class", vec!["declaration".into()])));
+    assert_eq!(find(3, 4), Some(("method", vec!["declaration".into()])));
+    assert_eq!(
+        find(3, 8),
+        Some(("parameter", vec!["declaration".into(), "readonly".into()]))
+    );
+    assert_eq!(find(4, 0), Some(("function", vec!["declaration".into()])));
+    assert_eq!(
+        find(5, 0),
+        Some(("variable", vec!["declaration".into(), "mutable".into()]))
+    );
+    assert_eq!(
+        find(6, 4),
+        Some(("method", vec!["defaultLibrary".into(), "procedure".into()]))
+    );
+    assert_eq!(find(6, 14), Some(("number", vec![])));
+    // delta
+    assert!(diff_semantic_tokens(&tokens, &tokens).is_empty());
+    let mut edited = tokens.clone();
+    edited.remove(3);
+    let edits = diff_semantic_tokens(&tokens, &edited);
+    assert_eq!(edits.len(), 1);
+    assert_eq!((edits[0].start, edits[0].delete_count), (15, 5));
+    assert!(edits[0].data.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_next_line_indent() {
+    let code =
+        "f x =\n    y = x + 1 # comment\n    C.\n        g self = do!:\n\n\ns = \"\"\"\nfoo\n";
+    // after `f x =`
+    assert_eq!(next_line_indent(code, 1), Some(4));
+    // after `y = x + 1 # comment`
+    assert_eq!(next_line_indent(code, 2), Some(4));
+    // after `C.`
+    assert_eq!(next_line_indent(code, 3), Some(8));
+    // after `g self = do!:`
+    assert_eq!(next_line_indent(code, 4), Some(12));
+    // a blank line ends the block
+    assert_eq!(next_line_indent(code, 5), Some(8));
+    assert_eq!(next_line_indent(code, 0), None);
+    // in a multi-line string
+    assert_eq!(next_line_indent(code, 7), None);
+}
+
+const STRUCTURE_CODE: &str = "# a\n# b\nC = Class { .x = Int }\nC.\n    f self, y =\n        z = y + 1\n        print! z\nx = f(\n    1,\n    2\n)\n#[\nblock\n]#\ng x = x +\n";
+
+fn parse_partially(code: &str) -> Module {
+    match SimpleParser::parse(code.into()) {
+        Ok(artifact) => artifact.ast,
+        Err(iart) => iart.ast.unwrap(),
+    }
+}
+
+#[test]
+fn test_folding_ranges() {
+    // the last line has a syntax error
+    let module = parse_partially(STRUCTURE_CODE);
+    let ranges = folding_ranges(&module)
+        .into_iter()
+        .map(|range| (range.start_line, range.end_line, range.kind))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ranges,
+        vec![
+            (0, 1, Some(FoldingRangeKind::Comment)),
+            (3, 6, None),
+            (4, 6, None),
+            (7, 10, None),
+            (11, 13, Some(FoldingRangeKind::Comment)),
+        ]
+    );
+}
+
+#[test]
+fn test_edited_def() {
+    let old = parse_partially("f x = x + 1\ng y = f y\nprint! g 1\n");
+    let edited = |code: &str| edited_def(&old, &parse_partially(code));
+    assert_eq!(edited("f x = x + 1\ng y = f y\nprint! g 1\n"), Some(None));
+    assert_eq!(
+        edited("f x = x + 2\ng y = f y\nprint! g 1\n"),
+        Some(Some(0))
+    );
+    assert_eq!(
+        edited("f x = x + 1\ng y = f(y)\nprint! g 1\n"),
+        Some(Some(1))
+    );
+    // not a definition
+    assert_eq!(edited("f x = x + 1\ng y = f y\nprint! g 2\n"), None);
+    // renamed
+    assert_eq!(edited("h x = x + 1\ng y = f y\nprint! g 1\n"), None);
+    // the following chunks are moved
+    assert_eq!(edited("f x =\n    x + 1\ng y = f y\nprint! g 1\n"), None);
+    let names = old
+        .iter()
+        .map(|chunk| {
+            let mut names = referred_names(chunk)
+                .into_iter()
+                .map(|name| name.to_string())
+                .collect::<Vec<_>>();
+            names.sort();
+            names
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec![vec!["x"], vec!["f", "y"], vec!["g", "print!"]]);
+}
+
+#[test]
+fn test_desugared_ast() {
+    let module = parse_partially("y = [1, 2] |> len()\nf [a, b] = a + b\n");
+    // `[1, 2] |> len()`
+    let pipeline = desugared_ast(
+        &module,
+        Some(Range::new(Position::new(0, 4), Position::new(0, 19))),
+    );
+    assert!(pipeline.starts_with("::len:"), "{pipeline}");
+    // the cursor is in `f`
+    let def = desugared_ast(
+        &module,
+        Some(Range::new(Position::new(1, 0), Position::new(1, 0))),
+    );
+    assert!(def.starts_with("::f(%v_desugar_1"), "{def}");
+    assert!(def.contains("__getitem__"), "{def}");
+    let all = desugared_ast(&module, None);
+    assert!(all.starts_with("::y =") && all.contains("::f("), "{all}");
+}
+
+#[test]
+fn test_find_py_def() {
+    let code = "import sys\nX: int\nclass Bar:\n    X = 1\n\n    def baz(self) -> int: ...\nif sys.version_info >= (3, 10):\n    def qux(x: int) -> str: ...\nasync def baz(): ...\n";
+    let def = |name: &str, class: Option<&str>| {
+        find_py_def(code, name, class).map(|range| (range.start.line, range.start.character))
+    };
+    assert_eq!(def("X", None), Some((1, 0)));
+    assert_eq!(def("X", Some("Bar")), Some((3, 4)));
+    assert_eq!(def("baz", Some("Bar")), Some((5, 8)));
+    assert_eq!(def("baz", None), Some((8, 10)));
+    assert_eq!(def("Bar", Some("foo")), Some((2, 6)));
+    assert_eq!(def("qux", None), Some((7, 8)));
+    assert_eq!(def("sys", None), None);
+}
+
+#[test]
+fn test_test_functions() -> Result<(), ()> {
+    exec_new_thread(_test_test_functions, "test_test_functions")
+}
+
+fn _test_test_functions() -> Result<(), ()> {
+    let path = PathBuf::from("tests/tests.er").canonicalize().unwrap();
+    let mut cfg = ErgConfig::with_main_path(path);
+    let shared = SharedCompilerResource::new(cfg.copy());
+    let mut checker = HIRBuilder::inherit(cfg.copy(), shared);
+    checker.build(cfg.input.read(), "exec").map_err(|_| ())?;
+    let mod_ctx = checker.pop_mod_ctx().ok_or(())?;
+    let tests = test_functions(&mod_ctx)
+        .into_iter()
+        .map(|(name, range)| (name, range.start.line))
+        .collect::<Vec<_>>();
+    // `test_with_arg` requires an argument
+    assert_eq!(
+        tests,
+        vec![("test_add".to_string(), 2), ("check_print!".to_string(), 6)]
+    );
+    assert_eq!(test_script("f() = 1\n", "f"), "f() = 1\nf()\n");
+    Ok(())
+}
+
+#[test]
+fn test_server_settings() {
+    let value = serde_json::json!({
+        "erg": {
+            "checkingMode": "pyCompat",
+            "inlayHints": { "parameterNames": false },
+            "disabledFeatures": ["semanticTokens", "unknown"],
+        }
+    });
+    let settings = ServerSettings::parse(&value, "erg").unwrap();
+    assert_eq!(settings.checking_mode, Some(CheckingMode::PyCompat));
+    assert!(!settings.inlay_hints.parameter_names);
+    assert!(settings.inlay_hints.variable_types);
+    assert_eq!(
+        settings.disabled_features().collect::<Vec<_>>(),
+        vec![DefaultFeatures::SemanticTokens]
+    );
+    let mut cfg = ErgConfig::default();
+    settings.apply(&mut cfg);
+    assert!(cfg.py_compat);
+    // the section may be omitted
+    let settings =
+        ServerSettings::parse(&serde_json::json!({ "pythonPath": "python3" }), "erg").unwrap();
+    assert_eq!(settings.python_path.as_deref(), Some("python3"));
+    assert_eq!(settings.checking_mode, None);
+    assert_eq!(
+        ServerSettings::parse(&serde_json::Value::Null, "erg").unwrap(),
+        ServerSettings::default()
+    );
+}
+
+#[test]
+fn test_selection_ranges() {
+    let module = parse_partially(STRUCTURE_CODE);
+    // `y` of `z = y + 1`
+    let selections = selection_ranges(&module, &[Position::new(5, 12)]);
+    let mut ranges = vec![];
+    let mut selection = selections.into_iter().next().map(Box::new);
+    while let Some(sel) = selection {
+        let Range { start, end } = sel.range;
+        ranges.push(((start.line, start.character), (end.line, end.character)));
+        selection = sel.parent;
+    }
+    assert_eq!(
+        ranges,
+        vec![
+            ((5, 12), (5, 13)),
+            ((5, 12), (5, 17)),
+            ((5, 8), (5, 17)),
+            ((4, 4), (6, 16)),
+            ((3, 0), (6, 16)),
+        ]
+    );
+}
+
+#[test]
+fn test_document_symbols() -> Result<(), ()> {
+    exec_new_thread(_test_document_symbols, "test_document_symbols")
+}
+
+fn _test_document_symbols() -> Result<(), ()> {
+    let path = PathBuf::from("tests/symbols.er").canonicalize().unwrap();
+    let mut cfg = ErgConfig::with_main_path(path.clone());
+    // the local contexts are kept only in the language server mode
+    cfg.mode = ErgMode::LanguageServer;
+    let shared = SharedCompilerResource::new(cfg.copy());
+    let mut checker = HIRBuilder::inherit(cfg.copy(), shared);
+    checker.build(cfg.input.read(), "exec").map_err(|_| ())?;
+    let mod_ctx = checker.pop_mod_ctx().ok_or(())?;
+    let ast = parse_partially(&cfg.input.read());
+    let symbols = document_symbols(&mod_ctx, &ast, &path);
+    fn names(symbols: &[DocumentSymbol]) -> Vec<(&str, SymbolKind)> {
+        symbols
+            .iter()
+            .map(|sym| (&sym.name[..], sym.kind))
+            .collect()
+    }
+    assert_eq!(
+        names(&symbols),
+        vec![
+            ("Point", SymbolKind::CLASS),
+            ("origin", SymbolKind::VARIABLE),
+            ("private", SymbolKind::VARIABLE),
+            ("Shape", SymbolKind::INTERFACE),
+            ("square", SymbolKind::FUNCTION),
+        ]
+    );
+    // the class contains the fields and the methods
+    let point = &symbols[0];
+    assert_eq!(
+        names(point.children.as_ref().unwrap()),
+        vec![
+            ("x", SymbolKind::FIELD),
+            ("y", SymbolKind::FIELD),
+            ("norm", SymbolKind::METHOD),
+        ]
+    );
+    assert_eq!(point.range.end, Position::new(2, 42));
+    assert_eq!(point.selection_range.end, Position::new(0, 6));
+    let shape = &symbols[3];
+    assert_eq!(
+        names(shape.children.as_ref().unwrap()),
+        vec![("area", SymbolKind::METHOD)]
+    );
+    // the local variables of the function
+    let square = &symbols[4];
+    assert_eq!(
+        names(square.children.as_ref().unwrap()),
+        vec![("y", SymbolKind::VARIABLE)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_trait_impls() -> Result<(), ()> {
+    exec_new_thread(_test_trait_impls, "test_trait_impls")
+}
+
+fn _test_trait_impls() -> Result<(), ()> {
+    let path = PathBuf::from("tests/code_lens.er").canonicalize().unwrap();
+    let mut cfg = ErgConfig::with_main_path(path);
+    let mut checker = HIRBuilder::new(cfg.copy());
+    checker.build(cfg.input.read(), "exec").map_err(|_| ())?;
+    let mod_ctx = checker.pop_mod_ctx().ok_or(())?;
+    // `A` and `B` (not `C`)
+    let impls = get_trait_impls(&mod_ctx.context, "Greet");
+    let lines = impls
+        .iter()
+        .map(|loc| loc.range.start.line)
+        .collect::<Vec<_>>();
+    assert_eq!(lines, vec![2, 6]);
+    assert!(get_trait_impls(&mod_ctx.context, "Undefined").is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_free_variables() -> Result<(), ()> {
+    exec_new_thread(_test_free_variables, "test_free_variables")
+}
+
+fn _test_free_variables() -> Result<(), ()> {
+    let path = PathBuf::from("tests/extract.er").canonicalize().unwrap();
+    let cfg = ErgConfig::with_main_path(path);
+    let names = |vars: &[(erg_common::Str, erg_compiler::ty::Type)]| {
+        vars.iter()
+            .map(|(name, t)| (name.to_string(), t.to_string()))
+            .collect::<Vec<_>>()
+    };
+    // `x * offset + total`: `total` is not a local variable
+    let range = Range::new(Position::new(3, 8), Position::new(3, 26));
+    let (vars, is_proc) = free_variables(cfg.copy(), range).map_err(|_| ())?;
+    assert_eq!(
+        names(&vars),
+        vec![
+            ("x".to_string(), "Int".to_string()),
+            ("offset".to_string(), "Nat".to_string()),
+        ]
+    );
+    assert!(!is_proc);
+    // `offset = 2` and `y = x * offset + total`: `offset` is defined inside the range
+    let range = Range::new(Position::new(2, 4), Position::new(3, 26));
+    let (vars, _) = free_variables(cfg.copy(), range).map_err(|_| ())?;
+    assert_eq!(names(&vars), vec![("x".to_string(), "Int".to_string())]);
+    // `print! msg` calls a procedure
+    let range = Range::new(Position::new(7, 4), Position::new(7, 14));
+    let (vars, is_proc) = free_variables(cfg, range).map_err(|_| ())?;
+    assert_eq!(names(&vars), vec![("msg".to_string(), "Str".to_string())]);
+    assert!(is_proc);
+    Ok(())
+}
+
+#[test]
+fn test_type_param_signature_help() -> Result<(), ()> {
+    exec_new_thread(
+        _test_type_param_signature_help,
+        "test_type_param_signature_help",
+    )
+}
+
+fn _test_type_param_signature_help() -> Result<(), ()> {
+    let path = PathBuf::from("tests/sig_help.er").canonicalize().unwrap();
+    let cfg = ErgConfig::with_main_path(path);
+    let help_at = |line, character| {
+        let help = type_param_signature_help(cfg.copy(), Position::new(line, character))
+            .map_err(|_| ())?
+            .ok_or(())?;
+        let sig = help.signatures.into_iter().next().ok_or(())?;
+        let params = sig
+            .parameters
+            .unwrap_or_default()
+            .into_iter()
+            .map(|param| match param.label {
+                ParameterLabel::LabelOffsets([start, end]) => {
+                    sig.label[start as usize..end as usize].to_string()
+                }
+                ParameterLabel::Simple(label) => label,
+            })
+            .collect::<Vec<_>>();
+        Ok::<_, ()>((params, sig.active_parameter))
+    };
+    // `id|T := |Int|`
+    let (params, active) = help_at(3, 12)?;
+    assert_eq!(params, vec!["T: Type"]);
+    assert_eq!(active, Some(0));
+    // `pair|T := Int, |U := Str|`
+    let (params, active) = help_at(4, 19)?;
+    assert_eq!(params, vec!["T: Type", "U: Type"]);
+    assert_eq!(active, Some(1));
+    // `pair|T := Int, U := |Str|`
+    let (_, active) = help_at(4, 24)?;
+    assert_eq!(active, Some(1));
+    // not in a type argument list
+    assert!(type_param_signature_help(cfg, Position::new(4, 30))
+        .map_err(|_| ())?
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn test_postfix_completions() -> Result<(), ()> {
+    exec_new_thread(_test_postfix_completions, "test_postfix_completions")
+}
+
+fn _test_postfix_completions() -> Result<(), ()> {
+    let path = PathBuf::from("tests/postfix.er").canonicalize().unwrap();
+    let cfg = ErgConfig::with_main_path(path);
+    let labels = |items: Vec<lsp_types::CompletionItem>| {
+        items.into_iter().map(|item| item.label).collect::<Vec<_>>()
+    };
+    // `xs.`
+    let items = postfix_completions(cfg.copy(), Position::new(2, 10)).map_err(|_| ())?;
+    let edit = match &items[0].text_edit {
+        Some(lsp_types::CompletionTextEdit::Edit(edit)) => edit.clone(),
+        _ => return Err(()),
+    };
+    assert_eq!(
+        edit.range,
+        Range::new(Position::new(2, 7), Position::new(2, 10))
+    );
+    assert_eq!(edit.new_text, "match xs:\n    ${1:_} -> $0");
+    assert_eq!(labels(items), vec!["match", "for!", "print!"]);
+    // `flag.`
+    let items = postfix_completions(cfg, Position::new(3, 12)).map_err(|_| ())?;
+    assert_eq!(labels(items), vec!["match", "if", "print!"]);
+    Ok(())
+}
@@ -0,0 +1,277 @@
+//! `erg type-at file.er:LINE:COL`
+//!
+//! Reports the type of the innermost expression at a position without a language client.
+//! The expression is searched by the same visitor as hover (`HIRVisitor`).
+
+use std::fmt;
+
+use erg_common::config::ErgConfig;
+use erg_common::error::Location;
+use erg_common::shared::{RwLockReadGuard, Shared};
+use erg_common::traits::{ExitStatus, Locational, Runnable, Stream};
+use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::erg_parser::token::Token;
+use erg_compiler::hir::{Accessor, Args, Array, Block, Call, Def, Dict, Expr, Set, Tuple, HIR};
+use erg_compiler::ty::{HasType, Type};
+
+use lsp_types::Position;
+
+use crate::file_cache::FileCache;
+use crate::hir_visitor::HIRVisitor;
+use crate::server::ELSResult;
+use crate::util::{self, NormalizedUrl};
+
+#[derive(Debug, Clone)]
+pub struct ExprTypeInfo {
+    /// the token at the position
+    pub token: Token,
+    /// the location of the innermost expression containing `token`
+    pub loc: Location,
+    pub t: Type,
+    /// the type expected by the surroundings, e.g. the parameter type for an argument
+    pub expected_t: Option<Type>,
+    /// the type of the variable before narrowing, e.g. `Int or Str` in `if x in Int: ...`
+    pub narrowed_from: Option<Type>,
+}
+
+impl fmt::Display for ExprTypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.token.content, self.t)?;
+        if let Some(expected) = &self.expected_t {
+            write!(f, "\nexpected: {expected}")?;
+        }
+        if let Some(narrowed_from) = &self.narrowed_from {
+            write!(f, "\nnarrowed from: {narrowed_from}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the type of the innermost expression at `line`:`col` (1-based) of `cfg.input`.
+/// The module does not have to pass the check; the (incomplete) HIR is searched as far as it was built.
+pub fn type_at(mut cfg: ErgConfig, line: u32, col: u32) -> ELSResult<Option<ExprTypeInfo>> {
+    let path = cfg
+        .input
+        .path()
+        .ok_or("type-at requires a file")?
+        .canonicalize()?;
+    let uri = NormalizedUrl::from_file_path(path)?;
+    let code = cfg.input.read();
+    let mut builder = HIRBuilder::new(cfg);
+    let hir = match builder.build(code.clone(), "exec") {
+        Ok(artifact) => artifact.object,
+        Err(artifact) => {
+            let Some(hir) = artifact.object else {
+                return Ok(None);
+            };
+            hir
+        }
+    };
+    let file_cache = FileCache::new();
+    file_cache.update(&uri, code, None);
+    let hir = Shared::new(hir);
+    let visitor = HIRVisitor::new(
+        RwLockReadGuard::map(hir.borrow(), |hir| hir),
+        &file_cache,
+        uri.clone(),
+    );
+    let pos = Position::new(line.saturating_sub(1), col.saturating_sub(1));
+    let Some(token) = file_cache.get_token(&uri, pos) else {
+        return Ok(None);
+    };
+    let Some(expr) = visitor.get_min_expr(&token) else {
+        return Ok(None);
+    };
+    let expected_t = get_expected_t(&hir.borrow(), expr).flatten();
+    let narrowed_from = get_narrowed_from(&visitor, &file_cache, &uri, expr, &token);
+    // the type of a definition itself is `NoneType`
+    let t = match expr {
+        Expr::Def(def) => def.sig.ident().t(),
+        _ => expr.t(),
+    };
+    Ok(Some(ExprTypeInfo {
+        loc: expr.loc(),
+        t,
+        token,
+        expected_t,
+        narrowed_from,
+    }))
+}
+
+/// Compares the type of the variable with that of its definition (in the same module).
+fn get_narrowed_from(
+    visitor: &HIRVisitor,
+    file_cache: &FileCache,
+    uri: &NormalizedUrl,
+    expr: &Expr,
+    token: &Token,
+) -> Option<Type> {
+    let Expr::Accessor(Accessor::Ident(ident)) = expr else {
+        return None;
+    };
+    let def_loc = &ident.vi.def_loc;
+    let def_path = def_loc.module.as_ref()?.canonicalize().ok()?;
+    let def_uri = NormalizedUrl::from_file_path(def_path).ok()?;
+    if &def_uri != uri || def_loc.loc == token.loc() {
+        return None;
+    }
+    let def_token = file_cache.get_token(uri, util::loc_to_range(def_loc.loc)?.start)?;
+    let def_t = visitor.get_info(&def_token)?.t;
+    // a type with unresolved type variables is not narrowed
+    (def_t != *ident.ref_t() && ident.ref_t().has_no_unbound_var()).then_some(def_t)
+}
+
+/// The entry point of `erg type-at`.
+pub fn run_type_at(cfg: ErgConfig) -> ExitStatus {
+    let Some((line, col)) = cfg.query_pos else {
+        eprintln!("the position is not passed (expected `file.er:LINE:COL`)");
+        return ExitStatus::ERR1;
+    };
+    match type_at(cfg, line, col) {
+        Ok(Some(info)) => {
+            println!("{info}");
+            ExitStatus::OK
+        }
+        Ok(None) => {
+            eprintln!("no expression found at {line}:{col}");
+            ExitStatus::ERR1
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            ExitStatus::ERR1
+        }
+    }
+}
+
+/// Returns the type expected for `target` by its surroundings,
+/// e.g. the parameter type for an argument, the specified type for the body of a definition.
+/// `None` if `target` is not in the HIR, `Some(None)` if no type is expected.
+fn get_expected_t(hir: &HIR, target: &Expr) -> Option<Option<Type>> {
+    hir.module
+        .iter()
+        .find_map(|chunk| get_expr_expected_t(chunk, target, None))
+}
+
+fn get_expr_expected_t(
+    expr: &Expr,
+    target: &Expr,
+    expected: Option<&Type>,
+) -> Option<Option<Type>> {
+    if std::ptr::eq(expr, target) {
+        return Some(expected.cloned());
+    }
+    match expr {
+        Expr::Call(call) => get_expr_expected_t(&call.obj, target, None)
+            .or_else(|| get_call_args_expected_t(call, target)),
+        Expr::BinOp(bin) => {
+            let params = bin.info.t.non_default_params();
+            let lhs_t = params.and_then(|params| params.first()).map(|p| p.typ());
+            let rhs_t = params.and_then(|params| params.get(1)).map(|p| p.typ());
+            get_expr_expected_t(&bin.lhs, target, lhs_t)
+                .or_else(|| get_expr_expected_t(&bin.rhs, target, rhs_t))
+        }
+        Expr::UnaryOp(unary) => {
+            let params = unary.info.t.non_default_params();
+            let t = params.and_then(|params| params.first()).map(|p| p.typ());
+            get_expr_expected_t(&unary.expr, target, t)
+        }
+        Expr::Def(def) => get_def_expected_t(def, target),
+        Expr::ClassDef(class_def) => get_block_expected_t(&class_def.methods, target, None),
+        Expr::PatchDef(patch_def) => get_block_expected_t(&patch_def.methods, target, None),
+        Expr::Lambda(lambda) => get_block_expected_t(&lambda.body, target, None),
+        Expr::ReDef(redef) => get_block_expected_t(&redef.block, target, Some(redef.attr.ref_t())),
+        Expr::TypeAsc(tasc) => get_expr_expected_t(&tasc.expr, target, Some(&tasc.spec.spec_t)),
+        Expr::Array(Array::Normal(arr)) => get_args_expected_t(&arr.elems, target),
+        Expr::Set(Set::Normal(set)) => get_args_expected_t(&set.elems, target),
+        Expr::Tuple(Tuple::Normal(tuple)) => get_args_expected_t(&tuple.elems, target),
+        Expr::Dict(Dict::Normal(dict)) => dict.kvs.iter().find_map(|kv| {
+            get_expr_expected_t(&kv.key, target, None)
+                .or_else(|| get_expr_expected_t(&kv.value, target, None))
+        }),
+        Expr::Record(record) => record
+            .attrs
+            .iter()
+            .find_map(|field| get_def_expected_t(field, target)),
+        Expr::Dummy(dummy) => dummy
+            .iter()
+            .find_map(|chunk| get_expr_expected_t(chunk, target, None)),
+        Expr::Compound(block) | Expr::Code(block) => get_block_expected_t(block, target, None),
+        _ => None,
+    }
+}
+
+fn get_def_expected_t(def: &Def, target: &Expr) -> Option<Option<Type>> {
+    let spec_t = def.sig.t_spec_with_op().map(|spec| &spec.spec_t);
+    get_block_expected_t(&def.body.block, target, spec_t)
+}
+
+/// Only the last expression of a block is expected to have the type.
+fn get_block_expected_t(
+    block: &Block,
+    target: &Expr,
+    expected: Option<&Type>,
+) -> Option<Option<Type>> {
+    let last = block.len().saturating_sub(1);
+    block.iter().enumerate().find_map(|(i, chunk)| {
+        let expected = if i == last { expected } else { None };
+        get_expr_expected_t(chunk, target, expected)
+    })
+}
+
+fn get_args_expected_t(args: &Args, target: &Expr) -> Option<Option<Type>> {
+    args.pos_args
+        .iter()
+        .map(|arg| &arg.expr)
+        .chain(args.var_args.iter().map(|arg| &arg.expr))
+        .chain(args.kw_args.iter().map(|arg| &arg.expr))
+        .find_map(|arg| get_expr_expected_t(arg, target, None))
+}
+
+fn get_call_args_expected_t(call: &Call, target: &Expr) -> Option<Option<Type>> {
+    let Some(subr_t) = call.signature_t() else {
+        return get_args_expected_t(&call.args, target);
+    };
+    let empty = vec![];
+    // `self` is passed as the receiver
+    let skip = usize::from(call.is_method_call());
+    // the rest of the positional arguments are passed to the variable-length parameter if any
+    let defaults = if subr_t.var_params().is_some() {
+        &empty
+    } else {
+        subr_t.default_params().unwrap_or(&empty)
+    };
+    let mut pos_params = subr_t
+        .non_default_params()
+        .unwrap_or(&empty)
+        .iter()
+        .chain(defaults)
+        .skip(skip);
+    for arg in call.args.pos_args.iter() {
+        let param_t = pos_params
+            .next()
+            .or(subr_t.var_params())
+            .map(|param| param.typ());
+        if let Some(found) = get_expr_expected_t(&arg.expr, target, param_t) {
+            return Some(found);
+        }
+    }
+    if let Some(var_args) = &call.args.var_args {
+        let param_t = subr_t.var_params().map(|param| param.typ());
+        if let Some(found) = get_expr_expected_t(&var_args.expr, target, param_t) {
+            return Some(found);
+        }
+    }
+    for arg in call.args.kw_args.iter() {
+        let param_t = subr_t
+            .non_default_params()
+            .unwrap_or(&empty)
+            .iter()
+            .chain(subr_t.default_params().unwrap_or(&empty))
+            .find(|param| param.name() == Some(arg.keyword.inspect()))
+            .map(|param| param.typ());
+        if let Some(found) = get_expr_expected_t(&arg.expr, target, param_t) {
+            return Some(found);
+        }
+    }
+    None
+}
@@ -3,9 +3,14 @@ use std::fs::{metadata, Metadata};
 use std::path::{Path, PathBuf};
 
 use erg_common::consts::CASE_SENSITIVE;
+use erg_common::error::Location;
 use erg_common::normalize_path;
 use erg_common::traits::{DequeStream, Locational};
 
+use erg_compiler::erg_parser::ast::{
+    Accessor, Args, Array, Block, ClassAttr, Def, Dict, Expr, Methods, Record, RecordAttrOrIdent,
+    Set, Tuple,
+};
 use erg_compiler::erg_parser::token::{Token, TokenStream};
 
 use lsp_types::{Position, Range, Url};
@@ -155,3 +160,142 @@ pub(crate) fn uri_to_path(uri: &NormalizedUrl) -> PathBuf {
 pub(crate) fn denormalize(uri: Url) -> Url {
     Url::parse(&uri.as_str().replace("c:", "file:///c%3A")).unwrap()
 }
+
+/// A node of the AST, used to walk the AST without type checking
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum ASTNode<'a> {
+    Expr(&'a Expr),
+    Def(&'a Def),
+    Methods(&'a Methods),
+    /// a node whose children are not needed (e.g. an identifier in a record, a type specification)
+    Leaf(Location),
+}
+
+impl Locational for ASTNode<'_> {
+    fn loc(&self) -> Location {
+        match self {
+            // the location of `BinOp` starts from the operator
+            Self::Expr(Expr::BinOp(bin)) => {
+                Location::concat(bin.args[0].as_ref(), bin.args[1].as_ref())
+            }
+            Self::Expr(expr) => expr.loc(),
+            Self::Def(def) => def.loc(),
+            Self::Methods(methods) => methods.loc(),
+            Self::Leaf(loc) => *loc,
+        }
+    }
+}
+
+fn block_nodes(block: &Block) -> impl Iterator<Item = ASTNode<'_>> {
+    block.iter().map(ASTNode::Expr)
+}
+
+fn args_nodes(args: &Args) -> impl Iterator<Item = ASTNode<'_>> {
+    args.pos_args()
+        .iter()
+        .chain(args.var_args())
+        .map(|arg| ASTNode::Expr(&arg.expr))
+        .chain(args.kw_args().iter().map(|arg| ASTNode::Expr(&arg.expr)))
+}
+
+fn record_nodes(record: &Record) -> Vec<ASTNode<'_>> {
+    match record {
+        Record::Normal(record) => record.attrs.iter().map(ASTNode::Def).collect(),
+        Record::Mixed(record) => record
+            .attrs
+            .iter()
+            .map(|attr| match attr {
+                RecordAttrOrIdent::Attr(def) => ASTNode::Def(def),
+                RecordAttrOrIdent::Ident(ident) => ASTNode::Leaf(ident.loc()),
+            })
+            .collect(),
+    }
+}
+
+fn class_nodes<'a>(def: &'a Def, methods_list: &'a [Methods]) -> Vec<ASTNode<'a>> {
+    let mut children = vec![ASTNode::Def(def)];
+    children.extend(methods_list.iter().map(ASTNode::Methods));
+    children
+}
+
+impl<'a> ASTNode<'a> {
+    /// The direct children of the node, in the order of appearance
+    pub(crate) fn children(&self) -> Vec<ASTNode<'a>> {
+        let expr = match self {
+            Self::Expr(expr) => expr,
+            Self::Def(def) => return block_nodes(&def.body.block).collect(),
+            Self::Methods(methods) => {
+                return methods
+                    .attrs
+                    .iter()
+                    .map(|attr| match attr {
+                        ClassAttr::Def(def) => ASTNode::Def(def),
+                        ClassAttr::Decl(decl) => ASTNode::Leaf(decl.loc()),
+                        ClassAttr::Doc(doc) => ASTNode::Leaf(doc.loc()),
+                    })
+                    .collect();
+            }
+            Self::Leaf(_) => return vec![],
+        };
+        match expr {
+            Expr::Literal(_) | Expr::Accessor(Accessor::Ident(_)) => vec![],
+            Expr::Accessor(Accessor::Attr(attr)) => vec![ASTNode::Expr(&attr.obj)],
+            Expr::Accessor(Accessor::TupleAttr(attr)) => vec![ASTNode::Expr(&attr.obj)],
+            Expr::Accessor(Accessor::Subscr(subscr)) => {
+                vec![ASTNode::Expr(&subscr.obj), ASTNode::Expr(&subscr.index)]
+            }
+            Expr::Accessor(Accessor::TypeApp(tapp)) => vec![ASTNode::Expr(&tapp.obj)],
+            Expr::Array(Array::Normal(arr)) => args_nodes(&arr.elems).collect(),
+            Expr::Array(Array::WithLength(arr)) => {
+                vec![ASTNode::Expr(&arr.elem.expr), ASTNode::Expr(&arr.len)]
+            }
+            Expr::Array(Array::Comprehension(arr)) => {
+                let mut children = vec![ASTNode::Expr(&arr.elem)];
+                for (var, iter) in arr.generators.iter() {
+                    children.push(ASTNode::Leaf(var.loc()));
+                    children.push(ASTNode::Expr(iter));
+                }
+                children.extend(arr.guards.iter().map(ASTNode::Expr));
+                children
+            }
+            Expr::Tuple(Tuple::Normal(tuple)) => args_nodes(&tuple.elems).collect(),
+            Expr::Dict(Dict::Normal(dict)) => dict
+                .kvs
+                .iter()
+                .flat_map(|kv| [ASTNode::Expr(&kv.key), ASTNode::Expr(&kv.value)])
+                .collect(),
+            Expr::Dict(Dict::Comprehension(dict)) => args_nodes(&dict.attrs).collect(),
+            Expr::Set(Set::Normal(set)) => args_nodes(&set.elems).collect(),
+            Expr::Set(Set::WithLength(set)) => {
+                vec![ASTNode::Expr(&set.elem.expr), ASTNode::Expr(&set.len)]
+            }
+            Expr::Set(Set::Comprehension(set)) => {
+                vec![ASTNode::Expr(&set.iter), ASTNode::Expr(&set.pred)]
+            }
+            Expr::Record(record) => record_nodes(record),
+            Expr::BinOp(bin) => vec![ASTNode::Expr(&bin.args[0]), ASTNode::Expr(&bin.args[1])],
+            Expr::UnaryOp(unary) => vec![ASTNode::Expr(&unary.args[0])],
+            Expr::Call(call) => {
+                let mut children = vec![ASTNode::Expr(&call.obj)];
+                children.extend(call.attr_name.iter().map(|name| ASTNode::Leaf(name.loc())));
+                children.extend(args_nodes(&call.args));
+                children
+            }
+            Expr::DataPack(pack) => {
+                let mut children = vec![ASTNode::Expr(&pack.class)];
+                children.extend(record_nodes(&pack.args));
+                children
+            }
+            Expr::Lambda(lambda) => block_nodes(&lambda.body).collect(),
+            Expr::TypeAscription(tasc) => {
+                vec![ASTNode::Expr(&tasc.expr), ASTNode::Leaf(tasc.t_spec.loc())]
+            }
+            Expr::Def(def) => ASTNode::Def(def).children(),
+            Expr::Methods(methods) => ASTNode::Methods(methods).children(),
+            Expr::ClassDef(class_def) => class_nodes(&class_def.def, &class_def.methods_list),
+            Expr::PatchDef(patch_def) => class_nodes(&patch_def.def, &patch_def.methods_list),
+            Expr::ReDef(redef) => vec![ASTNode::Leaf(redef.attr.loc()), ASTNode::Expr(&redef.expr)],
+            Expr::Dummy(dummy) => block_nodes(&dummy.exprs).collect(),
+        }
+    }
+}
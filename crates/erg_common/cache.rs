@@ -1,11 +1,12 @@
 use std::borrow::{Borrow, ToOwned};
 use std::hash::Hash;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use std::thread::LocalKey;
 
 use crate::dict::Dict;
 use crate::set::Set;
 use crate::shared::Shared;
+use crate::str::ArcStr;
 use crate::{ArcArray, Str};
 
 #[derive(Debug)]
@@ -72,6 +73,152 @@ impl<T: Hash + Eq> CacheSet<T> {
     }
 }
 
+/// The names that appear in almost every module (the builtin types, subroutines, and special names).
+/// Only these are interned so that the interner does not grow with the user code.
+const BUILTIN_NAMES: &[&str] = &[
+    "self",
+    "Self",
+    "_",
+    "True",
+    "False",
+    "None",
+    "NotImplemented",
+    "Ellipsis",
+    "Obj",
+    "Int",
+    "Nat",
+    "Float",
+    "Complex",
+    "Ratio",
+    "Bool",
+    "Str",
+    "Bytes",
+    "NoneType",
+    "Never",
+    "Type",
+    "ClassType",
+    "TraitType",
+    "Array",
+    "Dict",
+    "Set",
+    "Tuple",
+    "Record",
+    "Range",
+    "Iterable",
+    "Iterator",
+    "Sequence",
+    "Mapping",
+    "Eq",
+    "Ord",
+    "Hash",
+    "Add",
+    "Sub",
+    "Mul",
+    "Div",
+    "Show",
+    "Int!",
+    "Nat!",
+    "Float!",
+    "Bool!",
+    "Str!",
+    "Array!",
+    "Dict!",
+    "Set!",
+    "Class",
+    "Inherit",
+    "Trait",
+    "Subsume",
+    "Patch",
+    "Structural",
+    "Property",
+    "Override",
+    "Overload",
+    "Test",
+    "Derive",
+    "import",
+    "pyimport",
+    "rsimport",
+    "if",
+    "if!",
+    "for",
+    "for!",
+    "while",
+    "while!",
+    "match",
+    "match!",
+    "with!",
+    "assert",
+    "discard",
+    "print!",
+    "input!",
+    "len",
+    "id",
+    "abs",
+    "all",
+    "any",
+    "min",
+    "max",
+    "sum",
+    "map",
+    "filter",
+    "zip",
+    "enumerate",
+    "range",
+    "reversed",
+    "sorted",
+    "isinstance",
+    "issubclass",
+    "hasattr",
+    "getattr",
+    "str",
+    "repr",
+    "hash",
+    "open!",
+    "new",
+    "__new__",
+    "__init__",
+    "__call__",
+    "__getitem__",
+    "__setitem__",
+    "__len__",
+    "__iter__",
+    "__next__",
+    "__contains__",
+    "__eq__",
+    "__hash__",
+    "__add__",
+    "__sub__",
+    "__mul__",
+    "__div__",
+    "__name__",
+    "__module__",
+    "__dict__",
+    "__class__",
+];
+
+static STR_INTERNER: OnceLock<Set<ArcStr>> = OnceLock::new();
+
+/// Returns the interned `Str` of `s` if it is a builtin name (see `BUILTIN_NAMES`).
+/// Interned strings with the same content share the same allocation, so comparing them is a pointer comparison.
+pub fn get_interned(s: &str) -> Option<Str> {
+    STR_INTERNER
+        .get_or_init(|| {
+            BUILTIN_NAMES
+                .iter()
+                .map(|&name| ArcStr::from(name))
+                .collect()
+        })
+        .get(s)
+        .map(Str::from)
+}
+
+/// Returns the interned `Str` of `s` if it is a builtin name, otherwise a new `Str`.
+///
+/// The set of interned strings is fixed, so interning never leaks memory.
+pub fn intern(s: &str) -> Str {
+    get_interned(s).unwrap_or_else(|| Str::rc(s))
+}
+
 pub struct CacheDict<K, V: ?Sized>(Shared<Dict<K, Arc<V>>>);
 
 pub struct GlobalCacheDict<K: 'static, V: ?Sized + 'static>(LocalKey<Shared<CacheDict<K, V>>>);
@@ -8,12 +8,18 @@ use std::path::PathBuf;
 use std::process;
 use std::str::FromStr;
 
+use crate::consts::PYTHON_MODE;
+use crate::edition::LangOptions;
 use crate::help_messages::{command_message, mode_message, OPTIONS};
 use crate::io::{Input, Output};
 use crate::levenshtein::get_similar_name;
 use crate::normalize_path;
-use crate::python_util::{detect_magic_number, get_python_version, PythonVersion};
+use crate::python_util::{
+    detect_magic_number, get_python_version, PythonVersion, MICROPYTHON_MODS,
+    MICROPYTHON_UNAVAILABLE_BUILTINS,
+};
 use crate::serialize::{get_magic_num_from_bytes, get_ver_from_magic_num};
+use crate::timings::TimingsFormat;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ErgMode {
@@ -24,6 +30,15 @@ pub enum ErgMode {
     FullCheck,
     Compile,
     Transpile,
+    Migrate,
+    TypeAt,
+    Test,
+    Audit,
+    Declare,
+    Stub,
+    Fix,
+    Dump,
+    Graph,
     Execute,
     LanguageServer,
     Read,
@@ -40,6 +55,15 @@ impl TryFrom<&str> for ErgMode {
             "fullcheck" | "check" | "checker" => Ok(Self::FullCheck),
             "compile" | "compiler" => Ok(Self::Compile),
             "transpile" | "transpiler" => Ok(Self::Transpile),
+            "migrate" | "migrator" => Ok(Self::Migrate),
+            "type-at" => Ok(Self::TypeAt),
+            "test" => Ok(Self::Test),
+            "audit" => Ok(Self::Audit),
+            "declare" => Ok(Self::Declare),
+            "stub" => Ok(Self::Stub),
+            "fix" => Ok(Self::Fix),
+            "dump" => Ok(Self::Dump),
+            "graph" => Ok(Self::Graph),
             "run" | "execute" => Ok(Self::Execute),
             "server" | "language-server" => Ok(Self::LanguageServer),
             "byteread" | "read" | "reader" => Ok(Self::Read),
@@ -58,6 +82,15 @@ impl From<ErgMode> for &str {
             ErgMode::FullCheck => "fullcheck",
             ErgMode::Compile => "compile",
             ErgMode::Transpile => "transpile",
+            ErgMode::Migrate => "migrate",
+            ErgMode::TypeAt => "type-at",
+            ErgMode::Test => "test",
+            ErgMode::Audit => "audit",
+            ErgMode::Declare => "declare",
+            ErgMode::Stub => "stub",
+            ErgMode::Fix => "fix",
+            ErgMode::Dump => "dump",
+            ErgMode::Graph => "graph",
             ErgMode::Execute => "execute",
             ErgMode::LanguageServer => "language-server",
             ErgMode::Read => "read",
@@ -71,6 +104,63 @@ impl fmt::Display for ErgMode {
     }
 }
 
+/// The runtime that executes the compiled program.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TargetProfile {
+    #[default]
+    CPython,
+    /// Only Python scripts can be generated (`erg transpile`),
+    /// and the available modules and builtins are restricted to those of MicroPython.
+    MicroPython,
+}
+
+impl FromStr for TargetProfile {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cpython" | "python" => Ok(Self::CPython),
+            "micropython" | "mpy" => Ok(Self::MicroPython),
+            other => Err(format!("unknown target: {other}")),
+        }
+    }
+}
+
+impl fmt::Display for TargetProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CPython => write!(f, "cpython"),
+            Self::MicroPython => write!(f, "micropython"),
+        }
+    }
+}
+
+impl TargetProfile {
+    pub const fn is_micropython(&self) -> bool {
+        matches!(self, Self::MicroPython)
+    }
+
+    /// `u`-prefixed names (e.g. `utime`) are aliases of MicroPython modules.
+    pub fn is_available_module(&self, name: &str) -> bool {
+        match self {
+            Self::CPython => true,
+            Self::MicroPython => {
+                let top = name.split('.').next().unwrap_or(name);
+                MICROPYTHON_MODS.contains(&top)
+                    || top
+                        .strip_prefix('u')
+                        .is_some_and(|name| MICROPYTHON_MODS.contains(&name))
+            }
+        }
+    }
+
+    pub fn is_available_builtin(&self, name: &str) -> bool {
+        match self {
+            Self::CPython => true,
+            Self::MicroPython => !MICROPYTHON_UNAVAILABLE_BUILTINS.contains(&name),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ErgConfig {
     pub mode: ErgMode,
@@ -81,13 +171,47 @@ pub struct ErgConfig {
     /// * 3: e.g. JIT compiling
     pub opt_level: u8,
     pub no_std: bool,
+    /// insert runtime type checks where `Dynamic` values are passed to typed parameters or variables
+    pub runtime_checks: bool,
+    /// report the lenient checks (implicit unions, `Dynamic` coercions, untyped attribute accesses) as errors (`--strict`)
+    pub strict: bool,
+    /// Python-compatible checking for this module.
+    /// Enabled by the `py_compat` feature, and per module by the compiler (see `ASTLowerer::new_with_cache`).
+    pub py_compat: bool,
+    /// The edition and the features of this module.
+    /// Set per module from the header (see `ErgConfig::apply_header`).
+    pub lang: LangOptions,
+    /// The prelude of the project (its public names overlay the builtins).
+    /// Set per module by the compiler (see `ASTLowerer::new_with_cache`).
+    pub prelude: Option<PathBuf>,
     pub py_magic_num: Option<u32>, // the magic number cannot be uniquely determined from `target_version`
     pub py_command: Option<&'static str>,
     pub target_version: Option<PythonVersion>,
+    pub target: TargetProfile,
     pub py_server_timeout: u64,
+    /// the maximum nesting depth of type checking (unification, type evaluation, call substitution)
+    pub max_type_check_depth: usize,
+    /// the maximum number of the type checking steps performed for a single top-level check
+    pub max_type_check_steps: usize,
     pub quiet_repl: bool,
+    /// print diagnostics as `{file}:{line}:{col}: {kind}` lines
+    pub codes_only: bool,
+    /// report the time of each compilation phase (`--timings`, `--timings-trace`)
+    pub timings: Option<TimingsFormat>,
+    /// print the statistics of the compiler's caches (`--stats`)
+    pub stats: bool,
     pub show_type: bool,
     pub input: Input,
+    /// the position queried by `erg type-at` (1-based line and column)
+    pub query_pos: Option<(u32, u32)>,
+    /// check the examples in declaration files (`erg test --decls`)
+    pub test_decls: bool,
+    /// dump HIR instead of AST (`erg dump --hir`)
+    pub dump_hir: bool,
+    /// print the module graph as JSON instead of DOT (`erg graph --json`)
+    pub graph_json: bool,
+    /// dump the AST after each desugaring pass (`--dump-desugar`)
+    pub dump_desugar: bool,
     pub output: Output,
     pub dist_dir: Option<&'static str>,
     /// module name to be executed
@@ -109,13 +233,29 @@ impl Default for ErgConfig {
             mode: ErgMode::Execute,
             opt_level: 1,
             no_std: false,
+            runtime_checks: false,
+            strict: false,
+            py_compat: PYTHON_MODE,
+            lang: LangOptions::default(),
+            prelude: None,
             py_magic_num: None,
             py_command: None,
             target_version: None,
+            target: TargetProfile::CPython,
             py_server_timeout: 10,
+            max_type_check_depth: 256,
+            max_type_check_steps: 100_000,
             quiet_repl: false,
+            codes_only: false,
+            timings: None,
+            stats: false,
             show_type: false,
             input: Input::repl(),
+            query_pos: None,
+            test_decls: false,
+            dump_hir: false,
+            graph_json: false,
+            dump_desugar: false,
             output: Output::stdout(),
             dist_dir: None,
             module: "<module>",
@@ -172,6 +312,13 @@ impl ErgConfig {
         dump_path
     }
 
+    /// e.g. `foo.er` -> `foo.d.er`
+    pub fn dump_decl_path(&self) -> PathBuf {
+        let mut dump_path = self.dump_path();
+        dump_path.set_extension("d.er");
+        dump_path
+    }
+
     pub fn dump_pyc_filename(&self) -> String {
         let dump_filename = self.dump_filename();
         if dump_filename.ends_with(".er") {
@@ -186,10 +333,33 @@ impl ErgConfig {
         Self {
             module: Box::leak(path.to_str().unwrap().to_string().into_boxed_str()),
             input: Input::file(path),
+            // recomputed for each module by the compiler
+            py_compat: PYTHON_MODE,
+            lang: LangOptions::default(),
+            prelude: None,
             ..self.copy()
         }
     }
 
+    /// Sets the language options specified by the header of `src` (the defaults if there is no header).
+    /// Returns the line number and the reason if the header is invalid.
+    pub fn apply_header(&mut self, src: &str) -> Option<(u32, String)> {
+        match LangOptions::parse_header(src) {
+            Some((_, Ok(lang))) => {
+                self.lang = lang;
+                None
+            }
+            Some((lineno, Err(err))) => {
+                self.lang = LangOptions::default();
+                Some((lineno, err))
+            }
+            None => {
+                self.lang = LangOptions::default();
+                None
+            }
+        }
+    }
+
     pub fn parse() -> Self {
         let mut args = env::args();
         args.next(); // "ergc"
@@ -199,7 +369,8 @@ impl ErgConfig {
             match &arg[..] {
                 /* Commands */
                 "lex" | "parse" | "desugar" | "typecheck" | "check" | "compile" | "transpile"
-                | "run" | "execute" | "server" | "tc" => {
+                | "migrate" | "type-at" | "test" | "audit" | "declare" | "stub" | "fix"
+                | "dump" | "graph" | "run" | "execute" | "server" | "tc" => {
                     cfg.mode = ErgMode::try_from(&arg[..]).unwrap();
                 }
                 /* Options */
@@ -218,12 +389,36 @@ impl ErgConfig {
                 "--compile" | "--dump-as-pyc" => {
                     cfg.mode = ErgMode::Compile;
                 }
+                "--ast" => {
+                    cfg.dump_hir = false;
+                }
+                "--hir" => {
+                    cfg.dump_hir = true;
+                }
+                "--dot" => {
+                    cfg.graph_json = false;
+                }
+                "--json" => {
+                    cfg.graph_json = true;
+                }
+                "--decls" => {
+                    cfg.test_decls = true;
+                }
+                "--dump-desugar" => {
+                    cfg.dump_desugar = true;
+                }
                 "--language-server" => {
                     cfg.mode = ErgMode::LanguageServer;
                 }
                 "--no-std" => {
                     cfg.no_std = true;
                 }
+                "--runtime-checks" => {
+                    cfg.runtime_checks = true;
+                }
+                "--strict" => {
+                    cfg.strict = true;
+                }
                 "-?" | "-h" | "--help" => {
                     println!("{}", command_message());
                     if let "--mode" = args.next().as_ref().map(|s| &s[..]).unwrap_or("") {
@@ -238,6 +433,20 @@ impl ErgConfig {
                         .into_boxed_str();
                     cfg.module = Box::leak(module);
                 }
+                "--max-type-check-depth" => {
+                    cfg.max_type_check_depth = args
+                        .next()
+                        .expect("the value of `--max-type-check-depth` is not passed")
+                        .parse::<usize>()
+                        .expect("the value of `--max-type-check-depth` is not a number");
+                }
+                "--max-type-check-steps" => {
+                    cfg.max_type_check_steps = args
+                        .next()
+                        .expect("the value of `--max-type-check-steps` is not passed")
+                        .parse::<usize>()
+                        .expect("the value of `--max-type-check-steps` is not a number");
+                }
                 "--mode" => {
                     let mode = args.next().expect("the value of `--mode` is not passed");
                     if let "-?" | "-h" | "--help" = &mode[..] {
@@ -317,12 +526,31 @@ impl ErgConfig {
                         .parse::<u64>()
                         .expect("the value of `--py-server-timeout` is not a number");
                 }
-                "--quiet-startup" | "--quiet-repl" => {
+                "--quiet" | "--quiet-startup" | "--quiet-repl" => {
                     cfg.quiet_repl = true;
                 }
+                "--codes-only" => {
+                    cfg.codes_only = true;
+                }
+                "--stats" => {
+                    cfg.stats = true;
+                }
+                "--timings" => {
+                    cfg.timings = Some(TimingsFormat::Table);
+                }
+                "--timings-trace" => {
+                    cfg.timings = Some(TimingsFormat::Chrome);
+                }
                 "-t" | "--show-type" => {
                     cfg.show_type = true;
                 }
+                "--target" => {
+                    cfg.target = args
+                        .next()
+                        .expect("the value of `--target` is not passed")
+                        .parse::<TargetProfile>()
+                        .unwrap_or_else(|err| panic!("{err}"));
+                }
                 "--target-version" => {
                     let target_version = args
                         .next()
@@ -380,14 +608,29 @@ USAGE:
                     process::exit(2);
                 }
                 _ => {
+                    // `erg type-at file.er:LINE:COL`
+                    let arg = if cfg.mode == ErgMode::TypeAt {
+                        let mut split = arg.rsplitn(3, ':');
+                        let col = split.next().and_then(|col| col.parse::<u32>().ok());
+                        let line = split.next().and_then(|line| line.parse::<u32>().ok());
+                        let (Some(line), Some(col), Some(path)) = (line, col, split.next()) else {
+                            panic!("invalid position: {arg} (expected `file.er:LINE:COL`)");
+                        };
+                        cfg.query_pos = Some((line, col));
+                        path.to_string()
+                    } else {
+                        arg
+                    };
                     let path = PathBuf::from_str(&arg[..])
                         .unwrap_or_else(|_| panic!("invalid file path: {arg}"));
                     let path = normalize_path(path);
                     cfg.input = Input::file(path);
-                    if let Some("--") = args.next().as_ref().map(|s| &s[..]) {
-                        for arg in args {
-                            cfg.runtime_args.push(Box::leak(arg.into_boxed_str()));
-                        }
+                    // the arguments after the script are passed to the script
+                    // (e.g. `erg script.er a b` or `./script.er a b` with `#!/usr/bin/env erg`)
+                    let mut args = args.peekable();
+                    args.next_if(|arg| arg == "--");
+                    for arg in args {
+                        cfg.runtime_args.push(Box::leak(arg.into_boxed_str()));
                     }
                     break;
                 }
@@ -0,0 +1,209 @@
+//! Defines the language edition and the feature switches of a module.
+//!
+//! A module can specify them in the header, at the first line (or the line after a shebang):
+//! ```erg
+//! #!erg 0.6 +conditional_binding
+//! ```
+//! Features that are not switched explicitly are enabled if the edition of the module supports them.
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Edition {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl fmt::Display for Edition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl Default for Edition {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+impl FromStr for Edition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid edition: {s} (expected `<major>.<minor>`)");
+        let (major, minor) = s.split_once('.').ok_or_else(invalid)?;
+        let edition = Self::new(
+            major.parse().map_err(|_| invalid())?,
+            minor.parse().map_err(|_| invalid())?,
+        );
+        if edition < Self::OLDEST || edition > Self::CURRENT {
+            return Err(format!(
+                "unsupported edition: {edition} (supported: {} to {})",
+                Self::OLDEST,
+                Self::CURRENT
+            ));
+        }
+        Ok(edition)
+    }
+}
+
+impl Edition {
+    pub const OLDEST: Self = Self::new(0, 6);
+    pub const CURRENT: Self = Self::new(0, 7);
+
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+}
+
+/// The features that can be switched per module, and the editions in which they are enabled by default
+pub const FEATURES: &[(&str, Edition)] = &[
+    // `if (i: Int) := x, do: ...`
+    ("conditional_binding", Edition::new(0, 7)),
+];
+
+/// The language options of a module (`#!erg <edition> [+feature | -feature]...`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LangOptions {
+    pub edition: Edition,
+    enabled: Vec<&'static str>,
+    disabled: Vec<&'static str>,
+}
+
+impl fmt::Display for LangOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#!erg {}", self.edition)?;
+        for feature in self.enabled.iter() {
+            write!(f, " +{feature}")?;
+        }
+        for feature in self.disabled.iter() {
+            write!(f, " -{feature}")?;
+        }
+        Ok(())
+    }
+}
+
+impl LangOptions {
+    pub const HEADER: &'static str = "#!erg";
+
+    pub const fn new(edition: Edition) -> Self {
+        Self {
+            edition,
+            enabled: Vec::new(),
+            disabled: Vec::new(),
+        }
+    }
+
+    /// Returns `false` if `feature` is unknown.
+    pub fn enable(&mut self, feature: &str) -> bool {
+        let Some((feature, _)) = FEATURES.iter().find(|(name, _)| *name == feature) else {
+            return false;
+        };
+        self.disabled.retain(|f| f != feature);
+        if !self.enabled.contains(feature) {
+            self.enabled.push(feature);
+        }
+        true
+    }
+
+    /// Returns `false` if `feature` is unknown.
+    pub fn disable(&mut self, feature: &str) -> bool {
+        let Some((feature, _)) = FEATURES.iter().find(|(name, _)| *name == feature) else {
+            return false;
+        };
+        self.enabled.retain(|f| f != feature);
+        if !self.disabled.contains(feature) {
+            self.disabled.push(feature);
+        }
+        true
+    }
+
+    /// Unknown features are regarded as enabled.
+    pub fn is_enabled(&self, feature: &str) -> bool {
+        if self.disabled.contains(&feature) {
+            return false;
+        }
+        if self.enabled.contains(&feature) {
+            return true;
+        }
+        FEATURES
+            .iter()
+            .find(|(name, _)| *name == feature)
+            .is_none_or(|(_, since)| *since <= self.edition)
+    }
+
+    /// The edition that enables `feature` by default
+    pub fn edition_of(feature: &str) -> Option<Edition> {
+        FEATURES
+            .iter()
+            .find(|(name, _)| *name == feature)
+            .map(|(_, since)| *since)
+    }
+
+    /// Finds the header in `src` and parses it.
+    /// Returns `(line number, result)` of the header (`None` if `src` has no header).
+    pub fn parse_header(src: &str) -> Option<(u32, Result<Self, String>)> {
+        let mut lines = src.lines();
+        let first = lines.next()?;
+        let (lineno, header) = if first.starts_with(Self::HEADER) {
+            (1, first)
+        } else if first.starts_with("#!") {
+            (2, lines.next().filter(|l| l.starts_with(Self::HEADER))?)
+        } else {
+            return None;
+        };
+        let rest = &header[Self::HEADER.len()..];
+        // e.g. `#!erg-foo`
+        if !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let mut args = rest.split_whitespace();
+        let Some(edition) = args.next() else {
+            return Some((lineno, Err("the edition is not specified".to_string())));
+        };
+        let mut opts = match edition.parse() {
+            Ok(edition) => Self::new(edition),
+            Err(err) => return Some((lineno, Err(err))),
+        };
+        for arg in args {
+            let (known, feature) = if let Some(feature) = arg.strip_prefix('+') {
+                (opts.enable(feature), feature)
+            } else if let Some(feature) = arg.strip_prefix('-') {
+                (opts.disable(feature), feature)
+            } else {
+                let err =
+                    format!("invalid feature switch: {arg} (expected `+feature` or `-feature`)");
+                return Some((lineno, Err(err)));
+            };
+            if !known {
+                return Some((lineno, Err(format!("unknown feature: {feature}"))));
+            }
+        }
+        Some((lineno, Ok(opts)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header() {
+        let (lineno, opts) = LangOptions::parse_header("#!erg 0.6\nx = 1").unwrap();
+        assert_eq!(lineno, 1);
+        let opts = opts.unwrap();
+        assert_eq!(opts.edition, Edition::new(0, 6));
+        assert!(!opts.is_enabled("conditional_binding"));
+        let src = "#!/usr/bin/env erg\n#!erg 0.6 +conditional_binding\n";
+        let (lineno, opts) = LangOptions::parse_header(src).unwrap();
+        assert_eq!(lineno, 2);
+        let opts = opts.unwrap();
+        assert!(opts.is_enabled("conditional_binding"));
+        assert_eq!(opts.to_string(), "#!erg 0.6 +conditional_binding");
+        assert!(LangOptions::default().is_enabled("conditional_binding"));
+        assert!(LangOptions::parse_header("x = 1\n#!erg 0.6").is_none());
+        assert!(LangOptions::parse_header("#!erg 1.0").unwrap().1.is_err());
+        let (_, unknown) = LangOptions::parse_header("#!erg 0.7 +foo").unwrap();
+        assert!(unknown.is_err());
+    }
+}
@@ -66,6 +66,11 @@ fn _python_site_packages() -> impl Iterator<Item = PathBuf> {
             })
         })
 }
+fn _typeshed_path() -> Option<PathBuf> {
+    var("ERG_TYPESHED_PATH")
+        .ok()
+        .and_then(|path| PathBuf::from(path).canonicalize().ok())
+}
 
 pub static ERG_PATH: OnceLock<PathBuf> = OnceLock::new();
 pub static ERG_STD_PATH: OnceLock<PathBuf> = OnceLock::new();
@@ -73,6 +78,7 @@ pub static ERG_STD_DECL_PATH: OnceLock<PathBuf> = OnceLock::new();
 pub static ERG_PYSTD_PATH: OnceLock<PathBuf> = OnceLock::new();
 pub static ERG_EXTERNAL_LIB_PATH: OnceLock<PathBuf> = OnceLock::new();
 pub static PYTHON_SITE_PACKAGES: OnceLock<Vec<PathBuf>> = OnceLock::new();
+pub static TYPESHED_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
 
 pub fn erg_path() -> &'static PathBuf {
     ERG_PATH.get_or_init(|| normalize_path(_erg_path())) // .with(|s| s.clone())
@@ -98,6 +104,13 @@ pub fn python_site_packages() -> &'static Vec<PathBuf> {
     PYTHON_SITE_PACKAGES.get_or_init(|| _python_site_packages().collect())
 }
 
+/// The root of a typeshed checkout (`$ERG_TYPESHED_PATH`), if specified
+pub fn typeshed_path() -> Option<&'static PathBuf> {
+    TYPESHED_PATH
+        .get_or_init(|| _typeshed_path().map(normalize_path))
+        .as_ref()
+}
+
 pub fn is_std_decl_path(path: &Path) -> bool {
     path.starts_with(erg_pystd_path().as_path())
         || path.starts_with(erg_std_decl_path().as_path())
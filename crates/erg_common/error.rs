@@ -4,6 +4,8 @@
 use std::cmp::{self, Ordering};
 use std::fmt;
 use std::io::{stderr, BufWriter, Write as _};
+use std::path::PathBuf;
+use std::sync::atomic::{self, AtomicBool};
 
 use crate::io::{Input, InputKind};
 use crate::style::Attribute;
@@ -735,6 +737,46 @@ impl SubMessage {
     }
 }
 
+/// An edit that fixes an error, which tools (e.g. ELS) can apply as is.
+/// It is shown as a hint in the error message, so the edit itself is not displayed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Suggestion {
+    /// e.g. "make `x` public"
+    pub title: String,
+    /// The file to be edited. `None` means the file where the error occurred.
+    pub path: Option<PathBuf>,
+    /// The range to be replaced. If it is empty, `replacement` is inserted.
+    pub loc: Location,
+    pub replacement: String,
+}
+
+impl Suggestion {
+    pub fn new<S: Into<String>>(
+        title: S,
+        path: Option<PathBuf>,
+        loc: Location,
+        replacement: S,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            path,
+            loc,
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Inserts `text` at `ln`:`col` (`ln` is 1-origin, `col` is 0-origin).
+    pub fn insert<S: Into<String>>(
+        title: S,
+        path: Option<PathBuf>,
+        ln: u32,
+        col: u32,
+        text: S,
+    ) -> Self {
+        Self::new(title, path, Location::range(ln, col, ln, col), text)
+    }
+}
+
 /// In Erg, common parts used by error.
 /// Must be wrap when to use.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -744,6 +786,7 @@ pub struct ErrorCore {
     pub errno: usize,
     pub kind: ErrorKind,
     pub loc: Location,
+    pub suggestions: Vec<Suggestion>,
     theme: Theme,
 }
 
@@ -769,10 +812,16 @@ impl ErrorCore {
             errno,
             kind,
             loc,
+            suggestions: vec![],
             theme: THEME,
         }
     }
 
+    pub fn with_suggestions(mut self, suggestions: Vec<Suggestion>) -> Self {
+        self.suggestions = suggestions;
+        self
+    }
+
     pub fn dummy(errno: usize) -> Self {
         Self::new(
             vec![SubMessage::only_loc(Location::Unknown)],
@@ -879,6 +928,17 @@ impl ErrorCore {
     }
 }
 
+static CODES_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// If true, diagnostics are rendered as `{file}:{line}:{col}: {kind}` lines (`--codes-only`).
+pub fn set_codes_only(codes_only: bool) {
+    CODES_ONLY.store(codes_only, atomic::Ordering::Relaxed);
+}
+
+pub fn is_codes_only() -> bool {
+    CODES_ONLY.load(atomic::Ordering::Relaxed)
+}
+
 /// format:
 /// ```txt
 /// Error[#{.errno}]: File {file}, line {.loc (as line)}, in {.caused_by}
@@ -929,6 +989,9 @@ pub trait ErrorDisplay {
     }
 
     fn show(&self) -> String {
+        if is_codes_only() {
+            return self.show_code() + "\n";
+        }
         let core = self.core();
         let (color, mark) = core.specified_theme();
         let (gutter_color, chars) = core.theme.characters();
@@ -949,8 +1012,27 @@ pub trait ErrorDisplay {
         msg
     }
 
+    /// `{file}:{line}:{col}: {kind}` (1-origin, 0 if unknown)
+    fn show_code(&self) -> String {
+        let core = self.core();
+        format!(
+            "{}:{}:{}: {}",
+            self.input().kind.enclosed_name(),
+            core.loc.ln_begin().unwrap_or(0),
+            core.loc.col_begin().map_or(0, |col| col + 1),
+            core.kind
+        )
+    }
+
     /// for fmt::Display
     fn format(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if is_codes_only() {
+            writeln!(f, "{}", self.show_code())?;
+            return match self.ref_inner() {
+                Some(inner) => inner.format(f),
+                None => Ok(()),
+            };
+        }
         let core = self.core();
         let (color, mark) = core.specified_theme();
         let (gutter_color, chars) = core.theme.characters();
@@ -18,8 +18,18 @@ OPTIONS
     --verbose 0|1|2                      冗長性レベルを指定
     --opt-level/-o 0|1|2|3               最適化レベルを指定
     --python-version/-p (uint 32 number) Pythonバージョンを指定
+    --target cpython|micropython         ターゲットのランタイムを指定
     --py-server-timeout (uint 64 number) PythonのREPLサーバーのタイムアウト時間を指定
+    --max-type-check-depth (uint number) 型検査の再帰の深さの上限
+    --max-type-check-steps (uint number) 一つの型検査で行う処理の上限
     --dump-as-pyc                        .pycファイルにダンプ
+    --dump-desugar                       各脱糖パスの後のASTを出力
+    --codes-only                         エラーを`ファイル:行:列: 種類`の形式で出力
+    --stats                              コンパイラのキャッシュの統計を表示
+    --timings                            各コンパイルフェーズの所要時間を表示
+    --timings-trace                      所要時間をChromeのトレース形式(erg-timings.json)で出力
+    --runtime-checks                     Dynamic型の値の実行時型検査を挿入
+    --strict                             寛容な検査(暗黙の合併型、Dynamic型の暗黙の変換など)をエラーにする
     --mode (mode)                        指定モードで実行(詳細は--mode --helpを参照)
     --code/-c (string)                   文字列として渡したプログラムを実行
     --module/-m (string)                 モジュールを実行
@@ -31,6 +41,15 @@ COMMAND
     check                                全ての検査(所有権検査, 副作用検査などを含む)
     compile                              コンパイル
     transpile                            トランスパイル
+    migrate                              Pythonスクリプトを移植
+    type-at (file:line:col)              指定位置の式の型を表示
+    test --decls (file|dir)              .d.erファイル中の例を検査
+    audit                                Noneが流れ込みうる引数を報告
+    declare                              公開インターフェースから.d.erファイルを生成
+    stub                                 transpileされたモジュールの.pyiスタブを生成
+    fix                                  提案された修正を適用
+    dump --ast|--hir                     AST/HIRをJSONで出力
+    graph --dot|--json                   モジュールの依存グラフをDOT/JSONで出力
     run|exec                             実行(デフォルト)
     server                               言語サーバーを起動",
 
@@ -49,8 +68,18 @@ OPTIONS
     --verbose 0|1|2                      指定细致程度
     --opt-level/-o 0|1|2|3               指定优化级别
     --python-version/-p (uint 32 number) Python 版本
+    --target cpython|micropython         目标运行时
     --py-server-timeout (uint 64 number) 指定等待 REPL 输出的秒数
+    --max-type-check-depth (uint number) 类型检查的最大递归深度
+    --max-type-check-steps (uint number) 单次类型检查的最大步骤数
     --dump-as-pyc                        转储为 .pyc 文件
+    --dump-desugar                       输出每个脱糖阶段之后的 AST
+    --codes-only                         以`文件:行:列: 种类`格式输出错误
+    --stats                              显示编译器缓存的统计信息
+    --timings                            显示各编译阶段的耗时
+    --timings-trace                      以Chrome跟踪格式(erg-timings.json)输出耗时
+    --runtime-checks                     插入 Dynamic 类型值的运行时类型检查
+    --strict                             将宽松的检查(隐式联合类型、Dynamic 类型的隐式转换等)视为错误
     --mode (mode)                        执行模式 (更多信息见`--mode --help`)
     --code/-c (string)                   作为字符串传入程序
     --module/-m (string)                 要执行的模块
@@ -62,6 +91,15 @@ COMMAND
     check                                全部检查(包括所有权检查, 副作用检查等)
     compile                              编译
     transpile                            转译
+    migrate                              迁移 Python 脚本
+    type-at (file:line:col)              显示指定位置的表达式的类型
+    test --decls (file|dir)              检查 .d.er 文件中的示例
+    audit                                报告可能传入 None 的参数
+    declare                              从公开接口生成 .d.er 文件
+    stub                                 为转译后的模块生成 .pyi 存根
+    fix                                  应用建议的修复
+    dump --ast|--hir                     以 JSON 输出 AST/HIR
+    graph --dot|--json                   以 DOT/JSON 输出模块依赖图
     run|exec                             执行(默认模式)
     server                               执行语言服务器",
 
@@ -80,8 +118,18 @@ OPTIONS
     --verbose 0|1|2                      指定細緻程度
     --opt-level/-o 0|1|2|3               指定優化級別
     --python-version/-p (uint 32 number) Python 版本
+    --target cpython|micropython         目標運行時
     --py-server-timeout (uint 64 number) 指定等待 REPL 輸出的秒數
+    --max-type-check-depth (uint number) 型別檢查的最大遞迴深度
+    --max-type-check-steps (uint number) 單次型別檢查的最大步驟數
     --dump-as-pyc                        轉儲為 .pyc 文件
+    --dump-desugar                       輸出每個脫糖階段之後的 AST
+    --codes-only                         以`文件:行:列: 種類`格式輸出錯誤
+    --stats                              顯示編譯器快取的統計資訊
+    --timings                            顯示各編譯階段的耗時
+    --timings-trace                      以Chrome追蹤格式(erg-timings.json)輸出耗時
+    --runtime-checks                     插入 Dynamic 型別值的執行時型別檢查
+    --strict                             將寬鬆的檢查(隱式聯合型別、Dynamic 型別的隱式轉換等)視為錯誤
     --mode (mode)                        執行模式 (更多信息見`--mode --help`)
     --code/-c (string)                   作為字串傳入程式
     --module/-m (string)                 要執行的模塊
@@ -93,6 +141,15 @@ COMMAND
     check                                全部檢查(包括所有權檢查, 副作用檢查等)
     compile                              編譯
     transpile                            轉譯
+    migrate                              遷移 Python 腳本
+    type-at (file:line:col)              顯示指定位置的表達式的類型
+    test --decls (file|dir)              檢查 .d.er 檔案中的示例
+    audit                                報告可能傳入 None 的參數
+    declare                              從公開介面產生 .d.er 檔案
+    stub                                 為轉譯後的模組產生 .pyi 存根
+    fix                                  套用建議的修正
+    dump --ast|--hir                     以 JSON 輸出 AST/HIR
+    graph --dot|--json                   以 DOT/JSON 輸出模組依賴圖
     run|exec                             執行(預設模式)
     server                               執行語言伺服器",
 
@@ -111,8 +168,18 @@ OPTIONS
     --verbose 0|1|2                      verbosity level
     --opt-level/-o 0|1|2|3               optimization level
     --python-version/-p (uint 32 number) Python version
+    --target cpython|micropython         target runtime
     --py-server-timeout (uint 64 number) timeout for the Python REPL server
+    --max-type-check-depth (uint number) maximum nesting depth of type checking
+    --max-type-check-steps (uint number) maximum steps of a single type check
     --dump-as-pyc                        dump as .pyc file
+    --dump-desugar                       dump the AST after each desugaring pass
+    --codes-only                         print diagnostics as `file:line:col: kind` lines
+    --stats                              show the statistics of the compiler's caches
+    --timings                            show the time of each compilation phase
+    --timings-trace                      write the timings as a Chrome trace (erg-timings.json)
+    --runtime-checks                     insert runtime type checks for Dynamic values
+    --strict                             report lenient checks (implicit unions, Dynamic coercions, etc.) as errors
     --mode (mode)                        execution mode (See `--mode --help` for details)
     --code/-c (string)                   program passed in as string
     --module/-m (string)                 module to be executed
@@ -124,6 +191,15 @@ COMMAND
     check                                full check (including ownership check, effect check, etc.)
     compile                              compile
     transpile                            transpile
+    migrate                              migrate a Python script
+    type-at (file:line:col)              show the type of the expression at the position
+    test --decls (file|dir)              check the examples in .d.er files
+    audit                                report where None may flow into parameters
+    declare                              generate a .d.er file from the public interface
+    stub                                 generate a .pyi stub for the transpiled module
+    fix                                  apply the suggested fixes
+    dump --ast|--hir                     print the AST/HIR as JSON
+    graph --dot|--json                   print the module dependency graph as DOT/JSON
     run|exec                             execute (default mode)
     server                               execute language server",
     )
@@ -160,6 +236,42 @@ transpile
     checkを実行
     HIRからPythonスクリプトを生成し、<filename>.pyを出力
 
+migrate
+    <filename>.pyをErgに(可能な範囲で)翻訳し、<filename>.erを出力
+    翻訳できなかった箇所はTODOコメントとプレースホルダ(...)で示される
+
+type-at
+    checkを実行し、<filename>.er:LINE:COLの位置にある最小の式の型を表示
+    期待される型と型の絞り込みも表示される
+
+test --decls
+    .d.erファイル(またはディレクトリ以下の全ての.d.erファイル)のドキュメントコメント中の`erg`コード例を型検査する
+    コード例は実行されない
+
+audit
+    checkを実行し、Pythonの値(宣言ファイルで宣言された値)がモジュールを跨いでNoneTypeを許容しない引数に渡される箇所を報告
+    Pythonの関数は宣言に関わらずNoneを返しうるため、報告は流入元ごとにまとめられ、注釈を付けるべき順に並べられる
+
+declare
+    checkを実行し、モジュールの公開インターフェース(変数、サブルーチン、クラスの属性とメソッド)を<filename>.d.erに出力
+    下流のビルドは実装をパースせずに宣言ファイルに対して型検査できる
+
+stub
+    checkを実行し、transpileで生成されるPythonスクリプトのPEP 484スタブを<filename>.pyiに出力
+    篩型は拡大され、トレイトはProtocolとして宣言される
+
+fix
+    checkを実行し、エラーと警告が提案する修正(似た名前への置換、未使用のインポートの削除、可視性の変更など)をソースファイルに適用
+    他の修正と重なる修正は適用されず、競合として報告される
+
+dump --ast|--hir
+    脱糖されたAST(--ast、デフォルト)またはcheckで得られたHIR(--hir)をJSONで出力
+    各ノードは種類(node)と位置(loc)を持ち、HIRのノードは型(type)も持つ
+
+graph --dot|--json
+    checkを実行し、インポートされたモジュール(Pythonモジュールを含む)の依存グラフをDOT(--dot、デフォルト)またはJSON(--json)で出力
+    辺 a -> b はaがbをインポートすることを表す
+
 run/exec
     compileを実行し、更に<filename>.pycを実行
 
@@ -195,6 +307,42 @@ transpile
     运行 check 以获取检查完成的 AST
     将 AST 转换为 Python 代码并返回 <文件名>.py
 
+migrate
+    将 <文件名>.py 尽可能翻译为 Erg 并输出 <文件名>.er
+    无法翻译的部分用 TODO 注释和占位符(...)标记
+
+type-at
+    执行 check, 显示 <文件名>.er:LINE:COL 位置的最小表达式的类型
+    同时显示期望的类型和类型收窄
+
+test --decls
+    对 .d.er 文件(或目录下的所有 .d.er 文件)的文档注释中的 `erg` 示例进行类型检查
+    示例不会被执行
+
+audit
+    执行 check，并报告 Python 值(在声明文件中声明的值)跨模块传给不接受 NoneType 的参数的位置
+    无论声明如何，Python 函数都可能返回 None。报告按来源分组，并按需要添加注解的顺序排列
+
+declare
+    执行 check，并将模块的公开接口(变量、子程序、类的属性和方法)输出到 <文件名>.d.er
+    下游构建可以针对声明文件进行类型检查，而无需解析实现
+
+stub
+    执行 check，并将 transpile 生成的 Python 脚本的 PEP 484 存根输出到 <文件名>.pyi
+    筛型会被放宽，trait 被声明为 Protocol
+
+fix
+    执行 check，并将错误和警告建议的修复(替换为相似名称、删除未使用的导入、更改可见性等)应用到源文件
+    与其他修复重叠的修复不会被应用，并作为冲突报告
+
+dump --ast|--hir
+    以 JSON 输出脱糖后的 AST(--ast，默认)或 check 得到的 HIR(--hir)
+    每个节点都有种类(node)和位置(loc)，HIR 的节点还有类型(type)
+
+graph --dot|--json
+    执行 check，并以 DOT(--dot，默认)或 JSON(--json)输出导入的模块(包括 Python 模块)的依赖图
+    边 a -> b 表示 a 导入了 b
+
 run/exec
     运行 check 以获取检查完成的 AST
     在执行 <文件名>.pyc 后删除 <文件名>.pyc
@@ -231,6 +379,42 @@ transpile
     運行 check 以獲取檢查完成的 AST
     從 HIR 生成 Python 腳本並返回 <檔名>.py
 
+migrate
+    將 <檔名>.py 盡可能翻譯為 Erg 並輸出 <檔名>.er
+    無法翻譯的部分用 TODO 註釋和佔位符(...)標記
+
+type-at
+    執行 check, 顯示 <檔名>.er:LINE:COL 位置的最小表達式的類型
+    同時顯示期望的類型和類型收窄
+
+test --decls
+    對 .d.er 檔案(或目錄下的所有 .d.er 檔案)的文件註解中的 `erg` 示例進行類型檢查
+    示例不會被執行
+
+audit
+    執行 check，並報告 Python 值(在宣告檔案中宣告的值)跨模組傳給不接受 NoneType 的參數的位置
+    無論宣告如何，Python 函式都可能回傳 None。報告按來源分組，並按需要加上註解的順序排列
+
+declare
+    執行 check，並將模組的公開介面(變數、子程式、類別的屬性和方法)輸出到 <檔名>.d.er
+    下游建置可以針對宣告檔案進行型檢查，而無需解析實作
+
+stub
+    執行 check，並將 transpile 產生的 Python 腳本的 PEP 484 存根輸出到 <檔名>.pyi
+    篩型會被放寬，trait 被宣告為 Protocol
+
+fix
+    執行 check，並將錯誤和警告建議的修正(替換為相似名稱、刪除未使用的匯入、變更可見性等)套用到原始檔
+    與其他修正重疊的修正不會被套用，並作為衝突報告
+
+dump --ast|--hir
+    以 JSON 輸出脫糖後的 AST(--ast，預設)或 check 得到的 HIR(--hir)
+    每個節點都有種類(node)和位置(loc)，HIR 的節點還有型別(type)
+
+graph --dot|--json
+    執行 check，並以 DOT(--dot，預設)或 JSON(--json)輸出匯入的模組(包括 Python 模組)的依賴圖
+    邊 a -> b 表示 a 匯入了 b
+
 exec
     運行check以獲取檢查完成的 AST
     在執行 <檔名>.pyc 後删除 <檔名>.pyc
@@ -267,6 +451,42 @@ transpile
     Execute check
     Generates Python script from HIR and outputs <filename>.py
 
+migrate
+    Translates <filename>.py into Erg on a best-effort basis and outputs <filename>.er
+    Untranslatable spots are marked with TODO comments and placeholders (...)
+
+type-at
+    Execute check and show the type of the innermost expression at <filename>.er:LINE:COL
+    The expected type and the narrowing of the type are also shown
+
+test --decls
+    Type-check the `erg` examples in the doc comments of a .d.er file (or all .d.er files under a directory)
+    The examples are not executed
+
+audit
+    Perform check, and report where Python values (declared in declaration files) are passed across modules to parameters that do not accept NoneType
+    A Python function may return None regardless of its declaration, so the report is grouped by origin and ranked to show where to add annotations first
+
+declare
+    Perform check, and write the public interface of the module (variables, subroutines, and attributes and methods of classes) to <filename>.d.er
+    Downstream builds can type-check against the declaration file without parsing the implementation
+
+stub
+    Perform check, and write a PEP 484 stub of the Python script generated by transpile to <filename>.pyi
+    Refinement types are widened, and traits are declared as Protocols
+
+fix
+    Perform check, and apply the fixes suggested by the errors and warnings (e.g. replacing with a similar name, removing an unused import, changing the visibility) to the source files
+    A fix overlapping with another fix is not applied and reported as a conflict
+
+dump --ast|--hir
+    Print the desugared AST (--ast, default) or the HIR obtained by check (--hir) as JSON
+    Each node has its kind (node) and location (loc), and the nodes of HIR also have their types (type)
+
+graph --dot|--json
+    Perform check, and print the dependency graph of the imported modules (including Python modules) as DOT (--dot, default) or JSON (--json)
+    An edge a -> b means that a imports b
+
 run/exec
     Execute compile and then <filename>.pyc
 
@@ -276,20 +496,29 @@ read
 }
 
 pub const OPTIONS: &[&str] = &[
+    "--ast",
     "--build-features",
     "-c",
     "--code",
     "--check",
+    "--codes-only",
     "--compile",
+    "--decls",
+    "--dot",
     "--dest",
     "--dump-as-pyc",
+    "--dump-desugar",
     "--language-server",
     "--no-std",
     "--help",
     "-?",
     "-h",
     "--hex-py-magic-num",
+    "--hir",
     "--hex-python-magic-number",
+    "--json",
+    "--max-type-check-depth",
+    "--max-type-check-steps",
     "--mode",
     "--module",
     "-m",
@@ -307,11 +536,18 @@ pub const OPTIONS: &[&str] = &[
     "--python-command",
     "--py-magic-num",
     "--python-magic-number",
+    "--quiet",
     "--quiet-startup",
     "--quiet-repl",
+    "--runtime-checks",
     "--show-type",
+    "--stats",
+    "--strict",
     "-t",
+    "--target",
     "--target-version",
+    "--timings",
+    "--timings-trace",
     "--version",
     "-V",
     "--verbose",
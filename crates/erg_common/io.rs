@@ -1,13 +1,14 @@
 use std::ffi::OsStr;
 use std::fs::File;
 use std::io::{BufRead, BufReader, Read, Stdout, Write};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::process;
 use std::process::Stdio;
 
 use crate::consts::{ERG_MODE, EXPERIMENTAL_MODE};
 use crate::env::{
     erg_path, erg_py_external_lib_path, erg_pystd_path, erg_std_path, python_site_packages,
+    typeshed_path,
 };
 use crate::pathutil::{add_postfix_foreach, remove_postfix};
 use crate::python_util::get_sys_path;
@@ -208,8 +209,12 @@ impl Input {
         GLOBAL_STDIN.insert_whitespace(whitespace);
     }
 
-    pub fn set_indent(&self, indent: usize) {
-        GLOBAL_STDIN.set_indent(indent);
+    pub fn set_prompt(&self, prompt: &str) {
+        GLOBAL_STDIN.set_prompt(prompt);
+    }
+
+    pub fn set_completion_words(&self, words: Vec<String>) {
+        GLOBAL_STDIN.set_completion_words(words);
     }
 
     pub fn file_stem(&self) -> String {
@@ -344,6 +349,39 @@ impl Input {
         }
     }
 
+    /// Whether the header (the leading comment lines) of the input has `#pragma: {pragma}`.
+    ///
+    /// Only the header is read, and the source is not copied.
+    pub fn has_pragma(&self, pragma: &str) -> bool {
+        let in_header = |line: &str| {
+            let line = line.trim();
+            line.is_empty() || line.starts_with('#')
+        };
+        let is_pragma = |line: &str| {
+            line.trim()
+                .trim_start_matches('#')
+                .trim()
+                .strip_prefix("pragma:")
+                .is_some_and(|p| p.trim() == pragma)
+        };
+        match &self.kind {
+            InputKind::File(path) => {
+                let Ok(file) = File::open(path) else {
+                    return false;
+                };
+                BufReader::new(file)
+                    .lines()
+                    .map_while(Result::ok)
+                    .take_while(|line| in_header(line))
+                    .any(|line| is_pragma(&line))
+            }
+            InputKind::Pipe(s) | InputKind::Str(s) => {
+                s.lines().take_while(|line| in_header(line)).any(is_pragma)
+            }
+            _ => false,
+        }
+    }
+
     pub fn read_non_dummy(&self) -> String {
         match &self.kind {
             InputKind::File(filename) => {
@@ -575,6 +613,7 @@ impl Input {
     /// 8.  `std/{path/to}/__init__.d.er`
     /// 9.  `site-packages/{path}/__pycache__/{to}.d.er`
     /// 10. `site-packages/{path/to}/__pycache__/__init__.d.er`
+    /// 11. Python stubs (see `resolve_pyi_path`)
     pub fn resolve_decl_path(&self, path: &Path) -> Option<PathBuf> {
         if let Ok(path) = self.resolve_local_decl(self.dir(), path) {
             return Some(path);
@@ -605,7 +644,60 @@ impl Input {
                 return Some(path);
             }
         }
-        None
+        self.resolve_pyi_path(path)
+    }
+
+    /// Searches for a Python stub (`{path/to}.pyi` or `{path/to}/__init__.pyi`).
+    ///
+    /// resolution order:
+    /// 1. `./{path/to}.pyi`
+    /// 2. `site-packages/{path}-stubs/{to}.pyi` (stub-only packages)
+    /// 3. `site-packages/{path/to}.pyi`
+    /// 4. `$ERG_TYPESHED_PATH/stdlib/{path/to}.pyi`
+    /// 5. `$ERG_TYPESHED_PATH/stubs/*/{path/to}.pyi`
+    pub fn resolve_pyi_path(&self, path: &Path) -> Option<PathBuf> {
+        if let Some(path) = Self::resolve_pyi_in(&self.dir(), path) {
+            return Some(path);
+        }
+        // relative imports are not searched in site-packages and typeshed
+        let Some(Component::Normal(first)) = path.components().next() else {
+            return None;
+        };
+        let rest = path.iter().skip(1).collect::<PathBuf>();
+        for site_packages in python_site_packages() {
+            let mut stubs = first.to_os_string();
+            stubs.push("-stubs");
+            if let Some(path) = Self::resolve_pyi_in(&site_packages.join(stubs), &rest) {
+                return Some(path);
+            }
+            if let Some(path) = Self::resolve_pyi_in(site_packages, path) {
+                return Some(path);
+            }
+        }
+        let typeshed = typeshed_path()?;
+        if let Some(path) = Self::resolve_pyi_in(&typeshed.join("stdlib"), path) {
+            return Some(path);
+        }
+        let dists = typeshed.join("stubs").read_dir().ok()?;
+        dists
+            .flatten()
+            .find_map(|dist| Self::resolve_pyi_in(&dist.path(), path))
+    }
+
+    /// `{root}/{path/to}.pyi` or `{root}/{path/to}/__init__.pyi`
+    fn resolve_pyi_in(root: &Path, path: &Path) -> Option<PathBuf> {
+        let dir = root.join(path);
+        let file = dir
+            .file_name()
+            .filter(|_| path.file_name().is_some())
+            .map(|name| {
+                let mut name = name.to_os_string();
+                name.push(".pyi");
+                dir.with_file_name(name)
+            });
+        file.and_then(|file| file.canonicalize().ok())
+            .or_else(|| dir.join("__init__.pyi").canonicalize().ok())
+            .map(normalize_path)
     }
 
     /// 1. `site-packages/{path/to}.d.er`
@@ -0,0 +1,188 @@
+//! A minimal JSON value for the machine-readable outputs (e.g. `erg dump`, `--timings-trace`).
+//!
+//! The keys of an object are kept in insertion order, so the output is stable.
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(n: u32) -> Self {
+        Self::Int(n as i64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Self {
+        Self::Int(n as i64)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Self::Str(s.to_string())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Self {
+        Self::Str(s)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(opt: Option<T>) -> Self {
+        opt.map_or(Self::Null, Into::into)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(v: Vec<T>) -> Self {
+        Self::Array(v.into_iter().map(Into::into).collect())
+    }
+}
+
+/// `{}` prints compactly, `{:#}` prints with indentation.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indent(f, if f.alternate() { Some(0) } else { None })
+    }
+}
+
+impl Json {
+    pub fn object(fields: impl IntoIterator<Item = (&'static str, Json)>) -> Self {
+        Self::Object(fields.into_iter().collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Self::Object(fields) => fields.iter().find(|(k, _)| *k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn fmt_indent(&self, f: &mut fmt::Formatter<'_>, level: Option<usize>) -> fmt::Result {
+        let newline =
+            |f: &mut fmt::Formatter<'_>, level: usize| write!(f, "\n{}", "  ".repeat(level));
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Int(n) => write!(f, "{n}"),
+            Self::Str(s) => write!(f, "\"{}\"", escape(s)),
+            Self::Array(elems) if elems.is_empty() => write!(f, "[]"),
+            Self::Array(elems) => {
+                write!(f, "[")?;
+                for (i, elem) in elems.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    if let Some(level) = level {
+                        newline(f, level + 1)?;
+                    }
+                    elem.fmt_indent(f, level.map(|l| l + 1))?;
+                }
+                if let Some(level) = level {
+                    newline(f, level)?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Self::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    if let Some(level) = level {
+                        newline(f, level + 1)?;
+                    }
+                    write!(f, "\"{}\":", escape(key))?;
+                    if level.is_some() {
+                        write!(f, " ")?;
+                    }
+                    value.fmt_indent(f, level.map(|l| l + 1))?;
+                }
+                if let Some(level) = level {
+                    newline(f, level)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Converts a value (e.g. an AST node) to `Json`.
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        self.as_ref().map_or(Json::Null, ToJson::to_json)
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Json {
+        self.as_ref().to_json()
+    }
+}
+
+/// Escapes `s` to be embedded in a JSON string literal.
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_display() {
+        let json = Json::object([
+            ("name", "a\"b\n".into()),
+            ("lines", vec![1u32, 2].into()),
+            ("t", Json::Null),
+            ("empty", Json::Array(vec![])),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            r#"{"name":"a\"b\n","lines":[1,2],"t":null,"empty":[]}"#
+        );
+        assert_eq!(
+            format!("{json:#}"),
+            "{\n  \"name\": \"a\\\"b\\n\",\n  \"lines\": [\n    1,\n    2\n  ],\n  \"t\": null,\n  \"empty\": []\n}"
+        );
+    }
+}
@@ -7,6 +7,7 @@ pub mod config;
 pub mod consts;
 pub mod datetime;
 pub mod dict;
+pub mod edition;
 pub mod env;
 pub mod erg_util;
 pub mod error;
@@ -14,6 +15,7 @@ pub mod fresh;
 pub mod fxhash;
 pub mod help_messages;
 pub mod io;
+pub mod json;
 pub mod lang;
 pub mod levenshtein;
 pub mod macros;
@@ -31,6 +33,7 @@ pub mod spawn;
 pub mod stdin;
 pub mod str;
 pub mod style;
+pub mod timings;
 pub mod traits;
 pub mod triple;
 pub mod tsort;
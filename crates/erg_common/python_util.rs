@@ -2,8 +2,9 @@
 //!
 //! CPythonを呼び出すためのユーティリティー
 use std::fs;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
 
 use crate::fn_name_full;
 use crate::pathutil::remove_verbatim;
@@ -548,6 +549,51 @@ pub const EXT_COMMON_ALIAS: [&str; 7] = [
     "tqdm",
     "urllib3",
 ];
+/// modules available on MicroPython (`u`-prefixed aliases are also available)
+pub const MICROPYTHON_MODS: [&str; 40] = [
+    "_thread",
+    "array",
+    "asyncio",
+    "binascii",
+    "bluetooth",
+    "btree",
+    "builtins",
+    "cmath",
+    "collections",
+    "cryptolib",
+    "deflate",
+    "errno",
+    "framebuf",
+    "gc",
+    "hashlib",
+    "heapq",
+    "io",
+    "json",
+    "machine",
+    "math",
+    "micropython",
+    "neopixel",
+    "network",
+    "os",
+    "platform",
+    "random",
+    "re",
+    "select",
+    "socket",
+    "ssl",
+    "struct",
+    "sys",
+    "time",
+    "uctypes",
+    "vfs",
+    "zlib",
+    "esp",
+    "esp32",
+    "pyb",
+    "rp2",
+];
+/// builtin functions of CPython that MicroPython does not have
+pub const MICROPYTHON_UNAVAILABLE_BUILTINS: [&str; 3] = ["ascii", "exit", "quit"];
 
 pub fn opt_which_python() -> Result<String, String> {
     let (cmd, python) = if cfg!(windows) {
@@ -764,10 +810,14 @@ pub fn exec_pyc<S: Into<String>, T: Into<Stdio>>(
             .spawn()
             .expect("cannot execute python")
     } else {
-        let exec_command = format!("{command} {} {}", file.into(), argv.join(" "));
+        // the file and the arguments are passed as the positional parameters (`$@`) to keep them unsplit
+        let exec_command = format!("{command} \"$@\"");
         Command::new("sh")
             .arg("-c")
             .arg(exec_command)
+            .arg("sh")
+            .arg(file.into())
+            .args(argv)
             .stdout(stdout)
             .spawn()
             .expect("cannot execute python")
@@ -816,6 +866,34 @@ pub fn exec_py(file: &str) -> Option<i32> {
     child.wait().expect("python doesn't work").code()
 }
 
+/// Executes `code` with `input` as the standard input and returns its output.
+///
+/// The code is not passed over a shell to avoid quoting problems.
+pub fn eval_py_with_input(
+    py_command: Option<&str>,
+    code: &str,
+    args: &[&str],
+    input: &str,
+) -> io::Result<Output> {
+    let command = match py_command {
+        Some(command) => command.to_string(),
+        None => opt_which_python().map_err(|err| io::Error::new(io::ErrorKind::NotFound, err))?,
+    };
+    let mut child = Command::new(command)
+        .arg("-c")
+        .arg(code)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // `stdin` is closed when dropped
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    child.wait_with_output()
+}
+
 pub fn env_spawn_py(code: &str) {
     if cfg!(windows) {
         Command::new(which_python())
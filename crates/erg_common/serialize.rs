@@ -59,6 +59,7 @@ pub enum DataTypePrefix {
     True = b'T',       // 0x54
     False = b'F',      // 0x46
     None = b'N',       // 0x4E
+    Ellipsis = b'.',   // 0x2E
     StopIter = b'S',   // 0x53
     Ref = b'r',
     /* unsized objects (ref counted) */
@@ -70,6 +71,8 @@ pub enum DataTypePrefix {
     Interned = b't', // 0x74 + len + payload
     SmallTuple = b')', // 0x29 + len:  u8 + payload
     Tuple = b'(', // 0x28 + len:  u32 + payload
+    Dict = b'{', // 0x7B + (key, value)* + Null
+    Null = b'0', // 0x30 (the end of a dict)
     Code = b'c' + 0x80, // 0x63 (0xE3)
     /* Erg specific prefix */
     Builtin = b'b', // 0x62 + str
@@ -91,6 +94,7 @@ impl From<u8> for DataTypePrefix {
             'T' => Self::True,
             'F' => Self::False,
             'N' => Self::None,
+            '.' => Self::Ellipsis,
             'S' => Self::StopIter,
             's' | '\u{00F3}' => Self::Str,
             'Z' | '\u{00DA}' => Self::ShortAsciiInterned,
@@ -99,6 +103,8 @@ impl From<u8> for DataTypePrefix {
             't' => Self::Interned,
             '(' | '\u{00A8}' => Self::Tuple,
             ')' | '\u{00A9}' => Self::SmallTuple,
+            '{' | '\u{00FB}' => Self::Dict,
+            '0' => Self::Null,
             'c' | '\u{00E3}' => Self::Code,
             'b' => Self::Builtin,
             'n' => Self::Nat,
@@ -4,21 +4,113 @@ use std::sync::OnceLock;
 use std::io::{stdin, BufRead, BufReader};
 
 #[cfg(feature = "full-repl")]
-use crossterm::{
-    cursor::MoveToColumn,
-    event::{read, Event, KeyCode, KeyEvent, KeyModifiers},
-    execute,
-    style::Print,
-    terminal::{disable_raw_mode, enable_raw_mode},
-    terminal::{Clear, ClearType},
-};
+use rustyline::completion::Completer;
 #[cfg(feature = "full-repl")]
-use std::process::Command;
+use rustyline::error::ReadlineError;
 #[cfg(feature = "full-repl")]
-use std::process::Output;
+use rustyline::history::DefaultHistory;
+#[cfg(feature = "full-repl")]
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+#[cfg(feature = "full-repl")]
+use rustyline::{Editor, Helper, Highlighter, Hinter};
 
+#[cfg(feature = "full-repl")]
+use crate::env::erg_path;
 use crate::shared::Shared;
 
+/// The number of the brackets (`(`, `[`, `{`) that are not closed in `src`.
+/// The brackets in string literals and comments are ignored.
+pub fn unclosed_brackets(src: &str) -> usize {
+    let mut depth = 0usize;
+    let mut chars = src.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '"' => {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' | '\n' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '#' => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    depth
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '!'
+}
+
+/// The completions of the word before `pos` in `line`, and the start position of the word
+pub fn complete_word<'a>(words: &'a [String], line: &str, pos: usize) -> (usize, Vec<&'a String>) {
+    let start = line[..pos]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word_char(*c))
+        .last()
+        .map_or(pos, |(i, _)| i);
+    let word = &line[start..pos];
+    let mut candidates = words
+        .iter()
+        .filter(|candidate| candidate.starts_with(word) && *candidate != word)
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates.dedup();
+    (start, candidates)
+}
+
+/// Completes the names given by `Runnable::completion_words`,
+/// and continues the input to the next line while the brackets are not closed.
+#[cfg(feature = "full-repl")]
+#[derive(Debug, Default, Helper, Hinter, Highlighter)]
+struct ReplHelper {
+    words: Vec<String>,
+}
+
+#[cfg(feature = "full-repl")]
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &rustyline::Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let (start, candidates) = complete_word(&self.words, line, pos);
+        // Tab at the beginning of a word inserts an indentation
+        if start == pos {
+            return Ok((pos, vec!["    ".to_string()]));
+        }
+        Ok((start, candidates.into_iter().cloned().collect()))
+    }
+}
+
+#[cfg(feature = "full-repl")]
+impl Validator for ReplHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        if unclosed_brackets(ctx.input()) > 0 {
+            Ok(ValidationResult::Incomplete)
+        } else {
+            Ok(ValidationResult::Valid(None))
+        }
+    }
+}
+
 /// e.g.
 /// ```erg
 /// >>> print! 1
@@ -35,58 +127,29 @@ pub struct StdinReader {
     block_begin: usize,
     lineno: usize,
     buf: Vec<String>,
+    /// The prompt printed by the line editor (`ps1`, or `ps2` with the indentation)
     #[cfg(feature = "full-repl")]
-    history_input_position: usize,
-    indent: u16,
+    prompt: String,
+    /// Created at the first input, so that the history file is not touched if the REPL is not used
+    #[cfg(feature = "full-repl")]
+    editor: Option<Editor<ReplHelper, DefaultHistory>>,
 }
 
 impl StdinReader {
-    #[cfg(all(feature = "full-repl", target_os = "linux"))]
-    fn access_clipboard() -> Option<Output> {
-        if let Ok(str) = std::fs::read("/proc/sys/kernel/osrelease") {
-            if let Ok(str) = std::str::from_utf8(&str) {
-                if str.to_ascii_lowercase().contains("microsoft") {
-                    return Some(
-                        Command::new("powershell")
-                            .args(["get-clipboard"])
-                            .output()
-                            .expect("failed to get clipboard"),
-                    );
-                }
-            }
-        }
-        match Command::new("xsel")
-            .args(["--output", "--clipboard"])
-            .output()
-        {
-            Ok(output) => Some(output),
-            Err(_) => {
-                execute!(
-                    std::io::stdout(),
-                    Print("You need to install `xsel` to use the paste feature on Linux desktop"),
-                )
-                .unwrap();
-                None
-            }
-        }
-    }
-    #[cfg(all(feature = "full-repl", target_os = "macos"))]
-    fn access_clipboard() -> Option<Output> {
-        Some(
-            Command::new("pbpast")
-                .output()
-                .expect("failed to get clipboard"),
-        )
+    #[cfg(feature = "full-repl")]
+    fn history_path() -> std::path::PathBuf {
+        erg_path().join("repl_history")
     }
 
-    #[cfg(all(feature = "full-repl", target_os = "windows"))]
-    fn access_clipboard() -> Option<Output> {
-        Some(
-            Command::new("powershell")
-                .args(["get-clipboard"])
-                .output()
-                .expect("failed to get clipboard"),
-        )
+    #[cfg(feature = "full-repl")]
+    fn editor(&mut self) -> &mut Editor<ReplHelper, DefaultHistory> {
+        self.editor.get_or_insert_with(|| {
+            let mut editor = Editor::new().expect("failed to initialize the line editor");
+            editor.set_helper(Some(ReplHelper::default()));
+            // the history file does not exist at the first run
+            let _ = editor.load_history(&Self::history_path());
+            editor
+        })
     }
 
     #[cfg(not(feature = "full-repl"))]
@@ -100,153 +163,25 @@ impl StdinReader {
         self.buf.last().cloned().unwrap_or_default()
     }
 
+    /// The input continued by the unclosed brackets is returned at once (with the newlines).
     #[cfg(feature = "full-repl")]
     pub fn read(&mut self) -> String {
-        enable_raw_mode().unwrap();
-        let mut output = std::io::stdout();
-        let mut line = String::new();
-        self.input(&mut line).unwrap();
-        disable_raw_mode().unwrap();
-        execute!(output, MoveToColumn(0)).unwrap();
-        self.lineno += 1;
-        self.buf.push(line);
-        self.buf.last().cloned().unwrap_or_default()
-    }
-
-    #[cfg(feature = "full-repl")]
-    fn input(&mut self, line: &mut String) -> std::io::Result<()> {
-        let mut position = 0;
-        let mut consult_history = false;
-        let mut stdout = std::io::stdout();
-        while let Event::Key(KeyEvent {
-            code, modifiers, ..
-        }) = read()?
-        {
-            consult_history = false;
-            match (code, modifiers) {
-                (KeyCode::Char('z'), KeyModifiers::CONTROL)
-                | (KeyCode::Char('d'), KeyModifiers::CONTROL) => {
-                    println!();
-                    line.clear();
-                    line.push_str(":exit");
-                    return Ok(());
-                }
-                (KeyCode::Char('v'), KeyModifiers::CONTROL) => {
-                    let output = match Self::access_clipboard() {
-                        None => {
-                            continue;
-                        }
-                        Some(output) => output,
-                    };
-                    let clipboard = {
-                        let this = String::from_utf8_lossy(&output.stdout).to_string();
-                        this.trim_matches(|c: char| c.is_whitespace())
-                            .to_string()
-                            .replace(['\n', '\r'], "")
-                            .replace(|c: char| c.len_utf8() >= 2, "")
-                    };
-                    line.insert_str(position, &clipboard);
-                    position += clipboard.len();
-                }
-                (_, KeyModifiers::CONTROL) => continue,
-                (KeyCode::Tab, _) => {
-                    line.insert_str(position, "    ");
-                    position += 4;
-                }
-                (KeyCode::Home, _) => {
-                    position = 0;
-                }
-                (KeyCode::End, _) => {
-                    position = line.len();
-                }
-                (KeyCode::Backspace, _) => {
-                    if position == 0 {
-                        continue;
-                    }
-                    line.remove(position - 1);
-                    position -= 1;
-                }
-                (KeyCode::Delete, _) => {
-                    if position == line.len() {
-                        continue;
-                    }
-                    line.remove(position);
-                }
-                (KeyCode::Up, _) => {
-                    consult_history = true;
-                    if self.history_input_position == 0 {
-                        continue;
-                    }
-                    self.history_input_position -= 1;
-                    execute!(stdout, MoveToColumn(4), Clear(ClearType::UntilNewLine))?;
-                    if let Some(l) = self.buf.get(self.history_input_position) {
-                        position = l.len();
-                        line.clear();
-                        line.push_str(l);
-                    }
-                }
-                (KeyCode::Down, _) => {
-                    if self.history_input_position == self.buf.len() {
-                        continue;
-                    }
-                    if self.history_input_position == self.buf.len() - 1 {
-                        *line = "".to_string();
-                        position = 0;
-                        self.history_input_position += 1;
-                        execute!(
-                            stdout,
-                            MoveToColumn(4),
-                            Clear(ClearType::UntilNewLine),
-                            MoveToColumn(self.indent * 4),
-                            Print(line.to_owned()),
-                            MoveToColumn(self.indent * 4 + position as u16)
-                        )?;
-                        continue;
-                    }
-                    self.history_input_position += 1;
-                    execute!(stdout, MoveToColumn(4), Clear(ClearType::UntilNewLine))?;
-                    if let Some(l) = self.buf.get(self.history_input_position) {
-                        position = l.len();
-                        line.clear();
-                        line.push_str(l);
-                    }
-                }
-                (KeyCode::Left, _) => {
-                    if position == 0 {
-                        continue;
-                    }
-                    position -= 1;
-                }
-                (KeyCode::Right, _) => {
-                    if position == line.len() {
-                        continue;
-                    }
-                    position += 1;
-                }
-                (KeyCode::Enter, _) => {
-                    println!();
-                    break;
-                }
-                // TODO: check a full-width char and possible to insert
-                (KeyCode::Char(c), _) if c.len_utf8() < 2 => {
-                    line.insert(position, c);
-                    position += 1;
-                }
-                _ => {}
-            }
-            execute!(
-                stdout,
-                MoveToColumn(4),
-                Clear(ClearType::UntilNewLine),
-                MoveToColumn(self.indent * 4),
-                Print(line.to_owned()),
-                MoveToColumn(self.indent * 4 + position as u16)
-            )?;
+        let prompt = self.prompt.clone();
+        let input = match self.editor().readline(&prompt) {
+            Ok(input) => input,
+            Err(ReadlineError::Interrupted) => String::new(),
+            Err(_) => ":exit".to_string(),
+        };
+        if !input.trim().is_empty() {
+            let editor = self.editor();
+            let _ = editor.add_history_entry(input.as_str());
+            let _ = editor.append_history(&Self::history_path());
         }
-        if !consult_history {
-            self.history_input_position = self.buf.len() + 1;
+        for line in input.split('\n') {
+            self.lineno += 1;
+            self.buf.push(line.to_string());
         }
-        Ok(())
+        input
     }
 
     pub fn reread(&self) -> String {
@@ -260,6 +195,22 @@ impl StdinReader {
     pub fn last_line(&mut self) -> Option<&mut String> {
         self.buf.last_mut()
     }
+
+    #[allow(unused_variables)]
+    pub fn set_prompt(&mut self, prompt: &str) {
+        #[cfg(feature = "full-repl")]
+        {
+            self.prompt = prompt.to_string();
+        }
+    }
+
+    #[allow(unused_variables)]
+    pub fn set_completion_words(&mut self, words: Vec<String>) {
+        #[cfg(feature = "full-repl")]
+        if let Some(helper) = self.editor().helper_mut() {
+            helper.words = words;
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -275,8 +226,9 @@ impl GlobalStdin {
                 lineno: 1,
                 buf: vec![],
                 #[cfg(feature = "full-repl")]
-                history_input_position: 1,
-                indent: 1,
+                prompt: String::new(),
+                #[cfg(feature = "full-repl")]
+                editor: None,
             })
         })
     }
@@ -305,8 +257,12 @@ impl GlobalStdin {
         self.get().borrow_mut().block_begin = n;
     }
 
-    pub fn set_indent(&'static self, n: usize) {
-        self.get().borrow_mut().indent = n as u16;
+    pub fn set_prompt(&'static self, prompt: &str) {
+        self.get().borrow_mut().set_prompt(prompt);
+    }
+
+    pub fn set_completion_words(&'static self, words: Vec<String>) {
+        self.get().borrow_mut().set_completion_words(words);
     }
 
     pub fn insert_whitespace(&'static self, whitespace: &str) {
@@ -315,3 +271,30 @@ impl GlobalStdin {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unclosed_brackets() {
+        assert_eq!(unclosed_brackets("f(1, [2"), 2);
+        assert_eq!(unclosed_brackets("f(1, [2])"), 0);
+        assert_eq!(unclosed_brackets("print! \"(\" # ["), 0);
+        assert_eq!(unclosed_brackets("x = {\n    .a = \"\\\"(\"\n"), 1);
+        assert_eq!(unclosed_brackets(")("), 1);
+    }
+
+    #[test]
+    fn test_complete_word() {
+        let words = ["print!", "pow", "int", "print!"].map(String::from);
+        let (start, candidates) = complete_word(&words, "x = pr 1", 6);
+        assert_eq!((start, candidates), (4, vec![&words[0]]));
+        let (start, candidates) = complete_word(&words, "p", 1);
+        assert_eq!((start, candidates), (0, vec![&words[1], &words[0]]));
+        assert_eq!(
+            complete_word(&words, "f ", 2),
+            (2, vec![&words[2], &words[1], &words[0]])
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use std::borrow::{Borrow, Cow};
 use std::fmt;
 use std::hash::{Hash, Hasher};
-use std::ops::{Add, Deref};
+use std::ops::{Add, Deref, Range};
 
 pub type ArcStr = std::sync::Arc<str>;
 
@@ -12,12 +12,18 @@ pub type ArcStr = std::sync::Arc<str>;
 pub enum Str {
     Rc(ArcStr),
     Static(&'static str),
+    /// A range of a shared string (e.g. a token of the source code), which is not copied
+    Slice(ArcStr, u32, u32),
 }
 
 impl PartialEq for Str {
     #[inline]
     fn eq(&self, other: &Str) -> bool {
-        self[..] == other[..]
+        match (self, other) {
+            // fast path for interned strings
+            (Str::Rc(l), Str::Rc(r)) if ArcStr::ptr_eq(l, r) => true,
+            _ => self[..] == other[..],
+        }
     }
 }
 
@@ -34,6 +40,7 @@ impl Hash for Str {
         match self {
             Str::Rc(s) => s[..].hash(state),
             Str::Static(s) => (*s).hash(state),
+            Str::Slice(..) => self[..].hash(state),
         }
     }
 }
@@ -43,6 +50,7 @@ impl fmt::Display for Str {
         match self {
             Str::Rc(s) => write!(f, "{s}"),
             Str::Static(s) => write!(f, "{s}"),
+            Str::Slice(..) => write!(f, "{}", &self[..]),
         }
     }
 }
@@ -65,7 +73,7 @@ impl<'a> From<Str> for Cow<'a, str> {
     fn from(s: Str) -> Self {
         match s {
             Str::Static(s) => Cow::Borrowed(s),
-            Str::Rc(s) => Cow::Owned(s.to_string()),
+            other => Cow::Owned(other.to_string()),
         }
     }
 }
@@ -110,10 +118,7 @@ impl From<ArcStr> for Str {
 impl From<&Str> for Str {
     #[inline]
     fn from(s: &Str) -> Self {
-        match s {
-            Str::Rc(s) => Str::Rc(s.clone()),
-            Str::Static(s) => Str::Static(s),
-        }
+        s.clone()
     }
 }
 
@@ -140,6 +145,7 @@ impl Borrow<str> for Str {
         match self {
             Str::Rc(s) => &s[..],
             Str::Static(s) => s,
+            Str::Slice(s, start, end) => &s[*start as usize..*end as usize],
         }
     }
 }
@@ -159,10 +165,30 @@ impl Str {
         Str::Rc(s.into())
     }
 
+    /// `src[range]` without copying it. `range` must be on char boundaries.
+    pub fn slice(src: &ArcStr, range: Range<usize>) -> Self {
+        debug_assert!(src.is_char_boundary(range.start) && src.is_char_boundary(range.end));
+        Str::Slice(src.clone(), range.start as u32, range.end as u32)
+    }
+
+    /// The range of `self` in the shared string, if `self` is made by `Str::slice`.
+    pub fn slice_range(&self) -> Option<Range<usize>> {
+        match self {
+            Str::Slice(_, start, end) => Some(*start as usize..*end as usize),
+            _ => None,
+        }
+    }
+
+    /// Returns the interned `Str` if `s` is a builtin name, otherwise a new `Str` (see `erg_common::cache::intern`).
+    pub fn intern(s: &str) -> Self {
+        crate::cache::intern(s)
+    }
+
     pub fn into_rc(self) -> ArcStr {
         match self {
             Str::Rc(s) => s,
             Str::Static(s) => ArcStr::from(s),
+            Str::Slice(..) => ArcStr::from(&self[..]),
         }
     }
 
@@ -276,4 +302,29 @@ mod tests {
             vec!["aa", "bb", "ff"]
         );
     }
+    #[test]
+    fn test_intern() {
+        let a = Str::intern("Int");
+        let b = Str::intern(&String::from("Int"));
+        match (&a, &b) {
+            (Str::Rc(a), Str::Rc(b)) => assert!(ArcStr::ptr_eq(a, b)),
+            _ => unreachable!(),
+        }
+        assert_eq!(a, Str::rc("Int"));
+        assert_ne!(a, Str::intern("Str"));
+        // user-defined names are not retained by the interner
+        assert!(crate::cache::get_interned("foo").is_none());
+        assert_eq!(Str::intern("foo"), Str::rc("foo"));
+    }
+
+    #[test]
+    fn test_slice() {
+        let src = ArcStr::from("x = \"ああ\"");
+        let s = Str::slice(&src, 4..12);
+        assert_eq!(&s[..], "\"ああ\"");
+        assert_eq!(s.slice_range(), Some(4..12));
+        assert_eq!(s, Str::rc("\"ああ\""));
+        assert_eq!(s.clone().into_rc(), ArcStr::from("\"ああ\""));
+        assert_eq!(Str::rc("x").slice_range(), None);
+    }
 }
@@ -0,0 +1,216 @@
+//! Collects the elapsed time of each compilation phase (`--timings`).
+use std::fmt::{self, Write as _};
+use std::str::FromStr;
+use std::sync::atomic::{self, AtomicBool, AtomicU64};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::dict::Dict;
+use crate::json::escape;
+
+/// The output format of `--timings`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TimingsFormat {
+    /// A summary table (printed to stderr)
+    #[default]
+    Table,
+    /// A JSON file of the Chrome trace event format (viewable in `chrome://tracing` or Perfetto)
+    Chrome,
+}
+
+impl FromStr for TimingsFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Self::Table),
+            "chrome" | "json" => Ok(Self::Chrome),
+            other => Err(format!("unknown timings format: {other}")),
+        }
+    }
+}
+
+impl fmt::Display for TimingsFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Table => write!(f, "table"),
+            Self::Chrome => write!(f, "chrome"),
+        }
+    }
+}
+
+/// The file to which the Chrome trace is written
+pub const TRACE_FILE: &str = "erg-timings.json";
+
+#[derive(Debug, Clone)]
+pub struct TimingEvent {
+    pub module: String,
+    pub phase: &'static str,
+    /// The time elapsed from `enable()` to the start of the phase
+    pub start: Duration,
+    pub duration: Duration,
+    pub thread: u64,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+static EVENTS: Mutex<Vec<TimingEvent>> = Mutex::new(Vec::new());
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static THREAD: u64 = NEXT_THREAD.fetch_add(1, atomic::Ordering::Relaxed);
+}
+
+pub fn enable() {
+    EPOCH.get_or_init(Instant::now);
+    ENABLED.store(true, atomic::Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(atomic::Ordering::Relaxed)
+}
+
+/// Runs `f` and records its elapsed time as `phase` of `module` (if `--timings` is enabled).
+/// Phases may be nested (e.g. an imported module is analyzed while lowering).
+pub fn measure<T>(module: &str, phase: &'static str, f: impl FnOnce() -> T) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let epoch = *EPOCH.get_or_init(Instant::now);
+    let start = Instant::now();
+    let res = f();
+    let event = TimingEvent {
+        module: module.to_string(),
+        phase,
+        start: start.duration_since(epoch),
+        duration: start.elapsed(),
+        thread: THREAD.with(|id| *id),
+    };
+    EVENTS.lock().unwrap().push(event);
+    res
+}
+
+pub fn events() -> Vec<TimingEvent> {
+    EVENTS.lock().unwrap().clone()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The total time of each phase, and of each module.
+/// Note that the time of a phase includes the time of the nested phases (e.g. the analysis of the imported modules).
+pub fn summary_table(events: &[TimingEvent]) -> String {
+    // in order of appearance
+    let mut phases: Vec<(&str, Duration, usize)> = vec![];
+    let mut modules: Vec<(&str, Dict<&str, Duration>)> = vec![];
+    for event in events {
+        match phases.iter_mut().find(|(phase, ..)| *phase == event.phase) {
+            Some((_, total, count)) => {
+                *total += event.duration;
+                *count += 1;
+            }
+            None => phases.push((event.phase, event.duration, 1)),
+        }
+        let idx = match modules
+            .iter()
+            .position(|(module, _)| *module == event.module)
+        {
+            Some(idx) => idx,
+            None => {
+                modules.push((&event.module, Dict::new()));
+                modules.len() - 1
+            }
+        };
+        *modules[idx].1.entry(event.phase).or_default() += event.duration;
+    }
+    let mut table = String::new();
+    let _ = writeln!(table, "{:<20} {:>12} {:>8}", "phase", "time (ms)", "count");
+    for (phase, total, count) in phases.iter() {
+        let _ = writeln!(table, "{phase:<20} {:>12.3} {count:>8}", millis(*total));
+    }
+    table.push('\n');
+    let _ = write!(table, "{:<20}", "module");
+    for (phase, ..) in phases.iter() {
+        let _ = write!(table, " {phase:>16}");
+    }
+    table.push('\n');
+    for (module, times) in modules.iter() {
+        let _ = write!(table, "{module:<20}");
+        for (phase, ..) in phases.iter() {
+            match times.get(phase) {
+                Some(duration) => {
+                    let _ = write!(table, " {:>16.3}", millis(*duration));
+                }
+                None => {
+                    let _ = write!(table, " {:>16}", "-");
+                }
+            }
+        }
+        table.push('\n');
+    }
+    table
+}
+
+/// The complete events (`"ph": "X"`) of the Chrome trace event format
+pub fn chrome_trace(events: &[TimingEvent]) -> String {
+    let events = events
+        .iter()
+        .map(|event| {
+            format!(
+                "{{\"name\": \"{}\", \"cat\": \"{}\", \"ph\": \"X\", \"ts\": {}, \"dur\": {}, \"pid\": 0, \"tid\": {}, \"args\": {{\"module\": \"{}\"}}}}",
+                escape(&format!("{} ({})", event.phase, event.module)),
+                event.phase,
+                event.start.as_micros(),
+                event.duration.as_micros(),
+                event.thread,
+                escape(&event.module),
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("{{\"traceEvents\": [\n{}\n]}}\n", events.join(",\n"))
+}
+
+/// Reports the collected timings in `format`.
+pub fn report(format: TimingsFormat) {
+    let events = events();
+    match format {
+        TimingsFormat::Table => eprint!("{}", summary_table(&events)),
+        TimingsFormat::Chrome => match std::fs::write(TRACE_FILE, chrome_trace(&events)) {
+            Ok(()) => eprintln!("the timings are written to {TRACE_FILE}"),
+            Err(err) => eprintln!("failed to write the timings to {TRACE_FILE}: {err}"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(module: &str, phase: &'static str, start: u64, duration: u64) -> TimingEvent {
+        TimingEvent {
+            module: module.to_string(),
+            phase,
+            start: Duration::from_millis(start),
+            duration: Duration::from_millis(duration),
+            thread: 0,
+        }
+    }
+
+    #[test]
+    fn test_timings_report() {
+        let events = [
+            event("main", "parse", 0, 2),
+            event("foo", "parse", 3, 1),
+            event("main", "lower", 2, 5),
+        ];
+        let table = summary_table(&events);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert!(lines[1].starts_with("parse") && lines[1].contains("3.000"));
+        assert!(lines[2].starts_with("lower") && lines[2].contains("5.000"));
+        assert!(lines[6].starts_with("foo") && lines[6].ends_with('-'));
+        let trace = chrome_trace(&events);
+        assert!(trace.starts_with("{\"traceEvents\": ["));
+        assert!(trace.contains("\"name\": \"lower (main)\", \"cat\": \"lower\", \"ph\": \"X\", \"ts\": 2000, \"dur\": 5000"));
+        assert_eq!("chrome".parse(), Ok(TimingsFormat::Chrome));
+    }
+}
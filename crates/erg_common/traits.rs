@@ -11,8 +11,10 @@ use std::slice::{Iter, IterMut};
 
 use crate::config::ErgConfig;
 use crate::consts::{BUILD_DATE, GIT_HASH_SHORT, SEMVER};
-use crate::error::{ErrorDisplay, ErrorKind, Location, MultiErrorDisplay};
+use crate::error::{set_codes_only, ErrorDisplay, ErrorKind, Location, MultiErrorDisplay};
 use crate::io::{Input, InputKind};
+use crate::stdin::unclosed_brackets;
+use crate::timings;
 use crate::{addr_eq, chomp, log, switch_unreachable};
 
 pub trait DequeStream<T>: Sized {
@@ -518,6 +520,16 @@ pub enum BlockKind {
     None,         // one line
 }
 
+impl BlockKind {
+    /// Whether the following lines are indented as a block
+    pub const fn is_block(&self) -> bool {
+        matches!(
+            self,
+            Self::Assignment | Self::ClassPriv | Self::ClassPub | Self::ColonCall | Self::Lambda
+        )
+    }
+}
+
 pub fn from_str(bk: &str) -> BlockKind {
     match bk {
         "Assignment" => BlockKind::Assignment,
@@ -534,6 +546,93 @@ pub fn from_str(bk: &str) -> BlockKind {
     }
 }
 
+/// Guesses what follows the line `src` (used for the auto-indentation of the REPL and ELS)
+pub fn expect_block(src: &str) -> BlockKind {
+    let multi_line_str = "\"\"\"";
+    if src.contains(multi_line_str) && src.rfind(multi_line_str) == src.find(multi_line_str) {
+        return BlockKind::MultiLineStr;
+    }
+    if src.trim_start().starts_with('@') {
+        return BlockKind::AtMark;
+    }
+    if src.ends_with("do!:") && !src.starts_with("do!:") {
+        return BlockKind::Lambda;
+    }
+    if src.ends_with("do:") && !src.starts_with("do:") {
+        return BlockKind::Lambda;
+    }
+    if src.ends_with(':') && !src.starts_with(':') {
+        return BlockKind::Lambda;
+    }
+    if src.ends_with('=') && !src.starts_with('=') {
+        return BlockKind::Assignment;
+    }
+    if src.ends_with('.') && !src.starts_with('.') {
+        return BlockKind::ClassPub;
+    }
+    if src.ends_with("::") && !src.starts_with("::") {
+        return BlockKind::ClassPriv;
+    }
+    if src.ends_with("=>") && !src.starts_with("=>") {
+        return BlockKind::Lambda;
+    }
+    if src.ends_with("->") && !src.starts_with("->") {
+        return BlockKind::Lambda;
+    }
+    if src.contains("Class") || src.contains("Inherit") {
+        return BlockKind::ClassDef;
+    }
+    BlockKind::None
+}
+
+/// Splits the script `src` into the top-level chunks (with the line numbers where they start).
+/// A non-indented line starts a new chunk unless the previous chunk continues
+/// (unclosed brackets, multi-line strings, decorators, and the clauses of a function definition).
+pub fn split_chunks(src: &str) -> Vec<(usize, String)> {
+    fn is_code(line: &str) -> bool {
+        let line = line.trim_start();
+        !line.is_empty() && !line.starts_with('#')
+    }
+    // e.g. `f(x, 0) = ...` => `f`
+    fn clause_name(line: &str) -> Option<&str> {
+        let end = line.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '!'))?;
+        (end > 0 && line[end..].starts_with('(')).then_some(&line[..end])
+    }
+    let mut chunks = vec![];
+    let mut chunk = String::new();
+    let mut begin = 0;
+    for (i, line) in src.lines().enumerate() {
+        let continued = unclosed_brackets(&chunk) > 0
+            || chunk.matches("\"\"\"").count() % 2 == 1
+            || chunk
+                .lines()
+                .rev()
+                .find(|line| is_code(line))
+                .is_some_and(|line| line.starts_with('@'))
+            || clause_name(line).is_some_and(|name| clause_name(&chunk) == Some(name));
+        if !chunk.is_empty()
+            && is_code(line)
+            && !line.starts_with(char::is_whitespace)
+            && !continued
+        {
+            chunks.push((begin, mem::take(&mut chunk).trim_end().to_string()));
+        }
+        if chunk.is_empty() {
+            // the comments and blank lines before a chunk are skipped
+            if !is_code(line) {
+                continue;
+            }
+            begin = i + 1;
+        }
+        chunk.push_str(line);
+        chunk.push('\n');
+    }
+    if !chunk.is_empty() {
+        chunks.push((begin, chunk.trim_end().to_string()));
+    }
+    chunks
+}
+
 pub struct VirtualMachine {
     pub codes: String,
     pub now_block: Vec<BlockKind>,
@@ -642,6 +741,47 @@ impl ExitStatus {
     }
 }
 
+/// Evaluates the chunks of the script `path` (see `split_chunks`) one by one,
+/// so that a chunk with errors does not prevent the following chunks from being evaluated.
+/// Returns the number of the errors, or `None` if the script exits the REPL.
+fn load_script<R: Runnable>(
+    instance: &mut R,
+    path: &str,
+    output: &mut impl Write,
+) -> Option<usize> {
+    let src = match std::fs::read_to_string(path) {
+        Ok(src) => src,
+        Err(err) => {
+            writeln!(output, "cannot load {path}: {err}").unwrap();
+            output.flush().unwrap();
+            return Some(1);
+        }
+    };
+    let mut num_errors = 0;
+    for (lineno, chunk) in split_chunks(&src) {
+        match instance.eval(chunk) {
+            Ok(out) if out.is_empty() => {}
+            Ok(out) => writeln!(output, "{out}").unwrap(),
+            Err(errs) => {
+                if errs
+                    .first()
+                    .is_some_and(|e| e.core().kind == ErrorKind::SystemExit)
+                {
+                    return None;
+                }
+                writeln!(output, "{path}:{lineno}: failed to evaluate the chunk").unwrap();
+                output.flush().unwrap();
+                num_errors += errs.len();
+                errs.write_all_stderr();
+            }
+        }
+        instance.clear();
+    }
+    writeln!(output, "loaded {path}").unwrap();
+    output.flush().unwrap();
+    Some(num_errors)
+}
+
 /// This trait implements REPL (Read-Eval-Print-Loop) automatically
 /// The `exec` method is called for file input, etc.
 pub trait Runnable: Sized + Default {
@@ -658,42 +798,13 @@ pub trait Runnable: Sized + Default {
     fn clear(&mut self);
     fn eval(&mut self, src: String) -> Result<String, Self::Errs>;
     fn exec(&mut self) -> Result<ExitStatus, Self::Errs>;
+    /// Roll back the last successful `eval` (called when the `:undo` command is received).
+    /// Returns `false` if there is nothing to undo.
+    fn undo(&mut self) -> bool {
+        false
+    }
     fn expect_block(&self, src: &str) -> BlockKind {
-        let multi_line_str = "\"\"\"";
-        if src.contains(multi_line_str) && src.rfind(multi_line_str) == src.find(multi_line_str) {
-            return BlockKind::MultiLineStr;
-        }
-        if src.trim_start().starts_with('@') {
-            return BlockKind::AtMark;
-        }
-        if src.ends_with("do!:") && !src.starts_with("do!:") {
-            return BlockKind::Lambda;
-        }
-        if src.ends_with("do:") && !src.starts_with("do:") {
-            return BlockKind::Lambda;
-        }
-        if src.ends_with(':') && !src.starts_with(':') {
-            return BlockKind::Lambda;
-        }
-        if src.ends_with('=') && !src.starts_with('=') {
-            return BlockKind::Assignment;
-        }
-        if src.ends_with('.') && !src.starts_with('.') {
-            return BlockKind::ClassPub;
-        }
-        if src.ends_with("::") && !src.starts_with("::") {
-            return BlockKind::ClassPriv;
-        }
-        if src.ends_with("=>") && !src.starts_with("=>") {
-            return BlockKind::Lambda;
-        }
-        if src.ends_with("->") && !src.starts_with("->") {
-            return BlockKind::Lambda;
-        }
-        if src.contains("Class") || src.contains("Inherit") {
-            return BlockKind::ClassDef;
-        }
-        BlockKind::None
+        expect_block(src)
     }
     fn input(&self) -> &Input {
         &self.cfg().input
@@ -717,6 +828,18 @@ pub trait Runnable: Sized + Default {
     fn ps2(&self) -> String {
         self.cfg().ps2.to_string()
     }
+    /// The sources of the evaluations accepted so far, except the rolled back ones (`:save` writes them out as a script)
+    fn accepted_inputs(&self) -> &[String] {
+        &[]
+    }
+    /// The names completed in the REPL (with the `full-repl` feature), e.g. the variables defined so far
+    fn completion_words(&self) -> Vec<String> {
+        vec![]
+    }
+    /// The statistics of the compiler's caches, printed by `--stats`
+    fn stats(&self) -> Option<String> {
+        None
+    }
 
     #[inline]
     fn quit(&mut self, code: i32) -> ! {
@@ -734,6 +857,11 @@ pub trait Runnable: Sized + Default {
 
     fn run(cfg: ErgConfig) -> ExitStatus {
         let quiet_repl = cfg.quiet_repl;
+        set_codes_only(cfg.codes_only);
+        let timings = cfg.timings;
+        if timings.is_some() {
+            timings::enable();
+        }
         let mut num_errors = 0;
         let mut instance = Self::new(cfg);
         let res = match &instance.input().kind {
@@ -751,15 +879,21 @@ pub trait Runnable: Sized + Default {
                 let mut vm = VirtualMachine::new();
                 loop {
                     let indent = vm.indent();
-                    if vm.now_block.len() > 1 {
-                        output.write_all(instance.ps2().as_bytes()).unwrap();
-                        output.write_all(indent.as_str().as_bytes()).unwrap();
-                        output.flush().unwrap();
+                    let prompt = if vm.now_block.len() > 1 {
+                        instance.ps2() + indent.as_str()
+                    } else {
+                        instance
+                            .input()
+                            .set_completion_words(instance.completion_words());
+                        instance.ps1()
+                    };
+                    // the line editor prints the prompt by itself
+                    if cfg!(feature = "full-repl") && instance.input().kind == InputKind::REPL {
+                        instance.input().set_prompt(&prompt);
                     } else {
-                        output.write_all(instance.ps1().as_bytes()).unwrap();
+                        output.write_all(prompt.as_bytes()).unwrap();
                         output.flush().unwrap();
                     }
-                    instance.cfg().input.set_indent(vm.length);
                     let line = chomp(&instance.cfg_mut().input.read());
                     let line = line.trim_end();
                     match line {
@@ -773,6 +907,62 @@ pub trait Runnable: Sized + Default {
                             instance.clear();
                             continue;
                         }
+                        ":undo" => {
+                            let msg = if instance.undo() {
+                                "undid the last evaluation\n"
+                            } else {
+                                "nothing to undo\n"
+                            };
+                            output.write_all(msg.as_bytes()).unwrap();
+                            output.flush().unwrap();
+                            continue;
+                        }
+                        ":reset" => {
+                            vm.clear();
+                            instance.initialize();
+                            output.write_all("reset the session\n".as_bytes()).unwrap();
+                            output.flush().unwrap();
+                            continue;
+                        }
+                        _ if line.starts_with(":del ") => {
+                            // e.g. `:del x, y` => `Del x, y`
+                            let names = line.trim_start_matches(":del").trim();
+                            if let Err(errs) = instance.eval(format!("Del {names}")) {
+                                num_errors += errs.len();
+                                errs.write_all_stderr();
+                            }
+                            instance.input().set_block_begin();
+                            instance.clear();
+                            vm.clear();
+                            continue;
+                        }
+                        _ if line.starts_with(":load ") => {
+                            let path = line.trim_start_matches(":load").trim();
+                            match load_script(&mut instance, path, &mut output) {
+                                Some(errs) => num_errors += errs,
+                                None => instance.quit_successfully(output),
+                            }
+                            instance.input().set_block_begin();
+                            vm.clear();
+                            continue;
+                        }
+                        _ if line.starts_with(":save ") => {
+                            let path = line.trim_start_matches(":save").trim();
+                            let mut script = instance
+                                .accepted_inputs()
+                                .iter()
+                                .map(|input| input.trim_end())
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            script.push('\n');
+                            let msg = match std::fs::write(path, script) {
+                                Ok(()) => format!("saved the session to {path}\n"),
+                                Err(err) => format!("cannot save the session to {path}: {err}\n"),
+                            };
+                            output.write_all(msg.as_bytes()).unwrap();
+                            output.flush().unwrap();
+                            continue;
+                        }
                         "" => {
                             // eval after the end of the block
                             if vm.now_block.len() == 2 {
@@ -807,11 +997,19 @@ pub trait Runnable: Sized + Default {
                         }
                         _ => {}
                     }
-                    let line = if let Some(comment_start) = line.find('#') {
-                        &line[..comment_start]
-                    } else {
-                        line
-                    };
+                    // the line editor returns the lines continued by the unclosed brackets at once
+                    let line = line
+                        .lines()
+                        .map(|line| {
+                            if let Some(comment_start) = line.find('#') {
+                                &line[..comment_start]
+                            } else {
+                                line
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    let line = line.as_str();
                     let bk = instance.expect_block(line);
                     match bk {
                         BlockKind::None if vm.now == BlockKind::AtMark => {
@@ -932,6 +1130,14 @@ pub trait Runnable: Sized + Default {
             }
             InputKind::Dummy => switch_unreachable!(),
         };
+        if let Some(format) = timings {
+            timings::report(format);
+        }
+        if instance.cfg().stats {
+            if let Some(stats) = instance.stats() {
+                eprintln!("{stats}");
+            }
+        }
         match res {
             Ok(status) => status,
             Err(errs) => {
@@ -1211,3 +1417,44 @@ pub trait StructuralEq {
 pub trait __Str__ {
     fn __str__(&self) -> String;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_chunks() {
+        let src = r#"# comment
+x = 1
+
+f a =
+    # comment
+    a + 1
+@Inheritable
+C = Class { .x = Int }
+C.
+    y = 1
+print! f(
+1
+)
+g(_, 0) = 0
+g(x, _) = x
+s = """
+a
+"""
+"#;
+        let chunks = split_chunks(src);
+        assert_eq!(
+            chunks,
+            vec![
+                (2, "x = 1".to_string()),
+                (4, "f a =\n    # comment\n    a + 1".to_string()),
+                (7, "@Inheritable\nC = Class { .x = Int }".to_string()),
+                (9, "C.\n    y = 1".to_string()),
+                (11, "print! f(\n1\n)".to_string()),
+                (14, "g(_, 0) = 0\ng(x, _) = x".to_string()),
+                (16, "s = \"\"\"\na\n\"\"\"".to_string()),
+            ]
+        );
+    }
+}
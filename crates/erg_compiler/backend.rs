@@ -0,0 +1,24 @@
+//! defines `Backend`, the interface between the lowerer and the code generators.
+//!
+//! A backend converts a (linked, desugared) `HIR` into the target representation.
+//! The CPython bytecode generator (→ `CodeObj`) and the Python script generator (→ `PyScript`) are implementations of it.
+//! Other targets can be added by implementing this trait and passing the backend to `Compiler::compile_with`.
+use crate::hir::{Call, ClassDef, DefBody, Expr, Literal, SubrSignature, HIR};
+
+pub trait Backend {
+    /// The artifact of a module (e.g. `CodeObj`, `PyScript`).
+    type Output;
+    /// The result of emitting an expression.
+    /// e.g. `()` for the bytecode generator (the instructions are written into the current code object),
+    /// `String` for the script generator.
+    type Fragment;
+
+    fn emit_module(&mut self, hir: HIR) -> Self::Output;
+    fn emit_expr(&mut self, expr: Expr) -> Self::Fragment;
+    fn emit_function(&mut self, sig: SubrSignature, body: DefBody) -> Self::Fragment;
+    fn emit_class(&mut self, class_def: ClassDef) -> Self::Fragment;
+    fn emit_literal(&mut self, lit: Literal) -> Self::Fragment;
+    fn emit_call(&mut self, call: Call) -> Self::Fragment;
+    /// Resets the state so that the next module can be emitted.
+    fn clear(&mut self);
+}
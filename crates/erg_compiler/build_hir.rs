@@ -1,6 +1,7 @@
 use erg_common::config::ErgConfig;
 use erg_common::dict::Dict;
 use erg_common::error::MultiErrorDisplay;
+use erg_common::timings;
 use erg_common::traits::{ExitStatus, Runnable, Stream};
 use erg_common::Str;
 
@@ -66,10 +67,11 @@ impl Runnable for HIRBuilder {
     }
 
     fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let src = self.cfg_mut().input.read();
+        // an invalid header is reported by the parser
+        let _ = self.cfg_mut().apply_header(&src);
         let mut builder = ASTBuilder::new(self.cfg().copy());
-        let artifact = builder
-            .build(self.cfg_mut().input.read())
-            .map_err(|arti| arti.errors)?;
+        let artifact = builder.build(src).map_err(|arti| arti.errors)?;
         artifact.warns.write_all_stderr();
         let artifact = self
             .check(artifact.ast, "exec")
@@ -81,14 +83,32 @@ impl Runnable for HIRBuilder {
 
     fn eval(&mut self, src: String) -> Result<String, Self::Errs> {
         let mut builder = ASTBuilder::new(self.cfg().copy());
-        let artifact = builder.build(src).map_err(|arti| arti.errors)?;
+        let artifact = builder.build(src.clone()).map_err(|arti| arti.errors)?;
         artifact.warns.write_all_stderr();
         let artifact = self
             .check(artifact.ast, "eval")
             .map_err(|arti| arti.errors)?;
         artifact.warns.write_all_stderr();
+        self.lowerer.accept_input(src);
         Ok(artifact.object.to_string())
     }
+
+    fn undo(&mut self) -> bool {
+        self.ownership_checker.rollback();
+        self.lowerer.undo_eval()
+    }
+
+    fn accepted_inputs(&self) -> &[String] {
+        self.lowerer.accepted_inputs()
+    }
+
+    fn completion_words(&self) -> Vec<String> {
+        self.dir_names()
+    }
+
+    fn stats(&self) -> Option<String> {
+        self.lowerer.stats()
+    }
 }
 
 impl Buildable for HIRBuilder {
@@ -149,34 +169,67 @@ impl HIRBuilder {
     }
 
     pub fn check(&mut self, ast: AST, mode: &str) -> Result<CompleteArtifact, IncompleteArtifact> {
-        let mut artifact = self.lowerer.lower(ast, mode)?;
+        if mode == "eval" {
+            self.ownership_checker.snapshot();
+        }
+        let module = self.cfg().input.module_name();
+        let result = timings::measure(&module, "lower", || self.lowerer.lower(ast, mode));
+        if result.is_err() && mode == "eval" {
+            self.ownership_checker.rollback();
+        }
+        let mut artifact = result?;
         let effect_checker = SideEffectChecker::new(self.cfg().clone());
-        let hir = effect_checker
-            .check(artifact.object)
-            .map_err(|(hir, errs)| {
-                self.lowerer.module.context.clear_invalid_vars();
-                IncompleteArtifact::new(Some(hir), errs, artifact.warns.take_all().into())
-            })?;
-        let hir = self.ownership_checker.check(hir).map_err(|(hir, errs)| {
-            self.lowerer.module.context.clear_invalid_vars();
+        let hir = timings::measure(&module, "effect check", || {
+            effect_checker.check(artifact.object)
+        })
+        .map_err(|(hir, errs)| {
+            self.discard_invalid_vars(mode);
+            IncompleteArtifact::new(Some(hir), errs, artifact.warns.take_all().into())
+        })?;
+        let hir = timings::measure(&module, "ownership check", || {
+            self.ownership_checker.check(hir)
+        })
+        .map_err(|(hir, errs)| {
+            self.discard_invalid_vars(mode);
             IncompleteArtifact::new(Some(hir), errs, artifact.warns.take_all().into())
         })?;
         Ok(CompleteArtifact::new(hir, artifact.warns))
     }
 
+    /// A failed REPL evaluation is rolled back entirely;
+    /// otherwise only the variables that failed to be defined are removed.
+    fn discard_invalid_vars(&mut self, mode: &str) {
+        if mode == "eval" {
+            self.lowerer.rollback();
+            self.ownership_checker.rollback();
+        } else {
+            self.lowerer.module.context.clear_invalid_vars();
+        }
+    }
+
     pub fn build(
         &mut self,
         src: String,
         mode: &str,
     ) -> Result<CompleteArtifact, IncompleteArtifact> {
-        let mut ast_builder = ASTBuilder::new(self.cfg().copy());
-        let artifact = ast_builder
-            .build(src)
-            .map_err(|iart| IncompleteArtifact::new(None, iart.errors.into(), iart.warns.into()))?;
-        self.lowerer
-            .warns
-            .extend(LowerWarnings::from(artifact.warns));
-        self.check(artifact.ast, mode)
+        let module = self.cfg().input.module_name();
+        // an invalid header is reported by the parser
+        let _ = self.cfg_mut().apply_header(&src);
+        timings::measure(&module, "analysis", || {
+            let mut ast_builder = ASTBuilder::new(self.cfg().copy());
+            let input = (mode == "eval").then(|| src.clone());
+            let artifact = ast_builder.build(src).map_err(|iart| {
+                IncompleteArtifact::new(None, iart.errors.into(), iart.warns.into())
+            })?;
+            self.lowerer
+                .warns
+                .extend(LowerWarnings::from(artifact.warns));
+            let artifact = self.check(artifact.ast, mode)?;
+            if let Some(input) = input {
+                self.lowerer.accept_input(input);
+            }
+            Ok(artifact)
+        })
     }
 
     pub fn build_module(&mut self) -> Result<CompleteArtifact, IncompleteArtifact> {
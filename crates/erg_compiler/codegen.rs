@@ -18,7 +18,7 @@ use erg_common::opcode310::Opcode310;
 use erg_common::opcode311::{BinOpCode, Opcode311};
 use erg_common::option_enum_unwrap;
 use erg_common::python_util::{env_python_version, PythonVersion};
-use erg_common::traits::{Locational, Stream};
+use erg_common::traits::{Locational, NoTypeDisplay, Stream};
 use erg_common::Str;
 use erg_common::{debug_power_assert, fn_name, fn_name_full, impl_stream, log, switch_unreachable};
 use erg_parser::ast::VisModifierSpec;
@@ -30,16 +30,17 @@ use erg_parser::token::DOT;
 use erg_parser::token::EQUAL;
 use erg_parser::token::{Token, TokenKind};
 
+use crate::backend::Backend;
 use crate::compile::{AccessKind, Name, StoreLoadKind};
-use crate::error::CompileError;
+use crate::error::{ordinal_num, CompileError};
 use crate::hir::{
-    Accessor, Args, Array, BinOp, Block, Call, ClassDef, Def, DefBody, Expr, Identifier, Lambda,
-    Literal, NonDefaultParamSignature, Params, PatchDef, PosArg, ReDef, Record, Signature,
+    Accessor, Args, Array, BinOp, Block, Call, ClassDef, Def, DefBody, Dummy, Expr, Identifier,
+    Lambda, Literal, NonDefaultParamSignature, Params, PatchDef, PosArg, ReDef, Record, Signature,
     SubrSignature, Tuple, UnaryOp, VarSignature, HIR,
 };
 use crate::ty::value::ValueObj;
-use crate::ty::{HasType, Type, TypeCode, TypePair, VisibilityModifier};
-use crate::varinfo::VarInfo;
+use crate::ty::{HasType, TyParam, Type, TypeCode, TypePair, VisibilityModifier};
+use crate::varinfo::{MethodKind, VarInfo};
 use AccessKind::*;
 use Type::*;
 
@@ -69,6 +70,45 @@ fn debind(ident: &Identifier) -> Option<Str> {
     }
 }
 
+/// Python sequences cannot be indexed by `Range`s
+///
+/// `x.__getitem__(a..b)` => `x.__getitem__((a..b).as_slice__())`
+pub(crate) fn range_index_as_slice(method_name: &Identifier, args: &mut Args) {
+    if &method_name.inspect()[..] != "__getitem__" {
+        return;
+    }
+    let Some(arg) = args.pos_args.first_mut() else {
+        return;
+    };
+    if &arg.expr.ref_t().qual_name()[..] != "Range" {
+        return;
+    }
+    let range = std::mem::replace(&mut arg.expr, Expr::Dummy(Dummy::new(vec![])));
+    let as_slice = Identifier::public("as_slice__");
+    arg.expr = Expr::Call(Call::new(range, Some(as_slice), Args::empty()));
+}
+
+/// The runtime class to check `Dynamic` values of type `t` (`None` if `t` cannot be checked)
+fn runtime_class(t: &Type) -> Option<&'static str> {
+    match t {
+        FreeVar(fv) if fv.is_linked() => runtime_class(&fv.crack()),
+        Int => Some("Int"),
+        Nat => Some("Nat"),
+        Float => Some("Float"),
+        Str => Some("Str"),
+        Bool => Some("Bool"),
+        Mono(name) if &name[..] == "Bytes" => Some("Bytes"),
+        Poly { name, .. } => match &name[..] {
+            "Array" | "Array!" => Some("list"),
+            "Dict" | "Dict!" => Some("dict"),
+            "Set" | "Set!" => Some("set"),
+            "Tuple" | "HomogenousTuple" => Some("tuple"),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn escape_name(name: &str, vis: &VisibilityModifier, def_line: u32, def_col: u32) -> Str {
     let name = name.replace('!', "__erg_proc__");
     let name = name.replace('$', "__erg_shared__");
@@ -152,6 +192,9 @@ impl PyCodeGenUnit {
     }
 }
 
+/// `match` with more literal arms than this is compiled to a table lookup (see `emit_match_table`)
+const MATCH_TABLE_MIN_ARMS: usize = 3;
+
 #[derive(Debug, Clone)]
 pub struct PyCodeGenStack(Vec<PyCodeGenUnit>);
 
@@ -165,6 +208,7 @@ pub struct PyCodeGenerator {
     prelude_loaded: bool,
     mutate_op_loaded: bool,
     in_op_loaded: bool,
+    assert_type_loaded: bool,
     record_type_loaded: bool,
     module_type_loaded: bool,
     control_loaded: bool,
@@ -184,6 +228,7 @@ impl PyCodeGenerator {
             prelude_loaded: false,
             mutate_op_loaded: false,
             in_op_loaded: false,
+            assert_type_loaded: false,
             record_type_loaded: false,
             module_type_loaded: false,
             control_loaded: false,
@@ -203,6 +248,7 @@ impl PyCodeGenerator {
             prelude_loaded: false,
             mutate_op_loaded: false,
             in_op_loaded: false,
+            assert_type_loaded: false,
             record_type_loaded: false,
             module_type_loaded: false,
             control_loaded: false,
@@ -222,6 +268,7 @@ impl PyCodeGenerator {
         self.prelude_loaded = false;
         self.mutate_op_loaded = false;
         self.in_op_loaded = false;
+        self.assert_type_loaded = false;
         self.record_type_loaded = false;
         self.module_type_loaded = false;
         self.control_loaded = false;
@@ -286,7 +333,7 @@ impl PyCodeGenerator {
         self.stack_dec();
     }
 
-    fn _emit_compare_op(&mut self, op: CompareOp) {
+    fn emit_compare_op(&mut self, op: CompareOp) {
         self.write_instr(Opcode311::COMPARE_OP);
         self.write_arg(op as usize);
         self.stack_dec();
@@ -710,7 +757,7 @@ impl PyCodeGenerator {
             "if__" | "for__" | "while__" | "with__" | "discard__" => {
                 self.load_control();
             }
-            "int__" | "nat__" | "str__" | "float__" => {
+            "int__" | "nat__" | "str__" | "float__" | "dict_as_record__" => {
                 self.load_convertors();
             }
             // NoneType is not defined in the global scope, use `type(None)` instead
@@ -1096,6 +1143,13 @@ impl PyCodeGenerator {
         debug_assert_eq!(kind, DefKind::Trait);
         let name = sig.ident().inspect().clone();
         let Expr::Call(mut trait_call) = block.remove(0) else { unreachable!() };
+        // `Derivable(Trait {...}, "module")`
+        if trait_call.obj.show_acc().as_deref() == Some("Derivable") {
+            let Some(Expr::Call(inner)) = trait_call.args.remove_left_or_key("Trait") else {
+                unreachable!()
+            };
+            trait_call = inner;
+        }
         let req = if let Some(Expr::Record(req)) = trait_call.args.remove_left_or_key("Requirement")
         {
             req.attrs.into_iter()
@@ -1151,7 +1205,9 @@ impl PyCodeGenerator {
         // end of flagging
         let unit = self.units.pop().unwrap();
         if !self.units.is_empty() {
-            let ld = unit.prev_lineno - self.cur_block().prev_lineno;
+            let ld = unit
+                .prev_lineno
+                .saturating_sub(self.cur_block().prev_lineno);
             if ld != 0 {
                 if let Some(l) = self.mut_cur_block_codeobj().lnotab.last_mut() {
                     *l += ld as u8;
@@ -1280,7 +1336,19 @@ impl PyCodeGenerator {
         match obj {
             GenTypeObj::Class(_) => 0,
             GenTypeObj::Subclass(_) => {
-                self.emit_expr(require_or_sup.unwrap());
+                let sup = match require_or_sup.unwrap() {
+                    // `Inherit Array(Int, 3)`: type arguments are erased at runtime
+                    Expr::Call(call)
+                        if matches!(
+                            call.ref_t().singleton_value(),
+                            Some(TyParam::Type(_) | TyParam::Value(ValueObj::Type(_)))
+                        ) =>
+                    {
+                        *call.obj
+                    }
+                    other => other,
+                };
+                self.emit_expr(sup);
                 1 // TODO: not always 1
             }
             _ => todo!(),
@@ -1295,6 +1363,10 @@ impl PyCodeGenerator {
 
     fn emit_var_def(&mut self, sig: VarSignature, mut body: DefBody) {
         log!(info "entered {} ({sig} = {})", fn_name!(), body.block);
+        if let (Some(t_spec), Some(value)) = (sig.t_spec.as_ref(), body.block.last_mut()) {
+            let desc = format!("`{}`", sig.ident.inspect());
+            self.check_dynamic(value, &t_spec.spec_t, desc);
+        }
         if body.block.len() == 1 {
             self.emit_expr(body.block.remove(0));
         } else {
@@ -1307,8 +1379,12 @@ impl PyCodeGenerator {
         }
     }
 
-    fn emit_subr_def(&mut self, class_name: Option<&str>, sig: SubrSignature, body: DefBody) {
+    fn emit_subr_def(&mut self, class_name: Option<&str>, sig: SubrSignature, mut body: DefBody) {
         log!(info "entered {} ({sig} = {})", fn_name!(), body.block);
+        if let (Some(t_spec), Some(value)) = (sig.return_t_spec.as_ref(), body.block.last_mut()) {
+            let desc = format!("the return value of `{}`", sig.ident.inspect());
+            self.check_dynamic(value, &t_spec.spec_t, desc);
+        }
         let name = sig.ident.inspect().clone();
         let mut make_function_flag = 0;
         let params = self.gen_param_names(&sig.params);
@@ -1901,6 +1977,11 @@ impl PyCodeGenerator {
         let init_stack_len = self.stack_len();
         let expr = args.remove(0);
         self.emit_expr(expr);
+        if let Some(keys) = self.match_table_keys(&args) {
+            self.emit_match_table(args, keys);
+            debug_assert_eq!(self.stack_len(), init_stack_len + 1);
+            return;
+        }
         let len = args.len();
         let mut jump_forward_points = vec![];
         while let Some(expr) = args.try_remove(0) {
@@ -1915,17 +1996,16 @@ impl PyCodeGenerator {
             }
             let param = lambda.params.non_defaults.remove(0);
             let pop_jump_points = self.emit_match_pattern(param, args.is_empty());
+            // the argument is replaced with the result of the arm,
+            // and the next arm starts with the argument (the stack size is the same)
             self.emit_frameless_block(lambda.body, Vec::new());
-            // If we move on to the next arm, the stack size will increase
-            // so `self.stack_dec();` for now (+1 at the end).
-            self.stack_dec();
             for pop_jump_point in pop_jump_points.into_iter() {
                 let idx = if self.py_version.minor >= Some(11) {
                     self.lasti() - pop_jump_point // - 2
                 } else {
                     self.lasti() + 2
                 };
-                self.calc_edit_jump(pop_jump_point + 1, idx); // jump to POP_TOP
+                self.calc_edit_jump(pop_jump_point + 1, idx); // jump to the next arm
                 jump_forward_points.push(self.lasti());
                 self.write_instr(JUMP_FORWARD); // jump to the end
                 self.write_arg(0);
@@ -1935,10 +2015,181 @@ impl PyCodeGenerator {
         for jump_point in jump_forward_points.into_iter() {
             self.calc_edit_jump(jump_point + 1, lasti - jump_point - 1);
         }
-        self.stack_inc();
         debug_assert_eq!(self.stack_len(), init_stack_len + 1);
     }
 
+    /// Returns the values of the arms if all the arms (except the last one) are integer or string literals,
+    /// e.g. `[0, 1, 2]` for `match x: 0 -> ...; 1 -> ...; 2 -> ...; _ -> ...`.
+    /// `None` if the arms should be compiled to equality checks.
+    fn match_table_keys(&self, arms: &Args) -> Option<Vec<ValueObj>> {
+        // `CONTAINS_OP` is available since Python 3.9
+        if self.py_version.minor < Some(9) || arms.len() <= MATCH_TABLE_MIN_ARMS + 1 {
+            return None;
+        }
+        let mut keys: Vec<ValueObj> = vec![];
+        for i in 0..arms.len() - 1 {
+            let Some(Expr::Lambda(lambda)) = arms.get(i) else {
+                return None;
+            };
+            let t_spec = lambda
+                .params
+                .non_defaults
+                .first()?
+                .t_spec_as_expr
+                .as_ref()?;
+            let key = match Self::literal_pattern_value(t_spec)? {
+                ValueObj::Nat(n) => ValueObj::Int(i32::try_from(n).ok()?),
+                key @ (ValueObj::Int(_) | ValueObj::Str(_)) => key,
+                _ => return None,
+            };
+            // an arm shadowed by the same literal is left to the equality checks
+            if keys.contains(&key) {
+                return None;
+            }
+            keys.push(key);
+        }
+        Some(keys)
+    }
+
+    /// Compiles the arms of `match` to a lookup of a constant dict `{key: index of the arm}`,
+    /// followed by a binary search of the index (instead of checking the keys one by one).
+    ///
+    /// ```python
+    /// # match x: 0 -> a; 1 -> b; 2 -> c; 3 -> d; _ -> e
+    /// if x in {0: 0, 1: 1, 2: 2, 3: 3}:
+    ///     i = {0: 0, 1: 1, 2: 2, 3: 3}[x]
+    ///     if i < 2:
+    ///         a if i < 1 else b
+    ///     else:
+    ///         c if i < 3 else d
+    /// else:
+    ///     e
+    /// ```
+    fn emit_match_table(&mut self, mut arms: Args, keys: Vec<ValueObj>) {
+        log!(info "entered {}", fn_name!());
+        let table = ValueObj::Dict(
+            keys.into_iter()
+                .enumerate()
+                .map(|(i, key)| (key, ValueObj::Int(i as i32)))
+                .collect(),
+        );
+        let n_keys = arms.len() - 1;
+        // arg
+        // ↓ DUP_TOP, LOAD_CONST(table)
+        // arg arg table
+        // ↓ CONTAINS_OP
+        // arg (arg in table)
+        // ↓ POP_JUMP_IF_FALSE(default)
+        // arg
+        self.dup_top();
+        self.emit_load_const(table.clone());
+        self.write_instr(Opcode310::CONTAINS_OP);
+        self.write_arg(0);
+        self.stack_dec();
+        let default_jump = self.write_wide_jump(Opcode310::POP_JUMP_IF_FALSE);
+        self.stack_dec();
+        // arg
+        // ↓ DUP_TOP, LOAD_CONST(table), ROT_TWO
+        // arg table arg
+        // ↓ BINARY_SUBSCR
+        // arg index
+        self.dup_top();
+        self.emit_load_const(table);
+        self.rot2();
+        self.write_instr(Opcode310::BINARY_SUBSCR);
+        self.write_arg(0);
+        if self.py_version.minor >= Some(11) {
+            self.write_bytes(&[0; 8]);
+        }
+        self.stack_dec();
+        let mut end_jumps = vec![];
+        self.emit_match_table_search(&mut arms, 0, n_keys, &mut end_jumps);
+        // arg (not in the table)
+        self.edit_wide_jump(default_jump, true);
+        let Expr::Lambda(mut lambda) = arms.remove(0) else { unreachable!() };
+        self.emit_match_table_arm(&mut lambda);
+        for end_jump in end_jumps {
+            self.edit_wide_jump(end_jump, false);
+        }
+    }
+
+    /// Emits the arms `lo..hi`. The stack is `arg index` at the start and `result` at the end.
+    fn emit_match_table_search(
+        &mut self,
+        arms: &mut Args,
+        lo: usize,
+        hi: usize,
+        end_jumps: &mut Vec<usize>,
+    ) {
+        if hi - lo == 1 {
+            self.emit_pop_top();
+            // the arms are emitted in order (`lo` is always the first arm in `arms`)
+            let Expr::Lambda(mut lambda) = arms.remove(0) else { unreachable!() };
+            self.emit_match_table_arm(&mut lambda);
+            end_jumps.push(self.write_wide_jump(JUMP_FORWARD));
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        // arg index
+        // ↓ DUP_TOP, LOAD_CONST(mid), COMPARE_OP(<)
+        // arg index (index < mid)
+        // ↓ POP_JUMP_IF_FALSE(right)
+        // arg index
+        self.dup_top();
+        self.emit_load_const(mid);
+        self.emit_compare_op(CompareOp::LT);
+        let right_jump = self.write_wide_jump(Opcode310::POP_JUMP_IF_FALSE);
+        self.stack_dec();
+        self.emit_match_table_search(arms, lo, mid, end_jumps);
+        // `result` -> `arg index`
+        self.stack_dec();
+        self.stack_inc_n(2);
+        self.edit_wide_jump(right_jump, true);
+        self.emit_match_table_search(arms, mid, hi, end_jumps);
+    }
+
+    /// `arg` -> `result`
+    fn emit_match_table_arm(&mut self, lambda: &mut Lambda) {
+        debug_power_assert!(lambda.params.len(), ==, 1);
+        if !lambda.params.defaults.is_empty() {
+            todo!("default values in match expression are not supported yet")
+        }
+        let param = lambda.params.non_defaults.remove(0);
+        self.emit_match_pattern_binding(param.raw.pat, param.vi, vec![]);
+        self.emit_frameless_block(std::mem::take(&mut lambda.body), Vec::new());
+    }
+
+    /// Writes a jump with an `EXTENDED_ARG` prefix, and returns the index of the jump instruction.
+    /// Editing the argument later (`edit_wide_jump`) does not shift the code, unlike `calc_edit_jump`,
+    /// so the jumps written before remain valid.
+    fn write_wide_jump<C: Into<u8>>(&mut self, instr: C) -> usize {
+        self.write_instr(EXTENDED_ARG);
+        self.write_arg(0);
+        let idx = self.lasti();
+        self.write_instr(instr);
+        self.write_arg(0);
+        idx
+    }
+
+    /// Makes the jump written by `write_wide_jump` jump to the current position.
+    /// `POP_JUMP_IF_FALSE` is an absolute jump before Python 3.11 (`is_cond`).
+    fn edit_wide_jump(&mut self, idx: usize, is_cond: bool) {
+        let jump_to = if is_cond && self.py_version.minor < Some(11) {
+            self.lasti()
+        } else {
+            self.lasti() - idx - 2
+        };
+        let arg = if self.py_version.minor >= Some(10) {
+            jump_to / 2
+        } else {
+            jump_to
+        };
+        let [_, _, hi, lo] = u32::try_from(arg).unwrap().to_be_bytes();
+        let code = &mut self.mut_cur_block_codeobj().code;
+        code[idx - 1] = hi;
+        code[idx + 1] = lo;
+    }
+
     fn emit_match_pattern(
         &mut self,
         param: NonDefaultParamSignature,
@@ -1949,25 +2200,49 @@ impl PyCodeGenerator {
         if let Some(t_spec) = param.t_spec_as_expr {
             // If it's the last arm, there's no need to inspect it
             if !is_last_arm {
+                // `1 -> ...`, `"a" -> ...`
+                // compiled to `arg == 1` like Python's `match` statement (no function call)
+                if let Some(value) = Self::literal_pattern_value(&t_spec) {
+                    // arg arg
+                    // ↓ LOAD_CONST(value), COMPARE_OP(==)
+                    // arg (arg == value)
+                    // ↓ POP_JUMP_IF_FALSE
+                    // arg
+                    self.emit_load_const(value);
+                    self.emit_compare_op(CompareOp::EQ);
+                    pop_jump_points.push(self.lasti());
+                    self.write_instr(Opcode310::POP_JUMP_IF_FALSE); // jump to the next case
+                    self.write_arg(0);
+                    self.stack_dec();
+                    return self.emit_match_pattern_binding(
+                        param.raw.pat,
+                        param.vi,
+                        pop_jump_points,
+                    );
+                }
                 // < v3.11:
-                // arg
+                // arg arg
                 // ↓ LOAD_NAME(in_operator)
-                // arg in_operator
+                // arg arg in_operator
                 // ↓ ROT_TWO
-                // in_operator arg
+                // arg in_operator arg
                 // ↓ load expr
-                // in_operator arg expr
+                // arg in_operator arg expr
+                // ↓ CALL_FUNCTION(2)
+                // arg (arg in expr)
                 //
                 // in v3.11:
-                // arg null
-                // ↓ SWAP 1
-                // null arg
+                // arg arg null
+                // ↓ SWAP 2
+                // arg null arg
                 // ↓ LOAD_NAME(in_operator)
-                // null arg in_operator
-                // ↓ SWAP 1
-                // null in_operator arg
+                // arg null arg in_operator
+                // ↓ SWAP 2
+                // arg null in_operator arg
                 // ↓ load expr
-                // null in_operator arg expr
+                // arg null in_operator arg expr
+                // ↓ PRECALL(2), CALL(2)
+                // arg (arg in expr)
                 if self.py_version.minor >= Some(11) {
                     self.emit_push_null();
                     self.rot2();
@@ -1979,12 +2254,14 @@ impl PyCodeGenerator {
                 self.rot2();
                 self.emit_expr(t_spec);
                 if self.py_version.minor >= Some(11) {
+                    // `emit_precall_and_call` pops one of the four
                     self.emit_precall_and_call(2);
+                    self.stack_dec_n(2);
                 } else {
                     self.write_instr(Opcode310::CALL_FUNCTION);
                     self.write_arg(2);
+                    self.stack_dec_n(2);
                 }
-                self.stack_dec();
                 pop_jump_points.push(self.lasti());
                 // in 3.11, POP_JUMP_IF_FALSE is replaced with POP_JUMP_FORWARD_IF_FALSE
                 // but the numbers are the same, only the way the jumping points are calculated is different.
@@ -1993,10 +2270,19 @@ impl PyCodeGenerator {
                 self.stack_dec();
             }
         }
-        match param.raw.pat {
+        self.emit_match_pattern_binding(param.raw.pat, param.vi, pop_jump_points)
+    }
+
+    fn emit_match_pattern_binding(
+        &mut self,
+        pat: ParamPattern,
+        vi: VarInfo,
+        pop_jump_points: Vec<usize>,
+    ) -> Vec<usize> {
+        match pat {
             ParamPattern::VarName(name) => {
                 let ident = erg_parser::ast::Identifier::private_from_varname(name);
-                let ident = Identifier::new(ident, None, param.vi);
+                let ident = Identifier::new(ident, None, vi);
                 self.emit_store_instr(ident, AccessKind::Name);
             }
             ParamPattern::Discard(_) => {
@@ -2007,6 +2293,28 @@ impl PyCodeGenerator {
         pop_jump_points
     }
 
+    /// `{1}` => `Some(1)`
+    ///
+    /// Only the values compared by `==` in Python's `match` statement are returned
+    /// (`True`/`False`/`None` are compared by `is`).
+    fn literal_pattern_value(t_spec: &Expr) -> Option<ValueObj> {
+        let Expr::Set(crate::hir::Set::Normal(set)) = t_spec else {
+            return None;
+        };
+        if set.elems.pos_args.len() != 1 || !set.elems.kw_args.is_empty() {
+            return None;
+        }
+        match &set.elems.pos_args[0].expr {
+            Expr::Lit(lit) => match &lit.value {
+                ValueObj::Int(_) | ValueObj::Nat(_) | ValueObj::Float(_) | ValueObj::Str(_) => {
+                    Some(lit.value.clone())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     fn emit_with_instr_311(&mut self, mut args: Args) {
         log!(info "entered {}", fn_name!());
         if !matches!(args.get(1).unwrap(), Expr::Lambda(_)) {
@@ -2140,9 +2448,10 @@ impl PyCodeGenerator {
         self.emit_load_name_instr(stash);
     }
 
-    fn emit_call(&mut self, call: Call) {
+    fn emit_call(&mut self, mut call: Call) {
         log!(info "entered {} ({call})", fn_name!());
         let init_stack_len = self.stack_len();
+        self.check_dynamic_args(&mut call);
         // Python cannot distinguish at compile time between a method call and a attribute call
         if let Some(attr_name) = call.attr_name {
             self.emit_call_method(*call.obj, attr_name, call.args);
@@ -2162,10 +2471,78 @@ impl PyCodeGenerator {
         debug_assert_eq!(self.stack_len(), init_stack_len + 1);
     }
 
+    /// `f(x)` (`x: Dynamic`, `f: Int -> Int`) => `f(assert_type(x, Int, "..."))`
+    fn check_dynamic_args(&mut self, call: &mut Call) {
+        if !self.cfg.runtime_checks {
+            return;
+        }
+        let name = call.attr_name.as_ref().map_or_else(
+            || {
+                call.obj
+                    .local_name()
+                    .map_or_else(|| call.obj.to_string_notype(), str::to_string)
+            },
+            |attr| attr.inspect().to_string(),
+        );
+        let (pos_params, kw_params) = call.arg_param_types();
+        let pos_params = pos_params
+            .into_iter()
+            .map(|t| t.cloned())
+            .collect::<Vec<_>>();
+        let kw_params = kw_params
+            .into_iter()
+            .map(|t| t.cloned())
+            .collect::<Vec<_>>();
+        for (i, (arg, param_t)) in call.args.pos_args.iter_mut().zip(pos_params).enumerate() {
+            if let Some(param_t) = param_t {
+                let desc = format!("the {} argument of `{name}`", ordinal_num(i + 1));
+                self.check_dynamic(&mut arg.expr, &param_t, desc);
+            }
+        }
+        for (arg, param_t) in call.args.kw_args.iter_mut().zip(kw_params) {
+            if let Some(param_t) = param_t {
+                let desc = format!("the argument `{}` of `{name}`", arg.keyword.inspect());
+                self.check_dynamic(&mut arg.expr, &param_t, desc);
+            }
+        }
+    }
+
+    /// Wraps a `Dynamic` expression with `assert_type` to check it at runtime (if `--runtime-checks` is specified).
+    fn check_dynamic(&mut self, expr: &mut Expr, expected: &Type, desc: String) {
+        if !self.cfg.runtime_checks || self.cfg.no_std || !expr.ref_t().is_dynamic() {
+            return;
+        }
+        let Some(class) = runtime_class(expected) else {
+            return;
+        };
+        if !self.assert_type_loaded {
+            self.load_assert_type();
+        }
+        let msg = format!(
+            "{}:{}: {desc} should be {expected}",
+            self.cfg.input.unescaped_filename(),
+            expr.ln_begin().unwrap_or(0)
+        );
+        let msg = Literal::new(ValueObj::Str(msg.into()), Token::DUMMY);
+        let value = std::mem::replace(expr, Expr::Dummy(Dummy::new(vec![])));
+        let args = Args::values(
+            vec![
+                value,
+                Expr::Accessor(Accessor::Ident(Identifier::public(class))),
+                Expr::Lit(msg),
+            ],
+            None,
+        );
+        let callee = Expr::Accessor(Accessor::Ident(Identifier::private("#assert_type")));
+        *expr = Expr::Call(Call::new(callee, None, args));
+    }
+
     fn emit_call_local(&mut self, local: Identifier, args: Args) {
         log!(info "entered {}", fn_name!());
         match &local.inspect()[..] {
             "assert" => self.emit_assert_instr(args),
+            // already checked in lowering
+            "assert_static" => self.emit_load_const(ValueObj::None),
             "Del" => self.emit_del_instr(args),
             "not" => self.emit_not_instr(args),
             "discard" => self.emit_discard_instr(args),
@@ -2189,7 +2566,7 @@ impl PyCodeGenerator {
         }
     }
 
-    fn emit_call_method(&mut self, obj: Expr, method_name: Identifier, args: Args) {
+    fn emit_call_method(&mut self, obj: Expr, method_name: Identifier, mut args: Args) {
         log!(info "entered {}", fn_name!());
         match &method_name.inspect()[..] {
             "update!" => {
@@ -2211,7 +2588,15 @@ impl PyCodeGenerator {
         if let Some(func_name) = debind(&method_name) {
             return self.emit_call_fake_method(obj, func_name, method_name, args);
         }
+        range_index_as_slice(&method_name, &mut args);
         let is_py_api = method_name.is_py_api();
+        // `CALL_FUNCTION_EX` cannot call an unbound method loaded by `LOAD_METHOD`
+        if args.var_args.is_some() {
+            self.emit_push_null();
+            self.emit_expr(obj);
+            self.emit_load_attr_instr(method_name);
+            return self.emit_args_311(args, Name, is_py_api);
+        }
         self.emit_expr(obj);
         self.emit_load_method_instr(method_name);
         self.emit_args_311(args, BoundAttr, is_py_api);
@@ -2282,6 +2667,10 @@ impl PyCodeGenerator {
                 } else {
                     self.write_arg(1);
                 }
+                // `CALL_FUNCTION_EX` also pops the `NULL` pushed before the callable
+                if self.py_version.minor >= Some(11) {
+                    self.stack_dec();
+                }
             } else {
                 self.emit_call_instr(argc, kind);
             }
@@ -2796,6 +3185,8 @@ impl PyCodeGenerator {
     fn emit_class_block(&mut self, class: ClassDef) -> CodeObj {
         log!(info "entered {}", fn_name!());
         let name = class.sig.ident().inspect().clone();
+        let non_instance_methods = class.non_instance_methods();
+        let properties = class.properties();
         self.unit_size += 1;
         let firstlineno = match class.methods.get(0).and_then(|def| def.ln_begin()) {
             Some(l) => l,
@@ -2814,15 +3205,19 @@ impl PyCodeGenerator {
         let mod_name = self.toplevel_block_codeobj().name.clone();
         self.emit_load_const(mod_name);
         self.emit_store_instr(Identifier::public("__module__"), Name);
-        self.emit_load_const(name);
+        self.emit_load_const(name.clone());
         self.emit_store_instr(Identifier::public("__qualname__"), Name);
+        self.emit_inspection_attrs(name, &class.__new__);
         self.emit_init_method(&class.sig, class.__new__.clone());
         if class.need_to_gen_new {
             self.emit_new_func(&class.sig, class.__new__);
         }
+        self.emit_derived_methods(&class.obj);
         if !class.methods.is_empty() {
             self.emit_frameless_block(class.methods, vec![]);
         }
+        self.emit_non_instance_methods(non_instance_methods);
+        self.emit_properties(properties);
         if self.stack_len() == init_stack_len {
             self.emit_load_const(ValueObj::None);
         }
@@ -2848,7 +3243,9 @@ impl PyCodeGenerator {
         // end of flagging
         let unit = self.units.pop().unwrap();
         if !self.units.is_empty() {
-            let ld = unit.prev_lineno - self.cur_block().prev_lineno;
+            let ld = unit
+                .prev_lineno
+                .saturating_sub(self.cur_block().prev_lineno);
             if ld != 0 {
                 if let Some(l) = self.mut_cur_block_codeobj().lnotab.last_mut() {
                     *l += ld as u8;
@@ -2859,6 +3256,106 @@ impl PyCodeGenerator {
         unit.codeobj
     }
 
+    /// Emits the attributes used by the runtime inspection protocol (see `_erg_inspect.py`).
+    /// ```python
+    /// __erg_type__ = "C"
+    /// __erg_fields__ = ("::x", "y") # C = Class {x = Int; .y = Int}
+    /// ```
+    fn emit_inspection_attrs(&mut self, name: Str, __new__: &Type) {
+        self.emit_load_const(name);
+        self.emit_store_instr(Identifier::public("__erg_type__"), Name);
+        let mut fields = vec![];
+        if let Some(Type::Record(rec)) = __new__
+            .non_default_params()
+            .and_then(|params| params.first())
+            .map(|pt| pt.typ())
+        {
+            for field in rec.keys() {
+                if field.vis.is_private() {
+                    fields.push(format!("::{}", field.symbol));
+                } else {
+                    fields.push(field.symbol.to_string());
+                }
+            }
+        }
+        fields.sort();
+        let fields = fields
+            .into_iter()
+            .map(|field| ValueObj::Str(Str::from(field)))
+            .collect::<Vec<_>>();
+        self.emit_load_const(ValueObj::Tuple(fields.into()));
+        self.emit_store_instr(Identifier::public("__erg_fields__"), Name);
+    }
+
+    /// `@Derive [Eq]` => `from _erg_derive import __eq__` (in the class namespace)
+    fn emit_derived_methods(&mut self, obj: &GenTypeObj) {
+        log!(info "entered {}", fn_name!());
+        for derived in obj.derives() {
+            let names = derived.py_names();
+            self.emit_load_const(0);
+            let item_name_tuple = names
+                .iter()
+                .map(|name| ValueObj::Str(name.clone()))
+                .collect::<Vec<_>>();
+            self.emit_load_const(item_name_tuple);
+            let module = Identifier::public_with_line(DOT, derived.py_module.clone(), 0);
+            self.emit_import_name_instr(module, names.len());
+            for name in names.iter() {
+                let item = Identifier::public_with_line(DOT, name.clone(), 0);
+                self.emit_import_from_instr(item.clone());
+                self.emit_store_instr(item, Name);
+            }
+            self.emit_pop_top(); // discard IMPORT_FROM object
+        }
+    }
+
+    /// ```python
+    /// f = staticmethod(f)
+    /// g = classmethod(g)
+    /// ```
+    fn emit_non_instance_methods(&mut self, methods: Vec<(MethodKind, Identifier)>) {
+        log!(info "entered {}", fn_name!());
+        for (kind, method) in methods {
+            let wrapper = match kind {
+                MethodKind::Static => "staticmethod",
+                MethodKind::Class => "classmethod",
+                MethodKind::Instance => continue,
+            };
+            self.emit_method_wrapper(wrapper, method, None);
+        }
+    }
+
+    /// ```python
+    /// x = property(x, x__erg_proc__)
+    /// ```
+    fn emit_properties(&mut self, properties: Vec<(Identifier, Option<Identifier>)>) {
+        log!(info "entered {}", fn_name!());
+        for (getter, setter) in properties {
+            self.emit_method_wrapper("property", getter, setter);
+        }
+    }
+
+    /// `method = wrapper(method, extra)`
+    fn emit_method_wrapper(
+        &mut self,
+        wrapper: &'static str,
+        method: Identifier,
+        extra: Option<Identifier>,
+    ) {
+        self.emit_push_null();
+        self.emit_load_name_instr(Identifier::public(wrapper));
+        self.emit_load_name_instr(method.clone());
+        let argc = if let Some(extra) = extra {
+            self.emit_load_name_instr(extra);
+            2
+        } else {
+            1
+        };
+        self.emit_call_instr(argc, Name);
+        self.stack_dec_n(argc);
+        self.emit_store_instr(method, Name);
+    }
+
     fn emit_init_method(&mut self, sig: &Signature, __new__: Type) {
         log!(info "entered {}", fn_name!());
         let new_first_param = __new__.non_default_params().unwrap().first();
@@ -2926,9 +3423,17 @@ impl PyCodeGenerator {
                 }
             }
             // self::base = %x
-            Some(_) => {
+            Some(base_t) => {
                 let expr =
                     Expr::Accessor(Accessor::private_with_line(Str::from(&param_name), line));
+                // mutable containers are initialized by `__init__`, not `__new__`
+                // Array(Int, 3) => list.__init__(self, %x)
+                if let Some(py_base) = Self::container_py_base(base_t) {
+                    let base = Expr::Accessor(Accessor::public_with_line(Str::ever(py_base), line));
+                    let init = Identifier::public_with_line(DOT, Str::ever("__init__"), line);
+                    let slf = Expr::Accessor(Accessor::private_with_line(Str::ever("self"), line));
+                    attrs.push(base.attr_expr(init).call2(slf, expr.clone()));
+                }
                 let obj = Expr::Accessor(Accessor::private_with_line(Str::ever("self"), line));
                 let attr = obj.attr(Identifier::private_with_line(Str::ever("base"), line));
                 let redef = ReDef::new(attr, Block::new(vec![expr]));
@@ -2943,6 +3448,15 @@ impl PyCodeGenerator {
         self.emit_subr_def(Some(class_name), subr_sig, body);
     }
 
+    fn container_py_base(t: &Type) -> Option<&'static str> {
+        match &t.qual_name()[..] {
+            "Array" | "Array!" => Some("list"),
+            "Dict" | "Dict!" => Some("dict"),
+            "Set" | "Set!" => Some("set"),
+            _ => None,
+        }
+    }
+
     /// ```python
     /// class C:
     ///     # __new__ => __call__
@@ -3115,6 +3629,18 @@ impl PyCodeGenerator {
         self.in_op_loaded = true;
     }
 
+    fn load_assert_type(&mut self) {
+        let mod_name = Identifier::public("_erg_std_prelude");
+        self.emit_global_import_items(
+            mod_name,
+            vec![(
+                Identifier::public("assert_type"),
+                Some(Identifier::private("#assert_type")),
+            )],
+        );
+        self.assert_type_loaded = true;
+    }
+
     fn load_mutate_op(&mut self) {
         let mod_name = Identifier::public("_erg_std_prelude");
         self.emit_global_import_items(
@@ -3200,8 +3726,13 @@ impl PyCodeGenerator {
             )],
         );
     }
+}
 
-    pub fn emit(&mut self, hir: HIR) -> CodeObj {
+impl Backend for PyCodeGenerator {
+    type Output = CodeObj;
+    type Fragment = ();
+
+    fn emit_module(&mut self, hir: HIR) -> CodeObj {
         log!(info "the code-generating process has started.{RESET}");
         self.unit_size += 1;
         self.units.push(PyCodeGenUnit::new(
@@ -3258,7 +3789,9 @@ impl PyCodeGenerator {
         // end of flagging
         let unit = self.units.pop().unwrap();
         if !self.units.is_empty() {
-            let ld = unit.prev_lineno - self.cur_block().prev_lineno;
+            let ld = unit
+                .prev_lineno
+                .saturating_sub(self.cur_block().prev_lineno);
             if ld != 0 {
                 if let Some(l) = self.mut_cur_block_codeobj().lnotab.last_mut() {
                     *l += ld as u8;
@@ -3269,4 +3802,28 @@ impl PyCodeGenerator {
         log!(info "the code-generating process has completed.{RESET}");
         unit.codeobj
     }
+
+    fn emit_expr(&mut self, expr: Expr) {
+        PyCodeGenerator::emit_expr(self, expr);
+    }
+
+    fn emit_function(&mut self, sig: SubrSignature, body: DefBody) {
+        self.emit_subr_def(None, sig, body);
+    }
+
+    fn emit_class(&mut self, class_def: ClassDef) {
+        self.emit_class_def(class_def);
+    }
+
+    fn emit_literal(&mut self, lit: Literal) {
+        self.emit_load_const(lit.value);
+    }
+
+    fn emit_call(&mut self, call: Call) {
+        PyCodeGenerator::emit_call(self, call);
+    }
+
+    fn clear(&mut self) {
+        PyCodeGenerator::clear(self);
+    }
 }
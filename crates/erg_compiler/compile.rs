@@ -5,12 +5,14 @@ use std::path::Path;
 
 use erg_common::config::ErgConfig;
 use erg_common::dict::Dict;
-use erg_common::error::MultiErrorDisplay;
+use erg_common::error::{Location, MultiErrorDisplay};
 use erg_common::log;
+use erg_common::timings;
 use erg_common::traits::{ExitStatus, Runnable, Stream};
 use erg_parser::ast::VarName;
 
 use crate::artifact::{CompleteArtifact, ErrorArtifact};
+use crate::backend::Backend;
 use crate::context::{Context, ContextProvider};
 use crate::optimize::HIROptimizer;
 use crate::ty::codeobj::CodeObj;
@@ -19,7 +21,7 @@ use crate::build_hir::HIRBuilder;
 use crate::codegen::PyCodeGenerator;
 use crate::desugar_hir::HIRDesugarer;
 use crate::error::{CompileError, CompileErrors, CompileWarnings};
-use crate::hir::Expr;
+use crate::hir::{Expr, HIR};
 use crate::link_hir::HIRLinker;
 use crate::module::SharedCompilerResource;
 use crate::varinfo::VarInfo;
@@ -183,6 +185,22 @@ impl Runnable for Compiler {
         arti.warns.write_all_stderr();
         Ok(arti.object.code_info(Some(self.code_generator.py_version)))
     }
+
+    fn undo(&mut self) -> bool {
+        self.builder.undo()
+    }
+
+    fn accepted_inputs(&self) -> &[String] {
+        self.builder.accepted_inputs()
+    }
+
+    fn completion_words(&self) -> Vec<String> {
+        self.dir_names()
+    }
+
+    fn stats(&self) -> Option<String> {
+        self.builder.stats()
+    }
 }
 
 impl ContextProvider for Compiler {
@@ -232,13 +250,28 @@ impl Compiler {
         mode: &str,
     ) -> Result<CompleteArtifact<CodeObj>, ErrorArtifact> {
         log!(info "the compiling process has started.");
+        self.check_bytecode_target()?;
         let arti = self.build_optimize_link_desugar(src, mode)?;
-        let codeobj = self.code_generator.emit(arti.object);
+        let codeobj = self.emit_module(arti.object);
         log!(info "code object:\n{}", codeobj.code_info(Some(self.code_generator.py_version)));
         log!(info "the compiling process has completed");
         Ok(CompleteArtifact::new(codeobj, arti.warns))
     }
 
+    /// Compiles `src` with the given backend instead of the CPython bytecode generator.
+    pub fn compile_with<B: Backend>(
+        &mut self,
+        backend: &mut B,
+        src: String,
+        mode: &str,
+    ) -> Result<CompleteArtifact<B::Output>, ErrorArtifact> {
+        log!(info "the compiling process has started.");
+        let arti = self.build_optimize_link_desugar(src, mode)?;
+        let output = backend.emit_module(arti.object);
+        log!(info "the compiling process has completed");
+        Ok(CompleteArtifact::new(output, arti.warns))
+    }
+
     pub fn compile_module(&mut self) -> Result<CompleteArtifact<CodeObj>, ErrorArtifact> {
         let src = self.cfg.input.read();
         self.compile(src, "exec")
@@ -250,14 +283,39 @@ impl Compiler {
         mode: &str,
     ) -> Result<CompleteArtifact<(CodeObj, Option<Expr>)>, ErrorArtifact> {
         log!(info "the compiling process has started.");
+        self.check_bytecode_target()?;
         let arti = self.build_optimize_link_desugar(src, mode)?;
         let last = arti.object.module.last().cloned();
-        let codeobj = self.code_generator.emit(arti.object);
+        let codeobj = self.emit_module(arti.object);
         log!(info "code object:\n{}", codeobj.code_info(Some(self.code_generator.py_version)));
         log!(info "the compiling process has completed");
         Ok(CompleteArtifact::new((codeobj, last), arti.warns))
     }
 
+    fn emit_module(&mut self, hir: HIR) -> CodeObj {
+        let module = self.cfg.input.module_name();
+        timings::measure(&module, "codegen", || self.code_generator.emit_module(hir))
+    }
+
+    /// Only the script generator (transpiler) supports the restricted runtimes.
+    fn check_bytecode_target(&self) -> Result<(), ErrorArtifact> {
+        if self.cfg.target.is_micropython() {
+            let err = CompileError::not_available_on_target_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                "bytecode generation",
+                self.cfg.target,
+                Location::Unknown,
+                "<module>".into(),
+            );
+            return Err(ErrorArtifact::new(
+                CompileErrors::from(err),
+                CompileWarnings::empty(),
+            ));
+        }
+        Ok(())
+    }
+
     fn build_optimize_link_desugar(
         &mut self,
         src: String,
@@ -0,0 +1,70 @@
+//! Defines `AttrCache`, the memoization of attribute lookups.
+//!
+//! Looking up an attribute (or a method) of a nominal type walks all the supertype contexts of the receiver type,
+//! which is repeated for every attribute access.
+//! The results for receiver types without type variables are cached.
+//! The cache is shared by all the scopes of a module and is cleared when a type, a method, or a trait implementation is registered.
+use erg_common::dict::Dict;
+use erg_common::shared::Shared;
+use erg_common::Str;
+
+use crate::context::Context;
+use crate::ty::Type;
+use crate::varinfo::VarInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AttrLookupKind {
+    /// `Context::get_bound_attr_from_nominal_t`
+    BoundAttr,
+    /// `Context::get_method_info_from_nominal_t`
+    Method,
+}
+
+/// (lookup kind, receiver type, attribute name, accessor (`.` or `::`))
+pub type AttrCacheKey = (AttrLookupKind, Type, Str, &'static str);
+
+/// Public attributes are visible from any namespace, so they are cached without the namespace name.
+#[derive(Debug, Clone, Default)]
+pub struct AttrCache(Shared<Dict<(AttrCacheKey, Option<Str>), VarInfo>>);
+
+impl AttrCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &AttrCacheKey, namespace: &Str) -> Option<VarInfo> {
+        let cache = self.0.borrow();
+        cache
+            .get(&(key.clone(), None))
+            .or_else(|| cache.get(&(key.clone(), Some(namespace.clone()))))
+            .cloned()
+    }
+
+    pub fn insert(&self, key: AttrCacheKey, namespace: &Str, vi: &VarInfo) {
+        // the type of a method being defined may be changed
+        if vi.t.has_unbound_var() {
+            return;
+        }
+        let namespace = (!vi.vis.is_public()).then(|| namespace.clone());
+        self.0.borrow_mut().insert((key, namespace), vi.clone());
+    }
+
+    pub fn clear(&self) {
+        self.0.borrow_mut().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+}
+
+impl Context {
+    /// Must be called when the contexts that attribute lookups walk are modified.
+    pub(crate) fn invalidate_attr_cache(&self) {
+        self.attr_cache.clear();
+    }
+}
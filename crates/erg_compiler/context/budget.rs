@@ -0,0 +1,55 @@
+//! Limits the recursion depth and the amount of work of the type checker.
+//!
+//! Deeply nested generic code can make unification (or type evaluation) recurse for a very long time.
+//! `sub_unify`, `eval_t_params` and `substitute_call` enter a `TypeCheckGuard` at each call,
+//! and fail with a "type checking too complex" error when the limits of `ErgConfig` are exceeded.
+use std::cell::Cell;
+
+use erg_common::traits::Locational;
+
+use crate::context::Context;
+use crate::error::{TyCheckError, TyCheckErrors, TyCheckResult};
+
+thread_local! {
+    /// The current nesting depth of the guarded functions
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// The number of the guarded calls since the current top-level check has started
+    static STEPS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Decrements the depth when dropped.
+#[derive(Debug)]
+pub(crate) struct TypeCheckGuard {
+    _priv: (),
+}
+
+impl Drop for TypeCheckGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+impl Context {
+    /// Enters a guarded type checking function.
+    /// The work budget is reset when a top-level check (depth 0) begins.
+    pub(crate) fn enter_type_check(&self, loc: &impl Locational) -> TyCheckResult<TypeCheckGuard> {
+        let depth = DEPTH.with(|depth| depth.get());
+        let steps = if depth == 0 {
+            0
+        } else {
+            STEPS.with(|steps| steps.get())
+        } + 1;
+        STEPS.with(|cell| cell.set(steps));
+        if depth >= self.cfg.max_type_check_depth || steps > self.cfg.max_type_check_steps {
+            return Err(TyCheckErrors::from(TyCheckError::too_complex_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc.loc(),
+                self.caused_by(),
+                depth >= self.cfg.max_type_check_depth,
+            )));
+        }
+        DEPTH.with(|cell| cell.set(depth + 1));
+        Ok(TypeCheckGuard { _priv: () })
+    }
+}
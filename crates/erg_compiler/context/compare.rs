@@ -1,4 +1,5 @@
 //! provides type-comparison
+use std::cell::RefCell;
 use std::option::Option; // conflicting to Type::Option
 
 use erg_common::consts::DEBUG_MODE;
@@ -10,8 +11,8 @@ use erg_common::{Str, Triple};
 
 use crate::context::initialize::const_func::sub_tpdict_get;
 use crate::ty::constructors::{and, bounded, not, or, poly};
-use crate::ty::free::{Constraint, FreeKind, FreeTyVar};
-use crate::ty::typaram::{TyParam, TyParamOrdering};
+use crate::ty::free::{Constraint, FreeKind, FreeTyVar, HasLevel};
+use crate::ty::typaram::{OpKind, TyParam, TyParamOrdering};
 use crate::ty::value::ValueObj;
 use crate::ty::value::ValueObj::Inf;
 use crate::ty::{Field, GuardType, Predicate, RefinementType, SubrKind, SubrType, Type};
@@ -32,6 +33,11 @@ use Credibility::*;
 
 use super::ContextKind;
 
+thread_local! {
+    /// The pairs of types (lhs :> rhs) being judged by unfolding recursive type aliases
+    static REC_ALIAS_ASSUMPTIONS: RefCell<Vec<(Type, Type)>> = const { RefCell::new(vec![]) };
+}
+
 impl Context {
     pub(crate) fn eq_tp(&self, lhs: &TyParam, rhs: &TyParam) -> bool {
         match (lhs, rhs) {
@@ -118,12 +124,31 @@ impl Context {
                 judge
                     || self.structural_supertype_of(lhs, rhs)
                     || self.nominal_supertype_of(lhs, rhs)
+                    || self.rec_alias_supertype_of(lhs, rhs)
             }
         };
         log!("answer: {lhs} {DEBUG_ERROR}:>{RESET} {rhs} == {res}");
         res
     }
 
+    /// Judges after unfolding recursive type aliases.
+    /// The judgement being made is assumed to hold while unfolding (coinduction), so this always terminates.
+    /// e.g. `Json :> Array(Json, _)` => `Int or Str or Array(Json, _) :> Array(Json, _)` => true
+    fn rec_alias_supertype_of(&self, lhs: &Type, rhs: &Type) -> bool {
+        let (l, r) = (self.unfold_rec_alias(lhs), self.unfold_rec_alias(rhs));
+        if l.is_none() && r.is_none() {
+            return false;
+        }
+        let pair = (lhs.clone(), rhs.clone());
+        if REC_ALIAS_ASSUMPTIONS.with(|assumptions| assumptions.borrow().contains(&pair)) {
+            return true;
+        }
+        REC_ALIAS_ASSUMPTIONS.with(|assumptions| assumptions.borrow_mut().push(pair));
+        let res = self.supertype_of(l.as_ref().unwrap_or(lhs), r.as_ref().unwrap_or(rhs));
+        REC_ALIAS_ASSUMPTIONS.with(|assumptions| assumptions.borrow_mut().pop());
+        res
+    }
+
     /// lhs <: rhs ?
     ///
     /// e.g.
@@ -153,6 +178,8 @@ impl Context {
         }
         match (lhs, rhs) {
             (Obj, _) | (_, Never | Failure) => (Absolutely, true),
+            // gradual typing
+            (Mono(n), _) | (_, Mono(n)) if &n[..] == "Dynamic" => (Absolutely, true),
             (_, Obj) if lhs.is_simple_class() => (Absolutely, false),
             (Never | Failure, _) if rhs.is_simple_class() => (Absolutely, false),
             (Complex | Float | Ratio | Int | Nat | Bool, Bool)
@@ -745,7 +772,11 @@ impl Context {
                 let r_fields = self.fields(r);
                 for (l_field, l_ty) in self.fields(l) {
                     if let Some((r_field, r_ty)) = r_fields.get_key_value(&l_field) {
-                        let compatible = self.supertype_of(&l_ty, r_ty);
+                        // Structural { .f = (x: Int) -> Int } :> C (C.f: (self: C, x: Int) -> Int)
+                        let compatible = self.supertype_of(&l_ty, r_ty)
+                            || (!l_ty.is_method()
+                                && Self::bound_method_t(r_ty)
+                                    .is_some_and(|r_ty| self.supertype_of(&l_ty, &r_ty)));
                         if r_field.vis != l_field.vis || !compatible {
                             return false;
                         }
@@ -759,6 +790,19 @@ impl Context {
         }
     }
 
+    /// `(self: C, x: Int) -> Int` => `(x: Int) -> Int`
+    fn bound_method_t(t: &Type) -> Option<Type> {
+        match t {
+            FreeVar(fv) if fv.is_linked() => Self::bound_method_t(&fv.crack()),
+            Subr(subr) if subr.is_method() => {
+                let mut subr = subr.clone();
+                subr.non_default_params.remove(0);
+                Some(Subr(subr))
+            }
+            _ => None,
+        }
+    }
+
     pub fn fields(&self, t: &Type) -> Dict<Field, Type> {
         match t {
             Type::FreeVar(fv) if fv.is_linked() => self.fields(&fv.crack()),
@@ -904,6 +948,20 @@ impl Context {
                     self.same_type_of(&fv_t, &sub_t) || self.same_type_of(&fv_t, &sub_t.derefine())
                 }
             }
+            // e.g. Quantity(Float, "s") :> Quantity(Float, ?V) (impl of `Div(Quantity(T, V))`)
+            (_, TyParam::FreeVar(fv)) if fv.is_unbound() && fv.is_generalized() => {
+                let Some(fv_t) = fv.get_type() else {
+                    return false;
+                };
+                let sup_t = match self.get_tp_t(sup_p) {
+                    Ok(t) => t,
+                    Err(err) => {
+                        log!("supertype_of_tp: {err}");
+                        return false;
+                    }
+                };
+                self.subtype_of(&sup_t, &fv_t)
+            }
             (TyParam::Value(sup), _) => {
                 if let Ok(sup) = Self::convert_value_into_tp(sup.clone()) {
                     self.supertype_of_tp(&sup, sub_p, variance)
@@ -965,6 +1023,20 @@ impl Context {
                 self.same_type_of(l, r.typ()).then_some(Equal),
             (TyParam::Value(ValueObj::Type(l)), TyParam::Type(r)) =>
                 self.same_type_of(l.typ(), r).then_some(Equal),
+            // pred(N) == N - 1, succ(N) == N + 1
+            (TyParam::App { name, args }, r) if args.len() == 1 && matches!(&name[..], "pred" | "succ") => {
+                let op = if &name[..] == "pred" { OpKind::Sub } else { OpKind::Add };
+                self.try_cmp(&TyParam::bin(op, args[0].clone(), TyParam::value(1usize)), r)
+            }
+            (l, TyParam::App { name, args }) if args.len() == 1 && matches!(&name[..], "pred" | "succ") => {
+                let op = if &name[..] == "pred" { OpKind::Sub } else { OpKind::Add };
+                self.try_cmp(l, &TyParam::bin(op, args[0].clone(), TyParam::value(1usize)))
+            }
+            // pred(N) == N - 1
+            (l @ TyParam::App { .. }, r) if l.unfold_succ_pred().is_some() =>
+                self.try_cmp(&l.unfold_succ_pred().unwrap(), r),
+            (l, r @ TyParam::App { .. }) if r.unfold_succ_pred().is_some() =>
+                self.try_cmp(l, &r.unfold_succ_pred().unwrap()),
             // TODO: 型を見て判断する
             (TyParam::BinOp{ op, lhs, rhs }, r) => {
                 if let Ok(evaled) = self.eval_bin_tp(*op, lhs.as_ref().clone(), rhs.as_ref().clone()) {
@@ -1082,12 +1154,21 @@ impl Context {
             (l, r @ (TyParam::Erased(_) | TyParam::FreeVar(_))) =>
                 self.try_cmp(r, l).map(|ord| ord.reverse()),
             (TyParam::App { name, args }, r) => {
-                self.eval_app(name.clone(), args.clone()).ok()
-                    .and_then(|tp| self.try_cmp(&tp, r))
+                let evaled = self.eval_app(name.clone(), args.clone()).ok()?;
+                // cannot be reduced: only `unit_mul(?M, ?N)` vs `unit_mul(?M, ?N)` is comparable
+                if &evaled == l {
+                    (l == r).then_some(Any)
+                } else {
+                    self.try_cmp(&evaled, r)
+                }
             }
             (l, TyParam::App { name, args }) => {
-                self.eval_app(name.clone(), args.clone()).ok()
-                    .and_then(|tp| self.try_cmp(l, &tp))
+                let evaled = self.eval_app(name.clone(), args.clone()).ok()?;
+                if &evaled == r {
+                    (l == r).then_some(Any)
+                } else {
+                    self.try_cmp(l, &evaled)
+                }
             }
             (_l, _r) => {
                 erg_common::fmt_dbg!(_l, _r,);
@@ -1472,9 +1553,11 @@ impl Context {
                 (self.is_super_pred_of(l1, l2) && self.is_super_pred_of(r1, r2))
                     || (self.is_super_pred_of(l1, r2) && self.is_super_pred_of(r1, l2))
             }
+            // {1, 2, 3} :> {1, 3, 2} == { I == 1 or I == 2 or I == 3 } :> { I == 1 or I == 3 or I == 2 }
             (Pred::Or(l1, r1), Pred::Or(l2, r2)) => {
                 (self.is_super_pred_of(l1, l2) && self.is_super_pred_of(r1, r2))
                     || (self.is_super_pred_of(l1, r2) && self.is_super_pred_of(r1, l2))
+                    || (self.is_super_pred_of(lhs, l2) && self.is_super_pred_of(lhs, r2))
             }
             (lhs, Pred::And(l, r)) => {
                 self.is_super_pred_of(lhs, l) || self.is_super_pred_of(lhs, r)
@@ -1,8 +1,10 @@
+use std::cmp::Ordering;
 use std::mem;
 
 use erg_common::consts::DEBUG_MODE;
 use erg_common::dict::Dict;
 use erg_common::error::Location;
+use erg_common::fresh::FRESH_GEN;
 #[allow(unused)]
 use erg_common::log;
 use erg_common::set::Set;
@@ -17,10 +19,10 @@ use erg_parser::ast::*;
 use erg_parser::token::{Token, TokenKind};
 
 use crate::ty::constructors::{
-    array_t, dict_t, mono, poly, proj, proj_call, ref_, ref_mut, refinement, set_t, subr_t,
-    tp_enum, tuple_t, v_enum,
+    array_t, dict_t, free_var, mono, poly, proj, proj_call, ref_, ref_mut, refinement, set_t,
+    subr_t, tp_enum, tuple_t, ty_tp, v_enum,
 };
-use crate::ty::free::{FreeTyVar, HasLevel};
+use crate::ty::free::{Constraint, FreeTyVar, HasLevel};
 use crate::ty::typaram::{OpKind, TyParam};
 use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
 use crate::ty::{ConstSubr, HasType, Predicate, SubrKind, Type, UserConstSubr, ValueArgs};
@@ -28,6 +30,7 @@ use crate::ty::{ConstSubr, HasType, Predicate, SubrKind, Type, UserConstSubr, Va
 use crate::context::instantiate_spec::ParamKind;
 use crate::context::{ClassDefType, Context, ContextKind, RegistrationMode};
 use crate::error::{EvalError, EvalErrors, EvalResult, SingleEvalResult};
+use crate::AccessKind;
 
 use super::instantiate::TyVarCache;
 use Type::{Failure, Never, Subr};
@@ -44,6 +47,38 @@ macro_rules! unreachable_error {
 }
 
 #[inline]
+/// Whether `expr` refers to any of `names`.
+/// Only the expressions that can be evaluated at compile time are inspected.
+fn refers_to(expr: &Expr, names: &[&str]) -> bool {
+    let args_refer = |args: &Args| {
+        args.pos_args()
+            .iter()
+            .any(|arg| refers_to(&arg.expr, names))
+            || args.kw_args().iter().any(|arg| refers_to(&arg.expr, names))
+    };
+    match expr {
+        Expr::Accessor(Accessor::Ident(ident)) => names.contains(&&ident.inspect()[..]),
+        Expr::Accessor(Accessor::Attr(attr)) => refers_to(&attr.obj, names),
+        Expr::Accessor(Accessor::TupleAttr(attr)) => refers_to(&attr.obj, names),
+        Expr::Accessor(Accessor::Subscr(subscr)) => {
+            refers_to(&subscr.obj, names) || refers_to(&subscr.index, names)
+        }
+        Expr::Accessor(Accessor::TypeApp(tapp)) => refers_to(&tapp.obj, names),
+        Expr::BinOp(bin) => bin.args.iter().any(|arg| refers_to(arg, names)),
+        Expr::UnaryOp(unary) => refers_to(&unary.args[0], names),
+        Expr::Call(call) => refers_to(&call.obj, names) || args_refer(&call.args),
+        Expr::Array(Array::Normal(arr)) => args_refer(&arr.elems),
+        Expr::Tuple(Tuple::Normal(tuple)) => args_refer(&tuple.elems),
+        Expr::Record(Record::Normal(rec)) => rec
+            .attrs
+            .iter()
+            .any(|def| def.body.block.iter().any(|expr| refers_to(expr, names))),
+        Expr::TypeAscription(tasc) => refers_to(&tasc.expr, names),
+        Expr::Lambda(lambda) => lambda.body.iter().any(|expr| refers_to(expr, names)),
+        _ => false,
+    }
+}
+
 pub fn type_from_token_kind(kind: TokenKind) -> Type {
     use TokenKind::*;
 
@@ -219,6 +254,10 @@ impl Context {
         let lhs = self.eval_const_expr(&bin.args[0])?;
         let rhs = self.eval_const_expr(&bin.args[1])?;
         let op = self.try_get_op_kind_from_token(&bin.op)?;
+        // operators overloaded by a user-defined class (e.g. `C.__add__`)
+        if let Some(method) = self.get_const_method(&lhs, op_to_name(op)) {
+            return self.call(method, ValueArgs::new(vec![lhs, rhs], dict! {}), bin.loc());
+        }
         self.eval_bin(op, lhs, rhs)
     }
 
@@ -243,19 +282,38 @@ impl Context {
     }
 
     fn eval_const_call(&self, call: &Call) -> EvalResult<ValueObj> {
+        if let Some(attr) = call.attr_name.as_ref() {
+            return self.eval_const_method_call(&call.obj, attr, &call.args, call.loc());
+        }
         if let Expr::Accessor(acc) = call.obj.as_ref() {
             match acc {
                 Accessor::Ident(ident) => {
-                    let obj = self.rec_get_const_obj(ident.inspect()).ok_or_else(|| {
-                        EvalError::no_var_error(
+                    let Some(obj) = self.rec_get_const_obj(ident.inspect()) else {
+                        if let Some(res) = self.eval_builtin_func_call(ident, &call.args) {
+                            return res;
+                        }
+                        return Err(EvalErrors::from(EvalError::no_var_error(
                             self.cfg.input.clone(),
                             line!() as usize,
                             ident.loc(),
                             self.caused_by(),
                             ident.inspect(),
                             self.get_similar_name(ident.inspect()),
-                        )
-                    })?;
+                        )));
+                    };
+                    // e.g. `Array(Int, 3)`
+                    if let ValueObj::Type(type_obj) = obj {
+                        if let Type::Poly { .. } = type_obj.typ() {
+                            let args = self.eval_args(&call.args)?;
+                            let t = self.eval_type_app(type_obj.typ(), args, call.loc())?;
+                            let mut type_obj = type_obj.clone();
+                            match &mut type_obj {
+                                TypeObj::Builtin { t: typ, .. } => *typ = t,
+                                TypeObj::Generated(gen) => *gen.typ_mut() = t,
+                            }
+                            return Ok(ValueObj::Type(type_obj));
+                        }
+                    }
                     let subr = option_enum_unwrap!(obj, ValueObj::Subr)
                         .ok_or_else(|| {
                             EvalError::type_mismatch_error(
@@ -275,13 +333,9 @@ impl Context {
                     let args = self.eval_args(&call.args)?;
                     self.call(subr, args, call.loc())
                 }
-                // TODO: eval attr
-                Accessor::Attr(_attr) => Err(EvalErrors::from(EvalError::not_const_expr(
-                    self.cfg.input.clone(),
-                    line!() as usize,
-                    call.loc(),
-                    self.caused_by(),
-                ))),
+                Accessor::Attr(attr) => {
+                    self.eval_const_method_call(&attr.obj, &attr.ident, &call.args, call.loc())
+                }
                 // TODO: eval type app
                 Accessor::TypeApp(_type_app) => Err(EvalErrors::from(EvalError::not_const_expr(
                     self.cfg.input.clone(),
@@ -301,11 +355,161 @@ impl Context {
         }
     }
 
+    /// Evaluates the builtin (non-constant) functions that can be computed at compile time.
+    /// e.g. `str(1)` (used in string interpolation)
+    fn eval_builtin_func_call(
+        &self,
+        ident: &Identifier,
+        args: &Args,
+    ) -> Option<EvalResult<ValueObj>> {
+        // the function may be shadowed
+        let is_builtin = self
+            .rec_get_var_info(ident, AccessKind::Name, &self.cfg.input, self)
+            .map_ok_or(false, |vi| &vi.vis.def_namespace[..] == "<builtins>");
+        if !is_builtin || &ident.inspect()[..] != "str" {
+            return None;
+        }
+        let args = match self.eval_args(args) {
+            Ok(args) => args,
+            Err(errs) => return Some(Err(errs)),
+        };
+        let res = match &args.pos_args[..] {
+            [obj] if args.kw_args.is_empty() => obj.try_str().map(ValueObj::Str),
+            _ => None,
+        };
+        Some(res.ok_or_else(|| {
+            EvalErrors::from(EvalError::not_const_expr(
+                self.cfg.input.clone(),
+                line!() as usize,
+                ident.loc(),
+                self.caused_by(),
+            ))
+        }))
+    }
+
+    /// e.g. `"a".upper()`, `D.get("a")`, `C.new {.x = 1}`, `m.F(1)`
+    fn eval_const_method_call(
+        &self,
+        receiver: &Expr,
+        attr: &Identifier,
+        args: &Args,
+        loc: Location,
+    ) -> EvalResult<ValueObj> {
+        let obj = match self.eval_const_expr(receiver) {
+            Ok(obj) => obj,
+            Err(errs) => {
+                // e.g. `m = import "m"; m.F(1)`
+                let Expr::Accessor(acc) = receiver else {
+                    return Err(errs);
+                };
+                let Some(ValueObj::Subr(subr)) = self
+                    .get_mod_ctx_from_acc(acc)
+                    .and_then(|mod_ctx| mod_ctx.eval_const_ident(attr).ok())
+                else {
+                    return Err(errs);
+                };
+                let args = self.eval_args(args)?;
+                return self.call(subr, args, loc);
+            }
+        };
+        let mut args = self.eval_args(args)?;
+        if args.kw_args.is_empty() {
+            if let Some(res) = obj.try_call_method(attr.inspect(), &args.pos_args) {
+                return Ok(res);
+            }
+        }
+        // the default constructor of a user-defined class
+        if let (ValueObj::Type(TypeObj::Generated(gen)), "new", [ValueObj::Record(rec)]) =
+            (&obj, &attr.inspect()[..], &args.pos_args[..])
+        {
+            return Ok(ValueObj::DataClass {
+                name: gen.typ().qual_name(),
+                fields: rec.clone(),
+            });
+        }
+        if let Some(method) = self.get_const_method(&obj, attr.inspect()) {
+            args.pos_args.insert(0, obj);
+            return self.call(method, args, loc);
+        }
+        match self.eval_attr(obj, attr)? {
+            ValueObj::Subr(subr) => self.call(subr, args, loc),
+            other => Err(EvalErrors::from(EvalError::type_mismatch_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                attr.loc(),
+                self.caused_by(),
+                attr.inspect(),
+                None,
+                &mono("Subroutine"),
+                &other.t(),
+                self.get_candidates(&other.t()),
+                None,
+            ))),
+        }
+    }
+
+    /// Returns the method `name` of a user-defined class whose body is a constant expression (see `Context::register_const_method`).
+    /// Builtin methods are evaluated by `ValueObj::try_call_method`.
+    fn get_const_method(&self, obj: &ValueObj, name: &str) -> Option<ConstSubr> {
+        let ValueObj::DataClass { .. } = obj else {
+            return None;
+        };
+        for ctx in self.get_nominal_super_type_ctxs(&obj.class())? {
+            let method = ctx.consts.get(name).or_else(|| {
+                ctx.methods_list
+                    .iter()
+                    .find_map(|(_, methods)| methods.consts.get(name))
+            });
+            if let Some(ValueObj::Subr(subr @ ConstSubr::User(_))) = method {
+                return Some(subr.clone());
+            }
+        }
+        None
+    }
+
+    /// Applies type arguments to a polymorphic type (e.g. `Array(Int, 3)`).
+    /// Omitted parameters are erased: `Array(Int)` == `Array(Int, _: Nat)`.
+    /// Fails if the arguments do not match the parameters
+    /// (the error is reported when the call is type-checked against the meta-type, see `register_poly_type`).
+    fn eval_type_app(&self, t: &Type, args: ValueArgs, loc: Location) -> EvalResult<Type> {
+        let Some((_, ctx)) = self.get_nominal_type_ctx(t) else {
+            return Err(EvalErrors::from(EvalError::not_const_expr(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc,
+                self.caused_by(),
+            )));
+        };
+        if args.pos_args.len() > ctx.params.len() {
+            return Err(EvalErrors::empty());
+        }
+        let mut pos_args = args.pos_args.into_iter();
+        let mut kw_args = args.kw_args;
+        let mut params = vec![];
+        for (param_name, vi) in ctx.params.iter() {
+            let arg = pos_args.next().or_else(|| {
+                param_name
+                    .as_ref()
+                    .and_then(|name| kw_args.remove(name.inspect()))
+            });
+            let tp = match arg {
+                Some(arg) => match Self::convert_value_into_tp(arg) {
+                    Ok(tp) | Err(tp) => tp,
+                },
+                None => TyParam::erased(vi.t.clone()),
+            };
+            params.push(tp);
+        }
+        // unknown keyword arguments (or the ones already passed as positional arguments)
+        if !kw_args.is_empty() {
+            return Err(EvalErrors::empty());
+        }
+        Ok(poly(t.qual_name(), params))
+    }
+
     fn call(&self, subr: ConstSubr, args: ValueArgs, loc: Location) -> EvalResult<ValueObj> {
         match subr {
-            ConstSubr::User(_user) => {
-                feature_error!(self, loc, "calling user-defined subroutines").map_err(Into::into)
-            }
+            ConstSubr::User(user) => self.call_user_subr(&user, args, loc),
             ConstSubr::Builtin(builtin) => builtin.call(args, self).map_err(|mut e| {
                 if e.0.loc.is_unknown() {
                     e.0.loc = loc;
@@ -319,6 +523,70 @@ impl Context {
         }
     }
 
+    /// Calls a user-defined constant subroutine (e.g. `F = x -> x + 1`)
+    /// by evaluating the body with the arguments bound to the parameters.
+    fn call_user_subr(
+        &self,
+        user: &UserConstSubr,
+        mut args: ValueArgs,
+        loc: Location,
+    ) -> EvalResult<ValueObj> {
+        // recursive calls are limited in the same way as the type checking
+        let _guard = self.enter_type_check(&loc)?;
+        let not_const = || {
+            EvalErrors::from(EvalError::not_const_expr(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc,
+                self.caused_by(),
+            ))
+        };
+        // HACK: should avoid cloning
+        let mut subr_ctx = Context::instant(
+            user.name().clone(),
+            self.cfg.clone(),
+            0,
+            self.shared.clone(),
+            self.clone(),
+        );
+        let params = user.params();
+        let mut pos_args = args.pos_args.into_iter();
+        for param in params.non_defaults.iter() {
+            let arg = pos_args
+                .next()
+                .or_else(|| param.inspect().and_then(|name| args.kw_args.remove(name)))
+                .ok_or_else(not_const)?;
+            if let Some(name) = param.name() {
+                subr_ctx.consts.insert(name.clone(), arg);
+            }
+        }
+        for param in params.defaults.iter() {
+            let arg = if params.var_params.is_none() {
+                pos_args.next()
+            } else {
+                None
+            };
+            let arg =
+                match arg.or_else(|| param.inspect().and_then(|name| args.kw_args.remove(name))) {
+                    Some(arg) => arg,
+                    None => self.eval_const_expr(&param.default_val)?,
+                };
+            if let Some(name) = param.sig.name() {
+                subr_ctx.consts.insert(name.clone(), arg);
+            }
+        }
+        if let Some(param) = params.var_params.as_ref() {
+            if let Some(name) = param.name() {
+                let rest = ValueObj::Array(pos_args.by_ref().collect());
+                subr_ctx.consts.insert(name.clone(), rest);
+            }
+        }
+        if pos_args.next().is_some() || !args.kw_args.is_empty() {
+            return Err(not_const());
+        }
+        subr_ctx.eval_const_block(&user.block().clone().downgrade())
+    }
+
     fn eval_const_def(&mut self, def: &Def) -> EvalResult<ValueObj> {
         if def.is_const() {
             let __name__ = def.sig.ident().unwrap().inspect();
@@ -365,6 +633,25 @@ impl Context {
                 }
                 Ok(ValueObj::Array(ArcArray::from(elems)))
             }
+            // e.g. `[Int; _]`, `[Str; 3]`
+            Array::WithLength(arr) => {
+                let elem = self.eval_const_expr(&arr.elem.expr)?;
+                let len = match arr.len.as_ref() {
+                    Expr::Accessor(Accessor::Ident(ident)) if &ident.inspect()[..] == "_" => {
+                        TyParam::erased(Type::Nat)
+                    }
+                    other => TyParam::value(self.eval_const_expr(other)?),
+                };
+                match self.convert_value_into_type(elem) {
+                    Ok(elem_t) => Ok(ValueObj::builtin_class(array_t(elem_t, len))),
+                    Err(_) => Err(EvalErrors::from(EvalError::not_const_expr(
+                        self.cfg.input.clone(),
+                        line!() as usize,
+                        arr.loc(),
+                        self.caused_by(),
+                    ))),
+                }
+            }
             _ => Err(EvalErrors::from(EvalError::not_const_expr(
                 self.cfg.input.clone(),
                 line!() as usize,
@@ -512,7 +799,30 @@ impl Context {
             self.shared.clone(),
             self.clone(),
         );
-        let return_t = v_enum(set! {lambda_ctx.eval_const_block(&lambda.body)?});
+        // the body cannot be evaluated here if it refers to the parameters (e.g. `x -> x + 1`),
+        // then the return type is generalized (`|T| (x: Obj) -> T`)
+        let (return_t, generic) = match lambda_ctx.eval_const_block(&lambda.body) {
+            Ok(ret) => (v_enum(set! {ret}), false),
+            Err(errs) => {
+                let params = lambda
+                    .sig
+                    .params
+                    .non_defaults
+                    .iter()
+                    .chain(lambda.sig.params.var_params.as_deref())
+                    .chain(lambda.sig.params.defaults.iter().map(|p| &p.sig))
+                    .filter_map(|p| p.inspect().map(|name| &name[..]))
+                    .collect::<Vec<_>>();
+                // e.g. `(self: T, other: U) -> V` in a type specification is not a constant lambda
+                if !lambda.body.iter().any(|expr| refers_to(expr, &params)) {
+                    return Err(errs);
+                }
+                (
+                    free_var(self.level + 1, Constraint::new_type_of(Type::Type)),
+                    true,
+                )
+            }
+        };
         let sig_t = subr_t(
             SubrKind::from(lambda.op.kind),
             non_default_params.clone(),
@@ -520,6 +830,11 @@ impl Context {
             default_params.clone(),
             return_t,
         );
+        let sig_t = if generic {
+            self.generalize_t(sig_t)
+        } else {
+            sig_t
+        };
         let block =
             erg_parser::Parser::validate_const_block(lambda.body.clone()).map_err(|_| {
                 EvalErrors::from(EvalError::not_const_expr(
@@ -695,6 +1010,15 @@ impl Context {
                 (ValueObj::Bool(l), ValueObj::Bool(r)) => Ok(ValueObj::Bool(l || r)),
                 (ValueObj::Int(l), ValueObj::Int(r)) => Ok(ValueObj::Int(l | r)),
                 (ValueObj::Type(lhs), ValueObj::Type(rhs)) => Ok(self.eval_or_type(lhs, rhs)),
+                // e.g. `Int or {Str: Int}`
+                (ValueObj::Type(lhs), rhs) => {
+                    let rhs = self.convert_value_as_type_obj(rhs)?;
+                    Ok(self.eval_or_type(lhs, rhs))
+                }
+                (lhs, ValueObj::Type(rhs)) => {
+                    let lhs = self.convert_value_as_type_obj(lhs)?;
+                    Ok(self.eval_or_type(lhs, rhs))
+                }
                 _ => Err(EvalErrors::from(EvalError::unreachable(
                     self.cfg.input.clone(),
                     fn_name!(),
@@ -705,6 +1029,14 @@ impl Context {
                 (ValueObj::Bool(l), ValueObj::Bool(r)) => Ok(ValueObj::Bool(l && r)),
                 (ValueObj::Int(l), ValueObj::Int(r)) => Ok(ValueObj::Int(l & r)),
                 (ValueObj::Type(lhs), ValueObj::Type(rhs)) => Ok(self.eval_and_type(lhs, rhs)),
+                (ValueObj::Type(lhs), rhs) => {
+                    let rhs = self.convert_value_as_type_obj(rhs)?;
+                    Ok(self.eval_and_type(lhs, rhs))
+                }
+                (lhs, ValueObj::Type(rhs)) => {
+                    let lhs = self.convert_value_as_type_obj(lhs)?;
+                    Ok(self.eval_and_type(lhs, rhs))
+                }
                 _ => Err(EvalErrors::from(EvalError::unreachable(
                     self.cfg.input.clone(),
                     fn_name!(),
@@ -728,6 +1060,22 @@ impl Context {
         }
     }
 
+    /// Used for the operands of `or`/`and` such as `{Str: Int}` (a dict value)
+    fn convert_value_as_type_obj(&self, val: ValueObj) -> EvalResult<TypeObj> {
+        self.convert_value_into_type(val)
+            .map(|t| TypeObj::Builtin {
+                t,
+                meta_t: Type::ClassType,
+            })
+            .map_err(|_| {
+                EvalErrors::from(EvalError::unreachable(
+                    self.cfg.input.clone(),
+                    fn_name!(),
+                    line!(),
+                ))
+            })
+    }
+
     fn eval_or_type(&self, lhs: TypeObj, rhs: TypeObj) -> ValueObj {
         match (lhs, rhs) {
             (
@@ -915,6 +1263,13 @@ impl Context {
         }
     }
 
+    fn eval_min_func(&self, lhs: ValueObj, rhs: ValueObj) -> ValueObj {
+        match lhs.try_cmp(&rhs) {
+            Some(Ordering::Greater) => rhs,
+            _ => lhs,
+        }
+    }
+
     fn eval_pred_func(&self, val: ValueObj) -> EvalResult<ValueObj> {
         match val {
             ValueObj::Bool(_) => Ok(ValueObj::Nat(0)),
@@ -932,6 +1287,11 @@ impl Context {
     }
 
     pub(crate) fn eval_app(&self, name: Str, args: Vec<TyParam>) -> EvalResult<TyParam> {
+        // e.g. min(2, 2 + 2) => min(2, 4)
+        let args = args
+            .into_iter()
+            .map(|tp| self.eval_tp(tp))
+            .collect::<EvalResult<Vec<_>>>()?;
         if let Ok(mut value_args) = args
             .iter()
             .map(|tp| self.convert_tp_into_value(tp.clone()))
@@ -944,7 +1304,17 @@ impl Context {
                 "pred" => self
                     .eval_pred_func(value_args.remove(0))
                     .map(TyParam::Value),
+                "min" if value_args.len() == 2 => {
+                    let rhs = value_args.remove(1);
+                    let lhs = value_args.remove(0);
+                    Ok(TyParam::Value(self.eval_min_func(lhs, rhs)))
+                }
                 _ => {
+                    if let Some(ValueObj::Subr(subr)) = self.rec_get_const_obj(&name) {
+                        let args = ValueArgs::new(value_args, dict! {});
+                        let value = self.call(subr.clone(), args, Location::Unknown)?;
+                        return Ok(TyParam::Value(value));
+                    }
                     log!(err "eval_app({name}({}))", fmt_vec(&args));
                     Ok(TyParam::app(name, args))
                 }
@@ -955,6 +1325,17 @@ impl Context {
         }
     }
 
+    /// Whether `name(...)` is an application of a const function that returns a non-type value (e.g. `unit_mul(M, N)`).
+    pub(crate) fn is_value_app(&self, name: &str) -> bool {
+        match self.rec_get_const_obj(name) {
+            Some(ValueObj::Subr(subr)) => subr
+                .sig_t()
+                .return_t()
+                .is_some_and(|t| !matches!(t, Type::Type | Type::ClassType | Type::TraitType)),
+            _ => false,
+        }
+    }
+
     /// Quantified variables, etc. are returned as is.
     /// 量化変数などはそのまま返す
     pub(crate) fn eval_tp(&self, p: TyParam) -> EvalResult<TyParam> {
@@ -1015,6 +1396,9 @@ impl Context {
         level: usize,
         t_loc: &impl Locational,
     ) -> Result<Type, (Type, EvalErrors)> {
+        let _guard = self
+            .enter_type_check(t_loc)
+            .map_err(|errs| (Failure, errs))?;
         match substituted {
             Type::FreeVar(fv) if fv.is_linked() => {
                 self.eval_t_params(fv.crack().clone(), level, t_loc)
@@ -1065,9 +1449,15 @@ impl Context {
                 }
             }
             Type::Refinement(refine) => {
-                let pred = self
-                    .eval_pred(*refine.pred)
-                    .map_err(|errs| (Failure, errs))?;
+                let pred = self.eval_pred(*refine.pred).map_err(|mut errs| {
+                    // e.g. `{unit_div("m", "s^x")}`: errors raised by const functions have no location
+                    for err in errs.iter_mut() {
+                        if err.core.loc.is_unknown() {
+                            err.core.loc = t_loc.loc();
+                        }
+                    }
+                    (Failure, errs)
+                })?;
                 Ok(refinement(refine.var, *refine.t, pred))
             }
             // [?T; 0].MutType! == [?T; !0]
@@ -1327,6 +1717,9 @@ impl Context {
             TyParam::FreeVar(fv) if fv.is_linked() => self.convert_tp_into_type(fv.crack().clone()),
             TyParam::Type(t) => Ok(t.as_ref().clone()),
             TyParam::Mono(name) => Ok(Type::Mono(name)),
+            TyParam::App { name, args } if self.is_value_app(&name) => {
+                Err(TyParam::App { name, args })
+            }
             TyParam::App { name, args } => Ok(Type::Poly { name, params: args }),
             TyParam::Proj { obj, attr } => {
                 let lhs = self.convert_tp_into_type(*obj)?;
@@ -1341,6 +1734,9 @@ impl Context {
 
     pub(crate) fn convert_tp_into_value(&self, tp: TyParam) -> Result<ValueObj, TyParam> {
         match tp {
+            TyParam::FreeVar(fv) if fv.is_linked() => {
+                self.convert_tp_into_value(fv.crack().clone())
+            }
             TyParam::Value(v) => Ok(v),
             other => Err(other),
         }
@@ -1389,6 +1785,22 @@ impl Context {
                 Ok(array_t(union, len))
             }
             ValueObj::Set(set) => Ok(v_enum(set)),
+            ValueObj::Dict(dic) => {
+                let mut new_dic = dict! {};
+                for (k, v) in dic.iter() {
+                    let k = match self.convert_value_into_type(k.clone()) {
+                        Ok(k) => Ok(k),
+                        // typed dict (e.g. `{"name": Str}`): the key `"name"` means `{"name"}`
+                        Err(k @ ValueObj::Str(_)) => Ok(v_enum(set! { k })),
+                        Err(k) => Err(k),
+                    };
+                    let (Ok(k), Ok(v)) = (k, self.convert_value_into_type(v.clone())) else {
+                        return Err(ValueObj::Dict(dic));
+                    };
+                    new_dic.insert(TyParam::t(k), TyParam::t(v));
+                }
+                Ok(dict_t(TyParam::Dict(new_dic)))
+            }
             ValueObj::Subr(subr) => subr.as_type(self).ok_or(ValueObj::Subr(subr)),
             other => Err(other),
         }
@@ -1596,6 +2008,16 @@ impl Context {
                 }
                 poly(name, new_params)
             }
+            Type::Or(l, r) => {
+                let l = self.detach(*l, tv_cache);
+                let r = self.detach(*r, tv_cache);
+                self.union(&l, &r)
+            }
+            Type::And(l, r) => {
+                let l = self.detach(*l, tv_cache);
+                let r = self.detach(*r, tv_cache);
+                self.intersection(&l, &r)
+            }
             _ => ty,
         }
     }
@@ -1772,7 +2194,11 @@ impl Context {
         level: usize,
         t_loc: &impl Locational,
     ) -> EvalResult<Type> {
-        let t = self.get_tp_t(&lhs)?;
+        let t = match &lhs {
+            // the methods of record types are defined in `Record` (e.g. `{.name = Str}.as_dict()`)
+            TyParam::Type(t) if t.is_record() => mono("Record"),
+            _ => self.get_tp_t(&lhs)?,
+        };
         for ty_ctx in self.get_nominal_super_type_ctxs(&t).ok_or_else(|| {
             EvalError::type_not_found(
                 self.cfg.input.clone(),
@@ -1906,6 +2332,180 @@ impl Context {
         }
     }
 
+    /// The bounds of an `Int`/`Nat` (refinement) type. `None` if `t` is not an integer type.
+    ///
+    /// e.g. `0..10` => `Some((Some(0), Some(10)))`, `Nat` => `Some((Some(0), None))`
+    fn int_interval_of(&self, t: &Type) -> Option<(Option<i64>, Option<i64>)> {
+        let to_i64 = |tp: Option<&TyParam>| match tp.map(|tp| self.eval_tp(tp.clone())) {
+            Some(Ok(TyParam::Value(ValueObj::Nat(n)))) => i64::try_from(n).ok(),
+            Some(Ok(TyParam::Value(ValueObj::Int(i)))) => Some(i64::from(i)),
+            _ => None,
+        };
+        match t {
+            Type::FreeVar(fv) if fv.is_linked() => self.int_interval_of(&fv.crack()),
+            Type::Nat => Some((Some(0), None)),
+            Type::Int => Some((None, None)),
+            Type::Refinement(refine) if matches!(refine.t.as_ref(), Type::Int | Type::Nat) => {
+                let pred = self.eval_pred(*refine.pred.clone()).ok()?;
+                let (min, max) = pred.min_max(None, None);
+                let min = to_i64(min);
+                let min = if refine.t.as_ref() == &Type::Nat {
+                    Some(min.unwrap_or(0).max(0))
+                } else {
+                    min
+                };
+                Some((min, to_i64(max)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The type of `partial(f, *args)` (`f.bind(*args)`), computed by the const function `Partial`.
+    ///
+    /// e.g. `partial((x: Int, y: Str) -> Bool, 1)` => `(y: Str) -> Bool`
+    pub(crate) fn eval_partial_t<'a>(
+        &self,
+        callee: &Type,
+        bound: impl Iterator<Item = &'a Type>,
+        loc: &impl Locational,
+    ) -> EvalResult<Type> {
+        let Some(ValueObj::Subr(partial)) = self.rec_get_const_obj("Partial") else {
+            return feature_error!(self, loc.loc(), "Partial");
+        };
+        let bound = bound.map(|t| ValueObj::builtin_type(t.clone())).collect();
+        let args = ValueArgs::new(
+            vec![
+                ValueObj::builtin_type(callee.clone()),
+                ValueObj::Array(bound),
+            ],
+            dict! {},
+        );
+        let residual = self.call(partial.clone(), args, loc.loc())?;
+        self.convert_value_into_type(residual).map_err(|value| {
+            EvalErrors::from(EvalError::feature_error(
+                self.cfg.input.clone(),
+                loc.loc(),
+                &format!("partial application returning {value}"),
+                self.caused_by(),
+            ))
+        })
+    }
+
+    /// `lens({.name = Str}, "name") == Lens({.name = Str}, Str)`
+    ///
+    /// Only the public fields of a record type can be accessed through a lens.
+    pub(crate) fn eval_lens_t(&self, record: &Expr, field: &Expr) -> EvalResult<Type> {
+        let record_obj = self.eval_const_expr(record)?;
+        let record_t = match self.convert_value_into_type(record_obj.clone()) {
+            Ok(t @ Type::Record(_)) => t,
+            _ => {
+                return Err(EvalErrors::from(EvalError::type_mismatch_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    record.loc(),
+                    self.caused_by(),
+                    "record",
+                    Some(1),
+                    &mono("Record"),
+                    &record_obj.t(),
+                    None,
+                    None,
+                )));
+            }
+        };
+        let ValueObj::Str(name) = self.eval_const_expr(field)? else {
+            return feature_error!(self, field.loc(), "non-literal lens field");
+        };
+        let Type::Record(fields) = &record_t else {
+            unreachable!()
+        };
+        let Some((_, field_t)) = fields
+            .iter()
+            .find(|(f, _)| f.symbol == name && f.vis.is_public())
+        else {
+            return Err(EvalErrors::from(EvalError::no_attr_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                field.loc(),
+                self.caused_by(),
+                &record_t,
+                &name,
+                None,
+            )));
+        };
+        let field_t = field_t.clone();
+        Ok(poly("Lens", vec![ty_tp(record_t), ty_tp(field_t)]))
+    }
+
+    /// Computes the refinement type of `lhs op rhs` from the bounds of the operands.
+    /// Bounds that overflow `i64` are dropped (i.e. become unbounded).
+    /// Returns `None` if nothing more precise than `Int`/`Nat` can be derived.
+    ///
+    /// e.g. `0..10 + 0..5` => `0..15`, `0..10 - 0..5` => `-5..10`
+    pub(crate) fn eval_refinement_bin(&self, op: OpKind, lhs: &Type, rhs: &Type) -> Option<Type> {
+        let (l_min, l_max) = self.int_interval_of(lhs)?;
+        let (r_min, r_max) = self.int_interval_of(rhs)?;
+        // literal arithmetic (e.g. `1 + 2`) is handled elsewhere
+        if l_min.is_some() && l_min == l_max && r_min.is_some() && r_min == r_max {
+            return None;
+        }
+        let add = |l: Option<i64>, r: Option<i64>| l?.checked_add(r?);
+        let sub = |l: Option<i64>, r: Option<i64>| l?.checked_sub(r?);
+        let mul = |l: Option<i64>, r: Option<i64>| l?.checked_mul(r?);
+        let (min, max) = match op {
+            OpKind::Add => (add(l_min, r_min), add(l_max, r_max)),
+            OpKind::Sub => (sub(l_min, r_max), sub(l_max, r_min)),
+            OpKind::Mul => {
+                let bounds = [l_min, l_max, r_min, r_max];
+                if bounds.iter().all(Option::is_some) {
+                    let products = [
+                        mul(l_min, r_min),
+                        mul(l_min, r_max),
+                        mul(l_max, r_min),
+                        mul(l_max, r_max),
+                    ];
+                    if products.iter().all(Option::is_some) {
+                        (
+                            products.iter().flatten().min().copied(),
+                            products.iter().flatten().max().copied(),
+                        )
+                    } else {
+                        return None;
+                    }
+                } else if l_min.is_some_and(|n| n >= 0) && r_min.is_some_and(|n| n >= 0) {
+                    (mul(l_min, r_min), mul(l_max, r_max))
+                } else {
+                    return None;
+                }
+            }
+            _ => return None,
+        };
+        let nat = min.is_some_and(|n| n >= 0);
+        // `Int` values are `i32`, so bounds out of range are dropped too
+        let tp = |n: Option<i64>| {
+            if nat {
+                n.map(|n| TyParam::value(ValueObj::Nat(n as u64)))
+            } else {
+                n.and_then(|n| i32::try_from(n).ok())
+                    .map(|n| TyParam::value(ValueObj::Int(n)))
+            }
+        };
+        let name = FRESH_GEN.fresh_varname();
+        let pred = match (tp(min), tp(max)) {
+            (Some(min), Some(max)) => Predicate::and(
+                Predicate::ge(name.clone(), min),
+                Predicate::le(name.clone(), max),
+            ),
+            (Some(min), None) if min != TyParam::value(ValueObj::Nat(0)) => {
+                Predicate::ge(name.clone(), min)
+            }
+            (None, Some(max)) => Predicate::le(name.clone(), max),
+            _ => return None,
+        };
+        let base = if nat { Type::Nat } else { Type::Int };
+        Some(refinement(name, base, pred))
+    }
+
     pub(crate) fn get_tp_t(&self, p: &TyParam) -> EvalResult<Type> {
         let p = self
             .eval_tp(p.clone())
@@ -1939,6 +2539,9 @@ impl Context {
             TyParam::App { name, args } => self
                 .rec_get_const_obj(&name)
                 .and_then(|v| {
+                    if let ValueObj::Subr(subr) = v {
+                        return subr.sig_t().return_t().map(|t| t.derefine());
+                    }
                     let ty = self.convert_value_into_type(v.clone()).ok()?;
                     let instance = self
                         .instantiate_def_type(&ty)
@@ -2011,13 +2614,20 @@ impl Context {
                         )
                     }
                 }
+                _ if op.is_comparison() => Ok(Type::Bool),
                 _ => {
-                    let op_name = op_to_name(op);
-                    feature_error!(
-                        self,
-                        Location::Unknown,
-                        &format!("get type: {op_name}({lhs}, {rhs})")
-                    )
+                    let lhs = self.get_tp_t(&lhs)?;
+                    let rhs = self.get_tp_t(&rhs)?;
+                    if let Some(t) = self.get_arithmetic_tp_t(op, &lhs, &rhs) {
+                        Ok(t)
+                    } else {
+                        let op_name = op_to_name(op);
+                        feature_error!(
+                            self,
+                            Location::Unknown,
+                            &format!("get type: {op_name}({lhs}, {rhs})")
+                        )
+                    }
                 }
             },
             other => feature_error!(
@@ -2028,6 +2638,32 @@ impl Context {
         }
     }
 
+    /// Returns the type of the arithmetic type parameter `lhs op rhs`.
+    /// e.g. `M + N` (M, N: Nat) => Nat, `M - N` (M, N: Nat) => Int, `U + V` (U, V: Str) => Str
+    fn get_arithmetic_tp_t(&self, op: OpKind, lhs: &Type, rhs: &Type) -> Option<Type> {
+        let both = |t: &Type| self.subtype_of(lhs, t) && self.subtype_of(rhs, t);
+        match op {
+            OpKind::Add | OpKind::Mul if both(&Type::Nat) => Some(Type::Nat),
+            OpKind::Add | OpKind::Mul if both(&Type::Str) => Some(Type::Str),
+            OpKind::Add
+            | OpKind::Sub
+            | OpKind::Mul
+            | OpKind::FloorDiv
+            | OpKind::Mod
+            | OpKind::Pow
+                if both(&Type::Int) =>
+            {
+                Some(Type::Int)
+            }
+            OpKind::Add | OpKind::Sub | OpKind::Mul | OpKind::Div | OpKind::FloorDiv
+                if both(&Type::Float) =>
+            {
+                Some(Type::Float)
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn _get_tp_class(&self, p: &TyParam) -> EvalResult<Type> {
         let p = self.eval_tp(p.clone())?;
         match p {
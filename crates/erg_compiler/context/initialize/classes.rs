@@ -29,6 +29,7 @@ impl Context {
         let N = mono_q_tp(TY_N, instanceof(Nat));
         let M = mono_q_tp(TY_M, instanceof(Nat));
         let never = Self::builtin_mono_class(NEVER, 1);
+        let dynamic = Self::builtin_mono_class(DYNAMIC, 1);
         /* Obj */
         let mut obj = Self::builtin_mono_class(OBJ, 2);
         let Slf = mono_q(SELF, subtypeof(Obj));
@@ -1199,15 +1200,23 @@ impl Context {
         let t_count =
             fn_met(arr_t.clone(), vec![kw(KW_X, T.clone())], None, vec![], Nat).quantify();
         array_.register_py_builtin(FUNC_COUNT, t_count, Some(FUNC_COUNT), 17);
-        // Array(T, N)|<: Add(Array(T, M))|.
-        //     Output = Array(T, N + M)
-        //     __add__: (self: Array(T, N), other: Array(T, M)) -> Array(T, N + M) = Array.concat
+        // Array(T, N)|<: Add(Array(U, M))|.
+        //     Output = Array(T or U, N + M)
+        //     __add__: (self: Array(T, N), other: Array(U, M)) -> Array(T or U, N + M)
+        let out_t = array_t(or(T.clone(), U.clone()), N.clone() + M.clone());
         let mut array_add = Self::builtin_methods(
-            Some(poly(ADD, vec![ty_tp(array_t(T.clone(), M.clone()))])),
+            Some(poly(ADD, vec![ty_tp(array_t(U.clone(), M.clone()))])),
             2,
         );
+        let t = fn_met(
+            arr_t.clone(),
+            vec![kw(KW_RHS, array_t(U.clone(), M.clone()))],
+            None,
+            vec![],
+            out_t.clone(),
+        )
+        .quantify();
         array_add.register_builtin_erg_impl(OP_ADD, t, Immutable, Visibility::BUILTIN_PUBLIC);
-        let out_t = array_t(T.clone(), N.clone() + M);
         array_add.register_builtin_const(
             OUTPUT,
             Visibility::BUILTIN_PUBLIC,
@@ -1318,7 +1327,30 @@ impl Context {
                 array_t(T.clone(), TyParam::erased(Nat)),
             ]),
         );
-        array_.register_py_builtin(FUNC_PARTITION, t.quantify(), Some(FUNC_PARTITION), 37);
+        array_.register_py_builtin(FUNC_PARTITION, t.quantify(), Some(FUNC_PARTITION), 52);
+        let t = fn1_met(
+            array_t(T.clone(), TyParam::erased(Nat)),
+            func1(T.clone(), Bool),
+            array_t(T.clone(), TyParam::erased(Nat)),
+        );
+        array_.register_py_builtin(FUNC_FILTER, t.quantify(), Some(FUNC_FILTER), 35);
+        // map: |T, U, N|(self: Array(T, N), f: T -> U) -> Array(U, N)
+        let t = fn1_met(
+            arr_t.clone(),
+            func1(T.clone(), U.clone()),
+            array_t(U.clone(), N.clone()),
+        );
+        array_.register_py_builtin(FUNC_MAP, t.quantify(), Some(FUNC_MAP), 43);
+        // zip: |T, U, N, M|(self: Array(T, N), other: Array(U, M)) -> Array((T, U), min(N, M))
+        let t = fn1_met(
+            arr_t.clone(),
+            array_t(U.clone(), M.clone()),
+            array_t(
+                tuple_t(vec![T.clone(), U.clone()]),
+                N.clone().min(M.clone()),
+            ),
+        );
+        array_.register_py_builtin(FUNC_ZIP, t.quantify(), Some(FUNC_ZIP), 60);
         let t = fn_met(
             array_t(T.clone(), TyParam::erased(Nat)),
             vec![],
@@ -1500,6 +1532,21 @@ impl Context {
             None,
         )));
         dict_.register_builtin_const(VALUES, Visibility::BUILTIN_PUBLIC, values);
+        // `{"name": Str}.as_record() == {.name = Str}`
+        let dict_as_record_t =
+            fn0_met(dict_t.clone(), proj_call(D.clone(), AS_RECORD, vec![])).quantify();
+        let as_record = ValueObj::Subr(ConstSubr::Builtin(BuiltinConstSubr::new(
+            AS_RECORD,
+            dict_as_record,
+            dict_as_record_t,
+            None,
+        )));
+        dict_.register_builtin_const_impl(
+            AS_RECORD,
+            Visibility::BUILTIN_PUBLIC,
+            as_record,
+            Some(FUNC_DICT_AS_RECORD),
+        );
         let dict_items_t = fn0_met(dict_t.clone(), proj_call(D.clone(), ITEMS, vec![])).quantify();
         let items = ValueObj::Subr(ConstSubr::Builtin(BuiltinConstSubr::new(
             ITEMS,
@@ -1611,6 +1658,22 @@ impl Context {
         /* record */
         let mut record = Self::builtin_mono_class(RECORD, 2);
         record.register_superclass(Obj, &obj);
+        // `{.name = Str}.as_dict() == {"name": Str}`
+        let Rec = mono_q(TY_R, instanceof(Type));
+        let record_as_dict_t =
+            fn0_met(Rec.clone(), proj_call(ty_tp(Rec), AS_DICT, vec![])).quantify();
+        let as_dict = ValueObj::Subr(ConstSubr::Builtin(BuiltinConstSubr::new(
+            AS_DICT,
+            record_as_dict,
+            record_as_dict_t,
+            None,
+        )));
+        record.register_builtin_const_impl(
+            AS_DICT,
+            Visibility::BUILTIN_PUBLIC,
+            as_dict,
+            Some(FUNC_ASDICT),
+        );
         /* Or (true or type) */
         let or_t = poly(OR, vec![ty_tp(L), ty_tp(R)]);
         let mut or = Self::builtin_poly_class(OR, vec![PS::t_nd(TY_L), PS::t_nd(TY_R)], 2);
@@ -2295,6 +2358,100 @@ impl Context {
             None,
         )));
         range.register_builtin_const(FUNDAMENTAL_GETITEM, Visibility::BUILTIN_PUBLIC, get_item);
+        /* Quantity */
+        // Quantity(T, U): a value of `T` measured in the unit `U` (e.g. `Quantity(Float, "m/s")`)
+        let unit_u = mono_q_tp(TY_U, instanceof(Str));
+        let unit_v = mono_q_tp(TY_V, instanceof(Str));
+        let quantity_t = |unit: TyParam| poly(QUANTITY, vec![ty_tp(T.clone()), unit]);
+        let q_t = quantity_t(unit_u.clone());
+        let mut quantity =
+            Self::builtin_poly_class(QUANTITY, vec![PS::t_nd(TY_T), PS::named_nd(TY_U, Str)], 4);
+        quantity.register_superclass(Obj, &obj);
+        quantity
+            .register_marker_trait(self, poly(OUTPUT, vec![ty_tp(T.clone())]))
+            .unwrap();
+        quantity.register_builtin_py_impl(
+            KW_VALUE,
+            fn0_met(q_t.clone(), T.clone()).quantify(),
+            Immutable,
+            Visibility::BUILTIN_PUBLIC,
+            Some(KW_VALUE),
+        );
+        quantity.register_builtin_py_impl(
+            KW_UNIT,
+            fn0_met(q_t.clone(), singleton(Str, unit_u.clone())).quantify(),
+            Immutable,
+            Visibility::BUILTIN_PUBLIC,
+            Some(KW_UNIT),
+        );
+        // Quantity(T, U)|<: Add(Quantity(T, U))|.
+        //     Output = Quantity(T, U)
+        for (trait_name, op) in [(ADD, OP_ADD), (SUB, OP_SUB)] {
+            let mut quantity_op =
+                Self::builtin_methods(Some(poly(trait_name, vec![ty_tp(q_t.clone())])), 2);
+            let op_t = fn1_met(q_t.clone(), q_t.clone(), q_t.clone()).quantify();
+            quantity_op.register_builtin_erg_impl(op, op_t, Const, Visibility::BUILTIN_PUBLIC);
+            quantity_op.register_builtin_const(
+                OUTPUT,
+                Visibility::BUILTIN_PUBLIC,
+                ValueObj::builtin_class(q_t.clone()),
+            );
+            quantity.register_trait(q_t.clone(), quantity_op);
+        }
+        // Quantity(T, U)|<: Mul(Quantity(T, V))|.
+        //     Output = Quantity(T, unit_mul(U, V))
+        for (trait_name, op, unit_op) in
+            [(MUL, OP_MUL, FUNC_UNIT_MUL), (DIV, OP_DIV, FUNC_UNIT_DIV)]
+        {
+            let rhs_t = quantity_t(unit_v.clone());
+            let out_t = quantity_t(TyParam::app(
+                unit_op.into(),
+                vec![unit_u.clone(), unit_v.clone()],
+            ));
+            let mut quantity_op =
+                Self::builtin_methods(Some(poly(trait_name, vec![ty_tp(rhs_t.clone())])), 2);
+            let op_t = fn1_met(q_t.clone(), rhs_t, out_t.clone()).quantify();
+            quantity_op.register_builtin_erg_impl(op, op_t, Const, Visibility::BUILTIN_PUBLIC);
+            quantity_op.register_builtin_const(
+                OUTPUT,
+                Visibility::BUILTIN_PUBLIC,
+                ValueObj::builtin_class(out_t),
+            );
+            quantity.register_trait(q_t.clone(), quantity_op);
+        }
+        /* Lens */
+        // Lens(R, T): a first-class accessor of a field of type `T` of the record type `R` (e.g. `R::.name`)
+        let rec = mono_q(TY_R, instanceof(Type));
+        let lens_t = poly(LENS, vec![ty_tp(rec.clone()), ty_tp(T.clone())]);
+        let mut lens = Self::builtin_poly_class(LENS, vec![PS::t_nd(TY_R), PS::t_nd(TY_T)], 2);
+        lens.register_superclass(Obj, &obj);
+        // a lens can be applied to records with more fields
+        lens.register_marker_trait(self, poly(INPUT, vec![ty_tp(rec.clone())]))
+            .unwrap();
+        lens.register_marker_trait(self, poly(OUTPUT, vec![ty_tp(T.clone())]))
+            .unwrap();
+        lens.register_builtin_py_impl(
+            FUNC_GET,
+            fn1_kw_met(lens_t.clone(), kw(KW_RECORD, rec.clone()), T.clone()).quantify(),
+            Immutable,
+            Visibility::BUILTIN_PUBLIC,
+            Some(FUNC_GET),
+        );
+        // returns a copy of the record whose field is replaced with `value`
+        lens.register_builtin_py_impl(
+            FUNC_SET,
+            fn_met(
+                lens_t.clone(),
+                vec![kw(KW_RECORD, rec.clone()), kw(KW_VALUE, T.clone())],
+                None,
+                vec![],
+                rec.clone(),
+            )
+            .quantify(),
+            Immutable,
+            Visibility::BUILTIN_PUBLIC,
+            Some(FUNC_SET),
+        );
         let mut g_callable = Self::builtin_mono_class(GENERIC_CALLABLE, 2);
         g_callable.register_superclass(Obj, &obj);
         let t_return = fn1_met(mono(GENERIC_CALLABLE), Obj, Never).quantify();
@@ -2304,6 +2461,22 @@ impl Context {
             Immutable,
             Visibility::BUILTIN_PUBLIC,
         );
+        // `f.bind(x)` is compiled to `partial(f, x)`, the return type is computed by `Partial` in lowering
+        let t_bind = fn_met(
+            mono(GENERIC_CALLABLE),
+            vec![],
+            Some(kw(KW_ARGS, Obj)),
+            vec![],
+            mono(GENERIC_CALLABLE),
+        );
+        g_callable.register_builtin_impl(
+            VarName::from_static(FUNC_BIND),
+            t_bind,
+            Immutable,
+            Visibility::BUILTIN_PUBLIC,
+            Some(FUNC_PARTIAL_BIND),
+            AbsLocation::unknown(),
+        );
         let mut g_generator = Self::builtin_mono_class(GENERIC_GENERATOR, 2);
         g_generator.register_superclass(mono(GENERIC_CALLABLE), &g_callable);
         let t_yield = fn1_met(mono(GENERIC_GENERATOR), Obj, Never).quantify();
@@ -2331,6 +2504,13 @@ impl Context {
         qfunc.register_superclass(mono(FUNC), &func);
         self.register_builtin_type(Never, never, vis.clone(), Const, Some(NEVER));
         self.register_builtin_type(Obj, obj, vis.clone(), Const, Some(FUNC_OBJECT));
+        self.register_builtin_type(
+            mono(DYNAMIC),
+            dynamic,
+            vis.clone(),
+            Const,
+            Some(FUNC_OBJECT),
+        );
         // self.register_type(mono(RECORD), vec![], record, Visibility::BUILTIN_PRIVATE, Const);
         let name = if PYTHON_MODE { FUNC_INT } else { INT };
         self.register_builtin_type(Int, int, vis.clone(), Const, Some(name));
@@ -2517,6 +2697,8 @@ impl Context {
         self.register_builtin_type(mono(PROC), proc, vis.clone(), Const, Some(PROC));
         self.register_builtin_type(mono(FUNC), func, vis.clone(), Const, Some(FUNC));
         self.register_builtin_type(range_t, range, vis.clone(), Const, Some(FUNC_RANGE));
+        self.register_builtin_type(q_t, quantity, vis.clone(), Const, Some(QUANTITY));
+        self.register_builtin_type(lens_t, lens, vis.clone(), Const, Some(LENS));
         if ERG_MODE {
             self.register_builtin_type(module_t, module, vis.clone(), Const, Some(MODULE_TYPE));
             self.register_builtin_type(
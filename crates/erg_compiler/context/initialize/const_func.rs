@@ -1,13 +1,15 @@
+use std::collections::BTreeMap;
+use std::fmt;
 use std::mem;
 
 use erg_common::dict::Dict;
-use erg_common::enum_unwrap;
+use erg_common::{enum_unwrap, set, Str};
 
 use crate::context::Context;
 use crate::feature_error;
-use crate::ty::constructors::{and, mono, poly, tuple_t, ty_tp};
+use crate::ty::constructors::{and, dict_t, mono, or, poly, subr_t, tuple_t, ty_tp, v_enum};
 use crate::ty::value::{EvalValueError, EvalValueResult, GenTypeObj, TypeObj, ValueObj};
-use crate::ty::{TyParam, Type, ValueArgs};
+use crate::ty::{Field, SubrType, TyParam, Type, ValueArgs};
 use erg_common::error::{ErrorCore, ErrorKind, Location, SubMessage};
 use erg_common::style::{Color, StyledStr, StyledString, THEME};
 
@@ -15,10 +17,14 @@ use super::{DICT_ITEMS, DICT_KEYS, DICT_VALUES};
 
 const ERR: Color = THEME.colors.error;
 const WARN: Color = THEME.colors.warning;
+const HINT: Color = THEME.colors.hint;
 
 const SUP_ERR: StyledStr = StyledStr::new("Super", Some(ERR), None);
 const SUP_WARN: StyledStr = StyledStr::new("Super", Some(WARN), None);
 const CLASS_ERR: StyledStr = StyledStr::new("Class", Some(ERR), None);
+const TRAITS_ERR: StyledStr = StyledStr::new("Traits", Some(ERR), None);
+const TRAIT_ERR: StyledStr = StyledStr::new("Trait", Some(ERR), None);
+const MODULE_ERR: StyledStr = StyledStr::new("Module", Some(ERR), None);
 const REQ_ERR: StyledStr = StyledStr::new("Requirement", Some(ERR), None);
 const REQ_WARN: StyledStr = StyledStr::new("Requirement", Some(WARN), None);
 const BASE_ERR: StyledStr = StyledStr::new("Base", Some(ERR), None);
@@ -98,19 +104,7 @@ pub(crate) fn inheritable_func(mut args: ValueArgs, _ctx: &Context) -> EvalValue
     })?;
     match class {
         ValueObj::Type(TypeObj::Generated(mut gen)) => {
-            if let Some(typ) = gen.impls_mut() {
-                match typ.as_mut().map(|x| x.as_mut()) {
-                    Some(TypeObj::Generated(gen)) => {
-                        *gen.typ_mut() = and(mem::take(gen.typ_mut()), mono("InheritableType"));
-                    }
-                    Some(TypeObj::Builtin { t, .. }) => {
-                        *t = and(mem::take(t), mono("InheritableType"));
-                    }
-                    _ => {
-                        *typ = Some(Box::new(TypeObj::builtin_trait(mono("InheritableType"))));
-                    }
-                }
-            }
+            add_marker_trait(&mut gen, "InheritableType");
             Ok(ValueObj::Type(TypeObj::Generated(gen)))
         }
         other => feature_error!(
@@ -122,6 +116,148 @@ pub(crate) fn inheritable_func(mut args: ValueArgs, _ctx: &Context) -> EvalValue
     }
 }
 
+/// Class: ClassType -> ClassType (with `InheritableType` and `AbstractType`)
+/// Abstract classes cannot be instantiated, and their subclasses must implement all `@Abstract` methods (checked by the lowerer).
+pub(crate) fn abstract_func(mut args: ValueArgs, _ctx: &Context) -> EvalValueResult<ValueObj> {
+    let class = args.remove_left_or_key("Class").ok_or_else(|| {
+        ErrorCore::new(
+            vec![SubMessage::only_loc(Location::Unknown)],
+            format!("{CLASS_ERR} is not passed"),
+            line!() as usize,
+            ErrorKind::KeyError,
+            Location::Unknown,
+        )
+    })?;
+    match class {
+        ValueObj::Type(TypeObj::Generated(mut gen)) => {
+            add_marker_trait(&mut gen, "InheritableType");
+            add_marker_trait(&mut gen, "AbstractType");
+            Ok(ValueObj::Type(TypeObj::Generated(gen)))
+        }
+        other => feature_error!(
+            EvalValueError,
+            _ctx,
+            Location::Unknown,
+            &format!("Abstract {other}")
+        ),
+    }
+}
+
+fn add_marker_trait(gen: &mut GenTypeObj, marker: &'static str) {
+    if let Some(typ) = gen.impls_mut() {
+        match typ.as_mut().map(|x| x.as_mut()) {
+            Some(TypeObj::Generated(gen)) => {
+                *gen.typ_mut() = and(mem::take(gen.typ_mut()), mono(marker));
+            }
+            Some(TypeObj::Builtin { t, .. }) => {
+                *t = and(mem::take(t), mono(marker));
+            }
+            _ => {
+                *typ = Some(Box::new(TypeObj::builtin_trait(mono(marker))));
+            }
+        }
+    }
+}
+
+/// Class: ClassType, Traits: [Type; _] -> ClassType (with derives)
+/// The methods of the traits are synthesized here (see `crate::derive`).
+pub(crate) fn derive_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
+    let class = args.remove_left_or_key("Class").ok_or_else(|| {
+        ErrorCore::new(
+            vec![SubMessage::only_loc(Location::Unknown)],
+            format!("{CLASS_ERR} is not passed"),
+            line!() as usize,
+            ErrorKind::KeyError,
+            Location::Unknown,
+        )
+    })?;
+    let Some(ValueObj::Array(traits)) = args.remove_left_or_key("Traits") else {
+        return Err(ErrorCore::new(
+            vec![SubMessage::only_loc(Location::Unknown)],
+            format!("{TRAITS_ERR} is not passed"),
+            line!() as usize,
+            ErrorKind::KeyError,
+            Location::Unknown,
+        )
+        .into());
+    };
+    match class {
+        ValueObj::Type(TypeObj::Generated(mut gen)) => {
+            let class_t = gen.typ().clone();
+            let Some(derives) = gen.derives_mut() else {
+                return feature_error!(
+                    EvalValueError,
+                    ctx,
+                    Location::Unknown,
+                    &format!("Derive {class_t}")
+                );
+            };
+            for trait_ in traits.iter() {
+                let derived = trait_
+                    .as_type(ctx)
+                    .and_then(|t| ctx.synthesize_derive(t.typ(), &class_t));
+                let Some(derived) = derived else {
+                    let trait_ = StyledString::new(format!("{trait_}"), Some(ERR), None);
+                    return Err(ErrorCore::new(
+                        vec![SubMessage::only_loc(Location::Unknown)],
+                        format!("{trait_} cannot be derived"),
+                        line!() as usize,
+                        ErrorKind::TypeError,
+                        Location::Unknown,
+                    )
+                    .into());
+                };
+                derives.push(derived);
+            }
+            Ok(ValueObj::Type(TypeObj::Generated(gen)))
+        }
+        other => feature_error!(
+            EvalValueError,
+            ctx,
+            Location::Unknown,
+            &format!("Derive {other}")
+        ),
+    }
+}
+
+/// Trait: TraitType, Module: Str -> TraitType
+/// Makes the trait derivable; the derived methods are implemented in `Module` (see `crate::derive`).
+pub(crate) fn derivable_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
+    let trait_ = args.remove_left_or_key("Trait").ok_or_else(|| {
+        ErrorCore::new(
+            vec![SubMessage::only_loc(Location::Unknown)],
+            format!("{TRAIT_ERR} is not passed"),
+            line!() as usize,
+            ErrorKind::KeyError,
+            Location::Unknown,
+        )
+    })?;
+    let Some(ValueObj::Str(module)) = args.remove_left_or_key("Module") else {
+        return Err(ErrorCore::new(
+            vec![SubMessage::only_loc(Location::Unknown)],
+            format!("{MODULE_ERR} is not passed"),
+            line!() as usize,
+            ErrorKind::KeyError,
+            Location::Unknown,
+        )
+        .into());
+    };
+    match trait_ {
+        ValueObj::Type(TypeObj::Generated(GenTypeObj::Trait(mut trait_))) => {
+            trait_.derive = Some(module);
+            Ok(ValueObj::Type(TypeObj::Generated(GenTypeObj::Trait(
+                trait_,
+            ))))
+        }
+        other => feature_error!(
+            EvalValueError,
+            ctx,
+            Location::Unknown,
+            &format!("Derivable {other}")
+        ),
+    }
+}
+
 /// Base: Type, Impl := Type -> TraitType
 pub(crate) fn trait_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
     let req = args.remove_left_or_key("Requirement").ok_or_else(|| {
@@ -239,6 +375,48 @@ pub(crate) fn structural_func(mut args: ValueArgs, ctx: &Context) -> EvalValueRe
     Ok(ValueObj::gen_t(GenTypeObj::structural(t, base)))
 }
 
+/// `assert_static(N > 0, "N must be positive")`
+pub(crate) fn assert_static_func(mut args: ValueArgs, _ctx: &Context) -> EvalValueResult<ValueObj> {
+    let test = args.remove_left_or_key("test").ok_or_else(|| {
+        let test = StyledStr::new("test", Some(ERR), None);
+        ErrorCore::new(
+            vec![SubMessage::only_loc(Location::Unknown)],
+            format!("{test} is not passed"),
+            line!() as usize,
+            ErrorKind::KeyError,
+            Location::Unknown,
+        )
+    })?;
+    match test {
+        ValueObj::Bool(true) => Ok(ValueObj::None),
+        ValueObj::Bool(false) => {
+            let msg = match args.remove_left_or_key("msg") {
+                Some(ValueObj::Str(msg)) => format!("static assertion failed: {msg}"),
+                _ => "static assertion failed".to_string(),
+            };
+            Err(ErrorCore::new(
+                vec![SubMessage::only_loc(Location::Unknown)],
+                msg,
+                line!() as usize,
+                ErrorKind::AssertionError,
+                Location::Unknown,
+            )
+            .into())
+        }
+        other => {
+            let other = StyledString::new(format!("{other}"), Some(ERR), None);
+            Err(ErrorCore::new(
+                vec![SubMessage::only_loc(Location::Unknown)],
+                format!("non-Bool object {other} is passed to assert_static"),
+                line!() as usize,
+                ErrorKind::TypeError,
+                Location::Unknown,
+            )
+            .into())
+        }
+    }
+}
+
 pub(crate) fn __array_getitem__(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
     let slf = ctx
         .convert_value_into_array(args.remove_left_or_key("Self").unwrap())
@@ -411,6 +589,64 @@ pub(crate) fn dict_items(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<
     Ok(ValueObj::builtin_class(items))
 }
 
+fn not_convertible_error(obj: impl std::fmt::Display, into: &str) -> EvalValueError {
+    let obj = StyledString::new(format!("{obj}"), Some(ERR), None);
+    ErrorCore::new(
+        vec![SubMessage::only_loc(Location::Unknown)],
+        format!("{obj} cannot be converted into {into}"),
+        line!() as usize,
+        ErrorKind::TypeError,
+        Location::Unknown,
+    )
+    .into()
+}
+
+/// `{"name": Str, "age": Nat}.as_record() == {.name = Str; .age = Nat}`
+///
+/// The keys must be string literals that are valid identifiers.
+pub(crate) fn dict_as_record(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
+    let slf = args.remove_left_or_key("Self").unwrap();
+    let ValueObj::Dict(slf) = slf else {
+        return Err(not_convertible_error(slf, "a record type"));
+    };
+    let mut fields = Dict::new();
+    for (k, v) in slf.into_iter() {
+        let key = ctx
+            .convert_value_into_type(k)
+            .map_err(|k| not_convertible_error(k, "a field"))?;
+        let name = match key.singleton_value() {
+            Some(TyParam::Value(ValueObj::Str(name)))
+                if name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_alphanumeric() || c == '_') =>
+            {
+                name.clone()
+            }
+            _ => return Err(not_convertible_error(key, "a field")),
+        };
+        let t = ctx
+            .convert_value_into_type(v)
+            .map_err(|v| not_convertible_error(v, "a type"))?;
+        fields.insert(Field::public(name), t);
+    }
+    Ok(ValueObj::builtin_type(Type::Record(fields)))
+}
+
+/// `{.name = Str; .age = Nat}.as_dict() == {"name": Str, "age": Nat}`
+pub(crate) fn record_as_dict(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
+    let slf = args.remove_left_or_key("Self").unwrap();
+    let Ok(Type::Record(fields)) = ctx.convert_value_into_type(slf.clone()) else {
+        return Err(not_convertible_error(slf, "a typed dict"));
+    };
+    let dict = fields
+        .into_iter()
+        .map(|(field, t)| {
+            let key = v_enum(set! { ValueObj::Str(field.symbol) });
+            (ty_tp(key), ty_tp(t))
+        })
+        .collect::<Dict<_, _>>();
+    Ok(ValueObj::builtin_type(dict_t(TyParam::Dict(dict))))
+}
+
 /// `[Int, Str].union() == Int or Str`
 pub(crate) fn array_union(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
     let slf = args.remove_left_or_key("Self").unwrap();
@@ -449,3 +685,286 @@ pub(crate) fn __range_getitem__(mut args: ValueArgs, _ctx: &Context) -> EvalValu
         .into())
     }
 }
+
+/// base unit => exponent
+type UnitDims = BTreeMap<String, i32>;
+
+/// Adds `exp` to the exponent of `base` in `dims`.
+/// Returns `None` if the exponent overflows (`i32::MIN` is also rejected so that it can be negated).
+fn add_exp(dims: &mut UnitDims, base: String, exp: i32) -> Option<()> {
+    let acc = dims.entry(base).or_insert(0);
+    *acc = acc.checked_add(exp).filter(|exp| *exp != i32::MIN)?;
+    Some(())
+}
+
+/// Parses a unit expression such as `kg*m/s^2` into base units and their exponents.
+/// `1` (or an empty string) is the dimensionless unit.
+fn parse_unit(unit: &str) -> Option<UnitDims> {
+    let mut dims = BTreeMap::new();
+    let mut sign = 1;
+    let mut factor = String::new();
+    let mut push = |factor: &str, sign: i32| -> Option<()> {
+        let factor = factor.trim();
+        if factor.is_empty() || factor == "1" {
+            return Some(());
+        }
+        let (base, exp) = match factor.split_once('^') {
+            Some((base, exp)) => (base.trim(), exp.trim().parse::<i32>().ok()?),
+            None => (factor, 1),
+        };
+        if base.is_empty() || !base.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        add_exp(&mut dims, base.to_string(), sign.checked_mul(exp)?)
+    };
+    for c in unit.chars() {
+        match c {
+            '*' | '/' => {
+                push(&factor, sign)?;
+                factor.clear();
+                sign = if c == '/' { -1 } else { 1 };
+            }
+            _ => factor.push(c),
+        }
+    }
+    push(&factor, sign)?;
+    dims.retain(|_, exp| *exp != 0);
+    Some(dims)
+}
+
+/// Formats base units into the canonical form: `kg*m/s^2`.
+fn fmt_unit(dims: &UnitDims) -> String {
+    let fmt_factor = |base: &str, exp: i32| {
+        if exp == 1 {
+            base.to_string()
+        } else {
+            format!("{base}^{exp}")
+        }
+    };
+    let num = dims
+        .iter()
+        .filter(|(_, exp)| **exp > 0)
+        .map(|(base, exp)| fmt_factor(base, *exp))
+        .collect::<Vec<_>>();
+    let den = dims
+        .iter()
+        .filter(|(_, exp)| **exp < 0)
+        .map(|(base, exp)| fmt_factor(base, -exp))
+        .collect::<Vec<_>>();
+    let mut unit = if num.is_empty() {
+        "1".to_string()
+    } else {
+        num.join("*")
+    };
+    for factor in den {
+        unit.push('/');
+        unit.push_str(&factor);
+    }
+    unit
+}
+
+fn unit_args(mut args: ValueArgs) -> EvalValueResult<(UnitDims, UnitDims)> {
+    let mut take = |key: &str| {
+        let unit = args.remove_left_or_key(key).ok_or_else(|| {
+            let key = StyledString::new(key, Some(ERR), None);
+            ErrorCore::new(
+                vec![SubMessage::only_loc(Location::Unknown)],
+                format!("{key} is not passed"),
+                line!() as usize,
+                ErrorKind::KeyError,
+                Location::Unknown,
+            )
+        })?;
+        let dims = match &unit {
+            ValueObj::Str(s) => parse_unit(s),
+            _ => None,
+        };
+        dims.ok_or_else(|| invalid_unit_error(&unit))
+    };
+    let lhs = take("lhs")?;
+    let rhs = take("rhs")?;
+    Ok((lhs, rhs))
+}
+
+fn invalid_unit_error(unit: &impl fmt::Display) -> EvalValueError {
+    let unit = StyledString::new(format!("{unit}"), Some(ERR), None);
+    EvalValueError::from(ErrorCore::new(
+        vec![SubMessage::only_loc(Location::Unknown)],
+        format!("{unit} is not a valid unit"),
+        line!() as usize,
+        ErrorKind::TypeError,
+        Location::Unknown,
+    ))
+}
+
+/// Multiplies `lhs` by `rhs` raised to `sign` (`1` or `-1`).
+fn combine_units(lhs: UnitDims, rhs: UnitDims, sign: i32) -> EvalValueResult<ValueObj> {
+    let mut dims = lhs.clone();
+    for (base, exp) in rhs.iter() {
+        // `exp` is never `i32::MIN` (see `add_exp`), so `sign * exp` does not overflow
+        if add_exp(&mut dims, base.clone(), sign * exp).is_none() {
+            let op = if sign < 0 { "/" } else { "*" };
+            let unit = format!("{:?} {op} {:?}", fmt_unit(&lhs), fmt_unit(&rhs));
+            return Err(invalid_unit_error(&unit));
+        }
+    }
+    dims.retain(|_, exp| *exp != 0);
+    Ok(ValueObj::Str(fmt_unit(&dims).into()))
+}
+
+/// `unit_mul("m", "m/s") == "m^2/s"`
+pub(crate) fn unit_mul(args: ValueArgs, _ctx: &Context) -> EvalValueResult<ValueObj> {
+    let (lhs, rhs) = unit_args(args)?;
+    combine_units(lhs, rhs, 1)
+}
+
+/// `unit_div("m", "s") == "m/s"`
+pub(crate) fn unit_div(args: ValueArgs, _ctx: &Context) -> EvalValueResult<ValueObj> {
+    let (lhs, rhs) = unit_args(args)?;
+    combine_units(lhs, rhs, -1)
+}
+
+fn partial_error(msg: String) -> EvalValueError {
+    ErrorCore::new(
+        vec![SubMessage::only_loc(Location::Unknown)],
+        msg,
+        line!() as usize,
+        ErrorKind::TypeError,
+        Location::Unknown,
+    )
+    .into()
+}
+
+/// `Partial((x: Int, y: Str) -> Bool, [Int]) == (y: Str) -> Bool`
+/// `Partial({.name = Str; .age = Nat}) == {.name = Str or NoneType; .age = Nat or NoneType}`
+///
+/// The parameters bound by `Args` (positional argument types) are removed from the subroutine type `F`.
+/// If `F` is a record type, all the fields become optional (`Args` cannot be passed).
+pub(crate) fn partial_func(mut args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
+    let f = args.remove_left_or_key("F").ok_or_else(|| {
+        let f = StyledStr::new("F", Some(ERR), None);
+        partial_error(format!("{f} is not passed"))
+    })?;
+    let bound = args.remove_left_or_key("Args");
+    let f_t = ctx
+        .convert_value_into_type(f)
+        .map_err(|f| not_convertible_error(f, "a subroutine type"))?;
+    if let Type::Record(fields) = f_t {
+        if bound.is_some() {
+            let bound = StyledStr::new("Args", Some(ERR), None);
+            return Err(partial_error(format!(
+                "{bound} cannot be passed with a record type"
+            )));
+        }
+        let fields = fields
+            .into_iter()
+            .map(|(field, t)| (field, or(t, Type::NoneType)))
+            .collect();
+        return Ok(ValueObj::builtin_type(Type::Record(fields)));
+    }
+    let Some(ValueObj::Array(bound)) = bound else {
+        let bound = StyledStr::new("Args", Some(ERR), None);
+        return Err(partial_error(format!("{bound} is not passed")));
+    };
+    let quantified = f_t.is_quantified_subr();
+    let subr = SubrType::try_from(f_t.clone())
+        .map_err(|_| not_convertible_error(&f_t, "a subroutine type"))?;
+    let mut non_defaults = subr.non_default_params.into_iter();
+    let mut defaults = subr.default_params.into_iter();
+    // type variables fixed by the bound arguments (e.g. `T` of `|T|(x: T) -> T`)
+    let mut substs = vec![];
+    for (nth, arg) in bound.iter().enumerate() {
+        let arg_t = ctx
+            .convert_value_into_type(arg.clone())
+            .map_err(|arg| not_convertible_error(arg, "a type"))?;
+        let param_t = if let Some(pt) = non_defaults.next().or_else(|| defaults.next()) {
+            pt.typ().clone()
+        } else if let Some(var_params) = subr.var_params.as_ref() {
+            var_params.typ().clone()
+        } else {
+            let f_t = StyledString::new(format!("{f_t}"), Some(ERR), None);
+            return Err(partial_error(format!(
+                "too many arguments to bind: {f_t} takes {nth} positional argument(s), but {} were given",
+                bound.len()
+            )));
+        };
+        if param_t.is_unbound_var() {
+            substs.push((param_t, arg_t));
+            continue;
+        }
+        if !param_t.has_qvar() && !ctx.subtype_of(&arg_t, &param_t) {
+            let arg_t = StyledString::new(format!("{arg_t}"), Some(ERR), None);
+            let param_t = StyledString::new(format!("{param_t}"), Some(HINT), None);
+            return Err(partial_error(format!(
+                "the type of the bound argument #{} is mismatched: expected {param_t}, but found {arg_t}",
+                nth + 1
+            )));
+        }
+    }
+    let residual = subr_t(
+        subr.kind,
+        non_defaults.collect(),
+        subr.var_params.map(|v| *v),
+        defaults.collect(),
+        *subr.return_t,
+    );
+    let residual = substs
+        .iter()
+        .fold(residual, |t, (target, to)| t.replace(target, to));
+    let residual = if quantified && residual.has_qvar() {
+        residual.quantify()
+    } else {
+        residual
+    };
+    Ok(ValueObj::builtin_type(residual))
+}
+
+fn record_and_keys(
+    mut args: ValueArgs,
+    ctx: &Context,
+) -> EvalValueResult<(Dict<Field, Type>, Vec<Str>)> {
+    let r = args.remove_left_or_key("R").ok_or_else(|| {
+        let r = StyledStr::new("R", Some(ERR), None);
+        partial_error(format!("{r} is not passed"))
+    })?;
+    let Ok(Type::Record(fields)) = ctx.convert_value_into_type(r.clone()) else {
+        return Err(not_convertible_error(r, "a record type"));
+    };
+    let Some(ValueObj::Array(keys)) = args.remove_left_or_key("Keys") else {
+        let keys = StyledStr::new("Keys", Some(ERR), None);
+        return Err(partial_error(format!("{keys} is not passed")));
+    };
+    let mut names = vec![];
+    for key in keys.iter() {
+        let ValueObj::Str(name) = key else {
+            return Err(not_convertible_error(key, "a field name"));
+        };
+        if !fields.keys().any(|field| &field.symbol == name) {
+            let r = StyledString::new(format!("{}", Type::Record(fields)), Some(HINT), None);
+            let name = StyledString::new(&name[..], Some(ERR), None);
+            return Err(partial_error(format!("{r} has no field {name}")));
+        }
+        names.push(name.clone());
+    }
+    Ok((fields, names))
+}
+
+/// `Omit({.name = Str; .password = Str}, ["password"]) == {.name = Str}`
+pub(crate) fn omit_func(args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
+    let (fields, keys) = record_and_keys(args, ctx)?;
+    let fields = fields
+        .into_iter()
+        .filter(|(field, _)| !keys.contains(&field.symbol))
+        .collect();
+    Ok(ValueObj::builtin_type(Type::Record(fields)))
+}
+
+/// `Pick({.name = Str; .password = Str}, ["name"]) == {.name = Str}`
+pub(crate) fn pick_func(args: ValueArgs, ctx: &Context) -> EvalValueResult<ValueObj> {
+    let (fields, keys) = record_and_keys(args, ctx)?;
+    let fields = fields
+        .into_iter()
+        .filter(|(field, _)| keys.contains(&field.symbol))
+        .collect();
+    Ok(ValueObj::builtin_type(Type::Record(fields)))
+}
@@ -193,6 +193,11 @@ impl Context {
         let M = mono_q(TY_M, Constraint::Uninited);
         let M = mono_q(TY_M, subtypeof(poly(MUL, vec![ty_tp(M)])));
         // TODO: mod
+        let t_partial = nd_func(
+            vec![kw(KW_FUNC, mono(GENERIC_CALLABLE))],
+            Some(kw(KW_ARGS, Obj)),
+            mono(GENERIC_CALLABLE),
+        );
         let t_pow = nd_func(
             vec![kw(KW_BASE, M.clone()), kw(KW_EXP, M.clone())],
             None,
@@ -202,7 +207,7 @@ impl Context {
         let t_pyimport = nd_func(
             vec![anon(tp_enum(Str, set! {Path.clone()}))],
             None,
-            py_module(Path),
+            py_module(Path.clone()),
         )
         .quantify();
         let t_pycompile = nd_func(
@@ -321,6 +326,14 @@ impl Context {
         self.register_builtin_py_impl(FUNC_NOT, t_not, Immutable, vis.clone(), None); // `not` is not a function in Python
         self.register_builtin_py_impl(FUNC_OCT, t_oct, Immutable, vis.clone(), Some(FUNC_OCT));
         self.register_builtin_py_impl(FUNC_ORD, t_ord, Immutable, vis.clone(), Some(FUNC_ORD));
+        // the return type is computed by `Partial` in lowering
+        self.register_builtin_py_impl(
+            FUNC_PARTIAL,
+            t_partial,
+            Immutable,
+            vis.clone(),
+            Some(FUNC_PARTIAL),
+        );
         self.register_builtin_py_impl(FUNC_POW, t_pow, Immutable, vis.clone(), Some(FUNC_POW));
         self.register_builtin_py_impl(
             PYIMPORT,
@@ -458,6 +471,62 @@ impl Context {
                 vis.clone(),
                 Some(FUNC_COMPILE),
             );
+            // |T|(code: Str) -> T
+            // The result type is given by the user (e.g. `f: Int -> Int = py_inline "lambda x: x + 1"`)
+            let t_py_inline = nd_func(vec![kw(KW_CODE, Str)], None, T.clone()).quantify();
+            self.register_builtin_py_impl(
+                PY_INLINE,
+                t_py_inline,
+                Immutable,
+                vis.clone(),
+                Some(FUNC_EVAL),
+            );
+            // |Path: Str|(Path, decls: Str) -> PyModule(Path)
+            // The declarations are registered in `pre_import` and removed in `link_hir`
+            let t_cimport = nd_func(
+                vec![anon(tp_enum(Str, set! {Path.clone()})), kw(KW_DECLS, Str)],
+                None,
+                py_module(Path),
+            )
+            .quantify();
+            self.register_builtin_py_impl(
+                CIMPORT,
+                t_cimport,
+                Immutable,
+                vis.clone(),
+                Some(FUNDAMENTAL_IMPORT),
+            );
+            // |U: Str|(value: Float, unit: {U}) -> Quantity(Float, U)
+            let unit = mono_q_tp(TY_U, instanceof(Str));
+            let t_quantity = nd_func(
+                vec![
+                    kw(KW_VALUE, Float),
+                    kw(KW_UNIT, singleton(Str, unit.clone())),
+                ],
+                None,
+                poly(QUANTITY, vec![ty_tp(Float), unit]),
+            )
+            .quantify();
+            self.register_builtin_py_impl(
+                FUNC_QUANTITY,
+                t_quantity,
+                Immutable,
+                vis.clone(),
+                Some(QUANTITY),
+            );
+            // `R::.name` is desugared to `lens(R, "name")`, the return type is computed in lowering
+            let t_lens = nd_func(
+                vec![kw(KW_RECORD, Type), kw(KW_FIELD, Str)],
+                None,
+                poly(LENS, vec![ty_tp(Obj), ty_tp(Obj)]),
+            );
+            self.register_builtin_py_impl(
+                FUNC_LENS,
+                t_lens,
+                Immutable,
+                vis.clone(),
+                Some(FUNC_LENS),
+            );
             // TODO: original implementation
             self.register_builtin_py_impl(
                 FUNC_UNREACHABLE,
@@ -566,6 +635,36 @@ impl Context {
             None,
         ));
         self.register_builtin_const(INHERITABLE, vis.clone(), ValueObj::Subr(inheritable));
+        let abstract_t = func1(ClassType, ClassType);
+        let abstract_ = ConstSubr::Builtin(BuiltinConstSubr::new(
+            ABSTRACT,
+            abstract_func,
+            abstract_t,
+            None,
+        ));
+        self.register_builtin_const(ABSTRACT, vis.clone(), ValueObj::Subr(abstract_));
+        let derive_t = nd_func(
+            vec![
+                kw(KW_CLASS, ClassType),
+                kw(KW_TRAITS, unknown_len_array_t(Type)),
+            ],
+            None,
+            ClassType,
+        );
+        let derive = ConstSubr::Builtin(BuiltinConstSubr::new(DERIVE, derive_func, derive_t, None));
+        self.register_builtin_const(DERIVE, vis.clone(), ValueObj::Subr(derive));
+        let derivable_t = nd_func(
+            vec![kw(KW_TRAIT, TraitType), kw(KW_MODULE, Str)],
+            None,
+            TraitType,
+        );
+        let derivable = ConstSubr::Builtin(BuiltinConstSubr::new(
+            DERIVABLE,
+            derivable_func,
+            derivable_t,
+            None,
+        ));
+        self.register_builtin_const(DERIVABLE, vis.clone(), ValueObj::Subr(derivable));
         // TODO: register Del function object
         let t_del = nd_func(vec![kw(KW_OBJ, Obj)], None, NoneType);
         self.register_builtin_erg_impl(DEL, t_del, Immutable, vis.clone());
@@ -576,7 +675,99 @@ impl Context {
             TraitType,
         );
         let patch = ConstSubr::Builtin(BuiltinConstSubr::new(PATCH, patch_func, patch_t, None));
-        self.register_builtin_const(PATCH, vis, ValueObj::Subr(patch));
+        self.register_builtin_const(PATCH, vis.clone(), ValueObj::Subr(patch));
+        // evaluated in lowering, nothing is executed at runtime
+        let assert_static_t = func(
+            vec![kw(KW_TEST, Bool)],
+            None,
+            vec![kw(KW_MSG, Str)],
+            NoneType,
+        );
+        let assert_static = ConstSubr::Builtin(BuiltinConstSubr::new(
+            FUNC_ASSERT_STATIC,
+            assert_static_func,
+            assert_static_t,
+            None,
+        ));
+        self.register_builtin_const(
+            FUNC_ASSERT_STATIC,
+            vis.clone(),
+            ValueObj::Subr(assert_static),
+        );
+        // unit-of-measure arithmetic (e.g. `unit_div("m", "s") == "m/s"`)
+        // |L: Str, R: Str|(lhs: {L}, rhs: {R}) -> {unit_mul(L, R)}
+        let L = mono_q_tp(TY_L, instanceof(Str));
+        let R = mono_q_tp(TY_R, instanceof(Str));
+        let unit_t = |name: &'static str| {
+            nd_func(
+                vec![
+                    kw(KW_LHS, singleton(Str, L.clone())),
+                    kw(KW_RHS, singleton(Str, R.clone())),
+                ],
+                None,
+                singleton(Str, TyParam::app(name.into(), vec![L.clone(), R.clone()])),
+            )
+            .quantify()
+        };
+        let unit_mul = ConstSubr::Builtin(BuiltinConstSubr::new(
+            FUNC_UNIT_MUL,
+            unit_mul,
+            unit_t(FUNC_UNIT_MUL),
+            None,
+        ));
+        self.register_builtin_const_impl(
+            FUNC_UNIT_MUL,
+            vis.clone(),
+            ValueObj::Subr(unit_mul),
+            Some(FUNC_UNIT_MUL),
+        );
+        let unit_div = ConstSubr::Builtin(BuiltinConstSubr::new(
+            FUNC_UNIT_DIV,
+            unit_div,
+            unit_t(FUNC_UNIT_DIV),
+            None,
+        ));
+        self.register_builtin_const_impl(
+            FUNC_UNIT_DIV,
+            vis.clone(),
+            ValueObj::Subr(unit_div),
+            Some(FUNC_UNIT_DIV),
+        );
+        // Partial((x: Int, y: Str) -> Bool, [Int]) == (y: Str) -> Bool
+        // Partial({.name = Str}) == {.name = Str or NoneType}
+        let partial_t = func(
+            vec![kw(TY_F, Type)],
+            None,
+            vec![kw(TY_ARGS, unknown_len_array_t(Type))],
+            Type,
+        );
+        let partial = ConstSubr::Builtin(BuiltinConstSubr::new(
+            PARTIAL,
+            partial_func,
+            partial_t,
+            None,
+        ));
+        self.register_builtin_const_impl(
+            PARTIAL,
+            vis.clone(),
+            ValueObj::Subr(partial),
+            Some(PARTIAL),
+        );
+        // Omit({.name = Str; .age = Nat}, ["age"]) == {.name = Str}
+        let record_keys_t = nd_func(
+            vec![kw(TY_R, Type), kw(TY_KEYS, unknown_len_array_t(Str))],
+            None,
+            Type,
+        );
+        let omit = ConstSubr::Builtin(BuiltinConstSubr::new(
+            OMIT,
+            omit_func,
+            record_keys_t.clone(),
+            None,
+        ));
+        self.register_builtin_const_impl(OMIT, vis.clone(), ValueObj::Subr(omit), Some(OMIT));
+        let pick = ConstSubr::Builtin(BuiltinConstSubr::new(PICK, pick_func, record_keys_t, None));
+        self.register_builtin_const_impl(PICK, vis, ValueObj::Subr(pick), Some(PICK));
     }
 
     pub(super) fn init_builtin_py_specific_funcs(&mut self) {
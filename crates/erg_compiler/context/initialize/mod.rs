@@ -33,7 +33,9 @@ use crate::module::SharedCompilerResource;
 use crate::ty::constructors::*;
 use crate::ty::free::Constraint;
 use crate::ty::value::ValueObj;
-use crate::ty::{BuiltinConstSubr, ConstSubr, ParamTy, Predicate, TyParam, Type, Visibility};
+use crate::ty::{
+    BuiltinConstSubr, ConstSubr, ParamTy, Predicate, ReplaceTable, TyParam, Type, Visibility,
+};
 use crate::varinfo::{AbsLocation, Mutability, VarInfo, VarKind};
 use Mutability::*;
 use ParamSpec as PS;
@@ -44,6 +46,7 @@ const NUM: &str = "Num";
 
 const UNPACK: &str = "Unpack";
 const INHERITABLE_TYPE: &str = "InheritableType";
+const ABSTRACT_TYPE: &str = "AbstractType";
 const NAMED: &str = "Named";
 const SIZED: &str = "Sized";
 const MUTABLE: &str = "Mutable";
@@ -83,6 +86,8 @@ const PROC_WRITE: &str = "write!";
 const FILE_LIKE: &str = "FileLike";
 const MUTABLE_FILE_LIKE: &str = "FileLike!";
 const SHOW: &str = "Show";
+const INTO: &str = "Into";
+const FUNC_INTO: &str = "into";
 const INPUT: &str = "Input";
 const OUTPUT: &str = "Output";
 const POW_OUTPUT: &str = "PowOutput";
@@ -95,6 +100,7 @@ const MAPPING: &str = "Mapping";
 const MUTABLE_MAPPING: &str = "Mapping!";
 const EQ: &str = "Eq";
 const ORD: &str = "Ord";
+const HASH: &str = "Hash";
 const TO_STR: &str = "to_str";
 const ORDERING: &str = "Ordering";
 const SEQUENCE: &str = "Sequence";
@@ -118,6 +124,7 @@ const FLOOR_DIV: &str = "FloorDiv";
 const POS: &str = "Pos";
 const NEG: &str = "Neg";
 const NEVER: &str = "Never";
+const DYNAMIC: &str = "Dynamic";
 const OBJ: &str = "Obj";
 const MUTABLE_OBJ: &str = "Obj!";
 const FUNC_CLONE: &str = "clone";
@@ -199,6 +206,7 @@ const FUNC_CO_NLOCALS: &str = "co_nlocals";
 const FUNC_CO_KWONLYARGCOUNT: &str = "co_kwonlyargcount";
 const FUNC_CO_POSONLYARGCOUNT: &str = "co_posonlyargcount";
 const FUNC_MODULE: &str = "module";
+const ARGV: &str = "argv";
 const FUNC_GLOBAL: &str = "global";
 const GENERIC_MODULE: &str = "GenericModule";
 const PATH: &str = "Path";
@@ -271,6 +279,7 @@ const PROC_ADD: &str = "add!";
 const FUNC_INVERT: &str = "invert";
 const PROC_INVERT: &str = "invert!";
 const RANGE: &str = "Range";
+const QUANTITY: &str = "Quantity";
 const GENERIC_CALLABLE: &str = "GenericCallable";
 const GENERIC_GENERATOR: &str = "GenericGenerator";
 const FUNC_RETURN: &str = "return";
@@ -292,7 +301,7 @@ const FUNC_TYPE: &str = "type";
 const CODE_TYPE: &str = "CodeType";
 const MODULE_TYPE: &str = "ModuleType";
 const FUNC_LIST: &str = "list";
-const _FUNC_SET: &str = "set";
+const FUNC_SET: &str = "set";
 const FUNC_DICT: &str = "dict";
 const FUNC_TUPLE: &str = "tuple";
 const UNION: &str = "Union";
@@ -315,11 +324,13 @@ const FUNC_ALL: &str = "all";
 const FUNC_ANY: &str = "any";
 const FUNC_ASCII: &str = "ascii";
 const FUNC_ASSERT: &str = "assert";
+const FUNC_ASSERT_STATIC: &str = "assert_static";
 const FUNC_BIN: &str = "bin";
 const FUNC_BYTES: &str = "bytes";
 const FUNC_CHR: &str = "chr";
 const FUNC_CLASSOF: &str = "classof";
 const FUNC_COMPILE: &str = "compile";
+const FUNC_EVAL: &str = "eval";
 const FUNC_EXIT: &str = "exit";
 const FUNC_ISINSTANCE: &str = "isinstance";
 const FUNC_ISSUBCLASS: &str = "issubclass";
@@ -348,15 +359,33 @@ const FUNC_UNREACHABLE: &str = "unreachable";
 const SUBSUME: &str = "Subsume";
 const INHERIT: &str = "Inherit";
 const INHERITABLE: &str = "Inheritable";
+const ABSTRACT: &str = "Abstract";
+const DERIVE: &str = "Derive";
+const DERIVABLE: &str = "Derivable";
 const DEL: &str = "Del";
 const PATCH: &str = "Patch";
 const STRUCTURAL: &str = "Structural";
+const FUNC_UNIT_MUL: &str = "unit_mul";
+const FUNC_UNIT_DIV: &str = "unit_div";
+const FUNC_QUANTITY: &str = "quantity";
+const PARTIAL: &str = "Partial";
+const FUNC_PARTIAL: &str = "partial";
+const FUNC_BIND: &str = "bind";
+const FUNC_PARTIAL_BIND: &str = "Function::partial";
+const LENS: &str = "Lens";
+const FUNC_LENS: &str = "lens";
+const OMIT: &str = "Omit";
+const PICK: &str = "Pick";
 const KEYS: &str = "keys";
 const VALUES: &str = "values";
 const ITEMS: &str = "items";
 const DICT_KEYS: &str = "DictKeys";
 const DICT_VALUES: &str = "DictValues";
 const DICT_ITEMS: &str = "DictItems";
+const AS_RECORD: &str = "as_record";
+const AS_DICT: &str = "as_dict";
+const FUNC_DICT_AS_RECORD: &str = "Function::dict_as_record__";
+const FUNC_ASDICT: &str = "_asdict";
 const FUNC_DICT_KEYS: &str = "dict_keys";
 const FUNC_DICT_VALUES: &str = "dict_values";
 const FUNC_DICT_ITEMS: &str = "dict_items";
@@ -430,6 +459,8 @@ const SITEBUILTINS_PRINTER: &str = "_sitebuiltins._Printer";
 const PY: &str = "py";
 const PYIMPORT: &str = "pyimport";
 const PYCOMPILE: &str = "pycompile";
+const PY_INLINE: &str = "py_inline";
+const CIMPORT: &str = "cimport";
 
 const TY_A: &str = "A";
 const TY_B: &str = "B";
@@ -447,6 +478,9 @@ const TY_M: &str = "M";
 const TY_O: &str = "O";
 const TY_K: &str = "K";
 const TY_V: &str = "V";
+const TY_F: &str = "F";
+const TY_ARGS: &str = "Args";
+const TY_KEYS: &str = "Keys";
 
 const KW_OLD: &str = "old";
 const KW_B: &str = "b";
@@ -471,6 +505,9 @@ const KW_ITERABLE: &str = "iterable";
 const KW_INDEX: &str = "index";
 const KW_KEY: &str = "key";
 const KW_VALUE: &str = "value";
+const KW_UNIT: &str = "unit";
+const KW_RECORD: &str = "record";
+const KW_FIELD: &str = "field";
 const KW_KEEPENDS: &str = "keepends";
 const KW_OBJECT: &str = "object";
 const KW_OBJECTS: &str = "objects";
@@ -501,10 +538,15 @@ const KW_NUMBER: &str = "number";
 const KW_ITERABLE1: &str = "iterable1";
 const KW_ITERABLE2: &str = "iterable2";
 const KW_CODE: &str = "code";
+const KW_DECLS: &str = "decls";
 const KW_STOP: &str = "stop";
 const KW_STEP: &str = "step";
 const KW_REQUIREMENT: &str = "Requirement";
 const KW_IMPL: &str = "Impl";
+const KW_CLASS: &str = "Class";
+const KW_TRAITS: &str = "Traits";
+const KW_TRAIT: &str = "Trait";
+const KW_MODULE: &str = "Module";
 const KW_ADDITIONAL: &str = "Additional";
 const KW_SUPER: &str = "Super";
 const KW_MAXSPLIT: &str = "maxsplit";
@@ -671,6 +713,17 @@ impl Context {
     }
 
     fn register_builtin_const(&mut self, name: &str, vis: Visibility, obj: ValueObj) {
+        self.register_builtin_const_impl(name, vis, obj, None);
+    }
+
+    /// Registers a const object that also has a runtime implementation named `py_name`.
+    fn register_builtin_const_impl(
+        &mut self,
+        name: &str,
+        vis: Visibility,
+        obj: ValueObj,
+        py_name: Option<&'static str>,
+    ) {
         if self.rec_get_const_obj(name).is_some() {
             panic!("already registered: {} {name}", self.name);
         } else {
@@ -687,7 +740,7 @@ impl Context {
                 Builtin,
                 None,
                 impl_of,
-                None,
+                py_name.map(Str::ever),
                 AbsLocation::unknown(),
             );
             self.consts.insert(VarName::from_str(Str::rc(name)), obj);
@@ -705,6 +758,7 @@ impl Context {
 
     /// FIXME: トレイトの汎化型を指定するのにも使っているので、この名前は適当でない
     pub(crate) fn register_superclass(&mut self, sup: Type, sup_ctx: &Context) {
+        self.invalidate_super_type_cache();
         self.super_classes.push(sup);
         self.super_classes.extend(sup_ctx.super_classes.clone());
         self.super_traits.extend(sup_ctx.super_traits.clone());
@@ -712,7 +766,35 @@ impl Context {
         unique_in_place(&mut self.super_traits);
     }
 
+    /// e.g. `Inherit Array(Int, 3)`: `sup_t` = `Array(T, N)`, `sup` = `Array(Int, 3)`
+    /// The super types of `Array(T, N)` are inherited with the arguments substituted (`Iterable(T)` => `Iterable(Int)`).
+    pub(crate) fn register_applied_superclass(
+        &mut self,
+        sup: Type,
+        sup_t: &Type,
+        sup_ctx: &Context,
+    ) {
+        self.invalidate_super_type_cache();
+        let table = ReplaceTable::make(sup_t, &sup);
+        self.super_classes.push(sup.clone());
+        self.super_classes.extend(
+            sup_ctx
+                .super_classes
+                .iter()
+                .map(|t| table.replace(t.clone())),
+        );
+        self.super_traits.extend(
+            sup_ctx
+                .super_traits
+                .iter()
+                .map(|t| table.replace(t.clone())),
+        );
+        unique_in_place(&mut self.super_classes);
+        unique_in_place(&mut self.super_traits);
+    }
+
     pub(crate) fn register_supertrait(&mut self, sup: Type, sup_ctx: &Context) {
+        self.invalidate_super_type_cache();
         self.super_traits.push(sup);
         self.super_traits.extend(sup_ctx.super_traits.clone());
         unique_in_place(&mut self.super_traits);
@@ -790,12 +872,41 @@ impl Context {
             };
             let name = VarName::from_str(t.local_name());
             // e.g Array!: |T, N|(_: {T}, _: {N}) -> {Array!(T, N)}
-            let params = t
+            let mut params = t
                 .typarams()
                 .into_iter()
-                .map(|tp| ParamTy::Pos(tp_enum(self.get_tp_t(&tp).unwrap_or(Obj), set! { tp })))
-                .collect();
-            let meta_t = func(params, None, vec![], v_enum(set! { val.clone() })).quantify();
+                .enumerate()
+                .map(|(i, tp)| {
+                    let name = ctx
+                        .params
+                        .get(i)
+                        .and_then(|(name, _)| name.as_ref().map(|name| name.inspect().clone()));
+                    let t = tp_enum(self.get_tp_t(&tp).unwrap_or(Obj), set! { tp });
+                    (name, t)
+                })
+                .collect::<Vec<_>>();
+            // classes can be inherited with the trailing parameters omitted (they are erased)
+            // e.g. Array!: |T, N|(_: {T}, N := {N}) -> {Array!(T, N)}
+            let default_params = if ctx.kind.is_class()
+                && params.len() > 1
+                && params[1..].iter().all(|(name, _)| name.is_some())
+            {
+                params
+                    .split_off(1)
+                    .into_iter()
+                    .map(|(name, t)| ParamTy::kw_default(name.unwrap(), t.clone(), t))
+                    .collect()
+            } else {
+                vec![]
+            };
+            let non_default_params = params.into_iter().map(|(_, t)| ParamTy::Pos(t)).collect();
+            let meta_t = func(
+                non_default_params,
+                None,
+                default_params,
+                v_enum(set! { val.clone() }),
+            )
+            .quantify();
             if ERG_MODE {
                 self.locals.insert(
                     name.clone(),
@@ -966,9 +1077,17 @@ impl Context {
                 FUNC_MODULE,
                 module(TyParam::value(self.get_module().unwrap().name.clone())),
                 Immutable,
-                vis,
+                vis.clone(),
                 None,
             );
+            // the command line arguments (`sys.argv`), `argv[0]` is the path of the executed file
+            self.register_builtin_py_impl(
+                ARGV,
+                array_t(Str, TyParam::erased(Nat)),
+                Immutable,
+                vis,
+                Some(ARGV),
+            );
         }
     }
 
@@ -1,5 +1,6 @@
 #[allow(unused_imports)]
 use erg_common::log;
+use erg_common::Str;
 
 use crate::ty::constructors::*;
 use crate::ty::value::ValueObj;
@@ -9,6 +10,7 @@ use Type::*;
 
 use crate::context::initialize::*;
 use crate::context::{ConstTemplate, Context, DefaultInfo, ParamSpec};
+use crate::derive::{BUILTIN_DERIVE_MODULE, DERIVE_ATTR};
 use crate::varinfo::Mutability;
 use DefaultInfo::*;
 use Mutability::*;
@@ -27,6 +29,7 @@ impl Context {
         };
         let unpack = Self::builtin_mono_trait(UNPACK, 2);
         let inheritable_type = Self::builtin_mono_trait(INHERITABLE_TYPE, 2);
+        let abstract_type = Self::builtin_mono_trait(ABSTRACT_TYPE, 2);
         let mut named = Self::builtin_mono_trait(NAMED, 2);
         named.register_builtin_erg_decl(FUNC_NAME, Str, Visibility::BUILTIN_PUBLIC);
         let mut sized = Self::builtin_mono_trait(SIZED, 2);
@@ -167,6 +170,7 @@ impl Context {
         let mut filelike_mut = Self::builtin_mono_trait(MUTABLE_FILE_LIKE, 2);
         filelike_mut.register_superclass(mono(FILE_LIKE), &filelike);
         filelike_mut.register_superclass(mono(MUTABLE_WRITABLE), &writable);
+        let derive_module = ValueObj::Str(Str::ever(BUILTIN_DERIVE_MODULE));
         /* Show */
         let mut show = Self::builtin_mono_trait(SHOW, 2);
         let Slf = mono_q(SELF, subtypeof(mono(SHOW)));
@@ -177,6 +181,17 @@ impl Context {
             Visibility::BUILTIN_PUBLIC,
             Some(FUNDAMENTAL_STR),
         );
+        show.register_builtin_const(
+            DERIVE_ATTR,
+            Visibility::BUILTIN_PRIVATE,
+            derive_module.clone(),
+        );
+        /* Into */
+        let mut into = Self::builtin_poly_trait(INTO, vec![PS::t_nd(TY_T)], 2);
+        let T = mono_q(TY_T, instanceof(Type));
+        let Slf = mono_q(SELF, subtypeof(poly(INTO, vec![ty_tp(T.clone())])));
+        let t_into = fn0_met(Slf, T).quantify();
+        into.register_builtin_erg_decl(FUNC_INTO, t_into, Visibility::BUILTIN_PUBLIC);
         /* In */
         let mut in_ = Self::builtin_poly_trait(IN, vec![PS::t_nd(TY_T)], 2);
         let params = vec![PS::t_nd(TY_T)];
@@ -196,12 +211,32 @@ impl Context {
         // __eq__: |Self <: Eq| (self: Self, other: Self) -> Bool
         let op_t = fn1_met(Slf.clone(), Slf, Bool).quantify();
         eq.register_builtin_erg_decl(OP_EQ, op_t, Visibility::BUILTIN_PUBLIC);
+        eq.register_builtin_const(
+            DERIVE_ATTR,
+            Visibility::BUILTIN_PRIVATE,
+            derive_module.clone(),
+        );
         /* Ord */
         let mut ord = Self::builtin_mono_trait(ORD, 2);
         ord.register_superclass(mono(EQ), &eq);
         let Slf = mono_q(SELF, subtypeof(mono(ORD)));
         let op_t = fn1_met(Slf.clone(), Slf, or(mono(ORDERING), NoneType)).quantify();
         ord.register_builtin_erg_decl(OP_CMP, op_t, Visibility::BUILTIN_PUBLIC);
+        ord.register_builtin_const(
+            DERIVE_ATTR,
+            Visibility::BUILTIN_PRIVATE,
+            derive_module.clone(),
+        );
+        /* Hash */
+        let mut hash = Self::builtin_mono_trait(HASH, 2);
+        let Slf = mono_q(SELF, subtypeof(mono(HASH)));
+        let t_hash = fn0_met(Slf, Nat).quantify();
+        hash.register_builtin_erg_decl(FUNDAMENTAL_HASH, t_hash, Visibility::BUILTIN_PUBLIC);
+        hash.register_builtin_const(
+            DERIVE_ATTR,
+            Visibility::BUILTIN_PRIVATE,
+            derive_module.clone(),
+        );
         /* Iterable */
         let mut iterable = Self::builtin_poly_trait(ITERABLE, vec![PS::t_nd(TY_T)], 2);
         iterable.register_superclass(poly(OUTPUT, vec![ty_tp(T.clone())]), &output);
@@ -410,6 +445,13 @@ impl Context {
             Const,
             None,
         );
+        self.register_builtin_type(
+            mono(ABSTRACT_TYPE),
+            abstract_type,
+            Visibility::BUILTIN_PRIVATE,
+            Const,
+            None,
+        );
         self.register_builtin_type(mono(NAMED), named, vis.clone(), Const, None);
         self.register_builtin_type(mono(SIZED), sized, vis.clone(), Const, None);
         self.register_builtin_type(mono(MUTABLE), mutable, vis.clone(), Const, None);
@@ -446,6 +488,13 @@ impl Context {
             None,
         );
         self.register_builtin_type(mono(SHOW), show, vis.clone(), Const, None);
+        self.register_builtin_type(
+            poly(INTO, vec![ty_tp(T.clone())]),
+            into,
+            vis.clone(),
+            Const,
+            None,
+        );
         self.register_builtin_type(
             poly(INPUT, vec![ty_tp(T.clone())]),
             input,
@@ -469,6 +518,7 @@ impl Context {
         );
         self.register_builtin_type(mono(EQ), eq, vis.clone(), Const, None);
         self.register_builtin_type(mono(ORD), ord, vis.clone(), Const, None);
+        self.register_builtin_type(mono(HASH), hash, vis.clone(), Const, None);
         self.register_builtin_type(mono(NUM), num, vis.clone(), Const, None);
         self.register_builtin_type(
             poly(SEQUENCE, vec![ty_tp(T.clone())]),
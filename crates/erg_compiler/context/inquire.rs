@@ -2,7 +2,7 @@
 use std::option::Option; // conflicting to Type::Option
 use std::path::{Path, PathBuf};
 
-use erg_common::consts::{ERG_MODE, PYTHON_MODE};
+use erg_common::consts::ERG_MODE;
 use erg_common::error::{ErrorCore, Location, SubMessage};
 use erg_common::io::Input;
 use erg_common::levenshtein;
@@ -17,7 +17,9 @@ use erg_common::{
 use erg_parser::ast::{self, Identifier, VarName};
 use erg_parser::token::Token;
 
-use crate::ty::constructors::{anon, fn_met, free_var, func, mono, poly, proc, proj, ref_, subr_t};
+use crate::ty::constructors::{
+    anon, dynamic_func, fn_met, free_var, func, func1, func2, mono, poly, proc, proj, ref_, subr_t,
+};
 use crate::ty::free::{Constraint, FreeTyParam};
 use crate::ty::typaram::TyParam;
 use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
@@ -26,7 +28,9 @@ use crate::ty::{
 };
 use Type::*;
 
+use crate::context::attr_cache::{AttrCacheKey, AttrLookupKind};
 use crate::context::instantiate_spec::ConstTemplate;
+use crate::context::lookup::LookupOutcome;
 use crate::context::{Context, RegistrationMode, TraitImpl, TyVarCache, Variance};
 use crate::error::{
     binop_to_dname, readable_name, unaryop_to_dname, SingleTyCheckResult, TyCheckError,
@@ -38,6 +42,7 @@ use crate::{unreachable_error, AccessKind};
 use RegistrationMode::*;
 
 use super::instantiate_spec::ParamKind;
+use super::severity::LenientCheck;
 use super::{ContextKind, MethodPair};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -47,23 +52,108 @@ pub enum SubstituteResult {
     Coerced(Type),
 }
 
+/// The attributes, the calls and the operations of `Dynamic` values are not checked.
+fn dynamic_var_info(t: Type, muty: Mutability) -> VarInfo {
+    VarInfo::new(
+        t,
+        muty,
+        Visibility::DUMMY_PUBLIC,
+        VarKind::Builtin,
+        None,
+        None,
+        None,
+        AbsLocation::unknown(),
+    )
+}
+
+/// The type of a subroutine taking the given arguments, used to select an overload
+fn overload_input_t(
+    pos_args: impl Iterator<Item = Type>,
+    kw_args: impl Iterator<Item = (Str, Type)>,
+) -> Type {
+    subr_t(
+        SubrKind::Proc,
+        pos_args.map(ParamTy::Pos).collect(),
+        None,
+        kw_args.map(|(name, t)| ParamTy::kw(name, t)).collect(),
+        Obj,
+    )
+}
+
+/// Truncates the positional parameters of `subr` to the first `n` ones.
+/// The default parameters passed positionally are removed from the keyword parameters.
+/// e.g. `(x: Int, y: Int, z := Int) -> Int` -> `(x: Int) -> Int` (n == 1), `(x: Int, y: Int, z: Int) -> Int` (n == 3)
+///
+/// Returns `None` if `subr` cannot take `n` positional arguments.
+fn truncate_pos_params(subr: &SubrType, n: usize) -> Option<Type> {
+    let var_params = subr
+        .var_params
+        .iter()
+        .flat_map(|pt| std::iter::repeat(pt.as_ref()));
+    let pos_params = subr
+        .non_default_params
+        .iter()
+        .chain(subr.default_params.iter())
+        .chain(var_params)
+        .take(n)
+        .map(|pt| ParamTy::Pos(pt.typ().clone()))
+        .collect::<Vec<_>>();
+    if pos_params.len() < n {
+        return None;
+    }
+    let n_defaults = n.saturating_sub(subr.non_default_params.len());
+    let default_params = subr
+        .default_params
+        .iter()
+        .skip(n_defaults)
+        .cloned()
+        .collect();
+    Some(subr_t(
+        subr.kind,
+        pos_params,
+        None,
+        default_params,
+        subr.return_t.as_ref().clone(),
+    ))
+}
+
 impl Context {
     pub(crate) fn mod_registered(&self, path: &Path) -> bool {
         self.shared.is_some() && self.promises().is_registered(path)
     }
 
     /// Get the context of the module. If it was in analysis, wait until analysis is complete and join the thread.
+    /// If the current module imports it cyclically and it has an interface, the interface is returned instead.
     /// If you only want to know if the module is registered, use `mod_registered`.
     pub(crate) fn get_mod_with_path(&self, path: &Path) -> Option<&Context> {
         if self.module_path() == Some(path) {
             return self.get_module();
         }
+        if self.shared.is_some()
+            && self.promises().uses_interface(path)
+            && self.mod_cache().get(path).is_none()
+        {
+            return self
+                .opt_mod_cache()?
+                .raw_ref_ctx(&Self::interface_path(path))
+                .map(|mod_ctx| &mod_ctx.context);
+        }
         if self.shared.is_some()
             && self.promises().is_registered(path)
             && !self.promises().is_finished(path)
             && (self.mod_cache().get(path).is_none() && self.py_mod_cache().get(path).is_none())
         {
-            let _result = self.promises().join(path);
+            if let Err(deadlock) = self.promises().join(path) {
+                let err = TyCheckError::deadlock_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    deadlock.loc,
+                    self.caused_by(),
+                    &deadlock.waiter.display().to_string(),
+                    &deadlock.target.display().to_string(),
+                );
+                self.shared().errors.extend(TyCheckErrors::from(err));
+            }
         }
         self.opt_mod_cache()?
             .raw_ref_ctx(path)
@@ -72,13 +162,7 @@ impl Context {
     }
 
     pub(crate) fn get_current_scope_var(&self, name: &VarName) -> Option<&VarInfo> {
-        #[cfg(feature = "py_compat")]
-        let search_name = self
-            .erg_to_py_names
-            .get(name.inspect())
-            .unwrap_or(name.inspect());
-        #[cfg(not(feature = "py_compat"))]
-        let search_name = name.inspect();
+        let search_name = self.search_name(name.inspect());
         self.locals
             .get(search_name)
             .or_else(|| self.decls.get(search_name))
@@ -88,7 +172,7 @@ impl Context {
                     .find(|(opt_name, _)| {
                         opt_name
                             .as_ref()
-                            .map(|n| n.inspect() == search_name)
+                            .map(|n| &n.inspect()[..] == search_name)
                             .unwrap_or(false)
                     })
                     .map(|(_, vi)| vi)
@@ -104,13 +188,7 @@ impl Context {
     }
 
     pub(crate) fn get_mut_current_scope_var(&mut self, name: &VarName) -> Option<&mut VarInfo> {
-        #[cfg(feature = "py_compat")]
-        let search_name = self
-            .erg_to_py_names
-            .get(name.inspect())
-            .unwrap_or(name.inspect());
-        #[cfg(not(feature = "py_compat"))]
-        let search_name = name.inspect();
+        let search_name = self.name_resolver.registered_name(name.inspect(), self.cfg.py_compat);
         self.locals
             .get_mut(search_name)
             .or_else(|| self.decls.get_mut(search_name))
@@ -120,7 +198,7 @@ impl Context {
                     .find(|(opt_name, _)| {
                         opt_name
                             .as_ref()
-                            .map(|n| n.inspect() == search_name)
+                            .map(|n| &n.inspect()[..] == search_name)
                             .unwrap_or(false)
                     })
                     .map(|(_, vi)| vi)
@@ -136,8 +214,7 @@ impl Context {
     }
 
     pub(crate) fn get_var_kv(&self, name: &str) -> Option<(&VarName, &VarInfo)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.search_name(name);
         self.locals
             .get_key_value(name)
             .or_else(|| self.get_param_kv(name))
@@ -147,8 +224,7 @@ impl Context {
     }
 
     fn get_param_kv(&self, name: &str) -> Option<(&VarName, &VarInfo)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.search_name(name);
         self.params
             .iter()
             .find(|(opt_name, _)| {
@@ -428,16 +504,23 @@ impl Context {
         acc_kind: AccessKind,
         input: &Input,
         namespace: &Context,
-    ) -> Triple<VarInfo, TyCheckError> {
+    ) -> LookupOutcome {
+        let mut outcome = LookupOutcome::Absent;
         if let Some(vi) = self.get_current_scope_var(&ident.name) {
-            match self.validate_visibility(ident, vi, input, namespace) {
+            match self.validate_visibility(ident, vi, input, namespace, self) {
                 Ok(()) if acc_kind.matches(vi) => {
-                    return Triple::Ok(vi.clone());
+                    return LookupOutcome::Found(vi.clone());
                 }
                 Err(err) => {
+                    let invisible = LookupOutcome::Invisible {
+                        vi: vi.clone(),
+                        err,
+                    };
                     if !acc_kind.is_local() {
-                        return Triple::Err(err);
+                        return invisible;
                     }
+                    // an outer scope may have a visible variable with the same name
+                    outcome = invisible;
                 }
                 _ => {}
             }
@@ -445,7 +528,7 @@ impl Context {
             .future_defined_locals
             .get_key_value(&ident.inspect()[..])
         {
-            return Triple::Err(TyCheckError::access_before_def_error(
+            return LookupOutcome::NotYetDefined(TyCheckError::access_before_def_error(
                 input.clone(),
                 line!() as usize,
                 ident.loc(),
@@ -455,7 +538,16 @@ impl Context {
                 self.get_similar_name(ident.inspect()),
             ));
         } else if let Some((name, _vi)) = self.deleted_locals.get_key_value(&ident.inspect()[..]) {
-            return Triple::Err(TyCheckError::access_deleted_var_error(
+            if self.is_prelude_overlay() {
+                return LookupOutcome::Deleted(TyCheckError::banned_builtin_error(
+                    input.clone(),
+                    line!() as usize,
+                    ident.loc(),
+                    namespace.name.to_string(),
+                    ident.inspect(),
+                ));
+            }
+            return LookupOutcome::Deleted(TyCheckError::access_deleted_var_error(
                 input.clone(),
                 line!() as usize,
                 ident.loc(),
@@ -467,21 +559,22 @@ impl Context {
         }
         for (_, method_ctx) in self.methods_list.iter() {
             match method_ctx.rec_get_var_info(ident, acc_kind, input, namespace) {
-                Triple::Ok(vi) => {
-                    return Triple::Ok(vi);
+                LookupOutcome::Absent => {}
+                invisible @ LookupOutcome::Invisible { .. } if acc_kind.is_local() => {
+                    outcome = outcome.or_else(|| invisible);
                 }
-                Triple::Err(e) => {
-                    return Triple::Err(e);
+                other => {
+                    return other;
                 }
-                Triple::None => {}
             }
         }
         if acc_kind.is_local() {
             if let Some(parent) = self.get_outer().or_else(|| self.get_builtins()) {
-                return parent.rec_get_var_info(ident, acc_kind, input, namespace);
+                return outcome
+                    .or_fallback(|| parent.rec_get_var_info(ident, acc_kind, input, namespace));
             }
         }
-        Triple::None
+        outcome
     }
 
     pub(crate) fn rec_get_mut_var_info(
@@ -490,7 +583,7 @@ impl Context {
         acc_kind: AccessKind,
     ) -> Option<&mut VarInfo> {
         if let Some(vi) = self.get_current_scope_var(&ident.name) {
-            match self.validate_visibility(ident, vi, &self.cfg.input, self) {
+            match self.validate_visibility(ident, vi, &self.cfg.input, self, self) {
                 Ok(()) if acc_kind.matches(vi) => {
                     let vi = self.get_mut_current_scope_var(&ident.name).unwrap();
                     return Some(vi);
@@ -517,30 +610,37 @@ impl Context {
         acc_kind: AccessKind,
         input: &Input,
         namespace: &Context,
-    ) -> Triple<VarInfo, TyCheckError> {
+    ) -> LookupOutcome {
+        let mut outcome = LookupOutcome::Absent;
         if let Some(vi) = self
             .decls
             .get(&ident.inspect()[..])
             .or_else(|| self.future_defined_locals.get(&ident.inspect()[..]))
         {
-            match self.validate_visibility(ident, vi, input, namespace) {
+            match self.validate_visibility(ident, vi, input, namespace, self) {
                 Ok(()) if acc_kind.matches(vi) => {
-                    return Triple::Ok(vi.clone());
+                    return LookupOutcome::Found(vi.clone());
                 }
                 Err(err) => {
+                    let invisible = LookupOutcome::Invisible {
+                        vi: vi.clone(),
+                        err,
+                    };
                     if !acc_kind.is_local() {
-                        return Triple::Err(err);
+                        return invisible;
                     }
+                    outcome = invisible;
                 }
                 _ => {}
             }
         }
         if acc_kind.is_local() {
             if let Some(parent) = self.get_outer().or_else(|| self.get_builtins()) {
-                return parent.rec_get_decl_info(ident, acc_kind, input, namespace);
+                return outcome
+                    .or_fallback(|| parent.rec_get_decl_info(ident, acc_kind, input, namespace));
             }
         }
-        Triple::None
+        outcome
     }
 
     pub(crate) fn get_attr_info(
@@ -549,10 +649,22 @@ impl Context {
         ident: &Identifier,
         input: &Input,
         namespace: &Context,
-    ) -> Triple<VarInfo, TyCheckError> {
+    ) -> LookupOutcome {
+        let untyped = obj.ref_t().is_dynamic()
+            || (self.cfg.py_compat && obj.var_info().is_some_and(|vi| vi.is_untyped_parameter()));
+        if untyped && self.severity_of(LenientCheck::UntypedAttr).is_error() {
+            return LookupOutcome::Err(TyCheckError::untyped_attr_error(
+                input.clone(),
+                line!() as usize,
+                ident.loc(),
+                namespace.caused_by(),
+                obj.ref_t(),
+                ident.inspect(),
+            ));
+        }
         // get_attr_info(?T, aaa) == None
         // => ?T(<: Structural({ .aaa = ?U }))
-        if PYTHON_MODE && obj.var_info().is_some_and(|vi| vi.is_untyped_parameter()) {
+        if self.cfg.py_compat && obj.var_info().is_some_and(|vi| vi.is_untyped_parameter()) {
             let t = free_var(self.level, Constraint::new_type_of(Type));
             if let Some(fv) = obj.ref_t().as_free() {
                 if fv.get_sub().is_some() {
@@ -575,52 +687,43 @@ impl Context {
                 None,
                 AbsLocation::unknown(),
             );
-            return Triple::Ok(vi);
+            return LookupOutcome::Found(vi);
+        }
+        if obj.ref_t().is_dynamic() {
+            let muty = Mutability::from(&ident.inspect()[..]);
+            return LookupOutcome::Found(dynamic_var_info(mono("Dynamic"), muty));
         }
         let self_t = obj.t();
-        match self.get_attr_info_from_attributive(&self_t, ident) {
-            Triple::Ok(vi) => {
-                return Triple::Ok(vi);
-            }
-            Triple::Err(e) => {
-                return Triple::Err(e);
-            }
-            _ => {}
+        let outcome = self.get_attr_info_from_attributive(&self_t, ident);
+        if !outcome.is_absent() {
+            return outcome;
         }
         // class/module attr
         if let Ok(singular_ctxs) = self.get_singular_ctxs_by_hir_expr(obj, namespace) {
             for ctx in singular_ctxs {
-                match ctx.rec_get_var_info(ident, AccessKind::UnboundAttr, input, namespace) {
-                    Triple::Ok(vi) => {
-                        return Triple::Ok(vi);
-                    }
-                    Triple::Err(e) => {
-                        return Triple::Err(e);
-                    }
-                    Triple::None => {}
+                let outcome =
+                    ctx.rec_get_var_info(ident, AccessKind::UnboundAttr, input, namespace);
+                if !outcome.is_absent() {
+                    return outcome.map(|vi| vi.into_bound_class_method());
                 }
             }
         }
         // bound method/instance attr
         match self.get_bound_attr_from_nominal_t(obj, ident, input, namespace) {
-            Triple::Ok(vi) => {
+            LookupOutcome::Found(vi) => {
                 if let Some(self_t) = vi.t.self_t() {
-                    match self
-                        .sub_unify(obj.ref_t(), self_t, obj, Some(&"self".into()))
-                        .map_err(|mut e| e.remove(0))
+                    if let Err(mut errs) =
+                        self.sub_unify(obj.ref_t(), self_t, obj, Some(&"self".into()))
                     {
-                        Ok(_) => {}
-                        Err(e) => {
-                            return Triple::Err(e);
-                        }
+                        return LookupOutcome::Err(errs.remove(0));
                     }
                 }
-                return Triple::Ok(vi);
+                return LookupOutcome::Found(self.attr_value_of(obj, vi).into_bound_class_method());
             }
-            Triple::Err(e) => {
-                return Triple::Err(e);
+            LookupOutcome::Absent => {}
+            other => {
+                return other.map(|vi| self.attr_value_of(obj, vi).into_bound_class_method());
             }
-            _ => {}
         }
         for patch in self.find_patches_of(obj.ref_t()) {
             if let Some(vi) = patch
@@ -628,10 +731,7 @@ impl Context {
                 .get(ident.inspect())
                 .or_else(|| patch.decls.get(ident.inspect()))
             {
-                return match self.validate_visibility(ident, vi, input, namespace) {
-                    Ok(_) => Triple::Ok(vi.clone()),
-                    Err(e) => Triple::Err(e),
-                };
+                return self.lookup_visible(ident, vi, input, namespace, patch);
             }
             for (_, methods_ctx) in patch.methods_list.iter() {
                 if let Some(vi) = methods_ctx
@@ -639,10 +739,7 @@ impl Context {
                     .get(ident.inspect())
                     .or_else(|| methods_ctx.decls.get(ident.inspect()))
                 {
-                    return match self.validate_visibility(ident, vi, input, namespace) {
-                        Ok(_) => Triple::Ok(vi.clone()),
-                        Err(e) => Triple::Err(e),
-                    };
+                    return self.lookup_visible(ident, vi, input, namespace, methods_ctx);
                 }
             }
         }
@@ -651,47 +748,81 @@ impl Context {
                 if let Err(mut errs) =
                     self.sub_unify(obj.ref_t(), &method.definition_type, obj, None)
                 {
-                    return Triple::Err(errs.remove(0));
+                    return LookupOutcome::Err(errs.remove(0));
                 }
-                return Triple::Ok(method.method_info.clone());
+                return LookupOutcome::Found(method.method_info.clone());
             }
             Triple::Err(err) if ERG_MODE => {
-                return Triple::Err(err);
+                return LookupOutcome::Err(err);
             }
             _ => {}
         }
-        Triple::None
+        LookupOutcome::Absent
     }
 
+    /// The results are cached by `AttrCache` if the receiver type has no type variables.
     fn get_bound_attr_from_nominal_t(
         &self,
         obj: &hir::Expr,
         ident: &Identifier,
         input: &Input,
         namespace: &Context,
-    ) -> Triple<VarInfo, TyCheckError> {
+    ) -> LookupOutcome {
+        let Some(key) = Self::attr_cache_key(AttrLookupKind::BoundAttr, obj, ident) else {
+            return self.lookup_bound_attr_from_nominal_t(obj, ident, input, namespace);
+        };
+        if let Some(vi) = self.attr_cache.get(&key, &namespace.name) {
+            return LookupOutcome::Found(vi);
+        }
+        let outcome = self.lookup_bound_attr_from_nominal_t(obj, ident, input, namespace);
+        if let LookupOutcome::Found(vi) = &outcome {
+            self.attr_cache.insert(key, &namespace.name, vi);
+        }
+        outcome
+    }
+
+    /// Returns `None` if the lookup result may depend on the type variables of the receiver (e.g. coercion).
+    fn attr_cache_key(
+        kind: AttrLookupKind,
+        obj: &hir::Expr,
+        ident: &Identifier,
+    ) -> Option<AttrCacheKey> {
+        if obj.ref_t().has_unbound_var() || obj.ref_t().has_qvar() {
+            return None;
+        }
+        // the nominal contexts of `{"a"}` are that of `Str`
+        let receiver = match obj.ref_t() {
+            Type::Refinement(refine) => refine.t.as_ref().clone(),
+            other => other.clone(),
+        };
+        Some((
+            kind,
+            receiver,
+            ident.inspect().clone(),
+            ident.vis.display_as_accessor(),
+        ))
+    }
+
+    fn lookup_bound_attr_from_nominal_t(
+        &self,
+        obj: &hir::Expr,
+        ident: &Identifier,
+        input: &Input,
+        namespace: &Context,
+    ) -> LookupOutcome {
         let self_t = obj.t();
         if let Some(sups) = self.get_nominal_super_type_ctxs(&self_t) {
             for ctx in sups {
-                match ctx.rec_get_var_info(ident, AccessKind::BoundAttr, input, namespace) {
-                    Triple::Ok(vi) => {
-                        return Triple::Ok(vi);
-                    }
-                    Triple::Err(e) => {
-                        return Triple::Err(e);
-                    }
-                    _ => {}
+                let outcome = ctx.rec_get_var_info(ident, AccessKind::BoundAttr, input, namespace);
+                if !outcome.is_absent() {
+                    return outcome;
                 }
                 // if self is a methods context
                 if let Some(ctx) = self.get_same_name_context(&ctx.name) {
-                    match ctx.rec_get_var_info(ident, AccessKind::BoundAttr, input, namespace) {
-                        Triple::Ok(vi) => {
-                            return Triple::Ok(vi);
-                        }
-                        Triple::Err(e) => {
-                            return Triple::Err(e);
-                        }
-                        _ => {}
+                    let outcome =
+                        ctx.rec_get_var_info(ident, AccessKind::BoundAttr, input, namespace);
+                    if !outcome.is_absent() {
+                        return outcome;
                     }
                 }
             }
@@ -699,7 +830,7 @@ impl Context {
         let coerced = match self.coerce(obj.t(), &()).map_err(|mut es| es.remove(0)) {
             Ok(t) => t,
             Err(e) => {
-                return Triple::Err(e);
+                return LookupOutcome::Err(e);
             }
         };
         if obj.ref_t() != &coerced {
@@ -714,84 +845,76 @@ impl Context {
             }) {
                 Ok(ctxs) => ctxs,
                 Err(e) => {
-                    return Triple::Err(e);
+                    return LookupOutcome::Err(e);
                 }
             };
             for ctx in ctxs {
                 match ctx.rec_get_var_info(ident, AccessKind::BoundAttr, input, namespace) {
-                    Triple::Ok(vi) => {
+                    LookupOutcome::Found(vi) => {
                         obj.ref_t().coerce();
-                        return Triple::Ok(vi);
+                        return LookupOutcome::Found(vi);
                     }
-                    Triple::Err(e) => {
-                        return Triple::Err(e);
+                    LookupOutcome::Absent => {}
+                    other => {
+                        return other;
                     }
-                    _ => {}
                 }
                 if let Some(ctx) = self.get_same_name_context(&ctx.name) {
-                    match ctx.rec_get_var_info(ident, AccessKind::BoundAttr, input, namespace) {
-                        Triple::Ok(vi) => {
-                            return Triple::Ok(vi);
-                        }
-                        Triple::Err(e) => {
-                            return Triple::Err(e);
-                        }
-                        _ => {}
+                    let outcome =
+                        ctx.rec_get_var_info(ident, AccessKind::BoundAttr, input, namespace);
+                    if !outcome.is_absent() {
+                        return outcome;
                     }
                 }
             }
         }
-        Triple::None
+        LookupOutcome::Absent
     }
 
     /// get type from given attributive type (Record).
     /// not ModuleType or ClassType etc.
     /// if `t == Never`, returns `VarInfo::ILLEGAL`
-    fn get_attr_info_from_attributive(
-        &self,
-        t: &Type,
-        ident: &Identifier,
-    ) -> Triple<VarInfo, TyCheckError> {
-        match t {
-            // (obj: Never).foo: Never
-            Type::Never => Triple::Ok(VarInfo::ILLEGAL),
-            Type::FreeVar(fv) if fv.is_linked() => {
-                self.get_attr_info_from_attributive(&fv.crack(), ident)
-            }
-            Type::FreeVar(fv) /* if fv.is_unbound() */ => {
-                let sup = fv.get_super().unwrap();
-                self.get_attr_info_from_attributive(&sup, ident)
-            }
-            Type::Ref(t) => self.get_attr_info_from_attributive(t, ident),
-            Type::RefMut { before, .. } => {
-                self.get_attr_info_from_attributive(before, ident)
-            }
-            Type::Refinement(refine) => {
-                self.get_attr_info_from_attributive(&refine.t, ident)
-            }
-            Type::Record(record) => {
-                if let Some((field, attr_t)) = record.get_key_value(ident.inspect()) {
-                    let muty = Mutability::from(&ident.inspect()[..]);
-                    let vi = VarInfo::new(
-                        attr_t.clone(),
-                        muty,
-                        Visibility::new(field.vis.clone(), Str::ever("<dummy>")),
-                        VarKind::Builtin,
-                        None,
-                        None,
-                        None,
-                        AbsLocation::unknown(),
-                    );
-                    if let Err(err) = self.validate_visibility(ident, &vi, &self.cfg.input, self) {
-                        return Triple::Err(err);
-                    }
-                    Triple::Ok(vi)
-                } else {
-                    Triple::None
+    fn get_attr_info_from_attributive(&self, t: &Type, ident: &Identifier) -> LookupOutcome {
+        // follows the wrapped types with a loop so that deeply nested types don't overflow the stack
+        // (recurses only into the linked types)
+        let mut t = t;
+        loop {
+            match t {
+                // (obj: Never).foo: Never
+                Type::Never => return LookupOutcome::Found(VarInfo::ILLEGAL),
+                Type::FreeVar(fv) if fv.is_linked() => {
+                    return self.get_attr_info_from_attributive(&fv.crack(), ident);
+                }
+                Type::FreeVar(fv) /* if fv.is_unbound() */ => {
+                    let sup = fv.get_super().unwrap();
+                    return self.get_attr_info_from_attributive(&sup, ident);
+                }
+                Type::Ref(inner) | Type::RefMut { before: inner, .. } | Type::Structural(inner) => {
+                    t = inner;
+                }
+                Type::Refinement(refine) => {
+                    t = &refine.t;
+                }
+                Type::Record(record) => {
+                    return if let Some((field, attr_t)) = record.get_key_value(ident.inspect()) {
+                        let muty = Mutability::from(&ident.inspect()[..]);
+                        let vi = VarInfo::new(
+                            attr_t.clone(),
+                            muty,
+                            Visibility::new(field.vis.clone(), Str::ever("<dummy>")),
+                            VarKind::Builtin,
+                            None,
+                            None,
+                            None,
+                            AbsLocation::unknown(),
+                        );
+                        self.lookup_visible(ident, &vi, &self.cfg.input, self, self)
+                    } else {
+                        LookupOutcome::Absent
+                    };
                 }
+                _other => return LookupOutcome::Absent,
             }
-            Type::Structural(t) => self.get_attr_info_from_attributive(t, ident),
-            _other => Triple::None,
         }
     }
 
@@ -843,18 +966,11 @@ impl Context {
         if intersecs.len() == 1 {
             Ok(instance)
         } else {
-            let input_t = subr_t(
-                SubrKind::Proc,
-                pos_args
-                    .iter()
-                    .map(|pos| ParamTy::Pos(pos.expr.t()))
-                    .collect(),
-                None,
+            let input_t = overload_input_t(
+                pos_args.iter().map(|pos| pos.expr.t()),
                 kw_args
                     .iter()
-                    .map(|kw| ParamTy::kw(kw.keyword.content.clone(), kw.expr.t()))
-                    .collect(),
-                Obj,
+                    .map(|kw| (kw.keyword.content.clone(), kw.expr.t())),
             );
             for ty in intersecs.iter() {
                 if self.subtype_of(ty, &input_t) {
@@ -874,6 +990,36 @@ impl Context {
         }
     }
 
+    /// Returns the index (in `instance.intersection_types()`) of the overload accepting the arguments passed so far.
+    /// Unlike `resolve_overload`, `pos_args` may be a part of the arguments (e.g. while the call is being edited),
+    /// so each overload is checked with as many positional parameters as `pos_args`.
+    pub fn resolve_partial_overload(
+        &self,
+        instance: &Type,
+        pos_args: &[Type],
+        kw_args: &[(Str, Type)],
+    ) -> Option<usize> {
+        let input_t = overload_input_t(pos_args.iter().cloned(), kw_args.iter().cloned());
+        instance
+            .intersection_types()
+            .iter()
+            .position(|ty| match ty {
+                Subr(subr) => truncate_pos_params(subr, pos_args.len())
+                    .is_some_and(|truncated| self.subtype_of(&truncated, &input_t)),
+                _ => self.subtype_of(ty, &input_t),
+            })
+    }
+
+    /// A property is accessed as its value only from the instances.
+    /// `C.x` is the `property` object itself, so the getter is returned as is.
+    fn attr_value_of(&self, obj: &hir::Expr, vi: VarInfo) -> VarInfo {
+        if self.subtype_of(obj.ref_t(), &Type) {
+            vi
+        } else {
+            vi.into_property_value()
+        }
+    }
+
     pub(crate) fn get_same_name_context(&self, name: &str) -> Option<&Context> {
         if &self.name[..] == name {
             Some(self)
@@ -882,7 +1028,86 @@ impl Context {
         }
     }
 
-    // Note that the method may be static.
+    /// The results are cached by `AttrCache` if the receiver type has no type variables.
+    fn get_method_info_from_nominal_t(
+        &self,
+        obj: &hir::Expr,
+        attr_name: &Identifier,
+        input: &Input,
+        namespace: &Context,
+    ) -> SingleTyCheckResult<Option<VarInfo>> {
+        let Some(key) = Self::attr_cache_key(AttrLookupKind::Method, obj, attr_name) else {
+            return self.search_method_info_from_nominal_t(obj, attr_name, input, namespace);
+        };
+        if let Some(vi) = self.attr_cache.get(&key, &namespace.name) {
+            return Ok(Some(vi));
+        }
+        let found = self.search_method_info_from_nominal_t(obj, attr_name, input, namespace)?;
+        if let Some(vi) = &found {
+            self.attr_cache.insert(key, &namespace.name, vi);
+        }
+        Ok(found)
+    }
+
+    fn search_method_info_from_nominal_t(
+        &self,
+        obj: &hir::Expr,
+        attr_name: &Identifier,
+        input: &Input,
+        namespace: &Context,
+    ) -> SingleTyCheckResult<Option<VarInfo>> {
+        for ctx in self
+            .get_nominal_super_type_ctxs(obj.ref_t())
+            .ok_or_else(|| {
+                TyCheckError::type_not_found(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    obj.loc(),
+                    self.caused_by(),
+                    obj.ref_t(),
+                )
+            })?
+        {
+            if let Some(vi) = ctx
+                .locals
+                .get(attr_name.inspect())
+                .or_else(|| ctx.decls.get(attr_name.inspect()))
+            {
+                self.validate_visibility(attr_name, vi, input, namespace, ctx)?;
+                return Ok(Some(
+                    self.attr_value_of(obj, vi.clone())
+                        .into_bound_class_method(),
+                ));
+            }
+            for (_, methods_ctx) in ctx.methods_list.iter() {
+                if let Some(vi) = methods_ctx
+                    .locals
+                    .get(attr_name.inspect())
+                    .or_else(|| methods_ctx.decls.get(attr_name.inspect()))
+                {
+                    self.validate_visibility(attr_name, vi, input, namespace, methods_ctx)?;
+                    return Ok(Some(
+                        self.attr_value_of(obj, vi.clone())
+                            .into_bound_class_method(),
+                    ));
+                }
+            }
+            if let Some(ctx) = self.get_same_name_context(&ctx.name) {
+                match ctx.rec_get_var_info(attr_name, AccessKind::BoundAttr, input, namespace) {
+                    LookupOutcome::Found(vi) => {
+                        return Ok(Some(self.attr_value_of(obj, vi).into_bound_class_method()));
+                    }
+                    LookupOutcome::Absent => {}
+                    other => {
+                        return Err(other.err().unwrap());
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // Note that the method may be static or a class method (see `MethodKind`).
     fn search_method_info(
         &self,
         obj: &hir::Expr,
@@ -894,7 +1119,17 @@ impl Context {
     ) -> SingleTyCheckResult<VarInfo> {
         // search_method_info(?T, aaa, pos_args: [1, 2]) == None
         // => ?T(<: Structural({ .aaa = (self: ?T, ?U, ?V) -> ?W }))
-        if PYTHON_MODE && obj.var_info().is_some_and(|vi| vi.is_untyped_parameter()) {
+        if self.cfg.py_compat && obj.var_info().is_some_and(|vi| vi.is_untyped_parameter()) {
+            if self.severity_of(LenientCheck::UntypedAttr).is_error() {
+                return Err(TyCheckError::untyped_attr_error(
+                    input.clone(),
+                    line!() as usize,
+                    attr_name.loc(),
+                    namespace.caused_by(),
+                    obj.ref_t(),
+                    attr_name.inspect(),
+                ));
+            }
             let nd_params = pos_args
                 .iter()
                 .map(|_| ParamTy::Pos(free_var(self.level, Constraint::new_type_of(Type))))
@@ -934,55 +1169,16 @@ impl Context {
             return Ok(vi);
         }
         match self.get_attr_info_from_attributive(obj.ref_t(), attr_name) {
-            Triple::Ok(vi) => {
+            LookupOutcome::Found(vi) => {
                 return Ok(vi);
             }
-            Triple::Err(e) => {
-                return Err(e);
+            LookupOutcome::Absent => {}
+            other => {
+                return Err(other.err().unwrap());
             }
-            _ => {}
         }
-        for ctx in self
-            .get_nominal_super_type_ctxs(obj.ref_t())
-            .ok_or_else(|| {
-                TyCheckError::type_not_found(
-                    self.cfg.input.clone(),
-                    line!() as usize,
-                    obj.loc(),
-                    self.caused_by(),
-                    obj.ref_t(),
-                )
-            })?
-        {
-            if let Some(vi) = ctx
-                .locals
-                .get(attr_name.inspect())
-                .or_else(|| ctx.decls.get(attr_name.inspect()))
-            {
-                self.validate_visibility(attr_name, vi, input, namespace)?;
-                return Ok(vi.clone());
-            }
-            for (_, methods_ctx) in ctx.methods_list.iter() {
-                if let Some(vi) = methods_ctx
-                    .locals
-                    .get(attr_name.inspect())
-                    .or_else(|| methods_ctx.decls.get(attr_name.inspect()))
-                {
-                    self.validate_visibility(attr_name, vi, input, namespace)?;
-                    return Ok(vi.clone());
-                }
-            }
-            if let Some(ctx) = self.get_same_name_context(&ctx.name) {
-                match ctx.rec_get_var_info(attr_name, AccessKind::BoundAttr, input, namespace) {
-                    Triple::Ok(t) => {
-                        return Ok(t);
-                    }
-                    Triple::Err(e) => {
-                        return Err(e);
-                    }
-                    Triple::None => {}
-                }
-            }
+        if let Some(vi) = self.get_method_info_from_nominal_t(obj, attr_name, input, namespace)? {
+            return Ok(vi);
         }
         if let Ok(singular_ctxs) = self.get_singular_ctxs_by_hir_expr(obj, namespace) {
             for ctx in singular_ctxs {
@@ -991,8 +1187,8 @@ impl Context {
                     .get(attr_name.inspect())
                     .or_else(|| ctx.decls.get(attr_name.inspect()))
                 {
-                    self.validate_visibility(attr_name, vi, input, namespace)?;
-                    return Ok(vi.clone());
+                    self.validate_visibility(attr_name, vi, input, namespace, ctx)?;
+                    return Ok(vi.clone().into_bound_class_method());
                 }
                 for (_, method_ctx) in ctx.methods_list.iter() {
                     if let Some(vi) = method_ctx
@@ -1000,8 +1196,8 @@ impl Context {
                         .get(attr_name.inspect())
                         .or_else(|| method_ctx.decls.get(attr_name.inspect()))
                     {
-                        self.validate_visibility(attr_name, vi, input, namespace)?;
-                        return Ok(vi.clone());
+                        self.validate_visibility(attr_name, vi, input, namespace, method_ctx)?;
+                        return Ok(vi.clone().into_bound_class_method());
                     }
                 }
             }
@@ -1035,7 +1231,7 @@ impl Context {
                 .get(attr_name.inspect())
                 .or_else(|| patch.decls.get(attr_name.inspect()))
             {
-                self.validate_visibility(attr_name, vi, input, namespace)?;
+                self.validate_visibility(attr_name, vi, input, namespace, patch)?;
                 return Ok(vi.clone());
             }
             for (_, methods_ctx) in patch.methods_list.iter() {
@@ -1044,7 +1240,7 @@ impl Context {
                     .get(attr_name.inspect())
                     .or_else(|| methods_ctx.decls.get(attr_name.inspect()))
                 {
-                    self.validate_visibility(attr_name, vi, input, namespace)?;
+                    self.validate_visibility(attr_name, vi, input, namespace, methods_ctx)?;
                     return Ok(vi.clone());
                 }
             }
@@ -1071,16 +1267,19 @@ impl Context {
         ))
     }
 
+    /// `owner`: the context in which `vi` is defined
     fn validate_visibility(
         &self,
         ident: &Identifier,
         vi: &VarInfo,
         input: &Input,
         namespace: &Context,
+        owner: &Context,
     ) -> SingleTyCheckResult<()> {
         if vi.vis.compatible(&ident.acc_kind(), namespace) {
             Ok(())
         } else {
+            let public_alt = owner.get_public_alternative(ident, namespace);
             Err(TyCheckError::visibility_error(
                 input.clone(),
                 line!() as usize,
@@ -1088,10 +1287,53 @@ impl Context {
                 self.caused_by(),
                 ident.inspect(),
                 vi.vis.clone(),
+                &vi.def_loc,
+                public_alt.as_ref().map(|alt| (&alt[..], ident.name.loc())),
             ))
         }
     }
 
+    /// Finds an expression accessible from `namespace` that can be used instead of the invisible `ident`,
+    /// e.g. `x` or `get_x()` for `_x`.
+    fn get_public_alternative(&self, ident: &Identifier, namespace: &Context) -> Option<Str> {
+        let name = ident.inspect().trim_start_matches('_');
+        let candidates = [name.to_string(), format!("get_{name}")];
+        candidates.into_iter().find_map(|cand| {
+            if cand == ident.inspect()[..] {
+                return None;
+            }
+            let vi = self
+                .locals
+                .get(&cand[..])
+                .or_else(|| self.decls.get(&cand[..]))?;
+            if !vi.vis.compatible(&ident.acc_kind(), namespace) {
+                return None;
+            }
+            if vi.t.is_subr() {
+                Some(Str::from(format!("{cand}()")))
+            } else {
+                Some(Str::from(cand))
+            }
+        })
+    }
+
+    fn lookup_visible(
+        &self,
+        ident: &Identifier,
+        vi: &VarInfo,
+        input: &Input,
+        namespace: &Context,
+        owner: &Context,
+    ) -> LookupOutcome {
+        match self.validate_visibility(ident, vi, input, namespace, owner) {
+            Ok(()) => LookupOutcome::Found(vi.clone()),
+            Err(err) => LookupOutcome::Invisible {
+                vi: vi.clone(),
+                err,
+            },
+        }
+    }
+
     // HACK: dname.loc()はダミーLocationしか返さないので、エラーならop.loc()で上書きする
     fn append_loc_info(&self, e: TyCheckError, loc: Location) -> TyCheckError {
         if e.core.loc == Location::Unknown {
@@ -1105,7 +1347,8 @@ impl Context {
                 e.core.errno,
                 e.core.kind,
                 e.core.loc,
-            );
+            )
+            .with_suggestions(e.core.suggestions);
             TyCheckError::new(core, self.cfg.input.clone(), e.caused_by)
         } else {
             e
@@ -1120,6 +1363,10 @@ impl Context {
         namespace: &Context,
     ) -> TyCheckResult<VarInfo> {
         erg_common::debug_power_assert!(args.len() == 2);
+        if args.iter().any(|arg| arg.expr.ref_t().is_dynamic()) {
+            let t = func2(args[0].expr.t(), args[1].expr.t(), mono("Dynamic"));
+            return Ok(dynamic_var_info(t, Mutability::Immutable));
+        }
         let cont = Str::rc(binop_to_dname(op.inspect()));
         // not a `Token::from_str(op.kind, cont)` because ops are defined as symbols
         let symbol = Token::symbol_with_loc(cont, Location::concat(&args[0], &args[1]));
@@ -1165,6 +1412,10 @@ impl Context {
         namespace: &Context,
     ) -> TyCheckResult<VarInfo> {
         erg_common::debug_power_assert!(args.len() == 1);
+        if args[0].expr.ref_t().is_dynamic() {
+            let t = func1(args[0].expr.t(), mono("Dynamic"));
+            return Ok(dynamic_var_info(t, Mutability::Immutable));
+        }
         let cont = unaryop_to_dname(op.inspect());
         let symbol = Token::symbol(cont);
         let ident = Identifier::private_from_token(symbol.clone());
@@ -1222,6 +1473,7 @@ impl Context {
                     log!(info "~> {after}\n");
                     *self_t = *after.clone();
                     if let hir::Expr::Accessor(hir::Accessor::Ident(ident)) = receiver {
+                        self.check_exact_mutation(ident, self_t)?;
                         if let Some(vi) = self.rec_get_mut_var_info(&ident.raw, AccessKind::Name) {
                             vi.t = self_t.clone();
                         }
@@ -1234,6 +1486,39 @@ impl Context {
         }
     }
 
+    /// `x: Exact T` cannot be widened beyond `T` by a mutation of `x`
+    fn check_exact_mutation(&mut self, ident: &hir::Identifier, after: &Type) -> TyCheckResult<()> {
+        let Some(exact_t) = self
+            .rec_get_mut_var_info(&ident.raw, AccessKind::Name)
+            .and_then(|vi| vi.exact_t.clone())
+        else {
+            return Ok(());
+        };
+        let widened = self.coerce(after.clone(), ident)?;
+        if self.subtype_of(&widened, &exact_t) {
+            return Ok(());
+        }
+        let name = ident.inspect();
+        let hint = switch_lang!(
+            "japanese" => format!("{name}は`Exact`で宣言されているため、型を広げることはできません"),
+            "simplified_chinese" => format!("{name}是用`Exact`声明的，因此不能扩大其类型"),
+            "traditional_chinese" => format!("{name}是用`Exact`宣告的，因此不能擴大其型別"),
+            "english" => format!("{name} is declared with `Exact`, so its type cannot be widened"),
+        );
+        Err(TyCheckErrors::from(TyCheckError::type_mismatch_error(
+            self.cfg.input.clone(),
+            line!() as usize,
+            ident.loc(),
+            self.caused_by(),
+            name,
+            None,
+            &exact_t,
+            &widened,
+            None,
+            Some(hint),
+        )))
+    }
+
     fn not_callable_error(
         &self,
         obj: &hir::Expr,
@@ -1284,6 +1569,7 @@ impl Context {
         pos_args: &[hir::PosArg],
         kw_args: &[hir::KwArg],
     ) -> TyCheckResult<SubstituteResult> {
+        let _guard = self.enter_type_check(obj)?;
         match instance {
             Type::FreeVar(fv) if fv.is_linked() => {
                 self.substitute_call(obj, attr_name, &fv.crack(), pos_args, kw_args)
@@ -1658,6 +1944,14 @@ impl Context {
             }
         }
         self.sub_unify(arg_t, param_t, arg, param.name())
+            .or_else(|errs| {
+                // the argument will be converted by `Into(param_t).into` (see `Lowerer::insert_into_conversions`)
+                if self.get_into_target(arg_t, param_t).is_some() {
+                    Ok(())
+                } else {
+                    Err(errs)
+                }
+            })
             .map_err(|errs| {
                 log!(err "semi-unification failed with {callee}\n{arg_t} !<: {param_t}");
                 let name = if let Some(attr) = attr_name {
@@ -1844,6 +2138,10 @@ impl Context {
                 }
             }
         }
+        // `Dynamic` values can be called with any arguments
+        if obj.ref_t().is_dynamic() {
+            return Ok(dynamic_var_info(dynamic_func(), Mutability::Immutable));
+        }
         let found = self
             .search_callee_info(obj, attr_name, pos_args, kw_args, input, namespace)
             .map_err(|err| (None, TyCheckErrors::from(err)))?;
@@ -1962,6 +2260,41 @@ impl Context {
         )
     }
 
+    /// Searches the other modules in the module cache for a public variable `name`,
+    /// and returns the path to import the module from the current module (e.g. `"foo"`, `"sub/bar"`).
+    /// Only the modules under the directory of the current module are searched.
+    pub(crate) fn get_import_path_of(&self, name: &str) -> Option<String> {
+        let cur_path = self.module_path()?.canonicalize().ok()?;
+        let cur_dir = cur_path.parent()?;
+        let mod_cache = self.shared.as_ref()?.mod_cache.ref_inner();
+        let mut found = mod_cache
+            .iter()
+            .filter(|(path, entry)| {
+                *path != &cur_path
+                    && entry
+                        .module
+                        .context
+                        .locals
+                        .get(name)
+                        .is_some_and(|vi| vi.vis.is_public())
+            })
+            .filter_map(|(path, _)| {
+                let rel = path.strip_prefix(cur_dir).ok()?;
+                let rel = if rel.file_stem()? == "__init__" {
+                    rel.parent()?.to_path_buf()
+                } else {
+                    rel.with_extension("")
+                };
+                let rel = rel.to_str()?.replace('\\', "/");
+                // declaration files (`.d.er`) are imported by `pyimport`
+                (!rel.is_empty() && !rel.ends_with(".d")).then_some(rel)
+            })
+            .collect::<Vec<_>>();
+        // the nearest module
+        found.sort_by_key(|rel| (rel.matches('/').count(), rel.clone()));
+        found.into_iter().next()
+    }
+
     pub(crate) fn get_similar_attr_from_singular<'a>(
         &'a self,
         obj: &hir::Expr,
@@ -2203,18 +2536,82 @@ impl Context {
         t: &Type,
     ) -> Option<impl Iterator<Item = &'a Context>> {
         let (_, ctx) = self.get_nominal_type_ctx(t)?;
-        let sups = ctx
-            .super_classes
-            .iter()
-            .chain(ctx.super_traits.iter())
-            .map(|sup| {
-                self.get_nominal_type_ctx(sup)
-                    .unwrap_or_else(|| todo!("compiler bug: {sup} not found"))
-                    .1
-            });
+        // the cached types are the registered ones, so they are resolved without evaluation
+        let cached = ctx.super_type_cache.get().and_then(|sups| {
+            sups.iter()
+                .map(|sup| self.get_nominal_type_ctx(sup).map(|(_, ctx)| ctx))
+                .collect::<Option<Vec<_>>>()
+        });
+        let sups = if let Some(sups) = cached {
+            sups
+        } else {
+            let (sup_ts, sups): (Vec<_>, Vec<_>) = ctx
+                .super_classes
+                .iter()
+                .chain(ctx.super_traits.iter())
+                .map(|sup| {
+                    let (sup_t, sup_ctx) = self
+                        .get_nominal_type_ctx(sup)
+                        .unwrap_or_else(|| todo!("compiler bug: {sup} not found"));
+                    (sup_t.clone(), sup_ctx)
+                })
+                .unzip();
+            ctx.super_type_cache.set(sup_ts);
+            sups
+        };
         Some(vec![ctx].into_iter().chain(sups))
     }
 
+    /// Returns the `@Abstract` methods of `class` (including inherited ones) that have not been overridden,
+    /// together with the class that declares them.
+    pub(crate) fn get_unimplemented_abstract_methods(&self, class: &Type) -> Vec<(VarName, Type)> {
+        let mut defined = Set::new();
+        let mut abstracts = vec![];
+        // subclasses come first
+        for ctx in self
+            .get_nominal_super_type_ctxs(class)
+            .into_iter()
+            .flatten()
+        {
+            if !ctx.kind.is_class() {
+                continue;
+            }
+            for (name, vi) in ctx.locals.iter().chain(
+                ctx.methods_list
+                    .iter()
+                    .flat_map(|(_, methods)| methods.locals.iter()),
+            ) {
+                if !defined.insert(name.inspect().clone()) {
+                    continue;
+                }
+                if vi
+                    .comptime_decos
+                    .as_ref()
+                    .is_some_and(|decos| decos.contains("Abstract"))
+                {
+                    abstracts.push((name.clone(), mono(ctx.name.clone())));
+                }
+            }
+        }
+        abstracts
+    }
+
+    /// Returns the setter (`@Property x!`) of the property `x` of `class`.
+    pub(crate) fn get_property_setter(&self, class: &Type, name: &str) -> Option<VarInfo> {
+        let setter = Str::from(format!("{name}!"));
+        for ctx in self.get_nominal_super_type_ctxs(class)? {
+            let found = ctx.locals.get(&setter).or_else(|| {
+                ctx.methods_list
+                    .iter()
+                    .find_map(|(_, methods)| methods.locals.get(&setter))
+            });
+            if let Some(vi) = found {
+                return vi.is_property().then(|| vi.clone());
+            }
+        }
+        None
+    }
+
     pub(crate) fn _get_super_traits(&self, typ: &Type) -> Option<impl Iterator<Item = Type>> {
         self.get_nominal_type_ctx(typ)
             .map(|(_, ctx)| ctx.super_traits.clone().into_iter())
@@ -2264,116 +2661,93 @@ impl Context {
         &'a self,
         typ: &Type,
     ) -> Option<(&'a Type, &'a Context)> {
-        match typ {
-            Type::FreeVar(fv) if fv.is_linked() => {
-                if let Some(res) = self.get_nominal_type_ctx(&fv.crack()) {
-                    return Some(res);
+        // follows the wrapped types with a loop so that deeply nested types don't overflow the stack
+        // (recurses only into the linked types and the evaluated types)
+        let mut typ = typ;
+        loop {
+            match typ {
+                Type::FreeVar(fv) if fv.is_linked() => {
+                    return self.get_nominal_type_ctx(&fv.crack());
+                }
+                Type::FreeVar(fv) => {
+                    let sup = fv.get_super()?;
+                    return self.get_nominal_type_ctx(&sup);
+                }
+                Type::Refinement(refine) => {
+                    typ = &refine.t;
+                }
+                Type::Quantified(_) => {
+                    return self
+                        .get_builtins()
+                        .unwrap_or(self)
+                        .rec_local_get_mono_type("QuantifiedFunc");
                 }
-            }
-            Type::FreeVar(fv) => {
-                let sup = fv.get_super()?;
-                if let Some(res) = self.get_nominal_type_ctx(&sup) {
-                    return Some(res);
+                Type::Subr(subr) => {
+                    let name = match subr.kind {
+                        SubrKind::Func => "Func",
+                        SubrKind::Proc => "Proc",
+                    };
+                    return self
+                        .get_builtins()
+                        .unwrap_or(self)
+                        .rec_local_get_mono_type(name);
                 }
-            }
-            Type::Refinement(refine) => {
-                if let Some(res) = self.get_nominal_type_ctx(&refine.t) {
-                    return Some(res);
+                Type::Mono(name) => {
+                    return self.get_mono_type(name);
                 }
-            }
-            Type::Quantified(_) => {
-                if let Some((t, ctx)) = self
-                    .get_builtins()
-                    .unwrap_or(self)
-                    .rec_local_get_mono_type("QuantifiedFunc")
-                {
-                    return Some((t, ctx));
+                Type::Poly { name, .. } => {
+                    return self.get_poly_type(name);
                 }
-            }
-            Type::Subr(subr) => match subr.kind {
-                SubrKind::Func => {
-                    if let Some((t, ctx)) = self
+                Type::Record(rec) if rec.values().all(|attr| self.supertype_of(&Type, attr)) => {
+                    return self
                         .get_builtins()
                         .unwrap_or(self)
-                        .rec_local_get_mono_type("Func")
-                    {
-                        return Some((t, ctx));
-                    }
+                        .rec_local_get_mono_type("RecordType");
                 }
-                SubrKind::Proc => {
-                    if let Some((t, ctx)) = self
+                Type::Record(_) => {
+                    return self
                         .get_builtins()
                         .unwrap_or(self)
-                        .rec_local_get_mono_type("Proc")
-                    {
-                        return Some((t, ctx));
-                    }
+                        .rec_local_get_mono_type("Record");
                 }
-            },
-            Type::Mono(name) => {
-                return self.get_mono_type(name);
-            }
-            Type::Poly { name, .. } => {
-                return self.get_poly_type(name);
-            }
-            Type::Record(rec) if rec.values().all(|attr| self.supertype_of(&Type, attr)) => {
-                return self
-                    .get_builtins()
-                    .unwrap_or(self)
-                    .rec_local_get_mono_type("RecordType");
-            }
-            Type::Record(_) => {
-                return self
-                    .get_builtins()
-                    .unwrap_or(self)
-                    .rec_local_get_mono_type("Record");
-            }
-            Type::Or(_l, _r) => {
-                if let Some(ctx) = self.get_nominal_type_ctx(&poly("Or", vec![])) {
-                    return Some(ctx);
+                Type::Or(_l, _r) => {
+                    return self.get_nominal_type_ctx(&poly("Or", vec![]));
                 }
-            }
-            // FIXME: `F()`などの場合、実際は引数が省略されていてもmonomorphicになる
-            other if other.is_monomorphic() => {
-                if let Some((t, ctx)) = self.rec_local_get_mono_type(&other.local_name()) {
-                    return Some((t, ctx));
-                }
-            }
-            Type::Ref(t) | Type::RefMut { before: t, .. } => {
-                if let Some(res) = self.get_nominal_type_ctx(t) {
-                    return Some(res);
+                // FIXME: `F()`などの場合、実際は引数が省略されていてもmonomorphicになる
+                other if other.is_monomorphic() => {
+                    return self.rec_local_get_mono_type(&other.local_name());
                 }
-            }
-            Type::Bounded { sup, .. } => {
-                if let Some(res) = self.get_nominal_type_ctx(sup) {
-                    return Some(res);
+                Type::Ref(t) | Type::RefMut { before: t, .. } | Type::Bounded { sup: t, .. } => {
+                    typ = t;
                 }
-            }
-            Type::Proj { lhs, rhs } => {
-                if let Ok(typ) = self.eval_proj(*lhs.clone(), rhs.clone(), self.level, &()) {
+                Type::Proj { lhs, rhs } => {
+                    let typ = self
+                        .eval_proj(*lhs.clone(), rhs.clone(), self.level, &())
+                        .ok()?;
                     return self.get_nominal_type_ctx(&typ);
                 }
-            }
-            Type::ProjCall {
-                lhs,
-                attr_name,
-                args,
-            } => {
-                if let Ok(typ) = self.eval_proj_call(
-                    *lhs.clone(),
-                    attr_name.clone(),
-                    args.clone(),
-                    self.level,
-                    &(),
-                ) {
+                Type::ProjCall {
+                    lhs,
+                    attr_name,
+                    args,
+                } => {
+                    let typ = self
+                        .eval_proj_call(
+                            *lhs.clone(),
+                            attr_name.clone(),
+                            args.clone(),
+                            self.level,
+                            &(),
+                        )
+                        .ok()?;
                     return self.get_nominal_type_ctx(&typ);
                 }
-            }
-            other => {
-                log!("{other} has no nominal definition");
+                other => {
+                    log!("{other} has no nominal definition");
+                    return None;
+                }
             }
         }
-        None
     }
 
     /// It is currently not possible to get the type defined in another module
@@ -2466,6 +2840,25 @@ impl Context {
         }
     }
 
+    /// Returns `T` if `from` implements exactly one `Into(T)` such that `T <: to`.
+    /// No conversion is chosen if the candidates are ambiguous.
+    pub(crate) fn get_into_target(&self, from: &Type, to: &Type) -> Option<Type> {
+        if !from.has_no_unbound_var() || !to.has_no_unbound_var() {
+            return None;
+        }
+        let mut targets = self
+            .get_simple_trait_impls(&poly("Into", vec![]))
+            .into_iter()
+            .filter(|imp| self.subtype_of(from, &imp.sub_type))
+            .filter_map(|imp| {
+                let tp = imp.sup_trait.typarams().into_iter().next()?;
+                self.convert_tp_into_type(tp).ok()
+            })
+            .filter(|target| self.subtype_of(target, to));
+        let target = targets.next()?;
+        targets.next().is_none().then_some(target)
+    }
+
     pub(crate) fn get_simple_trait_impls(&self, trait_: &Type) -> Set<TraitImpl> {
         let current = if let Some(impls) = self.trait_impls().get(&trait_.qual_name()) {
             impls.clone()
@@ -2492,7 +2885,7 @@ impl Context {
         if name == "module" && ERG_MODE {
             self.get_module()
         } else if name == "global" {
-            self.get_builtins()
+            self.get_builtin_module()
         } else {
             let t = self.get_var_info(name).map(|(_, vi)| &vi.t)?;
             self.get_mod_with_t(t)
@@ -2519,8 +2912,7 @@ impl Context {
 
     // rec_get_const_localとは違い、位置情報を持たないしエラーとならない
     pub(crate) fn rec_get_const_obj(&self, name: &str) -> Option<&ValueObj> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.search_name(name);
         if let Some(val) = self.consts.get(name) {
             return Some(val);
         }
@@ -2585,10 +2977,17 @@ impl Context {
 
     pub(crate) fn get_namespace(&self, namespace: &Str) -> Option<&Context> {
         if &namespace[..] == "global" {
-            return self.get_builtins();
+            return self.get_builtin_module();
         } else if &namespace[..] == "module" {
             return self.get_module();
         }
+        // the types defined in the prelude
+        if let Some(prelude) = self
+            .get_prelude_module()
+            .filter(|prelude| prelude.name == *namespace)
+        {
+            return Some(prelude);
+        }
         self.get_mod_with_path(self.get_namespace_path(namespace)?.as_path())
     }
 
@@ -2610,10 +3009,32 @@ impl Context {
         None
     }
 
+    /// Returns the body of a recursive type alias (see `Context::eval_rec_type_alias`).
+    /// e.g. `<module>::Json` => `Int or Str or Array(<module>::Json, _)`
+    pub(crate) fn unfold_rec_alias(&self, typ: &Type) -> Option<Type> {
+        let Type::Mono(_) = typ else {
+            return None;
+        };
+        let namespace = typ.namespace();
+        let ctx = if self.name.starts_with(&namespace[..]) {
+            self
+        } else {
+            self.get_namespace(&namespace)?
+        };
+        match ctx.rec_get_const_obj(&typ.local_name())? {
+            ValueObj::Type(t)
+                if (t.typ().is_union_type() || t.typ().is_intersection_type())
+                    && t.typ().contains_type(typ) =>
+            {
+                Some(t.typ().clone())
+            }
+            _ => None,
+        }
+    }
+
     /// you should use `get_mono_type` instead of this
     pub(crate) fn rec_local_get_mono_type(&self, name: &str) -> Option<(&Type, &Context)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.search_name(name);
         if let Some((t, ctx)) = self.mono_types.get(name) {
             Some((t, ctx))
         } else if let Some(outer) = self.get_outer().or_else(|| self.get_builtins()) {
@@ -2624,8 +3045,7 @@ impl Context {
     }
 
     pub(crate) fn rec_local_get_poly_type(&self, name: &str) -> Option<(&Type, &Context)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.search_name(name);
         if let Some((t, ctx)) = self.poly_types.get(name) {
             Some((t, ctx))
         } else if let Some(outer) = self.get_outer().or_else(|| self.get_builtins()) {
@@ -2654,8 +3074,7 @@ impl Context {
     }
 
     fn rec_get_mut_mono_type(&mut self, name: &str) -> Option<(&mut Type, &mut Context)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.name_resolver.registered_name(name, self.cfg.py_compat);
         if let Some((t, ctx)) = self.mono_types.get_mut(name) {
             Some((t, ctx))
         } else if let Some(outer) = self.outer.as_mut() {
@@ -2667,8 +3086,7 @@ impl Context {
     }
 
     fn rec_get_mut_poly_type(&mut self, name: &str) -> Option<(&mut Type, &mut Context)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.name_resolver.registered_name(name, self.cfg.py_compat);
         if let Some((t, ctx)) = self.poly_types.get_mut(name) {
             Some((t, ctx))
         } else if let Some(outer) = self.outer.as_mut() {
@@ -2679,8 +3097,7 @@ impl Context {
     }
 
     pub(crate) fn rec_get_mut_type(&mut self, name: &str) -> Option<(&Type, &mut Context)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.name_resolver.registered_name(name, self.cfg.py_compat);
         if let Some((t, ctx)) = self.mono_types.get_mut(name) {
             Some((t, ctx))
         } else if let Some((t, ctx)) = self.poly_types.get_mut(name) {
@@ -2712,8 +3129,7 @@ impl Context {
 
     /// you should use `get_type` instead of this
     pub(crate) fn rec_local_get_type(&self, name: &str) -> Option<(&Type, &Context)> {
-        #[cfg(feature = "py_compat")]
-        let name = self.erg_to_py_names.get(name).map_or(name, |s| &s[..]);
+        let name = self.search_name(name);
         if let Some((t, ctx)) = self.mono_types.get(name) {
             Some((t, ctx))
         } else if let Some((t, ctx)) = self.poly_types.get(name) {
@@ -2941,27 +3357,46 @@ impl Context {
     }
 
     pub(crate) fn is_class(&self, typ: &Type) -> bool {
-        match typ {
-            Type::And(_l, _r) => false,
-            Type::Never => true,
-            Type::FreeVar(fv) if fv.is_linked() => self.is_class(&fv.crack()),
-            Type::FreeVar(_) => false,
-            Type::Or(l, r) => self.is_class(l) && self.is_class(r),
-            Type::Proj { lhs, rhs } => self
-                .get_proj_candidates(lhs, rhs)
-                .iter()
-                .all(|t| self.is_class(t)),
-            Type::Refinement(refine) => self.is_class(&refine.t),
-            Type::Ref(t) | Type::RefMut { before: t, .. } => self.is_class(t),
-            _ => {
-                if let Some((_, ctx)) = self.get_nominal_type_ctx(typ) {
-                    ctx.kind.is_class()
-                } else {
-                    // TODO: unknown types
-                    false
+        // uses a worklist so that deeply nested types don't overflow the stack
+        // (recurses only into the linked types and the projection candidates)
+        let mut worklist = vec![typ];
+        while let Some(typ) = worklist.pop() {
+            let is_class = match typ {
+                Type::And(_l, _r) => false,
+                Type::Never => true,
+                Type::FreeVar(fv) if fv.is_linked() => self.is_class(&fv.crack()),
+                Type::FreeVar(_) => false,
+                Type::Or(l, r) => {
+                    worklist.push(l);
+                    worklist.push(r);
+                    true
+                }
+                Type::Proj { lhs, rhs } => self
+                    .get_proj_candidates(lhs, rhs)
+                    .iter()
+                    .all(|t| self.is_class(t)),
+                Type::Refinement(refine) => {
+                    worklist.push(&refine.t);
+                    true
+                }
+                Type::Ref(t) | Type::RefMut { before: t, .. } => {
+                    worklist.push(t);
+                    true
+                }
+                _ => {
+                    if let Some((_, ctx)) = self.get_nominal_type_ctx(typ) {
+                        ctx.kind.is_class()
+                    } else {
+                        // TODO: unknown types
+                        false
+                    }
                 }
+            };
+            if !is_class {
+                return false;
             }
         }
+        true
     }
 
     pub(crate) fn is_trait(&self, typ: &Type) -> bool {
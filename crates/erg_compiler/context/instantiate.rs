@@ -273,13 +273,6 @@ impl Context {
                         tmp_tv_cache.push_or_init_typaram(&varname, &tp, self);
                         return Ok(tp);
                     }
-                    if let Some(tv_cache) = &self.tv_cache {
-                        if let Some(tp) = tv_cache.get_typaram(&name) {
-                            return Ok(tp.clone());
-                        } else if let Some(t) = tv_cache.get_tyvar(&name) {
-                            return Ok(TyParam::t(t.clone()));
-                        }
-                    }
                     tmp_tv_cache.push_appeared(name.clone());
                     let constr = tmp_tv_cache.instantiate_constraint(constr, self, loc)?;
                     let tp = TyParam::named_free_var(name.clone(), self.level, constr);
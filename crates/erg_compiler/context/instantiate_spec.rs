@@ -546,6 +546,23 @@ impl Context {
                 if let Some(decl_t) = opt_decl_t {
                     return Ok(decl_t.typ().clone());
                 }
+                // union/intersection/record aliases and structural aliases (e.g. `Person = {"name": Str}`)
+                // have no nominal context of their own
+                match self.rec_get_const_obj(ident.inspect()) {
+                    Some(ValueObj::Type(t))
+                        if t.typ().is_union_type()
+                            || t.typ().is_intersection_type()
+                            || t.typ().is_record() =>
+                    {
+                        return Ok(t.typ().clone());
+                    }
+                    Some(value @ (ValueObj::Dict(_) | ValueObj::Record(_))) => {
+                        if let Some(t) = value.as_type(self) {
+                            return Ok(t.into_typ());
+                        }
+                    }
+                    _ => {}
+                }
                 if let Some((typ, _)) = self.get_type(ident.inspect()) {
                     Ok(typ.clone())
                 } else if not_found_is_qvar {
@@ -664,7 +681,61 @@ impl Context {
                 )?;
                 Ok(t.structuralize())
             }
+            // Interval(L, R) == L..<R
+            "Interval" => {
+                let mut args = poly_spec.args.pos_args();
+                let (Some(lhs), Some(rhs)) = (args.next(), args.next()) else {
+                    return Err(TyCheckErrors::from(TyCheckError::args_missing_error(
+                        self.cfg.input.clone(),
+                        line!() as usize,
+                        poly_spec.args.loc(),
+                        "Interval",
+                        self.caused_by(),
+                        vec![Str::from("L"), Str::from("R")],
+                    )));
+                };
+                let l =
+                    self.instantiate_const_expr(&lhs.expr, None, tmp_tv_cache, not_found_is_qvar)?;
+                let l = self.eval_tp(l)?;
+                let r =
+                    self.instantiate_const_expr(&rhs.expr, None, tmp_tv_cache, not_found_is_qvar)?;
+                let r = self.eval_tp(r)?;
+                Ok(int_interval(IntervalOp::RightOpen, l, r))
+            }
+            // the exactness is recorded in the variable (see `Context::assign_var_sig`)
+            "Exact" => {
+                let mut args = poly_spec.args.pos_args();
+                let Some(first) = args.next() else {
+                    return Err(TyCheckErrors::from(TyCheckError::args_missing_error(
+                        self.cfg.input.clone(),
+                        line!() as usize,
+                        poly_spec.args.loc(),
+                        "Exact",
+                        self.caused_by(),
+                        vec![Str::from("T")],
+                    )));
+                };
+                self.instantiate_const_expr_as_type(
+                    &first.expr,
+                    None,
+                    tmp_tv_cache,
+                    not_found_is_qvar,
+                )
+            }
             other => {
+                // type-level const functions (e.g. `Omit(User, ["password"])`)
+                if let Some(ValueObj::Subr(_)) = self.rec_get_const_obj(other) {
+                    let app = ast::ConstApp::new(poly_spec.acc.clone(), poly_spec.args.clone());
+                    let value = self.eval_const_expr(&ast::ConstExpr::App(app).downgrade())?;
+                    return self.convert_value_into_type(value).map_err(|value| {
+                        TyCheckErrors::from(TyCheckError::feature_error(
+                            self.cfg.input.clone(),
+                            poly_spec.loc(),
+                            &format!("instantiating {value} as a type"),
+                            self.caused_by(),
+                        ))
+                    });
+                }
                 let Some((typ, ctx)) = self.get_type(&Str::rc(other)) else {
                     return Err(TyCheckErrors::from(TyCheckError::no_type_error(
                         self.cfg.input.clone(),
@@ -832,10 +903,19 @@ impl Context {
                 let ast::ConstAccessor::Local(ident) = &app.acc else {
                     return type_feature_error!(self, app.loc(), "instantiating const callee");
                 };
-                let &ctx = self
-                    .get_singular_ctxs_by_ident(ident, self)?
-                    .first()
-                    .unwrap_or(&self);
+                let ctx = match self.get_singular_ctxs_by_ident(ident, self) {
+                    Ok(ctxs) => ctxs.first().copied().unwrap_or(self),
+                    // const functions (e.g. `unit_mul`) have no type context
+                    Err(_)
+                        if matches!(
+                            self.rec_get_const_obj(ident.inspect()),
+                            Some(ValueObj::Subr(_))
+                        ) =>
+                    {
+                        self
+                    }
+                    Err(err) => return Err(err.into()),
+                };
                 let mut args = vec![];
                 for (i, arg) in app.args.pos_args().enumerate() {
                     let arg_t = self.instantiate_const_expr(
@@ -1116,7 +1196,7 @@ impl Context {
                 let obj = self.instantiate_tp_as_type(*obj, loc)?;
                 Ok(proj(obj, attr))
             }
-            TyParam::App { name, args } => Ok(poly(name, args)),
+            TyParam::App { name, args } if !self.is_value_app(&name) => Ok(poly(name, args)),
             TyParam::Type(t) => Ok(*t),
             #[allow(clippy::bind_instead_of_map)]
             TyParam::Value(value) => self.convert_value_into_type(value).or_else(|value| {
@@ -1440,9 +1520,10 @@ impl Context {
                         not_found_is_qvar,
                     )?);
                 }
-                let ty = new_set.iter().fold(Type::Never, |t, tp| {
-                    self.union(&t, &self.get_tp_t(tp).unwrap())
-                });
+                let mut ty = Type::Never;
+                for tp in new_set.iter() {
+                    ty = self.union(&ty, &self.get_tp_t(tp)?);
+                }
                 Ok(tp_enum(ty, new_set))
             }
             TypeSpec::Interval { op, lhs, rhs } => {
@@ -0,0 +1,110 @@
+//! defines `LookupOutcome`, the result of variable/attribute lookups
+use crate::error::{SingleTyCheckResult, TyCheckError};
+use crate::varinfo::VarInfo;
+
+/// The result of looking up a variable or an attribute.
+///
+/// Unlike `Triple<VarInfo, TyCheckError>`, a failed lookup tells why the name could not be used,
+/// so that callers can report precise errors (and ELS can offer fixes).
+#[derive(Debug, Clone)]
+pub enum LookupOutcome {
+    Found(VarInfo),
+    /// The name exists, but it is not visible from the namespace.
+    /// `vi` is the hidden definition.
+    Invisible {
+        vi: VarInfo,
+        err: TyCheckError,
+    },
+    /// The name is defined later in the same scope
+    NotYetDefined(TyCheckError),
+    /// The name has been deleted by `Del`
+    Deleted(TyCheckError),
+    /// The name was found, but it cannot be used here (e.g. the receiver type does not match)
+    Err(TyCheckError),
+    /// The name is not defined anywhere
+    Absent,
+}
+
+impl From<SingleTyCheckResult<VarInfo>> for LookupOutcome {
+    fn from(res: SingleTyCheckResult<VarInfo>) -> Self {
+        match res {
+            Ok(vi) => Self::Found(vi),
+            Err(err) => Self::Err(err),
+        }
+    }
+}
+
+impl LookupOutcome {
+    pub const fn is_found(&self) -> bool {
+        matches!(self, Self::Found(_))
+    }
+
+    pub const fn is_absent(&self) -> bool {
+        matches!(self, Self::Absent)
+    }
+
+    pub fn ok(self) -> Option<VarInfo> {
+        match self {
+            Self::Found(vi) => Some(vi),
+            _ => None,
+        }
+    }
+
+    pub fn unwrap_or(self, default: VarInfo) -> VarInfo {
+        self.ok().unwrap_or(default)
+    }
+
+    pub fn map_ok_or<U>(self, default: U, f: impl FnOnce(VarInfo) -> U) -> U {
+        self.ok().map_or(default, f)
+    }
+
+    /// The error to be reported, if any (`Absent` has none)
+    pub fn err(self) -> Option<TyCheckError> {
+        match self {
+            Self::Found(_) | Self::Absent => None,
+            Self::Invisible { err, .. }
+            | Self::NotYetDefined(err)
+            | Self::Deleted(err)
+            | Self::Err(err) => Some(err),
+        }
+    }
+
+    /// Applies `f` to the found (or hidden) `VarInfo`.
+    pub fn map(self, f: impl FnOnce(VarInfo) -> VarInfo) -> Self {
+        match self {
+            Self::Found(vi) => Self::Found(f(vi)),
+            Self::Invisible { vi, err } => Self::Invisible { vi: f(vi), err },
+            other => other,
+        }
+    }
+
+    /// Looks up in another place only if the name is absent here.
+    pub fn or_else(self, f: impl FnOnce() -> Self) -> Self {
+        match self {
+            Self::Absent => f(),
+            other => other,
+        }
+    }
+
+    /// Looks up in another place (e.g. an outer scope) if the name is absent or invisible here.
+    /// If it is not found there either, the invisible one is reported.
+    pub fn or_fallback(self, f: impl FnOnce() -> Self) -> Self {
+        match self {
+            Self::Absent => f(),
+            Self::Invisible { .. } => match f() {
+                Self::Absent | Self::Invisible { .. } => self,
+                other => other,
+            },
+            other => other,
+        }
+    }
+
+    /// `Absent` is converted to the error returned by `f`.
+    pub fn none_or_result(self, f: impl FnOnce() -> TyCheckError) -> SingleTyCheckResult<VarInfo> {
+        match self {
+            Self::Found(vi) => Ok(vi),
+            Self::Absent => Err(f()),
+            other => Err(other.err().unwrap()),
+        }
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! `Context` is used for type inference and type checking.
 #![allow(clippy::result_unit_err)]
+pub mod attr_cache;
+pub mod budget;
 pub mod compare;
 pub mod eval;
 pub mod generalize;
@@ -10,7 +12,11 @@ pub mod initialize;
 pub mod inquire;
 pub mod instantiate;
 pub mod instantiate_spec;
+pub mod lookup;
+pub mod name_resolver;
 pub mod register;
+pub mod severity;
+pub mod super_type_cache;
 pub mod test;
 pub mod unify;
 
@@ -33,8 +39,11 @@ use erg_parser::ast;
 use erg_parser::ast::Def;
 use erg_parser::token::Token;
 
+use crate::context::attr_cache::AttrCache;
 use crate::context::instantiate::TyVarCache;
 use crate::context::instantiate_spec::ConstTemplate;
+use crate::context::name_resolver::NameResolver;
+use crate::context::super_type_cache::SuperTypeCache;
 use crate::error::{TyCheckError, TyCheckErrors};
 use crate::module::{
     SharedCompilerResource, SharedModuleCache, SharedModuleIndex, SharedPromises, SharedTraitImpls,
@@ -50,6 +59,18 @@ pub trait ContextProvider {
     fn dir(&self) -> Dict<&VarName, &VarInfo>;
     fn get_receiver_ctx(&self, receiver_name: &str) -> Option<&Context>;
     fn get_var_info(&self, name: &str) -> Option<(&VarName, &VarInfo)>;
+    /// The names in `dir` except the internal ones (e.g. `%v1`, `<lambda>`), used for the REPL completion
+    fn dir_names(&self) -> Vec<String> {
+        self.dir()
+            .into_iter()
+            .map(|(name, _)| name.inspect().to_string())
+            .filter(|name| {
+                name.chars()
+                    .next()
+                    .is_some_and(|c| c.is_alphabetic() || c == '_')
+            })
+            .collect()
+    }
 }
 
 const BUILTINS: &Str = &Str::ever("<builtins>");
@@ -407,6 +428,8 @@ pub struct Context {
     // patchによってsuper class/traitになったものはここに含まれない
     pub(crate) super_classes: Vec<Type>, // if self is a patch, means patch classes
     pub(crate) super_traits: Vec<Type>,  // if self is not a trait, means implemented traits
+    /// the resolved `super_classes` and `super_traits`
+    pub(crate) super_type_cache: SuperTypeCache,
     // method definitions, if the context is a type
     // specializations are included and needs to be separated out
     pub(crate) methods_list: Vec<(ClassDefType, Context)>,
@@ -446,9 +469,11 @@ pub struct Context {
     pub(crate) patches: Dict<VarName, Context>,
     pub(crate) shared: Option<SharedCompilerResource>,
     pub(crate) tv_cache: Option<TyVarCache>,
+    /// shared with the outer contexts
+    pub(crate) attr_cache: AttrCache,
     pub(crate) higher_order_caller: Vec<Str>,
     pub(crate) guards: Vec<GuardType>,
-    pub(crate) erg_to_py_names: Dict<Str, Str>,
+    pub(crate) name_resolver: NameResolver,
     pub(crate) level: usize,
 }
 
@@ -482,7 +507,7 @@ impl ContextProvider for Context {
         if let Some(outer) = self.get_outer() {
             vars.guaranteed_extend(outer.dir());
         } else if let Some(builtins) = self.get_builtins() {
-            vars.guaranteed_extend(builtins.locals.iter());
+            vars.guaranteed_extend(builtins.dir());
         }
         vars
     }
@@ -500,7 +525,7 @@ impl ContextProvider for Context {
     fn get_var_info(&self, name: &str) -> Option<(&VarName, &VarInfo)> {
         self.get_var_kv(name).or_else(|| {
             self.get_builtins()
-                .and_then(|builtin| builtin.get_var_kv(name))
+                .and_then(|builtin| builtin.get_var_info(name))
         })
     }
 }
@@ -542,7 +567,7 @@ impl Context {
         self.mono_types.remove(name);
         self.poly_types.remove(name);
         self.patches.remove(name);
-        self.erg_to_py_names.remove(name);
+        self.name_resolver.unregister(name);
         self.locals
             .remove(name)
             .or_else(|| self.locals.remove(name))
@@ -628,6 +653,7 @@ impl Context {
             outer: outer.map(Box::new),
             super_classes: vec![],
             super_traits: vec![],
+            super_type_cache: SuperTypeCache::new(),
             methods_list: vec![],
             const_param_defaults: Dict::default(),
             method_to_traits: Dict::default(),
@@ -643,10 +669,11 @@ impl Context {
             poly_types: Dict::default(),
             shared,
             tv_cache: None,
+            attr_cache: AttrCache::new(),
             patches: Dict::default(),
             higher_order_caller: vec![],
             guards: vec![],
-            erg_to_py_names: Dict::default(),
+            name_resolver: NameResolver::new(),
             level,
         }
     }
@@ -990,7 +1017,39 @@ impl Context {
 
     /// Returns None if self is `<builtins>`.
     /// This avoids infinite loops.
+    ///
+    /// If the project has a prelude, returns its overlay (whose parent is `<builtins>`).
     pub(crate) fn get_builtins(&self) -> Option<&Context> {
+        self.get_prelude().or_else(|| self.get_builtin_module())
+    }
+
+    /// Returns the overlay context built from the public names of the project prelude.
+    /// See `Context::build_prelude`.
+    pub(crate) fn get_prelude(&self) -> Option<&Context> {
+        let prelude = self.cfg.prelude.as_ref()?;
+        self.shared
+            .as_ref()?
+            .mod_cache
+            .raw_ref_ctx(&Self::prelude_overlay_path(prelude))
+            .map(|mod_ctx| &mod_ctx.context)
+    }
+
+    /// Returns the module context of the project prelude itself.
+    pub(crate) fn get_prelude_module(&self) -> Option<&Context> {
+        let prelude = self.cfg.prelude.as_ref()?;
+        self.shared
+            .as_ref()?
+            .mod_cache
+            .raw_ref_ctx(prelude.as_path())
+            .map(|mod_ctx| &mod_ctx.context)
+    }
+
+    pub(crate) fn is_prelude_overlay(&self) -> bool {
+        self.kind == ContextKind::Module && &self.name[..] == "<prelude>"
+    }
+
+    /// Returns `<builtins>` itself, ignoring the prelude overlay.
+    pub(crate) fn get_builtin_module(&self) -> Option<&Context> {
         // builtins中で定義した型等はmod_cacheがNoneになっている
         if self.kind != ContextKind::Module || &self.path()[..] != "<builtins>" {
             self.shared
@@ -1069,6 +1128,7 @@ impl Context {
         };
         self.cfg = self.get_outer().unwrap().cfg.clone();
         self.shared = self.get_outer().unwrap().shared.clone();
+        self.attr_cache = self.get_outer().unwrap().attr_cache.clone();
         self.tv_cache = tv_cache;
         self.name = name.into();
         self.kind = kind;
@@ -1163,6 +1223,60 @@ impl Context {
         self.type_dir(self)
     }
 
+    /// The names defined (or declared) in this context, in the order of definition (the declared-only names come last)
+    pub fn local_vars(&self) -> impl Iterator<Item = (&VarName, &VarInfo)> {
+        self.locals.iter().chain(
+            self.decls
+                .iter()
+                .filter(|(name, _)| !self.locals.contains_key(*name)),
+        )
+    }
+
+    /// The test functions defined in this context (see `VarInfo::is_test`), in the order of definition
+    pub fn test_functions(&self) -> Vec<(&VarName, &VarInfo)> {
+        let mut tests = self
+            .locals
+            .iter()
+            .filter(|(name, vi)| vi.is_test(name.inspect()))
+            .collect::<Vec<_>>();
+        tests.sort_by_key(|(_, vi)| (vi.def_loc.loc.ln_begin(), vi.def_loc.loc.col_begin()));
+        tests
+    }
+
+    /// The types defined in this context with their attributes (including the methods)
+    pub fn local_type_members(&self) -> Vec<(&VarName, Vec<(&VarName, &VarInfo)>)> {
+        self.mono_types
+            .iter()
+            .chain(self.poly_types.iter())
+            .map(|(type_name, (_, type_ctx))| {
+                let attrs = type_ctx.local_vars().chain(
+                    type_ctx
+                        .methods_list
+                        .iter()
+                        .flat_map(|(_, methods)| methods.local_vars()),
+                );
+                (type_name, attrs.collect())
+            })
+            .collect()
+    }
+
+    /// The names defined (or declared) in this context and the attributes of the types defined in it.
+    /// Each name is paired with the name of the type it belongs to (`None` for the names of this context).
+    pub fn local_members(&self) -> Vec<(Option<&VarName>, &VarName, &VarInfo)> {
+        let mut members = self
+            .local_vars()
+            .map(|(name, vi)| (None, name, vi))
+            .collect::<Vec<_>>();
+        for (type_name, attrs) in self.local_type_members() {
+            members.extend(
+                attrs
+                    .into_iter()
+                    .map(|(name, vi)| (Some(type_name), name, vi)),
+            );
+        }
+        members
+    }
+
     pub(crate) fn opt_mod_cache(&self) -> Option<&SharedModuleCache> {
         self.shared.as_ref().map(|s| &s.mod_cache)
     }
@@ -0,0 +1,97 @@
+//! Defines `NameResolver`, the mapping between Erg names and Python names.
+//!
+//! A variable defined with a Python name (e.g. converted from a Python script) is registered by the Python name,
+//! but referred to by the Erg name in the Python compatible mode (`py_compat`).
+use erg_common::dict::Dict;
+use erg_common::Str;
+
+use crate::context::Context;
+
+/// Erg name <-> Python name
+#[derive(Debug, Clone, Default)]
+pub struct NameResolver {
+    erg_to_py: Dict<Str, Str>,
+    py_to_erg: Dict<Str, Str>,
+}
+
+impl NameResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, erg_name: Str, py_name: Str) {
+        if let Some(old) = self.erg_to_py.insert(erg_name.clone(), py_name.clone()) {
+            self.py_to_erg.remove(&old);
+        }
+        self.py_to_erg.insert(py_name, erg_name);
+    }
+
+    /// Returns the Python name of `erg_name`
+    pub fn unregister(&mut self, erg_name: &str) -> Option<Str> {
+        let py_name = self.erg_to_py.remove(erg_name)?;
+        self.py_to_erg.remove(&py_name);
+        Some(py_name)
+    }
+
+    pub fn to_py_name(&self, erg_name: &str) -> Option<&Str> {
+        self.erg_to_py.get(erg_name)
+    }
+
+    pub fn from_py_name(&self, py_name: &str) -> Option<&Str> {
+        self.py_to_erg.get(py_name)
+    }
+
+    /// The name by which `erg_name` is registered (the Python name in the Python compatible mode)
+    pub fn registered_name<'a>(&'a self, erg_name: &'a str, py_compat: bool) -> &'a str {
+        if py_compat {
+            self.to_py_name(erg_name).map_or(erg_name, |s| &s[..])
+        } else {
+            erg_name
+        }
+    }
+
+    /// (Erg name, Python name)
+    pub fn iter_aliases(&self) -> impl Iterator<Item = (&Str, &Str)> {
+        self.erg_to_py.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.erg_to_py.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.erg_to_py.is_empty()
+    }
+}
+
+impl Context {
+    pub fn name_resolver(&self) -> &NameResolver {
+        &self.name_resolver
+    }
+
+    pub(crate) fn search_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.name_resolver.registered_name(name, self.cfg.py_compat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_resolver() {
+        let mut resolver = NameResolver::new();
+        resolver.register(Str::ever("f!"), Str::ever("f"));
+        resolver.register(Str::ever("g"), Str::ever("g_"));
+        assert_eq!(resolver.to_py_name("f!"), Some(&Str::ever("f")));
+        assert_eq!(resolver.from_py_name("f"), Some(&Str::ever("f!")));
+        // re-registration replaces the old alias
+        resolver.register(Str::ever("g"), Str::ever("_g"));
+        assert_eq!(resolver.from_py_name("g_"), None);
+        assert_eq!(resolver.from_py_name("_g"), Some(&Str::ever("g")));
+        assert_eq!(resolver.iter_aliases().count(), 2);
+        assert_eq!(resolver.unregister("f!"), Some(Str::ever("f")));
+        assert_eq!(resolver.from_py_name("f"), None);
+        assert_eq!(resolver.len(), 1);
+    }
+}
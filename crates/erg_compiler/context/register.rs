@@ -1,13 +1,14 @@
 use std::fmt;
-use std::fs::{metadata, remove_file, File};
+use std::fs::{create_dir_all, metadata, read_to_string, remove_file, write, File};
 use std::io::{BufRead, BufReader};
 use std::option::Option;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::thread::sleep;
 use std::time::{Duration, SystemTime};
 
-use erg_common::config::ErgMode;
+use erg_common::config::{ErgConfig, ErgMode};
 use erg_common::consts::{ERG_MODE, PYTHON_MODE};
 use erg_common::dict::Dict;
 use erg_common::env::{is_pystd_main_module, is_std_decl_path};
@@ -18,8 +19,7 @@ use erg_common::pathutil::{DirKind, FileKind};
 use erg_common::python_util::BUILTIN_PYTHON_MODS;
 use erg_common::set::Set;
 use erg_common::spawn::spawn_new_thread;
-use erg_common::traits::{Locational, Stream};
-use erg_common::triple::Triple;
+use erg_common::traits::{Locational, Runnable, Stream};
 use erg_common::{dict, get_hash, log, set, unique_in_place, Str};
 
 use ast::{
@@ -27,6 +27,7 @@ use ast::{
     VarName,
 };
 use erg_parser::ast;
+use erg_parser::build_ast::ASTBuilder;
 
 use crate::ty::constructors::{
     free_var, func, func0, func1, proc, ref_, ref_mut, tp_enum, unknown_len_array_t, v_enum,
@@ -35,16 +36,24 @@ use crate::ty::free::{Constraint, HasLevel};
 use crate::ty::typaram::TyParam;
 use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
 use crate::ty::{
-    Field, GuardType, HasType, ParamTy, SubrType, Type, Variable, Visibility, VisibilityModifier,
+    ConstSubr, Field, GuardType, HasType, ParamTy, SubrType, Type, UserConstSubr, Variable,
+    Visibility, VisibilityModifier,
 };
 
 use crate::build_hir::HIRBuilder;
-use crate::context::{ClassDefType, Context, ContextKind, DefaultInfo, RegistrationMode};
+use crate::context::lookup::LookupOutcome;
+use crate::context::{
+    ClassDefType, Context, ContextKind, DefaultInfo, RegistrationMode, TraitImpl,
+};
+use crate::derive::DERIVE_ATTR;
 use crate::error::readable_name;
 use crate::error::{
     CompileError, CompileErrors, CompileResult, TyCheckError, TyCheckErrors, TyCheckResult,
 };
 use crate::hir::Literal;
+use crate::manifest::get_str_array_entry;
+use crate::module::{PromiseEvent, SharedCompilerResource};
+use crate::pyi_conv::PyiConverter;
 use crate::varinfo::{AbsLocation, Mutability, VarInfo, VarKind};
 use crate::{feature_error, hir};
 use Mutability::*;
@@ -58,6 +67,10 @@ pub fn valid_mod_name(name: &str) -> bool {
     !name.is_empty() && !name.starts_with('/') && name.trim() == name
 }
 
+fn is_pyi(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "pyi")
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CheckStatus {
     Succeed,
@@ -160,6 +173,21 @@ use Availability::*;
 
 const UBAR: &Str = &Str::ever("_");
 
+/// Removes the common indentation and the surrounding blank lines
+fn dedent(src: &str) -> String {
+    let indent = src
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let lines = src
+        .lines()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>();
+    format!("{}\n", lines.join("\n").trim_matches('\n'))
+}
+
 impl Context {
     /// If it is a constant that is defined, there must be no variable of the same name defined across all scopes
     pub(crate) fn registered_info(
@@ -312,10 +340,10 @@ impl Context {
             _ => unreachable!(),
         };
         if let Some(py_name) = &py_name {
-            self.erg_to_py_names
-                .insert(ident.inspect().clone(), py_name.clone());
+            self.name_resolver
+                .register(ident.inspect().clone(), py_name.clone());
         }
-        let ident = if PYTHON_MODE && py_name.is_some() {
+        let ident = if self.cfg.py_compat && py_name.is_some() {
             let mut symbol = ident.name.clone().into_token();
             symbol.content = py_name.clone().unwrap();
             Identifier::new(ident.vis.clone(), VarName::new(symbol))
@@ -368,7 +396,7 @@ impl Context {
                 body_t.clone()
             }
         });
-        let vi = VarInfo::new(
+        let mut vi = VarInfo::new(
             t,
             muty,
             Visibility::new(vis, self.name.clone()),
@@ -378,6 +406,11 @@ impl Context {
             py_name,
             self.absolutize(ident.name.loc()),
         );
+        vi.exact_t = sig
+            .t_spec
+            .as_ref()
+            .filter(|ts| ts.t_spec.is_exact())
+            .and_then(|ts| self.instantiate_typespec(&ts.t_spec).ok());
         log!(info "Registered {}{}: {}", self.name, ident, vi);
         self.locals.insert(ident.name.clone(), vi.clone());
         if let Ok(value) = self.convert_singular_type_into_value(vi.t.clone()) {
@@ -907,6 +940,8 @@ impl Context {
         let mut total_errs = TyCheckErrors::empty();
         for expr in block.iter() {
             match expr {
+                // nested `Enum`s are not desugared (reported in `lower_def`)
+                ast::Expr::Def(def) if def.body.is_enum() => {}
                 ast::Expr::Def(def) => {
                     if let Err(errs) = self.preregister_def(def) {
                         total_errs.extend(errs);
@@ -952,6 +987,22 @@ impl Context {
         let Ok(mod_name) = hir::Literal::try_from(mod_name.token.clone()) else {
             return Ok(());
         };
+        // cimport "mod", """.f: Int -> Int"""
+        if call
+            .obj
+            .get_name()
+            .is_some_and(|name| &name[..] == "cimport")
+        {
+            if let Some(ast::Expr::Literal(decls)) = call.args.nth_or_key(1, "decls") {
+                if let (ValueObj::Str(__name__), Ok(decls)) =
+                    (&mod_name.value, hir::Literal::try_from(decls.token.clone()))
+                {
+                    if let ValueObj::Str(decls) = &decls.value {
+                        self.register_ffi_decls(__name__, decls, &mod_name)?;
+                    }
+                }
+            }
+        }
         let res = self
             .import_mod(call.additional_operation().unwrap(), &mod_name)
             .map(|_path| ());
@@ -979,6 +1030,32 @@ impl Context {
         res
     }
 
+    /// Registers a method whose body is a constant expression as a constant subroutine,
+    /// so that it can be called at compile time (e.g. `assert_static((P + Q).x == 4)`).
+    /// Must be called after `preregister_def`.
+    /// Module-level constants are preregistered before the methods, so they cannot call these methods.
+    pub(crate) fn register_const_method(&mut self, def: &ast::Def) {
+        let ast::Signature::Subr(sig) = &def.sig else {
+            return;
+        };
+        let Ok(block) = erg_parser::Parser::validate_const_block(def.body.block.clone()) else {
+            return;
+        };
+        let Some((_, vi)) = self.get_var_info(sig.ident.inspect()) else {
+            return;
+        };
+        let subr = UserConstSubr::new(
+            sig.ident.inspect().clone(),
+            sig.params.clone(),
+            block,
+            vi.t.clone(),
+        );
+        self.consts.insert(
+            sig.ident.name.clone(),
+            ValueObj::Subr(ConstSubr::User(subr)),
+        );
+    }
+
     pub(crate) fn preregister_def(&mut self, def: &ast::Def) -> TyCheckResult<()> {
         let id = Some(def.body.id);
         let __name__ = def.sig.ident().map(|i| i.inspect()).unwrap_or(UBAR);
@@ -1029,12 +1106,18 @@ impl Context {
                 if sig.is_const() {
                     let kind = ContextKind::from(def);
                     let vis = self.instantiate_vis_modifier(sig.vis())?;
-                    self.grow(__name__, kind, vis, None);
+                    self.grow(__name__, kind.clone(), vis.clone(), None);
                     let (obj, const_t) = match self.eval_const_block(&def.body.block) {
                         Ok(obj) => (obj.clone(), v_enum(set! {obj})),
                         Err(errs) => {
                             self.pop();
-                            return Err(errs);
+                            let Some(obj) = sig.ident().and_then(|ident| {
+                                self.eval_rec_type_alias(ident, def, kind.clone(), vis.clone())
+                            }) else {
+                                return Err(errs);
+                            };
+                            self.grow(__name__, kind, vis, None);
+                            (obj.clone(), v_enum(set! {obj}))
                         }
                     };
                     if let Some(spec) = sig.t_spec.as_ref() {
@@ -1069,6 +1152,35 @@ impl Context {
         Ok(())
     }
 
+    /// Evaluates a self-referential type alias, e.g. `Json = Int or Str or [Json; _] or {Str: Json}`.
+    /// The references to the alias in the body are folded into the type of the alias (`<module>::Json`),
+    /// which is unfolded on demand (see `Context::unfold_rec_alias`).
+    /// Returns `None` if the body is not a union or an intersection referring to the alias.
+    fn eval_rec_type_alias(
+        &mut self,
+        ident: &Identifier,
+        def: &ast::Def,
+        kind: ContextKind,
+        vis: VisibilityModifier,
+    ) -> Option<ValueObj> {
+        let folded = self.gen_type(ident);
+        self.consts
+            .insert(ident.name.clone(), ValueObj::builtin_class(folded.clone()));
+        self.grow(ident.inspect(), kind, vis, None);
+        let res = self.eval_const_block(&def.body.block);
+        self.pop();
+        self.consts.remove(&ident.name);
+        match res {
+            Ok(ValueObj::Type(t))
+                if (t.typ().is_union_type() || t.typ().is_intersection_type())
+                    && t.typ().contains_type(&folded) =>
+            {
+                Some(ValueObj::Type(t))
+            }
+            _ => None,
+        }
+    }
+
     /// e.g. .new
     fn register_auto_impl(
         &mut self,
@@ -1106,13 +1218,13 @@ impl Context {
     /// e.g. ::__new__
     fn register_fixed_auto_impl(
         &mut self,
-        name: &'static str,
+        name: impl Into<Str>,
         t: Type,
         muty: Mutability,
         vis: Visibility,
         py_name: Option<Str>,
     ) -> CompileResult<()> {
-        let name = VarName::from_static(name);
+        let name = VarName::from_str(name.into());
         if self.locals.get(&name).is_some() {
             Err(CompileErrors::from(CompileError::reassign_error(
                 self.cfg.input.clone(),
@@ -1206,6 +1318,8 @@ impl Context {
     }
 
     pub(crate) fn register_trait(&mut self, class: Type, methods: Self) {
+        self.invalidate_attr_cache();
+        self.invalidate_super_type_cache();
         let trait_ = if let ContextKind::MethodDefs(Some(tr)) = &methods.kind {
             tr.clone()
         } else {
@@ -1217,6 +1331,8 @@ impl Context {
     }
 
     pub(crate) fn register_marker_trait(&mut self, ctx: &Self, trait_: Type) -> CompileResult<()> {
+        self.invalidate_attr_cache();
+        self.invalidate_super_type_cache();
         let (_, trait_ctx) = ctx.get_nominal_type_ctx(&trait_).ok_or_else(|| {
             CompileError::type_not_found(
                 self.cfg.input.clone(),
@@ -1298,6 +1414,7 @@ impl Context {
                         self.level,
                     );
                     self.gen_class_new_method(&gen, &mut ctx)?;
+                    self.gen_derived_methods(&gen, &mut ctx)?;
                     self.register_gen_mono_type(ident, gen, ctx, Const)
                 } else {
                     let params = gen
@@ -1315,6 +1432,7 @@ impl Context {
                         self.level,
                     );
                     self.gen_class_new_method(&gen, &mut ctx)?;
+                    self.gen_derived_methods(&gen, &mut ctx)?;
                     self.register_gen_poly_type(ident, gen, ctx, Const)
                 }
             }
@@ -1330,16 +1448,21 @@ impl Context {
                         self.level,
                     );
                     for sup in super_classes.into_iter() {
-                        let (_, sup_ctx) = self.get_nominal_type_ctx(&sup).ok_or_else(|| {
-                            TyCheckErrors::from(TyCheckError::type_not_found(
-                                self.cfg.input.clone(),
-                                line!() as usize,
-                                ident.loc(),
-                                self.caused_by(),
-                                &sup,
-                            ))
-                        })?;
-                        ctx.register_superclass(sup, sup_ctx);
+                        let (sup_t, sup_ctx) =
+                            self.get_nominal_type_ctx(&sup).ok_or_else(|| {
+                                TyCheckErrors::from(TyCheckError::type_not_found(
+                                    self.cfg.input.clone(),
+                                    line!() as usize,
+                                    ident.loc(),
+                                    self.caused_by(),
+                                    &sup,
+                                ))
+                            })?;
+                        if sup_t.has_qvar() {
+                            ctx.register_applied_superclass(sup, sup_t, sup_ctx);
+                        } else {
+                            ctx.register_superclass(sup, sup_ctx);
+                        }
                     }
                     let mut methods =
                         Self::methods(None, self.cfg.clone(), self.shared.clone(), 2, self.level);
@@ -1386,6 +1509,7 @@ impl Context {
                         )?;
                         ctx.methods_list
                             .push((ClassDefType::Simple(gen.typ().clone()), methods));
+                        self.gen_derived_methods(&gen, &mut ctx)?;
                         self.register_gen_mono_type(ident, gen, ctx, Const)
                     } else {
                         let class_name = gen.base_or_sup().unwrap().typ().local_name();
@@ -1419,6 +1543,10 @@ impl Context {
                     );
                     let Some(TypeObj::Builtin{ t: Type::Record(req), .. }) = gen.base_or_sup() else { todo!("{gen}") };
                     self.register_instance_attrs(&mut ctx, req)?;
+                    if let Some(module) = gen.derive_module() {
+                        let derive = VarName::from_static(DERIVE_ATTR);
+                        ctx.consts.insert(derive, ValueObj::Str(module.clone()));
+                    }
                     self.register_gen_mono_type(ident, gen, ctx, Const)
                 } else {
                     feature_error!(
@@ -1580,6 +1708,54 @@ impl Context {
         Ok(())
     }
 
+    /// Synthesizes the trait implementations requested by `Derive` (see `crate::derive`).
+    fn gen_derived_methods(&self, gen: &GenTypeObj, ctx: &mut Context) -> CompileResult<()> {
+        for derived in gen.derives() {
+            let trait_ = &derived.trait_;
+            let (_, trait_ctx) = self.get_nominal_type_ctx(trait_).ok_or_else(|| {
+                CompileError::type_not_found(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    ().loc(),
+                    self.caused_by(),
+                    trait_,
+                )
+            })?;
+            let mut methods = Self::methods(
+                Some(trait_.clone()),
+                self.cfg.clone(),
+                self.shared.clone(),
+                2,
+                self.level,
+            );
+            for method in derived.methods.iter() {
+                methods.register_fixed_auto_impl(
+                    method.name.clone(),
+                    method.t.clone(),
+                    Immutable,
+                    Visibility::BUILTIN_PUBLIC,
+                    method.py_name.clone(),
+                )?;
+            }
+            ctx.register_supertrait(trait_.clone(), trait_ctx);
+            ctx.methods_list.push((
+                ClassDefType::impl_trait(gen.typ().clone(), trait_.clone()),
+                methods,
+            ));
+            if let Some(shared) = self.shared.as_ref() {
+                let impl_ = TraitImpl::new(gen.typ().clone(), trait_.clone());
+                if let Some(mut impls) = shared.trait_impls.get_mut(&trait_.qual_name()) {
+                    impls.insert(impl_);
+                } else {
+                    shared
+                        .trait_impls
+                        .register(trait_.qual_name(), set! { impl_ });
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn register_type_alias(
         &mut self,
         ident: &Identifier,
@@ -1633,6 +1809,7 @@ impl Context {
         ctx: Self,
         muty: Mutability,
     ) -> CompileResult<()> {
+        self.invalidate_attr_cache();
         let vis = self.instantiate_vis_modifier(&ident.vis)?;
         // FIXME: recursive search
         if self.mono_types.contains_key(ident.inspect()) {
@@ -1683,6 +1860,7 @@ impl Context {
         ctx: Self,
         muty: Mutability,
     ) -> CompileResult<()> {
+        self.invalidate_attr_cache();
         let vis = self.instantiate_vis_modifier(&ident.vis)?;
         // FIXME: recursive search
         if self.poly_types.contains_key(ident.inspect()) {
@@ -1743,6 +1921,7 @@ impl Context {
         ctx: Self,
         muty: Mutability,
     ) -> CompileResult<()> {
+        self.invalidate_attr_cache();
         let vis = self.instantiate_vis_modifier(&ident.vis)?;
         // FIXME: recursive search
         if self.patches.contains_key(ident.inspect()) {
@@ -1817,6 +1996,25 @@ impl Context {
                 None,
             )));
         }
+        // declaration files are not executed on the target
+        let in_decl_file = self
+            .cfg
+            .input
+            .path()
+            .is_some_and(|path| path.to_string_lossy().ends_with(".d.er"));
+        if !kind.is_erg_import() && !in_decl_file && !self.cfg.target.is_available_module(__name__)
+        {
+            return Err(TyCheckErrors::from(
+                TyCheckError::not_available_on_target_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    &format!("module {__name__}"),
+                    self.cfg.target,
+                    mod_name.loc(),
+                    self.caused_by(),
+                ),
+            ));
+        }
         if kind.is_erg_import() {
             self.import_erg_mod(__name__, mod_name)
         } else {
@@ -1824,6 +2022,52 @@ impl Context {
         }
     }
 
+    /// Writes the declarations given to `cimport` to `{dir}/__pycache__/{mod}.d.er`,
+    /// so that the module is resolved and built like other declaration files.
+    fn register_ffi_decls(
+        &self,
+        __name__: &Str,
+        decls: &str,
+        loc: &impl Locational,
+    ) -> TyCheckResult<()> {
+        let path = Path::new(&__name__[..]);
+        let Some(file_name) = path.file_name() else {
+            return Ok(());
+        };
+        let mut decl_path = self.cfg.input.dir();
+        if let Some(parent) = path.parent() {
+            decl_path.push(parent);
+        }
+        decl_path.push("__pycache__");
+        decl_path.push(format!("{}.d.er", file_name.to_string_lossy()));
+        // The first line must not be empty (an empty first line means that the analysis is in progress)
+        let src = format!(
+            "# generated from the declarations of `cimport` in {}\n{}",
+            self.cfg.input.unescaped_filename(),
+            dedent(decls)
+        );
+        if read_to_string(&decl_path).is_ok_and(|old| old == src) {
+            return Ok(());
+        }
+        create_dir_all(decl_path.parent().unwrap())
+            .and_then(|_| write(&decl_path, src))
+            .map_err(|err| {
+                TyCheckErrors::from(TyCheckError::file_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    err.to_string(),
+                    loc.loc(),
+                    self.caused_by(),
+                    None,
+                ))
+            })?;
+        // the old declarations may have been built
+        if let Ok(path) = decl_path.canonicalize() {
+            self.py_mod_cache().remove(&path);
+        }
+        Ok(())
+    }
+
     fn import_err(&self, line: u32, __name__: &Str, loc: &impl Locational) -> TyCheckErrors {
         let mod_cache = self.mod_cache();
         let py_mod_cache = self.py_mod_cache();
@@ -1880,22 +2124,24 @@ impl Context {
     /// }
     /// .f() = b.f()
     /// ```
+    ///
+    /// If the module has top-level declarations, only they are built (see `build_mod_interface`).
     fn build_cyclic_mod(&self, path: &Path) {
+        let src = self.decl_src(path, Input::file(path.to_path_buf()).read());
+        let is_decl = path.to_string_lossy().ends_with("d.er") || is_pyi(path);
+        if !is_decl && self.build_mod_interface(path, src.clone()) {
+            return;
+        }
         let mod_ctx = ModuleContext::new(self.clone(), dict! {});
         let mut builder = HIRBuilder::new_with_ctx(mod_ctx);
-        let src = Input::file(path.to_path_buf()).read();
-        let mode = if path.to_string_lossy().ends_with("d.er") {
-            "declare"
-        } else {
-            "exec"
-        };
+        let mode = if is_decl { "declare" } else { "exec" };
         let res = builder.build(src, mode);
         let hir = match res {
             Ok(art) => Some(art.object),
             Err(art) => art.object,
         };
         let ctx = builder.pop_mod_ctx().unwrap();
-        let cache = if path.to_string_lossy().ends_with("d.er") {
+        let cache = if is_decl {
             &self.shared().py_mod_cache
         } else {
             &self.shared().mod_cache
@@ -1903,6 +2149,69 @@ impl Context {
         cache.register(path.to_path_buf(), hir, ctx);
     }
 
+    /// Builds the interface of the module, i.e. its top-level declarations, and registers it with `interface_path`.
+    /// Modules importing the module cyclically refer to the interface instead of the module being analyzed.
+    /// Returns `false` if the module has no declarations.
+    ///
+    /// ```erg
+    /// # a.er
+    /// b = import "b"
+    /// .f: () -> Int # this is the interface of `a`
+    /// .f() = b.g() + 1
+    /// ```
+    /// ```erg
+    /// # b.er
+    /// a = import "a"
+    /// .g() = 1
+    /// .h() = a.f() * 2 # OK: `a.f: () -> Int`
+    /// ```
+    fn build_mod_interface(&self, path: &Path, src: String) -> bool {
+        let cfg = self.cfg.inherit(path.to_path_buf());
+        let Ok(artifact) = ASTBuilder::new(cfg.copy()).build(src) else {
+            return false;
+        };
+        let ast = artifact.ast;
+        let decls = ast
+            .module
+            .into_iter()
+            .filter(|chunk| match chunk {
+                ast::Expr::TypeAscription(tasc) => {
+                    matches!(
+                        tasc.expr.as_ref(),
+                        ast::Expr::Accessor(ast::Accessor::Ident(_))
+                    )
+                }
+                ast::Expr::Literal(lit) => lit.is_doc_comment(),
+                _ => false,
+            })
+            .collect::<Vec<_>>();
+        if !decls
+            .iter()
+            .any(|chunk| matches!(chunk, ast::Expr::TypeAscription(_)))
+        {
+            return false;
+        }
+        let name = Str::rc(cfg.input.unescaped_file_stem());
+        let shared = self.shared().inherit(path.to_path_buf());
+        let mut builder = HIRBuilder::new_with_cache(cfg, name, shared);
+        let ast = ast::AST::new(ast.name, ast::Module::new(decls));
+        let hir = match builder.check(ast, "declare") {
+            Ok(artifact) => Some(artifact.object),
+            Err(artifact) => artifact.object,
+        };
+        let Some(ctx) = builder.pop_mod_ctx() else {
+            return false;
+        };
+        self.mod_cache()
+            .register(Self::interface_path(path), hir, ctx);
+        self.promises().register_interface(path.to_path_buf());
+        true
+    }
+
+    pub(crate) fn interface_path(path: &Path) -> PathBuf {
+        PathBuf::from(format!("<interface>{}", path.display()))
+    }
+
     /// If the path is like `foo/bar`, check if `bar` is a public module (the definition is in `foo/__init__.er`)
     fn check_mod_vis(
         &self,
@@ -1966,8 +2275,15 @@ impl Context {
         __name__: &Str,
         loc: &impl Locational,
     ) -> CompileResult<PathBuf> {
+        self.promises()
+            .register_import_site(path.clone(), loc.loc());
         if self.mod_registered(&path) {
-            return Ok(path);
+            // the dependencies of `path` have been modified after the analysis (see `SharedCompilerResource::invalidate`)
+            let stale = self.promises().is_finished(&path) && self.shared().graph.is_dirty(&path);
+            if !stale {
+                return Ok(path);
+            }
+            self.shared().discard(&path);
         }
         let mut cfg = self.cfg.inherit(path.clone());
         let src = cfg
@@ -1977,11 +2293,40 @@ impl Context {
         let name = __name__.clone();
         let _path = path.clone();
         let shared = self.shared.as_ref().unwrap().inherit(path.clone());
+        // used to report a panic of the analysis at the import site
+        let (input, loc, caused_by) = (self.cfg.input.clone(), loc.loc(), self.caused_by());
+        // the analysis must not start before the promise is registered,
+        // otherwise the module may be imported (cyclically) and analyzed again in the meantime
+        let (registered_tx, registered_rx) = std::sync::mpsc::channel::<()>();
         let run = move || {
-            let mut builder = HIRBuilder::new_with_cache(cfg, name, shared.clone());
-            match builder.build(src, "exec") {
+            let _ = registered_rx.recv();
+            let mut builder = HIRBuilder::new_with_cache(cfg, name.clone(), shared.clone());
+            // a panic is isolated to the module, so that the rest of the project can be analyzed
+            let res = catch_unwind(AssertUnwindSafe(|| builder.build(src, "exec")));
+            let res = match res {
+                Ok(res) => res,
+                Err(payload) => {
+                    let msg = payload
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| payload.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic".to_string());
+                    let err = CompileError::module_panic_error(
+                        input,
+                        line!() as usize,
+                        loc,
+                        caused_by,
+                        &name,
+                        &msg,
+                    );
+                    shared.errors.extend(CompileErrors::from(err));
+                    shared.promises.notify(PromiseEvent::Finished(_path));
+                    return;
+                }
+            };
+            match res {
                 Ok(artifact) => {
-                    shared.mod_cache.register(
+                    shared.register_module(
                         _path.clone(),
                         Some(artifact.object),
                         builder.pop_mod_ctx().unwrap(),
@@ -1990,20 +2335,110 @@ impl Context {
                 }
                 Err(artifact) => {
                     if let Some(hir) = artifact.object {
-                        shared
-                            .mod_cache
-                            .register(_path, Some(hir), builder.pop_mod_ctx().unwrap());
+                        shared.register_module(
+                            _path.clone(),
+                            Some(hir),
+                            builder.pop_mod_ctx().unwrap(),
+                        );
                     }
                     shared.warns.extend(artifact.warns);
                     shared.errors.extend(artifact.errors);
                 }
             }
+            shared.promises.notify(PromiseEvent::Finished(_path));
         };
+        self.shared()
+            .promises
+            .notify(PromiseEvent::Started(path.clone()));
         let handle = spawn_new_thread(run, __name__);
         self.shared().promises.insert(path.clone(), handle);
+        let _ = registered_tx.send(());
         Ok(path)
     }
 
+    pub(crate) fn prelude_overlay_path(prelude: &Path) -> PathBuf {
+        PathBuf::from(format!("<prelude>{}", prelude.display()))
+    }
+
+    /// Builds the project prelude and registers the overlay of `<builtins>`, which consists of the public names of the prelude.
+    /// Names listed in the `banned_builtins` entry of `package.er` cannot be referred to through the overlay.
+    ///
+    /// The prelude is built synchronously, because every module of the project depends on it.
+    pub(crate) fn build_prelude(cfg: &ErgConfig, prelude: &Path, shared: &SharedCompilerResource) {
+        let overlay_path = Self::prelude_overlay_path(prelude);
+        if shared.mod_cache.get(&overlay_path).is_some() {
+            return;
+        }
+        let mut cfg = cfg.inherit(prelude.to_path_buf());
+        let Ok(src) = cfg.input.try_read() else {
+            return;
+        };
+        let name = Str::rc(cfg.input.unescaped_file_stem());
+        let mut builder =
+            HIRBuilder::new_with_cache(cfg.copy(), name, shared.inherit(prelude.to_path_buf()));
+        let hir = match builder.build(src, "exec") {
+            Ok(artifact) => {
+                shared.warns.extend(artifact.warns);
+                Some(artifact.object)
+            }
+            Err(artifact) => {
+                shared.warns.extend(artifact.warns);
+                shared.errors.extend(artifact.errors);
+                artifact.object
+            }
+        };
+        let Some(mod_ctx) = builder.pop_mod_ctx() else {
+            return;
+        };
+        let mut overlay = Context::new(
+            "<prelude>".into(),
+            cfg.copy(),
+            ContextKind::Module,
+            vec![],
+            None,
+            Some(shared.clone()),
+            Self::TOP_LEVEL,
+        );
+        let prelude_ctx = &mod_ctx.context;
+        for (name, vi) in prelude_ctx.locals.iter() {
+            if !vi.vis.is_public() {
+                continue;
+            }
+            // referred to by the bare name, like the builtins (and compiled to the name defined in the prelude)
+            let mut vi = vi.clone();
+            vi.vis = Visibility::BUILTIN_PRIVATE;
+            vi.py_name = vi.py_name.or_else(|| Some(name.inspect().clone()));
+            overlay.locals.insert(name.clone(), vi);
+            if let Some(obj) = prelude_ctx.consts.get(name) {
+                overlay.consts.insert(name.clone(), obj.clone());
+            }
+            if let Some(ty_ctx) = prelude_ctx.mono_types.get(name) {
+                overlay.mono_types.insert(name.clone(), ty_ctx.clone());
+            }
+            if let Some(ty_ctx) = prelude_ctx.poly_types.get(name) {
+                overlay.poly_types.insert(name.clone(), ty_ctx.clone());
+            }
+            if let Some(patch) = prelude_ctx.patches.get(name) {
+                overlay.patches.insert(name.clone(), patch.clone());
+            }
+        }
+        if let Some(root) = cfg.input.project_root() {
+            for banned in get_str_array_entry(&root, "banned_builtins") {
+                let name = VarName::from_str(banned);
+                if overlay.locals.contains_key(&name) {
+                    continue;
+                }
+                overlay.deleted_locals.insert(name, VarInfo::ILLEGAL);
+            }
+        }
+        shared
+            .mod_cache
+            .register(prelude.to_path_buf(), hir, mod_ctx);
+        shared
+            .mod_cache
+            .register(overlay_path, None, ModuleContext::new(overlay, dict! {}));
+    }
+
     fn similar_builtin_py_mod_name(&self, name: &Str) -> Option<Str> {
         get_similar_name(BUILTIN_PYTHON_MODS.into_iter(), name).map(Str::rc)
     }
@@ -2021,6 +2456,7 @@ impl Context {
             .to_str()
             .unwrap()
             .trim_end_matches(".d.er")
+            .trim_end_matches(".pyi")
             .to_string();
         for parent in path.components().rev().skip(1) {
             let parent = parent.as_os_str().to_str().unwrap();
@@ -2071,7 +2507,7 @@ impl Context {
     fn get_decl_path(&self, __name__: &Str, loc: &impl Locational) -> CompileResult<PathBuf> {
         match self.cfg.input.resolve_decl_path(Path::new(&__name__[..])) {
             Some(path) => {
-                if self.cfg.input.decl_file_is(&path) {
+                if self.cfg.input.decl_file_is(&path) || is_pyi(&path) {
                     return Ok(path);
                 }
                 for _ in 0..600 {
@@ -2185,6 +2621,7 @@ impl Context {
             .input
             .try_read()
             .map_err(|_| self.import_err(line!(), __name__, loc))?;
+        let src = self.decl_src(&path, src);
         let mut builder = HIRBuilder::new_with_cache(
             cfg,
             self.mod_name(&path),
@@ -2196,6 +2633,14 @@ impl Context {
                 py_mod_cache.register(path.clone(), Some(artifact.object), ctx);
                 Ok(path)
             }
+            // The declarations converted from a stub may be partially invalid in Erg,
+            // but they cannot be fixed by the user, so the valid part is used.
+            Err(artifact) if is_pyi(&path) => {
+                log!(err "{}", artifact.errors);
+                let ctx = builder.pop_mod_ctx().unwrap();
+                py_mod_cache.register(path.clone(), artifact.object, ctx);
+                Ok(path)
+            }
             Err(artifact) => {
                 if let Some(hir) = artifact.object {
                     py_mod_cache.register(path, Some(hir), builder.pop_mod_ctx().unwrap());
@@ -2205,6 +2650,15 @@ impl Context {
         }
     }
 
+    /// Python stubs (`.pyi`) are converted into Erg declarations.
+    fn decl_src(&self, path: &Path, src: String) -> String {
+        if is_pyi(path) {
+            PyiConverter::new(self.cfg.target_version.unwrap_or_default()).convert(&src)
+        } else {
+            src
+        }
+    }
+
     pub fn del(&mut self, ident: &hir::Identifier) -> CompileResult<()> {
         let is_const = self
             .rec_get_var_info(&ident.raw, crate::AccessKind::Name, &self.cfg.input, self)
@@ -2212,7 +2666,7 @@ impl Context {
         let is_builtin = self
             .get_builtins()
             .unwrap()
-            .get_var_kv(ident.inspect())
+            .get_var_info(ident.inspect())
             .is_some();
         if is_const || is_builtin {
             Err(TyCheckErrors::from(TyCheckError::del_error(
@@ -2333,7 +2787,7 @@ impl Context {
     }
 
     fn inc_ref_mono_typespec(&self, ident: &Identifier, namespace: &Context) -> bool {
-        if let Triple::Ok(vi) = self.rec_get_var_info(
+        if let LookupOutcome::Found(vi) = self.rec_get_var_info(
             ident,
             crate::compile::AccessKind::Name,
             &self.cfg.input,
@@ -2352,7 +2806,7 @@ impl Context {
     }
 
     fn inc_ref_local(&self, local: &ConstIdentifier, namespace: &Context) -> bool {
-        if let Triple::Ok(vi) = self.rec_get_var_info(
+        if let LookupOutcome::Found(vi) = self.rec_get_var_info(
             local,
             crate::compile::AccessKind::Name,
             &self.cfg.input,
@@ -0,0 +1,77 @@
+//! Defines the severities of the lenient checks.
+//!
+//! Some checks are permissive by default so that gradually typed (or Python-like) code can be compiled:
+//! implicit union types are only warned, unconstrained parameters fall back to `Obj`, arguments are converted through `Into` implicitly,
+//! and `Dynamic` values and untyped parameters (in `py_compat` mode) are trusted.
+//! `--strict` raises all of them to errors.
+use erg_common::config::ErgConfig;
+
+use crate::context::Context;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LenientCheck {
+    /// The return type of a function is inferred as a union type (`f x = if x, do: 1, do: "a"`)
+    ImplicitUnion,
+    /// A `Dynamic` value is passed to a typed parameter or assigned to a typed variable
+    DynamicCoercion,
+    /// An attribute of a `Dynamic` value or an untyped parameter (in `py_compat` mode) is accessed
+    UntypedAttr,
+    /// The type of an unannotated parameter is inferred as `Obj` because nothing constrains it (`g = x -> None`)
+    ObjFallback,
+    /// An argument is converted through an `Into` implementation (see `ASTLowerer::insert_into_conversions`)
+    IntoConversion,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Allow,
+    Warn,
+    Error,
+}
+
+impl Severity {
+    pub const fn is_error(self) -> bool {
+        matches!(self, Self::Error)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SeverityPolicy {
+    strict: bool,
+    runtime_checks: bool,
+}
+
+impl SeverityPolicy {
+    pub const fn new(cfg: &ErgConfig) -> Self {
+        Self {
+            strict: cfg.strict,
+            runtime_checks: cfg.runtime_checks,
+        }
+    }
+
+    pub const fn severity(&self, check: LenientCheck) -> Severity {
+        match check {
+            LenientCheck::ImplicitUnion if self.strict => Severity::Error,
+            LenientCheck::ImplicitUnion => Severity::Warn,
+            // the coercions are not silent if they are checked at runtime
+            LenientCheck::DynamicCoercion if self.strict && !self.runtime_checks => Severity::Error,
+            LenientCheck::UntypedAttr
+            | LenientCheck::ObjFallback
+            | LenientCheck::IntoConversion
+                if self.strict =>
+            {
+                Severity::Error
+            }
+            LenientCheck::DynamicCoercion
+            | LenientCheck::UntypedAttr
+            | LenientCheck::ObjFallback
+            | LenientCheck::IntoConversion => Severity::Allow,
+        }
+    }
+}
+
+impl Context {
+    pub(crate) const fn severity_of(&self, check: LenientCheck) -> Severity {
+        SeverityPolicy::new(&self.cfg).severity(check)
+    }
+}
@@ -0,0 +1,49 @@
+//! Defines `SuperTypeCache`, the memoization of the supertype chains of nominal types.
+//!
+//! `Context::get_nominal_super_type_ctxs` resolves the super classes and the super traits of a type on every attribute lookup.
+//! The resolved contexts are borrowed from the scopes that define them, so the resolved types are cached instead,
+//! in the context of the nominal type itself.
+//! The cache is cleared when a super class or a super trait is registered.
+use erg_common::shared::Shared;
+
+use crate::context::Context;
+use crate::ty::Type;
+
+#[derive(Debug, Default)]
+pub struct SuperTypeCache(Shared<Option<Vec<Type>>>);
+
+// The context of a type may be cloned and get different super types, so the cache is not shared
+impl Clone for SuperTypeCache {
+    fn clone(&self) -> Self {
+        Self(Shared::new(self.0.borrow().clone()))
+    }
+}
+
+impl SuperTypeCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self) -> Option<Vec<Type>> {
+        self.0.borrow().clone()
+    }
+
+    pub fn set(&self, sups: Vec<Type>) {
+        *self.0.borrow_mut() = Some(sups);
+    }
+
+    pub fn clear(&self) {
+        *self.0.borrow_mut() = None;
+    }
+
+    pub fn is_cached(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+}
+
+impl Context {
+    /// Must be called when `super_classes` or `super_traits` of `self` are modified.
+    pub(crate) fn invalidate_super_type_cache(&self) {
+        self.super_type_cache.clear();
+    }
+}
@@ -2,7 +2,9 @@
 use erg_common::traits::StructuralEq;
 use erg_common::Str;
 
-use crate::ty::constructors::{func1, mono, mono_q, poly, refinement, ty_tp};
+use crate::ty::constructors::{
+    and, free_var, func1, mono, mono_q, poly, ref_, refinement, ty_tp, unknown_len_array_t,
+};
 use crate::ty::free::Constraint;
 use crate::ty::typaram::TyParam;
 use crate::ty::{Predicate, Type};
@@ -96,4 +98,63 @@ impl Context {
         assert!(self.subtype_of(&(Int | Str), &(Int | Str | Code | NoneType)));
         Ok(())
     }
+
+    /// The traversals of deeply nested types must not overflow the stack
+    pub fn test_deep_types(&self) -> Result<(), ()> {
+        const DEPTH: usize = 5000;
+        let mut refs = Never;
+        let mut ors = Never;
+        for _ in 0..DEPTH {
+            refs = ref_(refs);
+            ors = Or(Box::new(ors), Box::new(Never));
+        }
+        assert!(self.is_class(&refs));
+        assert!(self.is_class(&ors));
+        assert!(self.get_nominal_type_ctx(&refs).is_none());
+        Ok(())
+    }
+
+    pub fn test_infinite_type(&self) -> Result<(), ()> {
+        let t = free_var(self.level, Constraint::new_type_of(Type));
+        let arr = unknown_len_array_t(t.clone());
+        assert!(self.link_checked(&t, &arr, &()).is_err());
+        t.link(&arr);
+        assert!(t.is_unbound_var());
+        let u = free_var(self.level, Constraint::new_subtype_of(Obj));
+        let add_u = poly("Add", vec![ty_tp(u.clone())]);
+        self.update_super_checked(&u, |_| add_u, &())
+            .map_err(|_| ())?;
+        let show = mono("Show");
+        assert!(self
+            .update_super_checked(&u, |sup| and(and(sup, u.clone()), show), &())
+            .is_err());
+        self.link_checked(&t, &Int, &()).map_err(|_| ())?;
+        assert_eq!(t, Int);
+        Ok(())
+    }
+
+    /// `D <: C` (tests/super_types.er)
+    pub fn test_super_type_cache(&mut self) -> Result<(), ()> {
+        let d = mono("<module>::D");
+        let sup_names = |ctx: &Self| {
+            ctx.get_nominal_super_type_ctxs(&d)
+                .unwrap()
+                .into_iter()
+                .map(|ctx| ctx.name.clone())
+                .collect::<Vec<_>>()
+        };
+        let before = sup_names(self);
+        assert!(before.iter().any(|name| &name[..] == "<module>::C"), "{before:?}");
+        let (_, d_ctx) = self.get_nominal_type_ctx(&d).ok_or(())?;
+        assert!(d_ctx.super_type_cache.is_cached());
+        assert_eq!(sup_names(self), before);
+        let show = mono("Show");
+        let show_ctx = self.get_nominal_type_ctx(&show).ok_or(())?.1.clone();
+        let (_, d_ctx) = self.get_mut_nominal_type_ctx(&d).ok_or(())?;
+        d_ctx.register_supertrait(show, &show_ctx);
+        assert!(!d_ctx.super_type_cache.is_cached());
+        let after = sup_names(self);
+        assert!(after.iter().any(|name| &name[..] == "Show"), "{after:?}");
+        Ok(())
+    }
 }
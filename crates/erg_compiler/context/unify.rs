@@ -9,7 +9,7 @@ use erg_common::Str;
 use erg_common::{fmt_vec, fn_name, log};
 
 use crate::ty::constructors::*;
-use crate::ty::free::{Constraint, FreeKind, HasLevel, GENERIC_LEVEL};
+use crate::ty::free::{Constraint, FreeKind, FreeTyVar, HasLevel, GENERIC_LEVEL};
 use crate::ty::typaram::{OpKind, TyParam};
 use crate::ty::value::ValueObj;
 use crate::ty::{Predicate, SubrType, Type};
@@ -249,6 +249,65 @@ impl Context {
         }
     }
 
+    /// Links `maybe_fv` to `to`.
+    /// If `to` contains `maybe_fv` (e.g. `?T == Array(?T)`), the result would be an infinite type,
+    /// so an error is returned instead (`Type::link` silently refuses it).
+    pub(crate) fn link_checked(
+        &self,
+        maybe_fv: &Type,
+        to: &Type,
+        loc: &impl Locational,
+    ) -> TyCheckResult<()> {
+        if let Ok(fv) = <&FreeTyVar>::try_from(maybe_fv) {
+            if !maybe_fv.addr_eq(to) && to.occurs(fv) {
+                return Err(TyCheckErrors::from(TyCheckError::infinite_type_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    loc.loc(),
+                    self.caused_by(),
+                    maybe_fv,
+                    to,
+                )));
+            }
+        }
+        maybe_fv.link(to);
+        Ok(())
+    }
+
+    /// Updates the super type of `maybe_sub` (an unbound type variable) with `f`.
+    /// A super type referring to the variable itself (e.g. `?T(<: ?T and Show)`) is an error.
+    /// Recursive constraints such as `?T(<: Add(?T))` are allowed.
+    pub(crate) fn update_super_checked(
+        &self,
+        maybe_sub: &Type,
+        f: impl FnOnce(Type) -> Type,
+        loc: &impl Locational,
+    ) -> TyCheckResult<()> {
+        let Ok(sub_fv) = <&FreeTyVar>::try_from(maybe_sub) else {
+            return Ok(());
+        };
+        let Some(sup) = sub_fv.get_super() else {
+            return Ok(());
+        };
+        let new_sup = f(sup);
+        if new_sup
+            .intersection_types()
+            .iter()
+            .any(|t| t.addr_eq(maybe_sub))
+        {
+            return Err(TyCheckErrors::from(TyCheckError::infinite_type_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                loc.loc(),
+                self.caused_by(),
+                maybe_sub,
+                &new_sup,
+            )));
+        }
+        sub_fv.update_super(|_| new_sup);
+        Ok(())
+    }
+
     /// allow_divergence = trueにすると、Num型変数と±Infの単一化を許す
     pub(crate) fn sub_unify_tp(
         &self,
@@ -265,6 +324,12 @@ impl Context {
             return Ok(());
         }
         match (maybe_sub, maybe_sup) {
+            // e.g. Lens(Sub, T) <: Lens(?R, T) ==> ?R <: Sub
+            (TyParam::Type(sub), TyParam::Type(sup))
+                if _variance == Some(Variance::Contravariant) =>
+            {
+                self.sub_unify(sup, sub, loc, None)
+            }
             (TyParam::Type(sub), TyParam::Type(sup)) => self.sub_unify(sub, sup, loc, None),
             (TyParam::Value(ValueObj::Type(sub)), TyParam::Type(sup)) => {
                 self.sub_unify(sub.typ(), sup, loc, None)
@@ -297,7 +362,8 @@ impl Context {
                 } // &fv is dropped
                 let fv_t = sub_fv.constraint().unwrap().get_type().unwrap().clone(); // lfvを参照しないよいにcloneする(あとでborrow_mutするため)
                 let tp_t = self.get_tp_t(sup_tp)?;
-                if self.supertype_of(&fv_t, &tp_t) {
+                // ?N :> ?N + 1 is an infinite type
+                if self.supertype_of(&fv_t, &tp_t) && !sup_tp.occurs_tp(maybe_sub) {
                     // 外部未連携型変数の場合、linkしないで制約を弱めるだけにする(see compiler/inference.md)
                     if sub_fv.level() < Some(self.level) {
                         let new_constraint = Constraint::new_subtype_of(tp_t);
@@ -335,7 +401,8 @@ impl Context {
                 } // &fv is dropped
                 let fv_t = sup_fv.constraint().unwrap().get_type().unwrap().clone(); // fvを参照しないよいにcloneする(あとでborrow_mutするため)
                 let tp_t = self.get_tp_t(sub_tp)?;
-                if self.supertype_of(&fv_t, &tp_t) {
+                // ?N <: ?N + 1 is an infinite type
+                if self.supertype_of(&fv_t, &tp_t) && !sub_tp.occurs_tp(maybe_sup) {
                     // 外部未連携型変数の場合、linkしないで制約を弱めるだけにする(see compiler/inference.md)
                     if sup_fv.level() < Some(self.level) {
                         let new_constraint = Constraint::new_subtype_of(tp_t);
@@ -379,6 +446,23 @@ impl Context {
                 self.sub_unify_tp(lhs, lhs2, _variance, loc, allow_divergence)?;
                 self.sub_unify_tp(rhs, rhs2, _variance, loc, allow_divergence)
             }
+            // pred(N) == N - 1
+            (sub @ TyParam::App { .. }, sup) if sub.unfold_succ_pred().is_some() => self
+                .sub_unify_tp(
+                    &sub.unfold_succ_pred().unwrap(),
+                    sup,
+                    _variance,
+                    loc,
+                    allow_divergence,
+                ),
+            (sub, sup @ TyParam::App { .. }) if sup.unfold_succ_pred().is_some() => self
+                .sub_unify_tp(
+                    sub,
+                    &sup.unfold_succ_pred().unwrap(),
+                    _variance,
+                    loc,
+                    allow_divergence,
+                ),
             (TyParam::Lambda(_l), TyParam::Lambda(_r)) => {
                 todo!("{_l}/{_r}")
             }
@@ -463,6 +547,21 @@ impl Context {
                 }
                 Ok(())
             }
+            (
+                TyParam::App {
+                    name: sub_name,
+                    args: sub_args,
+                },
+                TyParam::App {
+                    name: sup_name,
+                    args: sup_args,
+                },
+            ) if sub_name == sup_name && sub_args.len() == sup_args.len() => {
+                for (l, r) in sub_args.iter().zip(sup_args.iter()) {
+                    self.sub_unify_tp(l, r, _variance, loc, allow_divergence)?;
+                }
+                Ok(())
+            }
             (l, r) => {
                 log!(err "{l} / {r}");
                 type_feature_error!(self, loc.loc(), &format!("unifying {l} and {r}"))
@@ -470,6 +569,17 @@ impl Context {
         }
     }
 
+    /// The rhs of the `>=` (`lower`) or `<=` conjunct of `l and r`
+    fn bound_of<'p>(l: &'p Predicate, r: &'p Predicate, lower: bool) -> Option<&'p TyParam> {
+        match (l, r, lower) {
+            (Pred::GreaterEqual { rhs, .. }, Pred::LessEqual { .. }, true)
+            | (Pred::LessEqual { .. }, Pred::GreaterEqual { rhs, .. }, true)
+            | (Pred::LessEqual { rhs, .. }, Pred::GreaterEqual { .. }, false)
+            | (Pred::GreaterEqual { .. }, Pred::LessEqual { rhs, .. }, false) => Some(rhs),
+            _ => None,
+        }
+    }
+
     /// predは正規化されているとする
     fn sub_unify_pred(
         &self,
@@ -499,6 +609,48 @@ impl Context {
             (Pred::Equal { rhs, .. }, Pred::LessEqual { rhs: rhs2, .. }) => {
                 self.coerce_greater_than(rhs2, rhs, loc)
             }
+            // sub_unify_pred(I >= ?M and I <= ?N, I >= 0) ==> ?M => 0
+            (Predicate::And(l, r), Pred::GreaterEqual { rhs, .. })
+                if Self::bound_of(l, r, true).is_some() =>
+            {
+                let ge_rhs = Self::bound_of(l, r, true).unwrap();
+                // already checked by `subtype_of`
+                if ge_rhs.has_no_unbound_var() && rhs.has_no_unbound_var() {
+                    return Ok(());
+                }
+                self.sub_unify_tp(ge_rhs, rhs, None, loc, false)
+                    .map_err(|_| {
+                        TyCheckErrors::from(TyCheckError::pred_unification_error(
+                            self.cfg.input.clone(),
+                            line!() as usize,
+                            sub_pred,
+                            sup_pred,
+                            loc.loc(),
+                            self.caused_by(),
+                        ))
+                    })
+            }
+            // sub_unify_pred(I >= ?M and I <= ?N, I <= N - 1) ==> ?N => N - 1
+            (Predicate::And(l, r), Pred::LessEqual { rhs, .. })
+                if Self::bound_of(l, r, false).is_some() =>
+            {
+                let le_rhs = Self::bound_of(l, r, false).unwrap();
+                // already checked by `subtype_of`
+                if le_rhs.has_no_unbound_var() && rhs.has_no_unbound_var() {
+                    return Ok(());
+                }
+                self.sub_unify_tp(le_rhs, rhs, None, loc, false)
+                    .map_err(|_| {
+                        TyCheckErrors::from(TyCheckError::pred_unification_error(
+                            self.cfg.input.clone(),
+                            line!() as usize,
+                            sub_pred,
+                            sup_pred,
+                            loc.loc(),
+                            self.caused_by(),
+                        ))
+                    })
+            }
             // sub_unify_pred(I >= 0, I >= ?M and I <= ?N) ==> ?M => 0, ?N => Inf
             (Pred::GreaterEqual { rhs, .. }, Pred::And(l, r))
             | (Predicate::And(l, r), Pred::GreaterEqual { rhs, .. }) => {
@@ -631,6 +783,7 @@ impl Context {
         if maybe_sub == &Type::Failure || maybe_sup == &Type::Failure {
             return Ok(());
         }
+        let _guard = self.enter_type_check(loc)?;
         self.occur(maybe_sub, maybe_sup, loc).map_err(|err| {
             log!(err "occur error: {maybe_sub} / {maybe_sup}");
             err
@@ -866,7 +1019,8 @@ impl Context {
                         }
                     }
                     if sup.contains_union(&new_sub) {
-                        maybe_sup.link(&new_sub); // Bool <: ?T <: Bool or Y ==> ?T == Bool
+                        // Bool <: ?T <: Bool or Y ==> ?T == Bool
+                        self.link_checked(maybe_sup, &new_sub, loc)?;
                     } else {
                         let constr = Constraint::new_sandwiched(new_sub, mem::take(&mut sup));
                         sup_fv.update_constraint(constr, true);
@@ -892,7 +1046,11 @@ impl Context {
                         return self.sub_unify(maybe_sub, maybe_sup, loc, param_name);
                     } else {
                         // e.g. ?T / Structural({ .method = (self: ?T) -> Int })
-                        sub_fv.update_super(|sup| self.intersection(&sup, maybe_sup));
+                        self.update_super_checked(
+                            maybe_sub,
+                            |sup| self.intersection(&sup, maybe_sup),
+                            loc,
+                        )?;
                     }
                 }
             }
@@ -933,7 +1091,7 @@ impl Context {
                         && !new_sup.is_unbound_var()
                         && !sub.is_unbound_var()
                     {
-                        maybe_sub.link(&sub);
+                        self.link_checked(maybe_sub, &sub, loc)?;
                     } else {
                         let constr = Constraint::new_sandwiched(sub, new_sup);
                         sub_fv.update_constraint(constr, true);
@@ -1099,8 +1257,14 @@ impl Context {
                 if ln != rn {
                     self.nominal_sub_unify(maybe_sub, maybe_sup, rps, loc)?;
                 } else {
-                    for (l_maybe_sub, r_maybe_sup) in lps.iter().zip(rps.iter()) {
-                        self.sub_unify_tp(l_maybe_sub, r_maybe_sup, None, loc, false)?;
+                    let variances = self
+                        .get_nominal_type_ctx(maybe_sub)
+                        .map(|(_, ctx)| ctx.type_params_variance().into_iter().map(Some).collect())
+                        .unwrap_or(vec![None; lps.len()]);
+                    for ((l_maybe_sub, r_maybe_sup), variance) in
+                        lps.iter().zip(rps.iter()).zip(variances)
+                    {
+                        self.sub_unify_tp(l_maybe_sub, r_maybe_sup, variance, loc, false)?;
                     }
                 }
             }
@@ -1151,6 +1315,20 @@ impl Context {
                     self.sub_unify(maybe_sub, r, loc, param_name)?;
                 }
             }
+            // e.g. Array(?T, _) <: Json ==> Array(?T, _) <: Int or Str or Array(Json, _)
+            (Mono(_), _) | (_, Mono(_))
+                if self.unfold_rec_alias(maybe_sub).is_some()
+                    || self.unfold_rec_alias(maybe_sup).is_some() =>
+            {
+                let sub = self.unfold_rec_alias(maybe_sub);
+                let sup = self.unfold_rec_alias(maybe_sup);
+                self.sub_unify(
+                    sub.as_ref().unwrap_or(maybe_sub),
+                    sup.as_ref().unwrap_or(maybe_sup),
+                    loc,
+                    param_name,
+                )?;
+            }
             (Ref(sub), Ref(sup)) => {
                 self.sub_unify(sub, sup, loc, param_name)?;
             }
@@ -1212,7 +1390,9 @@ impl Context {
             ) => {
                 self.nominal_sub_unify(maybe_sub, maybe_sup, sup_params, loc)?;
             }
-            (Subr(_), Mono(name)) if &name[..] == "GenericCallable" => {}
+            (Subr(_) | Quantified(_), Mono(name)) if &name[..] == "GenericCallable" => {}
+            // e.g. `i < len(xs)`: (i: {I: Nat | I <= pred(?N)}) <: Bool
+            (Guard(_), Bool) => {}
             _ => {
                 return type_feature_error!(
                     self,
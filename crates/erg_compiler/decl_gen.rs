@@ -0,0 +1,257 @@
+//! Generates a declaration file (`.d.er`) from an Erg module (`erg declare <file>.er`).
+//!
+//! The public interface of the module (variables, subroutines, and classes with their attributes and methods)
+//! is read from the type-checked `Context` and written as declarations.
+//! Downstream builds can type-check against the declaration file without parsing (or even having) the implementation,
+//! e.g. by distributing the compiled `.pyc` with the `.d.er` file.
+//!
+//! ```erg
+//! # foo.er
+//! .C = Class { .x = Int }
+//! .C.
+//!     .get self = self.x
+//! .f x: Int = x + 1
+//! ```
+//! ↓
+//! ```erg
+//! # foo.d.er
+//! .C: ClassType
+//! .C.
+//!     x: Int
+//!     __call__: (base: {.x = Int}) -> .C
+//!     get: (self: .C) -> Int
+//! .f: (x: Int) -> Int
+//! ```
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write;
+
+use erg_common::config::ErgConfig;
+use erg_common::error::{Location, MultiErrorDisplay};
+use erg_common::traits::{ExitStatus, Runnable, Stream};
+use erg_common::{log, Str};
+
+use crate::build_hir::HIRBuilder;
+use crate::context::{Context, ContextKind};
+use crate::error::{CompileError, CompileErrors};
+use crate::ty::Type;
+use crate::varinfo::{VarInfo, VarKind};
+
+/// Generates `.d.er` files from Erg scripts.
+#[derive(Debug)]
+pub struct DeclGenerator {
+    pub cfg: ErgConfig,
+    builder: HIRBuilder,
+}
+
+impl Default for DeclGenerator {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for DeclGenerator {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg declaration generator";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self {
+            builder: HIRBuilder::new(cfg.copy()),
+            cfg,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.builder.initialize();
+    }
+
+    fn clear(&mut self) {
+        self.builder.clear();
+    }
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let path = self.cfg.dump_decl_path();
+        let src = self.cfg.input.read();
+        let code = self.generate(src)?;
+        File::create(&path)
+            .and_then(|mut f| f.write_all(code.as_bytes()))
+            .map_err(|err| CompileErrors::from(self.file_error(err.to_string())))?;
+        // check the result
+        let cfg = self.cfg.inherit(path);
+        let mut checker = HIRBuilder::new(cfg);
+        match checker.build(code, "declare") {
+            Ok(artifact) => {
+                artifact.warns.write_all_stderr();
+                Ok(ExitStatus::compile_passed(artifact.warns.len()))
+            }
+            Err(artifact) => {
+                artifact.warns.write_all_stderr();
+                artifact.errors.write_all_stderr();
+                Ok(ExitStatus::new(
+                    1,
+                    artifact.warns.len(),
+                    artifact.errors.len(),
+                ))
+            }
+        }
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        self.generate(src)
+    }
+}
+
+impl DeclGenerator {
+    /// Type-checks `src` and returns the declarations of its public interface.
+    pub fn generate(&mut self, src: String) -> Result<String, CompileErrors> {
+        log!(info "the declaration generating process has started.");
+        let artifact = self.builder.build(src, "exec").map_err(|art| art.errors)?;
+        artifact.warns.write_all_stderr();
+        let Some(mod_ctx) = self.builder.pop_mod_ctx() else {
+            return Ok(String::new());
+        };
+        let code = DeclWriter::new(&mod_ctx.context).write_module();
+        log!(info "the declaration generating process has completed:\n{code}");
+        Ok(code)
+    }
+
+    fn file_error(&self, desc: String) -> CompileError {
+        CompileError::file_error(
+            self.cfg.input.clone(),
+            line!() as usize,
+            desc,
+            Location::Unknown,
+            "<declare>".into(),
+            None,
+        )
+    }
+}
+
+struct DeclWriter<'c> {
+    ctx: &'c Context,
+    code: String,
+}
+
+impl<'c> DeclWriter<'c> {
+    fn new(ctx: &'c Context) -> Self {
+        Self {
+            ctx,
+            code: String::new(),
+        }
+    }
+
+    fn write_module(mut self) -> String {
+        let mut vars = self
+            .ctx
+            .locals
+            .iter()
+            .chain(self.ctx.decls.iter())
+            .filter(|(_, vi)| vi.vis.is_public())
+            .collect::<Vec<_>>();
+        // in the order of definition
+        vars.sort_by_key(|(_, vi)| vi.def_loc.loc.ln_begin().unwrap_or(0));
+        for (name, vi) in vars {
+            match self.ctx.mono_types.get(name.inspect()) {
+                Some((_, class_ctx))
+                    if vi.t.is_class_type() && class_ctx.kind == ContextKind::Class =>
+                {
+                    self.write_class(name.inspect(), class_ctx);
+                }
+                _ => {
+                    let t = self.fmt_type(&vi.t);
+                    let _ = writeln!(self.code, ".{name}: {t}");
+                }
+            }
+        }
+        self.code
+    }
+
+    fn write_class(&mut self, name: &Str, class_ctx: &Context) {
+        let _ = writeln!(self.code, ".{name}: ClassType");
+        for sup in class_ctx.super_classes.iter() {
+            let sup = self.fmt_type(sup);
+            let _ = writeln!(self.code, ".{name} <: {sup}");
+        }
+        let mut attrs = class_ctx
+            .locals
+            .iter()
+            .chain(class_ctx.decls.iter())
+            .filter(|(_, vi)| vi.vis.is_public() && vi.kind.is_instance_attr())
+            .map(|(name, vi)| (name.inspect().clone(), vi))
+            .collect::<Vec<_>>();
+        for (_, methods) in class_ctx.methods_list.iter() {
+            for (name, vi) in methods.locals.iter().chain(methods.decls.iter()) {
+                if let Some(name) = Self::method_name(name.inspect(), vi) {
+                    attrs.push((name, vi));
+                }
+            }
+        }
+        if attrs.is_empty() {
+            return;
+        }
+        attrs.sort_by_key(|(_, vi)| vi.def_loc.loc.ln_begin().unwrap_or(0));
+        let _ = writeln!(self.code, ".{name}.");
+        for (attr, vi) in attrs {
+            let t = if &attr[..] == "__call__" {
+                self.fmt_constructor(&vi.t)
+            } else {
+                self.fmt_type(&vi.t)
+            };
+            let _ = writeln!(self.code, "    {attr}: {t}");
+        }
+    }
+
+    /// The generated constructor (`new`) is declared as `__call__`, other generated methods are omitted.
+    fn method_name(name: &Str, vi: &VarInfo) -> Option<Str> {
+        if !vi.vis.is_public() {
+            return None;
+        }
+        if matches!(vi.kind, VarKind::Auto | VarKind::FixedAuto) {
+            return (&name[..] == "new").then(|| Str::ever("__call__"));
+        }
+        Some(name.clone())
+    }
+
+    /// The record parameter of a constructor is passed as is (`C.new {.x = 1}` is compiled to `C({.x = 1})`).
+    fn fmt_constructor(&self, t: &Type) -> String {
+        let (Some(params), Some(ret)) = (t.non_default_params(), t.return_t()) else {
+            return self.fmt_type(t);
+        };
+        let [param] = &params[..] else {
+            return self.fmt_type(t);
+        };
+        let Type::Record(attrs) = param.typ() else {
+            return self.fmt_type(t);
+        };
+        let fields = attrs
+            .iter()
+            .map(|(field, t)| format!("{field} = {}", self.fmt_type(t)))
+            .collect::<Vec<_>>()
+            .join("; ");
+        format!("(base: {{{fields}}}) -> {}", self.fmt_type(ret))
+    }
+
+    /// Types defined in the module are referred as `.Name` (e.g. `<module>.C` -> `.C`).
+    fn fmt_type(&self, t: &Type) -> String {
+        let prefix = format!("{}.", self.ctx.name);
+        let t = if t.is_class_type() {
+            "ClassType".to_string()
+        } else {
+            t.to_string()
+        };
+        t.replace(&prefix, ".")
+    }
+}
@@ -0,0 +1,273 @@
+//! Checks the examples embedded in declaration files (`erg test --decls <file.d.er | dir>`).
+//!
+//! An example is a doc comment tagged with `erg`:
+//!
+//! ```erg
+//! '''erg
+//! assert [1, 2].concat([3, 4]) == [1, 2, 3, 4]
+//! '''
+//! concat: |T: Type, M: Nat, N: Nat|(self: Array(T, M), other: Array(T, N)) -> Array(T, M + N)
+//! ```
+//!
+//! Each example is type-checked (not executed) as a module located at the declaration file,
+//! so an example in `foo.d.er` can import the declarations with `pyimport "foo"`.
+//! The lines of an example keep their line numbers in the declaration file.
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use erg_common::config::ErgConfig;
+use erg_common::error::{Location, MultiErrorDisplay};
+use erg_common::io::Input;
+use erg_common::lang::LanguageCode;
+use erg_common::switch_lang;
+use erg_common::traits::{DequeStream, ExitStatus, Runnable, Stream};
+
+use erg_parser::lex::Lexer;
+use erg_parser::token::TokenKind;
+
+use crate::build_hir::HIRBuilder;
+use crate::error::{CompileError, CompileErrors};
+
+/// An `erg` code block in a doc comment of a declaration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclExample {
+    /// the line of the first line of the code (1-origin)
+    pub ln_begin: u32,
+    /// the indentation removed from each line
+    pub indent: u32,
+    pub code: String,
+}
+
+impl DeclExample {
+    /// The code padded with empty lines so that the line numbers match the declaration file.
+    pub fn padded_code(&self) -> String {
+        "\n".repeat(self.ln_begin.saturating_sub(1) as usize) + &self.code
+    }
+}
+
+/// Extracts the `erg` (or `erg,python`) code blocks in the doc comments of `src`.
+pub fn collect_examples(src: String) -> Vec<DeclExample> {
+    let Ok(tokens) = Lexer::from_str(src).lex() else {
+        return vec![];
+    };
+    let mut examples = vec![];
+    for token in tokens.iter().filter(|t| t.is(TokenKind::DocComment)) {
+        let content = token
+            .content
+            .trim_start_matches("'''")
+            .trim_end_matches("'''");
+        let mut lines = content.lines();
+        let lang = lines
+            .next()
+            .unwrap_or("")
+            .trim()
+            .parse()
+            .unwrap_or(LanguageCode::English);
+        if !matches!(lang, LanguageCode::Erg | LanguageCode::ErgOrPython) {
+            continue;
+        }
+        let lines = lines.collect::<Vec<_>>();
+        let indent = lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let code = lines
+            .iter()
+            .map(|line| line.get(indent..).unwrap_or("").trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if code.trim().is_empty() {
+            continue;
+        }
+        examples.push(DeclExample {
+            ln_begin: token.lineno + 1,
+            indent: indent as u32,
+            code,
+        });
+    }
+    examples
+}
+
+/// Collects `.d.er` files under `dir` (generated declarations in `__pycache__` are skipped).
+fn collect_decl_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "__pycache__") {
+                collect_decl_files(&path, files);
+            }
+        } else if path.to_string_lossy().ends_with(".d.er") {
+            files.push(path);
+        }
+    }
+}
+
+/// Moves the locations of an example back to the (indented) declaration file.
+fn shift_loc(loc: &mut Location, indent: u32) {
+    if let Location::Range {
+        col_begin, col_end, ..
+    } = loc
+    {
+        *col_begin += indent;
+        *col_end += indent;
+    }
+}
+
+/// Type-checks the examples in declaration files.
+#[derive(Debug)]
+pub struct DeclTester {
+    pub cfg: ErgConfig,
+    checked: usize,
+    failed: usize,
+}
+
+impl Default for DeclTester {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for DeclTester {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg declaration tester";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self {
+            cfg,
+            checked: 0,
+            failed: 0,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.checked = 0;
+        self.failed = 0;
+    }
+
+    fn clear(&mut self) {}
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let Some(root) = self.cfg.input.path().map(Path::to_path_buf) else {
+            let desc = switch_lang!(
+                "japanese" => "宣言ファイルまたはディレクトリを指定してください",
+                "simplified_chinese" => "请指定声明文件或目录",
+                "traditional_chinese" => "請指定宣告檔案或目錄",
+                "english" => "a declaration file or a directory is required",
+            );
+            return Err(CompileErrors::from(self.file_error(desc.into())));
+        };
+        let mut files = vec![];
+        if root.is_dir() {
+            collect_decl_files(&root, &mut files);
+        } else {
+            files.push(root);
+        }
+        let (mut num_warns, mut num_errors) = (0, 0);
+        for file in files {
+            let src = fs::read_to_string(&file)
+                .map_err(|err| CompileErrors::from(self.file_error(err.to_string())))?;
+            let (warns, errors) = self.check_examples(&file, src);
+            warns.write_all_stderr();
+            errors.write_all_stderr();
+            num_warns += warns.len();
+            num_errors += errors.len();
+        }
+        println!(
+            "{}",
+            switch_lang!(
+                "japanese" => format!("{}個の例を検査しました: {}個が失敗", self.checked, self.failed),
+                "simplified_chinese" => format!("检查了{}个示例: {}个失败", self.checked, self.failed),
+                "traditional_chinese" => format!("檢查了{}個示例: {}個失敗", self.checked, self.failed),
+                "english" => format!("{} example(s) checked: {} failed", self.checked, self.failed),
+            )
+        );
+        Ok(ExitStatus::new(
+            i32::from(self.failed > 0),
+            num_warns,
+            num_errors,
+        ))
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        let path = self.cfg.input.full_path();
+        let (_, errors) = self.check_examples(&path, src);
+        if errors.is_empty() {
+            Ok(format!("{} example(s) checked", self.checked))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl DeclTester {
+    /// The number of the examples failed to type-check in the last run
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// Returns the warnings and errors of the examples in the declaration file `path`.
+    pub fn check_examples(&mut self, path: &Path, src: String) -> (CompileErrors, CompileErrors) {
+        let (mut warns, mut errors) = (CompileErrors::empty(), CompileErrors::empty());
+        for example in collect_examples(src) {
+            self.checked += 1;
+            let input = Input::file(path.to_path_buf());
+            let cfg = ErgConfig {
+                input: input.clone(),
+                ..self.cfg.copy()
+            };
+            let mut builder = HIRBuilder::new(cfg);
+            let (mut ws, mut es) = match builder.build(example.padded_code(), "exec") {
+                Ok(artifact) => (artifact.warns, CompileErrors::empty()),
+                Err(artifact) => (artifact.warns, artifact.errors),
+            };
+            if !es.is_empty() {
+                self.failed += 1;
+            }
+            // the declaration file itself may be imported by the example
+            for err in ws.iter_mut().chain(es.iter_mut()) {
+                if err.input == input {
+                    shift_loc(&mut err.core.loc, example.indent);
+                    for sub in err.core.sub_messages.iter_mut() {
+                        shift_loc(&mut sub.loc, example.indent);
+                    }
+                }
+            }
+            warns.extend(ws);
+            errors.extend(es);
+        }
+        (warns, errors)
+    }
+
+    fn file_error(&self, desc: String) -> CompileError {
+        CompileError::file_error(
+            self.cfg.input.clone(),
+            line!() as usize,
+            desc,
+            Location::Unknown,
+            "<test>".into(),
+            None,
+        )
+    }
+}
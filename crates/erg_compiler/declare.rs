@@ -1,12 +1,12 @@
 use std::mem;
 
-use erg_common::consts::PYTHON_MODE;
 use erg_common::traits::{Locational, Runnable, Stream};
-use erg_common::{enum_unwrap, fn_name, log, set, Str, Triple};
+use erg_common::{enum_unwrap, fn_name, log, set, Str};
 
 use erg_parser::ast::{self, AscriptionKind, Identifier, VarName, AST};
 
 use crate::context::instantiate::TyVarCache;
+use crate::context::lookup::LookupOutcome;
 use crate::lower::ASTLowerer;
 use crate::ty::constructors::{mono, poly, ty_tp, type_q, v_enum};
 use crate::ty::free::HasLevel;
@@ -175,7 +175,7 @@ impl ASTLowerer {
                     .get_singular_ctxs_by_hir_expr(&obj, &self.module.context)
                 {
                     for ctx in ctxs {
-                        if let Triple::Ok(vi) = ctx.rec_get_var_info(
+                        if let LookupOutcome::Found(vi) = ctx.rec_get_var_info(
                             &ident.raw,
                             AccessKind::UnboundAttr,
                             self.input(),
@@ -457,7 +457,7 @@ impl ASTLowerer {
         Ok(hir::Params::new(non_defaults, var_params, defaults, parens))
     }
 
-    fn fake_lower_block(&self, block: ast::Block) -> LowerResult<hir::Block> {
+    pub(crate) fn fake_lower_block(&self, block: ast::Block) -> LowerResult<hir::Block> {
         let mut chunks = vec![];
         for chunk in block.into_iter() {
             let chunk = self.fake_lower_expr(chunk)?;
@@ -677,7 +677,7 @@ impl ASTLowerer {
                 ident.inspect(),
             )));
         }
-        let new_ident = if PYTHON_MODE {
+        let new_ident = if self.cfg().py_compat {
             let mut symbol = ident.name.clone().into_token();
             symbol.content = py_name.clone();
             Identifier::new(ident.vis.clone(), VarName::new(symbol))
@@ -727,11 +727,11 @@ impl ASTLowerer {
         if ident.is_raw() {
             return Ok(());
         }
-        let name = if PYTHON_MODE {
+        let name = if self.cfg().py_compat {
             self.module
                 .context
-                .erg_to_py_names
-                .get(ident.inspect())
+                .name_resolver
+                .to_py_name(ident.inspect())
                 .map_or(Str::ever("?"), |s| s.clone())
         } else {
             ident.inspect().clone()
@@ -788,12 +788,15 @@ impl ASTLowerer {
     pub(crate) fn declare_module(&mut self, ast: AST) -> HIR {
         let mut module = hir::Module::with_capacity(ast.module.len());
         let _ = self.module.context.preregister(ast.module.block());
+        let mut docs = vec![];
         for chunk in ast.module.into_iter() {
             match self.declare_chunk(chunk, false) {
-                Ok(chunk) => {
+                Ok(mut chunk) => {
+                    self.store_docs(&mut docs, &mut chunk);
                     module.push(chunk);
                 }
                 Err(errs) => {
+                    docs.clear();
                     self.errs.extend(errs);
                 }
             }
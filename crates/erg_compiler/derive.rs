@@ -0,0 +1,121 @@
+//! defines the `Derive` decorator framework.
+//!
+//! ```erg
+//! @Derive [Eq, Ord, Show, Hash]
+//! Point = Class { .x = Int; .y = Int }
+//! ```
+//!
+//! A trait is derivable if it has the `__derive__` attribute,
+//! the name of the module that provides the runtime implementations of its methods.
+//! The builtin derivable traits are `Eq`, `Ord`, `Show` and `Hash` (implemented in `_erg_derive`).
+//! Libraries can make their own traits derivable with the `Derivable` decorator:
+//!
+//! ```erg
+//! @Derivable "greet_impl"
+//! .Greet = Trait { .greet = (self: Self) -> Str }
+//! ```
+//!
+//! When `Derive` is evaluated, the methods declared in each trait are synthesized for the class
+//! (`Self` is replaced with the class), and the class registers them as a trait implementation.
+//! At runtime, the methods are imported from the module into the class namespace.
+use erg_common::Str;
+
+use crate::context::Context;
+use crate::ty::value::ValueObj;
+use crate::ty::Type;
+
+pub const DERIVE_ATTR: &str = "__derive__";
+pub const BUILTIN_DERIVE_MODULE: &str = "_erg_derive";
+
+/// A method synthesized by a derive
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DerivedMethod {
+    pub name: Str,
+    pub t: Type,
+    /// name of the runtime implementation, if it differs from `name`
+    pub py_name: Option<Str>,
+}
+
+impl DerivedMethod {
+    pub const fn new(name: Str, t: Type, py_name: Option<Str>) -> Self {
+        Self { name, t, py_name }
+    }
+
+    pub fn py_name(&self) -> &Str {
+        self.py_name.as_ref().unwrap_or(&self.name)
+    }
+}
+
+/// A trait implementation synthesized by `Derive`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DerivedTrait {
+    pub trait_: Type,
+    /// module that provides the runtime implementations of the methods
+    pub py_module: Str,
+    pub methods: Vec<DerivedMethod>,
+}
+
+impl DerivedTrait {
+    pub const fn new(trait_: Type, py_module: Str, methods: Vec<DerivedMethod>) -> Self {
+        Self {
+            trait_,
+            py_module,
+            methods,
+        }
+    }
+
+    /// names to be imported into the class namespace at runtime
+    pub fn py_names(&self) -> Vec<Str> {
+        let extras = py_protocol_methods(&self.trait_)
+            .iter()
+            .map(|name| Str::ever(name));
+        self.methods
+            .iter()
+            .map(|method| method.py_name().clone())
+            .chain(extras)
+            .collect()
+    }
+}
+
+/// Python uses these methods instead of the ones declared in the trait
+/// (e.g. comparison operators call `__lt__`, not `__cmp__`)
+fn py_protocol_methods(trait_: &Type) -> &'static [&'static str] {
+    match &trait_.qual_name()[..] {
+        "Ord" => &["__lt__", "__le__", "__gt__", "__ge__"],
+        "Show" => &["__repr__"],
+        _ => &[],
+    }
+}
+
+impl Context {
+    /// Synthesizes the methods of `trait_` for `class`.
+    /// Returns `None` if `trait_` is not derivable.
+    pub(crate) fn synthesize_derive(&self, trait_: &Type, class: &Type) -> Option<DerivedTrait> {
+        let (_, trait_ctx) = self.get_nominal_type_ctx(trait_)?;
+        let Some(ValueObj::Str(py_module)) = trait_ctx.consts.get(DERIVE_ATTR) else {
+            return None;
+        };
+        let mut methods = vec![];
+        for (name, vi) in trait_ctx.decls.iter() {
+            let t = match &vi.t {
+                Type::Quantified(quant) => quant.as_ref().clone(),
+                other => other.clone(),
+            };
+            let t = match t.self_t().cloned() {
+                Some(Type::Ref(self_t)) => t.replace(&self_t, class),
+                Some(self_t) => t.replace(&self_t, class),
+                None => t,
+            };
+            methods.push(DerivedMethod::new(
+                name.inspect().clone(),
+                t,
+                vi.py_name.clone(),
+            ));
+        }
+        Some(DerivedTrait::new(
+            trait_.clone(),
+            py_module.clone(),
+            methods,
+        ))
+    }
+}
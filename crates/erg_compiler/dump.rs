@@ -0,0 +1,396 @@
+//! Converts `HIR` to JSON and implements `erg dump`.
+//!
+//! The format is the same as that of `erg_parser::dump`,
+//! except that each expression has `"type"` (the inferred type).
+use erg_common::config::ErgConfig;
+use erg_common::error::MultiErrorDisplay;
+use erg_common::json::{Json, ToJson};
+use erg_common::traits::{ExitStatus, Runnable};
+
+use erg_parser::build_ast::ASTBuilder;
+use erg_parser::dump::{node, text, DUMP_FORMAT_VERSION};
+
+use crate::build_hir::HIRBuilder;
+use crate::error::{CompileError, CompileErrors};
+use crate::hir::{
+    Accessor, Args, Array, Block, Def, Dict, Expr, Identifier, Params, Record, Set, Signature,
+    Tuple, TypeSpecWithOp, HIR,
+};
+use crate::ty::HasType;
+
+fn t_spec_json(t_spec: &Option<TypeSpecWithOp>) -> Json {
+    t_spec
+        .as_ref()
+        .map_or(Json::Null, |t_spec| text(&t_spec.raw.t_spec))
+}
+
+fn block_json(block: &Block) -> Json {
+    Json::Array(block.iter().map(ToJson::to_json).collect())
+}
+
+/// Inserts `"type"` after `"node"` and `"loc"`.
+fn typed(json: Json, t: &impl HasType) -> Json {
+    let Json::Object(mut fields) = json else {
+        return json;
+    };
+    fields.insert(2.min(fields.len()), ("type", text(t.ref_t())));
+    Json::Object(fields)
+}
+
+impl ToJson for HIR {
+    fn to_json(&self) -> Json {
+        let module = self.module.iter().map(ToJson::to_json).collect();
+        Json::object([
+            ("format_version", DUMP_FORMAT_VERSION.into()),
+            ("name", text(&self.name)),
+            ("module", Json::Array(module)),
+        ])
+    }
+}
+
+impl ToJson for Identifier {
+    fn to_json(&self) -> Json {
+        typed(
+            node(
+                "Identifier",
+                self,
+                [
+                    ("vis", text(&self.raw.vis)),
+                    ("name", text(self.inspect())),
+                    ("qual_name", self.qual_name.as_ref().map(text).into()),
+                ],
+            ),
+            self,
+        )
+    }
+}
+
+impl ToJson for Args {
+    fn to_json(&self) -> Json {
+        let kw_args = self
+            .kw_args
+            .iter()
+            .map(|arg| {
+                Json::object([
+                    ("keyword", text(&arg.keyword.content)),
+                    ("value", arg.expr.to_json()),
+                ])
+            })
+            .collect();
+        Json::object([
+            (
+                "pos",
+                Json::Array(self.pos_args.iter().map(|arg| arg.expr.to_json()).collect()),
+            ),
+            (
+                "var",
+                self.var_args
+                    .as_ref()
+                    .map_or(Json::Null, |arg| arg.expr.to_json()),
+            ),
+            ("kw", Json::Array(kw_args)),
+        ])
+    }
+}
+
+impl ToJson for Params {
+    fn to_json(&self) -> Json {
+        let defaults = self
+            .defaults
+            .iter()
+            .map(|param| {
+                Json::object([
+                    ("param", text(&param.sig)),
+                    ("default", param.default_val.to_json()),
+                ])
+            })
+            .collect();
+        Json::object([
+            (
+                "non_defaults",
+                Json::Array(self.non_defaults.iter().map(text).collect()),
+            ),
+            ("var_params", self.var_params.as_ref().map(text).into()),
+            ("defaults", Json::Array(defaults)),
+        ])
+    }
+}
+
+impl ToJson for Signature {
+    fn to_json(&self) -> Json {
+        match self {
+            Self::Var(var) => typed(
+                node(
+                    "VarSignature",
+                    var,
+                    [
+                        ("ident", var.ident.to_json()),
+                        ("t_spec", t_spec_json(&var.t_spec)),
+                    ],
+                ),
+                &var.ident,
+            ),
+            Self::Subr(subr) => typed(
+                node(
+                    "SubrSignature",
+                    subr,
+                    [
+                        ("ident", subr.ident.to_json()),
+                        ("bounds", text(&subr.bounds)),
+                        ("params", subr.params.to_json()),
+                        ("return_t_spec", t_spec_json(&subr.return_t_spec)),
+                    ],
+                ),
+                &subr.ident,
+            ),
+        }
+    }
+}
+
+impl ToJson for Def {
+    fn to_json(&self) -> Json {
+        typed(
+            node(
+                "Def",
+                self,
+                [
+                    ("sig", self.sig.to_json()),
+                    ("op", text(&self.body.op.content)),
+                    ("body", block_json(&self.body.block)),
+                ],
+            ),
+            self,
+        )
+    }
+}
+
+impl ToJson for Record {
+    fn to_json(&self) -> Json {
+        let attrs = self.attrs.iter().map(ToJson::to_json).collect();
+        typed(node("Record", self, [("attrs", Json::Array(attrs))]), self)
+    }
+}
+
+impl ToJson for Accessor {
+    fn to_json(&self) -> Json {
+        match self {
+            Self::Ident(ident) => ident.to_json(),
+            Self::Attr(attr) => typed(
+                node(
+                    "Attribute",
+                    attr,
+                    [("obj", attr.obj.to_json()), ("ident", attr.ident.to_json())],
+                ),
+                attr,
+            ),
+        }
+    }
+}
+
+impl ToJson for Expr {
+    fn to_json(&self) -> Json {
+        let json = match self {
+            Self::Lit(lit) => node(
+                "Literal",
+                lit,
+                [
+                    ("kind", format!("{:?}", lit.token.kind).into()),
+                    ("value", text(&lit.token.content)),
+                ],
+            ),
+            Self::Accessor(acc) => return acc.to_json(),
+            Self::Array(Array::Normal(arr)) => node("Array", arr, [("elems", arr.elems.to_json())]),
+            Self::Array(Array::WithLength(arr)) => node(
+                "ArrayWithLength",
+                arr,
+                [("elem", arr.elem.to_json()), ("len", arr.len.to_json())],
+            ),
+            Self::Array(Array::Comprehension(arr)) => node(
+                "ArrayComprehension",
+                arr,
+                [("elem", arr.elem.to_json()), ("guard", arr.guard.to_json())],
+            ),
+            Self::Tuple(Tuple::Normal(tup)) => node("Tuple", tup, [("elems", tup.elems.to_json())]),
+            Self::Set(Set::Normal(set)) => node("Set", set, [("elems", set.elems.to_json())]),
+            Self::Set(Set::WithLength(set)) => node(
+                "SetWithLength",
+                set,
+                [("elem", set.elem.to_json()), ("len", set.len.to_json())],
+            ),
+            Self::Dict(Dict::Normal(dict)) => {
+                let kvs = dict
+                    .kvs
+                    .iter()
+                    .map(|kv| {
+                        Json::object([("key", kv.key.to_json()), ("value", kv.value.to_json())])
+                    })
+                    .collect();
+                node("Dict", dict, [("kvs", Json::Array(kvs))])
+            }
+            Self::Dict(Dict::Comprehension(dict)) => node(
+                "DictComprehension",
+                dict,
+                [
+                    ("key", dict.key.to_json()),
+                    ("value", dict.value.to_json()),
+                    ("guard", dict.guard.to_json()),
+                ],
+            ),
+            Self::Record(record) => return record.to_json(),
+            Self::BinOp(bin) => node(
+                "BinOp",
+                bin,
+                [
+                    ("op", text(&bin.op.content)),
+                    ("lhs", bin.lhs.to_json()),
+                    ("rhs", bin.rhs.to_json()),
+                ],
+            ),
+            Self::UnaryOp(unary) => node(
+                "UnaryOp",
+                unary,
+                [
+                    ("op", text(&unary.op.content)),
+                    ("expr", unary.expr.to_json()),
+                ],
+            ),
+            Self::Call(call) => node(
+                "Call",
+                call,
+                [
+                    ("obj", call.obj.to_json()),
+                    (
+                        "attr_name",
+                        call.attr_name.as_ref().map_or(Json::Null, ToJson::to_json),
+                    ),
+                    ("args", call.args.to_json()),
+                ],
+            ),
+            Self::Lambda(lambda) => node(
+                "Lambda",
+                lambda,
+                [
+                    ("params", lambda.params.to_json()),
+                    ("op", text(&lambda.op.content)),
+                    ("body", block_json(&lambda.body)),
+                ],
+            ),
+            Self::Def(def) => return def.to_json(),
+            Self::ClassDef(class_def) => node(
+                "ClassDef",
+                class_def,
+                [
+                    ("sig", class_def.sig.to_json()),
+                    ("require_or_sup", class_def.require_or_sup.to_json()),
+                    ("methods", block_json(&class_def.methods)),
+                ],
+            ),
+            Self::PatchDef(patch_def) => node(
+                "PatchDef",
+                patch_def,
+                [
+                    ("sig", patch_def.sig.to_json()),
+                    ("base", patch_def.base.to_json()),
+                    ("methods", block_json(&patch_def.methods)),
+                ],
+            ),
+            Self::ReDef(redef) => node(
+                "ReDef",
+                redef,
+                [
+                    ("attr", redef.attr.to_json()),
+                    ("block", block_json(&redef.block)),
+                ],
+            ),
+            Self::TypeAsc(tasc) => node(
+                "TypeAscription",
+                tasc,
+                [
+                    ("expr", tasc.expr.to_json()),
+                    ("op", text(&tasc.spec.raw.op.content)),
+                    ("t_spec", text(&tasc.spec.raw.t_spec)),
+                ],
+            ),
+            Self::Code(block) => node("Code", block, [("block", block_json(block))]),
+            Self::Compound(block) => node("Compound", block, [("block", block_json(block))]),
+            Self::Import(acc) => node("Import", acc, [("module", acc.to_json())]),
+            Self::Dummy(dummy) => node(
+                "Dummy",
+                dummy,
+                [(
+                    "exprs",
+                    Json::Array(dummy.iter().map(ToJson::to_json).collect()),
+                )],
+            ),
+        };
+        typed(json, self)
+    }
+}
+
+/// Dumps the AST (`erg dump --ast`) or the HIR (`erg dump --hir`) as JSON.
+#[derive(Debug)]
+pub struct Dumper {
+    pub cfg: ErgConfig,
+}
+
+impl Default for Dumper {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for Dumper {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg AST/HIR dumper";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self { cfg }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {}
+
+    fn clear(&mut self) {}
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let src = self.cfg.input.read();
+        let json = self.dump(src)?;
+        println!("{json:#}");
+        Ok(ExitStatus::OK)
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        let json = self.dump(src)?;
+        Ok(format!("{json:#}"))
+    }
+}
+
+impl Dumper {
+    pub fn dump(&mut self, src: String) -> Result<Json, CompileErrors> {
+        if self.cfg.dump_hir {
+            let mut builder = HIRBuilder::new(self.cfg.copy());
+            let artifact = builder.build(src, "exec").map_err(|eart| eart.errors)?;
+            artifact.warns.write_all_stderr();
+            Ok(artifact.object.to_json())
+        } else {
+            let mut builder = ASTBuilder::new(self.cfg.copy());
+            let artifact = builder
+                .build(src)
+                .map_err(|eart| CompileErrors::from(eart.errors))?;
+            artifact.warns.write_all_stderr();
+            Ok(artifact.ast.to_json())
+        }
+    }
+}
@@ -3,13 +3,16 @@
 //! 関数や不変型に副作用がないかチェックする
 
 use erg_common::config::ErgConfig;
+use erg_common::io::Input;
 use erg_common::log;
 use erg_common::traits::{Locational, Stream};
 use erg_common::Str;
+use erg_parser::ast::OperationKind;
 use erg_parser::token::TokenKind;
 
 use crate::error::{EffectError, EffectErrors};
-use crate::hir::{Array, Def, Dict, Expr, Params, Set, Signature, Tuple, HIR};
+use crate::hir::{Accessor, Array, Def, Dict, Expr, Params, Set, Signature, Tuple, HIR};
+use crate::manifest::is_listed_in;
 use crate::ty::{HasType, Visibility};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -26,6 +29,32 @@ enum BlockKind {
 
 use BlockKind::*;
 
+/// The builtin procedures that perform IO
+const IO_BUILTINS: [&str; 3] = ["print!", "input!", "open!"];
+
+/// Restrictions on the side effects of a module, declared in the project manifest (`package.er`).
+///
+/// ```erg
+/// pure = ["src/plugins/"]
+/// no_io = ["src/sandbox.er"]
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct EffectPolicy {
+    /// no side effects are allowed, even at the top level or in procedures
+    pure: bool,
+    /// the IO builtins (`print!`, `input!`, `open!`) cannot be used
+    no_io: bool,
+}
+
+impl EffectPolicy {
+    fn new(input: &Input) -> Self {
+        Self {
+            pure: is_listed_in(input, "pure"),
+            no_io: is_listed_in(input, "no_io"),
+        }
+    }
+}
+
 /// Checks code for side effects.
 /// For example:
 /// * check if expressions with side effects are not used in functions
@@ -35,12 +64,14 @@ pub struct SideEffectChecker {
     cfg: ErgConfig,
     path_stack: Vec<Visibility>,
     block_stack: Vec<BlockKind>,
+    policy: EffectPolicy,
     errs: EffectErrors,
 }
 
 impl SideEffectChecker {
     pub fn new(cfg: ErgConfig) -> Self {
         Self {
+            policy: EffectPolicy::new(&cfg.input),
             cfg,
             path_stack: vec![],
             block_stack: vec![],
@@ -79,11 +110,51 @@ impl SideEffectChecker {
         }
     }
 
+    /// `expr` causes a side-effect here
+    fn check_effect(&mut self, expr: &Expr) {
+        if !self.in_context_effects_allowed() {
+            self.errs.push(EffectError::has_effect(
+                self.cfg.input.clone(),
+                line!() as usize,
+                expr,
+                self.full_path(),
+            ));
+        } else if self.policy.pure {
+            self.errs.push(EffectError::effect_in_pure_module_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                expr,
+                self.full_path(),
+            ));
+        }
+    }
+
+    /// check if `expr` refers to an IO builtin in a `no_io` module
+    fn check_io(&mut self, expr: &Expr) {
+        if !self.policy.no_io {
+            return;
+        }
+        if let Expr::Accessor(Accessor::Ident(ident)) = expr {
+            if &ident.vi.vis.def_namespace[..] == "<builtins>"
+                && IO_BUILTINS.contains(&&ident.inspect()[..])
+            {
+                self.errs.push(EffectError::io_denied_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    expr,
+                    self.full_path(),
+                    ident.inspect(),
+                ));
+            }
+        }
+    }
+
     pub fn check(mut self, hir: HIR) -> Result<HIR, (HIR, EffectErrors)> {
         self.path_stack.push(Visibility::private(hir.name.clone()));
         self.block_stack.push(Module);
         log!(info "the side-effects checking process has started.{RESET}");
-        // At the top level, there is no problem with side effects, only check for purity violations.
+        // At the top level, there is no problem with side effects, only check for purity violations
+        // (and the restrictions of `EffectPolicy`).
         // トップレベルでは副作用があっても問題なく、純粋性違反がないかのみチェックする
         for expr in hir.module.iter() {
             match expr {
@@ -106,17 +177,8 @@ impl SideEffectChecker {
                         self.check_expr(def);
                     }
                 }
-                Expr::Call(call) => {
-                    for parg in call.args.pos_args.iter() {
-                        self.check_expr(&parg.expr);
-                    }
-                    for kwarg in call.args.kw_args.iter() {
-                        self.check_expr(&kwarg.expr);
-                    }
-                }
-                Expr::BinOp(bin) => {
-                    self.check_expr(&bin.lhs);
-                    self.check_expr(&bin.rhs);
+                Expr::Call(_) | Expr::BinOp(_) => {
+                    self.check_expr(expr);
                 }
                 Expr::UnaryOp(unary) => {
                     self.check_expr(&unary.expr);
@@ -385,21 +447,18 @@ impl SideEffectChecker {
                 other => todo!("{other}"),
             },
             Expr::Call(call) => {
-                if (call.obj.t().is_procedure()
+                // the code embedded by `py_inline` may do anything
+                if call.obj.t().is_procedure()
                     || call
                         .attr_name
                         .as_ref()
                         .map(|name| name.is_procedural())
-                        .unwrap_or(false))
-                    && !self.in_context_effects_allowed()
+                        .unwrap_or(false)
+                    || call.additional_operation() == Some(OperationKind::PyInline)
                 {
-                    self.errs.push(EffectError::has_effect(
-                        self.cfg.input.clone(),
-                        line!() as usize,
-                        expr,
-                        self.full_path(),
-                    ));
+                    self.check_effect(expr);
                 }
+                self.check_io(&call.obj);
                 call.args
                     .pos_args
                     .iter()
@@ -415,15 +474,8 @@ impl SideEffectChecker {
             Expr::BinOp(bin) => {
                 self.check_expr(&bin.lhs);
                 self.check_expr(&bin.rhs);
-                if (bin.op.kind == TokenKind::IsOp || bin.op.kind == TokenKind::IsNotOp)
-                    && !self.in_context_effects_allowed()
-                {
-                    self.errs.push(EffectError::has_effect(
-                        self.cfg.input.clone(),
-                        line!() as usize,
-                        expr,
-                        self.full_path(),
-                    ));
+                if bin.op.kind == TokenKind::IsOp || bin.op.kind == TokenKind::IsNotOp {
+                    self.check_effect(expr);
                 }
             }
             Expr::Lambda(lambda) => {
@@ -446,6 +498,8 @@ impl SideEffectChecker {
                 self.check_expr(&type_asc.expr);
             }
             Expr::Accessor(acc) => {
+                // e.g. `p! = print!`
+                self.check_io(expr);
                 if !self.in_context_effects_allowed() && acc.ref_t().is_mut_type() {
                     self.errs.push(EffectError::touch_mut_error(
                         self.cfg.input.clone(),
@@ -467,6 +521,7 @@ impl SideEffectChecker {
         match expr {
             Expr::Call(call) => {
                 call.ref_t().is_procedure()
+                    || call.additional_operation() == Some(OperationKind::PyInline)
                     || call
                         .args
                         .pos_args
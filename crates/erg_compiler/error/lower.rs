@@ -1,4 +1,6 @@
-use erg_common::error::{ErrorCore, ErrorKind::*, Location, SubMessage};
+use erg_common::config::TargetProfile;
+use erg_common::consts::{ERG_MODE, PYTHON_MODE};
+use erg_common::error::{ErrorCore, ErrorKind::*, Location, SubMessage, Suggestion};
 use erg_common::io::Input;
 use erg_common::style::{StyledStr, StyledString, StyledStrings, Stylize};
 use erg_common::traits::Locational;
@@ -7,7 +9,7 @@ use erg_common::{switch_lang, Str};
 use crate::error::*;
 use crate::hir::{Expr, Identifier};
 use crate::ty::{HasType, Type, Visibility};
-use crate::varinfo::VarInfo;
+use crate::varinfo::{AbsLocation, MethodKind, VarInfo};
 
 pub type LowerError = CompileError;
 pub type LowerWarning = LowerError;
@@ -16,6 +18,41 @@ pub type LowerWarnings = LowerErrors;
 pub type LowerResult<T> = CompileResult<T>;
 pub type SingleLowerResult<T> = SingleCompileResult<T>;
 
+/// Replaces the misspelled `name` with `similar_name`, if `loc` points to the name itself.
+fn typo_suggestions(loc: Location, name: &str, similar_name: Option<&str>) -> Vec<Suggestion> {
+    match similar_name {
+        Some(similar)
+            if similar != name
+                && loc.ln_begin() == loc.ln_end()
+                && loc.length() == Some(name.chars().count() as u32) =>
+        {
+            vec![Suggestion::new(
+                format!("Replace with `{similar}`"),
+                None,
+                loc,
+                similar.into(),
+            )]
+        }
+        _ => vec![],
+    }
+}
+
+/// Imports `name` from the module `path` at the beginning of the file.
+pub(crate) fn import_suggestion(name: &str, path: &str) -> Suggestion {
+    let import = if PYTHON_MODE {
+        format!("from {} import {name}\n", path.replace('/', "."))
+    } else {
+        format!("{{{name};}} = import \"{path}\"\n")
+    };
+    Suggestion::insert(
+        format!("Import `{name}` from \"{path}\""),
+        None,
+        1,
+        0,
+        import,
+    )
+}
+
 impl LowerError {
     pub fn syntax_error(
         input: Input,
@@ -65,6 +102,71 @@ impl LowerError {
         )
     }
 
+    /// A function has no side effects, so discarding its result makes the call meaningless.
+    pub fn unused_func_result_warning(
+        input: Input,
+        errno: usize,
+        expr: &Expr,
+        caused_by: String,
+    ) -> Self {
+        let desc = switch_lang!(
+            "japanese" => format!("関数呼び出しの結果(: {})が捨てられているため、この呼び出しは何の効果もありません", expr.ref_t()),
+            "simplified_chinese" => format!("函数调用的结果(: {})被丢弃, 因此该调用没有任何效果", expr.ref_t()),
+            "traditional_chinese" => format!("函數調用的結果(: {})被丟棄, 因此該調用沒有任何效果", expr.ref_t()),
+            "english" => format!("the result of the function call (: {}) is discarded, so the call has no effect", expr.ref_t()),
+        );
+        let discard = StyledString::new("discard", Some(HINT), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => format!("関数は副作用を持ちません。意図的に捨てる場合は、{discard}関数を使用してください"),
+            "simplified_chinese" => format!("函数没有副作用。如果是有意丢弃, 请使用{discard}函数"),
+            "traditional_chinese" => format!("函數沒有副作用。如果是有意丟棄, 請使用{discard}函數"),
+            "english" => format!("functions have no side effects. If you discard the result on purpose, use {discard} function"),
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(expr.loc(), vec![], Some(hint))],
+                desc,
+                errno,
+                UnusedWarning,
+                expr.loc(),
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn unused_proc_result_warning(
+        input: Input,
+        errno: usize,
+        expr: &Expr,
+        caused_by: String,
+    ) -> Self {
+        let desc = switch_lang!(
+            "japanese" => format!("プロシージャの戻り値(: {})が使われていません", expr.ref_t()),
+            "simplified_chinese" => format!("过程的返回值(: {})未使用", expr.ref_t()),
+            "traditional_chinese" => format!("程序的返回值(: {})未使用", expr.ref_t()),
+            "english" => format!("the return value of the procedure (: {}) is not used", expr.ref_t()),
+        );
+        let discard = StyledString::new("discard", Some(HINT), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => format!("値を使わない場合は、{discard}関数を使用してください"),
+            "simplified_chinese" => format!("如果您不想使用该值，请使用{discard}函数"),
+            "traditional_chinese" => format!("如果您不想使用該值，請使用{discard}函數"),
+            "english" => format!("if you don't use the value, use {discard} function"),
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(expr.loc(), vec![], Some(hint))],
+                desc,
+                errno,
+                UnusedWarning,
+                expr.loc(),
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn unused_subroutine_warning(
         input: Input,
         errno: usize,
@@ -186,6 +288,7 @@ impl LowerError {
         name: &str,
         similar_name: Option<&str>,
     ) -> Self {
+        let suggestions = typo_suggestions(loc, name, similar_name);
         let name = readable_name(name);
         let hint = similar_name.map(|n| {
             let n = n.with_color_and_attr(HINT, ATTR);
@@ -209,7 +312,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -225,6 +329,7 @@ impl LowerError {
         similar_name: Option<&str>,
         similar_info: Option<&VarInfo>,
     ) -> Self {
+        let suggestions = typo_suggestions(loc, name, similar_name);
         let name = readable_name(name);
         let hint = similar_name.map(|n| {
             let vis = similar_info.map_or("".into(), |vi| vi.vis.modifier.display());
@@ -249,7 +354,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -293,6 +399,41 @@ impl LowerError {
         )
     }
 
+    /// `path`: e.g. `["A", "B", "A"]`
+    pub fn cyclic_definition_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        path: &[Str],
+    ) -> Self {
+        let path = path
+            .iter()
+            .map(|name| {
+                readable_name(name)
+                    .with_color_and_attr(ERR, ATTR)
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("定義が循環しています: {path}"),
+                    "simplified_chinese" => format!("定义存在循环依赖: {path}"),
+                    "traditional_chinese" => format!("定義存在循環依賴: {path}"),
+                    "english" => format!("cyclic definition: {path}"),
+                ),
+                errno,
+                NameError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn access_deleted_var_error(
         input: Input,
         errno: usize,
@@ -331,6 +472,33 @@ impl LowerError {
         )
     }
 
+    pub fn banned_builtin_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        name: &str,
+    ) -> Self {
+        let found = readable_name(name).with_color_and_attr(ERR, ATTR);
+        let entry = "banned_builtins".with_color(HINT);
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("組み込みの{found}はpackage.erの{entry}で禁止されています"),
+                    "simplified_chinese" => format!("内置的{found}被package.er的{entry}禁止"),
+                    "traditional_chinese" => format!("內置的{found}被package.er的{entry}禁止"),
+                    "english" => format!("the builtin {found} is banned by the {entry} entry of package.er"),
+                ),
+                errno,
+                NameError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn no_type_error(
         input: Input,
         errno: usize,
@@ -339,6 +507,7 @@ impl LowerError {
         name: &str,
         similar_name: Option<&str>,
     ) -> Self {
+        let suggestions = typo_suggestions(loc, name, similar_name);
         let name = readable_name(name);
         let hint = similar_name.map(|n| {
             let n = StyledStr::new(n, Some(HINT), Some(ATTR));
@@ -362,7 +531,8 @@ impl LowerError {
                 errno,
                 NameError,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -445,6 +615,7 @@ impl LowerError {
         name: &str,
         similar_name: Option<&str>,
     ) -> Self {
+        let suggestions = typo_suggestions(loc, name, similar_name);
         let hint = similar_name.map(|n| {
             switch_lang!(
                 "japanese" => format!("似た名前の属性があります: {n}"),
@@ -466,7 +637,8 @@ impl LowerError {
                 errno,
                 AttributeError,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -483,6 +655,7 @@ impl LowerError {
         similar_name: Option<&str>,
         similar_info: Option<&VarInfo>,
     ) -> Self {
+        let suggestions = typo_suggestions(loc, name, similar_name);
         let hint = similar_name.map(|n| {
             let vis = similar_info.map_or("".into(), |vi| vi.vis.modifier.display());
             let kind = similar_info.map_or("", |vi| vi.kind.display());
@@ -506,7 +679,8 @@ impl LowerError {
                 errno,
                 AttributeError,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -523,6 +697,7 @@ impl LowerError {
         name: &str,
         similar_name: Option<&str>,
     ) -> Self {
+        let suggestions = typo_suggestions(loc, name, similar_name);
         let hint = similar_name.map(|n| {
             let n = StyledStr::new(n, Some(HINT), Some(ATTR));
             switch_lang!(
@@ -545,7 +720,8 @@ impl LowerError {
                 errno,
                 AttributeError,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -603,6 +779,69 @@ impl LowerError {
         )
     }
 
+    pub fn self_param_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        kind: MethodKind,
+    ) -> Self {
+        let slf = StyledStr::new("self", Some(ERR), Some(ATTR));
+        let msg = if kind.is_class() {
+            switch_lang!(
+                "japanese" => format!("クラスメソッドの第一引数は{slf}ではなくクラスです"),
+                "simplified_chinese" => format!("类方法的第一个参数必须是类,而不是{slf}"),
+                "traditional_chinese" => format!("類方法的第一個參數必須是類,而不是{slf}"),
+                "english" => format!("the first parameter of a class method must be the class, not {slf}"),
+            )
+        } else {
+            switch_lang!(
+                "japanese" => format!("{slf}は第一引数でなくてはなりません"),
+                "simplified_chinese" => format!("{slf}必须是第一个参数"),
+                "traditional_chinese" => format!("{slf}必須是第一個參數"),
+                "english" => format!("{slf} must be the first parameter"),
+            )
+        };
+        Self::new(
+            ErrorCore::new(vec![SubMessage::only_loc(loc)], msg, errno, TypeError, loc),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn read_only_property_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        name: &str,
+    ) -> Self {
+        let setter = StyledString::new(format!("{name}!"), Some(HINT), Some(ATTR));
+        let name = StyledStr::new(readable_name(name), Some(WARN), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => format!("セッター{setter}を@Propertyで定義してください"),
+            "simplified_chinese" => format!("请用@Property定义setter {setter}"),
+            "traditional_chinese" => format!("請用@Property定義setter {setter}"),
+            "english" => format!("define the setter {setter} with @Property"),
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint))],
+                switch_lang!(
+                    "japanese" => format!("プロパティ{name}は読み取り専用です"),
+                    "simplified_chinese" => format!("属性{name}是只读的"),
+                    "traditional_chinese" => format!("屬性{name}是唯讀的"),
+                    "english" => format!("property {name} is read-only"),
+                ),
+                errno,
+                AssignError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn del_error(
         input: Input,
         errno: usize,
@@ -644,6 +883,8 @@ impl LowerError {
         )
     }
 
+    /// `public_alt`: a public variable that can be used instead (and the location of the accessed name)
+    #[allow(clippy::too_many_arguments)]
     pub fn visibility_error(
         input: Input,
         errno: usize,
@@ -651,12 +892,61 @@ impl LowerError {
         caused_by: String,
         name: &str,
         vis: Visibility,
+        def_loc: &AbsLocation,
+        public_alt: Option<(&str, Location)>,
     ) -> Self {
         let visibility = vis.modifier.display();
         let found = StyledString::new(readable_name(name), Some(ERR), Some(ATTR));
+        let mut suggestions = vec![];
+        if let Some((alt, name_loc)) = public_alt {
+            suggestions.push(Suggestion::new(
+                format!("Use `{alt}` instead"),
+                None,
+                name_loc,
+                alt.into(),
+            ));
+        }
+        // a private variable defined in an Erg script can be made public with `.`
+        let def = def_loc
+            .module
+            .as_ref()
+            .filter(|path| {
+                ERG_MODE && vis.is_private() && !path.to_string_lossy().ends_with(".d.er")
+            })
+            .and_then(|path| {
+                let (ln, col) = (def_loc.loc.ln_begin()?, def_loc.loc.col_begin()?);
+                suggestions.push(Suggestion::insert(
+                    format!("Make `{name}` public"),
+                    Some(path.clone()),
+                    ln,
+                    col,
+                    ".".into(),
+                ));
+                Some((path.file_name()?, ln))
+            });
+        let hint = if let Some((alt, _)) = public_alt {
+            let alt = StyledString::new(alt, Some(HINT), Some(ATTR));
+            Some(switch_lang!(
+                "japanese" => format!("公開されている{alt}を使ってください"),
+                "simplified_chinese" => format!("请使用公开的{alt}"),
+                "traditional_chinese" => format!("請使用公開的{alt}"),
+                "english" => format!("use the public {alt} instead"),
+            ))
+        } else {
+            def.map(|(file, line)| {
+                let file = file.to_string_lossy();
+                let public = StyledString::new(format!(".{name}"), Some(HINT), Some(ATTR));
+                switch_lang!(
+                    "japanese" => format!("公開するには、{file}の{line}行目の定義を{public}にしてください"),
+                    "simplified_chinese" => format!("要公开它，请将{file}第{line}行的定义改为{public}"),
+                    "traditional_chinese" => format!("要公開它，請將{file}第{line}行的定義改為{public}"),
+                    "english" => format!("to make it public, define it as {public} (line {line} of {file})"),
+                )
+            })
+        };
         Self::new(
             ErrorCore::new(
-                vec![SubMessage::only_loc(loc)],
+                vec![SubMessage::ambiguous_new(loc, vec![], hint)],
                 switch_lang!(
                     "japanese" => format!("{found}は{visibility}変数です"),
                     "simplified_chinese" => format!("{found}是{visibility}变量",),
@@ -666,7 +956,8 @@ impl LowerError {
                 errno,
                 VisibilityError,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -775,6 +1066,58 @@ impl LowerError {
         )
     }
 
+    pub fn abstract_instantiation_error(
+        input: Input,
+        errno: usize,
+        class: &str,
+        loc: Location,
+        caused_by: String,
+    ) -> Self {
+        let class = StyledString::new(class, Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("抽象クラス{class}はインスタンス化できません"),
+                    "simplified_chinese" => format!("抽象类{class}不可实例化"),
+                    "traditional_chinese" => format!("抽象類{class}不可實例化"),
+                    "english" => format!("abstract class {class} cannot be instantiated"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn slice_out_of_range_error(
+        input: Input,
+        errno: usize,
+        len: usize,
+        end: usize,
+        loc: Location,
+        caused_by: String,
+    ) -> Self {
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("要素数は{len}ですが、{end}番目の要素までスライスしようとしました"),
+                    "simplified_chinese" => format!("有{len}个元素，但切片到第{end}个元素"),
+                    "traditional_chinese" => format!("有{len}個元素，但切片到第{end}個元素"),
+                    "english" => format!("it has {len} elements, but sliced up to the {end}th element"),
+                ),
+                errno,
+                IndexError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn file_error(
         input: Input,
         errno: usize,
@@ -812,6 +1155,62 @@ impl LowerError {
         Self::file_error(input, errno, desc, loc, caused_by, None)
     }
 
+    pub fn not_available_on_target_error(
+        input: Input,
+        errno: usize,
+        name: &str,
+        target: TargetProfile,
+        loc: Location,
+        caused_by: String,
+    ) -> Self {
+        let name = StyledString::new(name, Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("{name}はターゲット{target}では利用できません"),
+                    "simplified_chinese" => format!("{name}在目标{target}上不可用"),
+                    "traditional_chinese" => format!("{name}在目標{target}上不可用"),
+                    "english" => format!("{name} is not available on target {target}"),
+                ),
+                errno,
+                EnvironmentError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `waiter` and `target` wait for each other's analysis
+    pub fn deadlock_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        waiter: &str,
+        target: &str,
+    ) -> Self {
+        let waiter = waiter.with_color_and_attr(ERR, ATTR);
+        let target = target.with_color_and_attr(ERR, ATTR);
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("デッドロックを検出しました: {waiter}と{target}の解析が互いを待っています"),
+                    "simplified_chinese" => format!("检测到死锁: {waiter}和{target}的分析在互相等待"),
+                    "traditional_chinese" => format!("檢測到死鎖: {waiter}和{target}的分析在互相等待"),
+                    "english" => format!("deadlock detected: the analyses of {waiter} and {target} wait for each other"),
+                ),
+                errno,
+                ImportError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn import_error(
         input: Input,
         errno: usize,
@@ -1002,6 +1401,62 @@ impl LowerError {
         )
     }
 
+    pub fn inner_export_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+    ) -> Self {
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("exportはトップレベルで宣言されなければなりません"),
+                    "simplified_chinese" => format!("export必须在顶层声明"),
+                    "traditional_chinese" => format!("export必須在頂層宣告"),
+                    "english" => format!("export must be declared at the top level"),
+                ),
+                errno,
+                SyntaxError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn export_target_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        found: &Type,
+    ) -> Self {
+        let found = StyledString::new(format!("{found}"), Some(ERR), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => "`export foo`(モジュールの全ての公開名) または `export foo.bar`(モジュールの属性)の形で指定してください",
+            "simplified_chinese" => "请使用`export foo`(模块的所有公开名称)或`export foo.bar`(模块的属性)",
+            "traditional_chinese" => "請使用`export foo`(模組的所有公開名稱)或`export foo.bar`(模組的屬性)",
+            "english" => "use `export foo` (all the public names of a module) or `export foo.bar` (an attribute of a module)",
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint.into()))],
+                switch_lang!(
+                    "japanese" => format!("モジュールまたはモジュールの属性が必要ですが、{found}型のオブジェクトが渡されました"),
+                    "simplified_chinese" => format!("需要模块或模块的属性，但传入了{found}类型的对象"),
+                    "traditional_chinese" => format!("需要模組或模組的屬性，但傳入了{found}型別的物件"),
+                    "english" => format!("a module or an attribute of a module is expected, but an object of {found} was passed"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn declare_error(input: Input, errno: usize, loc: Location, caused_by: String) -> Self {
         Self::new(
             ErrorCore::new(
@@ -1021,6 +1476,60 @@ impl LowerError {
         )
     }
 
+    pub fn py_inline_literal_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+    ) -> Self {
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => "py_inlineの引数は文字列リテラルでなくてはなりません",
+                    "simplified_chinese" => "py_inline的参数必须是字符串字面量",
+                    "traditional_chinese" => "py_inline的參數必須是字串字面量",
+                    "english" => "the argument of py_inline must be a string literal",
+                ),
+                errno,
+                SyntaxError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn py_inline_untyped_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+    ) -> Self {
+        let hint = switch_lang!(
+            "japanese" => "型を宣言してください: `f: Int -> Int = py_inline \"lambda x: x + 1\"`",
+            "simplified_chinese" => "请声明类型: `f: Int -> Int = py_inline \"lambda x: x + 1\"`",
+            "traditional_chinese" => "請聲明類型: `f: Int -> Int = py_inline \"lambda x: x + 1\"`",
+            "english" => "declare the type: `f: Int -> Int = py_inline \"lambda x: x + 1\"`",
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint.into()))],
+                switch_lang!(
+                    "japanese" => "py_inlineの型を推論できません",
+                    "simplified_chinese" => "无法推断py_inline的类型",
+                    "traditional_chinese" => "無法推斷py_inline的類型",
+                    "english" => "cannot infer the type of py_inline",
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn invalid_type_cast_error(
         input: Input,
@@ -1055,13 +1564,18 @@ impl LowerError {
 }
 
 impl LowerWarning {
+    /// `removal`: the lines of the definition, if it can be removed as is (e.g. an unused import)
     pub fn unused_warning(
         input: Input,
         errno: usize,
         loc: Location,
         name: &str,
         caused_by: String,
+        removal: Option<Location>,
     ) -> Self {
+        let suggestions = removal
+            .map(|lines| vec![Suggestion::new(format!("Remove `{name}`"), None, lines, "".into())])
+            .unwrap_or_default();
         let name = StyledString::new(readable_name(name), Some(WARN), Some(ATTR));
         Self::new(
             ErrorCore::new(
@@ -1075,7 +1589,8 @@ impl LowerWarning {
                 errno,
                 UnusedWarning,
                 loc,
-            ),
+            )
+            .with_suggestions(suggestions),
             input,
             caused_by,
         )
@@ -258,6 +258,33 @@ impl CompileError {
         )
     }
 
+    /// The analysis thread of the module `mod_name` panicked
+    pub fn module_panic_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        mod_name: &str,
+        msg: &str,
+    ) -> Self {
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(loc)],
+                switch_lang!(
+                    "japanese" => format!("モジュール{mod_name}の解析中にコンパイラ内部エラーが発生しました: {msg}\nこれはErg compilerのバグです、開発者に報告して下さい ({URL})"),
+                    "simplified_chinese" => format!("分析模块{mod_name}时发生编译器内部错误: {msg}\n这是Erg编译器的错误，请报告给{URL}"),
+                    "traditional_chinese" => format!("分析模塊{mod_name}時發生編譯器內部錯誤: {msg}\n這是Erg編譯器的錯誤，請報告給{URL}"),
+                    "english" => format!("internal compiler error in module {mod_name}: {msg}\nthis is a bug of the Erg compiler, please report it to {URL}"),
+                ),
+                errno,
+                CompilerSystemError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn stack_bug(
         input: Input,
         loc: Location,
@@ -357,6 +384,56 @@ impl EffectError {
         )
     }
 
+    pub fn effect_in_pure_module_error(
+        input: Input,
+        errno: usize,
+        expr: &Expr,
+        caused_by: String,
+    ) -> Self {
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(expr.loc())],
+                switch_lang!(
+                    "japanese" => "この式には副作用がありますが、このモジュールはpackage.erで純粋と宣言されています",
+                    "simplified_chinese" => "此表达式会产生副作用，但此模块在package.er中被声明为纯模块",
+                    "traditional_chinese" => "此表達式會產生副作用，但此模組在package.er中被聲明為純模組",
+                    "english" => "this expression causes a side-effect, but this module is declared pure in package.er",
+                ),
+                errno,
+                HasEffect,
+                expr.loc(),
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    pub fn io_denied_error(
+        input: Input,
+        errno: usize,
+        expr: &Expr,
+        caused_by: String,
+        name: &str,
+    ) -> Self {
+        let name = StyledStr::new(name, Some(ERR), Some(ATTR));
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::only_loc(expr.loc())],
+                switch_lang!(
+                    "japanese" => format!("このモジュールはpackage.erでIOを禁止されているため、{name}は使えません"),
+                    "simplified_chinese" => format!("此模块在package.er中被禁止IO，因此不能使用{name}"),
+                    "traditional_chinese" => format!("此模組在package.er中被禁止IO，因此不能使用{name}"),
+                    "english" => format!("{name} cannot be used because IO is denied for this module in package.er"),
+                ),
+                errno,
+                HasEffect,
+                expr.loc(),
+            ),
+            input,
+            caused_by,
+        )
+    }
+
     pub fn proc_assign_error(input: Input, errno: usize, loc: Location, caused_by: String) -> Self {
         let hint = Some(
             switch_lang!(
@@ -704,6 +781,20 @@ mod test {
         );
         errors.push(err);
 
+        let input = Input::pipe("trait members not defined error".to_string());
+        let missing = [("member name", "member_name(self): Int = unreachable()".to_string())];
+        let err = TyCheckError::trait_members_not_defined_error(
+            input,
+            errno,
+            caused_by.to_string(),
+            &missing,
+            trait_type,
+            class_type,
+            Some((2, 4)),
+            Location::Unknown,
+        );
+        errors.push(err);
+
         let input = Input::pipe("singular no attribute error".to_string());
         let loc = Location::Range {
             ln_begin: 1,
@@ -783,8 +874,17 @@ mod test {
         let input = Input::pipe("visibility error".to_string());
         let loc = Location::Line(1);
         let vis = crate::ty::Visibility::DUMMY_PRIVATE;
-        let err =
-            TyCheckError::visibility_error(input, errno, loc, caused_by.to_string(), name, vis);
+        let def_loc = crate::varinfo::AbsLocation::unknown();
+        let err = TyCheckError::visibility_error(
+            input,
+            errno,
+            loc,
+            caused_by.to_string(),
+            name,
+            vis,
+            &def_loc,
+            None,
+        );
         errors.push(err);
 
         let input = Input::pipe("import nunpy as np".to_string());
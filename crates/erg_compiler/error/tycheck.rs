@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use erg_common::error::{ErrorCore, ErrorKind::*, Location, SubMessage};
+use erg_common::error::{ErrorCore, ErrorKind::*, Location, SubMessage, Suggestion};
 use erg_common::io::Input;
 use erg_common::set::Set;
 use erg_common::style::{StyledStr, StyledString, StyledStrings, Stylize};
@@ -1053,6 +1053,75 @@ passed keyword args:    {kw_args_len}"
         )
     }
 
+    /// `missing`: the names of the members not implemented and their definition stubs
+    /// `stub_pos`: where to insert the stubs (the line after the methods block and its indentation)
+    #[allow(clippy::too_many_arguments)]
+    pub fn trait_members_not_defined_error(
+        input: Input,
+        errno: usize,
+        caused_by: String,
+        missing: &[(&str, String)],
+        trait_type: &Type,
+        class_type: &Type,
+        stub_pos: Option<(u32, u32)>,
+        loc: Location,
+    ) -> Self {
+        let members = missing
+            .iter()
+            .map(|(name, _)| name.with_color_and_attr(WARN, ATTR).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let indent = " ".repeat(stub_pos.map_or(4, |(_, col)| col as usize));
+        let stubs = missing
+            .iter()
+            .map(|(_, stub)| format!("{indent}{stub}\n"))
+            .collect::<String>();
+        let hint = switch_lang!(
+            "japanese" => format!("以下の定義を追加してください:\n{stubs}"),
+            "simplified_chinese" => format!("请添加以下定义:\n{stubs}"),
+            "traditional_chinese" => format!("請添加以下定義:\n{stubs}"),
+            "english" => format!("add the following definitions:\n{stubs}"),
+        );
+        let suggestions = stub_pos
+            .map(|(ln, _)| {
+                vec![Suggestion::insert(
+                    format!("Implement missing members of `{}`", trait_type.local_name()),
+                    None,
+                    ln,
+                    0,
+                    stubs.clone(),
+                )]
+            })
+            .unwrap_or_default();
+        let main_msg = if missing.len() == 1 {
+            switch_lang!(
+                "japanese" => format!("{trait_type}の{members}が{class_type}で実装されていません"),
+                "simplified_chinese" => format!("{trait_type}中的{members}没有在{class_type}中实现"),
+                "traditional_chinese" => format!("{trait_type}中的{members}沒有在{class_type}中實現"),
+                "english" => format!("{members} of {trait_type} is not implemented in {class_type}"),
+            )
+        } else {
+            switch_lang!(
+                "japanese" => format!("{trait_type}の{members}({}個)が{class_type}で実装されていません", missing.len()),
+                "simplified_chinese" => format!("{trait_type}中的{members}({}个)没有在{class_type}中实现", missing.len()),
+                "traditional_chinese" => format!("{trait_type}中的{members}({}個)沒有在{class_type}中實現", missing.len()),
+                "english" => format!("{members} ({} members) of {trait_type} are not implemented in {class_type}", missing.len()),
+            )
+        };
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint.trim_end().to_string()))],
+                main_msg,
+                errno,
+                TypeError,
+                loc,
+            )
+            .with_suggestions(suggestions),
+            input,
+            caused_by,
+        )
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn not_in_trait_error(
         input: Input,
@@ -1345,4 +1414,215 @@ passed keyword args:    {kw_args_len}"
             caused_by,
         )
     }
+
+    /// e.g. `?T == Array(?T)`
+    pub fn infinite_type_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        tyvar: &Type,
+        t: &Type,
+    ) -> Self {
+        let tyvar = StyledString::new(format!("{tyvar}"), Some(ERR), Some(ATTR));
+        let t = StyledString::new(format!("{t}"), Some(ERR), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => "型を明示的に指定してみてください",
+            "simplified_chinese" => "请尝试明确指定类型",
+            "traditional_chinese" => "請嘗試明確指定類型",
+            "english" => "try to explicitly specify the type",
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(
+                    loc,
+                    vec![],
+                    Some(hint.to_string()),
+                )],
+                switch_lang!(
+                    "japanese" => format!("無限の型を構築できません: {tyvar} == {t}"),
+                    "simplified_chinese" => format!("无法构造无限类型: {tyvar} == {t}"),
+                    "traditional_chinese" => format!("無法構造無限類型: {tyvar} == {t}"),
+                    "english" => format!("cannot construct the infinite type: {tyvar} == {t}"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `depth_exceeded`: whether the depth limit (`true`) or the work budget (`false`) is exceeded
+    pub fn too_complex_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        depth_exceeded: bool,
+    ) -> Self {
+        let option = if depth_exceeded {
+            "--max-type-check-depth"
+        } else {
+            "--max-type-check-steps"
+        };
+        let hint = switch_lang!(
+            "japanese" => format!("型を単純にするか、`{option}`で上限を引き上げてください"),
+            "simplified_chinese" => format!("请简化类型，或使用`{option}`提高上限"),
+            "traditional_chinese" => format!("請簡化類型，或使用`{option}`提高上限"),
+            "english" => format!("simplify the types, or raise the limit with `{option}`"),
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint))],
+                switch_lang!(
+                    "japanese" => "型検査が複雑すぎます",
+                    "simplified_chinese" => "类型检查过于复杂",
+                    "traditional_chinese" => "類型檢查過於複雜",
+                    "english" => "type checking too complex",
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `--strict`: accessing an attribute of a `Dynamic` value or an untyped parameter (in `py_compat` mode)
+    pub fn untyped_attr_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        obj_t: &Type,
+        attr: &str,
+    ) -> Self {
+        let attr = StyledStr::new(attr, Some(ERR), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => "型を指定するか、`if (x: T) := ...`などで型を絞り込んでください",
+            "simplified_chinese" => "请指定类型，或使用`if (x: T) := ...`等缩小类型",
+            "traditional_chinese" => "請指定類型，或使用`if (x: T) := ...`等縮小類型",
+            "english" => "specify the type, or narrow it (e.g. `if (x: T) := ...`)",
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint.into()))],
+                switch_lang!(
+                    "japanese" => format!("型が不明な値({obj_t})の属性{attr}にはアクセスできません(--strict)"),
+                    "simplified_chinese" => format!("无法访问类型未知的值({obj_t})的属性{attr}(--strict)"),
+                    "traditional_chinese" => format!("無法訪問類型未知的值({obj_t})的屬性{attr}(--strict)"),
+                    "english" => format!("cannot access the attribute {attr} of an untyped value ({obj_t}) (--strict)"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `--strict`: passing a `Dynamic` value where `expected` is required without a check
+    pub fn dynamic_coercion_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        expected: &Type,
+    ) -> Self {
+        let expected = StyledString::new(format!("{expected}"), Some(HINT), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => "`if (x: T) := ...`などで型を検査するか、`--runtime-checks`を指定してください",
+            "simplified_chinese" => "请使用`if (x: T) := ...`等检查类型，或指定`--runtime-checks`",
+            "traditional_chinese" => "請使用`if (x: T) := ...`等檢查類型，或指定`--runtime-checks`",
+            "english" => "check the type (e.g. `if (x: T) := ...`), or specify `--runtime-checks`",
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint.into()))],
+                switch_lang!(
+                    "japanese" => format!("Dynamic型の値が暗黙に{expected}に変換されています(--strict)"),
+                    "simplified_chinese" => format!("Dynamic 类型的值被隐式转换为{expected}(--strict)"),
+                    "traditional_chinese" => format!("Dynamic 型別的值被隱式轉換為{expected}(--strict)"),
+                    "english" => format!("a Dynamic value is implicitly converted to {expected} (--strict)"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `--strict`: the type of the unannotated parameter `name` is inferred as `Obj`
+    pub fn obj_fallback_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        name: &str,
+    ) -> Self {
+        let name = StyledStr::new(name, Some(ERR), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => format!("`{name}: T`のように型を指定してください"),
+            "simplified_chinese" => format!("请指定类型，例如`{name}: T`"),
+            "traditional_chinese" => format!("請指定類型，例如`{name}: T`"),
+            "english" => format!("specify the type (e.g. `{name}: T`)"),
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint))],
+                switch_lang!(
+                    "japanese" => format!("引数{name}の型がObjと推論されています(--strict)"),
+                    "simplified_chinese" => format!("参数{name}的类型被推断为Obj(--strict)"),
+                    "traditional_chinese" => format!("參數{name}的類型被推斷為Obj(--strict)"),
+                    "english" => format!("the type of the parameter {name} is inferred as Obj (--strict)"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
+
+    /// `--strict`: passing a value of `arg_t` where `param_t` is required through an `Into` implementation
+    pub fn into_conversion_error(
+        input: Input,
+        errno: usize,
+        loc: Location,
+        caused_by: String,
+        arg_t: &Type,
+        param_t: &Type,
+    ) -> Self {
+        let arg_t = StyledString::new(format!("{arg_t}"), Some(ERR), Some(ATTR));
+        let param_t = StyledString::new(format!("{param_t}"), Some(HINT), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => "`x.into()`のように明示的に変換してください",
+            "simplified_chinese" => "请显式转换，例如`x.into()`",
+            "traditional_chinese" => "請顯式轉換，例如`x.into()`",
+            "english" => "convert it explicitly (e.g. `x.into()`)",
+        );
+        Self::new(
+            ErrorCore::new(
+                vec![SubMessage::ambiguous_new(loc, vec![], Some(hint.into()))],
+                switch_lang!(
+                    "japanese" => format!("{arg_t}型の値が暗黙に{param_t}に変換されています(--strict)"),
+                    "simplified_chinese" => format!("{arg_t}类型的值被隐式转换为{param_t}(--strict)"),
+                    "traditional_chinese" => format!("{arg_t}型別的值被隱式轉換為{param_t}(--strict)"),
+                    "english" => format!("a value of {arg_t} is implicitly converted to {param_t} (--strict)"),
+                ),
+                errno,
+                TypeError,
+                loc,
+            ),
+            input,
+            caused_by,
+        )
+    }
 }
@@ -0,0 +1,262 @@
+//! Applies the fixes suggested by the compiler (`erg fix <file>.er`).
+//!
+//! The errors and warnings of the module (and the modules it imports) may carry [`Suggestion`]s,
+//! e.g. a misspelled name -> the similar name, an unused import -> removal, a private variable -> `.x`.
+//! The first suggestion of each diagnostic is applied to the source files.
+//! Edits that overlap an edit already accepted for the same file are reported as conflicts and left as is;
+//! running `erg fix` again applies them against the updated sources.
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use erg_common::config::ErgConfig;
+use erg_common::error::{Location, Suggestion};
+use erg_common::traits::{ExitStatus, Runnable};
+use erg_common::{log, normalize_path, switch_lang};
+
+use crate::build_hir::HIRBuilder;
+use crate::error::{CompileError, CompileErrors};
+
+/// A suggestion to be applied to `path` (`None` means the input which is not a file, e.g. a string).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Fix {
+    pub path: Option<PathBuf>,
+    pub suggestion: Suggestion,
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self
+            .path
+            .as_ref()
+            .map_or("<string>".into(), |path| path.display().to_string());
+        let loc = &self.suggestion.loc;
+        write!(
+            f,
+            "{path}:{}:{}: {}",
+            loc.ln_begin().unwrap_or(0),
+            loc.col_begin().unwrap_or(0) + 1,
+            self.suggestion.title
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FixReport {
+    pub applied: Vec<Fix>,
+    /// overlapping with an applied fix
+    pub conflicts: Vec<Fix>,
+}
+
+impl FixReport {
+    pub fn files(&self) -> usize {
+        let mut paths = self.applied.iter().map(|fix| &fix.path).collect::<Vec<_>>();
+        paths.dedup();
+        paths.len()
+    }
+}
+
+/// Applies the fixes suggested by the errors and warnings of Erg scripts.
+#[derive(Debug)]
+pub struct Fixer {
+    pub cfg: ErgConfig,
+    builder: HIRBuilder,
+}
+
+impl Default for Fixer {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for Fixer {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg fixer";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self {
+            builder: HIRBuilder::new(cfg.copy()),
+            cfg,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.builder.initialize();
+    }
+
+    fn clear(&mut self) {
+        self.builder.clear();
+    }
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let src = self.cfg.input.read();
+        let fixes = self.collect_fixes(src);
+        let mut report = FixReport::default();
+        for (path, fixes) in group_by_path(fixes) {
+            let Some(path) = path else {
+                continue;
+            };
+            let code = fs::read_to_string(&path)
+                .map_err(|err| CompileErrors::from(self.file_error(err.to_string())))?;
+            let (fixed, applied, conflicts) = apply_fixes(&code, fixes);
+            if !applied.is_empty() {
+                fs::write(&path, fixed)
+                    .map_err(|err| CompileErrors::from(self.file_error(err.to_string())))?;
+            }
+            report.applied.extend(applied);
+            report.conflicts.extend(conflicts);
+        }
+        for fix in report.applied.iter() {
+            println!("{fix}");
+        }
+        for fix in report.conflicts.iter() {
+            println!(
+                "{fix} ({})",
+                switch_lang!(
+                    "japanese" => "競合のためスキップ",
+                    "simplified_chinese" => "因冲突而跳过",
+                    "traditional_chinese" => "因衝突而跳過",
+                    "english" => "skipped due to a conflict",
+                )
+            );
+        }
+        println!(
+            "{}",
+            switch_lang!(
+                "japanese" => format!("{}個のファイルに{}個の修正を適用しました ({}個の競合)", report.files(), report.applied.len(), report.conflicts.len()),
+                "simplified_chinese" => format!("对{}个文件应用了{}个修复 ({}个冲突)", report.files(), report.applied.len(), report.conflicts.len()),
+                "traditional_chinese" => format!("對{}個檔案套用了{}個修正 ({}個衝突)", report.files(), report.applied.len(), report.conflicts.len()),
+                "english" => format!("applied {} fix(es) to {} file(s) ({} conflict(s))", report.applied.len(), report.files(), report.conflicts.len()),
+            )
+        );
+        Ok(ExitStatus::compile_passed(report.conflicts.len()))
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        let fixes = self.collect_fixes(src.clone());
+        let fixes = fixes.into_iter().filter(|fix| fix.path.is_none()).collect();
+        let (fixed, _, _) = apply_fixes(&src, fixes);
+        Ok(fixed)
+    }
+}
+
+impl Fixer {
+    /// Checks `src` and returns the first suggestion of each error and warning.
+    pub fn collect_fixes(&mut self, src: String) -> Vec<Fix> {
+        log!(info "the fixing process has started.");
+        let (errors, warns) = match self.builder.build(src, "exec") {
+            Ok(artifact) => (CompileErrors::empty(), artifact.warns),
+            Err(artifact) => (artifact.errors, artifact.warns),
+        };
+        let mut fixes = vec![];
+        for err in errors.into_iter().chain(warns) {
+            let Some(suggestion) = err.core.suggestions.first() else {
+                continue;
+            };
+            let path = suggestion
+                .path
+                .clone()
+                .or_else(|| err.input.path().map(|path| path.to_path_buf()))
+                .map(normalize_path);
+            let fix = Fix {
+                path,
+                suggestion: suggestion.clone(),
+            };
+            if !fixes.contains(&fix) {
+                fixes.push(fix);
+            }
+        }
+        log!(info "the fixing process has completed: {} fix(es) found", fixes.len());
+        fixes
+    }
+
+    fn file_error(&self, desc: String) -> CompileError {
+        CompileError::file_error(
+            self.cfg.input.clone(),
+            line!() as usize,
+            desc,
+            Location::Unknown,
+            "<fix>".into(),
+            None,
+        )
+    }
+}
+
+fn group_by_path(fixes: Vec<Fix>) -> Vec<(Option<PathBuf>, Vec<Fix>)> {
+    let mut groups: Vec<(Option<PathBuf>, Vec<Fix>)> = vec![];
+    for fix in fixes {
+        if let Some((_, group)) = groups.iter_mut().find(|(path, _)| path == &fix.path) {
+            group.push(fix);
+        } else {
+            groups.push((fix.path.clone(), vec![fix]));
+        }
+    }
+    groups
+}
+
+/// (ln, col) -> byte offset (`ln` is 1-origin, `col` is the 0-origin char index)
+fn offset(code: &str, ln: u32, col: u32) -> Option<usize> {
+    let mut line_start = 0;
+    for _ in 1..ln {
+        line_start += code[line_start..].find('\n')? + 1;
+    }
+    let line = code[line_start..].split('\n').next().unwrap_or("");
+    let col_offset = line
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(line.len()))
+        .nth(col as usize)?;
+    Some(line_start + col_offset)
+}
+
+fn range(code: &str, loc: Location) -> Option<(usize, usize)> {
+    let start = offset(code, loc.ln_begin()?, loc.col_begin()?)?;
+    // the line after the last line (e.g. the removal of the last line)
+    let end = offset(code, loc.ln_end()?, loc.col_end()?).or_else(|| {
+        (loc.col_end() == Some(0) && loc.ln_end()? as usize == code.lines().count() + 1)
+            .then_some(code.len())
+    })?;
+    (start <= end).then_some((start, end))
+}
+
+/// Applies the fixes to `code`. Returns the fixed code, the applied fixes, and the conflicting fixes.
+/// A fix whose range overlaps with an applied fix (or cannot be located in `code`) is not applied.
+pub fn apply_fixes(code: &str, fixes: Vec<Fix>) -> (String, Vec<Fix>, Vec<Fix>) {
+    let mut applied: Vec<((usize, usize), Fix)> = vec![];
+    let mut conflicts = vec![];
+    for fix in fixes {
+        let Some((start, end)) = range(code, fix.suggestion.loc) else {
+            conflicts.push(fix);
+            continue;
+        };
+        // two insertions at the same position also conflict
+        let overlaps = applied.iter().any(|((s, e), _)| {
+            (start < *e && *s < end) || (start == *s && (start == end || *s == *e))
+        });
+        if overlaps {
+            conflicts.push(fix);
+        } else {
+            applied.push(((start, end), fix));
+        }
+    }
+    applied.sort_by_key(|((start, _), _)| *start);
+    let mut fixed = code.to_string();
+    for ((start, end), fix) in applied.iter().rev() {
+        fixed.replace_range(*start..*end, &fix.suggestion.replacement);
+    }
+    let applied = applied.into_iter().map(|(_, fix)| fix).collect();
+    (fixed, applied, conflicts)
+}
@@ -27,7 +27,7 @@ use crate::ty::{Field, HasType, Type, VisibilityModifier};
 
 use crate::context::eval::type_from_token_kind;
 use crate::error::readable_name;
-use crate::varinfo::VarInfo;
+use crate::varinfo::{MethodKind, VarInfo};
 use crate::{impl_t, impl_t_for_enum};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -1418,9 +1418,11 @@ impl Call {
     pub fn additional_operation(&self) -> Option<OperationKind> {
         self.obj.show_acc().and_then(|s| match &s[..] {
             "import" => Some(OperationKind::Import),
-            "pyimport" | "py" | "__import__" => Some(OperationKind::PyImport),
+            "pyimport" | "py" | "__import__" | "cimport" => Some(OperationKind::PyImport),
             "Del" => Some(OperationKind::Del),
             "assert" => Some(OperationKind::Assert),
+            "py_inline" => Some(OperationKind::PyInline),
+            "assert_static" => Some(OperationKind::StaticAssert),
             _ => {
                 if self.obj.qual_name() == Some("typing".into())
                     && self
@@ -1450,6 +1452,47 @@ impl Call {
             self.obj.ref_t().return_t()
         }
     }
+
+    /// The types of the parameters to which the positional and the keyword arguments are passed
+    /// (`None` if the parameter is not found)
+    pub fn arg_param_types(&self) -> (Vec<Option<&Type>>, Vec<Option<&Type>>) {
+        let Some(sig_t) = self.signature_t() else {
+            return (
+                vec![None; self.args.pos_args.len()],
+                vec![None; self.args.kw_args.len()],
+            );
+        };
+        let non_defaults = sig_t.non_default_params().map_or(&[][..], |ps| &ps[..]);
+        let defaults = sig_t.default_params().map_or(&[][..], |ps| &ps[..]);
+        let is_method = self.attr_name.is_some() && sig_t.self_t().is_some();
+        let mut pos_params = non_defaults
+            .iter()
+            .skip(usize::from(is_method))
+            .collect::<Vec<_>>();
+        // the rest of the positional arguments are passed to the variable-length parameter (if any)
+        if let Some(var_params) = sig_t.var_params() {
+            pos_params.extend(std::iter::repeat_n(var_params, self.args.pos_args.len()));
+        } else {
+            pos_params.extend(defaults.iter());
+        }
+        let pos = (0..self.args.pos_args.len())
+            .map(|i| pos_params.get(i).map(|pt| pt.typ()))
+            .collect();
+        let kw = self
+            .args
+            .kw_args
+            .iter()
+            .map(|arg| {
+                let kw = arg.keyword.inspect();
+                non_defaults
+                    .iter()
+                    .chain(defaults.iter())
+                    .find(|pt| pt.name() == Some(kw))
+                    .map(|pt| pt.typ())
+            })
+            .collect();
+        (pos, kw)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -2122,29 +2165,44 @@ impl Def {
 
     pub fn def_kind(&self) -> DefKind {
         match self.body.block.first().unwrap() {
-            Expr::Call(call) => match call.obj.show_acc().as_ref().map(|n| &n[..]) {
-                Some("Class") => DefKind::Class,
-                Some("Inherit") => DefKind::Inherit,
-                Some("Trait") => DefKind::Trait,
-                Some("Subsume") => DefKind::Subsume,
-                Some("Inheritable") => {
-                    if let Some(Expr::Call(inner)) = call.args.get_left_or_key("Class") {
-                        match inner.obj.show_acc().as_ref().map(|n| &n[..]) {
-                            Some("Class") => DefKind::Class,
-                            Some("Inherit") => DefKind::Inherit,
-                            _ => DefKind::Other,
-                        }
-                    } else {
-                        DefKind::Other
+            Expr::Call(call) => Self::call_def_kind(call),
+            _ => DefKind::Other,
+        }
+    }
+
+    fn call_def_kind(call: &Call) -> DefKind {
+        match call.obj.show_acc().as_ref().map(|n| &n[..]) {
+            Some("Class") => DefKind::Class,
+            Some("Inherit") => DefKind::Inherit,
+            Some("Trait") => DefKind::Trait,
+            Some("Subsume") => DefKind::Subsume,
+            // decorators: `Inheritable(Derive(Class(...), [Eq]))`
+            Some("Inheritable" | "Abstract" | "Derive") => {
+                if let Some(Expr::Call(inner)) = call.args.get_left_or_key("Class") {
+                    match Self::call_def_kind(inner) {
+                        kind @ (DefKind::Class | DefKind::Inherit) => kind,
+                        _ => DefKind::Other,
                     }
+                } else {
+                    DefKind::Other
                 }
-                Some("Patch") => DefKind::Patch,
-                Some("import") => DefKind::ErgImport,
-                Some("pyimport") | Some("__import__") => DefKind::PyImport,
-                #[cfg(feature = "debug")]
-                Some("py") => DefKind::PyImport,
-                _ => DefKind::Other,
-            },
+            }
+            // `Derivable(Trait(...), "module")`
+            Some("Derivable") => {
+                if let Some(Expr::Call(inner)) = call.args.get_left_or_key("Trait") {
+                    match Self::call_def_kind(inner) {
+                        DefKind::Trait => DefKind::Trait,
+                        _ => DefKind::Other,
+                    }
+                } else {
+                    DefKind::Other
+                }
+            }
+            Some("Patch") => DefKind::Patch,
+            Some("import") => DefKind::ErgImport,
+            Some("pyimport") | Some("__import__") | Some("cimport") => DefKind::PyImport,
+            #[cfg(feature = "debug")]
+            Some("py") => DefKind::PyImport,
             _ => DefKind::Other,
         }
     }
@@ -2270,6 +2328,45 @@ impl ClassDef {
             methods,
         }
     }
+
+    /// The methods that do not take `self` (static methods and class methods).
+    pub fn non_instance_methods(&self) -> Vec<(MethodKind, Identifier)> {
+        self.methods
+            .iter()
+            .filter_map(|method| match method {
+                Expr::Def(Def {
+                    sig: Signature::Subr(sig),
+                    ..
+                }) => {
+                    let kind = sig.ident.vi.method_kind();
+                    (!kind.is_instance()).then(|| (kind, sig.ident.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The getters of `@Property` methods and their setters (`x!`, if defined).
+    pub fn properties(&self) -> Vec<(Identifier, Option<Identifier>)> {
+        let props = self
+            .methods
+            .iter()
+            .filter_map(|method| match method {
+                Expr::Def(def) if def.sig.ident().vi.is_property() => Some(def.sig.ident()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        props
+            .iter()
+            .filter(|getter| !getter.inspect().ends_with('!'))
+            .map(|getter| {
+                let setter = props
+                    .iter()
+                    .find(|setter| setter.inspect().strip_suffix('!') == Some(getter.inspect()));
+                ((*getter).clone(), setter.map(|setter| (*setter).clone()))
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -5,27 +5,47 @@ extern crate erg_common;
 pub extern crate erg_parser;
 
 pub mod artifact;
+pub mod backend;
 pub mod build_hir;
 mod compile;
 pub use compile::*;
 mod codegen;
 pub mod context;
+pub mod decl_gen;
+pub mod decl_test;
 pub mod declare;
+pub mod derive;
 pub mod desugar_hir;
+pub mod dump;
 pub mod effectcheck;
 pub mod error;
+pub mod fix;
 pub mod hir;
 pub mod link_ast;
 pub mod link_hir;
 pub mod lint;
 pub mod lower;
+pub mod manifest;
+pub mod migrate;
 pub mod module;
+pub mod module_graph;
+pub mod nil_audit;
 pub mod optimize;
 pub mod ownercheck;
+pub mod printer;
+pub mod pyi_conv;
+pub mod pyi_gen;
 pub mod transpile;
 pub mod ty;
 pub mod varinfo;
+pub mod visitor;
 
 pub use build_hir::HIRBuilder;
+pub use decl_gen::DeclGenerator;
+pub use decl_test::DeclTester;
 pub use erg_parser::build_ast::ASTBuilder;
+pub use fix::Fixer;
+pub use migrate::Migrator;
+pub use nil_audit::NilAuditor;
+pub use pyi_gen::PyiGenerator;
 pub use transpile::Transpiler;
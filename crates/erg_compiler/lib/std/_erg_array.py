@@ -22,5 +22,22 @@ class Array(list):
             list(filter(lambda x: not f(x), self))
         )
 
+    def map(self, f):
+        return Array(map(f, self))
+
+    def filter(self, f):
+        return Array(filter(f, self))
+
+    def zip(self, other):
+        return Array(zip(self, other))
+
+    def __add__(self, other):
+        return Array(list.__add__(self, other))
+
+    def __getitem__(self, index):
+        if isinstance(index, slice):
+            return Array(list.__getitem__(self, index))
+        return list.__getitem__(self, index)
+
     def __mul__(self, n):
         return then__(list.__mul__(self, n), Array)
@@ -30,3 +30,9 @@ def str__(s):
         return Str(s)
     except:
         return None
+
+
+def dict_as_record__(d):
+    from collections import namedtuple
+
+    return namedtuple("Record", d.keys())(**d)
@@ -0,0 +1,41 @@
+# Runtime implementations of the builtin derives (`@Derive [Eq, Ord, Show, Hash]`).
+# These functions are imported into the namespace of the derived class.
+def _fields(self):
+    return tuple(self.__dict__.values())
+
+
+def __eq__(self, other):
+    return type(self) is type(other) and _fields(self) == _fields(other)
+
+
+def __cmp__(self, other):
+    lhs, rhs = _fields(self), _fields(other)
+    return (lhs > rhs) - (lhs < rhs)
+
+
+def __lt__(self, other):
+    return _fields(self) < _fields(other)
+
+
+def __le__(self, other):
+    return _fields(self) <= _fields(other)
+
+
+def __gt__(self, other):
+    return _fields(self) > _fields(other)
+
+
+def __ge__(self, other):
+    return _fields(self) >= _fields(other)
+
+
+def __str__(self):
+    fields = "; ".join(f"{k} = {v}" for k, v in self.__dict__.items())
+    return f"{type(self).__name__} {{{fields}}}"
+
+
+def __hash__(self):
+    return hash((type(self).__name__,) + _fields(self))
+
+
+__repr__ = __str__
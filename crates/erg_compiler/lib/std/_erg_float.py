@@ -6,10 +6,10 @@ class Float(float):
     EPSILON = 2.220446049250313e-16
 
     def try_new(i):  # -> Result[Nat]
-        if isinstance(i, float):
-            Float(i)
+        if isinstance(i, (int, float)):
+            return Float(i)
         else:
-            Error("not a float")
+            return Error("not a float")
 
     def mutate(self):
         return FloatMut(self)
@@ -0,0 +1,163 @@
+# Runtime value inspection protocol (used by the REPL server and debuggers).
+# Classes generated by the Erg compiler have the following attributes:
+#   __erg_type__: the Erg type name of the class
+#   __erg_fields__: the names of the instance attributes (private ones are prefixed with `::`)
+# Records are namedtuples.
+import json
+
+MAX_ITEMS = 100
+
+
+def _is_record(obj):
+    return isinstance(obj, tuple) and hasattr(obj, "_fields")
+
+
+def _is_erg_class(obj):
+    return hasattr(type(obj), "__erg_fields__")
+
+
+def _field_name(py_name):
+    if py_name.startswith("::"):
+        return py_name[2:]
+    else:
+        return "." + py_name
+
+
+def erg_type(obj):
+    if obj is None:
+        return "NoneType"
+    if _is_erg_class(obj):
+        return type(obj).__erg_type__
+    if _is_record(obj):
+        fields = "; ".join(f"{k} = {erg_type(v)}" for k, v in zip(obj._fields, obj))
+        return "{" + fields + "}"
+    # builtin classes defined in `_erg_*.py` (e.g. `IntMut` => `Int!`)
+    if type(obj).__module__.startswith("_erg_"):
+        name = type(obj).__name__
+        return name[:-3] + "!" if name.endswith("Mut") else name
+    # bool is a subclass of int
+    if isinstance(obj, bool):
+        return "Bool"
+    if isinstance(obj, int):
+        return "Nat" if obj >= 0 else "Int"
+    if isinstance(obj, float):
+        return "Float"
+    if isinstance(obj, str):
+        return "Str"
+    if isinstance(obj, bytes):
+        return "Bytes"
+    if isinstance(obj, list):
+        return "Array"
+    if isinstance(obj, tuple):
+        return "Tuple"
+    if isinstance(obj, dict):
+        return "Dict"
+    if isinstance(obj, (set, frozenset)):
+        return "Set"
+    return type(obj).__qualname__
+
+
+def erg_fields(obj):
+    if _is_erg_class(obj):
+        return [
+            (_field_name(name), getattr(obj, name))
+            for name in type(obj).__erg_fields__
+            if hasattr(obj, name)
+        ]
+    if _is_record(obj):
+        return list(zip(obj._fields, obj))
+    return []
+
+
+def _items(it, seen):
+    it = list(it)
+    items = [pretty(x, seen) for x in it[:MAX_ITEMS]]
+    if len(it) > MAX_ITEMS:
+        items.append("...")
+    return ", ".join(items)
+
+
+def pretty(obj, seen=None):
+    seen = set() if seen is None else seen
+    if id(obj) in seen:
+        return "..."
+    if obj is None or isinstance(obj, (bool, int, float)):
+        return str(obj)
+    if isinstance(obj, str):
+        return json.dumps(obj, ensure_ascii=False)
+    seen = seen | {id(obj)}
+    if _is_erg_class(obj):
+        cls = type(obj)
+        # `Show` is implemented (by the user or `Derive`)
+        if any(
+            "__repr__" in c.__dict__ for c in cls.__mro__ if "__erg_type__" in c.__dict__
+        ):
+            return repr(obj)
+        name = cls.__erg_type__
+        # e.g. `IntArr = Inherit Array(Int, 3)`
+        if isinstance(obj, (list, dict, set)):
+            return f"{name} {_pretty_builtin(obj, seen)}"
+        fields = erg_fields(obj)
+        # variant of an `Enum`
+        if hasattr(cls, "__discriminant__"):
+            if not fields:
+                return name
+            return f"{name}({_items((v for _, v in fields), seen)})"
+        if not fields:
+            return f"{name}()"
+        fields = "; ".join(f"{k} = {pretty(v, seen)}" for k, v in fields)
+        return f"{name} {{{fields}}}"
+    if _is_record(obj):
+        if not obj._fields:
+            return "{=}"
+        fields = "; ".join(f"{k} = {pretty(v, seen)}" for k, v in zip(obj._fields, obj))
+        return "{" + fields + "}"
+    return _pretty_builtin(obj, seen)
+
+
+def _pretty_builtin(obj, seen):
+    if isinstance(obj, list):
+        return f"[{_items(obj, seen)}]"
+    if isinstance(obj, tuple):
+        if len(obj) == 1:
+            return f"({pretty(obj[0], seen)},)"
+        return f"({_items(obj, seen)})"
+    if isinstance(obj, dict):
+        if not obj:
+            return "{:}"
+        items = [
+            f"{pretty(k, seen)}: {pretty(v, seen)}"
+            for k, v in list(obj.items())[:MAX_ITEMS]
+        ]
+        if len(obj) > MAX_ITEMS:
+            items.append("...")
+        return "{" + ", ".join(items) + "}"
+    if isinstance(obj, (set, frozenset)):
+        return "{" + _items(obj, seen) + "}"
+    return repr(obj)
+
+
+def inspect(obj):
+    """Returns the inspection result of `obj` as a JSON string."""
+    return json.dumps(
+        {
+            "type": erg_type(obj),
+            "pretty": pretty(obj),
+            "fields": [
+                {"name": k, "type": erg_type(v), "pretty": pretty(v)}
+                for k, v in erg_fields(obj)
+            ],
+        },
+        ensure_ascii=False,
+    )
+
+
+def displayhook(obj):
+    """`sys.displayhook` for the REPL"""
+    if obj is None:
+        return
+    import builtins
+
+    builtins._ = None
+    print(pretty(obj))
+    builtins._ = obj
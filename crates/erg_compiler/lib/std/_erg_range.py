@@ -39,6 +39,12 @@ class Range:
     def __iter__(self):
         return RangeIterator(rng=self)
 
+    # `(1, 2, 3)[1..2]` => `(1, 2, 3)[slice(1, 3)]`
+    def as_slice__(self):
+        start = self.start if self.start in self else self.start + 1
+        stop = self.end + 1 if self.end in self else self.end
+        return slice(start, stop)
+
 
 # Sequence.register(Range)
 # Container.register(Range)
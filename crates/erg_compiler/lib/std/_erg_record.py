@@ -0,0 +1,47 @@
+# Runtime counterparts of the compile-time record type functions (`Omit`, `Pick`, `Partial`)
+# and the record field accessor `Lens` (`R::.field`).
+# A record type is represented as a namedtuple whose values are the field types.
+from collections import namedtuple
+from typing import Optional
+
+
+def _record(fields):
+    return namedtuple("Record", fields.keys())(*fields.values())
+
+
+def _is_record(r):
+    return isinstance(r, tuple) and hasattr(r, "_asdict")
+
+
+def Omit(r, keys):
+    return _record({k: v for k, v in r._asdict().items() if k not in keys})
+
+
+def Pick(r, keys):
+    return _record({k: v for k, v in r._asdict().items() if k in keys})
+
+
+def Partial(f, args=None):
+    if args is None and _is_record(f):
+        return _record({k: Optional[v] for k, v in f._asdict().items()})
+    # subroutine types are erased at runtime
+    return f
+
+
+class Lens:
+    def __init__(self, record, field):
+        self.record = record
+        self.field = str(field)
+
+    def __repr__(self):
+        return f"Lens(.{self.field})"
+
+    def get(self, record):
+        return getattr(record, self.field)
+
+    def set(self, record, value):
+        return record._replace(**{self.field: value})
+
+
+def lens(record, field):
+    return Lens(record, field)
@@ -18,7 +18,19 @@ from _erg_dict import Dict
 from _erg_set import Set
 from _erg_in_operator import in_operator
 from _erg_mutate_operator import mutate_operator
+from _erg_unit import unit_mul, unit_div, Quantity
+from _erg_record import Omit, Pick, Partial, Lens, lens
+from functools import partial
+from sys import argv as _argv
 
 
 class Never:
     pass
+
+
+argv = Array([Str(arg) for arg in _argv])
+
+
+def assert_type(obj, typ, desc):
+    assert in_operator(obj, typ), f"{desc}, but found {type(obj).__name__}"
+    return obj
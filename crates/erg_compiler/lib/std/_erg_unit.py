@@ -0,0 +1,105 @@
+# Runtime counterparts of the compile-time unit functions (`unit_mul`, `unit_div`).
+# Units are written as `kg*m/s^2`, and `1` means dimensionless.
+
+
+def _parse_factor(unit, factor):
+    base, caret, exp = factor.partition("^")
+    base = base.strip()
+    exp = exp.strip()
+    if caret and not _is_int(exp):
+        raise ValueError(f"{unit!r} is not a valid unit")
+    if not base or not all(c.isalnum() or c == "_" for c in base):
+        raise ValueError(f"{unit!r} is not a valid unit")
+    return base, int(exp) if caret else 1
+
+
+def _is_int(s):
+    digits = s[1:] if s[:1] in "+-" else s
+    return digits.isascii() and digits.isdigit()
+
+
+def _parse_unit(unit):
+    dims = {}
+    sign = 1
+    factor = ""
+    for c in unit + "*":
+        if c in "*/":
+            factor = factor.strip()
+            if factor and factor != "1":
+                base, exp = _parse_factor(unit, factor)
+                dims[base] = dims.get(base, 0) + sign * exp
+            factor = ""
+            sign = -1 if c == "/" else 1
+        else:
+            factor += c
+    return {base: exp for base, exp in dims.items() if exp != 0}
+
+
+def _fmt_unit(dims):
+    def fmt_factor(base, exp):
+        return base if exp == 1 else f"{base}^{exp}"
+
+    bases = sorted(dims.keys())
+    num = [fmt_factor(base, dims[base]) for base in bases if dims[base] > 0]
+    den = [fmt_factor(base, -dims[base]) for base in bases if dims[base] < 0]
+    unit = "*".join(num) if num else "1"
+    for factor in den:
+        unit += "/" + factor
+    return unit
+
+
+def unit_mul(lhs, rhs):
+    dims = _parse_unit(lhs)
+    for base, exp in _parse_unit(rhs).items():
+        dims[base] = dims.get(base, 0) + exp
+    return _fmt_unit({base: exp for base, exp in dims.items() if exp != 0})
+
+
+def unit_div(lhs, rhs):
+    dims = _parse_unit(lhs)
+    for base, exp in _parse_unit(rhs).items():
+        dims[base] = dims.get(base, 0) - exp
+    return _fmt_unit({base: exp for base, exp in dims.items() if exp != 0})
+
+
+class Quantity:
+    def __init__(self, value, unit):
+        self._value = value
+        self._unit = _fmt_unit(_parse_unit(unit))
+
+    def value(self):
+        return self._value
+
+    def unit(self):
+        return self._unit
+
+    def _check_unit(self, other):
+        if self._unit != other._unit:
+            raise ValueError(f"unit mismatch: {self._unit} and {other._unit}")
+
+    def __add__(self, other):
+        self._check_unit(other)
+        return Quantity(self._value + other._value, self._unit)
+
+    def __sub__(self, other):
+        self._check_unit(other)
+        return Quantity(self._value - other._value, self._unit)
+
+    def __mul__(self, other):
+        return Quantity(self._value * other._value, unit_mul(self._unit, other._unit))
+
+    def __truediv__(self, other):
+        return Quantity(self._value / other._value, unit_div(self._unit, other._unit))
+
+    def __eq__(self, other):
+        return (
+            isinstance(other, Quantity)
+            and self._value == other._value
+            and self._unit == other._unit
+        )
+
+    def __hash__(self):
+        return hash((self._value, self._unit))
+
+    def __repr__(self):
+        return f"{self._value} {self._unit}"
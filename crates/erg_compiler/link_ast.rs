@@ -1,13 +1,19 @@
 use erg_common::config::ErgConfig;
 use erg_common::dict::Dict;
+use erg_common::error::Location;
 use erg_common::log;
+use erg_common::set::Set;
 use erg_common::traits::{Locational, Stream};
 use erg_common::Str;
 
 use erg_parser::ast::{
-    Accessor, ClassAttr, ClassDef, Expr, Identifier, Methods, Module, PatchDef, PreDeclTypeSpec,
-    TypeAscription, TypeSpec, AST,
+    Accessor, Array, ClassAttr, ClassDef, Def, Expr, Identifier, Methods, Module, Params, PatchDef,
+    PreDeclTypeSpec, Record, RecordAttrOrIdent, Set as SetExpr, Signature, TypeAscription,
+    TypeSpec, AST,
 };
+use erg_parser::error::{ParseError, ParserRunnerError};
+use erg_parser::token::TokenKind;
+use erg_parser::visitor::{walk_args, walk_block, walk_expr, Visit};
 
 use crate::error::{TyCheckError, TyCheckErrors};
 
@@ -31,8 +37,10 @@ impl ASTLinker {
         }
     }
 
-    pub fn link(mut self, ast: AST, mode: &str) -> Result<AST, TyCheckErrors> {
+    pub fn link(mut self, mut ast: AST, mode: &str) -> Result<AST, TyCheckErrors> {
         log!(info "the AST-linking process has started.");
+        self.check_features(&ast.module);
+        let trivia = ast.module.take_trivia();
         let mut new = vec![];
         for chunk in ast.module.into_iter() {
             match chunk {
@@ -41,7 +49,9 @@ impl ASTLinker {
                         Expr::Call(call) => {
                             match call.obj.get_name().map(|s| &s[..]) {
                                 // TODO: decorator
-                                Some("Class" | "Inherit" | "Inheritable") => {
+                                Some(
+                                    "Class" | "Inherit" | "Inheritable" | "Abstract" | "Derive",
+                                ) => {
                                     self.def_root_pos_map.insert(
                                         def.sig.ident().unwrap().inspect().clone(),
                                         new.len(),
@@ -107,7 +117,14 @@ impl ASTLinker {
                 }
             }
         }
-        let ast = AST::new(ast.name, Module::new(new));
+        let new = if mode == "declare" {
+            new
+        } else {
+            self.hoist_definitions(new)
+        };
+        let mut module = Module::new(new);
+        module.set_trivia(trivia);
+        let ast = AST::new(ast.name, module);
         log!(info "the AST-linking process has completed:\n{}", ast);
         if self.errs.is_empty() {
             Ok(ast)
@@ -116,6 +133,28 @@ impl ASTLinker {
         }
     }
 
+    /// Reports the features used in `module` that the language options of the module (`cfg.lang`) disable.
+    /// The parser also reports them, but `module` may have been parsed with other options.
+    fn check_features(&mut self, module: &Module) {
+        let feature = "conditional_binding";
+        if self.cfg.lang.is_enabled(feature) {
+            return;
+        }
+        // conditional bindings that have not been desugared
+        let mut bindings = BindingCollector(vec![]);
+        bindings.visit_module(module);
+        for loc in bindings.0 {
+            let err = ParseError::unavailable_feature_error(
+                line!() as usize,
+                loc,
+                feature,
+                self.cfg.lang.edition,
+            );
+            let err = ParserRunnerError::new(err.into(), self.cfg.input.clone());
+            self.errs.push(TyCheckError::from(err));
+        }
+    }
+
     /// ```erg
     /// C.
     ///     x: Int
@@ -195,3 +234,479 @@ impl ASTLinker {
         }
     }
 }
+
+/// A reference to a top-level definition.
+#[derive(Debug, Clone, Copy)]
+struct DefRef {
+    /// index of the chunk that defines the name
+    target: usize,
+    /// `false` if the reference is in a lambda (it may never be evaluated)
+    certain: bool,
+    /// the name is called directly (`f(...)`)
+    called: bool,
+}
+
+/// Collects the locations of conditional bindings (`pattern := expr`).
+struct BindingCollector(Vec<Location>);
+
+impl Visit for BindingCollector {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::Lambda(lambda) = expr {
+            if lambda.op.is(TokenKind::Walrus) {
+                self.0.push(lambda.op.loc());
+            }
+        }
+        walk_expr(self, expr);
+    }
+}
+
+/// Collects references to top-level definitions.
+/// Names shadowed by parameters or local variables are skipped.
+struct RefCollector<'a> {
+    defs: &'a Dict<Str, usize>,
+    scopes: Vec<Set<Str>>,
+    lambda_depth: usize,
+    refs: Vec<DefRef>,
+}
+
+impl<'a> RefCollector<'a> {
+    fn new(defs: &'a Dict<Str, usize>) -> Self {
+        Self {
+            defs,
+            scopes: vec![],
+            lambda_depth: 0,
+            refs: vec![],
+        }
+    }
+
+    fn param_names(params: &Params) -> Set<Str> {
+        params
+            .non_defaults
+            .iter()
+            .chain(params.var_params.as_deref())
+            .chain(params.defaults.iter().map(|param| &param.sig))
+            .filter_map(|param| param.inspect().cloned())
+            .collect()
+    }
+
+    fn push_ref(&mut self, ident: &Identifier, called: bool) {
+        let name = ident.inspect();
+        if self.scopes.iter().any(|scope| scope.contains(name)) {
+            return;
+        }
+        if let Some(&target) = self.defs.get(name) {
+            self.refs.push(DefRef {
+                target,
+                certain: self.lambda_depth == 0,
+                called,
+            });
+        }
+    }
+
+    /// Collects the references evaluated when the subroutine is called.
+    fn collect_subr_body(&mut self, params: &Params, body: &[Expr]) {
+        self.scopes.push(Self::param_names(params));
+        for chunk in body.iter() {
+            self.visit_expr(chunk);
+        }
+        self.scopes.pop();
+    }
+
+    fn visit_subr(&mut self, params: &Params, body: &[Expr]) {
+        for default in params.defaults.iter() {
+            self.visit_expr(&default.default_val);
+        }
+        self.lambda_depth += 1;
+        self.collect_subr_body(params, body);
+        self.lambda_depth -= 1;
+    }
+
+    fn visit_def_body(&mut self, def: &Def) {
+        match &def.sig {
+            Signature::Subr(subr) => {
+                for deco in subr.decorators.iter() {
+                    self.visit_expr(deco.expr());
+                }
+                self.visit_subr(&subr.params, def.body.block.ref_payload());
+            }
+            Signature::Var(_) => walk_block(self, &def.body.block),
+        }
+    }
+
+    fn visit_methods(&mut self, methods: &Methods) {
+        for attr in methods.attrs.iter() {
+            if let ClassAttr::Def(def) = attr {
+                self.visit_def_body(def);
+            }
+        }
+    }
+}
+
+impl Visit for RefCollector<'_> {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Accessor(Accessor::Ident(ident)) => self.push_ref(ident, false),
+            Expr::Call(call) if call.attr_name.is_none() => {
+                if let Expr::Accessor(Accessor::Ident(ident)) = call.obj.as_ref() {
+                    self.push_ref(ident, true);
+                } else {
+                    self.visit_expr(&call.obj);
+                }
+                walk_args(self, &call.args);
+            }
+            Expr::Lambda(lambda) => self.visit_subr(&lambda.sig.params, lambda.body.ref_payload()),
+            // record attributes are not variables
+            Expr::Record(Record::Normal(rec)) => {
+                for attr in rec.attrs.iter() {
+                    self.visit_def_body(attr);
+                }
+            }
+            Expr::Record(Record::Mixed(mixed)) => {
+                for attr in mixed.attrs.iter() {
+                    match attr {
+                        RecordAttrOrIdent::Attr(attr) => self.visit_def_body(attr),
+                        RecordAttrOrIdent::Ident(ident) => self.push_ref(ident, false),
+                    }
+                }
+            }
+            Expr::Array(Array::Comprehension(arr)) => {
+                for (_, gen) in arr.generators.iter() {
+                    self.visit_expr(gen);
+                }
+                let vars = arr
+                    .generators
+                    .iter()
+                    .map(|(ident, _)| ident.inspect().clone())
+                    .collect();
+                self.scopes.push(vars);
+                self.visit_expr(&arr.elem);
+                for guard in arr.guards.iter() {
+                    self.visit_expr(guard);
+                }
+                self.scopes.pop();
+            }
+            Expr::Set(SetExpr::Comprehension(set)) => {
+                self.visit_expr(&set.iter);
+                self.scopes.push(Set::from([set.var.content.clone()]));
+                self.visit_expr(&set.pred);
+                self.scopes.pop();
+            }
+            Expr::Methods(methods) => self.visit_methods(methods),
+            Expr::ClassDef(class_def) => {
+                self.visit_def(&class_def.def);
+                for methods in class_def.methods_list.iter() {
+                    self.visit_methods(methods);
+                }
+            }
+            Expr::PatchDef(patch_def) => {
+                self.visit_def(&patch_def.def);
+                for methods in patch_def.methods_list.iter() {
+                    self.visit_methods(methods);
+                }
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    /// Local definitions shadow the top-level ones.
+    fn visit_def(&mut self, def: &Def) {
+        if let (Some(ident), Some(scope)) = (def.sig.ident(), self.scopes.last_mut()) {
+            scope.insert(ident.inspect().clone());
+        }
+        self.visit_def_body(def);
+    }
+}
+
+/// Dependencies of a top-level chunk.
+#[derive(Debug, Default)]
+struct ChunkDeps {
+    /// the name defined by the chunk (only if defined once in the module)
+    name: Option<Str>,
+    loc: Location,
+    /// subroutine and class definitions can be moved
+    hoistable: bool,
+    is_subr: bool,
+    /// references evaluated when the chunk is executed
+    eager: Vec<DefRef>,
+    /// references evaluated when the subroutine (or a method of the class) is called
+    deferred: Vec<DefRef>,
+}
+
+impl ChunkDeps {
+    fn new(chunk: &Expr, defs: &Dict<Str, usize>) -> Self {
+        let mut deps = Self {
+            loc: chunk.loc(),
+            ..Default::default()
+        };
+        let mut eager = RefCollector::new(defs);
+        let mut deferred = RefCollector::new(defs);
+        match chunk {
+            Expr::Def(def) => {
+                if let Some(ident) = def.sig.ident() {
+                    deps.name =
+                        Some(ident.inspect().clone()).filter(|name| defs.contains_key(name));
+                    deps.loc = ident.loc();
+                }
+                match (&def.sig, def.body.block.first()) {
+                    (Signature::Subr(subr), _) => {
+                        deps.hoistable = subr.decorators.is_empty();
+                        deps.is_subr = true;
+                        for deco in subr.decorators.iter() {
+                            eager.visit_expr(deco.expr());
+                        }
+                        for default in subr.params.defaults.iter() {
+                            eager.visit_expr(&default.default_val);
+                        }
+                        deferred.collect_subr_body(&subr.params, def.body.block.ref_payload());
+                    }
+                    (Signature::Var(_), Some(Expr::Lambda(lambda)))
+                        if def.body.block.len() == 1 =>
+                    {
+                        deps.hoistable = true;
+                        deps.is_subr = true;
+                        for default in lambda.sig.params.defaults.iter() {
+                            eager.visit_expr(&default.default_val);
+                        }
+                        deferred.collect_subr_body(&lambda.sig.params, lambda.body.ref_payload());
+                    }
+                    (Signature::Var(_), _) => walk_block(&mut eager, &def.body.block),
+                }
+            }
+            Expr::ClassDef(ClassDef { def, methods_list })
+            | Expr::PatchDef(PatchDef { def, methods_list }) => {
+                if let Some(ident) = def.sig.ident() {
+                    deps.name =
+                        Some(ident.inspect().clone()).filter(|name| defs.contains_key(name));
+                    deps.loc = ident.loc();
+                }
+                deps.hoistable = matches!(chunk, Expr::ClassDef(_));
+                eager.visit_def_body(def);
+                for methods in methods_list.iter() {
+                    deferred.visit_methods(methods);
+                }
+            }
+            other => eager.visit_expr(other),
+        }
+        deps.eager = eager.refs;
+        deps.deferred = deferred.refs;
+        deps
+    }
+
+    /// Is `chunk` a doc comment or a declaration of `name`?
+    fn is_attached_to(chunk: &Expr, name: &str) -> bool {
+        match chunk {
+            Expr::Literal(lit) => lit.is_doc_comment(),
+            Expr::TypeAscription(tasc) => {
+                matches!(
+                    tasc.expr.as_ref(),
+                    Expr::Accessor(Accessor::Ident(ident)) if &ident.inspect()[..] == name
+                )
+            }
+            _ => false,
+        }
+    }
+}
+
+/// How a chunk is reached from the references of another chunk.
+#[derive(Debug, Clone, Copy, Default)]
+struct Reach {
+    /// must be defined beforehand
+    needed: bool,
+    /// its value is surely read
+    certain: bool,
+    /// it is surely called
+    invoked: bool,
+    parent: Option<usize>,
+}
+
+/// Reorders top-level chunks so that definitions are executed before they are used.
+///
+/// Subroutine and class definitions are hoisted just before the first chunk that needs them
+/// (directly, or through the subroutines/methods it may call).
+/// Other chunks keep their order.
+struct Hoister {
+    chunks: Vec<ChunkDeps>,
+    /// index of the first doc comment/declaration attached to each chunk
+    attached_from: Vec<usize>,
+    placed: Vec<bool>,
+    in_progress: Vec<bool>,
+    cyclic: Set<usize>,
+    order: Vec<usize>,
+    /// (the chunk, the names on the cycle)
+    cycles: Vec<(usize, Vec<Str>)>,
+}
+
+impl Hoister {
+    fn new(module: &[Expr]) -> Self {
+        let mut counts = Dict::<Str, usize>::new();
+        let mut defs = Dict::new();
+        for (i, chunk) in module.iter().enumerate() {
+            let ident = match chunk {
+                Expr::Def(def) => def.sig.ident(),
+                Expr::ClassDef(class_def) => class_def.def.sig.ident(),
+                Expr::PatchDef(patch_def) => patch_def.def.sig.ident(),
+                _ => None,
+            };
+            if let Some(ident) = ident {
+                *counts.entry(ident.inspect().clone()).or_insert(0) += 1;
+                defs.insert(ident.inspect().clone(), i);
+            }
+        }
+        defs.retain(|name, _| counts.get(name) == Some(&1));
+        // self-references (e.g. recursive type aliases) are resolved by the type checker
+        let chunks = module
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut deps = ChunkDeps::new(chunk, &defs);
+                deps.eager.retain(|r| r.target != i);
+                deps
+            })
+            .collect::<Vec<_>>();
+        let attached_from = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, deps)| {
+                let Some(name) = deps.name.as_ref().filter(|_| deps.hoistable) else {
+                    return i;
+                };
+                let mut from = i;
+                while from > 0 && ChunkDeps::is_attached_to(&module[from - 1], name) {
+                    from -= 1;
+                }
+                from
+            })
+            .collect();
+        let n = module.len();
+        Self {
+            chunks,
+            attached_from,
+            placed: vec![false; n],
+            in_progress: vec![false; n],
+            cyclic: Set::new(),
+            order: Vec::with_capacity(n),
+            cycles: vec![],
+        }
+    }
+
+    fn reach(&self, refs: &[DefRef]) -> Vec<Reach> {
+        let mut reach = vec![Reach::default(); self.chunks.len()];
+        let mut stack = refs
+            .iter()
+            .map(|r| (r.target, r.certain, r.certain && r.called, None))
+            .collect::<Vec<_>>();
+        while let Some((t, certain, invoked, parent)) = stack.pop() {
+            let state = &mut reach[t];
+            let upgraded = !state.needed || (certain && !state.certain);
+            if !upgraded && (!invoked || state.invoked) {
+                continue;
+            }
+            if upgraded {
+                state.parent = parent;
+            }
+            state.needed = true;
+            state.certain |= certain;
+            state.invoked |= invoked;
+            let (certain, invoked) = (state.certain, state.invoked);
+            let chunk = &self.chunks[t];
+            if !self.placed[t] {
+                for r in chunk.eager.iter() {
+                    let certain = certain && r.certain;
+                    stack.push((r.target, certain, certain && r.called, Some(t)));
+                }
+            }
+            for r in chunk.deferred.iter() {
+                let certain = invoked && chunk.is_subr && r.certain;
+                stack.push((r.target, certain, certain && r.called, Some(t)));
+            }
+        }
+        reach
+    }
+
+    fn cycle_path(&self, i: usize, reach: &[Reach]) -> Vec<Str> {
+        let name = |i: usize| self.chunks[i].name.clone().unwrap_or_else(|| "".into());
+        let mut path = vec![name(i)];
+        let mut cur = i;
+        while let Some(parent) = reach[cur].parent {
+            if path.len() > reach.len() {
+                break;
+            }
+            path.push(name(parent));
+            cur = parent;
+        }
+        path.push(name(i));
+        path.reverse();
+        path
+    }
+
+    fn place(&mut self, i: usize) {
+        if self.placed[i] || self.in_progress[i] {
+            return;
+        }
+        self.in_progress[i] = true;
+        let reach = self.reach(&self.chunks[i].eager);
+        if self.chunks[i].name.is_some() && reach[i].certain && !self.cyclic.contains(&i) {
+            let path = self.cycle_path(i, &reach);
+            let mut cur = Some(i);
+            while let Some(c) = cur.filter(|c| self.cyclic.insert(*c)) {
+                cur = reach[c].parent;
+            }
+            self.cycles.push((i, path));
+        }
+        for (j, r) in reach.iter().enumerate() {
+            if r.needed && j != i && self.chunks[j].hoistable {
+                self.place(j);
+            }
+        }
+        for attached in self.attached_from[i]..i {
+            if !self.placed[attached] {
+                self.placed[attached] = true;
+                self.order.push(attached);
+            }
+        }
+        self.in_progress[i] = false;
+        self.placed[i] = true;
+        self.order.push(i);
+    }
+}
+
+impl ASTLinker {
+    /// Moves subroutine and class definitions before their first (possibly indirect) use,
+    /// so that top-level definitions can refer to each other regardless of their order.
+    /// Reports an error if a definition needs its own value to be evaluated.
+    ///
+    /// ```erg
+    /// print! f 1
+    /// A = Class { .b = B }
+    /// B = Class { .x = Int }
+    /// f x = x + 1
+    /// ```
+    /// ↓
+    /// ```erg
+    /// f x = x + 1
+    /// print! f 1
+    /// B = Class { .x = Int }
+    /// A = Class { .b = B }
+    /// ```
+    fn hoist_definitions(&mut self, module: Vec<Expr>) -> Vec<Expr> {
+        let mut hoister = Hoister::new(&module);
+        for i in 0..module.len() {
+            hoister.place(i);
+        }
+        for (i, path) in hoister.cycles.iter() {
+            self.errs.push(TyCheckError::cyclic_definition_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                hoister.chunks[*i].loc,
+                "".into(),
+                path,
+            ));
+        }
+        let mut module = module.into_iter().map(Some).collect::<Vec<_>>();
+        hoister
+            .order
+            .iter()
+            .filter_map(|i| module[*i].take())
+            .collect()
+    }
+}
@@ -19,6 +19,7 @@ use crate::ty::value::ValueObj;
 use crate::ty::HasType;
 
 use crate::hir::*;
+use crate::manifest::prelude_path;
 use crate::module::SharedModuleCache;
 
 pub struct Mod {
@@ -65,12 +66,21 @@ impl<'a> HIRLinker<'a> {
 
     pub fn link(&self, mut main: HIR) -> HIR {
         log!(info "the linking process has started.");
+        let prelude = self.link_prelude();
         for chunk in main.module.iter_mut() {
             self.replace_import(chunk);
         }
         // declare all modules first (due to cyclic modules)
+        let mut n_mods = 0;
         for (i, module) in self.removed_mods.borrow_mut().values_mut().enumerate() {
             main.module.insert(i, take(&mut module.definition));
+            n_mods += 1;
+        }
+        // the prelude is executed before the main module (and the imported modules inherit its names)
+        if let Some(prelude) = prelude {
+            for (i, chunk) in prelude.module.into_iter().enumerate() {
+                main.module.insert(n_mods + i, chunk);
+            }
         }
         for chunk in main.module.iter_mut() {
             Self::resolve_pymod_path(chunk);
@@ -79,6 +89,17 @@ impl<'a> HIRLinker<'a> {
         main
     }
 
+    /// Links the project prelude (see `Context::build_prelude`), which is inlined into the main module.
+    fn link_prelude(&self) -> Option<HIR> {
+        let path = prelude_path(&self.cfg.input)?;
+        let (hir, cfg) = self
+            .mod_cache
+            .get(path.as_path())
+            .and_then(|entry| entry.hir.clone().map(|hir| (hir, entry.cfg().clone())))?;
+        let linker = self.inherit(&cfg);
+        Some(linker.link_child(hir))
+    }
+
     fn link_child(&self, mut hir: HIR) -> HIR {
         for chunk in hir.module.iter_mut() {
             self.replace_import(chunk);
@@ -310,7 +331,7 @@ impl<'a> HIRLinker<'a> {
                         self.replace_import(&mut arg.expr);
                     }
                     if let Some(arg) = call.args.var_args.as_deref_mut() {
-                        self.replace_py_import(&mut arg.expr);
+                        self.replace_import(&mut arg.expr);
                     }
                     for arg in call.args.kw_args.iter_mut() {
                         self.replace_import(&mut arg.expr);
@@ -449,8 +470,14 @@ impl<'a> HIRLinker<'a> {
     /// x = __import__("a.x").x
     /// ```
     fn replace_py_import(&self, expr: &mut Expr) {
-        let args = &mut enum_unwrap!(expr, Expr::Call).args;
+        let call = enum_unwrap!(expr, Expr::Call);
+        let is_cimport = call.obj.show_acc().as_deref() == Some("cimport");
+        let args = &mut call.args;
         let mod_name_lit = enum_unwrap!(args.remove_left_or_key("Path").unwrap(), Expr::Lit);
+        // the declarations have been registered in `pre_import`
+        if is_cimport {
+            args.remove_left_or_key("decls");
+        }
         let mod_name_str = enum_unwrap!(mod_name_lit.value.clone(), ValueObj::Str);
         let mut dir = self.cfg.input.dir();
         let mod_path = self
@@ -458,10 +485,12 @@ impl<'a> HIRLinker<'a> {
             .input
             .resolve_decl_path(Path::new(&mod_name_str[..]))
             .unwrap();
-        if !mod_path
-            .canonicalize()
-            .unwrap()
-            .starts_with(&dir.canonicalize().unwrap())
+        // C extensions are imported by their names (the declarations are local, but the modules may not be)
+        if is_cimport
+            || !mod_path
+                .canonicalize()
+                .unwrap()
+                .starts_with(&dir.canonicalize().unwrap())
         {
             dir = PathBuf::new();
         }
@@ -4,12 +4,15 @@
 
 #[allow(unused_imports)]
 use erg_common::log;
+use erg_common::error::{ErrorKind, Location};
+use erg_common::io::Input;
 use erg_common::traits::{Locational, Runnable, Stream};
 use erg_common::Str;
 use erg_parser::ast::AST;
 use erg_parser::build_ast::ASTBuilder;
 use erg_parser::lex::Lexer;
 
+use crate::context::severity::{LenientCheck, Severity};
 use crate::context::ContextKind;
 use crate::link_ast::ASTLinker;
 use crate::ty::{HasType, Type, ValueObj, VisibilityModifier};
@@ -17,9 +20,42 @@ use crate::ty::{HasType, Type, ValueObj, VisibilityModifier};
 use crate::error::{
     CompileErrors, LowerError, LowerResult, LowerWarning, LowerWarnings, SingleLowerResult,
 };
-use crate::hir::{self, Expr, Signature, HIR};
+use crate::hir::{self, Def, Expr, Params, Signature, HIR};
 use crate::lower::ASTLowerer;
 use crate::varinfo::VarInfo;
+use crate::visitor::{walk_def, walk_expr, Visit};
+
+/// Collects the unannotated parameters whose types are inferred as `Obj`.
+struct ObjParams(Vec<(Str, Location)>);
+
+impl ObjParams {
+    fn collect(&mut self, params: &Params) {
+        for param in params.non_defaults.iter() {
+            if param.raw.t_spec.is_some() || param.vi.t != Type::Obj {
+                continue;
+            }
+            if let Some(name) = param.inspect() {
+                self.0.push((name.clone(), param.loc()));
+            }
+        }
+    }
+}
+
+impl Visit for ObjParams {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::Lambda(lambda) = expr {
+            self.collect(&lambda.params);
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_def(&mut self, def: &Def) {
+        if let Signature::Subr(subr) = &def.sig {
+            self.collect(&subr.params);
+        }
+        walk_def(self, def);
+    }
+}
 
 impl ASTLowerer {
     pub(crate) fn var_result_t_check(
@@ -65,9 +101,34 @@ impl ASTLowerer {
     /// OK: exec `i: Int = 1`
     /// NG: exec `1 + 2`
     /// OK: exec `None`
+    /// NG: exec `f(1)` (function call)
+    /// NG: exec `g!(1)` (procedure call returning non-`NoneType`)
+    /// OK: exec `discard f(1)`
     fn expr_use_check(&self, expr: &hir::Expr) -> LowerResult<()> {
         if !expr.ref_t().is_nonelike() && !expr.is_type_asc() && !expr.is_doc_comment() {
-            if expr.ref_t().is_subr() {
+            let callee_t = match expr {
+                hir::Expr::Call(call) => call.signature_t(),
+                _ => None,
+            };
+            if callee_t.is_some_and(|t| t.is_subr() && !t.is_procedure()) {
+                Err(LowerWarnings::from(
+                    LowerWarning::unused_func_result_warning(
+                        self.cfg().input.clone(),
+                        line!() as usize,
+                        expr,
+                        String::from(&self.module.context.name[..]),
+                    ),
+                ))
+            } else if callee_t.is_some_and(|t| t.is_procedure()) {
+                Err(LowerWarnings::from(
+                    LowerWarning::unused_proc_result_warning(
+                        self.cfg().input.clone(),
+                        line!() as usize,
+                        expr,
+                        String::from(&self.module.context.name[..]),
+                    ),
+                ))
+            } else if expr.ref_t().is_subr() {
                 Err(LowerWarnings::from(
                     LowerWarning::unused_subroutine_warning(
                         self.cfg().input.clone(),
@@ -178,23 +239,57 @@ impl ASTLowerer {
             }
             let name_is_auto = &value.name[..] == "_"
                 || !Lexer::is_valid_start_symbol_ch(value.name.chars().next().unwrap_or(' '));
-            if value.referrers.is_empty() && value.vi.vis.is_private() && !name_is_auto {
+            // test functions are called by the test runner
+            let is_test = value.vi.is_test(&value.name);
+            if value.referrers.is_empty() && value.vi.vis.is_private() && !name_is_auto && !is_test
+            {
                 let input = referee
                     .module
                     .as_ref()
                     .map_or(self.input().clone(), |path| path.as_path().into());
+                let removal = if value.vi.t.is_module() {
+                    Self::import_lines(&input, referee.loc, &value.name)
+                } else {
+                    None
+                };
                 let warn = LowerWarning::unused_warning(
                     input,
                     line!() as usize,
                     referee.loc,
                     &value.name,
                     self.module.context.caused_by(),
+                    removal,
                 );
                 self.warns.push(warn);
             }
         }
     }
 
+    /// The line of `name = import "..."` (or `pyimport`), if nothing else is written in the line.
+    fn import_lines(input: &Input, loc: Location, name: &str) -> Option<Location> {
+        if input.is_repl() {
+            return None;
+        }
+        let ln = loc.ln_begin()?;
+        let line = input.reread_lines(ln as usize, ln as usize).pop()?;
+        let (lhs, rhs) = line.split_once('=')?;
+        let rhs = rhs.split('#').next()?.trim();
+        let is_import = ["import", "pyimport"].iter().any(|import| {
+            rhs.strip_prefix(import).is_some_and(|path| {
+                let path = path.trim();
+                path.len() >= 2
+                    && path.starts_with('"')
+                    && path.ends_with('"')
+                    && !path[1..path.len() - 1].contains('"')
+            })
+        });
+        if lhs.trim() == name && is_import {
+            Some(Location::range(ln, 0, ln + 1, 0))
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn check_doc_comments(&mut self, hir: &HIR) {
         for chunk in hir.module.iter() {
             self.check_doc_comment(chunk);
@@ -286,6 +381,25 @@ impl ASTLowerer {
         self.module.context.pop();
     }
 
+    /// `--strict`: an unannotated parameter must not fall back to `Obj`.
+    pub(crate) fn check_obj_fallback(&mut self, hir: &HIR) {
+        let severity = self.module.context.severity_of(LenientCheck::ObjFallback);
+        if !severity.is_error() {
+            return;
+        }
+        let mut params = ObjParams(vec![]);
+        params.visit_module(&hir.module);
+        for (name, loc) in params.0 {
+            self.errs.push(LowerError::obj_fallback_error(
+                self.input().clone(),
+                line!() as usize,
+                loc,
+                self.module.context.caused_by(),
+                &name,
+            ));
+        }
+    }
+
     pub(crate) fn warn_implicit_union(&mut self, hir: &HIR) {
         for chunk in hir.module.iter() {
             self.warn_implicit_union_chunk(chunk);
@@ -307,13 +421,17 @@ impl ASTLowerer {
             Expr::Def(def) => {
                 if let Signature::Subr(subr) = &def.sig {
                     let return_t = subr.ref_t().return_t().unwrap();
-                    if return_t.union_pair().is_some() && subr.return_t_spec.is_none() {
+                    // the return types of `@Overload` definitions are specified for each branch
+                    if return_t.union_pair().is_some()
+                        && subr.return_t_spec.is_none()
+                        && !subr.ident.vi.is_overloaded()
+                    {
                         let typ = if cfg!(feature = "debug") {
                             return_t.clone()
                         } else {
                             self.module.context.readable_type(return_t.clone())
                         };
-                        let warn = LowerWarning::union_return_type_warning(
+                        let mut warn = LowerWarning::union_return_type_warning(
                             self.input().clone(),
                             line!() as usize,
                             subr.loc(),
@@ -321,7 +439,14 @@ impl ASTLowerer {
                             subr.ident.inspect(),
                             &typ,
                         );
-                        self.warns.push(warn);
+                        match self.module.context.severity_of(LenientCheck::ImplicitUnion) {
+                            Severity::Allow => {}
+                            Severity::Warn => self.warns.push(warn),
+                            Severity::Error => {
+                                warn.core.kind = ErrorKind::TypeError;
+                                self.errs.push(warn);
+                            }
+                        }
                     }
                 }
             }
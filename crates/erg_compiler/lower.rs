@@ -7,35 +7,41 @@ use erg_common::config::{ErgConfig, ErgMode};
 use erg_common::consts::{ELS, ERG_MODE, PYTHON_MODE};
 use erg_common::dict;
 use erg_common::dict::Dict;
-use erg_common::error::{Location, MultiErrorDisplay};
+use erg_common::error::{ErrorKind, Location, MultiErrorDisplay};
 use erg_common::fresh::FreshNameGenerator;
+use erg_common::io::Input;
 use erg_common::set;
 use erg_common::set::Set;
 use erg_common::traits::{ExitStatus, Locational, NoTypeDisplay, Runnable, Stream};
-use erg_common::triple::Triple;
 use erg_common::{fmt_option, fn_name, log, switch_lang, Str};
 
 use erg_parser::ast::{self, AscriptionKind, VisModifierSpec};
 use erg_parser::ast::{OperationKind, TypeSpecWithOp, VarName, AST};
 use erg_parser::build_ast::ASTBuilder;
-use erg_parser::token::{Token, TokenKind};
+use erg_parser::token::{Token, TokenKind, DOT};
 use erg_parser::Parser;
 
 use crate::artifact::{CompleteArtifact, IncompleteArtifact};
 use crate::context::instantiate::TyVarCache;
+use crate::context::lookup::LookupOutcome;
+use crate::context::severity::LenientCheck;
 use crate::module::SharedCompilerResource;
 use crate::ty::constructors::{
-    array_t, free_var, func, guard, mono, poly, proc, refinement, set_t, ty_tp, v_enum,
+    and, array_t, fn1_met, free_var, func, guard, mono, poly, proc, refinement, set_t, subr_t,
+    ty_tp, v_enum,
 };
 use crate::ty::free::Constraint;
-use crate::ty::typaram::TyParam;
+use crate::ty::typaram::{OpKind, TyParam};
 use crate::ty::value::{GenTypeObj, TypeObj, ValueObj};
-use crate::ty::{GuardType, HasType, ParamTy, Predicate, Type, Variable, VisibilityModifier};
+use crate::ty::{
+    GuardType, HasType, ParamTy, Predicate, Type, Variable, Visibility, VisibilityModifier,
+};
 
 use crate::context::{
     ClassDefType, Context, ContextKind, ContextProvider, ControlKind, ModuleContext,
     RegistrationMode, TraitImpl,
 };
+use crate::error::lower::import_suggestion;
 use crate::error::{
     CompileError, CompileErrors, CompileWarning, LowerError, LowerErrors, LowerResult,
     LowerWarning, LowerWarnings, SingleLowerResult,
@@ -43,12 +49,16 @@ use crate::error::{
 use crate::hir;
 use crate::hir::HIR;
 use crate::link_ast::ASTLinker;
-use crate::varinfo::{VarInfo, VarKind};
+use crate::manifest::{is_listed_in, prelude_path};
+use crate::varinfo::{AbsLocation, MethodKind, Mutability, VarInfo, VarKind};
 use crate::AccessKind;
 use crate::{feature_error, unreachable_error};
 
 use VisibilityModifier::*;
 
+/// The maximum number of REPL evaluations that can be undone
+pub(crate) const MAX_HISTORY: usize = 32;
+
 pub fn acc_to_variable(acc: &ast::Accessor) -> Option<Variable> {
     match acc {
         ast::Accessor::Ident(ident) => Some(Variable::Var(ident.inspect().clone(), ident.loc())),
@@ -76,6 +86,10 @@ pub struct ASTLowerer {
     pub(crate) errs: LowerErrors,
     pub(crate) warns: LowerWarnings,
     fresh_gen: FreshNameGenerator,
+    /// Snapshots of the module context taken before each REPL evaluation
+    history: Vec<ModuleContext>,
+    /// The sources of the REPL evaluations that have not been rolled back
+    inputs: Vec<String>,
 }
 
 impl Default for ASTLowerer {
@@ -115,6 +129,8 @@ impl Runnable for ASTLowerer {
 
     fn initialize(&mut self) {
         self.module.context.initialize();
+        self.history.clear();
+        self.inputs.clear();
         self.errs.clear();
         self.warns.clear();
     }
@@ -125,10 +141,11 @@ impl Runnable for ASTLowerer {
     }
 
     fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let src = self.cfg.input.read();
+        // an invalid header is reported by the parser
+        let _ = self.cfg.apply_header(&src);
         let mut ast_builder = ASTBuilder::new(self.cfg.copy());
-        let artifact = ast_builder
-            .build(self.cfg.input.read())
-            .map_err(|artifact| artifact.errors)?;
+        let artifact = ast_builder.build(src).map_err(|artifact| artifact.errors)?;
         artifact.warns.write_all_to(&mut self.cfg.output);
         let artifact = self
             .lower(artifact.ast, "exec")
@@ -141,14 +158,33 @@ impl Runnable for ASTLowerer {
 
     fn eval(&mut self, src: String) -> Result<String, Self::Errs> {
         let mut ast_builder = ASTBuilder::new(self.cfg.copy());
-        let artifact = ast_builder.build(src).map_err(|artifact| artifact.errors)?;
+        let artifact = ast_builder
+            .build(src.clone())
+            .map_err(|artifact| artifact.errors)?;
         artifact.warns.write_all_stderr();
         let artifact = self
             .lower(artifact.ast, "eval")
             .map_err(|artifact| artifact.errors)?;
         artifact.warns.write_all_stderr();
+        self.accept_input(src);
         Ok(format!("{}", artifact.object))
     }
+
+    fn undo(&mut self) -> bool {
+        self.undo_eval()
+    }
+
+    fn accepted_inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    fn completion_words(&self) -> Vec<String> {
+        self.dir_names()
+    }
+
+    fn stats(&self) -> Option<String> {
+        Some(self.module.context.shared().stats().to_string())
+    }
 }
 
 impl ContextProvider for ASTLowerer {
@@ -167,10 +203,15 @@ impl ContextProvider for ASTLowerer {
 
 impl ASTLowerer {
     pub fn new_with_cache<S: Into<Str>>(
-        cfg: ErgConfig,
+        mut cfg: ErgConfig,
         mod_name: S,
         shared: SharedCompilerResource,
     ) -> Self {
+        cfg.py_compat = PYTHON_MODE || Self::is_py_compat(&cfg.input);
+        cfg.prelude = prelude_path(&cfg.input);
+        if let Some(prelude) = cfg.prelude.as_ref() {
+            Context::build_prelude(&cfg, prelude, &shared);
+        }
         let toplevel = Context::new_module(mod_name, cfg.clone(), shared);
         let module = ModuleContext::new(toplevel, dict! {});
         Self {
@@ -179,9 +220,23 @@ impl ASTLowerer {
             errs: LowerErrors::empty(),
             warns: LowerWarnings::empty(),
             fresh_gen: FreshNameGenerator::new("lower"),
+            history: vec![],
+            inputs: vec![],
         }
     }
 
+    /// Whether the module opts in to Python-compatible checking.
+    ///
+    /// A module opts in with a `#pragma: py_compat` comment in its header,
+    /// or by being listed in the `py_compat` entry of `package.er`:
+    ///
+    /// ```erg
+    /// py_compat = ["src/legacy.er", "src/compat/"]
+    /// ```
+    fn is_py_compat(input: &Input) -> bool {
+        input.has_pragma("py_compat") || is_listed_in(input, "py_compat")
+    }
+
     pub fn new_with_ctx(module: ModuleContext) -> Self {
         Self {
             cfg: module.get_top_cfg(),
@@ -189,6 +244,8 @@ impl ASTLowerer {
             errs: LowerErrors::empty(),
             warns: LowerWarnings::empty(),
             fresh_gen: FreshNameGenerator::new("lower"),
+            history: vec![],
+            inputs: vec![],
         }
     }
 
@@ -213,6 +270,47 @@ impl ASTLowerer {
         &self.module
     }
 
+    /// Saves the current module context so that it can be restored by `rollback`.
+    /// The outer (builtin) context is shared by all snapshots and is not copied.
+    pub(crate) fn snapshot(&mut self) {
+        let outer = self.module.context.outer.take();
+        let snapshot = self.module.clone();
+        self.module.context.outer = outer;
+        if self.history.len() >= MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history.push(snapshot);
+    }
+
+    /// Restores the module context saved by the last `snapshot`.
+    /// Returns `false` if there is no snapshot.
+    ///
+    /// Only the type environment is restored;
+    /// values already computed at runtime (e.g. by the Python interpreter) are not.
+    pub fn rollback(&mut self) -> bool {
+        let Some(mut snapshot) = self.history.pop() else {
+            return false;
+        };
+        snapshot.context.outer = self.module.context.outer.take();
+        self.module = snapshot;
+        true
+    }
+
+    /// Records the source of a successful REPL evaluation.
+    pub(crate) fn accept_input(&mut self, src: String) {
+        self.inputs.push(src);
+    }
+
+    /// Rolls back the last successful REPL evaluation.
+    /// Unlike `rollback` (which is also used to discard a failed evaluation), the recorded source is also discarded.
+    pub fn undo_eval(&mut self) -> bool {
+        let undone = self.rollback();
+        if undone {
+            self.inputs.pop();
+        }
+        undone
+    }
+
     pub fn dir(&self) -> Dict<&VarName, &VarInfo> {
         ContextProvider::dir(self)
     }
@@ -633,12 +731,13 @@ impl ASTLowerer {
                     &self.cfg.input,
                     &self.module.context,
                 ) {
-                    Triple::Ok(vi) => vi,
-                    Triple::Err(errs) => {
-                        self.errs.push(errs);
-                        VarInfo::ILLEGAL
+                    LookupOutcome::Found(vi) => vi,
+                    // keep the hidden definition to avoid cascading errors
+                    LookupOutcome::Invisible { vi, err } => {
+                        self.errs.push(err);
+                        vi
                     }
-                    Triple::None => {
+                    LookupOutcome::Absent => {
                         let self_t = obj.t();
                         let (similar_info, similar_name) = self
                             .module
@@ -658,6 +757,10 @@ impl ASTLowerer {
                         self.errs.push(err);
                         VarInfo::ILLEGAL
                     }
+                    other => {
+                        self.errs.extend(other.err());
+                        VarInfo::ILLEGAL
+                    }
                 };
                 self.inc_ref(attr.ident.inspect(), &vi, &attr.ident.name);
                 let ident = hir::Identifier::new(attr.ident, None, vi);
@@ -695,18 +798,18 @@ impl ASTLowerer {
                 &self.cfg.input,
                 &self.module.context,
             ) {
-                Triple::Ok(vi) => vi,
-                Triple::Err(err) => {
+                LookupOutcome::Found(vi) => vi,
+                LookupOutcome::Invisible { vi, err } => {
                     self.errs.push(err);
-                    VarInfo::ILLEGAL
+                    vi
                 }
-                Triple::None => {
+                LookupOutcome::Absent => {
                     let (similar_info, similar_name) = self
                         .module
                         .context
                         .get_similar_name_and_info(ident.inspect())
                         .unzip();
-                    let err = LowerError::detailed_no_var_error(
+                    let mut err = LowerError::detailed_no_var_error(
                         self.cfg.input.clone(),
                         line!() as usize,
                         ident.loc(),
@@ -715,9 +818,19 @@ impl ASTLowerer {
                         similar_name,
                         similar_info,
                     );
+                    // defined in another module
+                    if let Some(path) = self.module.context.get_import_path_of(ident.inspect()) {
+                        err.core
+                            .suggestions
+                            .push(import_suggestion(ident.inspect(), &path));
+                    }
                     self.errs.push(err);
                     VarInfo::ILLEGAL
                 }
+                other => {
+                    self.errs.extend(other.err());
+                    VarInfo::ILLEGAL
+                }
             };
             (
                 res,
@@ -728,11 +841,153 @@ impl ASTLowerer {
                     .map(|ctx| ctx.first().unwrap().name.clone()),
             )
         };
+        if &vi.vis.def_namespace[..] == "<builtins>"
+            && !self.cfg.target.is_available_builtin(ident.inspect())
+        {
+            self.errs.push(LowerError::not_available_on_target_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                ident.inspect(),
+                self.cfg.target,
+                ident.loc(),
+                self.module.context.caused_by(),
+            ));
+        }
         self.inc_ref(ident.inspect(), &vi, &ident.name);
         let ident = hir::Identifier::new(ident, __name__, vi);
         Ok(ident)
     }
 
+    /// `x[a..b]` => `Some((a, b + 1))` if both ends of the range are constant
+    /// (the returned range is half-open)
+    fn get_const_slice(&self, call: &ast::Call) -> Option<(usize, usize)> {
+        if &call.attr_name.as_ref()?.inspect()[..] != "__getitem__" || !call.args.kw_is_empty() {
+            return None;
+        }
+        let [arg] = call.args.pos_args() else {
+            return None;
+        };
+        let ast::Expr::BinOp(bin) = &arg.expr else {
+            return None;
+        };
+        if !bin.op.kind.is_range_op() {
+            return None;
+        }
+        let as_index = |expr: &ast::Expr| match self.module.context.expr_to_value(expr.clone())? {
+            ValueObj::Nat(n) => Some(n as usize),
+            ValueObj::Int(i) if i >= 0 => Some(i as usize),
+            _ => None,
+        };
+        let start = as_index(&bin.args[0])?;
+        let end = as_index(&bin.args[1])?;
+        let (start, end) = match bin.op.kind {
+            TokenKind::Closed => (start, end + 1),
+            TokenKind::LeftOpen => (start + 1, end + 1),
+            TokenKind::RightOpen => (start, end),
+            _ => (start + 1, end),
+        };
+        Some((start, end.max(start)))
+    }
+
+    /// `(1, "a", 2.0)[1..2]: ("a", 2.0)`, `[1, 2, 3][0..1]: [{1, 2, 3}; 2]`
+    ///
+    /// Returns `None` if `obj` is neither a tuple nor an array.
+    fn get_slice_vi(
+        &self,
+        obj: &hir::Expr,
+        args: &hir::Args,
+        (start, end): (usize, usize),
+    ) -> Option<LowerResult<VarInfo>> {
+        let obj_t = obj.ref_t();
+        let params = obj_t.typarams();
+        let (len, sliced_t) = match &obj_t.qual_name()[..] {
+            "Tuple" => {
+                let elems = match params.first()? {
+                    TyParam::Array(elems) => elems.clone(),
+                    TyParam::Value(ValueObj::Array(elems)) => {
+                        elems.iter().cloned().map(TyParam::value).collect()
+                    }
+                    _ => return None,
+                };
+                let sliced = elems.get(start..end).unwrap_or_default().to_vec();
+                (
+                    Some(elems.len()),
+                    poly("Tuple", vec![TyParam::Array(sliced)]),
+                )
+            }
+            "Array" => {
+                let len = match params.get(1) {
+                    Some(TyParam::Value(ValueObj::Nat(n))) => Some(*n as usize),
+                    _ => None,
+                };
+                let elem_t = self
+                    .module
+                    .context
+                    .convert_tp_into_type(params.first()?.clone())
+                    .ok()?;
+                (len, array_t(elem_t, TyParam::value(end - start)))
+            }
+            _ => return None,
+        };
+        let arg = args.pos_args.first()?;
+        if len.is_some_and(|len| end > len) {
+            return Some(Err(LowerErrors::from(
+                LowerError::slice_out_of_range_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    len.unwrap_or(0),
+                    end,
+                    arg.loc(),
+                    self.module.context.caused_by(),
+                ),
+            )));
+        }
+        let t = fn1_met(obj_t.clone(), arg.expr.t(), sliced_t);
+        Some(Ok(VarInfo::new(
+            t,
+            Mutability::Immutable,
+            Visibility::BUILTIN_PUBLIC,
+            VarKind::Builtin,
+            None,
+            None,
+            None,
+            AbsLocation::unknown(),
+        )))
+    }
+
+    /// The bound of a comparison guard and its type.
+    /// The bound is a constant or the length of an array (`len(xs)`).
+    fn get_guard_bound(&self, rhs: &ast::Expr) -> Option<(TyParam, Type)> {
+        if let Some(value) = self.module.context.expr_to_value(rhs.clone()) {
+            let t = value.class();
+            return Some((TyParam::value(value), t));
+        }
+        let ast::Expr::Call(call) = rhs else {
+            return None;
+        };
+        let (ast::Expr::Accessor(ast::Accessor::Ident(func)), None) =
+            (call.obj.as_ref(), call.attr_name.as_ref())
+        else {
+            return None;
+        };
+        let [arg] = call.args.pos_args() else {
+            return None;
+        };
+        let ast::Expr::Accessor(ast::Accessor::Ident(ident)) = &arg.expr else {
+            return None;
+        };
+        if &func.inspect()[..] != "len" || !call.args.kw_is_empty() {
+            return None;
+        }
+        let (_, vi) = self.module.context.get_var_kv(ident.inspect())?;
+        let t = vi.t.derefine();
+        if !matches!(&t.qual_name()[..], "Array" | "Array!") {
+            return None;
+        }
+        let len = t.typarams().get(1)?.clone();
+        Some((len, Type::Nat))
+    }
+
     fn get_guard_type(&self, op: &Token, lhs: &ast::Expr, rhs: &ast::Expr) -> Option<Type> {
         let var = expr_to_variable(lhs)?;
         match op.kind {
@@ -758,35 +1013,27 @@ impl ASTLowerer {
                 let ty = guard(var, v_enum(set! { value }));
                 Some(self.module.context.complement(&ty))
             }
-            TokenKind::Gre => {
-                let value = self.module.context.expr_to_value(rhs.clone())?;
-                let t = value.class();
+            TokenKind::Gre | TokenKind::GreEq | TokenKind::Less | TokenKind::LessEq => {
+                let (bound, t) = self.get_guard_bound(rhs)?;
+                let ctx = &self.module.context;
+                let is_int = matches!(t, Type::Nat | Type::Int);
                 let varname = self.fresh_gen.fresh_varname();
-                let pred = Predicate::gt(varname.clone(), TyParam::value(value));
-                let refine = refinement(varname, t, pred);
-                Some(guard(var, refine))
-            }
-            TokenKind::GreEq => {
-                let value = self.module.context.expr_to_value(rhs.clone())?;
-                let t = value.class();
-                let varname = self.fresh_gen.fresh_varname();
-                let pred = Predicate::ge(varname.clone(), TyParam::value(value));
-                let refine = refinement(varname, t, pred);
-                Some(guard(var, refine))
-            }
-            TokenKind::Less => {
-                let value = self.module.context.expr_to_value(rhs.clone())?;
-                let t = value.class();
-                let varname = self.fresh_gen.fresh_varname();
-                let pred = Predicate::lt(varname.clone(), TyParam::value(value));
-                let refine = refinement(varname, t, pred);
-                Some(guard(var, refine))
-            }
-            TokenKind::LessEq => {
-                let value = self.module.context.expr_to_value(rhs.clone())?;
-                let t = value.class();
-                let varname = self.fresh_gen.fresh_varname();
-                let pred = Predicate::le(varname.clone(), TyParam::value(value));
+                let pred = match op.kind {
+                    // i > 1 => i >= 2
+                    TokenKind::Gre if is_int => {
+                        let succ = bound.clone().succ();
+                        Predicate::ge(varname.clone(), ctx.eval_tp(succ.clone()).unwrap_or(succ))
+                    }
+                    TokenKind::Gre => Predicate::gt(varname.clone(), bound),
+                    TokenKind::GreEq => Predicate::ge(varname.clone(), bound),
+                    // i < 3 => i <= 2, i < xs.len() => i <= pred(N)
+                    TokenKind::Less if is_int && bound != TyParam::value(0usize) => {
+                        let pred = bound.clone().pred();
+                        Predicate::le(varname.clone(), ctx.eval_tp(pred.clone()).unwrap_or(pred))
+                    }
+                    TokenKind::Less => Predicate::lt(varname.clone(), bound),
+                    _ => Predicate::le(varname.clone(), bound),
+                };
                 let refine = refinement(varname, t, pred);
                 Some(guard(var, refine))
             }
@@ -834,6 +1081,39 @@ impl ASTLowerer {
                 *return_t = guard;
             }
         }
+        // e.g. `i >= 0 and i < len(xs)`: both guards hold in the then-branch
+        if bin.op.kind == TokenKind::AndOp
+            && args.iter().all(|arg| arg.expr.ref_t().is_guard_like())
+        {
+            let both = and(args[0].expr.t(), args[1].expr.t());
+            if let Some(return_t) = vi.t.mut_return_t() {
+                *return_t = both;
+            } else if let Some(mut return_t) = vi.t.tyvar_mut_return_t() {
+                *return_t = both;
+            }
+        }
+        // e.g. {x | 0 <= x <= 10} + {y | 0 <= y <= 5} => {z | 0 <= z <= 15}
+        let op = match bin.op.kind {
+            TokenKind::Plus => Some(OpKind::Add),
+            TokenKind::Minus => Some(OpKind::Sub),
+            TokenKind::Star => Some(OpKind::Mul),
+            _ => None,
+        };
+        if let Some(refined) = op.and_then(|op| {
+            self.module
+                .context
+                .eval_refinement_bin(op, args[0].expr.ref_t(), args[1].expr.ref_t())
+        }) {
+            if let Some(return_t) = vi.t.mut_return_t() {
+                if matches!(return_t.derefine(), Type::Int | Type::Nat) {
+                    *return_t = refined;
+                }
+            } else if let Some(mut return_t) = vi.t.tyvar_mut_return_t() {
+                if matches!(return_t.derefine(), Type::Int | Type::Nat) {
+                    *return_t = refined;
+                }
+            }
+        }
         let mut args = args.into_iter();
         let lhs = args.next().unwrap().expr;
         let rhs = args.next().unwrap().expr;
@@ -863,8 +1143,19 @@ impl ASTLowerer {
         hir::UnaryOp::new(unary.op, expr, t)
     }
 
-    fn lower_args(&mut self, args: ast::Args, errs: &mut LowerErrors) -> hir::Args {
-        let (pos_args, var_args, kw_args, paren) = args.deconstruct();
+    fn lower_args(&mut self, mut args: ast::Args, errs: &mut LowerErrors) -> hir::Args {
+        let kw_var_args = args.take_kw_var_args();
+        let (mut pos_args, mut var_args, mut kw_args, paren) = args.deconstruct();
+        if let Some(expanded) = var_args.as_ref().and_then(|var| self.expand_var_args(var)) {
+            pos_args.extend(expanded);
+            var_args = None;
+        }
+        if let Some(kw_var_args) = kw_var_args {
+            match self.expand_kw_var_args(kw_var_args) {
+                Ok(expanded) => kw_args.extend(expanded),
+                Err(es) => errs.extend(es),
+            }
+        }
         let mut hir_args = hir::Args::new(
             Vec::with_capacity(pos_args.len()),
             None,
@@ -910,6 +1201,128 @@ impl ASTLowerer {
         hir_args
     }
 
+    /// `obj.{getitem}(index)`
+    fn getitem_call(obj: ast::Expr, getitem: &'static str, index: ast::Literal) -> ast::Expr {
+        let line = index.ln_begin().unwrap_or(0);
+        let getitem = ast::Identifier::public_with_line(DOT, getitem.into(), line);
+        let args = ast::Args::single(ast::PosArg::new(ast::Expr::Literal(index)));
+        ast::Expr::Call(ast::Call::new(obj, Some(getitem), args))
+    }
+
+    /// `f(*(1, 2))` ==> `f(1, 2)`
+    /// `f(*tup)` where `tup: (Int, Str)` ==> `f(tup.0, tup.1)`
+    /// `f(*arr)` where `arr: Array(Int, 2)` ==> `f(arr[0], arr[1])`
+    ///
+    /// Returns `None` if the length of `var_args` is not statically known.
+    fn expand_var_args(&mut self, var_args: &ast::PosArg) -> Option<Vec<ast::PosArg>> {
+        match &var_args.expr {
+            ast::Expr::Tuple(ast::Tuple::Normal(tup)) => {
+                return Some(tup.elems.pos_args().to_vec());
+            }
+            ast::Expr::Array(ast::Array::Normal(arr)) => {
+                return Some(arr.elems.pos_args().to_vec());
+            }
+            ast::Expr::Accessor(_) => {}
+            _ => return None,
+        }
+        let obj = self.lower_expr(var_args.expr.clone()).ok()?;
+        let (getitem, len) = match obj.ref_t().derefine() {
+            Type::Poly { name, params } if &name[..] == "Tuple" => match params.first() {
+                Some(TyParam::Array(elems)) => ("__Tuple_getitem__", elems.len()),
+                _ => return None,
+            },
+            Type::Poly { name, params } if &name[..] == "Array" => match params.get(1) {
+                Some(TyParam::Value(ValueObj::Nat(len))) => ("__getitem__", *len as usize),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let line = var_args.ln_begin().unwrap_or(0);
+        let expanded = (0..len)
+            .map(|i| {
+                let index = ast::Literal::nat(i, line);
+                ast::PosArg::new(Self::getitem_call(var_args.expr.clone(), getitem, index))
+            })
+            .collect();
+        Some(expanded)
+    }
+
+    /// `f(**rec)` where `rec: {.a = Int; .b = Str}` ==> `f(a := rec.a, b := rec.b)`
+    /// `f(**dic)` where `dic: {"a": Int, "b": Str}` ==> `f(a := dic["a"], b := dic["b"])`
+    ///
+    /// The keys are expanded into keyword arguments so that missing/extra keys are checked by `substitute_call`.
+    fn expand_kw_var_args(&mut self, kw_var: ast::PosArg) -> LowerResult<Vec<ast::KwArg>> {
+        let loc = kw_var.loc();
+        if !matches!(kw_var.expr, ast::Expr::Accessor(_)) {
+            return feature_error!(
+                LowerErrors,
+                LowerError,
+                self.module.context,
+                loc,
+                "unpacking keyword arguments from a non-variable expression"
+            );
+        }
+        let obj = self.lower_expr(kw_var.expr.clone())?;
+        let fields = match obj.ref_t().derefine() {
+            Type::Record(fields) => fields
+                .into_iter()
+                .map(|(field, _)| {
+                    let keyword = Token::symbol_with_loc(field.symbol, loc);
+                    let vis = if field.vis.is_public() {
+                        VisModifierSpec::Public(DOT)
+                    } else {
+                        VisModifierSpec::Auto
+                    };
+                    let attr = ast::Identifier::new(vis, VarName::new(keyword.clone()));
+                    ast::KwArg::new(keyword, None, kw_var.expr.clone().attr_expr(attr))
+                })
+                .collect(),
+            Type::Poly { name, params } if &name[..] == "Dict" => {
+                let Some(TyParam::Dict(dict)) = params.first() else {
+                    return feature_error!(
+                        LowerErrors,
+                        LowerError,
+                        self.module.context,
+                        loc,
+                        "unpacking keyword arguments from an untyped dict"
+                    );
+                };
+                let mut kw_args = vec![];
+                for key in dict.keys() {
+                    let key = self.module.context.convert_tp_into_type(key.clone()).ok();
+                    let Some(TyParam::Value(ValueObj::Str(key))) =
+                        key.as_ref().and_then(|key| key.singleton_value())
+                    else {
+                        return feature_error!(
+                            LowerErrors,
+                            LowerError,
+                            self.module.context,
+                            loc,
+                            "unpacking keyword arguments from a dict with non-literal keys"
+                        );
+                    };
+                    let keyword = Token::symbol_with_loc(key.clone(), loc);
+                    let (line, col) = (loc.ln_begin().unwrap_or(0), loc.col_begin().unwrap_or(0));
+                    let lit = Token::new(TokenKind::StrLit, format!("\"{key}\""), line, col);
+                    let index = ast::Literal::new(lit);
+                    let getitem = Self::getitem_call(kw_var.expr.clone(), "__getitem__", index);
+                    kw_args.push(ast::KwArg::new(keyword, None, getitem));
+                }
+                kw_args
+            }
+            other => {
+                return feature_error!(
+                    LowerErrors,
+                    LowerError,
+                    self.module.context,
+                    loc,
+                    &format!("unpacking keyword arguments from {other}")
+                );
+            }
+        };
+        Ok(fields)
+    }
+
     fn push_guard(&mut self, nth: usize, kind: ControlKind, t: &Type) {
         match t {
             Type::Guard(guard) => match nth {
@@ -925,7 +1338,8 @@ impl ASTLowerer {
                 }
                 _ => {}
             },
-            Type::And(lhs, rhs) => {
+            // not (A and B) != (not A) and (not B)
+            Type::And(lhs, rhs) if nth == 0 => {
                 self.push_guard(nth, kind, lhs);
                 self.push_guard(nth, kind, rhs);
             }
@@ -935,11 +1349,30 @@ impl ASTLowerer {
 
     /// returning `Ok(call)` does not mean the call is valid, just means it is syntactically valid
     /// `ASTLowerer` is designed to cause as little information loss in HIR as possible
+    /// `C(...)` or `C.new(...)` where `C` is an `@Abstract` class
+    fn get_abstract_class<'e>(
+        obj: &'e hir::Expr,
+        attr_name: Option<&ast::Identifier>,
+    ) -> Option<&'e Type> {
+        if attr_name.is_some_and(|name| &name.inspect()[..] != "new") {
+            return None;
+        }
+        match obj.ref_t().singleton_value() {
+            Some(TyParam::Value(ValueObj::Type(TypeObj::Generated(gen)))) if gen.is_abstract() => {
+                Some(gen.typ())
+            }
+            _ => None,
+        }
+    }
+
     pub(crate) fn lower_call(&mut self, call: ast::Call) -> LowerResult<hir::Call> {
         log!(info "entered {}({}{}(...))", fn_name!(), call.obj, fmt_option!(call.attr_name));
         if let (Some(name), None) = (call.obj.get_name(), &call.attr_name) {
             self.module.context.higher_order_caller.push(name.clone());
         }
+        // the arguments are evaluated again by the const evaluator
+        let static_assert = (call.additional_operation() == Some(OperationKind::StaticAssert))
+            .then(|| call.clone());
         let mut errs = LowerErrors::empty();
         let guard = if let (
             ast::Expr::Accessor(ast::Accessor::Ident(ident)),
@@ -956,6 +1389,13 @@ impl ASTLowerer {
         } else {
             None
         };
+        let slice = self.get_const_slice(&call);
+        // `lens(R, "field")` is evaluated again to get the record and field types
+        let lens_args = matches!(
+            (call.obj.as_ref(), &call.attr_name),
+            (ast::Expr::Accessor(ast::Accessor::Ident(ident)), None) if &ident.inspect()[..] == "lens"
+        )
+        .then(|| call.args.clone());
         let hir_args = self.lower_args(call.args, &mut errs);
         let mut obj = match self.lower_expr(*call.obj) {
             Ok(obj) => obj,
@@ -965,24 +1405,56 @@ impl ASTLowerer {
                 return Err(errs);
             }
         };
-        let mut vi = match self.module.context.get_call_t(
-            &obj,
-            &call.attr_name,
-            &hir_args.pos_args,
-            &hir_args.kw_args,
-            &self.cfg.input,
-            &self.module.context,
-        ) {
-            Ok(vi) => vi,
-            Err((vi, es)) => {
-                self.module.context.higher_order_caller.pop();
+        if let Some(class) = Self::get_abstract_class(&obj, call.attr_name.as_ref()) {
+            errs.push(LowerError::abstract_instantiation_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                &class.to_string(),
+                obj.loc(),
+                self.module.context.caused_by(),
+            ));
+        }
+        let slice_vi = slice.and_then(|slice| self.get_slice_vi(&obj, &hir_args, slice));
+        let mut vi = match slice_vi {
+            Some(Ok(vi)) => vi,
+            Some(Err(es)) => {
                 errs.extend(es);
-                vi.unwrap_or(VarInfo::ILLEGAL)
+                VarInfo::ILLEGAL
             }
+            None => match self.module.context.get_call_t(
+                &obj,
+                &call.attr_name,
+                &hir_args.pos_args,
+                &hir_args.kw_args,
+                &self.cfg.input,
+                &self.module.context,
+            ) {
+                Ok(vi) => vi,
+                Err((vi, es)) => {
+                    self.module.context.higher_order_caller.pop();
+                    errs.extend(es);
+                    vi.unwrap_or(VarInfo::ILLEGAL)
+                }
+            },
         };
         if let Err(es) = self.module.context.propagate(&mut vi.t, &obj) {
             errs.extend(es);
         }
+        let derived_t = self
+            .get_partial_t(&obj, call.attr_name.as_ref(), &vi, &hir_args)
+            .or_else(|| self.get_lens_t(&obj, lens_args.as_ref()?));
+        if let Some(derived_t) = derived_t {
+            match derived_t {
+                Ok(derived_t) => {
+                    if let Some(ret_t) = vi.t.mut_return_t() {
+                        *ret_t = derived_t;
+                    } else if let Some(mut ref_t) = vi.t.tyvar_mut_return_t() {
+                        *ref_t = derived_t;
+                    }
+                }
+                Err(es) => errs.extend(es),
+            }
+        }
         if let Some(guard) = guard {
             debug_assert!(
                 self.module
@@ -1012,13 +1484,164 @@ impl ASTLowerer {
         };
         let mut call = hir::Call::new(obj, attr_name, hir_args);
         self.module.context.higher_order_caller.pop();
+        let (pos_params, kw_params) = call.arg_param_types();
+        let pos_args = call.args.pos_args.iter().map(|arg| &arg.expr);
+        let kw_args = call.args.kw_args.iter().map(|arg| &arg.expr);
+        for (arg, param_t) in pos_args.zip(pos_params).chain(kw_args.zip(kw_params)) {
+            if let Some(param_t) = param_t {
+                self.check_dynamic_coercion(arg, param_t);
+            }
+        }
         if errs.is_empty() {
+            self.insert_into_conversions(&mut call);
             self.exec_additional_op(&mut call)?;
+            if let Some(static_assert) = static_assert {
+                self.eval_static_assert(static_assert)?;
+            }
         }
         self.errs.extend(errs);
         Ok(call)
     }
 
+    /// `partial(f, *args)` or `f.bind(*args)` ==> `Partial(F, [...Args])`
+    fn get_partial_t(
+        &self,
+        obj: &hir::Expr,
+        attr_name: Option<&ast::Identifier>,
+        vi: &VarInfo,
+        args: &hir::Args,
+    ) -> Option<LowerResult<Type>> {
+        if args.var_args.is_some() || !args.kw_args.is_empty() {
+            return None;
+        }
+        let (callee, bound) = match (obj, attr_name) {
+            (hir::Expr::Accessor(hir::Accessor::Ident(ident)), None)
+                if ident.vi.kind.is_builtin() && &ident.inspect()[..] == "partial" =>
+            {
+                let (callee, bound) = args.pos_args.split_first()?;
+                (callee.expr.ref_t(), bound)
+            }
+            (_, Some(_))
+                if vi
+                    .py_name
+                    .as_ref()
+                    .is_some_and(|name| &name[..] == "Function::partial") =>
+            {
+                (obj.ref_t(), &args.pos_args[..])
+            }
+            _ => return None,
+        };
+        if !callee.is_subr() {
+            return None;
+        }
+        let bound_ts = bound.iter().map(|arg| arg.expr.ref_t());
+        Some(self.module.context.eval_partial_t(callee, bound_ts, args))
+    }
+
+    /// `lens(R, "field")` (`R::.field`) ==> `Lens(R, R.field)`
+    fn get_lens_t(&self, obj: &hir::Expr, args: &ast::Args) -> Option<LowerResult<Type>> {
+        let hir::Expr::Accessor(hir::Accessor::Ident(ident)) = obj else {
+            return None;
+        };
+        if !ident.vi.kind.is_builtin() {
+            return None;
+        }
+        let record = args.nth_or_key(0, "record")?;
+        let field = args.nth_or_key(1, "field")?;
+        Some(self.module.context.eval_lens_t(record, field))
+    }
+
+    /// `assert_static N == M` fails at compile time if `N == M` is evaluated to `False`.
+    /// If the test is a binary operation, the error shows the evaluated operands.
+    fn eval_static_assert(&self, call: ast::Call) -> LowerResult<()> {
+        let test = call.args.get_left_or_key("test").cloned();
+        let Err(mut errs) = self
+            .module
+            .context
+            .eval_const_expr(&ast::Expr::Call(call))
+        else {
+            return Ok(());
+        };
+        if let Some(ast::Expr::BinOp(bin)) = test {
+            let [lhs, rhs] = &bin.args;
+            if let (Ok(lhs), Ok(rhs)) = (
+                self.module.context.eval_const_expr(lhs),
+                self.module.context.eval_const_expr(rhs),
+            ) {
+                let hint = format!("evaluated as `{lhs} {} {rhs}`", bin.op.content);
+                for err in errs
+                    .iter_mut()
+                    .filter(|err| err.core.kind == ErrorKind::AssertionError)
+                {
+                    for sub in err.core.sub_messages.iter_mut() {
+                        sub.set_hint(hint.clone());
+                    }
+                }
+            }
+        }
+        Err(errs)
+    }
+
+    /// Wraps the positional arguments accepted by an `Into` implementation
+    /// (see `Context::get_into_target`) in a call to `.into`.
+    fn insert_into_conversions(&mut self, call: &mut hir::Call) {
+        let (pos_params, _) = call.arg_param_types();
+        let param_ts = pos_params
+            .into_iter()
+            .map(|param_t| param_t.cloned())
+            .collect::<Vec<_>>();
+        for (arg, param_t) in call.args.pos_args.iter_mut().zip(param_ts) {
+            let Some(param_t) = param_t else {
+                continue;
+            };
+            let arg_t = arg.expr.ref_t();
+            // `get_into_target` rejects types with unbound type variables, so check it first
+            if self
+                .module
+                .context
+                .get_into_target(arg_t, &param_t)
+                .is_none()
+                || self.module.context.subtype_of(arg_t, &param_t)
+            {
+                continue;
+            }
+            let severity = self
+                .module
+                .context
+                .severity_of(LenientCheck::IntoConversion);
+            if severity.is_error() {
+                self.errs.push(LowerError::into_conversion_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    arg.expr.loc(),
+                    self.module.context.caused_by(),
+                    arg_t,
+                    &param_t,
+                ));
+                continue;
+            }
+            let expr = mem::replace(&mut arg.expr, hir::Expr::Dummy(hir::Dummy::new(vec![])));
+            let line = expr.ln_begin().unwrap_or(0);
+            let attr_name = ast::Identifier::public_with_line(DOT, Str::ever("into"), line);
+            let vi = match self.module.context.get_call_t(
+                &expr,
+                &Some(attr_name.clone()),
+                &[],
+                &[],
+                &self.cfg.input,
+                &self.module.context,
+            ) {
+                Ok(vi) => vi,
+                Err((vi, errs)) => {
+                    self.errs.extend(errs);
+                    vi.unwrap_or(VarInfo::ILLEGAL)
+                }
+            };
+            let attr_name = hir::Identifier::new(attr_name, None, vi);
+            arg.expr = hir::Expr::Call(hir::Call::new(expr, Some(attr_name), hir::Args::empty()));
+        }
+    }
+
     /// importing is done in [preregister](https://github.com/erg-lang/erg/blob/ffd33015d540ff5a0b853b28c01370e46e0fcc52/crates/erg_compiler/context/register.rs#L819)
     fn exec_additional_op(&mut self, call: &mut hir::Call) -> LowerResult<()> {
         match call.additional_operation() {
@@ -1063,6 +1686,28 @@ impl ASTLowerer {
                 }
                 Ok(())
             }
+            Some(OperationKind::PyImport) if call.obj.show_acc().as_deref() == Some("cimport") => {
+                match call.args.get(1) {
+                    Some(hir::Expr::Lit(lit)) if matches!(lit.value, ValueObj::Str(_)) => Ok(()),
+                    other => Err(LowerErrors::from(LowerError::syntax_error(
+                        self.input().clone(),
+                        line!() as usize,
+                        other.map_or(call.loc(), |expr| expr.loc()),
+                        self.module.context.caused_by(),
+                        "the declarations of cimport must be a string literal".into(),
+                        None,
+                    ))),
+                }
+            }
+            Some(OperationKind::PyInline) => match call.args.get_left_or_key("code") {
+                Some(hir::Expr::Lit(lit)) if matches!(lit.value, ValueObj::Str(_)) => Ok(()),
+                other => Err(LowerErrors::from(LowerError::py_inline_literal_error(
+                    self.input().clone(),
+                    line!() as usize,
+                    other.map_or(call.loc(), |expr| expr.loc()),
+                    self.module.context.caused_by(),
+                ))),
+            },
             Some(OperationKind::Cast) => {
                 self.warns.push(LowerWarning::use_cast_warning(
                     self.input().clone(),
@@ -1274,51 +1919,34 @@ impl ASTLowerer {
             .params
             .iter()
             .partition(|(_, vi)| !vi.kind.has_default());
-        #[cfg(not(feature = "py_compat"))]
-        let (var_params, non_default_params) = {
-            let (var_params, non_default_params): (Vec<_>, Vec<_>) = non_default_params
-                .into_iter()
-                .partition(|(_, vi)| vi.kind.is_var_params());
-            // vi.t: `[T; _]`
-            // pt: `name: T`
-            let var_params = var_params.get(0).map(|(name, vi)| {
-                ParamTy::pos_or_kw(
-                    name.as_ref().map(|n| n.inspect().clone()),
-                    vi.t.inner_ts().remove(0),
-                )
-            });
-            (var_params, non_default_params.into_iter())
-        };
-        #[cfg(feature = "py_compat")]
-        let (var_params, non_default_params) = {
-            let (var_params, non_default_params): (Vec<_>, Vec<_>) = non_default_params
-                .into_iter()
-                .partition(|(_, vi)| vi.kind.is_var_params());
-            let var_params = var_params.get(0).map(|(name, vi)| {
-                ParamTy::pos_or_kw(
-                    name.as_ref().map(|n| n.inspect().clone()),
-                    vi.t.inner_ts().remove(0),
-                )
-            });
-            let non_default_params = non_default_params.into_iter().filter(|(name, _)| {
-                params
-                    .non_defaults
-                    .iter()
-                    .any(|nd| nd.name() == name.as_ref())
-            });
-            (var_params, non_default_params)
-        };
+        let (var_params, non_default_params): (Vec<_>, Vec<_>) = non_default_params
+            .into_iter()
+            .partition(|(_, vi)| vi.kind.is_var_params());
+        // vi.t: `[T; _]`
+        // pt: `name: T`
+        let var_params = var_params.get(0).map(|(name, vi)| {
+            ParamTy::pos_or_kw(
+                name.as_ref().map(|n| n.inspect().clone()),
+                vi.t.inner_ts().remove(0),
+            )
+        });
+        let py_compat = self.cfg.py_compat;
         let non_default_param_tys = non_default_params
+            .into_iter()
+            .filter(|(name, _)| {
+                !py_compat
+                    || params
+                        .non_defaults
+                        .iter()
+                        .any(|nd| nd.name() == name.as_ref())
+            })
             .map(|(name, vi)| {
                 ParamTy::pos_or_kw(name.as_ref().map(|n| n.inspect().clone()), vi.t.clone())
             })
             .collect();
-        #[cfg(not(feature = "py_compat"))]
-        let default_params = default_params.into_iter();
-        #[cfg(feature = "py_compat")]
-        let default_params = default_params
-            .into_iter()
-            .filter(|(name, _)| params.defaults.iter().any(|d| d.name() == name.as_ref()));
+        let default_params = default_params.into_iter().filter(|(name, _)| {
+            !py_compat || params.defaults.iter().any(|d| d.name() == name.as_ref())
+        });
         let default_param_tys = default_params
             .map(|(name, vi)| ParamTy::kw(name.as_ref().unwrap().inspect().clone(), vi.t.clone()))
             .collect();
@@ -1363,7 +1991,9 @@ impl ASTLowerer {
 
     fn lower_def(&mut self, def: ast::Def) -> LowerResult<hir::Def> {
         log!(info "entered {}({})", fn_name!(), def.sig);
-        if def.def_kind().is_class_or_trait() && self.module.context.kind != ContextKind::Module {
+        if (def.def_kind().is_class_or_trait() || def.body.is_enum())
+            && self.module.context.kind != ContextKind::Module
+        {
             self.module
                 .context
                 .decls
@@ -1455,7 +2085,27 @@ impl ASTLowerer {
         if let Err(errs) = self.module.context.preregister(&body.block) {
             self.errs.extend(errs);
         }
-        match self.lower_block(body.block) {
+        // The body of a type alias can contain type-only syntax (e.g. `[T; _]`) or refer to the alias itself.
+        // Such a body cannot be checked as an expression, so it is lowered like a type specification
+        let is_type_alias = sig.is_const()
+            && sig
+                .ident()
+                .and_then(|ident| self.module.context.rec_get_const_obj(ident.inspect()))
+                .is_some_and(|obj| match obj {
+                    ValueObj::Type(t) => t.typ().is_union_type() || t.typ().is_intersection_type(),
+                    _ => false,
+                });
+        let alias_body = is_type_alias.then(|| body.block.clone());
+        let errs_len = self.errs.len();
+        let res = self.lower_block(body.block);
+        let res = match alias_body {
+            Some(block) if self.errs.len() > errs_len => {
+                self.errs.ref_mut_payload().truncate(errs_len);
+                self.fake_lower_block(block)
+            }
+            _ => res,
+        };
+        match res {
             Ok(block) => {
                 let found_body_t = block.ref_t();
                 let outer = self.module.context.outer.as_ref().unwrap();
@@ -1496,6 +2146,7 @@ impl ASTLowerer {
                         }
                     }
                 }
+                self.check_py_inline_typed(&block, sig.t_spec.is_some());
                 let vi = self.module.context.outer.as_mut().unwrap().assign_var_sig(
                     &sig,
                     found_body_t,
@@ -1510,6 +2161,9 @@ impl ASTLowerer {
                 } else {
                     None
                 };
+                if let (Some(t_spec), Some(value)) = (&t_spec, block.last()) {
+                    self.check_dynamic_coercion(value, &t_spec.spec_t);
+                }
                 let sig = hir::VarSignature::new(ident, t_spec);
                 let body = hir::DefBody::new(body.op, block, body.id);
                 Ok(hir::Def::new(hir::Signature::Var(sig), body))
@@ -1526,6 +2180,49 @@ impl ASTLowerer {
         }
     }
 
+    /// `py_inline` cannot infer the type of the embedded code, so it must be annotated.
+    fn check_py_inline_typed(&mut self, block: &hir::Block, annotated: bool) {
+        if annotated {
+            return;
+        }
+        if let Some(hir::Expr::Call(call)) = block.last() {
+            if call.additional_operation() == Some(OperationKind::PyInline) {
+                self.errs.push(LowerError::py_inline_untyped_error(
+                    self.cfg.input.clone(),
+                    line!() as usize,
+                    call.loc(),
+                    self.module.context.caused_by(),
+                ));
+            }
+        }
+    }
+
+    /// `Dynamic` values are accepted by any type, but they are not implicitly converted in `--strict` mode.
+    fn check_dynamic_coercion(&mut self, value: &hir::Expr, expected: &Type) {
+        if !value.ref_t().is_dynamic()
+            || expected.is_dynamic()
+            || expected.is_failure()
+            || self.module.context.subtype_of(&Type::Obj, expected)
+            || expected.is_unbound_var()
+            || expected.has_qvar()
+        {
+            return;
+        }
+        let severity = self
+            .module
+            .context
+            .severity_of(LenientCheck::DynamicCoercion);
+        if severity.is_error() {
+            self.errs.push(LowerError::dynamic_coercion_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                value.loc(),
+                self.module.context.caused_by(),
+                expected,
+            ));
+        }
+    }
+
     // NOTE: Note that this is in the inner scope while being called.
     fn lower_subr_def(
         &mut self,
@@ -1553,6 +2250,7 @@ impl ASTLowerer {
                 }
                 match self.lower_block(body.block) {
                     Ok(block) => {
+                        self.check_py_inline_typed(&block, sig.return_t_spec.is_some());
                         let found_body_t = self.module.context.squash_tyvar(block.t());
                         let vi = match self.module.context.outer.as_mut().unwrap().assign_subr(
                             &sig,
@@ -1566,6 +2264,9 @@ impl ASTLowerer {
                                 vi
                             }
                         };
+                        if vi.is_overloaded() {
+                            self.register_overloads(&sig.ident, &block, &vi);
+                        }
                         let ident = hir::Identifier::new(sig.ident, None, vi);
                         let ret_t_spec = if let Some(ts) = sig.return_t_spec {
                             let spec_t = self.module.context.instantiate_typespec(&ts.t_spec)?;
@@ -1574,6 +2275,9 @@ impl ASTLowerer {
                         } else {
                             None
                         };
+                        if let (Some(t_spec), Some(value)) = (&ret_t_spec, block.last()) {
+                            self.check_dynamic_coercion(value, &t_spec.spec_t);
+                        }
                         let sig = hir::SubrSignature::new(ident, sig.bounds, params, ret_t_spec);
                         let body = hir::DefBody::new(body.op, block, body.id);
                         Ok(hir::Def::new(hir::Signature::Subr(sig), body))
@@ -1639,12 +2343,79 @@ impl ASTLowerer {
         }
     }
 
+    /// `@Overload` definitions are desugared into a `match` call.
+    /// The branch types are collected into an intersection type, so that the calls are resolved by `resolve_overload`.
+    /// The definition itself keeps the union type (the branches are dispatched at runtime by `match`).
+    fn register_overloads(&mut self, ident: &ast::Identifier, block: &hir::Block, vi: &VarInfo) {
+        let Some(hir::Expr::Call(call)) = block.first() else {
+            return;
+        };
+        let arity = vi.t.non_default_params().map_or(0, |params| params.len());
+        let mut overloaded = None;
+        // the first argument is the matched object
+        for arg in call.args.pos_args.iter().skip(1) {
+            let hir::Expr::Lambda(branch) = &arg.expr else {
+                return;
+            };
+            let Some(t) = Self::overload_member_t(branch.ref_t(), arity) else {
+                return;
+            };
+            overloaded = Some(match overloaded {
+                Some(acc) => and(acc, t),
+                None => t,
+            });
+        }
+        if let Some(t) = overloaded {
+            let vi = VarInfo { t, ..vi.clone() };
+            self.module
+                .context
+                .outer
+                .as_mut()
+                .unwrap()
+                .locals
+                .insert(ident.name.clone(), vi);
+        }
+    }
+
+    /// `(_: (Int, Str)) -> R` (a branch of a multi-parameter definition) -> `(Int, Str) -> R`
+    fn overload_member_t(branch_t: &Type, arity: usize) -> Option<Type> {
+        let Type::Subr(subr) = branch_t else {
+            return None;
+        };
+        if arity <= 1 {
+            return Some(branch_t.clone());
+        }
+        let Type::Poly { name, params } = subr.non_default_params.first()?.typ() else {
+            return None;
+        };
+        let (true, Some(TyParam::Array(elems))) = (&name[..] == "Tuple", params.first()) else {
+            return None;
+        };
+        let mut non_default_params = vec![];
+        for elem in elems.iter() {
+            let t = <&Type>::try_from(elem).ok()?;
+            non_default_params.push(ParamTy::Pos(t.clone()));
+        }
+        Some(subr_t(
+            subr.kind,
+            non_default_params,
+            None,
+            vec![],
+            *subr.return_t.clone(),
+        ))
+    }
+
     fn lower_class_def(&mut self, class_def: ast::ClassDef) -> LowerResult<hir::ClassDef> {
         log!(info "entered {}({class_def})", fn_name!());
         let mut hir_def = self.lower_def(class_def.def)?;
         let mut hir_methods = hir::Block::empty();
         for mut methods in class_def.methods_list.into_iter() {
             let (class, impl_trait) = self.get_class_and_impl_trait(&methods.class)?;
+            // the stubs of unimplemented trait members are inserted after the block
+            let stub_pos = methods.ln_end().map(|ln| {
+                let indent = methods.attrs.iter().next().and_then(|attr| attr.col_begin());
+                (ln + 1, indent.unwrap_or(4))
+            });
             // assume the class has implemented the trait, regardless of whether the implementation is correct
             if let Some((trait_, trait_loc)) = &impl_trait {
                 self.register_trait_impl(&class, trait_, *trait_loc)?;
@@ -1681,6 +2452,7 @@ impl ASTLowerer {
                             self.pop_append_errs();
                             errs
                         })?;
+                        self.module.context.register_const_method(def);
                         if let Some(ident) = def.sig.ident() {
                             if self
                                 .module
@@ -1702,10 +2474,13 @@ impl ASTLowerer {
                     ast::ClassAttr::Decl(_) | ast::ClassAttr::Doc(_) => {}
                 }
             }
+            let mut docs = vec![];
             for attr in methods.attrs.into_iter() {
+                let len = hir_methods.len();
                 match attr {
                     ast::ClassAttr::Def(def) => match self.lower_def(def) {
                         Ok(def) => {
+                            self.check_method_receiver(&def);
                             hir_methods.push(hir::Expr::Def(def));
                         }
                         Err(errs) => {
@@ -1729,6 +2504,10 @@ impl ASTLowerer {
                         }
                     },
                 }
+                match hir_methods.get_mut(len) {
+                    Some(chunk) => self.store_docs(&mut docs, chunk),
+                    None => docs.clear(),
+                }
             }
             if let Err(errs) = self.module.context.check_decls() {
                 self.errs.extend(errs);
@@ -1738,20 +2517,15 @@ impl ASTLowerer {
             } else {
                 self.check_override(&class, None);
             }
-            if let Err(err) = self.check_trait_impl(impl_trait, &class) {
+            if let Err(err) = self.check_trait_impl(impl_trait, &class, stub_pos) {
                 self.errs.push(err);
             }
             self.check_collision_and_push(class);
         }
         let class = self.module.context.gen_type(&hir_def.sig.ident().raw);
         let Some((_, class_ctx)) = self.module.context.get_nominal_type_ctx(&class) else {
-            return Err(LowerErrors::from(LowerError::type_not_found(
-                self.cfg.input.clone(),
-                line!() as usize,
-                hir_def.sig.loc(),
-                self.module.context.caused_by(),
-                &class,
-            )));
+            // the class definition could not be evaluated (already reported in `preregister`)
+            return Err(LowerErrors::empty());
         };
         let Some(class_type) = self.module.context.rec_get_const_obj(hir_def.sig.ident().inspect()) else {
             return unreachable_error!(LowerErrors, LowerError, self);
@@ -1765,6 +2539,15 @@ impl ASTLowerer {
         if let Some(sup_type) = call.args.get_left_or_key("Super") {
             Self::check_inheritable(&self.cfg, &mut self.errs, type_obj, sup_type, &hir_def.sig);
         }
+        if !type_obj.is_abstract() {
+            Self::check_abstract_methods(
+                &self.cfg,
+                &mut self.errs,
+                &self.module.context,
+                &class,
+                &hir_def.sig,
+            );
+        }
         let Some(__new__) = class_ctx.get_current_scope_var(&VarName::from_static("__new__")).or(class_ctx.get_current_scope_var(&VarName::from_static("__call__"))) else {
             return unreachable_error!(LowerErrors, LowerError, self);
         };
@@ -1899,7 +2682,9 @@ impl ASTLowerer {
                     ast::ClassAttr::Decl(_) | ast::ClassAttr::Doc(_) => {}
                 }
             }
+            let mut docs = vec![];
             for attr in methods.attrs.into_iter() {
+                let len = hir_methods.len();
                 match attr {
                     ast::ClassAttr::Def(def) => match self.lower_def(def) {
                         Ok(def) => {
@@ -1926,6 +2711,10 @@ impl ASTLowerer {
                         }
                     },
                 }
+                match hir_methods.get_mut(len) {
+                    Some(chunk) => self.store_docs(&mut docs, chunk),
+                    None => docs.clear(),
+                }
             }
             if let Err(errs) = self.module.context.check_decls() {
                 self.errs.extend(errs);
@@ -1939,10 +2728,53 @@ impl ASTLowerer {
         log!(info "entered {}({redef})", fn_name!());
         let attr = self.lower_acc(redef.attr)?;
         let expr = self.lower_expr(*redef.expr)?;
-        if let Err(err) =
-            self.var_result_t_check(&attr, &Str::from(attr.show()), attr.ref_t(), expr.ref_t())
-        {
-            self.errs.push(err);
+        // `obj.x = v` calls the setter `x!` if `x` is a property
+        let expect = if let hir::Accessor::Attr(prop) = &attr {
+            if prop.ident.vi.is_property() {
+                let setter = self
+                    .module
+                    .context
+                    .get_property_setter(prop.obj.ref_t(), prop.ident.inspect());
+                if setter.is_none() {
+                    self.errs.push(LowerError::read_only_property_error(
+                        self.cfg.input.clone(),
+                        line!() as usize,
+                        prop.ident.loc(),
+                        self.module.context.caused_by(),
+                        prop.ident.inspect(),
+                    ));
+                }
+                setter.and_then(|setter| {
+                    let params = setter.t.non_default_params()?;
+                    params.get(1).map(|value| value.typ().clone())
+                })
+            } else {
+                // only the ASTs converted from Python code reassign the other attributes
+                let from_py = self
+                    .cfg
+                    .input
+                    .path()
+                    .is_some_and(|path| path.extension().is_some_and(|ext| ext == "py"));
+                if !from_py {
+                    self.errs.push(LowerError::reassign_error(
+                        self.cfg.input.clone(),
+                        line!() as usize,
+                        prop.ident.loc(),
+                        self.module.context.caused_by(),
+                        prop.ident.inspect(),
+                    ));
+                }
+                Some(attr.ref_t().clone())
+            }
+        } else {
+            Some(attr.ref_t().clone())
+        };
+        if let Some(expect) = expect {
+            if let Err(err) =
+                self.var_result_t_check(&attr, &Str::from(attr.show()), &expect, expr.ref_t())
+            {
+                self.errs.push(err);
+            }
         }
         Ok(hir::ReDef::new(attr, hir::Block::new(vec![expr])))
     }
@@ -1953,6 +2785,7 @@ impl ASTLowerer {
         trait_: &Type,
         trait_loc: &impl Locational,
     ) -> LowerResult<()> {
+        self.module.context.invalidate_attr_cache();
         // TODO: polymorphic trait
         if let Some(mut impls) = self
             .module
@@ -2025,6 +2858,77 @@ impl ASTLowerer {
         }
     }
 
+    /// A class that is not `@Abstract` must override all `@Abstract` methods
+    fn check_abstract_methods(
+        cfg: &ErgConfig,
+        errs: &mut LowerErrors,
+        ctx: &Context,
+        class: &Type,
+        sig: &hir::Signature,
+    ) {
+        for (name, declared_in) in ctx.get_unimplemented_abstract_methods(class) {
+            let hint = switch_lang!(
+                "japanese" => format!("{name}をオーバーライドするか、{class}を@Abstractにしてください"),
+                "simplified_chinese" => format!("请重写{name}或将{class}设为@Abstract"),
+                "traditional_chinese" => format!("請重寫{name}或將{class}設為@Abstract"),
+                "english" => format!("override {name} or make {class} @Abstract"),
+            );
+            errs.push(LowerError::trait_member_not_defined_error(
+                cfg.input.clone(),
+                line!() as usize,
+                ctx.caused_by(),
+                name.inspect(),
+                &declared_in,
+                class,
+                Some(hint),
+                sig.ident().loc(),
+            ));
+        }
+    }
+
+    /// `self` must be the first parameter of a method,
+    /// and class methods take the class as the first parameter instead
+    fn check_method_receiver(&mut self, def: &hir::Def) {
+        let hir::Signature::Subr(sig) = &def.sig else {
+            return;
+        };
+        let kind = sig.ident.vi.method_kind();
+        let params = sig
+            .params
+            .non_defaults
+            .iter()
+            .map(|param| (param.inspect(), param.loc()))
+            .chain(
+                sig.params
+                    .var_params
+                    .iter()
+                    .map(|param| (param.inspect(), param.loc())),
+            )
+            .chain(
+                sig.params
+                    .defaults
+                    .iter()
+                    .map(|param| (param.inspect(), param.loc())),
+            )
+            .collect::<Vec<_>>();
+        let self_pos = params
+            .iter()
+            .position(|(name, _)| name.is_some_and(|name| &name[..] == "self"));
+        let loc = match (kind, self_pos) {
+            (MethodKind::Class, Some(pos)) => params[pos].1,
+            (MethodKind::Class, None) if sig.params.non_defaults.is_empty() => sig.ident.loc(),
+            (_, Some(pos)) if pos > 0 => params[pos].1,
+            _ => return,
+        };
+        self.errs.push(LowerError::self_param_error(
+            self.cfg.input.clone(),
+            line!() as usize,
+            loc,
+            self.module.context.caused_by(),
+            kind,
+        ));
+    }
+
     fn check_override(&mut self, class: &Type, impl_trait: Option<&Type>) {
         if let Some(sups) = self.module.context.get_nominal_super_type_ctxs(class) {
             // exclude the first one because it is the class itself
@@ -2066,6 +2970,7 @@ impl ASTLowerer {
         &mut self, //: methods context
         impl_trait: Option<(Type, &TypeSpecWithOp)>,
         class: &Type,
+        stub_pos: Option<(u32, u32)>,
     ) -> SingleLowerResult<()> {
         if let Some((impl_trait, t_spec)) = impl_trait {
             let impl_trait = impl_trait.normalize();
@@ -2076,7 +2981,7 @@ impl ASTLowerer {
                 .unwrap()
                 .get_nominal_type_ctx(&impl_trait)
             {
-                self.check_methods_compatibility(&impl_trait, class, typ_ctx, t_spec)
+                self.check_methods_compatibility(&impl_trait, class, typ_ctx, t_spec, stub_pos)
             } else {
                 return Err(LowerError::no_type_error(
                     self.cfg.input.clone(),
@@ -2112,9 +3017,11 @@ impl ASTLowerer {
         class: &Type,
         (trait_type, trait_ctx): (&Type, &Context),
         t_spec: &TypeSpecWithOp,
+        stub_pos: Option<(u32, u32)>,
     ) -> (Set<&VarName>, CompileErrors) {
         let mut errors = CompileErrors::empty();
         let mut unverified_names = self.module.context.locals.keys().collect::<Set<_>>();
+        let mut missing = vec![];
         for (decl_name, decl_vi) in trait_ctx.decls.iter() {
             if let Some((name, vi)) = self.module.context.get_var_kv(decl_name.inspect()) {
                 let def_t = &vi.t;
@@ -2138,22 +3045,77 @@ impl ASTLowerer {
                     ));
                 }
             } else {
-                errors.push(LowerError::trait_member_not_defined_error(
-                    self.cfg.input.clone(),
-                    line!() as usize,
-                    self.module.context.caused_by(),
-                    decl_name.inspect(),
-                    impl_trait,
-                    class,
-                    None,
-                    t_spec.loc(),
-                ));
+                let decl_t = decl_vi
+                    .t
+                    .clone()
+                    .replace(trait_type, impl_trait)
+                    .replace(impl_trait, class);
+                let stub = Self::gen_member_stub(decl_name.inspect(), &decl_t, class);
+                missing.push((&decl_vi.def_loc.loc, &decl_name.inspect()[..], stub));
             }
         }
+        // in the order of declaration
+        missing.sort_by_key(|(loc, _, _)| (loc.ln_begin(), loc.col_begin()));
+        let missing = missing
+            .into_iter()
+            .map(|(_, name, stub)| (name, stub))
+            .collect::<Vec<_>>();
+        // reported at once, so that all the stubs can be inserted
+        if !missing.is_empty() {
+            errors.push(LowerError::trait_members_not_defined_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                self.module.context.caused_by(),
+                &missing,
+                impl_trait,
+                class,
+                stub_pos,
+                t_spec.loc(),
+            ));
+        }
         (unverified_names, errors)
     }
 
+    /// e.g. `scale: (self: C, k: Float) -> Float` -> `scale(self, k: Float): Float = unreachable()`
+    fn gen_member_stub(name: &str, t: &Type, class: &Type) -> String {
+        // types defined in the same module are referred to by their local names
+        let qual_name = class.qual_name();
+        let prefix = qual_name.trim_end_matches(&class.local_name()[..]);
+        let fmt_t = |t: &Type| {
+            if prefix.is_empty() {
+                t.to_string()
+            } else {
+                t.to_string().replace(prefix, "")
+            }
+        };
+        let Type::Subr(subr) = t else {
+            return format!("{name}: {} = unreachable()", fmt_t(t));
+        };
+        let mut params = vec![];
+        for (i, pt) in subr.non_default_params.iter().enumerate() {
+            match pt.name() {
+                Some(param) if &param[..] == "self" => params.push(param.to_string()),
+                Some(param) => params.push(format!("{param}: {}", fmt_t(pt.typ()))),
+                None => params.push(format!("arg{i}: {}", fmt_t(pt.typ()))),
+            }
+        }
+        if let Some(pt) = subr.var_params.as_ref() {
+            let param = pt.name().map_or("args", |name| &name[..]);
+            params.push(format!("*{param}: {}", fmt_t(pt.typ())));
+        }
+        for pt in subr.default_params.iter() {
+            let param = pt.name().map_or("_", |name| &name[..]);
+            params.push(format!("{param}: {} := unreachable()", fmt_t(pt.typ())));
+        }
+        format!(
+            "{name}({}): {} = unreachable()",
+            params.join(", "),
+            fmt_t(&subr.return_t)
+        )
+    }
+
     fn check_collision_and_push(&mut self, class: Type) {
+        self.module.context.invalidate_attr_cache();
         let methods = self.module.context.pop();
         let Some((_, class_root)) = self
             .module
@@ -2193,6 +3155,7 @@ impl ASTLowerer {
     }
 
     fn push_patch(&mut self) {
+        self.module.context.invalidate_attr_cache();
         let methods = self.module.context.pop();
         let ContextKind::PatchMethodDefs(base) = &methods.kind else { unreachable!() };
         let patch_name = *methods.name.split_with(&["::", "."]).last().unwrap();
@@ -2238,7 +3201,7 @@ impl ASTLowerer {
             hir::Expr::Call(mut call) => match call.obj.show_acc().as_ref().map(|s| &s[..]) {
                 Some("Class" | "Trait") => call.args.remove_left_or_key("Requirement"),
                 Some("Inherit") => call.args.remove_left_or_key("Super"),
-                Some("Inheritable") => {
+                Some("Inheritable" | "Abstract" | "Derive") => {
                     Self::get_require_or_sup_or_base(call.args.remove_left_or_key("Class").unwrap())
                 }
                 Some("Structural") => call.args.remove_left_or_key("Type"),
@@ -2324,7 +3287,7 @@ impl ASTLowerer {
                 &self.cfg.input,
                 &self.module.context,
             )
-            .none_or_else(|| {
+            .or_fallback(|| {
                 self.module.context.rec_get_var_info(
                     &ident,
                     AccessKind::Name,
@@ -2421,26 +3384,194 @@ impl ASTLowerer {
             ast::Expr::PatchDef(defs) => Ok(hir::Expr::PatchDef(self.lower_patch_def(defs)?)),
             ast::Expr::ReDef(redef) => Ok(hir::Expr::ReDef(self.lower_redef(redef)?)),
             ast::Expr::TypeAscription(tasc) => Ok(hir::Expr::TypeAsc(self.lower_decl(tasc)?)),
+            ast::Expr::Call(call) if call.additional_operation() == Some(OperationKind::Export) => {
+                Ok(hir::Expr::Compound(self.lower_export(call)?))
+            }
             other => self.lower_expr(other),
         }
     }
 
+    /// Lowers `chunk` again against the module context, which has already lowered the whole module
+    /// (e.g. a definition edited in the language server). The other chunks are not checked.
+    /// If `chunk` is a definition, the previous one is unregistered and the new one is preregistered first.
+    ///
+    /// Returns the resolved chunk (if lowering succeeded) and the errors & warnings found in it.
+    pub fn relower_chunk(
+        &mut self,
+        chunk: ast::Expr,
+    ) -> (Option<hir::Expr>, LowerErrors, LowerWarnings) {
+        log!(info "entered {}", fn_name!());
+        if let ast::Expr::Def(def) = &chunk {
+            if let Some(name) = def.sig.name_as_str() {
+                self.unregister(name);
+            }
+            if let Err(errs) = self.module.context.preregister_def(def) {
+                self.errs.extend(errs);
+            }
+        }
+        let chunk = match self.lower_chunk(chunk) {
+            Ok(chunk) => {
+                let hir = HIR::new(
+                    self.module.context.name.clone(),
+                    hir::Module::new(vec![chunk]),
+                );
+                let hir = self.module.context.resolve(hir).unwrap_or_else(|(hir, errs)| {
+                    self.errs.extend(errs);
+                    hir
+                });
+                hir.module.into_iter().next()
+            }
+            Err(errs) => {
+                self.errs.extend(errs);
+                None
+            }
+        };
+        (
+            chunk,
+            LowerErrors::from(self.errs.take_all()),
+            LowerWarnings::from(self.warns.take_all()),
+        )
+    }
+
+    /// `export foo` re-exports all the public names of the module `foo`,
+    /// and `export foo.bar` re-exports `bar` of `foo`.
+    /// Each name is defined as `.bar = foo.bar`, so the module works as a facade.
+    fn lower_export(&mut self, call: ast::Call) -> LowerResult<hir::Block> {
+        log!(info "entered {}", fn_name!());
+        if self.module.context.kind != ContextKind::Module {
+            return Err(LowerErrors::from(LowerError::inner_export_error(
+                self.cfg.input.clone(),
+                line!() as usize,
+                call.loc(),
+                self.module.context.caused_by(),
+            )));
+        }
+        let mut defs = vec![];
+        let (pos_args, ..) = call.args.deconstruct();
+        for arg in pos_args.into_iter().map(|arg| arg.expr) {
+            let line = arg.ln_begin().unwrap_or(0);
+            let target = self.lower_expr(arg.clone())?;
+            if target.ref_t().is_module() {
+                for name in self.get_public_names(target.ref_t()) {
+                    let attr = ast::Identifier::public_with_line(DOT, name, line);
+                    defs.push(Self::reexport_def(arg.clone().attr_expr(attr), line));
+                }
+                continue;
+            }
+            match &target {
+                hir::Expr::Accessor(hir::Accessor::Attr(attr)) if attr.obj.ref_t().is_module() => {
+                    defs.push(Self::reexport_def(arg, line));
+                }
+                _ => {
+                    let found = match &target {
+                        hir::Expr::Accessor(hir::Accessor::Attr(attr)) => attr.obj.ref_t(),
+                        _ => target.ref_t(),
+                    };
+                    return Err(LowerErrors::from(LowerError::export_target_error(
+                        self.cfg.input.clone(),
+                        line!() as usize,
+                        target.loc(),
+                        self.module.context.caused_by(),
+                        found,
+                    )));
+                }
+            }
+        }
+        let mut block = Vec::with_capacity(defs.len());
+        for def in defs {
+            block.push(hir::Expr::Def(self.lower_def(def)?));
+        }
+        Ok(hir::Block::new(block))
+    }
+
+    /// `foo.bar` -> `.bar = foo.bar`
+    fn reexport_def(attr: ast::Expr, line: u32) -> ast::Def {
+        let name = match &attr {
+            ast::Expr::Accessor(ast::Accessor::Attr(attr)) => attr.ident.inspect().clone(),
+            _ => unreachable!(),
+        };
+        let ident = ast::Identifier::public_with_line(DOT, name, line);
+        let sig = ast::VarSignature::new(ast::VarPattern::Ident(ident), None);
+        ast::Def::new(ast::Signature::Var(sig), ast::DefBody::new_single(attr))
+    }
+
+    /// The public names of the module (in the order of the definitions).
+    fn get_public_names(&self, mod_t: &Type) -> Vec<Str> {
+        let Some(ctx) = self.module.context.get_mod_with_t(mod_t) else {
+            return vec![];
+        };
+        let mut names = ctx
+            .locals
+            .iter()
+            .chain(
+                ctx.decls
+                    .iter()
+                    .filter(|(name, _)| !ctx.locals.contains_key(*name)),
+            )
+            .filter(|(name, vi)| vi.vis.is_public() && !name.inspect().starts_with("__"))
+            .map(|(name, vi)| (vi.def_loc.loc.ln_begin(), name.inspect().clone()))
+            .collect::<Vec<_>>();
+        names.sort_by(|(l_ln, l), (r_ln, r)| l_ln.cmp(r_ln).then_with(|| l[..].cmp(&r[..])));
+        names.into_iter().map(|(_, name)| name).collect()
+    }
+
     fn lower_block(&mut self, ast_block: ast::Block) -> LowerResult<hir::Block> {
         log!(info "entered {}", fn_name!());
         let mut hir_block = Vec::with_capacity(ast_block.len());
+        let mut docs = vec![];
         for chunk in ast_block.into_iter() {
-            let chunk = match self.lower_chunk(chunk) {
+            let mut chunk = match self.lower_chunk(chunk) {
                 Ok(chunk) => chunk,
                 Err(errs) => {
                     self.errs.extend(errs);
                     hir::Expr::Dummy(hir::Dummy::new(vec![]))
                 }
             };
+            self.store_docs(&mut docs, &mut chunk);
             hir_block.push(chunk);
         }
         Ok(hir::Block::new(hir_block))
     }
 
+    /// Stores the doc comments just before a definition (or a declaration) in the `VarInfo` of it.
+    /// `docs` are the doc comments found so far in the block.
+    pub(crate) fn store_docs(&mut self, docs: &mut Vec<Str>, chunk: &mut hir::Expr) {
+        let ident = match chunk {
+            hir::Expr::Lit(lit) if lit.is_doc_comment() => {
+                if let ValueObj::Str(doc) = &lit.value {
+                    docs.insert(0, doc.clone());
+                }
+                return;
+            }
+            hir::Expr::Def(def) => def.sig.ident_mut(),
+            hir::Expr::ClassDef(class_def) => class_def.sig.ident_mut(),
+            hir::Expr::PatchDef(patch_def) => patch_def.sig.ident_mut(),
+            hir::Expr::TypeAsc(tasc) => match tasc.expr.as_mut() {
+                hir::Expr::Accessor(hir::Accessor::Ident(ident)) => ident,
+                _ => {
+                    docs.clear();
+                    return;
+                }
+            },
+            _ => {
+                docs.clear();
+                return;
+            }
+        };
+        if docs.is_empty() {
+            return;
+        }
+        let docs = mem::take(docs);
+        if let Some(vi) = self
+            .module
+            .context
+            .get_mut_current_scope_var(&ident.raw.name)
+        {
+            vi.docs = docs.clone();
+        }
+        ident.vi.docs = docs;
+    }
+
     fn lower_dummy(&mut self, ast_dummy: ast::Dummy) -> LowerResult<hir::Dummy> {
         log!(info "entered {}", fn_name!());
         let mut hir_dummy = Vec::with_capacity(ast_dummy.len());
@@ -2451,8 +3582,12 @@ impl ASTLowerer {
         Ok(hir::Dummy::new(hir_dummy))
     }
 
-    fn return_incomplete_artifact(&mut self, hir: HIR) -> IncompleteArtifact {
-        self.module.context.clear_invalid_vars();
+    fn return_incomplete_artifact(&mut self, hir: HIR, mode: &str) -> IncompleteArtifact {
+        if mode == "eval" {
+            self.rollback();
+        } else {
+            self.module.context.clear_invalid_vars();
+        }
         IncompleteArtifact::new(
             Some(hir),
             LowerErrors::from(self.errs.take_all()),
@@ -2472,6 +3607,10 @@ impl ASTLowerer {
             .map_err(|errs| {
                 IncompleteArtifact::new(None, errs, LowerWarnings::from(self.warns.take_all()))
             })?;
+        // REPL evaluations are transactional: a failed one leaves the context untouched
+        if mode == "eval" {
+            self.snapshot();
+        }
         if mode == "declare" {
             let hir = self.declare_module(ast);
             if self.errs.is_empty() {
@@ -2483,19 +3622,22 @@ impl ASTLowerer {
                 ));
             } else {
                 log!(err "the declaring process has failed.");
-                return Err(self.return_incomplete_artifact(hir));
+                return Err(self.return_incomplete_artifact(hir, mode));
             }
         }
         let mut module = hir::Module::with_capacity(ast.module.len());
         if let Err(errs) = self.module.context.preregister(ast.module.block()) {
             self.errs.extend(errs);
         }
+        let mut docs = vec![];
         for chunk in ast.module.into_iter() {
             match self.lower_chunk(chunk) {
-                Ok(chunk) => {
+                Ok(mut chunk) => {
+                    self.store_docs(&mut docs, &mut chunk);
                     module.push(chunk);
                 }
                 Err(errs) => {
+                    docs.clear();
                     self.errs.extend(errs);
                 }
             }
@@ -2514,10 +3656,11 @@ impl ASTLowerer {
             Err((hir, errs)) => {
                 self.errs.extend(errs);
                 log!(err "the resolving process has failed. errs:  {}", self.errs.len());
-                return Err(self.return_incomplete_artifact(hir));
+                return Err(self.return_incomplete_artifact(hir, mode));
             }
         };
         self.warn_implicit_union(&hir);
+        self.check_obj_fallback(&hir);
         self.warn_unused_expr(&hir.module, mode);
         self.check_doc_comments(&hir);
         self.warn_unused_local_vars(mode);
@@ -2540,7 +3683,7 @@ impl ASTLowerer {
             ))
         } else {
             log!(err "the AST lowering process has failed. errs: {}", self.errs.len());
-            Err(self.return_incomplete_artifact(hir))
+            Err(self.return_incomplete_artifact(hir, mode))
         }
     }
 }
@@ -7,7 +7,15 @@ use erg_common::spawn::exec_new_thread;
 use erg_common::traits::{ExitStatus, Runnable};
 
 use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::decl_gen::DeclGenerator;
+use erg_compiler::decl_test::DeclTester;
+use erg_compiler::dump::Dumper;
+use erg_compiler::fix::Fixer;
 use erg_compiler::lower::ASTLowerer;
+use erg_compiler::migrate::Migrator;
+use erg_compiler::module_graph::GraphPrinter;
+use erg_compiler::nil_audit::NilAuditor;
+use erg_compiler::pyi_gen::PyiGenerator;
 use erg_compiler::transpile::Transpiler;
 use erg_compiler::ty::deserialize::Deserializer;
 use erg_compiler::Compiler;
@@ -23,6 +31,14 @@ fn run() {
         TypeCheck => ASTLowerer::run(cfg),
         FullCheck => HIRBuilder::run(cfg),
         Transpile => Transpiler::run(cfg),
+        Migrate => Migrator::run(cfg),
+        Test if cfg.test_decls => DeclTester::run(cfg),
+        Audit => NilAuditor::run(cfg),
+        Declare => DeclGenerator::run(cfg),
+        Stub => PyiGenerator::run(cfg),
+        Fix => Fixer::run(cfg),
+        Dump => Dumper::run(cfg),
+        Graph => GraphPrinter::run(cfg),
         Compile | Execute => Compiler::run(cfg),
         Read => Deserializer::run(cfg),
         other => {
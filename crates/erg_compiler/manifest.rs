@@ -0,0 +1,115 @@
+//! reads the entries of the project manifest (`package.er`)
+//!
+//! ```erg
+//! # package.er
+//! py_compat = ["src/legacy.er", "src/compat/"]
+//! prelude = "src/prelude.er"
+//! banned_builtins = ["exec", "eval"]
+//! pure = ["src/plugins/"]
+//! no_io = ["src/sandbox.er"]
+//! ```
+//!
+//! The public names of the prelude are visible in every module of the project, like the builtins.
+//! `banned_builtins` hides the builtins through the prelude overlay, so it requires a prelude (see `Context::build_prelude`).
+use std::path::{Path, PathBuf};
+
+use erg_common::config::ErgConfig;
+use erg_common::io::Input;
+use erg_common::traits::Runnable;
+use erg_common::Str;
+
+use erg_parser::ast;
+use erg_parser::parse::ParserRunner;
+use erg_parser::token::TokenKind;
+
+use crate::ty::value::ValueObj;
+use crate::ty::Type;
+
+pub const MANIFEST_FILE: &str = "package.er";
+/// The prelude used when `package.er` has no `prelude` entry
+pub const DEFAULT_PRELUDE_FILE: &str = "prelude.er";
+
+/// Returns the (unevaluated) value of the entry `name` in `{root}/package.er`.
+pub fn get_manifest_entry(root: &Path, name: &str) -> Option<ast::Expr> {
+    let manifest = root.join(MANIFEST_FILE);
+    let src = std::fs::read_to_string(&manifest).ok()?;
+    let artifact = ParserRunner::new(ErgConfig::with_main_path(manifest))
+        .parse(src)
+        .ok()?;
+    artifact.ast.into_iter().find_map(|chunk| match chunk {
+        ast::Expr::Def(def)
+            if def
+                .sig
+                .ident()
+                .is_some_and(|ident| &ident.inspect()[..] == name) =>
+        {
+            def.body.block.into_iter().next()
+        }
+        _ => None,
+    })
+}
+
+fn expr_as_str(expr: &ast::Expr) -> Option<Str> {
+    match expr {
+        ast::Expr::Literal(lit) if lit.token.is(TokenKind::StrLit) => {
+            match ValueObj::from_str(Type::Str, lit.token.content.clone())? {
+                ValueObj::Str(s) => Some(s),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// e.g. `prelude = "src/prelude.er"`
+pub fn get_str_entry(root: &Path, name: &str) -> Option<Str> {
+    expr_as_str(&get_manifest_entry(root, name)?)
+}
+
+/// e.g. `banned_builtins = ["exec", "eval"]`
+/// Non-string elements are ignored.
+pub fn get_str_array_entry(root: &Path, name: &str) -> Vec<Str> {
+    match get_manifest_entry(root, name) {
+        Some(ast::Expr::Array(ast::Array::Normal(arr))) => arr
+            .elems
+            .pos_args()
+            .iter()
+            .filter_map(|arg| expr_as_str(&arg.expr))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Whether the module `input` is listed in the entry `name` of its project manifest.
+/// Directories are listed with a trailing slash.
+///
+/// ```erg
+/// py_compat = ["src/legacy.er", "src/compat/"]
+/// ```
+pub fn is_listed_in(input: &Input, name: &str) -> bool {
+    let (Some(path), Some(root)) = (input.path(), input.project_root()) else {
+        return false;
+    };
+    let Ok(rel) = path.strip_prefix(&root) else {
+        return false;
+    };
+    let rel = rel.to_string_lossy().replace('\\', "/");
+    get_str_array_entry(&root, name)
+        .iter()
+        .any(|entry| rel == entry[..] || (entry.ends_with('/') && rel.starts_with(&entry[..])))
+}
+
+/// Returns the path of the prelude of the project to which `input` belongs.
+///
+/// The prelude is `{root}/prelude.er` by default, and can be changed by the `prelude` entry of `package.er`.
+/// Returns `None` if the prelude does not exist, or if `input` is the prelude itself.
+pub fn prelude_path(input: &Input) -> Option<PathBuf> {
+    let root = input.project_root()?;
+    let prelude = get_str_entry(&root, "prelude").map_or_else(
+        || root.join(DEFAULT_PRELUDE_FILE),
+        |path| root.join(&path[..]),
+    );
+    let prelude = prelude.canonicalize().ok()?;
+    let path = input.path()?.canonicalize().ok()?;
+    (path != prelude).then_some(prelude)
+}
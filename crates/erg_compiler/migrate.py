@@ -0,0 +1,659 @@
+# Best-effort translator from Python to Erg, used by `erg migrate` (see migrate.rs).
+# usage: python3 -c <this script> [builtin procedure names...] < input.py > output.er
+# The source is parsed with Python's own `ast` module,
+# and the names of the builtin procedures (`print`, `input`, ...) are given by the Erg compiler.
+import ast
+import io
+import sys
+import tokenize
+
+TODO_MARK = "# TODO(migrate):"
+INDENT = "    "
+
+# Python methods that mutate the receiver => Erg procedural methods
+PROC_METHODS = {
+    "append": "push!",
+    "extend": "extend!",
+    "insert": "insert!",
+    "remove": "remove!",
+    "pop": "pop!",
+    "clear": "clear!",
+    "sort": "sort!",
+    "reverse": "reverse!",
+    "add": "add!",
+}
+
+BINOPS = {
+    ast.Add: "+",
+    ast.Sub: "-",
+    ast.Mult: "*",
+    ast.Div: "/",
+    ast.FloorDiv: "//",
+    ast.Mod: "%",
+    ast.Pow: "**",
+    ast.LShift: "<<",
+    ast.RShift: ">>",
+    ast.BitOr: "|",
+    ast.BitXor: "^",
+    ast.BitAnd: "&",
+}
+CMPOPS = {
+    ast.Eq: "==",
+    ast.NotEq: "!=",
+    ast.Lt: "<",
+    ast.LtE: "<=",
+    ast.Gt: ">",
+    ast.GtE: ">=",
+    ast.Is: "is!",
+    ast.IsNot: "isnot!",
+    ast.In: "in",
+    ast.NotIn: "notin",
+}
+# the larger, the tighter
+PREC_OR, PREC_AND, PREC_NOT, PREC_CMP = 1, 2, 3, 4
+BINOP_PREC = {
+    ast.BitOr: 5,
+    ast.BitXor: 6,
+    ast.BitAnd: 7,
+    ast.LShift: 8,
+    ast.RShift: 8,
+    ast.Add: 9,
+    ast.Sub: 9,
+    ast.Mult: 10,
+    ast.Div: 10,
+    ast.FloorDiv: 10,
+    ast.Mod: 10,
+}
+PREC_UNARY, PREC_POW, PREC_ATOM = 11, 12, 13
+
+TYPES = {
+    "int": "Int",
+    "float": "Float",
+    "str": "Str",
+    "bool": "Bool",
+    "complex": "Complex",
+    "bytes": "Bytes",
+    "None": "NoneType",
+    "object": "Obj",
+    "Any": "Obj",
+}
+
+
+class Untranslatable(Exception):
+    pass
+
+
+def literal_type(node):
+    """The type of a literal (used to give types to unannotated parameters and returns)"""
+    if (
+        isinstance(node, ast.UnaryOp)
+        and isinstance(node.op, ast.USub)
+        and isinstance(node.operand, ast.Constant)
+        and type(node.operand.value) in (int, float)
+    ):
+        node = node.operand
+    if not isinstance(node, ast.Constant):
+        return None
+    return {bool: "Bool", int: "Int", float: "Float", str: "Str", type(None): "NoneType"}.get(
+        type(node.value)
+    )
+
+
+def is_typing_attr(node):
+    """`typing.X`"""
+    return (
+        isinstance(node, ast.Attribute)
+        and isinstance(node.value, ast.Name)
+        and node.value.id == "typing"
+    )
+
+
+def translate_type(node):
+    """Converts a Python type annotation into an Erg type specification."""
+    if isinstance(node, ast.Constant) and isinstance(node.value, str):
+        try:
+            node = ast.parse(node.value, mode="eval").body
+        except SyntaxError:
+            raise Untranslatable()
+    if isinstance(node, ast.Constant) and node.value is None:
+        return "NoneType"
+    if isinstance(node, ast.BinOp) and isinstance(node.op, ast.BitOr):
+        return f"{translate_type(node.left)} or {translate_type(node.right)}"
+    if is_typing_attr(node):
+        node = ast.Name(node.attr)
+    if isinstance(node, ast.Name) and node.id in TYPES:
+        return TYPES[node.id]
+    if isinstance(node, ast.Subscript):
+        name = translate_type_name(node.value)
+        elems = node.slice.elts if isinstance(node.slice, ast.Tuple) else [node.slice]
+        args = [translate_type(elem) for elem in elems]
+        if name in ("list", "List") and len(args) == 1:
+            return f"Array({args[0]})"
+        if name in ("set", "Set") and len(args) == 1:
+            return f"Set({args[0]})"
+        if name in ("dict", "Dict") and len(args) == 2:
+            return f"{{{args[0]}: {args[1]}}}"
+        if name in ("tuple", "Tuple"):
+            return f"({', '.join(args)})"
+        if name == "Optional" and len(args) == 1:
+            return f"{args[0]} or NoneType"
+        if name == "Union":
+            return " or ".join(args)
+    raise Untranslatable()
+
+
+def translate_type_name(node):
+    if is_typing_attr(node):
+        return node.attr
+    if isinstance(node, ast.Name):
+        return node.id
+    raise Untranslatable()
+
+
+def translate_str(value):
+    out = ""
+    for c in value:
+        if c == "\\":
+            out += "\\\\"
+        elif c == '"':
+            out += '\\"'
+        elif c == "\n":
+            out += "\\n"
+        elif c == "\t":
+            out += "\\t"
+        elif c == "\r":
+            out += "\\r"
+        elif not c.isprintable():
+            raise Untranslatable()
+        else:
+            out += c
+    return out
+
+
+def own_nodes(stmts):
+    """All nodes in `stmts`, excluding those in nested function definitions"""
+    stack = list(stmts)
+    while stack:
+        node = stack.pop()
+        yield node
+        for child in ast.iter_child_nodes(node):
+            if not isinstance(child, (ast.FunctionDef, ast.AsyncFunctionDef, ast.ClassDef)):
+                stack.append(child)
+
+
+def own_funcs(stmts):
+    for node in own_nodes(stmts):
+        for child in ast.iter_child_nodes(node):
+            if isinstance(child, ast.FunctionDef):
+                yield child
+    for stmt in stmts:
+        if isinstance(stmt, ast.FunctionDef):
+            yield stmt
+
+
+class Scope:
+    """Information about a function (or module) scope"""
+
+    def __init__(self, name, body, is_proc):
+        # (Erg) name of the function. `None` if module level.
+        self.name = name
+        self.is_proc = is_proc
+        assigned = {}
+        # variables whose methods mutate them (they become mutable containers)
+        self.mut_containers = set()
+        for node in own_nodes(body):
+            if isinstance(node, ast.Assign) and len(node.targets) == 1:
+                target = node.targets[0]
+                if isinstance(target, ast.Name):
+                    assigned[target.id] = assigned.get(target.id, 0) + 1
+            elif isinstance(node, ast.AugAssign) and isinstance(node.target, ast.Name):
+                assigned[node.target.id] = assigned.get(node.target.id, 0) + 2
+            elif (
+                isinstance(node, ast.Call)
+                and isinstance(node.func, ast.Attribute)
+                and isinstance(node.func.value, ast.Name)
+                and node.func.attr in PROC_METHODS
+            ):
+                self.mut_containers.add(node.func.value.id)
+        # variables assigned more than once (they become mutable variables)
+        self.mut_vars = {name for name, n in assigned.items() if n > 1}
+        # mutable variables that have already been defined
+        self.defined = set()
+
+
+class Migrator:
+    def __init__(self, src, builtin_procs):
+        self.src = src
+        self.module = ast.parse(src)
+        self.lines = src.splitlines()
+        self.builtin_procs = builtin_procs
+        # user defined function name -> is procedure
+        self.funcs = {}
+        self.out = []
+        # the last source line that has been emitted
+        self.last_line = 0
+        self.comments = {}
+        self.full_line_comments = set()
+        for tok in tokenize.generate_tokens(io.StringIO(src).readline):
+            if tok.type == tokenize.COMMENT:
+                line = tok.start[0]
+                self.comments[line] = tok.string
+                if not tok.line[: tok.start[1]].strip():
+                    self.full_line_comments.add(line)
+
+    def migrate(self):
+        body = self.module.body
+        self.collect_funcs(body)
+        # procedures can be called at the module level
+        scope = Scope(None, body, True)
+        self.translate_block(body, scope, 0, False)
+        self.emit_comments_until(len(self.lines) + 1, 0)
+        while self.out and not self.out[-1]:
+            self.out.pop()
+        return "\n".join(self.out) + "\n"
+
+    def is_proc_call(self, name):
+        if name in self.funcs:
+            return self.funcs[name]
+        return name in self.builtin_procs
+
+    def collect_funcs(self, body):
+        """Registers all user defined functions and decides which of them are procedures."""
+        defs = []
+        stack = [body]
+        while stack:
+            stmts = stack.pop()
+            for func in own_funcs(stmts):
+                defs.append(func)
+                stack.append(func.body)
+        for func in defs:
+            self.funcs[func.name] = False
+        # fixed point iteration
+        changed = True
+        while changed:
+            changed = False
+            for func in defs:
+                if not self.funcs[func.name] and self.has_side_effect(func.body):
+                    self.funcs[func.name] = True
+                    changed = True
+
+    def has_side_effect(self, body):
+        scope = Scope(None, body, False)
+        if scope.mut_vars or scope.mut_containers:
+            return True
+        for node in own_nodes(body):
+            if isinstance(node, (ast.For, ast.While, ast.Global, ast.Nonlocal)):
+                return True
+            if (
+                isinstance(node, ast.Call)
+                and isinstance(node.func, ast.Name)
+                and self.is_proc_call(node.func.id)
+            ):
+                return True
+        return False
+
+    # output
+
+    def emit(self, level, line):
+        self.out.append(f"{INDENT * level}{line}" if line else "")
+
+    def emit_comments_until(self, lineno, level):
+        """Emits the comments and blank lines before `lineno`"""
+        for line in range(self.last_line + 1, lineno):
+            if line in self.full_line_comments:
+                self.emit(level, self.comments[line])
+            elif line <= len(self.lines) and not self.lines[line - 1].strip():
+                self.emit(level, "")
+        self.last_line = max(self.last_line, lineno - 1)
+
+    def trailing_comment(self, stmt):
+        line = stmt.lineno
+        if line in self.comments and line not in self.full_line_comments:
+            return " " + self.comments.pop(line)
+        return ""
+
+    def source_of(self, node):
+        return ast.get_source_segment(self.src, node) or ""
+
+    def hole(self, stmt, level):
+        """The original code is kept as a `# TODO(migrate)` comment and replaced with `...`."""
+        self.emit(level, f"{TODO_MARK} line {stmt.lineno}")
+        decorators = getattr(stmt, "decorator_list", None)
+        first = decorators[0].lineno if decorators else stmt.lineno
+        original = self.lines[first - 1 : stmt.end_lineno]
+        col = len(original[0]) - len(original[0].lstrip())
+        for line in original:
+            self.emit(level, f"# {line[col:]}".rstrip())
+        self.emit(level, "...")
+        self.last_line = max(self.last_line, stmt.end_lineno)
+
+    # statements
+
+    def translate_block(self, stmts, scope, level, is_func_body):
+        start = len(self.out)
+        for i, stmt in enumerate(stmts):
+            is_last = is_func_body and i == len(stmts) - 1
+            self.emit_comments_until(stmt.lineno, level)
+            stmt_start = len(self.out)
+            try:
+                self.translate_stmt(stmt, scope, level, is_last)
+            except Untranslatable:
+                del self.out[stmt_start:]
+                self.hole(stmt, level)
+            self.last_line = max(self.last_line, stmt.end_lineno)
+        # a block cannot be empty
+        if level > 0 and all(
+            not line.strip() or line.lstrip().startswith("#") for line in self.out[start:]
+        ):
+            self.emit(level, "None")
+
+    def translate_stmt(self, stmt, scope, level, is_last):
+        comment = self.trailing_comment(stmt)
+        if isinstance(stmt, ast.FunctionDef):
+            self.translate_def(stmt, level, comment)
+        elif isinstance(stmt, ast.If):
+            self.translate_if(stmt, scope, level, is_last, comment)
+        elif isinstance(stmt, ast.For):
+            if stmt.orelse:
+                raise Untranslatable()
+            target = self.translate_target(stmt.target)
+            iter_ = self.translate_expr(stmt.iter)
+            self.emit(level, f"for! {iter_}, {target} =>{comment}")
+            self.translate_block(stmt.body, scope, level + 1, False)
+        elif isinstance(stmt, ast.While):
+            if stmt.orelse:
+                raise Untranslatable()
+            cond = self.translate_expr(stmt.test)
+            self.emit(level, f"while! do! {cond}, do!:{comment}")
+            self.translate_block(stmt.body, scope, level + 1, False)
+        elif isinstance(stmt, ast.Return):
+            value = "None" if stmt.value is None else self.translate_expr(stmt.value)
+            if scope.name is not None and not is_last:
+                value = f"{scope.name}.return {value}"
+            self.emit(level, value + comment)
+        elif isinstance(stmt, ast.Pass):
+            self.emit(level, "None" + comment)
+        elif isinstance(stmt, ast.Assert):
+            self.emit(level, f"assert {self.translate_expr(stmt.test)}{comment}")
+        elif isinstance(stmt, ast.Import):
+            for i, alias in enumerate(stmt.names):
+                name = alias.asname or alias.name
+                if "." in name:
+                    raise Untranslatable()
+                line = f'{name} = pyimport "{alias.name}"'
+                self.emit(level, line + (comment if i == 0 else ""))
+        elif isinstance(stmt, ast.ImportFrom):
+            if stmt.level > 0 or any(alias.name == "*" or alias.asname for alias in stmt.names):
+                raise Untranslatable()
+            names = [alias.name for alias in stmt.names]
+            names = f"{names[0]};" if len(names) == 1 else "; ".join(names)
+            self.emit(level, f'{{{names}}} = pyimport "{stmt.module}"{comment}')
+        elif isinstance(stmt, ast.Expr):
+            self.emit(level, self.translate_expr(stmt.value) + comment)
+        elif isinstance(stmt, ast.AugAssign):
+            if not isinstance(stmt.target, ast.Name) or type(stmt.op) not in BINOPS:
+                raise Untranslatable()
+            name = stmt.target.id
+            rhs = self.translate_expr(stmt.value)
+            if " " in rhs:
+                rhs = f"({rhs})"
+            op = BINOPS[type(stmt.op)]
+            self.emit(level, f"{name}.update! {name} -> {name} {op} {rhs}{comment}")
+        elif isinstance(stmt, (ast.Assign, ast.AnnAssign)):
+            self.emit(level, self.translate_assign(stmt, scope) + comment)
+        else:
+            # classes, exceptions, `with`, `global`, `break`, etc.
+            raise Untranslatable()
+
+    def translate_assign(self, stmt, scope):
+        if isinstance(stmt, ast.AnnAssign):
+            target, t_spec = stmt.target, translate_type(stmt.annotation)
+            if stmt.value is None:
+                if not isinstance(target, ast.Name):
+                    raise Untranslatable()
+                return f"{target.id}: {t_spec}"
+        elif len(stmt.targets) == 1:
+            target, t_spec = stmt.targets[0], None
+        else:
+            # chained assignment
+            raise Untranslatable()
+        if isinstance(target, ast.Name):
+            name = target.id
+        elif isinstance(target, ast.Tuple) and all(
+            isinstance(elt, ast.Name) for elt in target.elts
+        ):
+            name = None
+        else:
+            # attribute/subscript assignment
+            raise Untranslatable()
+        lhs = self.translate_target(target)
+        try:
+            value = self.translate_expr(stmt.value)
+        except Untranslatable:
+            if name is None:
+                raise
+            # keep the variable defined: `x = ... # TODO(migrate): <expr>`
+            return f"{name} = ... {TODO_MARK} {self.source_of(stmt.value)}"
+        if name in scope.mut_vars:
+            if name in scope.defined:
+                return f"{name}.update! {name} -> {value}"
+            scope.defined.add(name)
+            t_spec = t_spec or literal_type(stmt.value)
+            return f"{name}: {t_spec}! = !{value}" if t_spec else f"{name} = !{value}"
+        if name in scope.mut_containers:
+            value = f"!{value}"
+        return f"{lhs}: {t_spec} = {value}" if t_spec else f"{lhs} = {value}"
+
+    def translate_target(self, target):
+        if isinstance(target, ast.Name):
+            return target.id
+        if isinstance(target, ast.Tuple) and all(isinstance(elt, ast.Name) for elt in target.elts):
+            return f"({', '.join(elt.id for elt in target.elts)})"
+        raise Untranslatable()
+
+    def translate_if(self, stmt, scope, level, is_tail, comment):
+        if_, do_ = ("if!", "do!") if scope.is_proc else ("if", "do")
+        cond = self.translate_expr(stmt.test)
+        if not stmt.orelse:
+            self.emit(level, f"{if_} {cond}, {do_}:{comment}")
+            self.translate_block(stmt.body, scope, level + 1, False)
+            return
+        # the value of the last `if` (with `else`) is the return value
+        self.emit(level, f"{if_} {cond}:{comment}")
+        self.emit(level + 1, f"{do_}:")
+        self.translate_block(stmt.body, scope, level + 2, is_tail)
+        self.emit(level + 1, f"{do_}:")
+        orelse = stmt.orelse
+        if len(orelse) == 1 and isinstance(orelse[0], ast.If) and self.is_elif(orelse[0]):
+            self.emit_comments_until(orelse[0].lineno, level + 2)
+            comment = self.trailing_comment(orelse[0])
+            self.translate_if(orelse[0], scope, level + 2, is_tail, comment)
+        else:
+            self.translate_block(orelse, scope, level + 2, is_tail)
+
+    def is_elif(self, stmt):
+        return self.lines[stmt.lineno - 1][stmt.col_offset :].startswith("elif")
+
+    def translate_def(self, stmt, level, comment):
+        args = stmt.args
+        if (
+            stmt.decorator_list
+            or args.posonlyargs
+            or args.vararg
+            or args.kwonlyargs
+            or args.kwarg
+            or any(arg.arg == "self" for arg in args.args)
+        ):
+            raise Untranslatable()
+        defaults = [None] * (len(args.args) - len(args.defaults)) + args.defaults
+        params = []
+        for arg, default in zip(args.args, defaults):
+            param = arg.arg
+            if arg.annotation is not None:
+                t_spec = translate_type(arg.annotation)
+            else:
+                t_spec = default and literal_type(default)
+            if t_spec:
+                param += f": {t_spec}"
+            if default is not None:
+                param += f" := {self.translate_expr(default)}"
+            params.append(param)
+        if stmt.returns is not None:
+            ret_t = translate_type(stmt.returns)
+        else:
+            ret_t = self.infer_return_type(stmt.body)
+        name = stmt.name + "!" if self.funcs.get(stmt.name) else stmt.name
+        ret_t = f": {ret_t}" if ret_t else ""
+        self.emit(level, f"{name}({', '.join(params)}){ret_t} ={comment}")
+        scope = Scope(name, stmt.body, name.endswith("!"))
+        body = stmt.body
+        # the docstring becomes comments
+        if (
+            isinstance(body[0], ast.Expr)
+            and isinstance(body[0].value, ast.Constant)
+            and isinstance(body[0].value.value, str)
+        ):
+            for line in body[0].value.value.strip().splitlines():
+                self.emit(level + 1, f"# {line.strip()}".rstrip())
+            self.last_line = body[0].end_lineno
+            body = body[1:]
+        self.translate_block(body, scope, level + 1, True)
+
+    def infer_return_type(self, body):
+        """If all `return`s return literals of the same type, the type is the return type."""
+        types = {
+            literal_type(node.value) if node.value else "NoneType"
+            for node in own_nodes(body)
+            if isinstance(node, ast.Return)
+        }
+        if len(types) == 1:
+            return types.pop()
+        return None
+
+    # expressions
+
+    def translate_expr(self, node, prec=0):
+        """Translates a Python expression. Raises `Untranslatable` if it cannot be translated."""
+        expr, expr_prec = self.translate_expr_with_prec(node)
+        return f"({expr})" if expr_prec < prec else expr
+
+    def translate_expr_with_prec(self, node):
+        if isinstance(node, ast.Constant):
+            value = node.value
+            if isinstance(value, str):
+                return f'"{translate_str(value)}"', PREC_ATOM
+            if value is None or isinstance(value, (bool, int, float)):
+                return self.source_of(node) or repr(value), PREC_ATOM
+            # bytes, complex, Ellipsis
+            raise Untranslatable()
+        if isinstance(node, ast.Name):
+            return node.id, PREC_ATOM
+        if isinstance(node, ast.JoinedStr):
+            out = ""
+            for value in node.values:
+                if isinstance(value, ast.Constant):
+                    out += translate_str(value.value)
+                elif value.conversion == -1 and value.format_spec is None:
+                    out += f"\\{{{self.translate_expr(value.value)}}}"
+                else:
+                    # format specs / conversions are not supported
+                    raise Untranslatable()
+            return f'"{out}"', PREC_ATOM
+        if isinstance(node, ast.BoolOp):
+            op, prec = ("and", PREC_AND) if isinstance(node.op, ast.And) else ("or", PREC_OR)
+            values = [self.translate_expr(value, prec + 1) for value in node.values]
+            return f" {op} ".join(values), prec
+        if isinstance(node, ast.UnaryOp):
+            if isinstance(node.op, ast.Not):
+                return f"not {self.translate_expr(node.operand, PREC_NOT)}", PREC_NOT
+            if isinstance(node.op, (ast.UAdd, ast.USub)):
+                op = "-" if isinstance(node.op, ast.USub) else "+"
+                return op + self.translate_expr(node.operand, PREC_UNARY), PREC_UNARY
+            raise Untranslatable()
+        if isinstance(node, ast.BinOp):
+            if type(node.op) not in BINOPS:
+                raise Untranslatable()
+            op = BINOPS[type(node.op)]
+            if isinstance(node.op, ast.Pow):
+                lhs = self.translate_expr(node.left, PREC_POW + 1)
+                rhs = self.translate_expr(node.right, PREC_UNARY)
+                return f"{lhs} {op} {rhs}", PREC_POW
+            prec = BINOP_PREC[type(node.op)]
+            lhs = self.translate_expr(node.left, prec)
+            rhs = self.translate_expr(node.right, prec + 1)
+            return f"{lhs} {op} {rhs}", prec
+        if isinstance(node, ast.Compare):
+            out = self.translate_expr(node.left, PREC_CMP + 1)
+            for op, comparator in zip(node.ops, node.comparators):
+                out += f" {CMPOPS[type(op)]} {self.translate_expr(comparator, PREC_CMP + 1)}"
+            return out, PREC_CMP
+        if isinstance(node, ast.Call):
+            return self.translate_call(node), PREC_ATOM
+        if isinstance(node, ast.Attribute):
+            return f"{self.translate_expr(node.value, PREC_ATOM)}.{node.attr}", PREC_ATOM
+        if isinstance(node, ast.Subscript):
+            if isinstance(node.slice, ast.Slice):
+                raise Untranslatable()
+            obj = self.translate_expr(node.value, PREC_ATOM)
+            return f"{obj}[{self.translate_expr(node.slice)}]", PREC_ATOM
+        if isinstance(node, ast.List):
+            return f"[{self.translate_elems(node.elts)}]", PREC_ATOM
+        if isinstance(node, ast.Tuple):
+            if len(node.elts) == 1:
+                return f"({self.translate_expr(node.elts[0])},)", PREC_ATOM
+            return f"({self.translate_elems(node.elts)})", PREC_ATOM
+        if isinstance(node, ast.Set):
+            return f"{{{self.translate_elems(node.elts)}}}", PREC_ATOM
+        if isinstance(node, ast.Dict):
+            # `{}` is an empty set in Erg
+            if not node.keys:
+                return "{:}", PREC_ATOM
+            if any(key is None for key in node.keys):
+                raise Untranslatable()
+            pairs = ", ".join(
+                f"{self.translate_expr(key)}: {self.translate_expr(value)}"
+                for key, value in zip(node.keys, node.values)
+            )
+            return f"{{{pairs}}}", PREC_ATOM
+        if isinstance(node, ast.Lambda):
+            args = node.args
+            if args.posonlyargs or args.vararg or args.kwonlyargs or args.kwarg or args.defaults:
+                raise Untranslatable()
+            params = ", ".join(arg.arg for arg in args.args)
+            return f"({params}) -> {self.translate_expr(node.body)}", PREC_OR - 1
+        # comprehensions, conditional expressions, `:=`, `await`, `yield`, etc.
+        raise Untranslatable()
+
+    def translate_elems(self, elems):
+        if any(isinstance(elem, ast.Starred) for elem in elems):
+            raise Untranslatable()
+        return ", ".join(self.translate_expr(elem) for elem in elems)
+
+    def translate_call(self, node):
+        func = node.func
+        if isinstance(func, ast.Attribute):
+            obj = self.translate_expr(func.value, PREC_ATOM)
+            callee = f"{obj}.{PROC_METHODS.get(func.attr, func.attr)}"
+        elif isinstance(func, ast.Name) and self.is_proc_call(func.id):
+            callee = f"{func.id}!"
+        else:
+            callee = self.translate_expr(func, PREC_ATOM)
+        args = [self.translate_elems([arg]) for arg in node.args]
+        for keyword in node.keywords:
+            if keyword.arg is None:
+                raise Untranslatable()
+            args.append(f"{keyword.arg}:={self.translate_expr(keyword.value)}")
+        return f"{callee}({', '.join(args)})"
+
+
+def main():
+    src = sys.stdin.read()
+    try:
+        code = Migrator(src, set(sys.argv[1:])).migrate()
+    except SyntaxError as err:
+        print(f"{err.msg} (line {err.lineno})", file=sys.stderr)
+        sys.exit(1)
+    sys.stdout.write(code)
+
+
+main()
@@ -0,0 +1,176 @@
+//! Best-effort migration of Python scripts into Erg (`erg migrate <file>.py`).
+//!
+//! The Python script is parsed and translated by the Python interpreter itself (with the `ast` module, see `migrate.py`),
+//! so that no Python parser is maintained on the Rust side.
+//! The compiler provides the knowledge of Erg (which builtin functions are procedures, etc.)
+//! and checks the translated code.
+//!
+//! Constructs that have no direct counterpart (classes, exceptions, comprehensions, etc.) are left as "holes":
+//! the original code is kept as a `# TODO(migrate)` comment and replaced with `...`.
+use std::fs::File;
+use std::io::Write;
+
+use erg_common::config::ErgConfig;
+use erg_common::error::{Location, MultiErrorDisplay};
+use erg_common::python_util::eval_py_with_input;
+use erg_common::traits::{ExitStatus, Runnable, Stream};
+use erg_common::{log, switch_lang};
+
+use crate::build_hir::HIRBuilder;
+use crate::error::{CompileError, CompileErrors};
+
+const MIGRATOR: &str = include_str!("migrate.py");
+const TODO_MARK: &str = "# TODO(migrate):";
+
+/// Translates Python scripts into Erg scripts.
+#[derive(Debug)]
+pub struct Migrator {
+    pub cfg: ErgConfig,
+    builder: HIRBuilder,
+    holes: usize,
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for Migrator {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg migrator";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self {
+            builder: HIRBuilder::new(cfg.copy()),
+            holes: 0,
+            cfg,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.builder.initialize();
+        self.holes = 0;
+    }
+
+    fn clear(&mut self) {
+        self.builder.clear();
+    }
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let mut path = self.cfg.dump_path();
+        path.set_extension("er");
+        // do not overwrite the user's Erg script
+        if path.exists() {
+            let desc = switch_lang!(
+                "japanese" => format!("{}は既に存在します", path.display()),
+                "simplified_chinese" => format!("{}已存在", path.display()),
+                "traditional_chinese" => format!("{}已存在", path.display()),
+                "english" => format!("{} already exists", path.display()),
+            );
+            let hint = switch_lang!(
+                "japanese" => "上書きする場合はファイルを削除してください",
+                "simplified_chinese" => "如需覆盖, 请先删除该文件",
+                "traditional_chinese" => "如需覆蓋, 請先刪除該檔案",
+                "english" => "remove the file to overwrite it",
+            );
+            let err = self.file_error(desc, Some(hint.into()));
+            return Err(CompileErrors::from(err));
+        }
+        let src = self.cfg.input.read();
+        let code = self.migrate(&src)?;
+        File::create(&path)
+            .and_then(|mut f| f.write_all(code.as_bytes()))
+            .map_err(|err| CompileErrors::from(self.file_error(err.to_string(), None)))?;
+        if self.holes > 0 {
+            eprintln!(
+                "{}",
+                switch_lang!(
+                    "japanese" => format!("{}: {}箇所を手動で翻訳する必要があります", path.display(), self.holes),
+                    "simplified_chinese" => format!("{}: 有{}处需要手动翻译", path.display(), self.holes),
+                    "traditional_chinese" => format!("{}: 有{}處需要手動翻譯", path.display(), self.holes),
+                    "english" => format!("{}: {} spot(s) need to be translated manually", path.display(), self.holes),
+                )
+            );
+        }
+        // check the result
+        let cfg = self.cfg.inherit(path);
+        let mut checker = HIRBuilder::new(cfg);
+        match checker.build(code, "exec") {
+            Ok(artifact) => {
+                artifact.warns.write_all_stderr();
+                Ok(ExitStatus::compile_passed(artifact.warns.len()))
+            }
+            Err(artifact) => {
+                artifact.warns.write_all_stderr();
+                artifact.errors.write_all_stderr();
+                Ok(ExitStatus::new(
+                    0,
+                    artifact.warns.len(),
+                    artifact.errors.len(),
+                ))
+            }
+        }
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        self.migrate(&src)
+    }
+}
+
+impl Migrator {
+    /// The number of untranslatable spots in the last migration
+    pub fn holes(&self) -> usize {
+        self.holes
+    }
+
+    pub fn migrate(&mut self, src: &str) -> Result<String, CompileErrors> {
+        log!(info "the migrating process has started.");
+        self.holes = 0;
+        let procs = self.builtin_procs();
+        let args = procs.iter().map(|name| &name[..]).collect::<Vec<_>>();
+        let out = eval_py_with_input(self.cfg.py_command, MIGRATOR, &args, src)
+            .map_err(|err| CompileErrors::from(self.file_error(err.to_string(), None)))?;
+        if !out.status.success() {
+            let desc = String::from_utf8_lossy(&out.stderr).trim().to_string();
+            return Err(CompileErrors::from(self.file_error(desc, None)));
+        }
+        let code = String::from_utf8_lossy(&out.stdout).replace("\r\n", "\n");
+        self.holes = code.matches(TODO_MARK).count();
+        log!(info "the migrating process has completed:\n{code}");
+        Ok(code)
+    }
+
+    /// e.g. `print`, `input`, `open` (without `!`)
+    fn builtin_procs(&mut self) -> Vec<String> {
+        self.builder
+            .dir()
+            .keys()
+            .filter_map(|name| name.inspect().strip_suffix('!').map(String::from))
+            .collect()
+    }
+
+    fn file_error(&self, desc: String, hint: Option<String>) -> CompileError {
+        CompileError::file_error(
+            self.cfg.input.clone(),
+            line!() as usize,
+            desc,
+            Location::Unknown,
+            "<migrate>".into(),
+            hint,
+        )
+    }
+}
@@ -263,8 +263,10 @@ impl SharedModuleCache {
         let Some(builtin) = self.remove(&builtin_path) else {
             return;
         };
-        for path in self.ref_inner().keys() {
-            self.remove(path);
+        // the cache cannot be modified while being iterated
+        let paths = self.ref_inner().keys().cloned().collect::<Vec<_>>();
+        for path in paths {
+            self.remove(&path);
         }
         self.register(builtin_path, None, Arc::try_unwrap(builtin.module).unwrap());
     }
@@ -22,6 +22,14 @@ impl SharedCompileErrors {
     pub fn clear(&self) {
         self.0.borrow_mut().clear();
     }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
 }
 
 pub type SharedCompileWarnings = SharedCompileErrors;
@@ -1,8 +1,11 @@
+use std::fmt;
 use std::path::{Path, PathBuf};
 
 use erg_common::config::ErgConfig;
+use erg_common::set::Set;
 
-use crate::context::Context;
+use crate::context::{Context, ModuleContext};
+use crate::hir::HIR;
 
 use super::cache::SharedModuleCache;
 use super::errors::{SharedCompileErrors, SharedCompileWarnings};
@@ -11,6 +14,57 @@ use super::impls::SharedTraitImpls;
 use super::index::SharedModuleIndex;
 use super::promise::SharedPromises;
 
+/// The amount of the data held by `SharedCompilerResource` (see `SharedCompilerResource::stats`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompilerResourceStats {
+    /// The number of the cached Erg modules (including the builtin module)
+    pub modules: usize,
+    /// The number of the cached Erg modules whose HIR is kept
+    pub hirs: usize,
+    pub py_modules: usize,
+    /// The number of the types defined at the top level of the cached modules
+    pub types: usize,
+    /// The number of the variables defined or declared at the top level of the cached modules
+    pub vars: usize,
+    /// The number of the traits that have implementations
+    pub traits: usize,
+    pub trait_impls: usize,
+    /// The number of the indexed variables
+    pub index_members: usize,
+    /// The number of the references to the indexed variables
+    pub index_refs: usize,
+    pub promises: usize,
+    /// The number of the modules being analyzed
+    pub running_promises: usize,
+    pub errors: usize,
+    pub warns: usize,
+}
+
+impl fmt::Display for CompilerResourceStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "modules:      {} ({} with HIR)", self.modules, self.hirs)?;
+        writeln!(f, "py modules:   {}", self.py_modules)?;
+        writeln!(f, "types:        {}", self.types)?;
+        writeln!(f, "variables:    {}", self.vars)?;
+        writeln!(
+            f,
+            "trait impls:  {} ({} traits)",
+            self.trait_impls, self.traits
+        )?;
+        writeln!(
+            f,
+            "index:        {} ({} references)",
+            self.index_members, self.index_refs
+        )?;
+        writeln!(
+            f,
+            "promises:     {} ({} running)",
+            self.promises, self.running_promises
+        )?;
+        write!(f, "errors:       {} ({} warnings)", self.errors, self.warns)
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SharedCompilerResource {
     pub mod_cache: SharedModuleCache,
@@ -52,7 +106,7 @@ impl SharedCompilerResource {
 
     pub fn inherit(&self, path: PathBuf) -> Self {
         let mut _self = self.clone();
-        _self.promises.path = path;
+        _self.promises = self.promises.inherit(path);
         _self
     }
 
@@ -66,10 +120,65 @@ impl SharedCompilerResource {
         self.warns.clear();
     }
 
+    /// Counts the cached data, e.g. to monitor the memory usage of a long-running language server.
+    pub fn stats(&self) -> CompilerResourceStats {
+        let mut stats = CompilerResourceStats::default();
+        for (_, entry) in self.mod_cache.ref_inner().iter() {
+            let ctx = &entry.module.context;
+            stats.modules += 1;
+            stats.hirs += entry.hir.is_some() as usize;
+            stats.types += ctx.mono_types.len() + ctx.poly_types.len();
+            stats.vars += ctx.locals.len() + ctx.decls.len();
+        }
+        stats.py_modules = self.py_mod_cache.len();
+        for impls in self.trait_impls.ref_inner().values() {
+            stats.traits += 1;
+            stats.trait_impls += impls.len();
+        }
+        let members = self.index.members();
+        stats.index_members = members.values().len();
+        stats.index_refs = members.values().map(|value| value.referrers.len()).sum();
+        drop(members);
+        stats.promises = self.promises.len();
+        stats.running_promises = self.promises.num_running();
+        stats.errors = self.errors.len();
+        stats.warns = self.warns.len();
+        stats
+    }
+
     pub fn clear(&self, path: &Path) {
         self.mod_cache.remove(path);
         self.py_mod_cache.remove(path);
         self.index.remove_path(path);
         self.graph.remove(path);
     }
+
+    /// Registers the analysis result of the Erg module `path`, which is no longer dirty.
+    pub fn register_module(&self, path: PathBuf, hir: Option<HIR>, ctx: ModuleContext) {
+        self.graph.clear_dirty(&path);
+        self.mod_cache.register(path, hir, ctx);
+    }
+
+    /// Invalidates the analysis result of `path` so that it is analyzed again when imported next time.
+    /// The modules that (transitively) depend on `path` are kept but marked dirty (see `SharedModuleGraph::is_dirty`).
+    /// Dirty modules are analyzed again when imported next time, or when registered by the caller (e.g. ELS).
+    /// Returns the dirty modules.
+    pub fn invalidate(&self, path: &Path) -> Set<PathBuf> {
+        let dependents = self.graph.descendants(path);
+        for dependent in dependents.iter() {
+            self.graph.mark_dirty(dependent);
+        }
+        self.discard(path);
+        self.graph.remove(path);
+        dependents
+    }
+
+    /// Discards the analysis result of `path`. The module graph is kept.
+    pub(crate) fn discard(&self, path: &Path) {
+        self.promises.remove(path);
+        self.mod_cache.remove(path);
+        self.mod_cache.remove(&Context::interface_path(path));
+        self.py_mod_cache.remove(path);
+        self.index.remove_path(path);
+    }
 }
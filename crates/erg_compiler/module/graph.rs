@@ -1,11 +1,14 @@
 use std::fmt;
 use std::path::{Path, PathBuf};
 
+use erg_common::json::Json;
 use erg_common::set::Set;
 use erg_common::shared::{MappedRwLockReadGuard, RwLockReadGuard, Shared};
 use erg_common::tsort::{tsort, Graph, Node, TopoSortError};
 use erg_common::{normalize_path, set};
 
+use super::cache::SharedModuleCache;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum IncRefError {
     CycleDetected,
@@ -17,13 +20,22 @@ impl IncRefError {
     }
 }
 
+/// Is `path` a Python module (its declarations are registered in `py_mod_cache`)?
+fn is_py_mod(path: &Path, py_mod_cache: &SharedModuleCache) -> bool {
+    py_mod_cache.get(path).is_some()
+}
+
 #[derive(Debug, Clone, Default)]
-pub struct ModuleGraph(Graph<PathBuf, ()>);
+pub struct ModuleGraph {
+    graph: Graph<PathBuf, ()>,
+    /// modules whose dependencies have been invalidated and need to be rechecked
+    dirty: Set<PathBuf>,
+}
 
 impl fmt::Display for ModuleGraph {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "ModuleGraph {{")?;
-        for node in self.0.iter() {
+        for node in self.graph.iter() {
             writeln!(f, "{} depends on {{", node.id.display())?;
             for dep in node.depends_on.iter() {
                 writeln!(f, "{}, ", dep.display())?;
@@ -39,23 +51,29 @@ impl IntoIterator for ModuleGraph {
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
+        self.graph.into_iter()
     }
 }
 
 impl ModuleGraph {
     pub fn new() -> Self {
-        Self(Graph::new())
+        Self {
+            graph: Graph::new(),
+            dirty: Set::new(),
+        }
     }
 
     pub fn get_node(&self, path: &Path) -> Option<&Node<PathBuf, ()>> {
         let path = normalize_path(path.to_path_buf());
-        self.0.iter().find(|n| n.id == path)
+        self.graph.iter().find(|n| n.id == path)
     }
 
     fn parents(&self, path: &Path) -> Option<&Set<PathBuf>> {
         let path = normalize_path(path.to_path_buf());
-        self.0.iter().find(|n| n.id == path).map(|n| &n.depends_on)
+        self.graph
+            .iter()
+            .find(|n| n.id == path)
+            .map(|n| &n.depends_on)
     }
 
     pub fn ancestors(&self, path: &Path) -> Set<PathBuf> {
@@ -72,9 +90,9 @@ impl ModuleGraph {
 
     pub fn add_node_if_none(&mut self, path: &Path) {
         let path = normalize_path(path.to_path_buf());
-        if self.0.iter().all(|n| n.id != path) {
+        if self.graph.iter().all(|n| n.id != path) {
             let node = Node::new(path, (), set! {});
-            self.0.push(node);
+            self.graph.push(node);
         }
     }
 
@@ -85,7 +103,7 @@ impl ModuleGraph {
         if self.ancestors(&depends_on).contains(&referrer) && referrer != depends_on {
             return Err(IncRefError::CycleDetected);
         }
-        if let Some(node) = self.0.iter_mut().find(|n| n.id == referrer) {
+        if let Some(node) = self.graph.iter_mut().find(|n| n.id == referrer) {
             if referrer == depends_on {
                 return Ok(());
             }
@@ -97,12 +115,13 @@ impl ModuleGraph {
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Node<PathBuf, ()>> {
-        self.0.iter()
+        self.graph.iter()
     }
 
     #[allow(clippy::result_unit_err)]
     pub fn sorted(self) -> Result<Self, TopoSortError> {
-        tsort(self.0).map(Self)
+        let dirty = self.dirty;
+        tsort(self.graph).map(|graph| Self { graph, dirty })
     }
 
     #[allow(clippy::result_unit_err)]
@@ -111,13 +130,118 @@ impl ModuleGraph {
         Ok(())
     }
 
+    /// Returns all the modules in the graph, including the ones only depended on (e.g. Python modules), in sorted order.
+    pub fn modules(&self) -> Vec<PathBuf> {
+        let mut modules = set! {};
+        for node in self.graph.iter() {
+            modules.insert(node.id.clone());
+            modules.extend(node.depends_on.iter().cloned());
+        }
+        let mut modules = modules.into_iter().collect::<Vec<_>>();
+        modules.sort();
+        modules
+    }
+
+    /// Returns the modules that `path` directly depends on, in sorted order.
+    pub fn dependencies(&self, path: &Path) -> Vec<PathBuf> {
+        let mut deps = self
+            .parents(path)
+            .map_or(vec![], |deps| deps.iter().cloned().collect::<Vec<_>>());
+        deps.sort();
+        deps
+    }
+
+    /// `{ "modules": [{ "path": ..., "kind": "erg" | "py", "depends_on": [...] }, ...] }`
+    pub fn to_json(&self, py_mod_cache: &SharedModuleCache) -> Json {
+        let modules = self
+            .modules()
+            .into_iter()
+            .map(|path| {
+                let depends_on = self
+                    .dependencies(&path)
+                    .iter()
+                    .map(|dep| dep.display().to_string().into())
+                    .collect();
+                let kind = if is_py_mod(&path, py_mod_cache) {
+                    "py"
+                } else {
+                    "erg"
+                };
+                Json::object([
+                    ("path", path.display().to_string().into()),
+                    ("kind", kind.into()),
+                    ("depends_on", Json::Array(depends_on)),
+                ])
+            })
+            .collect();
+        Json::object([("modules", Json::Array(modules))])
+    }
+
+    /// Prints the graph in the DOT language (Graphviz).
+    /// An edge `a -> b` means `a` imports `b`, and the Python modules are drawn as boxes.
+    pub fn to_dot(&self, py_mod_cache: &SharedModuleCache) -> String {
+        let mut dot = String::from("digraph modules {\n");
+        for path in self.modules() {
+            let shape = if is_py_mod(&path, py_mod_cache) {
+                "box"
+            } else {
+                "ellipse"
+            };
+            dot += &format!("    {:?} [shape={shape}];\n", path.display().to_string());
+        }
+        for path in self.modules() {
+            for dep in self.dependencies(&path) {
+                dot += &format!(
+                    "    {:?} -> {:?};\n",
+                    path.display().to_string(),
+                    dep.display().to_string()
+                );
+            }
+        }
+        dot += "}";
+        dot
+    }
+
+    /// Returns the modules that (transitively) depend on `path`.
+    pub fn descendants(&self, path: &Path) -> Set<PathBuf> {
+        let path = normalize_path(path.to_path_buf());
+        let mut descendants = set! {};
+        let mut unvisited = vec![path];
+        while let Some(path) = unvisited.pop() {
+            for node in self.graph.iter() {
+                if node.depends_on.contains(&path) && descendants.insert(node.id.clone()) {
+                    unvisited.push(node.id.clone());
+                }
+            }
+        }
+        descendants
+    }
+
+    pub fn mark_dirty(&mut self, path: &Path) {
+        self.dirty.insert(normalize_path(path.to_path_buf()));
+    }
+
+    pub fn is_dirty(&self, path: &Path) -> bool {
+        self.dirty.contains(&normalize_path(path.to_path_buf()))
+    }
+
+    pub fn dirty_modules(&self) -> &Set<PathBuf> {
+        &self.dirty
+    }
+
+    pub fn clear_dirty(&mut self, path: &Path) {
+        self.dirty.remove(&normalize_path(path.to_path_buf()));
+    }
+
     pub fn remove(&mut self, path: &Path) {
         let path = normalize_path(path.to_path_buf());
-        self.0.retain(|n| n.id != path);
+        self.graph.retain(|n| n.id != path);
+        self.dirty.remove(&path);
     }
 
     pub fn initialize(&mut self) {
-        self.0.clear();
+        self.graph.clear();
+        self.dirty = Set::new();
     }
 }
 
@@ -166,6 +290,34 @@ impl SharedModuleGraph {
         self.0.borrow_mut().inc_ref(referrer, depends_on)
     }
 
+    pub fn descendants(&self, path: &Path) -> Set<PathBuf> {
+        self.0.borrow().descendants(path)
+    }
+
+    pub fn mark_dirty(&self, path: &Path) {
+        self.0.borrow_mut().mark_dirty(path);
+    }
+
+    pub fn is_dirty(&self, path: &Path) -> bool {
+        self.0.borrow().is_dirty(path)
+    }
+
+    pub fn dirty_modules(&self) -> Set<PathBuf> {
+        self.0.borrow().dirty_modules().clone()
+    }
+
+    pub fn clear_dirty(&self, path: &Path) {
+        self.0.borrow_mut().clear_dirty(path);
+    }
+
+    pub fn to_json(&self, py_mod_cache: &SharedModuleCache) -> Json {
+        self.0.borrow().to_json(py_mod_cache)
+    }
+
+    pub fn to_dot(&self, py_mod_cache: &SharedModuleCache) -> String {
+        self.0.borrow().to_dot(py_mod_cache)
+    }
+
     pub fn ref_inner(&self) -> RwLockReadGuard<ModuleGraph> {
         self.0.borrow()
     }
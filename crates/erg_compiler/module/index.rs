@@ -1,6 +1,6 @@
 use std::collections::hash_map::{Iter, Keys, Values};
 use std::fmt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use erg_common::dict::Dict;
 use erg_common::set;
@@ -57,9 +57,19 @@ impl ModuleIndexValue {
     }
 }
 
+/// (line, column) of the end of a name
+type EndPos = (u32, u32);
+
+fn end_pos(loc: &AbsLocation) -> Option<(&PathBuf, EndPos)> {
+    Some((loc.module.as_ref()?, (loc.loc.ln_end()?, loc.loc.col_end()?)))
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ModuleIndex {
     members: Dict<AbsLocation, ModuleIndexValue>,
+    /// module -> the end position of a name (a definition or a reference) -> the definition.
+    /// A name is found by its end because a public name (`.x`) starts with the dot.
+    referees: Dict<PathBuf, Dict<EndPos, AbsLocation>>,
 }
 
 impl fmt::Display for ModuleIndex {
@@ -72,11 +82,26 @@ impl ModuleIndex {
     pub fn new() -> Self {
         Self {
             members: Dict::new(),
+            referees: Dict::new(),
+        }
+    }
+
+    fn register_referee(&mut self, name_loc: &AbsLocation, referee: &AbsLocation) {
+        if let Some((path, pos)) = end_pos(name_loc) {
+            if let Some(names) = self.referees.get_mut(path) {
+                names.insert(pos, referee.clone());
+            } else {
+                let mut names = Dict::new();
+                names.insert(pos, referee.clone());
+                self.referees.insert(path.clone(), names);
+            }
         }
     }
 
     pub fn inc_ref(&mut self, name: &Str, vi: &VarInfo, referrer: AbsLocation) {
         let referee = vi.def_loc.clone();
+        self.register_referee(&referee, &referee);
+        self.register_referee(&referrer, &referee);
         if let Some(referrers) = self.members.get_mut(&referee) {
             referrers.push_ref(referrer);
         } else {
@@ -87,6 +112,7 @@ impl ModuleIndex {
 
     pub fn register(&mut self, name: Str, vi: &VarInfo) {
         let referee = vi.def_loc.clone();
+        self.register_referee(&referee, &referee);
         let value = ModuleIndexValue::new(name, vi.clone(), set! {});
         self.members.insert(referee, value);
     }
@@ -95,8 +121,14 @@ impl ModuleIndex {
         self.members.get(referee)
     }
 
+    /// Returns the definition of the name (a definition or a reference) ending at `ln`:`col` of `path`
+    pub fn get_referee(&self, path: &Path, ln: u32, col: u32) -> Option<&AbsLocation> {
+        self.referees.get(path)?.get(&(ln, col))
+    }
+
     pub fn initialize(&mut self) {
         self.members.clear();
+        self.referees.clear();
     }
 
     pub fn remove_path(&mut self, path: &Path) {
@@ -106,6 +138,10 @@ impl ModuleIndex {
                 .retain(|ref_loc| ref_loc.module.as_deref() != Some(path));
             loc.module.as_deref() != Some(path)
         });
+        self.referees.remove(path);
+        for names in self.referees.values_mut() {
+            names.retain(|_, referee| referee.module.as_deref() != Some(path));
+        }
     }
 }
 
@@ -144,10 +180,22 @@ impl SharedModuleIndex {
         }
     }
 
+    pub fn get_referee(&self, path: &Path, ln: u32, col: u32) -> Option<AbsLocation> {
+        self.0.borrow().get_referee(path, ln, col).cloned()
+    }
+
     pub fn members(&self) -> Members {
         Members(RwLockReadGuard::map(self.0.borrow(), |mi| &mi.members))
     }
 
+    pub fn len(&self) -> usize {
+        self.0.borrow().members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().members.is_empty()
+    }
+
     pub fn initialize(&self) {
         self.0.borrow_mut().initialize();
     }
@@ -1,8 +1,12 @@
 use std::fmt;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::thread::{current, JoinHandle, ThreadId};
+use std::time::{Duration, Instant};
 
 use erg_common::dict::Dict;
+use erg_common::error::Location;
+use erg_common::set::Set;
 use erg_common::shared::Shared;
 
 use super::SharedModuleGraph;
@@ -64,11 +68,83 @@ impl Promise {
     }
 }
 
+/// Waits longer than this are checked for deadlocks.
+const DEADLOCK_CHECK_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// The analysis of `waiter` waits for that of `target`, which (transitively) waits for `waiter`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deadlock {
+    pub waiter: PathBuf,
+    pub target: PathBuf,
+    /// where `waiter` imports `target` (`Location::Unknown` if not recorded)
+    pub loc: Location,
+}
+
+/// Which module each analysis thread waits for.
+#[derive(Debug, Default)]
+struct WaitGraph {
+    /// module -> the thread analyzing it
+    threads: Dict<PathBuf, ThreadId>,
+    /// thread -> the module it waits for
+    waits: Dict<ThreadId, PathBuf>,
+}
+
+impl WaitGraph {
+    /// Does the analysis of `target` (transitively) wait for the current thread?
+    fn waits_for_current(&self, target: &Path) -> bool {
+        let cur_id = current().id();
+        let mut module = target;
+        for _ in 0..=self.waits.len() {
+            let Some(thread) = self.threads.get(module) else {
+                return false;
+            };
+            if *thread == cur_id {
+                return true;
+            }
+            let Some(next) = self.waits.get(thread) else {
+                return false;
+            };
+            module = next;
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromiseEvent {
+    /// The analysis of the module is started on a new thread
+    Started(PathBuf),
+    Finished(PathBuf),
+}
+
+/// Receives the events of the module analyses (e.g. to report the progress to the user).
+/// It is called on the analysis threads.
+#[derive(Clone)]
+pub struct PromiseListener(Arc<dyn Fn(&PromiseEvent) + Send + Sync>);
+
+impl fmt::Debug for PromiseListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PromiseListener")
+    }
+}
+
+impl PromiseListener {
+    pub fn new(f: impl Fn(&PromiseEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SharedPromises {
     graph: SharedModuleGraph,
     pub(crate) path: PathBuf,
     promises: Shared<Dict<PathBuf, Promise>>,
+    /// module -> the modules that import it cyclically and refer to its interface (top-level declarations)
+    interfaces: Shared<Dict<PathBuf, Set<PathBuf>>>,
+    /// (importer, imported module) -> the location of the import
+    import_sites: Shared<Dict<(PathBuf, PathBuf), Location>>,
+    wait_graph: Shared<WaitGraph>,
+    listener: Shared<Option<PromiseListener>>,
 }
 
 impl fmt::Display for SharedPromises {
@@ -87,6 +163,29 @@ impl SharedPromises {
             graph,
             path,
             promises: Shared::new(Dict::new()),
+            interfaces: Shared::new(Dict::new()),
+            import_sites: Shared::new(Dict::new()),
+            wait_graph: Shared::new(WaitGraph::default()),
+            listener: Shared::new(None),
+        }
+    }
+
+    /// Shares the promises with the analysis of `path`.
+    pub fn inherit(&self, path: PathBuf) -> Self {
+        Self {
+            path,
+            ..self.clone()
+        }
+    }
+
+    pub fn set_listener(&self, listener: PromiseListener) {
+        *self.listener.borrow_mut() = Some(listener);
+    }
+
+    pub fn notify(&self, event: PromiseEvent) {
+        let listener = self.listener.borrow().clone();
+        if let Some(listener) = listener {
+            (listener.0)(&event);
         }
     }
 
@@ -95,11 +194,86 @@ impl SharedPromises {
             // panic!("already registered: {}", path.display());
             return;
         }
+        self.wait_graph
+            .borrow_mut()
+            .threads
+            .insert(path.clone(), handle.thread().id());
         self.promises
             .borrow_mut()
             .insert(path, Promise::running(handle));
     }
 
+    /// Forgets the analysis of `path`, so that it will be analyzed again when imported.
+    /// If the analysis is running, waits for it to finish (the result is discarded by the caller).
+    pub fn remove(&self, path: &Path) {
+        let promise = self.promises.borrow_mut().get_mut(path).map(|p| p.take());
+        if let Some(promise) = promise {
+            let _result = self.join_checked(path, promise);
+        }
+        self.promises.borrow_mut().remove(path);
+        self.wait_graph.borrow_mut().threads.remove(path);
+        let mut interfaces = self.interfaces.borrow_mut();
+        interfaces.remove(path);
+        for importers in interfaces.values_mut() {
+            importers.remove(path);
+        }
+    }
+
+    /// Marks that the current module refers to the interface of `path` (registered with `Context::interface_path`),
+    /// instead of waiting for the analysis of `path`, which imports the current module.
+    pub fn register_interface(&self, path: PathBuf) {
+        self.interfaces
+            .borrow_mut()
+            .entry(path)
+            .or_default()
+            .insert(self.path.clone());
+    }
+
+    /// Records where the current module imports `path` (used to report errors of waiting for `path`).
+    pub fn register_import_site(&self, path: PathBuf, loc: Location) {
+        self.import_sites
+            .borrow_mut()
+            .insert((self.path.clone(), path), loc);
+    }
+
+    fn import_site(&self, path: &Path) -> Location {
+        self.import_sites
+            .borrow()
+            .get(&(self.path.clone(), path.to_path_buf()))
+            .copied()
+            .unwrap_or(Location::Unknown)
+    }
+
+    /// Does the current module refer to the interface of `path`?
+    pub fn uses_interface(&self, path: &Path) -> bool {
+        self.interfaces
+            .borrow()
+            .get(path)
+            .is_some_and(|importers| importers.contains(&self.path))
+    }
+
+    pub fn len(&self) -> usize {
+        self.promises.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.promises.borrow().is_empty()
+    }
+
+    /// The number of the modules being analyzed
+    pub fn num_running(&self) -> usize {
+        self.promises
+            .borrow()
+            .values()
+            .filter(|promise| !promise.is_finished())
+            .count()
+    }
+
+    /// The number of the analysis threads which are not joined yet
+    pub fn num_threads(&self) -> usize {
+        self.wait_graph.borrow().threads.len()
+    }
+
     pub fn is_registered(&self, path: &Path) -> bool {
         self.promises.borrow().get(path).is_some()
     }
@@ -111,27 +285,93 @@ impl SharedPromises {
             .is_some_and(|promise| promise.is_finished())
     }
 
-    fn join_checked(&self, path: &Path, promise: Promise) -> std::thread::Result<()> {
-        let Promise::Running{ handle, parent } = promise else {
+    /// Registers that the current thread waits for `path`.
+    /// Returns `Err` if the analysis of `path` (transitively) waits for the current thread.
+    fn wait_for(&self, path: &Path) -> Result<(), Deadlock> {
+        // registration and detection are atomic, so that one of the threads in a cycle always notices it
+        let mut graph = self.wait_graph.borrow_mut();
+        if graph.waits_for_current(path) {
+            return Err(Deadlock {
+                waiter: self.path.clone(),
+                target: path.to_path_buf(),
+                loc: self.import_site(path),
+            });
+        }
+        graph.waits.insert(current().id(), path.to_path_buf());
+        Ok(())
+    }
+
+    fn stop_waiting(&self) {
+        self.wait_graph.borrow_mut().waits.remove(&current().id());
+    }
+
+    fn join_checked(&self, path: &Path, promise: Promise) -> Result<(), Deadlock> {
+        let Promise::Running { handle, parent } = promise else {
+            // restore the taken promise (e.g. `Finished`)
+            if let Some(taken) = self.promises.borrow_mut().get_mut(path) {
+                if matches!(taken, Promise::Joining) {
+                    *taken = promise;
+                }
+            }
             return Ok(());
         };
-        if self.graph.ancestors(path).contains(&self.path) || handle.thread().id() == current().id()
-        {
-            // cycle detected, `self.path` must not in the dependencies
-            // Erg analysis processes never join ancestor threads (although joining ancestors itself is allowed in Rust)
+        let cyclic = self.graph.ancestors(path).contains(&self.path);
+        // cycle detected, `self.path` must not in the dependencies
+        // Erg analysis processes never join ancestor threads (although joining ancestors itself is allowed in Rust)
+        // Descendant threads can be joined, because they refer to the interface of `self.path` instead of joining it
+        let skip = handle.thread().id() == current().id()
+            || (cyclic && !self.is_spawned_by_current(parent));
+        let res = if skip { Ok(()) } else { self.wait_for(path) };
+        if skip || res.is_err() {
             *self.promises.borrow_mut().get_mut(path).unwrap() =
                 Promise::Running { parent, handle };
-            return Ok(());
+            return res;
         }
-        let res = handle.join();
+        let result = handle.join();
+        self.stop_waiting();
+        self.wait_graph.borrow_mut().threads.remove(path);
         *self.promises.borrow_mut().get_mut(path).unwrap() = Promise::Finished;
-        res
+        // panics in the analysis are caught and reported as errors, so this is a bug of the compiler
+        if let Err(payload) = result {
+            std::panic::resume_unwind(payload);
+        }
+        Ok(())
+    }
+
+    /// Is the thread spawned by the current thread (directly or indirectly)?
+    fn is_spawned_by_current(&self, mut parent: ThreadId) -> bool {
+        let cur_id = current().id();
+        let promises = self.promises.borrow();
+        for _ in 0..=promises.len() {
+            if parent == cur_id {
+                return true;
+            }
+            let Some(grand_parent) = promises
+                .values()
+                .find(|promise| promise.thread_id() == Some(parent))
+                .and_then(|promise| promise.parent_thread_id())
+            else {
+                return false;
+            };
+            parent = grand_parent;
+        }
+        false
     }
 
-    pub fn join(&self, path: &Path) -> std::thread::Result<()> {
+    /// Waits for the analysis of `path`.
+    /// If the wait takes long, it is checked whether the analysis of `path` waits for the current thread.
+    pub fn join(&self, path: &Path) -> Result<(), Deadlock> {
+        let start = Instant::now();
         while let Some(Promise::Joining) | None = self.promises.borrow().get(path) {
+            if start.elapsed() > DEADLOCK_CHECK_THRESHOLD {
+                if let Err(deadlock) = self.wait_for(path) {
+                    self.stop_waiting();
+                    return Err(deadlock);
+                }
+            }
             std::thread::yield_now();
         }
+        self.stop_waiting();
         let promise = self.promises.borrow_mut().get_mut(path).unwrap().take();
         self.join_checked(path, promise)
     }
@@ -0,0 +1,99 @@
+//! Implements `erg graph`, which prints the dependency graph of the modules.
+use erg_common::config::ErgConfig;
+use erg_common::error::MultiErrorDisplay;
+use erg_common::traits::{ExitStatus, Runnable, Stream};
+
+use crate::artifact::Buildable;
+use crate::build_hir::HIRBuilder;
+use crate::error::{CompileError, CompileErrors};
+use crate::module::SharedCompilerResource;
+
+/// Prints the module graph as DOT (`erg graph --dot`) or JSON (`erg graph --json`).
+/// The graph is printed even if the check fails, so that it can be used to debug import errors.
+#[derive(Debug)]
+pub struct GraphPrinter {
+    pub cfg: ErgConfig,
+    builder: HIRBuilder,
+}
+
+impl Default for GraphPrinter {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for GraphPrinter {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg module graph printer";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self {
+            builder: HIRBuilder::new(cfg.copy()),
+            cfg,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.builder.initialize();
+    }
+
+    fn clear(&mut self) {
+        self.builder.clear();
+    }
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let src = self.cfg.input.read();
+        let (shared, errs) = self.analyze(src);
+        errs.write_all_stderr();
+        println!("{}", self.format(&shared));
+        let code = if errs.is_empty() { 0 } else { 1 };
+        Ok(ExitStatus::new(code, 0, errs.len()))
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        let (shared, _errs) = self.analyze(src);
+        Ok(self.format(&shared))
+    }
+}
+
+impl GraphPrinter {
+    /// Checks the module (and the modules it imports) and returns the shared resource, which has the module graph.
+    pub fn analyze(&mut self, src: String) -> (SharedCompilerResource, CompileErrors) {
+        let errs = match self.builder.build(src, "exec") {
+            Ok(artifact) => {
+                artifact.warns.write_all_stderr();
+                CompileErrors::empty()
+            }
+            Err(artifact) => artifact.errors,
+        };
+        let shared = self
+            .builder
+            .get_context()
+            .map_or_else(SharedCompilerResource::default, |ctx| {
+                ctx.context.shared().clone()
+            });
+        (shared, errs)
+    }
+
+    fn format(&self, shared: &SharedCompilerResource) -> String {
+        // Python modules are distinguished by whether they are registered in `py_mod_cache`
+        if self.cfg.graph_json {
+            format!("{:#}", shared.graph.to_json(&shared.py_mod_cache))
+        } else {
+            shared.graph.to_dot(&shared.py_mod_cache)
+        }
+    }
+}
@@ -0,0 +1,495 @@
+//! Reports where `None` may flow into parameters not typed as optional (`erg audit <file.er>`).
+//!
+//! Erg trusts the declarations of Python modules (`.d.er`), but a Python function may return `None`
+//! regardless of its declaration (e.g. by a bare `return`).
+//! The auditor traces the values of Python declarations through variables and the return values of Erg functions,
+//! across the module graph, into the arguments of parameters that do not accept `NoneType`.
+//!
+//! ```erg
+//! # foo.d.er
+//! .find: (x: Int) -> Int
+//! # main.er
+//! foo = pyimport "foo"
+//! f(x: Int): Int = x + 1
+//! r = foo.find 1
+//! print! f r # reported: the origin is `foo.find`
+//! ```
+//!
+//! The origins are ranked: untyped declarations (typed as `Obj`, or generated in `__pycache__`) first,
+//! then by the number of the flows.
+use std::fmt;
+use std::path::PathBuf;
+
+use erg_common::config::ErgConfig;
+use erg_common::dict::Dict;
+use erg_common::env::erg_path;
+use erg_common::error::MultiErrorDisplay;
+use erg_common::traits::{ExitStatus, Locational, Runnable, Stream};
+use erg_common::{switch_lang, Str};
+
+use crate::build_hir::HIRBuilder;
+use crate::context::Context;
+use crate::error::{CompileError, CompileErrors};
+use crate::hir::{Accessor, Array, Block, Call, Def, Expr, Identifier, Signature, Tuple, HIR};
+use crate::module::SharedCompilerResource;
+use crate::ty::{HasType, Type};
+use crate::varinfo::AbsLocation;
+
+/// The maximum number of the passes over the module graph (to propagate the summaries of functions)
+const MAX_PASSES: usize = 8;
+
+/// A Python declaration whose value may be `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NilOrigin {
+    pub name: Str,
+    pub loc: AbsLocation,
+    /// the declared type (the return type for a subroutine)
+    pub t: Type,
+    /// declared as `Obj` or generated automatically
+    pub untyped: bool,
+}
+
+impl fmt::Display for NilOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.name, fmt_loc(&self.loc), self.t)?;
+        if self.untyped {
+            write!(f, " (untyped)")?;
+        }
+        Ok(())
+    }
+}
+
+/// An argument which may be `None` passed to a parameter not typed as optional.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NilFlow {
+    /// the location of the argument
+    pub loc: AbsLocation,
+    pub callee: Str,
+    pub param_t: Type,
+    /// the variables and functions the value went through
+    pub via: Vec<Str>,
+}
+
+impl fmt::Display for NilFlow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: passed to `{}` (expected `{}`)",
+            fmt_loc(&self.loc),
+            self.callee,
+            self.param_t
+        )?;
+        if !self.via.is_empty() {
+            write!(f, " via {}", self.via.join(" <- "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The flows of an origin, a line of the report.
+#[derive(Debug, Clone)]
+pub struct NilReport {
+    pub origin: NilOrigin,
+    pub flows: Vec<NilFlow>,
+}
+
+fn fmt_loc(loc: &AbsLocation) -> String {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let path = loc.module.as_ref().map_or("?".into(), |path| {
+        path.strip_prefix(&cwd)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    });
+    match (loc.loc.ln_begin(), loc.loc.col_begin()) {
+        (Some(ln), Some(col)) => format!("{path}:{ln}:{}", col + 1),
+        (Some(ln), None) => format!("{path}:{ln}"),
+        _ => path,
+    }
+}
+
+/// Declared in a user's declaration file (the declarations bundled with Erg are trusted).
+fn is_py_decl(loc: &AbsLocation) -> bool {
+    loc.module.as_ref().is_some_and(|path| {
+        path.to_string_lossy().ends_with(".d.er") && !path.starts_with(erg_path())
+    })
+}
+
+#[derive(Debug, Clone)]
+struct Taint {
+    origin: NilOrigin,
+    via: Vec<Str>,
+}
+
+impl Taint {
+    fn through(&self, name: &Str) -> Self {
+        let mut via = vec![name.clone()];
+        via.extend(self.via.iter().cloned());
+        Self {
+            origin: self.origin.clone(),
+            via,
+        }
+    }
+}
+
+/// Propagates the taints of Python values over the HIRs.
+struct NilFlowAnalyzer<'c> {
+    ctx: &'c Context,
+    /// K: the definition of a variable
+    vars: Dict<AbsLocation, Taint>,
+    /// K: the definition of a function, V: the taint of the return value
+    subrs: Dict<AbsLocation, Taint>,
+    flows: Vec<(NilOrigin, NilFlow)>,
+}
+
+impl<'c> NilFlowAnalyzer<'c> {
+    fn new(ctx: &'c Context) -> Self {
+        Self {
+            ctx,
+            vars: Dict::new(),
+            subrs: Dict::new(),
+            flows: vec![],
+        }
+    }
+
+    fn analyze(&mut self, hirs: &[(Option<PathBuf>, HIR)]) {
+        for _ in 0..MAX_PASSES {
+            let known = self.vars.len() + self.subrs.len();
+            self.flows.clear();
+            for (path, hir) in hirs.iter() {
+                for chunk in hir.module.iter() {
+                    self.check_expr(chunk, path);
+                }
+            }
+            if self.vars.len() + self.subrs.len() == known {
+                break;
+            }
+        }
+    }
+
+    fn origin_of(ident: &Identifier) -> NilOrigin {
+        let t = ident.vi.t.return_t().unwrap_or(&ident.vi.t).clone();
+        let generated = ident.vi.def_loc.module.as_ref().is_some_and(|path| {
+            path.components()
+                .any(|comp| comp.as_os_str() == "__pycache__")
+        });
+        // `foo.d.er` -> `foo.find`
+        let mod_name = ident.vi.def_loc.module.as_ref().and_then(|path| {
+            let file_name = path.file_name()?.to_string_lossy();
+            Some(file_name.trim_end_matches(".d.er").to_string())
+        });
+        let name = match mod_name {
+            Some(mod_name) => Str::from(format!("{mod_name}.{}", ident.inspect())),
+            None => ident.inspect().clone(),
+        };
+        NilOrigin {
+            name,
+            loc: ident.vi.def_loc.clone(),
+            untyped: generated || t == Type::Obj,
+            t,
+        }
+    }
+
+    fn ident_taint(&self, ident: &Identifier) -> Option<Taint> {
+        if is_py_decl(&ident.vi.def_loc) {
+            return Some(Taint {
+                origin: Self::origin_of(ident),
+                via: vec![],
+            });
+        }
+        self.vars
+            .get(&ident.vi.def_loc)
+            .map(|taint| taint.through(ident.inspect()))
+    }
+
+    fn callee(call: &Call) -> Option<&Identifier> {
+        if let Some(attr) = &call.attr_name {
+            return Some(attr);
+        }
+        match call.obj.as_ref() {
+            Expr::Accessor(Accessor::Ident(ident)) => Some(ident),
+            Expr::Accessor(Accessor::Attr(attr)) => Some(&attr.ident),
+            _ => None,
+        }
+    }
+
+    /// The taint of the value of `expr`.
+    fn taint_of(&self, expr: &Expr) -> Option<Taint> {
+        match expr {
+            Expr::Accessor(Accessor::Ident(ident)) => self.ident_taint(ident),
+            Expr::Accessor(Accessor::Attr(attr)) => self.ident_taint(&attr.ident),
+            Expr::Call(call) => {
+                let callee = Self::callee(call)?;
+                if is_py_decl(&callee.vi.def_loc) {
+                    return Some(Taint {
+                        origin: Self::origin_of(callee),
+                        via: vec![],
+                    });
+                }
+                self.subrs
+                    .get(&callee.vi.def_loc)
+                    .map(|taint| taint.through(callee.inspect()))
+            }
+            Expr::TypeAsc(tasc) => self.taint_of(&tasc.expr),
+            Expr::Code(block) | Expr::Compound(block) => self.taint_of(block.last()?),
+            _ => None,
+        }
+    }
+
+    /// `None` cannot be passed to `param_t` (generic parameters are not checked).
+    fn rejects_none(&self, param_t: &Type) -> bool {
+        !param_t.has_qvar()
+            && param_t.has_no_unbound_var()
+            && param_t != &Type::Failure
+            && !self.ctx.subtype_of(&Type::NoneType, param_t)
+    }
+
+    fn check_arg(
+        &mut self,
+        arg: &Expr,
+        callee: &Str,
+        param_t: Option<&Type>,
+        path: &Option<PathBuf>,
+    ) {
+        if let (Some(param_t), Some(taint)) = (param_t, self.taint_of(arg)) {
+            if self.rejects_none(param_t) {
+                let flow = NilFlow {
+                    loc: AbsLocation::new(path.clone(), arg.loc()),
+                    callee: callee.clone(),
+                    param_t: param_t.clone(),
+                    via: taint.via,
+                };
+                self.flows.push((taint.origin, flow));
+            }
+        }
+        self.check_expr(arg, path);
+    }
+
+    fn check_call(&mut self, call: &Call, path: &Option<PathBuf>) {
+        self.check_expr(&call.obj, path);
+        let Some(subr_t) = call.signature_t() else {
+            for arg in call.args.pos_args.iter() {
+                self.check_expr(&arg.expr, path);
+            }
+            for arg in call.args.kw_args.iter() {
+                self.check_expr(&arg.expr, path);
+            }
+            return;
+        };
+        let callee = Self::callee(call).map_or(Str::ever("<lambda>"), |ident| {
+            ident.qual_name.clone().unwrap_or(ident.inspect().clone())
+        });
+        let empty = vec![];
+        // `self` is passed as the receiver
+        let skip = usize::from(call.is_method_call());
+        let non_defaults = subr_t.non_default_params().unwrap_or(&empty);
+        let defaults = subr_t.default_params().unwrap_or(&empty);
+        let mut pos_params = non_defaults
+            .iter()
+            .chain(if subr_t.var_params().is_some() {
+                &empty
+            } else {
+                defaults
+            })
+            .skip(skip);
+        for arg in call.args.pos_args.iter() {
+            let param_t = pos_params
+                .next()
+                .or(subr_t.var_params())
+                .map(|param| param.typ().clone());
+            self.check_arg(&arg.expr, &callee, param_t.as_ref(), path);
+        }
+        for arg in call.args.kw_args.iter() {
+            let param_t = non_defaults
+                .iter()
+                .chain(defaults)
+                .find(|param| param.name() == Some(arg.keyword.inspect()))
+                .map(|param| param.typ().clone());
+            self.check_arg(&arg.expr, &callee, param_t.as_ref(), path);
+        }
+    }
+
+    fn check_def(&mut self, def: &Def, path: &Option<PathBuf>) {
+        self.check_block(&def.body.block, path);
+        let Some(taint) = def.body.block.last().and_then(|last| self.taint_of(last)) else {
+            return;
+        };
+        let def_loc = def.sig.ident().vi.def_loc.clone();
+        match &def.sig {
+            Signature::Var(_) => {
+                self.vars.entry(def_loc).or_insert(taint);
+            }
+            Signature::Subr(_) => {
+                self.subrs.entry(def_loc).or_insert(taint);
+            }
+        }
+    }
+
+    fn check_block(&mut self, block: &Block, path: &Option<PathBuf>) {
+        for chunk in block.iter() {
+            self.check_expr(chunk, path);
+        }
+    }
+
+    fn check_expr(&mut self, expr: &Expr, path: &Option<PathBuf>) {
+        match expr {
+            Expr::Call(call) => self.check_call(call, path),
+            Expr::Def(def) => self.check_def(def, path),
+            Expr::ClassDef(class_def) => self.check_block(&class_def.methods, path),
+            Expr::PatchDef(patch_def) => self.check_block(&patch_def.methods, path),
+            Expr::Lambda(lambda) => self.check_block(&lambda.body, path),
+            Expr::BinOp(bin) => {
+                self.check_expr(&bin.lhs, path);
+                self.check_expr(&bin.rhs, path);
+            }
+            Expr::UnaryOp(unary) => self.check_expr(&unary.expr, path),
+            Expr::Accessor(Accessor::Attr(attr)) => self.check_expr(&attr.obj, path),
+            Expr::Array(Array::Normal(arr)) => {
+                for arg in arr.elems.pos_args.iter() {
+                    self.check_expr(&arg.expr, path);
+                }
+            }
+            Expr::Tuple(Tuple::Normal(tup)) => {
+                for arg in tup.elems.pos_args.iter() {
+                    self.check_expr(&arg.expr, path);
+                }
+            }
+            Expr::Record(record) => {
+                for attr in record.attrs.iter() {
+                    self.check_def(attr, path);
+                }
+            }
+            Expr::TypeAsc(tasc) => self.check_expr(&tasc.expr, path),
+            Expr::Code(block) | Expr::Compound(block) => self.check_block(block, path),
+            Expr::Dummy(dummy) => {
+                for chunk in dummy.iter() {
+                    self.check_expr(chunk, path);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Groups the flows by the origins and ranks them.
+    fn report(self) -> Vec<NilReport> {
+        let mut reports = Dict::<NilOrigin, Vec<NilFlow>>::new();
+        for (origin, flow) in self.flows {
+            reports.entry(origin).or_default().push(flow);
+        }
+        let mut reports = reports
+            .into_iter()
+            .map(|(origin, flows)| NilReport { origin, flows })
+            .collect::<Vec<_>>();
+        reports.sort_by(|l, r| {
+            r.origin
+                .untyped
+                .cmp(&l.origin.untyped)
+                .then(r.flows.len().cmp(&l.flows.len()))
+                .then_with(|| fmt_loc(&l.origin.loc).cmp(&fmt_loc(&r.origin.loc)))
+        });
+        reports
+    }
+}
+
+/// Reports the flows of `None` from Python declarations (see the module documentation).
+#[derive(Debug)]
+pub struct NilAuditor {
+    pub cfg: ErgConfig,
+}
+
+impl Default for NilAuditor {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for NilAuditor {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg nil-safety auditor";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self { cfg }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {}
+
+    fn clear(&mut self) {}
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let src = self.cfg.input.read();
+        let reports = self.audit(src)?;
+        println!("{}", Self::format_reports(&reports));
+        Ok(ExitStatus::OK)
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        let reports = self.audit(src)?;
+        Ok(Self::format_reports(&reports))
+    }
+}
+
+impl NilAuditor {
+    /// Audits `src` and the modules it imports.
+    /// Type errors are reported (to stderr) but do not stop the audit, unless no HIR is built.
+    pub fn audit(&mut self, src: String) -> Result<Vec<NilReport>, CompileErrors> {
+        let shared = SharedCompilerResource::new(self.cfg.copy());
+        let mut builder =
+            HIRBuilder::new_with_cache(self.cfg.copy(), Str::ever("<module>"), shared.clone());
+        let main = match builder.build(src, "exec") {
+            Ok(artifact) => artifact.object,
+            Err(artifact) => {
+                artifact.errors.write_all_stderr();
+                artifact.object.ok_or(artifact.errors)?
+            }
+        };
+        let Some(mod_ctx) = builder.pop_mod_ctx() else {
+            return Ok(vec![]);
+        };
+        let main_path = self
+            .cfg
+            .input
+            .path()
+            .map(|path| path.canonicalize().unwrap_or(path.to_path_buf()));
+        let mut hirs = vec![(main_path.clone(), main)];
+        for (path, entry) in shared.mod_cache.ref_inner().iter() {
+            if let Some(hir) = &entry.hir {
+                if Some(path) != main_path.as_ref() {
+                    hirs.push((Some(path.clone()), hir.clone()));
+                }
+            }
+        }
+        let mut analyzer = NilFlowAnalyzer::new(&mod_ctx.context);
+        analyzer.analyze(&hirs);
+        Ok(analyzer.report())
+    }
+
+    pub fn format_reports(reports: &[NilReport]) -> String {
+        let num_flows = reports.iter().map(|r| r.flows.len()).sum::<usize>();
+        let mut out = switch_lang!(
+            "japanese" => format!("{}個の流入元から、NoneTypeを許容しない引数へ{num_flows}個の流入があります", reports.len()),
+            "simplified_chinese" => format!("来自{}个来源的{num_flows}个值可能将 None 传给不接受 NoneType 的参数", reports.len()),
+            "traditional_chinese" => format!("來自{}個來源的{num_flows}個值可能將 None 傳給不接受 NoneType 的參數", reports.len()),
+            "english" => format!("{num_flows} possibly-None value(s) from {} origin(s) flow into parameters not accepting NoneType", reports.len()),
+        );
+        for (i, report) in reports.iter().enumerate() {
+            out.push_str(&format!("\n{}. {}", i + 1, report.origin));
+            for flow in report.flows.iter() {
+                out.push_str(&format!("\n    {flow}"));
+            }
+        }
+        out
+    }
+}
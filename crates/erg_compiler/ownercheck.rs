@@ -14,6 +14,7 @@ use crate::ty::{HasType, Ownership, Visibility};
 
 use crate::error::{OwnershipError, OwnershipErrors};
 use crate::hir::{self, Accessor, Array, Block, Def, Expr, Identifier, Signature, Tuple, HIR};
+use crate::lower::MAX_HISTORY;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum WrapperKind {
@@ -22,7 +23,7 @@ pub enum WrapperKind {
     Box,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 struct LocalVars {
     alive_vars: Set<Str>,
     dropped_vars: Dict<Str, Location>,
@@ -40,6 +41,7 @@ pub struct OwnershipChecker {
     path_stack: Vec<Visibility>,
     dict: Dict<Str, LocalVars>,
     errs: OwnershipErrors,
+    history: Vec<(Vec<Visibility>, Dict<Str, LocalVars>)>,
 }
 
 impl OwnershipChecker {
@@ -49,9 +51,29 @@ impl OwnershipChecker {
             path_stack: vec![],
             dict: Dict::new(),
             errs: OwnershipErrors::empty(),
+            history: vec![],
         }
     }
 
+    /// Saves the current ownership states so that they can be restored by `rollback`.
+    pub(crate) fn snapshot(&mut self) {
+        if self.history.len() >= MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history
+            .push((self.path_stack.clone(), self.dict.clone()));
+    }
+
+    /// Restores the ownership states saved by the last `snapshot`.
+    pub(crate) fn rollback(&mut self) -> bool {
+        let Some((path_stack, dict)) = self.history.pop() else {
+            return false;
+        };
+        self.path_stack = path_stack;
+        self.dict = dict;
+        true
+    }
+
     fn full_path(&self) -> String {
         self.path_stack.iter().fold(String::new(), |acc, vis| {
             if vis.is_public() {
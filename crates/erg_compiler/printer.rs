@@ -0,0 +1,627 @@
+//! Prints `HIR` back to Erg source code.
+//!
+//! The output is valid Erg code that can be checked again, as far as the HIR keeps enough information.
+//! Information lost in lowering (comments, decorators of subroutines, desugared patterns, etc.) is not restored.
+//!
+//! The printer also records where each node was printed ([`SpanMapping`]),
+//! so that the locations of the original source and the printed code can be mapped to each other.
+use erg_common::error::Location;
+use erg_common::traits::{Locational, Stream};
+
+use crate::hir::{
+    Accessor, Args, Array, ClassDef, Def, Dict, Expr, Identifier, Params, Record, Set, Signature,
+    Tuple, HIR,
+};
+use crate::ty::constructors::mono;
+use crate::ty::value::GenTypeObj;
+
+/// The original location of a node and the location where it was printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpanMapping {
+    pub original: Location,
+    pub printed: Location,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrintedSource {
+    pub code: String,
+    /// Innermost nodes come first
+    pub spans: Vec<SpanMapping>,
+}
+
+impl PrintedSource {
+    /// Returns where the innermost node at `original` was printed.
+    pub fn printed_loc(&self, original: Location) -> Option<Location> {
+        self.spans
+            .iter()
+            .find(|span| span.original == original)
+            .map(|span| span.printed)
+    }
+
+    /// Returns the original location of the innermost node printed over `printed`.
+    pub fn original_loc(&self, printed: Location) -> Option<Location> {
+        self.spans
+            .iter()
+            .find(|span| span.printed.contains(printed))
+            .map(|span| span.original)
+    }
+}
+
+/// Returns `true` if `expr` cannot be printed on a single line.
+fn is_multiline(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) | Expr::Accessor(_) | Expr::Import(_) => false,
+        Expr::Array(Array::Normal(arr)) => args_are_multiline(&arr.elems),
+        Expr::Array(Array::WithLength(arr)) => is_multiline(&arr.elem) || is_multiline(&arr.len),
+        Expr::Array(Array::Comprehension(arr)) => {
+            is_multiline(&arr.elem) || is_multiline(&arr.guard)
+        }
+        Expr::Tuple(Tuple::Normal(tup)) => args_are_multiline(&tup.elems),
+        Expr::Set(Set::Normal(set)) => args_are_multiline(&set.elems),
+        Expr::Set(Set::WithLength(set)) => is_multiline(&set.elem) || is_multiline(&set.len),
+        Expr::Dict(Dict::Normal(dict)) => dict
+            .kvs
+            .iter()
+            .any(|kv| is_multiline(&kv.key) || is_multiline(&kv.value)),
+        Expr::Dict(Dict::Comprehension(dict)) => {
+            is_multiline(&dict.key) || is_multiline(&dict.value) || is_multiline(&dict.guard)
+        }
+        Expr::Record(record) => record.attrs.iter().any(def_is_multiline),
+        Expr::BinOp(bin) => is_multiline(&bin.lhs) || is_multiline(&bin.rhs),
+        Expr::UnaryOp(unary) => is_multiline(&unary.expr),
+        Expr::Call(call) => is_multiline(&call.obj) || args_are_multiline(&call.args),
+        Expr::Lambda(lambda) => lambda.body.len() > 1 || lambda.body.iter().any(is_multiline),
+        Expr::Def(def) => def_is_multiline(def),
+        Expr::ClassDef(class_def) => !class_def.methods.is_empty(),
+        Expr::PatchDef(patch_def) => !patch_def.methods.is_empty(),
+        Expr::ReDef(redef) => redef.block.len() > 1 || redef.block.iter().any(is_multiline),
+        Expr::TypeAsc(tasc) => is_multiline(&tasc.expr),
+        Expr::Code(block) | Expr::Compound(block) => {
+            block.len() > 1 || block.iter().any(is_multiline)
+        }
+        Expr::Dummy(dummy) => dummy.len() > 1 || dummy.iter().any(is_multiline),
+    }
+}
+
+fn def_is_multiline(def: &Def) -> bool {
+    def.body.block.len() > 1 || def.body.block.iter().any(is_multiline)
+}
+
+fn args_are_multiline(args: &Args) -> bool {
+    args.pos_args.iter().any(|arg| is_multiline(&arg.expr))
+        || args.var_args.iter().any(|arg| is_multiline(&arg.expr))
+        || args.kw_args.iter().any(|arg| is_multiline(&arg.expr))
+}
+
+/// Statements cannot be written on the right-hand side of `=` or `->`.
+fn is_statement(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Def(_)
+            | Expr::ClassDef(_)
+            | Expr::PatchDef(_)
+            | Expr::ReDef(_)
+            | Expr::Code(_)
+            | Expr::Compound(_)
+            | Expr::Dummy(_)
+    )
+}
+
+/// Returns `true` if `expr` should be enclosed in parentheses as an operand.
+fn needs_parens(expr: &Expr) -> bool {
+    matches!(expr, Expr::BinOp(_) | Expr::Lambda(_) | Expr::TypeAsc(_))
+}
+
+enum Arg<'a> {
+    Pos(&'a Expr),
+    Var(&'a Expr),
+    Kw(&'a str, &'a Expr),
+}
+
+impl Arg<'_> {
+    const fn expr(&self) -> &Expr {
+        match self {
+            Self::Pos(expr) | Self::Var(expr) | Self::Kw(_, expr) => expr,
+        }
+    }
+}
+
+/// Prints `HIR` to Erg source code.
+#[derive(Debug)]
+pub struct HIRPrinter {
+    level: usize,
+    ln: u32,
+    col: u32,
+    code: String,
+    spans: Vec<SpanMapping>,
+}
+
+impl Default for HIRPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HIRPrinter {
+    pub const fn new() -> Self {
+        Self {
+            level: 0,
+            ln: 1,
+            col: 0,
+            code: String::new(),
+            spans: vec![],
+        }
+    }
+
+    pub fn print(mut self, hir: &HIR) -> PrintedSource {
+        for chunk in hir.module.iter() {
+            self.print_expr(chunk);
+            self.write("\n");
+        }
+        self.finish()
+    }
+
+    /// Prints a single expression, assuming that it is placed at indentation `level`.
+    /// The printed locations start at line 1, column 0.
+    pub fn print_expr_at(mut self, expr: &Expr, level: usize) -> PrintedSource {
+        self.level = level;
+        self.print_expr(expr);
+        self.finish()
+    }
+
+    fn finish(self) -> PrintedSource {
+        PrintedSource {
+            code: self.code,
+            spans: self.spans,
+        }
+    }
+
+    fn write(&mut self, s: &str) {
+        for c in s.chars() {
+            if c == '\n' {
+                self.ln += 1;
+                self.col = 0;
+            } else {
+                self.col += 1;
+            }
+        }
+        self.code.push_str(s);
+    }
+
+    fn newline(&mut self) {
+        self.write("\n");
+        self.write(&"    ".repeat(self.level));
+    }
+
+    /// Prints `print` and records the printed location of `original`.
+    fn spanned<L: Locational>(&mut self, original: &L, print: impl FnOnce(&mut Self)) {
+        let (ln_begin, col_begin) = (self.ln, self.col);
+        print(self);
+        let original = original.loc();
+        if !original.is_unknown() {
+            let printed = Location::range(ln_begin, col_begin, self.ln, self.col);
+            self.spans.push(SpanMapping { original, printed });
+        }
+    }
+
+    fn print_expr(&mut self, expr: &Expr) {
+        self.spanned(expr, |printer| printer.print_expr_inner(expr));
+    }
+
+    fn print_operand(&mut self, expr: &Expr) {
+        if needs_parens(expr) {
+            self.write("(");
+            self.print_expr(expr);
+            self.write(")");
+        } else {
+            self.print_expr(expr);
+        }
+    }
+
+    /// `1.f()` is lexed as `1.` and `f()`, so literals are also enclosed.
+    fn print_receiver(&mut self, expr: &Expr) {
+        if needs_parens(expr) || matches!(expr, Expr::Lit(_) | Expr::UnaryOp(_)) {
+            self.write("(");
+            self.print_expr(expr);
+            self.write(")");
+        } else {
+            self.print_expr(expr);
+        }
+    }
+
+    /// Prints each chunk on a new line, one level deeper.
+    fn print_block<'a>(&mut self, chunks: impl IntoIterator<Item = &'a Expr>) {
+        self.level += 1;
+        for chunk in chunks {
+            self.newline();
+            self.print_expr(chunk);
+        }
+        self.level -= 1;
+    }
+
+    /// Prints the right-hand side of `=` or `->`.
+    fn print_body(&mut self, chunks: &[Expr]) {
+        match chunks {
+            [chunk] if !is_statement(chunk) => {
+                self.write(" ");
+                self.print_expr(chunk);
+            }
+            _ => self.print_block(chunks),
+        }
+    }
+
+    /// Prints chunks at the current level (the first one is printed on the current line).
+    fn print_chunks(&mut self, chunks: &[Expr]) {
+        for (i, chunk) in chunks.iter().enumerate() {
+            if i > 0 {
+                self.newline();
+            }
+            self.print_expr(chunk);
+        }
+    }
+
+    fn print_ident(&mut self, ident: &Identifier) {
+        self.spanned(ident, |printer| {
+            printer.write(&format!("{}{}", ident.raw.vis, ident.inspect()));
+        });
+    }
+
+    fn print_attr_name(&mut self, ident: &Identifier) {
+        self.spanned(ident, |printer| {
+            let vis = ident.raw.vis.display_as_accessor();
+            printer.write(&format!("{vis}{}", ident.inspect()));
+        });
+    }
+
+    fn print_accessor(&mut self, acc: &Accessor) {
+        match acc {
+            Accessor::Ident(ident) => {
+                self.spanned(ident, |printer| printer.write(ident.inspect()));
+            }
+            Accessor::Attr(attr) => {
+                self.print_receiver(&attr.obj);
+                self.print_attr_name(&attr.ident);
+            }
+        }
+    }
+
+    fn print_arg(&mut self, arg: &Arg) {
+        match arg {
+            Arg::Pos(expr) => self.print_expr(expr),
+            Arg::Var(expr) => {
+                self.write("*");
+                self.print_expr(expr);
+            }
+            Arg::Kw(keyword, expr) => {
+                self.write(&format!("{keyword} := "));
+                self.print_expr(expr);
+            }
+        }
+    }
+
+    fn print_args_inline(&mut self, args: &[Arg]) {
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                self.write(", ");
+            }
+            self.print_arg(arg);
+        }
+    }
+
+    /// Multi-line arguments (e.g. `for! xs, x => ...`) cannot be enclosed in parentheses.
+    /// ```erg
+    /// f(x, y)
+    /// f x, y ->
+    ///     ...
+    /// f x:
+    ///     y ->
+    ///         ...
+    ///     z
+    /// ```
+    fn print_call_args(&mut self, args: &Args) {
+        let mut all = args
+            .pos_args
+            .iter()
+            .map(|arg| Arg::Pos(&arg.expr))
+            .collect::<Vec<_>>();
+        all.extend(args.var_args.iter().map(|arg| Arg::Var(&arg.expr)));
+        all.extend(
+            args.kw_args
+                .iter()
+                .map(|arg| Arg::Kw(&arg.keyword.content, &arg.expr)),
+        );
+        let multiline = all
+            .iter()
+            .map(|arg| is_multiline(arg.expr()))
+            .collect::<Vec<_>>();
+        match multiline.iter().position(|&b| b) {
+            None => {
+                self.write("(");
+                self.print_args_inline(&all);
+                self.write(")");
+            }
+            // only the last argument is multi-line, or the first argument cannot be placed before `:`
+            Some(i) if i == all.len() - 1 || i == 0 => {
+                self.write(" ");
+                self.print_args_inline(&all);
+            }
+            Some(_) => {
+                self.write(" ");
+                self.print_arg(&all[0]);
+                self.write(":");
+                self.level += 1;
+                for arg in all[1..].iter() {
+                    self.newline();
+                    self.print_arg(arg);
+                }
+                self.level -= 1;
+            }
+        }
+    }
+
+    fn print_params(&mut self, params: &Params) {
+        self.write("(");
+        let mut first = true;
+        let mut sep = |printer: &mut Self| {
+            if !first {
+                printer.write(", ");
+            }
+            first = false;
+        };
+        for param in params.non_defaults.iter() {
+            sep(self);
+            self.write(&param.raw.to_string());
+        }
+        if let Some(var_params) = &params.var_params {
+            sep(self);
+            self.write(&format!("*{}", var_params.raw));
+        }
+        for param in params.defaults.iter() {
+            sep(self);
+            self.write(&format!("{} := ", param.sig.raw));
+            self.print_expr(&param.default_val);
+        }
+        self.write(")");
+    }
+
+    fn print_sig(&mut self, sig: &Signature) {
+        match sig {
+            Signature::Var(var) => {
+                self.print_ident(&var.ident);
+                if let Some(t_spec) = &var.t_spec {
+                    self.write(&t_spec.raw.to_string());
+                }
+            }
+            Signature::Subr(subr) => {
+                self.print_ident(&subr.ident);
+                if !subr.bounds.is_empty() {
+                    self.write(&format!("|{}|", subr.bounds));
+                }
+                self.print_params(&subr.params);
+                if let Some(t_spec) = &subr.return_t_spec {
+                    self.write(&t_spec.raw.to_string());
+                }
+            }
+        }
+    }
+
+    fn print_def(&mut self, def: &Def) {
+        self.spanned(def, |printer| {
+            printer.print_sig(&def.sig);
+            printer.write(&format!(" {}", def.body.op.content));
+            printer.print_body(def.body.block.ref_payload());
+        });
+    }
+
+    /// Prints the methods as
+    /// ```erg
+    /// C.
+    ///     f self = ...
+    /// ```
+    fn print_methods(&mut self, sig: &Signature, methods: &[Expr]) {
+        if methods.is_empty() {
+            return;
+        }
+        self.newline();
+        self.write(&format!("{}.", sig.ident().inspect()));
+        self.print_block(methods);
+    }
+
+    /// Restores the decorators and the type generator call (`Class`, `Inherit`, ...).
+    fn print_class_def(&mut self, class_def: &ClassDef) {
+        let (gen, inheritable, derives) = match &class_def.obj {
+            GenTypeObj::Class(class) => (
+                "Class",
+                class.impls.as_ref(),
+                class.derives.iter().map(|d| d.trait_.to_string()).collect(),
+            ),
+            GenTypeObj::Subclass(class) => (
+                "Inherit",
+                class.impls.as_ref(),
+                class.derives.iter().map(|d| d.trait_.to_string()).collect(),
+            ),
+            GenTypeObj::Trait(_) => ("Trait", None, vec![]),
+            GenTypeObj::Subtrait(_) => ("Subsume", None, vec![]),
+            _ => ("Class", None, vec![]),
+        };
+        let inheritable =
+            inheritable.is_some_and(|impls| impls.contains_intersec(&mono("InheritableType")));
+        if inheritable {
+            self.write("@Inheritable");
+            self.newline();
+        }
+        if !derives.is_empty() {
+            self.write(&format!("@Derive [{}]", derives.join(", ")));
+            self.newline();
+        }
+        self.print_sig(&class_def.sig);
+        self.write(&format!(" = {gen}"));
+        if let Some(req) = &class_def.require_or_sup {
+            self.write(" ");
+            self.print_expr(req);
+        } else {
+            self.write("()");
+        }
+        self.print_methods(&class_def.sig, class_def.methods.ref_payload());
+    }
+
+    fn print_record(&mut self, record: &Record) {
+        if record.attrs.is_empty() {
+            self.write("{=}");
+        } else if record.attrs.iter().any(def_is_multiline) {
+            self.write("{");
+            self.level += 1;
+            for attr in record.attrs.iter() {
+                self.newline();
+                self.print_def(attr);
+            }
+            self.level -= 1;
+            self.newline();
+            self.write("}");
+        } else {
+            self.write("{ ");
+            for (i, attr) in record.attrs.iter().enumerate() {
+                if i > 0 {
+                    self.write("; ");
+                }
+                self.print_def(attr);
+            }
+            self.write(" }");
+        }
+    }
+
+    fn print_expr_inner(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Lit(lit) => self.write(&lit.token.content),
+            Expr::Accessor(acc) | Expr::Import(acc) => self.print_accessor(acc),
+            Expr::Array(Array::Normal(arr)) => {
+                self.write("[");
+                let elems = arr
+                    .elems
+                    .pos_args
+                    .iter()
+                    .map(|arg| Arg::Pos(&arg.expr))
+                    .collect::<Vec<_>>();
+                self.print_args_inline(&elems);
+                self.write("]");
+            }
+            Expr::Array(Array::WithLength(arr)) => {
+                self.write("[");
+                self.print_expr(&arr.elem);
+                self.write("; ");
+                self.print_expr(&arr.len);
+                self.write("]");
+            }
+            Expr::Array(Array::Comprehension(arr)) => {
+                self.write("[");
+                self.print_expr(&arr.elem);
+                self.write(" | ");
+                self.print_expr(&arr.guard);
+                self.write("]");
+            }
+            Expr::Tuple(Tuple::Normal(tup)) => {
+                self.write("(");
+                let elems = tup
+                    .elems
+                    .pos_args
+                    .iter()
+                    .map(|arg| Arg::Pos(&arg.expr))
+                    .collect::<Vec<_>>();
+                self.print_args_inline(&elems);
+                if elems.len() == 1 {
+                    self.write(",");
+                }
+                self.write(")");
+            }
+            Expr::Set(Set::Normal(set)) => {
+                self.write("{");
+                let elems = set
+                    .elems
+                    .pos_args
+                    .iter()
+                    .map(|arg| Arg::Pos(&arg.expr))
+                    .collect::<Vec<_>>();
+                self.print_args_inline(&elems);
+                self.write("}");
+            }
+            Expr::Set(Set::WithLength(set)) => {
+                self.write("{");
+                self.print_expr(&set.elem);
+                self.write("; ");
+                self.print_expr(&set.len);
+                self.write("}");
+            }
+            Expr::Dict(Dict::Normal(dict)) => {
+                if dict.kvs.is_empty() {
+                    self.write("{:}");
+                    return;
+                }
+                self.write("{");
+                for (i, kv) in dict.kvs.iter().enumerate() {
+                    if i > 0 {
+                        self.write(", ");
+                    }
+                    self.print_expr(&kv.key);
+                    self.write(": ");
+                    self.print_expr(&kv.value);
+                }
+                self.write("}");
+            }
+            Expr::Dict(Dict::Comprehension(dict)) => {
+                self.write("{");
+                self.print_expr(&dict.key);
+                self.write(": ");
+                self.print_expr(&dict.value);
+                self.write(" | ");
+                self.print_expr(&dict.guard);
+                self.write("}");
+            }
+            Expr::Record(record) => self.print_record(record),
+            Expr::BinOp(bin) => {
+                self.print_operand(&bin.lhs);
+                self.write(&format!(" {} ", bin.op.content));
+                self.print_operand(&bin.rhs);
+            }
+            Expr::UnaryOp(unary) => {
+                self.write(&unary.op.content);
+                self.print_operand(&unary.expr);
+            }
+            Expr::Call(call) => {
+                self.print_receiver(&call.obj);
+                if let Some(attr_name) = &call.attr_name {
+                    self.print_attr_name(attr_name);
+                }
+                self.print_call_args(&call.args);
+            }
+            Expr::Lambda(lambda) => {
+                self.print_params(&lambda.params);
+                self.write(&format!(" {}", lambda.op.content));
+                self.print_body(lambda.body.ref_payload());
+            }
+            Expr::Def(def) => self.print_def(def),
+            Expr::ClassDef(class_def) => self.print_class_def(class_def),
+            Expr::PatchDef(patch_def) => {
+                self.print_sig(&patch_def.sig);
+                self.write(" = Patch ");
+                self.print_expr(&patch_def.base);
+                self.print_methods(&patch_def.sig, patch_def.methods.ref_payload());
+            }
+            Expr::ReDef(redef) => {
+                self.print_accessor(&redef.attr);
+                self.write(" =");
+                self.print_body(redef.block.ref_payload());
+            }
+            Expr::TypeAsc(tasc) => {
+                self.print_operand(&tasc.expr);
+                self.write(&tasc.spec.raw.to_string());
+            }
+            Expr::Code(block) | Expr::Compound(block) => self.print_chunks(block.ref_payload()),
+            Expr::Dummy(dummy) => self.print_chunks(dummy.ref_payload()),
+        }
+    }
+}
+
+/// Prints `hir` to Erg source code.
+pub fn print_hir(hir: &HIR) -> PrintedSource {
+    HIRPrinter::new().print(hir)
+}
@@ -0,0 +1,2003 @@
+//! Converts Python stub files (`.pyi`) into Erg declarations (`.d.er`).
+//!
+//! If no declaration file is found for `pyimport "foo"`, the stub `foo.pyi` is used instead
+//! (see `Input::resolve_pyi_path` for the search order; typeshed can be used via `ERG_TYPESHED_PATH`).
+//! The stub is converted by [`PyiConverter`] and checked in the declare mode like an ordinary `.d.er` file,
+//! so the module gets the real signatures instead of `Obj`.
+//!
+//! Only the subset of Python used in stubs is understood: imports, `if sys.version_info`/`sys.platform` blocks,
+//! `TypeVar`s, type aliases, variables, (overloaded) functions and classes.
+//! Things that cannot be expressed in Erg (`**kwargs`, `ParamSpec`s, names imported from other modules, ...)
+//! are dropped or widened to `Obj`.
+use std::cell::RefCell;
+
+use erg_common::dict::Dict;
+use erg_common::python_util::PythonVersion;
+use erg_common::set::Set;
+
+/// The modules whose names are mapped to Erg's builtin types
+const TYPING_MODULES: [&str; 8] = [
+    "typing",
+    "typing_extensions",
+    "collections",
+    "collections.abc",
+    "builtins",
+    "_typeshed",
+    "types",
+    "enum",
+];
+
+/// `Obj` already has them (or they cannot be declared)
+const OBJ_DUNDERS: [&str; 24] = [
+    "__init__",
+    "__new__",
+    "__call__",
+    "__init_subclass__",
+    "__class_getitem__",
+    "__eq__",
+    "__ne__",
+    "__hash__",
+    "__repr__",
+    "__str__",
+    "__format__",
+    "__sizeof__",
+    "__reduce__",
+    "__reduce_ex__",
+    "__getattr__",
+    "__getattribute__",
+    "__setattr__",
+    "__delattr__",
+    "__dir__",
+    "__slots__",
+    "__match_args__",
+    "__dataclass_fields__",
+    "__annotations__",
+    "__module__",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Num(String),
+    Str(String),
+    Bytes,
+    Op(String),
+}
+
+fn read_str(chars: &[char], mut i: usize) -> (String, usize) {
+    let quote = chars[i];
+    let triple = chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote);
+    i += if triple { 3 } else { 1 };
+    let mut s = String::new();
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\\' {
+            if let Some(&next) = chars.get(i + 1) {
+                s.push(next);
+            }
+            i += 2;
+            continue;
+        }
+        if c == quote
+            && (!triple || (chars.get(i + 1) == Some(&quote) && chars.get(i + 2) == Some(&quote)))
+        {
+            i += if triple { 3 } else { 1 };
+            break;
+        }
+        s.push(c);
+        i += 1;
+    }
+    (s, i)
+}
+
+fn tokenize(code: &str) -> Vec<Token> {
+    let chars = code.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let name = chars[start..i].iter().collect::<String>();
+            let is_prefix = name.len() <= 2 && name.chars().all(|c| "rRbBuUfF".contains(c));
+            if is_prefix && matches!(chars.get(i), Some('"' | '\'')) {
+                let (s, next) = read_str(&chars, i);
+                i = next;
+                if name.contains(['b', 'B']) {
+                    tokens.push(Token::Bytes);
+                } else {
+                    tokens.push(Token::Str(s));
+                }
+            } else {
+                tokens.push(Token::Name(name));
+            }
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Num(chars[start..i].iter().collect()));
+        } else if c == '"' || c == '\'' {
+            let (s, next) = read_str(&chars, i);
+            i = next;
+            tokens.push(Token::Str(s));
+        } else {
+            let rest = chars[i..(i + 3).min(chars.len())]
+                .iter()
+                .collect::<String>();
+            let op = ["...", "**", "->", "<=", ">=", "==", "!=", ":="]
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .map_or(c.to_string(), |op| op.to_string());
+            i += op.chars().count();
+            tokens.push(Token::Op(op));
+        }
+    }
+    tokens
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PyExpr {
+    Name(String),
+    Attr(Box<PyExpr>, String),
+    Subscript(Box<PyExpr>, Vec<PyExpr>),
+    Call(Box<PyExpr>, Vec<(Option<String>, PyExpr)>),
+    BitOr(Box<PyExpr>, Box<PyExpr>),
+    Tuple(Vec<PyExpr>),
+    List(Vec<PyExpr>),
+    Str(String),
+    Num(String),
+    Bytes,
+    Ellipsis,
+    Not(Box<PyExpr>),
+    And(Box<PyExpr>, Box<PyExpr>),
+    Or(Box<PyExpr>, Box<PyExpr>),
+    Compare(Box<PyExpr>, String, Box<PyExpr>),
+}
+
+impl PyExpr {
+    /// `a.b.c` -> `Some("a.b.c")`
+    fn dotted(&self) -> Option<String> {
+        match self {
+            Self::Name(name) => Some(name.clone()),
+            Self::Attr(obj, attr) => Some(format!("{}.{attr}", obj.dotted()?)),
+            _ => None,
+        }
+    }
+
+    /// `a.B[T]` -> `Some("a.B")`
+    fn base_name(&self) -> Option<String> {
+        match self {
+            Self::Subscript(base, _) => base.dotted(),
+            _ => self.dotted(),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(code: &str) -> Self {
+        Self {
+            tokens: tokenize(code),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_op(&self, op: &str) -> bool {
+        matches!(self.peek(), Some(Token::Op(o)) if o == op)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        if self.peek_op(op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_name(&mut self, name: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Name(n)) if n == name) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        match self.next()? {
+            Token::Name(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    fn expect_op(&mut self, op: &str) -> Option<()> {
+        self.eat_op(op).then_some(())
+    }
+
+    fn is_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    /// Skips an expression (e.g. a default value) until `,` or `)`.
+    fn skip_expr(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Op(op) if depth == 0 && (op == "," || op == ")") => break,
+                Token::Op(op) if op == "(" || op == "[" || op == "{" => depth += 1,
+                Token::Op(op) if op == ")" || op == "]" || op == "}" => depth -= 1,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    fn expr(&mut self) -> Option<PyExpr> {
+        let mut lhs = self.and_test()?;
+        while self.eat_name("or") {
+            lhs = PyExpr::Or(Box::new(lhs), Box::new(self.and_test()?));
+        }
+        Some(lhs)
+    }
+
+    fn and_test(&mut self) -> Option<PyExpr> {
+        let mut lhs = self.not_test()?;
+        while self.eat_name("and") {
+            lhs = PyExpr::And(Box::new(lhs), Box::new(self.not_test()?));
+        }
+        Some(lhs)
+    }
+
+    fn not_test(&mut self) -> Option<PyExpr> {
+        if self.eat_name("not") {
+            Some(PyExpr::Not(Box::new(self.not_test()?)))
+        } else {
+            self.comparison()
+        }
+    }
+
+    fn comparison(&mut self) -> Option<PyExpr> {
+        let lhs = self.bitor()?;
+        for op in ["<", ">", "<=", ">=", "==", "!="] {
+            if self.eat_op(op) {
+                let rhs = self.bitor()?;
+                return Some(PyExpr::Compare(
+                    Box::new(lhs),
+                    op.to_string(),
+                    Box::new(rhs),
+                ));
+            }
+        }
+        Some(lhs)
+    }
+
+    fn bitor(&mut self) -> Option<PyExpr> {
+        let mut lhs = self.postfix()?;
+        while self.eat_op("|") {
+            lhs = PyExpr::BitOr(Box::new(lhs), Box::new(self.postfix()?));
+        }
+        Some(lhs)
+    }
+
+    fn postfix(&mut self) -> Option<PyExpr> {
+        let mut expr = self.atom()?;
+        loop {
+            if self.eat_op(".") {
+                expr = PyExpr::Attr(Box::new(expr), self.name()?);
+            } else if self.eat_op("[") {
+                let (args, _) = self.expr_list("]")?;
+                expr = PyExpr::Subscript(Box::new(expr), args);
+            } else if self.eat_op("(") {
+                let mut args = vec![];
+                while !self.eat_op(")") {
+                    let key = match (self.tokens.get(self.pos), self.tokens.get(self.pos + 1)) {
+                        (Some(Token::Name(key)), Some(Token::Op(op))) if op == "=" => {
+                            let key = key.clone();
+                            self.pos += 2;
+                            Some(key)
+                        }
+                        _ => None,
+                    };
+                    args.push((key, self.expr()?));
+                    if !self.eat_op(",") {
+                        self.expect_op(")")?;
+                        break;
+                    }
+                }
+                expr = PyExpr::Call(Box::new(expr), args);
+            } else {
+                return Some(expr);
+            }
+        }
+    }
+
+    fn atom(&mut self) -> Option<PyExpr> {
+        match self.next()? {
+            Token::Name(name) => Some(PyExpr::Name(name)),
+            Token::Num(num) => Some(PyExpr::Num(num)),
+            Token::Str(mut s) => {
+                while let Some(Token::Str(next)) = self.peek() {
+                    s.push_str(next);
+                    self.pos += 1;
+                }
+                Some(PyExpr::Str(s))
+            }
+            Token::Bytes => Some(PyExpr::Bytes),
+            Token::Op(op) => match &op[..] {
+                "..." => Some(PyExpr::Ellipsis),
+                "-" => match self.next()? {
+                    Token::Num(num) => Some(PyExpr::Num(format!("-{num}"))),
+                    _ => None,
+                },
+                "(" => {
+                    let (mut elems, comma) = self.expr_list(")")?;
+                    if elems.len() == 1 && !comma {
+                        elems.pop()
+                    } else {
+                        Some(PyExpr::Tuple(elems))
+                    }
+                }
+                "[" => Some(PyExpr::List(self.expr_list("]")?.0)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Returns the elements and whether a comma is included.
+    fn expr_list(&mut self, close: &str) -> Option<(Vec<PyExpr>, bool)> {
+        let mut elems = vec![];
+        let mut comma = false;
+        loop {
+            if self.eat_op(close) {
+                return Some((elems, comma));
+            }
+            elems.push(self.expr()?);
+            if self.eat_op(",") {
+                comma = true;
+            } else {
+                self.expect_op(close)?;
+                return Some((elems, comma));
+            }
+        }
+    }
+}
+
+/// A logical line and the block following it
+#[derive(Debug, Clone)]
+struct Stmt {
+    text: String,
+    body: Vec<Stmt>,
+}
+
+struct Line {
+    indent: usize,
+    text: String,
+}
+
+/// Joins the continuation lines and removes the comments.
+fn logical_lines(src: &str) -> Vec<Line> {
+    let chars = src.chars().collect::<Vec<_>>();
+    let mut lines = vec![];
+    let mut text = String::new();
+    let mut indent = 0;
+    let mut depth = 0usize;
+    let mut line_start = true;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if line_start {
+            if c == ' ' || c == '\t' {
+                indent += 1;
+                i += 1;
+                continue;
+            }
+            line_start = false;
+        }
+        match c {
+            '#' => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            '"' | '\'' => {
+                let (_, next) = read_str(&chars, i);
+                text.extend(&chars[i..next]);
+                i = next;
+                continue;
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '\\' if chars.get(i + 1) == Some(&'\n') => {
+                text.push(' ');
+                i += 2;
+                continue;
+            }
+            '\n' if depth == 0 => {
+                if !text.trim().is_empty() {
+                    lines.push(Line {
+                        indent,
+                        text: text.trim().to_string(),
+                    });
+                }
+                text.clear();
+                indent = 0;
+                line_start = true;
+                i += 1;
+                continue;
+            }
+            '\n' | '\r' => {
+                text.push(' ');
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        text.push(c);
+        i += 1;
+    }
+    if !text.trim().is_empty() {
+        lines.push(Line {
+            indent,
+            text: text.trim().to_string(),
+        });
+    }
+    lines
+}
+
+/// `def f(x: int) -> int: ...` -> (`def f(x: int) -> int`, Some(`...`))
+fn split_header(text: &str) -> (String, Option<String>) {
+    let keywords = [
+        "class ",
+        "def ",
+        "async def ",
+        "if ",
+        "elif ",
+        "else",
+        "try",
+        "except",
+        "finally",
+        "with ",
+        "for ",
+        "while ",
+    ];
+    if !keywords.iter().any(|kw| text.starts_with(kw)) {
+        return (text.to_string(), None);
+    }
+    let chars = text.char_indices().collect::<Vec<_>>();
+    let mut depth = 0usize;
+    let mut quote = None;
+    for (idx, c) in chars {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}') => depth = depth.saturating_sub(1),
+            (None, ':') if depth == 0 => {
+                let header = text[..idx].trim().to_string();
+                let body = text[idx + 1..].trim().to_string();
+                return (header, Some(body));
+            }
+            _ => {}
+        }
+    }
+    (text.to_string(), None)
+}
+
+fn parse_block(lines: &[Line], idx: &mut usize) -> Vec<Stmt> {
+    let Some(indent) = lines.get(*idx).map(|line| line.indent) else {
+        return vec![];
+    };
+    let mut stmts = vec![];
+    while let Some(line) = lines.get(*idx) {
+        if line.indent < indent {
+            break;
+        }
+        *idx += 1;
+        let (text, inline) = split_header(&line.text);
+        let body = match inline {
+            Some(inline) if !inline.is_empty() => inline
+                .split(';')
+                .map(|text| Stmt {
+                    text: text.trim().to_string(),
+                    body: vec![],
+                })
+                .collect(),
+            Some(_)
+                if lines
+                    .get(*idx)
+                    .is_some_and(|next| next.indent > line.indent) =>
+            {
+                parse_block(lines, idx)
+            }
+            _ => vec![],
+        };
+        stmts.push(Stmt { text, body });
+    }
+    stmts
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParamKind {
+    Normal,
+    VarArgs,
+    KwArgs,
+}
+
+#[derive(Debug, Clone)]
+struct Param {
+    name: String,
+    annot: Option<PyExpr>,
+    default: bool,
+    kw_only: bool,
+    kind: ParamKind,
+}
+
+/// `@dataclass(frozen=True)` => `dataclass`
+fn decorator_name(deco: &str) -> &str {
+    deco.split('(').next().unwrap_or(deco).trim()
+}
+
+#[derive(Debug, Clone)]
+struct FuncDef {
+    name: String,
+    params: Vec<Param>,
+    ret: Option<PyExpr>,
+    decorators: Vec<String>,
+    is_async: bool,
+}
+
+impl FuncDef {
+    fn parse(text: &str, decorators: Vec<String>) -> Option<Self> {
+        let mut parser = Parser::new(text);
+        let is_async = parser.eat_name("async");
+        if !parser.eat_name("def") {
+            return None;
+        }
+        let name = parser.name()?;
+        parser.expect_op("(")?;
+        let mut params = vec![];
+        let mut kw_only = false;
+        while !parser.eat_op(")") {
+            if parser.eat_op("/") {
+                parser.eat_op(",");
+                continue;
+            }
+            let kind = if parser.eat_op("**") {
+                ParamKind::KwArgs
+            } else if parser.eat_op("*") {
+                ParamKind::VarArgs
+            } else {
+                ParamKind::Normal
+            };
+            // bare `*`
+            if kind == ParamKind::VarArgs && (parser.peek_op(",") || parser.peek_op(")")) {
+                kw_only = true;
+                parser.eat_op(",");
+                continue;
+            }
+            let name = parser.name()?;
+            let annot = if parser.eat_op(":") {
+                Some(parser.expr()?)
+            } else {
+                None
+            };
+            let default = parser.eat_op("=");
+            if default {
+                parser.skip_expr();
+            }
+            params.push(Param {
+                name,
+                annot,
+                default,
+                kw_only,
+                kind,
+            });
+            if kind == ParamKind::VarArgs {
+                kw_only = true;
+            }
+            if !parser.eat_op(",") {
+                parser.expect_op(")")?;
+                break;
+            }
+        }
+        let ret = if parser.eat_op("->") {
+            parser.expr()
+        } else {
+            None
+        };
+        Some(Self {
+            name,
+            params,
+            ret,
+            decorators,
+            is_async,
+        })
+    }
+
+    fn has_decorator(&self, name: &str) -> bool {
+        self.decorators.iter().any(|deco| {
+            let deco = decorator_name(deco);
+            deco == name || deco.ends_with(&format!(".{name}"))
+        })
+    }
+
+    fn is_overload(&self) -> bool {
+        self.has_decorator("overload")
+    }
+
+    fn is_accessor(&self) -> bool {
+        self.has_decorator("setter") || self.has_decorator("deleter")
+    }
+}
+
+/// Overloaded functions are merged.
+fn push_def(defs: &mut Vec<(String, Vec<FuncDef>)>, def: FuncDef) {
+    if def.is_accessor() {
+        return;
+    }
+    match defs.iter_mut().find(|(name, _)| name == &def.name) {
+        Some((_, overloads)) if def.is_overload() && overloads.iter().all(|d| d.is_overload()) => {
+            overloads.push(def);
+        }
+        Some((_, overloads)) => *overloads = vec![def],
+        None => defs.push((def.name.clone(), vec![def])),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ClassDef {
+    name: String,
+    bases: Vec<PyExpr>,
+    decorators: Vec<String>,
+    body: Vec<Stmt>,
+    /// the fields given by the functional syntax (e.g. `P = NamedTuple("P", [("x", int)])`)
+    fields: Vec<Field>,
+}
+
+/// A field of a dataclass or a `NamedTuple`, i.e. a parameter of the synthesized `__init__`
+#[derive(Debug, Clone)]
+struct DataField {
+    name: String,
+    t: String,
+    default: bool,
+    kw_only: bool,
+    /// `false`: `field(init=False)`
+    init: bool,
+}
+
+/// `(x: Int, y := Int, z := Int)` (`z` is keyword-only)
+fn data_params(fields: &[DataField]) -> String {
+    let mut non_defaults = vec![];
+    let mut defaults = vec![];
+    for field in fields.iter().filter(|field| field.init && !field.kw_only) {
+        if field.default || !defaults.is_empty() {
+            defaults.push(format!("{} := {}", field.name, field.t));
+        } else {
+            non_defaults.push(format!("{}: {}", field.name, field.t));
+        }
+    }
+    for field in fields.iter().filter(|field| field.init && field.kw_only) {
+        defaults.push(format!("{} := {}", field.name, field.t));
+    }
+    non_defaults.extend(defaults);
+    format!("({})", non_defaults.join(", "))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MethodKind {
+    Instance,
+    Static,
+    Class,
+    Property,
+    /// a member of a structural type (the receiver is omitted)
+    Bound,
+}
+
+#[derive(Debug, Default)]
+struct TyScope {
+    class: Option<String>,
+    /// the type parameters of the class, which are replaced with `Obj`
+    class_params: Set<String>,
+    /// the type variables to be quantified
+    quantified: Vec<String>,
+    /// `true`: type variables are replaced with `Obj`
+    no_quant: bool,
+    depth: usize,
+}
+
+impl TyScope {
+    fn new(class: Option<&ClassDef>, class_params: Set<String>) -> Self {
+        Self {
+            class: class.map(|class| class.name.clone()),
+            class_params,
+            ..Default::default()
+        }
+    }
+}
+
+fn type_var_name(name: &str) -> String {
+    let trimmed = name.trim_start_matches('_');
+    if trimmed.is_empty() {
+        name.to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+fn param_name(name: &str) -> &str {
+    name.strip_prefix("__")
+        .filter(|stripped| !stripped.is_empty() && !stripped.ends_with("__"))
+        .unwrap_or(name)
+}
+
+fn is_public(name: &str, in_class: bool) -> bool {
+    if name.starts_with("__") && name.ends_with("__") {
+        in_class && !OBJ_DUNDERS.contains(&name)
+    } else {
+        !name.starts_with('_')
+    }
+}
+
+/// `(Int) -> Int` (not `((Int) -> Int)` or `[(Int) -> Int; _]`)
+fn is_subr_type(t: &str) -> bool {
+    let mut depth = 0usize;
+    for (i, c) in t.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '-' if depth == 0 && t[i..].starts_with("->") => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn paren_subr(t: String) -> String {
+    if is_subr_type(&t) {
+        format!("({t})")
+    } else {
+        t
+    }
+}
+
+fn simple_type(name: &str) -> Option<&'static str> {
+    let t = match name {
+        "int" => "Int",
+        "float" => "Float",
+        "complex" => "Complex",
+        "bool" => "Bool",
+        "str" | "LiteralString" | "AnyStr" | "StrPath" | "Text" => "Str",
+        "bytes" | "bytearray" | "memoryview" | "ReadableBuffer" | "WriteableBuffer"
+        | "ReadOnlyBuffer" | "ByteString" => "Bytes",
+        "None" | "NoneType" => "NoneType",
+        "NoReturn" | "Never" => "Never",
+        "type" | "Type" => "ClassType",
+        "list" | "List" | "Sequence" | "MutableSequence" => "[Obj; _]",
+        "tuple" | "Tuple" => "GenericTuple",
+        "dict" | "Dict" | "Mapping" | "MutableMapping" | "DefaultDict" | "defaultdict"
+        | "OrderedDict" | "ChainMap" | "Counter" => "GenericDict",
+        "set" | "Set" | "frozenset" | "FrozenSet" | "AbstractSet" | "MutableSet" => "{Obj; _}",
+        "Iterable" | "Collection" | "Container" | "Reversible" => "Iterable(Obj)",
+        "Iterator" | "Generator" => "Iterator(Obj)",
+        "Callable" => "GenericCallable",
+        _ => return None,
+    };
+    Some(t)
+}
+
+fn literal_value(expr: &PyExpr) -> Option<String> {
+    match expr {
+        PyExpr::Str(s) => Some(format!(
+            "\"{}\"",
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        )),
+        PyExpr::Num(num) if num.parse::<i64>().is_ok() => Some(num.clone()),
+        PyExpr::Name(name) if name == "True" || name == "False" => Some(name.clone()),
+        _ => None,
+    }
+}
+
+/// `1` -> `int`
+fn literal_type(expr: &PyExpr) -> Option<&'static str> {
+    match expr {
+        PyExpr::Str(_) => Some("str"),
+        PyExpr::Bytes => Some("bytes"),
+        PyExpr::Num(num) if num.parse::<i64>().is_ok() => Some("int"),
+        PyExpr::Num(num) if num.parse::<f64>().is_ok() => Some("float"),
+        PyExpr::Name(name) if name == "True" || name == "False" => Some("bool"),
+        PyExpr::Name(name) if name == "None" => Some("None"),
+        _ => None,
+    }
+}
+
+/// (name, annotation, value)
+type Field = (String, Option<PyExpr>, Option<PyExpr>);
+
+/// `name: annot = value`, `name: annot`, `name = value`
+fn parse_assign(text: &str) -> Option<Field> {
+    let mut parser = Parser::new(text);
+    let name = parser.name()?;
+    let annot = if parser.eat_op(":") {
+        Some(parser.expr()?)
+    } else {
+        None
+    };
+    let value = if parser.eat_op("=") {
+        parser.expr()
+    } else {
+        None
+    };
+    (parser.is_end() && (annot.is_some() || value.is_some())).then_some((name, annot, value))
+}
+
+/// Converts Python stub files (`.pyi`) into Erg declarations.
+#[derive(Debug)]
+pub struct PyiConverter {
+    version: PythonVersion,
+    platform: &'static str,
+    /// local name -> qualified name (e.g. `from typing import List as L` => `L` -> `typing.List`)
+    imports: Dict<String, String>,
+    /// type variable -> bound
+    type_vars: Dict<String, Option<PyExpr>>,
+    aliases: Dict<String, PyExpr>,
+    classes: Vec<ClassDef>,
+    class_names: Set<String>,
+    /// `Protocol` classes, which are expanded to `Structural { ... }` types where referenced
+    protocols: Dict<String, ClassDef>,
+    /// the protocols being expanded and their type arguments (e.g. `P[int]` => `("P", {T: Int})`)
+    expanding: RefCell<Vec<(String, Dict<String, String>)>>,
+    vars: Vec<(String, PyExpr)>,
+    funcs: Vec<(String, Vec<FuncDef>)>,
+    /// class name -> the overloaded constructor parameters (e.g. `|T|(x: T)`)
+    ctors: Dict<String, Vec<String>>,
+    /// dataclass name -> the fields (to be inherited by the subclasses)
+    data_fields: Dict<String, Vec<DataField>>,
+}
+
+impl PyiConverter {
+    pub fn new(version: PythonVersion) -> Self {
+        let platform = match std::env::consts::OS {
+            "macos" => "darwin",
+            "windows" => "win32",
+            os => os,
+        };
+        Self {
+            version,
+            platform,
+            imports: Dict::new(),
+            type_vars: Dict::new(),
+            aliases: Dict::new(),
+            classes: vec![],
+            class_names: Set::new(),
+            vars: vec![],
+            funcs: vec![],
+            ctors: Dict::new(),
+            data_fields: Dict::new(),
+            protocols: Dict::new(),
+            expanding: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn convert(mut self, src: &str) -> String {
+        let lines = logical_lines(src);
+        let stmts = parse_block(&lines, &mut 0);
+        self.collect(&stmts);
+        self.emit()
+    }
+
+    /// `None`: unknown
+    fn eval_cond(&self, cond: &PyExpr) -> Option<bool> {
+        match cond {
+            PyExpr::Not(cond) => self.eval_cond(cond).map(|b| !b),
+            PyExpr::And(lhs, rhs) => match (self.eval_cond(lhs), self.eval_cond(rhs)) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            },
+            PyExpr::Or(lhs, rhs) => match (self.eval_cond(lhs), self.eval_cond(rhs)) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            },
+            PyExpr::Name(name) if name == "TYPE_CHECKING" => Some(true),
+            PyExpr::Compare(lhs, op, rhs) => match (lhs.dotted()?.as_str(), rhs.as_ref()) {
+                ("sys.version_info", PyExpr::Tuple(elems)) => {
+                    let expected = elems
+                        .iter()
+                        .map(|elem| match elem {
+                            PyExpr::Num(num) => num.parse::<u8>().ok(),
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    let version = [
+                        self.version.major,
+                        self.version.minor.unwrap_or(0),
+                        self.version.micro.unwrap_or(0),
+                    ];
+                    let actual = &version[..expected.len().min(3)];
+                    let expected = &expected[..expected.len().min(3)];
+                    Some(match &op[..] {
+                        "<" => actual < expected,
+                        ">" => actual > expected,
+                        "<=" => actual <= expected,
+                        ">=" => actual >= expected,
+                        "==" => actual == expected,
+                        "!=" => actual != expected,
+                        _ => return None,
+                    })
+                }
+                ("sys.platform", PyExpr::Str(platform)) => match &op[..] {
+                    "==" => Some(platform == self.platform),
+                    "!=" => Some(platform != self.platform),
+                    _ => None,
+                },
+                _ => None,
+            },
+            PyExpr::Call(callee, args) => match (callee.dotted()?.as_str(), &args[..]) {
+                ("sys.platform.startswith", [(None, PyExpr::Str(prefix))]) => {
+                    Some(self.platform.starts_with(&prefix[..]))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Selects the statements in the `if` branches that are taken.
+    /// If the condition is unknown, the first branch is taken.
+    fn flatten<'s>(&self, stmts: &'s [Stmt]) -> Vec<&'s Stmt> {
+        let mut flattened = vec![];
+        // `Some(true)`: a branch of the current `if` statement has been taken
+        let mut taken = None;
+        for stmt in stmts {
+            let (cond, is_branch) = if let Some(cond) = stmt.text.strip_prefix("if ") {
+                taken = Some(false);
+                (Some(cond), true)
+            } else if let Some(cond) = stmt.text.strip_prefix("elif ") {
+                (Some(cond), true)
+            } else if stmt.text == "else" {
+                (None, true)
+            } else {
+                taken = None;
+                (None, false)
+            };
+            if !is_branch {
+                flattened.push(stmt);
+                continue;
+            }
+            if taken != Some(false) {
+                continue;
+            }
+            let satisfied = cond.map_or(Some(true), |cond| {
+                Parser::new(cond)
+                    .expr()
+                    .and_then(|cond| self.eval_cond(&cond))
+            });
+            if satisfied != Some(false) {
+                taken = Some(true);
+                flattened.extend(self.flatten(&stmt.body));
+            }
+        }
+        flattened
+    }
+
+    fn collect(&mut self, stmts: &[Stmt]) {
+        let mut decorators = vec![];
+        for stmt in self.flatten(stmts) {
+            let text = &stmt.text[..];
+            if let Some(deco) = text.strip_prefix('@') {
+                decorators.push(deco.trim().to_string());
+                continue;
+            }
+            let decorators = std::mem::take(&mut decorators);
+            if text.starts_with("def ") || text.starts_with("async def ") {
+                if let Some(def) = FuncDef::parse(text, decorators) {
+                    push_def(&mut self.funcs, def);
+                }
+            } else if text.starts_with("class ") {
+                self.collect_class(stmt, decorators);
+            } else if text.starts_with("import ") || text.starts_with("from ") {
+                self.collect_import(text);
+            } else if let Some((name, annot, value)) = parse_assign(text) {
+                self.collect_assign(name, annot, value);
+            }
+        }
+    }
+
+    fn collect_class(&mut self, stmt: &Stmt, decorators: Vec<String>) {
+        let mut parser = Parser::new(&stmt.text);
+        parser.eat_name("class");
+        // `Name` or `Name(Base, metaclass=...)`
+        let (name, bases) = match parser.postfix() {
+            Some(PyExpr::Name(name)) => (name, vec![]),
+            Some(PyExpr::Call(callee, args)) => match *callee {
+                PyExpr::Name(name) => {
+                    let bases = args
+                        .into_iter()
+                        .filter_map(|(key, base)| key.is_none().then_some(base))
+                        .collect();
+                    (name, bases)
+                }
+                _ => return,
+            },
+            _ => return,
+        };
+        let is_typed_dict = bases.iter().any(|base| {
+            base.dotted()
+                .is_some_and(|base| self.typing_name(&base) == Some("TypedDict".into()))
+        });
+        if is_typed_dict {
+            self.aliases.insert(name, PyExpr::Name("dict".into()));
+            return;
+        }
+        let is_protocol = bases.iter().any(|base| {
+            base.base_name()
+                .is_some_and(|base| self.typing_name(&base) == Some("Protocol".into()))
+        });
+        if is_protocol {
+            let class = ClassDef {
+                name: name.clone(),
+                bases,
+                decorators,
+                body: stmt.body.clone(),
+                fields: vec![],
+            };
+            self.protocols.insert(name, class);
+            return;
+        }
+        self.class_names.insert(name.clone());
+        self.classes.push(ClassDef {
+            name,
+            bases,
+            decorators,
+            body: stmt.body.clone(),
+            fields: vec![],
+        });
+    }
+
+    /// `P = NamedTuple("P", [("x", int), ("y", str)])`, `P = namedtuple("P", "x y", defaults=[0])`
+    fn collect_named_tuple(
+        &mut self,
+        name: String,
+        callee: &str,
+        args: &[(Option<String>, PyExpr)],
+    ) {
+        let mut fields = vec![];
+        match args.get(1).map(|(_, fields)| fields) {
+            // typing.NamedTuple
+            Some(PyExpr::List(elems) | PyExpr::Tuple(elems)) if callee == "NamedTuple" => {
+                for elem in elems {
+                    if let PyExpr::Tuple(pair) = elem {
+                        if let [PyExpr::Str(field), t] = &pair[..] {
+                            fields.push((field.clone(), Some(t.clone()), None));
+                        }
+                    }
+                }
+            }
+            // collections.namedtuple
+            Some(PyExpr::List(elems) | PyExpr::Tuple(elems)) => {
+                for elem in elems {
+                    if let PyExpr::Str(field) = elem {
+                        fields.push((field.clone(), Some(PyExpr::Name("object".into())), None));
+                    }
+                }
+            }
+            Some(PyExpr::Str(names)) => {
+                for field in names.split(|c: char| c == ',' || c.is_whitespace()) {
+                    if !field.is_empty() {
+                        fields.push((field.to_string(), Some(PyExpr::Name("object".into())), None));
+                    }
+                }
+            }
+            _ => return,
+        }
+        // the defaults are applied to the rightmost fields
+        let defaults = args
+            .iter()
+            .find_map(|(key, arg)| match (key.as_deref(), arg) {
+                (Some("defaults"), PyExpr::List(defaults) | PyExpr::Tuple(defaults)) => {
+                    Some(defaults.len())
+                }
+                _ => None,
+            });
+        let len = fields.len();
+        for (_, _, value) in fields
+            .iter_mut()
+            .skip(len.saturating_sub(defaults.unwrap_or(0)))
+        {
+            *value = Some(PyExpr::Ellipsis);
+        }
+        self.class_names.insert(name.clone());
+        self.classes.push(ClassDef {
+            name,
+            bases: vec![PyExpr::Name("typing.NamedTuple".into())],
+            decorators: vec![],
+            body: vec![],
+            fields,
+        });
+    }
+
+    fn collect_import(&mut self, text: &str) {
+        if let Some(mods) = text.strip_prefix("import ") {
+            for module in mods.split(',') {
+                let mut iter = module.split(" as ");
+                let module = iter.next().unwrap_or("").trim();
+                match iter.next() {
+                    Some(alias) => {
+                        self.imports
+                            .insert(alias.trim().to_string(), module.to_string());
+                    }
+                    None => {
+                        let first = module.split('.').next().unwrap_or(module);
+                        self.imports.insert(first.to_string(), first.to_string());
+                    }
+                }
+            }
+        } else if let Some((module, names)) = text
+            .strip_prefix("from ")
+            .and_then(|rest| rest.split_once(" import "))
+        {
+            let module = module.trim();
+            let names = names.trim().trim_start_matches('(').trim_end_matches(')');
+            for name in names.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                let mut iter = name.split(" as ");
+                let name = iter.next().unwrap_or("").trim();
+                if name == "*" {
+                    continue;
+                }
+                let alias = iter.next().map_or(name, str::trim);
+                self.imports
+                    .insert(alias.to_string(), format!("{module}.{name}"));
+            }
+        }
+    }
+
+    fn collect_assign(&mut self, name: String, annot: Option<PyExpr>, value: Option<PyExpr>) {
+        let is_type_alias = annot
+            .as_ref()
+            .and_then(PyExpr::dotted)
+            .is_some_and(|annot| self.typing_name(&annot).as_deref() == Some("TypeAlias"));
+        match value {
+            Some(value) if is_type_alias => {
+                self.aliases.insert(name, value);
+            }
+            Some(PyExpr::Call(callee, args)) => {
+                let Some(callee) = callee.dotted().and_then(|callee| self.typing_name(&callee))
+                else {
+                    return;
+                };
+                match &callee[..] {
+                    "TypeVar" => {
+                        let bound = args.iter().find_map(|(key, arg)| {
+                            (key.as_deref() == Some("bound")).then(|| arg.clone())
+                        });
+                        // constrained type variables (e.g. `TypeVar("T", str, bytes)`)
+                        let constraints = args
+                            .iter()
+                            .skip(1)
+                            .filter(|(key, _)| key.is_none())
+                            .map(|(_, arg)| arg.clone())
+                            .reduce(|l, r| PyExpr::BitOr(Box::new(l), Box::new(r)));
+                        self.type_vars.insert(name, bound.or(constraints));
+                    }
+                    "NewType" => {
+                        if let Some((_, base)) = args.get(1) {
+                            self.aliases.insert(name, base.clone());
+                        }
+                    }
+                    "NamedTuple" | "namedtuple" => self.collect_named_tuple(name, &callee, &args),
+                    _ => {}
+                }
+            }
+            Some(
+                value @ (PyExpr::Name(_)
+                | PyExpr::Attr(..)
+                | PyExpr::Subscript(..)
+                | PyExpr::BitOr(..)),
+            ) if annot.is_none() && literal_type(&value).is_none() => {
+                self.aliases.insert(name, value);
+            }
+            value => {
+                let typ = match (annot, value) {
+                    (Some(annot), value) => {
+                        let is_final = annot.dotted().is_some_and(|annot| {
+                            self.typing_name(&annot).as_deref() == Some("Final")
+                        });
+                        match value.as_ref().and_then(literal_type) {
+                            Some(t) if is_final => PyExpr::Name(t.to_string()),
+                            _ => annot,
+                        }
+                    }
+                    (None, Some(value)) => {
+                        let Some(t) = literal_type(&value) else {
+                            return;
+                        };
+                        PyExpr::Name(t.to_string())
+                    }
+                    (None, None) => return,
+                };
+                self.vars.retain(|(n, _)| n != &name);
+                self.vars.push((name, typ));
+            }
+        }
+    }
+
+    /// `dc.field` => `dataclasses.field` (`import dataclasses as dc`)
+    fn qualify(&self, name: &str) -> String {
+        let (first, rest) = name
+            .split_once('.')
+            .map_or((name, None), |(first, rest)| (first, Some(rest)));
+        match (self.imports.get(first), rest) {
+            (Some(module), Some(rest)) => format!("{module}.{rest}"),
+            (Some(module), None) => module.clone(),
+            (None, _) => name.to_string(),
+        }
+    }
+
+    /// Returns the name in the typing modules (e.g. `t.List` => `List`), or the builtin name.
+    fn typing_name(&self, name: &str) -> Option<String> {
+        let qualified = self.qualify(name);
+        match qualified.rsplit_once('.') {
+            Some((module, name)) if TYPING_MODULES.contains(&module) => Some(name.to_string()),
+            Some(_) => None,
+            None => Some(qualified),
+        }
+    }
+
+    fn is_local(&self, name: &str) -> bool {
+        !self.imports.contains_key(name)
+    }
+
+    fn conv_type(&self, expr: &PyExpr, scope: &mut TyScope) -> String {
+        match expr {
+            PyExpr::Name(_) | PyExpr::Attr(..) => match expr.dotted() {
+                Some(name) => self.conv_name(&name, scope),
+                None => "Obj".into(),
+            },
+            // forward reference
+            PyExpr::Str(s) => match Parser::new(s).expr() {
+                Some(expr) if !matches!(expr, PyExpr::Str(_)) => self.conv_type(&expr, scope),
+                _ => "Obj".into(),
+            },
+            PyExpr::BitOr(lhs, rhs) => {
+                let members = [lhs.as_ref().clone(), rhs.as_ref().clone()];
+                self.conv_union(&members, scope)
+            }
+            PyExpr::Subscript(base, args) => self.conv_generic(base, args, scope),
+            _ => "Obj".into(),
+        }
+    }
+
+    fn conv_name(&self, name: &str, scope: &mut TyScope) -> String {
+        if self.is_local(name) {
+            if let Some(t) = self
+                .expanding
+                .borrow()
+                .last()
+                .and_then(|(_, substs)| substs.get(name))
+            {
+                return t.clone();
+            }
+            if scope.class_params.contains(name) {
+                return "Obj".into();
+            }
+            if let Some(bound) = self.type_vars.get(name) {
+                if scope.no_quant {
+                    return bound
+                        .as_ref()
+                        .map_or("Obj".into(), |bound| self.conv_type(bound, scope));
+                }
+                let tv = type_var_name(name);
+                if !scope.quantified.contains(&name.to_string()) {
+                    scope.quantified.push(name.to_string());
+                }
+                return tv;
+            }
+            if self.class_names.contains(name) {
+                return format!(".{name}");
+            }
+            if let Some(proto) = self.protocols.get(name) {
+                return self.conv_protocol(proto, &[], scope);
+            }
+            if let Some(alias) = self.aliases.get(name) {
+                if scope.depth > 16 {
+                    return "Obj".into();
+                }
+                scope.depth += 1;
+                let t = self.conv_type(alias, scope);
+                scope.depth -= 1;
+                return t;
+            }
+        }
+        match self.typing_name(name).as_deref() {
+            Some("Self") => scope
+                .class
+                .as_ref()
+                .map_or("Obj".into(), |class| format!(".{class}")),
+            Some(name) => simple_type(name).unwrap_or("Obj").into(),
+            None => "Obj".into(),
+        }
+    }
+
+    /// `class P(Protocol): def f(self, x: int) -> int: ...` => `Structural { .f = (x: Int) -> Int }`
+    fn conv_protocol(&self, proto: &ClassDef, args: &[PyExpr], scope: &mut TyScope) -> String {
+        let mut members = vec![];
+        self.protocol_members(proto, args, scope, &mut members);
+        match &members[..] {
+            [] => "Obj".into(),
+            // callback protocol
+            [(name, t)] if name == "__call__" => t.clone(),
+            _ => {
+                let members = members
+                    .iter()
+                    .map(|(name, t)| format!(".{name} = {t}"))
+                    .collect::<Vec<_>>();
+                format!("Structural {{ {} }}", members.join("; "))
+            }
+        }
+    }
+
+    /// The members of `proto` (including the inherited ones), with its type parameters replaced by `args`.
+    /// A recursive reference to a protocol being expanded is `Obj`.
+    fn protocol_members(
+        &self,
+        proto: &ClassDef,
+        args: &[PyExpr],
+        scope: &mut TyScope,
+        members: &mut Vec<(String, String)>,
+    ) {
+        if self
+            .expanding
+            .borrow()
+            .iter()
+            .any(|(name, _)| name == &proto.name)
+        {
+            return;
+        }
+        let params = self.type_params(proto);
+        let substs = params
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| (param.clone(), paren_subr(self.conv_type(arg, scope))))
+            .collect();
+        self.expanding
+            .borrow_mut()
+            .push((proto.name.clone(), substs));
+        for base in proto.bases.iter() {
+            let (base, args) = match base {
+                PyExpr::Subscript(base, args) => (base.dotted(), &args[..]),
+                _ => (base.dotted(), &[][..]),
+            };
+            if let Some(base) = base
+                .filter(|base| self.is_local(base))
+                .and_then(|base| self.protocols.get(&base[..]))
+            {
+                self.protocol_members(base, args, &mut TyScope::default(), members);
+            }
+        }
+        let (fields, methods) = self.class_members(proto);
+        let class_params = params.into_iter().collect::<Set<_>>();
+        let mut scope = TyScope {
+            class_params: class_params.clone(),
+            no_quant: true,
+            ..Default::default()
+        };
+        let mut push = |name: &str, t: String| {
+            members.retain(|(n, _)| n != name);
+            members.push((name.to_string(), t));
+        };
+        for (name, annot, _) in fields.iter() {
+            if let Some(annot) = annot.as_ref().filter(|_| is_public(name, true)) {
+                push(name, self.conv_type(annot, &mut scope));
+            }
+        }
+        for (name, defs) in methods.iter() {
+            if !is_public(name, true) && name != "__call__" {
+                continue;
+            }
+            let t = match Self::method_kind(&defs[0]) {
+                MethodKind::Property => defs[0]
+                    .ret
+                    .as_ref()
+                    .map_or("Obj".into(), |ret| self.conv_type(ret, &mut scope)),
+                MethodKind::Static => {
+                    self.conv_overloads(defs, None, MethodKind::Static, &class_params)
+                }
+                _ => self.conv_overloads(defs, None, MethodKind::Bound, &class_params),
+            };
+            push(name, t);
+        }
+        self.expanding.borrow_mut().pop();
+    }
+
+    /// `class C(Base[T], Generic[T, U])` => `[T, U]`
+    fn type_params(&self, class: &ClassDef) -> Vec<String> {
+        let explicit = class.bases.iter().find_map(|base| match base {
+            PyExpr::Subscript(base, args)
+                if base.dotted().is_some_and(|base| {
+                    matches!(
+                        self.typing_name(&base).as_deref(),
+                        Some("Generic" | "Protocol")
+                    )
+                }) =>
+            {
+                Some(args)
+            }
+            _ => None,
+        });
+        if let Some(args) = explicit {
+            return args
+                .iter()
+                .filter_map(|arg| match arg {
+                    PyExpr::Name(name) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect();
+        }
+        let mut params = Set::new();
+        for base in class.bases.iter() {
+            self.collect_type_vars(base, &mut params);
+        }
+        params.into_iter().collect()
+    }
+
+    fn conv_union(&self, members: &[PyExpr], scope: &mut TyScope) -> String {
+        let mut types = vec![];
+        for member in members {
+            let t = self.conv_type(member, scope);
+            if t == "Obj" {
+                return t;
+            }
+            let t = paren_subr(t);
+            if t != "Never" && !types.contains(&t) {
+                types.push(t);
+            }
+        }
+        match &types[..] {
+            [] => "Never".into(),
+            [t] => t.clone(),
+            _ => types.join(" or "),
+        }
+    }
+
+    fn conv_literal(&self, args: &[PyExpr]) -> String {
+        let mut elems = vec![];
+        let mut none = false;
+        for arg in args {
+            match arg {
+                PyExpr::Name(name) if name == "None" => none = true,
+                _ => match literal_value(arg) {
+                    Some(value) => elems.push(value),
+                    None => return "Obj".into(),
+                },
+            }
+        }
+        match (elems.is_empty(), none) {
+            (true, _) => "NoneType".into(),
+            (false, false) => format!("{{{}}}", elems.join(", ")),
+            (false, true) => format!("{{{}}} or NoneType", elems.join(", ")),
+        }
+    }
+
+    fn conv_generic(&self, base: &PyExpr, args: &[PyExpr], scope: &mut TyScope) -> String {
+        let Some(name) = base.dotted() else {
+            return "Obj".into();
+        };
+        if let Some(proto) = self
+            .protocols
+            .get(&name[..])
+            .filter(|_| self.is_local(&name))
+        {
+            return self.conv_protocol(proto, args, scope);
+        }
+        // the type parameters of local classes are ignored
+        if self.is_local(&name)
+            && (self.class_names.contains(&name) || self.aliases.contains_key(&name))
+        {
+            return self.conv_name(&name, scope);
+        }
+        let Some(name) = self.typing_name(&name) else {
+            return "Obj".into();
+        };
+        let arg = |n: usize, scope: &mut TyScope| {
+            args.get(n)
+                .map_or("Obj".into(), |arg| paren_subr(self.conv_type(arg, scope)))
+        };
+        match &name[..] {
+            "list" | "List" | "Sequence" | "MutableSequence" => format!("[{}; _]", arg(0, scope)),
+            "tuple" | "Tuple" => match args {
+                [] | [_, PyExpr::Ellipsis] => "GenericTuple".into(),
+                [PyExpr::Tuple(elems)] if elems.is_empty() => "GenericTuple".into(),
+                [elem] => format!("({},)", paren_subr(self.conv_type(elem, scope))),
+                _ => {
+                    let elems = args
+                        .iter()
+                        .map(|elem| paren_subr(self.conv_type(elem, scope)))
+                        .collect::<Vec<_>>();
+                    format!("({})", elems.join(", "))
+                }
+            },
+            "dict" | "Dict" | "Mapping" | "MutableMapping" | "DefaultDict" | "defaultdict"
+            | "OrderedDict" | "ChainMap" => {
+                let key = arg(0, scope);
+                format!("{{{key}: {}}}", arg(1, scope))
+            }
+            "Counter" => format!("{{{}: Int}}", arg(0, scope)),
+            "set" | "Set" | "frozenset" | "FrozenSet" | "AbstractSet" | "MutableSet" => {
+                format!("{{{}; _}}", arg(0, scope))
+            }
+            "Iterable" | "Collection" | "Container" | "Reversible" => {
+                format!("Iterable({})", arg(0, scope))
+            }
+            "Iterator" | "Generator" => format!("Iterator({})", arg(0, scope)),
+            "Callable" => match args {
+                [PyExpr::List(params), ret] => {
+                    let params = params
+                        .iter()
+                        .map(|param| paren_subr(self.conv_type(param, scope)))
+                        .collect::<Vec<_>>();
+                    let ret = paren_subr(self.conv_type(ret, scope));
+                    format!("({}) -> {ret}", params.join(", "))
+                }
+                _ => "GenericCallable".into(),
+            },
+            "Optional" => {
+                let members = [
+                    args.first().cloned().unwrap_or(PyExpr::Ellipsis),
+                    PyExpr::Name("None".into()),
+                ];
+                self.conv_union(&members, scope)
+            }
+            "Union" => self.conv_union(args, scope),
+            "Literal" => self.conv_literal(args),
+            "Final" | "ClassVar" | "Annotated" | "Required" | "NotRequired" | "ReadOnly" => args
+                .first()
+                .map_or("Obj".into(), |arg| self.conv_type(arg, scope)),
+            "TypeGuard" | "TypeIs" => "Bool".into(),
+            "type" | "Type" => "ClassType".into(),
+            _ => "Obj".into(),
+        }
+    }
+
+    /// Quantifies the type variables used in the signature (e.g. `|T <: Int|`).
+    fn quantifier(&self, scope: &TyScope) -> String {
+        if scope.quantified.is_empty() {
+            return String::new();
+        }
+        let mut bound_scope = TyScope {
+            class: scope.class.clone(),
+            no_quant: true,
+            ..Default::default()
+        };
+        let vars = scope
+            .quantified
+            .iter()
+            .map(|name| {
+                let tv = type_var_name(name);
+                let bound = self
+                    .type_vars
+                    .get(name)
+                    .and_then(|bound| bound.as_ref())
+                    .map(|bound| self.conv_type(bound, &mut bound_scope));
+                match bound {
+                    Some(bound) if bound != "Obj" => format!("{tv} <: {bound}"),
+                    _ => tv,
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("|{}|", vars.join(", "))
+    }
+
+    /// Returns (quantifier + parameters, return type).
+    fn conv_sig(
+        &self,
+        def: &FuncDef,
+        class: Option<&ClassDef>,
+        kind: MethodKind,
+        class_params: &Set<String>,
+    ) -> (String, String) {
+        let mut scope = TyScope::new(class, class_params.clone());
+        let mut non_defaults = vec![];
+        let mut var_params = None;
+        let mut defaults = vec![];
+        let skip_first = matches!(kind, MethodKind::Class | MethodKind::Bound)
+            || def.name == "__init__"
+            || def.name == "__new__";
+        let has_pos_defaults = def
+            .params
+            .iter()
+            .any(|param| param.kind == ParamKind::Normal && param.default && !param.kw_only);
+        for (i, param) in def.params.iter().enumerate() {
+            if i == 0 && skip_first {
+                continue;
+            }
+            let name = param_name(&param.name);
+            let t = match (i, kind, class) {
+                (0, MethodKind::Instance, Some(class)) => format!(".{}", class.name),
+                _ => param.annot.as_ref().map_or("Obj".into(), |annot| {
+                    paren_subr(self.conv_type(annot, &mut scope))
+                }),
+            };
+            match param.kind {
+                ParamKind::Normal if param.default || param.kw_only || !defaults.is_empty() => {
+                    defaults.push(format!("{name} := {t}"));
+                }
+                ParamKind::Normal => non_defaults.push(format!("{name}: {t}")),
+                ParamKind::VarArgs if !has_pos_defaults => {
+                    var_params = Some(format!("*{name}: {t}"));
+                }
+                ParamKind::VarArgs | ParamKind::KwArgs => {}
+            }
+        }
+        let ret = match (&def.ret, def.is_async) {
+            (_, true) | (None, _) => "Obj".into(),
+            (Some(ret), false) => paren_subr(self.conv_type(ret, &mut scope)),
+        };
+        let params = non_defaults
+            .into_iter()
+            .chain(var_params)
+            .chain(defaults)
+            .collect::<Vec<_>>();
+        (
+            format!("{}({})", self.quantifier(&scope), params.join(", ")),
+            ret,
+        )
+    }
+
+    fn conv_overloads(
+        &self,
+        defs: &[FuncDef],
+        class: Option<&ClassDef>,
+        kind: MethodKind,
+        class_params: &Set<String>,
+    ) -> String {
+        let sigs = defs
+            .iter()
+            .map(|def| {
+                let (params, ret) = self.conv_sig(def, class, kind, class_params);
+                format!("{params} -> {ret}")
+            })
+            .collect::<Vec<_>>();
+        if sigs.len() == 1 {
+            sigs[0].clone()
+        } else {
+            let sigs = sigs
+                .iter()
+                .map(|sig| format!("({sig})"))
+                .collect::<Vec<_>>();
+            sigs.join(" and ")
+        }
+    }
+
+    fn method_kind(def: &FuncDef) -> MethodKind {
+        if def.has_decorator("staticmethod") {
+            MethodKind::Static
+        } else if def.has_decorator("classmethod") {
+            MethodKind::Class
+        } else if def.has_decorator("property") || def.has_decorator("cached_property") {
+            MethodKind::Property
+        } else {
+            MethodKind::Instance
+        }
+    }
+
+    fn class_members(&self, class: &ClassDef) -> (Vec<Field>, Vec<(String, Vec<FuncDef>)>) {
+        let mut fields = class.fields.clone();
+        let mut methods = vec![];
+        let mut decorators = vec![];
+        for stmt in self.flatten(&class.body) {
+            let text = &stmt.text[..];
+            if let Some(deco) = text.strip_prefix('@') {
+                decorators.push(deco.trim().to_string());
+                continue;
+            }
+            let decorators = std::mem::take(&mut decorators);
+            if text.starts_with("def ") || text.starts_with("async def ") {
+                if let Some(def) = FuncDef::parse(text, decorators) {
+                    push_def(&mut methods, def);
+                }
+            } else if let Some((name, annot, value)) = parse_assign(text) {
+                fields.retain(|(n, _, _)| n != &name);
+                fields.push((name, annot, value));
+            }
+        }
+        (fields, methods)
+    }
+
+    /// `@dataclass(init=False, kw_only=True)` => `Some((false, true))`
+    fn dataclass_options(&self, class: &ClassDef) -> Option<(bool, bool)> {
+        class.decorators.iter().find_map(|deco| {
+            let (name, args) = match Parser::new(deco).postfix()? {
+                PyExpr::Call(callee, args) => (callee.dotted()?, args),
+                deco => (deco.dotted()?, vec![]),
+            };
+            if !matches!(
+                &self.qualify(&name)[..],
+                "dataclass" | "dataclasses.dataclass"
+            ) {
+                return None;
+            }
+            let option = |key: &str, default: bool| {
+                args.iter()
+                    .find(|(k, _)| k.as_deref() == Some(key))
+                    .map_or(default, |(_, arg)| arg == &PyExpr::Name("True".into()))
+            };
+            Some((option("init", true), option("kw_only", false)))
+        })
+    }
+
+    /// The fields of a dataclass (after the fields of the base dataclass) or a `NamedTuple`.
+    /// `ClassVar`s are not fields, and the fields after `_: KW_ONLY` are keyword-only.
+    fn data_fields(
+        &self,
+        fields: &[Field],
+        base: Option<&String>,
+        mut kw_only: bool,
+        scope: &mut TyScope,
+    ) -> Vec<DataField> {
+        let mut data_fields = base
+            .and_then(|base| self.data_fields.get(base))
+            .cloned()
+            .unwrap_or_default();
+        for (name, annot, value) in fields {
+            let Some(annot) = annot else {
+                continue;
+            };
+            match annot
+                .base_name()
+                .map(|annot| self.qualify(&annot))
+                .as_deref()
+            {
+                Some("dataclasses.KW_ONLY") => {
+                    kw_only = true;
+                    continue;
+                }
+                Some(annot) if self.typing_name(annot).as_deref() == Some("ClassVar") => {
+                    continue;
+                }
+                _ => {}
+            }
+            let mut field = DataField {
+                name: name.clone(),
+                t: paren_subr(self.conv_type(annot, scope)),
+                default: value.is_some(),
+                kw_only,
+                init: true,
+            };
+            // `x: int = field(default=0, kw_only=True)`
+            if let Some(PyExpr::Call(callee, args)) = value {
+                if callee
+                    .dotted()
+                    .is_some_and(|callee| self.qualify(&callee) == "dataclasses.field")
+                {
+                    let is_true = |arg: &PyExpr| arg == &PyExpr::Name("True".into());
+                    field.default = false;
+                    for (key, arg) in args {
+                        match key.as_deref() {
+                            Some("default" | "default_factory") => field.default = true,
+                            Some("init") => field.init = is_true(arg),
+                            Some("kw_only") => field.kw_only = is_true(arg),
+                            _ => {}
+                        }
+                    }
+                }
+            }
+            if let Some(inherited) = data_fields.iter_mut().find(|f| &f.name == name) {
+                *inherited = field;
+            } else {
+                data_fields.push(field);
+            }
+        }
+        data_fields
+    }
+
+    fn emit_class(&mut self, class: &ClassDef, decls: &mut Vec<String>, blocks: &mut Vec<String>) {
+        let base_names = class
+            .bases
+            .iter()
+            .filter_map(PyExpr::base_name)
+            .collect::<Vec<_>>();
+        let has_base = |names: &[&str]| {
+            base_names.iter().any(|base| {
+                self.typing_name(base)
+                    .is_some_and(|base| names.contains(&&base[..]))
+            })
+        };
+        let is_enum = has_base(&["Enum", "IntEnum", "StrEnum", "Flag", "IntFlag"]);
+        let is_named_tuple = has_base(&["NamedTuple"]);
+        let dataclass = self.dataclass_options(class);
+        let local_base = base_names
+            .iter()
+            .find(|base| self.is_local(base) && self.class_names.contains(&base[..]))
+            .cloned();
+        let has_external_base = base_names.iter().any(|base| {
+            local_base.as_ref() != Some(base)
+                && !(self.is_local(base) && self.protocols.contains_key(&base[..]))
+                && !matches!(
+                    self.typing_name(base).as_deref(),
+                    Some("Generic" | "Protocol" | "object")
+                )
+        });
+        let mut class_params = Set::new();
+        for base in class.bases.iter() {
+            self.collect_type_vars(base, &mut class_params);
+        }
+        let (fields, methods) = self.class_members(class);
+        let mut scope = TyScope::new(Some(class), class_params.clone());
+        let mut members = vec![];
+        for (name, annot, value) in fields.iter() {
+            if !is_public(name, true) {
+                continue;
+            }
+            let t = if is_enum {
+                format!(".{}", class.name)
+            } else if let Some(annot) = annot {
+                self.conv_type(annot, &mut scope)
+            } else if let Some(t) = value.as_ref().and_then(literal_type).and_then(simple_type) {
+                t.to_string()
+            } else {
+                continue;
+            };
+            members.push(format!("{name}: {t}"));
+        }
+        let synthesized = if let Some((init, kw_only)) = dataclass {
+            let fields = self.data_fields(&fields, local_base.as_ref(), kw_only, &mut scope);
+            let params = init.then(|| data_params(&fields));
+            self.data_fields.insert(class.name.clone(), fields);
+            params
+        } else if is_named_tuple {
+            let fields = self.data_fields(&fields, None, false, &mut scope);
+            Some(data_params(&fields))
+        } else {
+            None
+        };
+        let ctor = methods
+            .iter()
+            .find(|(name, _)| name == "__init__")
+            .or_else(|| methods.iter().find(|(name, _)| name == "__new__"))
+            .map(|(_, defs)| {
+                defs.iter()
+                    .map(|def| {
+                        self.conv_sig(def, Some(class), MethodKind::Instance, &class_params)
+                            .0
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .or_else(|| synthesized.map(|params| vec![params]))
+            .or_else(|| {
+                local_base
+                    .as_ref()
+                    .and_then(|base| self.ctors.get(base).cloned())
+            })
+            .or_else(|| {
+                if is_enum {
+                    None
+                } else if has_external_base {
+                    Some(vec!["(*args: Obj)".into()])
+                } else {
+                    Some(vec!["()".into()])
+                }
+            });
+        if let Some(ctor) = ctor {
+            let sigs = ctor
+                .iter()
+                .map(|params| format!("{params} -> .{}", class.name))
+                .collect::<Vec<_>>();
+            let sig = if sigs.len() == 1 {
+                sigs[0].clone()
+            } else {
+                let sigs = sigs
+                    .iter()
+                    .map(|sig| format!("({sig})"))
+                    .collect::<Vec<_>>();
+                sigs.join(" and ")
+            };
+            members.insert(0, format!("__call__: {sig}"));
+            self.ctors.insert(class.name.clone(), ctor);
+        }
+        for (name, defs) in methods.iter() {
+            if !is_public(name, true) {
+                continue;
+            }
+            let kind = Self::method_kind(&defs[0]);
+            if kind == MethodKind::Property {
+                let mut scope = TyScope::new(Some(class), class_params.clone());
+                let t = defs[0]
+                    .ret
+                    .as_ref()
+                    .map_or("Obj".into(), |ret| self.conv_type(ret, &mut scope));
+                members.push(format!("{name}: {t}"));
+            } else {
+                let defs = defs
+                    .iter()
+                    .filter(|def| Self::method_kind(def) == kind)
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let sig = self.conv_overloads(&defs, Some(class), kind, &class_params);
+                members.push(format!("{name}: {sig}"));
+            }
+        }
+        decls.push(format!(".{}: ClassType", class.name));
+        if let Some(base) = local_base {
+            decls.push(format!(".{} <: .{base}", class.name));
+        }
+        if !members.is_empty() {
+            let mut block = format!(".{}.\n", class.name);
+            for member in members {
+                block.push_str(&format!("    {member}\n"));
+            }
+            blocks.push(block);
+        }
+    }
+
+    fn collect_type_vars(&self, expr: &PyExpr, vars: &mut Set<String>) {
+        match expr {
+            PyExpr::Name(name) if self.is_local(name) && self.type_vars.contains_key(name) => {
+                vars.insert(name.clone());
+            }
+            PyExpr::Subscript(_, args) | PyExpr::Tuple(args) | PyExpr::List(args) => {
+                for arg in args {
+                    self.collect_type_vars(arg, vars);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn emit(mut self) -> String {
+        let mut decls = vec![];
+        let mut blocks = vec![];
+        let classes = std::mem::take(&mut self.classes);
+        for class in classes.iter() {
+            if is_public(&class.name, false) {
+                self.emit_class(class, &mut decls, &mut blocks);
+            }
+        }
+        let mut code = String::new();
+        for decl in decls {
+            code.push_str(&decl);
+            code.push('\n');
+        }
+        for block in blocks {
+            code.push_str(&block);
+        }
+        for (name, t) in self.vars.iter() {
+            if is_public(name, false) && !self.class_names.contains(name) {
+                let t = self.conv_type(t, &mut TyScope::default());
+                code.push_str(&format!(".{name}: {t}\n"));
+            }
+        }
+        for (name, defs) in self.funcs.iter() {
+            if is_public(name, false) {
+                let sig = self.conv_overloads(defs, None, MethodKind::Static, &Set::new());
+                code.push_str(&format!(".{name}: {sig}\n"));
+            }
+        }
+        code
+    }
+}
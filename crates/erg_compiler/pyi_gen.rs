@@ -0,0 +1,445 @@
+//! Generates a PEP 484 stub file (`.pyi`) from an Erg module (`erg stub <file>.er`).
+//!
+//! The stub describes the Python script generated by `erg transpile`, so that Python consumers get editor support for Erg-authored libraries.
+//! Erg types are translated to the nearest Python types:
+//! refinement types are widened (`{1, 2}` -> `int`), traits are declared as `Protocol`s, and types that cannot be expressed become `Any`.
+//!
+//! ```erg
+//! # foo.er
+//! .Norm = Trait { .norm = (self: Self) -> Nat }
+//! .Point = Class { .x = Int; .y = Int }
+//! .Point.
+//!     .norm self = self.x**2 + self.y**2
+//! .f x: Int, y := 1 = x + y
+//! ```
+//! ↓
+//! ```python
+//! # foo.pyi
+//! from typing import Any, Protocol
+//!
+//! class Norm(Protocol):
+//!     def norm(self) -> int: ...
+//!
+//! class Point:
+//!     x: int
+//!     y: int
+//!     def __init__(self, param__: Any, /) -> None: ...
+//!     @staticmethod
+//!     def new(param__: Any, /) -> Point: ...
+//!     def norm(self) -> int: ...
+//!
+//! def f(x: int, /, y__: int = ...) -> int: ...
+//! ```
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write;
+
+use erg_common::config::ErgConfig;
+use erg_common::error::{Location, MultiErrorDisplay};
+use erg_common::set::Set;
+use erg_common::traits::{ExitStatus, Runnable};
+use erg_common::{log, Str};
+
+use crate::build_hir::HIRBuilder;
+use crate::context::{Context, ContextKind};
+use crate::error::{CompileError, CompileErrors};
+use crate::ty::{ParamTy, SubrType, TyParam, Type};
+use crate::varinfo::{MethodKind, VarInfo, VarKind};
+
+/// Generates `.pyi` files from Erg scripts.
+#[derive(Debug)]
+pub struct PyiGenerator {
+    pub cfg: ErgConfig,
+    builder: HIRBuilder,
+}
+
+impl Default for PyiGenerator {
+    fn default() -> Self {
+        Self::new(ErgConfig::default())
+    }
+}
+
+impl Runnable for PyiGenerator {
+    type Err = CompileError;
+    type Errs = CompileErrors;
+    const NAME: &'static str = "Erg stub generator";
+
+    fn new(cfg: ErgConfig) -> Self {
+        Self {
+            builder: HIRBuilder::new(cfg.copy()),
+            cfg,
+        }
+    }
+
+    #[inline]
+    fn cfg(&self) -> &ErgConfig {
+        &self.cfg
+    }
+    #[inline]
+    fn cfg_mut(&mut self) -> &mut ErgConfig {
+        &mut self.cfg
+    }
+
+    #[inline]
+    fn finish(&mut self) {}
+
+    fn initialize(&mut self) {
+        self.builder.initialize();
+    }
+
+    fn clear(&mut self) {
+        self.builder.clear();
+    }
+
+    fn exec(&mut self) -> Result<ExitStatus, Self::Errs> {
+        let mut path = self.cfg.dump_path();
+        path.set_extension("pyi");
+        let src = self.cfg.input.read();
+        let code = self.generate(src)?;
+        File::create(&path)
+            .and_then(|mut f| f.write_all(code.as_bytes()))
+            .map_err(|err| CompileErrors::from(self.file_error(err.to_string())))?;
+        Ok(ExitStatus::OK)
+    }
+
+    fn eval(&mut self, src: String) -> Result<String, CompileErrors> {
+        self.generate(src)
+    }
+}
+
+impl PyiGenerator {
+    /// Type-checks `src` and returns the stub of its public interface.
+    pub fn generate(&mut self, src: String) -> Result<String, CompileErrors> {
+        log!(info "the stub generating process has started.");
+        let artifact = self.builder.build(src, "exec").map_err(|art| art.errors)?;
+        artifact.warns.write_all_stderr();
+        let Some(mod_ctx) = self.builder.pop_mod_ctx() else {
+            return Ok(String::new());
+        };
+        let code = PyiWriter::new(&mod_ctx.context).write_module();
+        log!(info "the stub generating process has completed:\n{code}");
+        Ok(code)
+    }
+
+    fn file_error(&self, desc: String) -> CompileError {
+        CompileError::file_error(
+            self.cfg.input.clone(),
+            line!() as usize,
+            desc,
+            Location::Unknown,
+            "<stub>".into(),
+            None,
+        )
+    }
+}
+
+/// The name of a variable in the transpiled script (`f!` -> `f__erg_proc__`).
+fn py_name(name: &str) -> String {
+    name.replace('!', "__erg_proc__")
+}
+
+struct PyiWriter<'c> {
+    ctx: &'c Context,
+    code: String,
+    /// the names imported from `typing`
+    typing: Set<&'static str>,
+}
+
+impl<'c> PyiWriter<'c> {
+    fn new(ctx: &'c Context) -> Self {
+        Self {
+            ctx,
+            code: String::new(),
+            typing: Set::new(),
+        }
+    }
+
+    fn write_module(mut self) -> String {
+        let mut vars = self
+            .ctx
+            .locals
+            .iter()
+            .chain(self.ctx.decls.iter())
+            .filter(|(_, vi)| vi.vis.is_public())
+            .collect::<Vec<_>>();
+        // in the order of definition
+        vars.sort_by_key(|(_, vi)| vi.def_loc.loc.ln_begin().unwrap_or(0));
+        for (name, vi) in vars {
+            match self.ctx.mono_types.get(name.inspect()) {
+                Some((_, type_ctx)) if type_ctx.kind == ContextKind::Class => {
+                    self.write_class(name.inspect(), type_ctx);
+                }
+                Some((_, type_ctx)) if type_ctx.kind.is_trait() => {
+                    self.write_protocol(name.inspect(), type_ctx);
+                }
+                _ => match &vi.t {
+                    Type::Subr(subr) => {
+                        let sig = self.fmt_signature(subr, MethodKind::Static);
+                        let _ = writeln!(self.code, "def {}{sig}: ...", py_name(name.inspect()));
+                    }
+                    t => {
+                        let t = self.fmt_type(t);
+                        let _ = writeln!(self.code, "{}: {t}", py_name(name.inspect()));
+                    }
+                },
+            }
+        }
+        let mut header = String::new();
+        if !self.typing.is_empty() {
+            let mut typing = self.typing.into_iter().collect::<Vec<_>>();
+            typing.sort();
+            let _ = writeln!(header, "from typing import {}\n", typing.join(", "));
+        }
+        header + &self.code
+    }
+
+    fn write_class(&mut self, name: &Str, class_ctx: &Context) {
+        let bases = class_ctx
+            .super_classes
+            .iter()
+            .filter(|sup| sup != &&Type::Obj)
+            .map(|sup| self.fmt_type(sup))
+            .collect::<Vec<_>>();
+        if bases.is_empty() {
+            let _ = writeln!(self.code, "\nclass {name}:");
+        } else {
+            let _ = writeln!(self.code, "\nclass {name}({}):", bases.join(", "));
+        }
+        let mut attrs = class_ctx
+            .locals
+            .iter()
+            .chain(class_ctx.decls.iter())
+            .filter(|(_, vi)| vi.vis.is_public() && vi.kind.is_instance_attr())
+            .collect::<Vec<_>>();
+        attrs.sort_by_key(|(_, vi)| vi.def_loc.loc.ln_begin().unwrap_or(0));
+        for (attr, vi) in attrs {
+            let t = self.fmt_type(&vi.t);
+            let _ = writeln!(self.code, "    {}: {t}", py_name(attr.inspect()));
+        }
+        let mut methods = class_ctx
+            .methods_list
+            .iter()
+            .flat_map(|(_, methods)| methods.locals.iter().chain(methods.decls.iter()))
+            .filter(|(_, vi)| vi.vis.is_public())
+            .collect::<Vec<_>>();
+        methods.sort_by_key(|(_, vi)| vi.def_loc.loc.ln_begin().unwrap_or(0));
+        let mut written = false;
+        for (method, vi) in methods {
+            written |= self.write_method(method.inspect(), vi);
+        }
+        if !written && class_ctx.locals.is_empty() {
+            let _ = writeln!(self.code, "    ...");
+        }
+        self.code.push('\n');
+    }
+
+    /// Returns `false` if nothing is written.
+    fn write_method(&mut self, name: &Str, vi: &VarInfo) -> bool {
+        let Type::Subr(subr) = &vi.t else {
+            let t = self.fmt_type(&vi.t);
+            let _ = writeln!(self.code, "    {}: {t}", py_name(name));
+            return true;
+        };
+        match vi.kind {
+            // the generated constructor takes a record
+            VarKind::Auto if &name[..] == "new" => {
+                self.typing.insert("Any");
+                let ret = self.fmt_type(&subr.return_t);
+                let param = if subr.non_default_params.is_empty() {
+                    ""
+                } else {
+                    ", param__: Any, /"
+                };
+                let _ = writeln!(self.code, "    def __init__(self{param}) -> None: ...");
+                let _ = writeln!(self.code, "    @staticmethod");
+                let _ = writeln!(
+                    self.code,
+                    "    def new({}) -> {ret}: ...",
+                    param.trim_start_matches(", ")
+                );
+                return true;
+            }
+            VarKind::Auto | VarKind::FixedAuto => return false,
+            _ => {}
+        }
+        let kind = vi.method_kind();
+        if vi.is_property() {
+            if let Some(getter) = name.strip_suffix('!') {
+                let _ = writeln!(self.code, "    @{getter}.setter");
+                let sig = self.fmt_signature(subr, kind);
+                let _ = writeln!(self.code, "    def {getter}{sig}: ...");
+            } else {
+                let _ = writeln!(self.code, "    @property");
+                let sig = self.fmt_signature(subr, kind);
+                let _ = writeln!(self.code, "    def {name}{sig}: ...");
+            }
+            return true;
+        }
+        match kind {
+            MethodKind::Static => {
+                let _ = writeln!(self.code, "    @staticmethod");
+            }
+            MethodKind::Class => {
+                let _ = writeln!(self.code, "    @classmethod");
+            }
+            MethodKind::Instance => {}
+        }
+        let sig = self.fmt_signature(subr, kind);
+        let _ = writeln!(self.code, "    def {}{sig}: ...", py_name(name));
+        true
+    }
+
+    /// The members of a trait are declared as the members of a `Protocol`.
+    fn write_protocol(&mut self, name: &Str, trait_ctx: &Context) {
+        self.typing.insert("Protocol");
+        let _ = writeln!(self.code, "\nclass {name}(Protocol):");
+        let mut members = trait_ctx
+            .decls
+            .iter()
+            .filter(|(_, vi)| vi.vis.is_public())
+            .collect::<Vec<_>>();
+        if members.is_empty() {
+            let _ = writeln!(self.code, "    ...");
+        }
+        members.sort_by_key(|(_, vi)| vi.def_loc.loc.ln_begin().unwrap_or(0));
+        for (member, vi) in members {
+            self.write_method(member.inspect(), vi);
+        }
+        self.code.push('\n');
+    }
+
+    /// e.g. `(self: C, x: Int, y := Str) -> Int` -> `(self, x: int, /, y__: str = ...) -> int`
+    ///
+    /// The parameters are renamed in the transpiled script (`x` -> `x__`),
+    /// so the non-default parameters are declared as positional-only.
+    fn fmt_signature(&mut self, subr: &SubrType, kind: MethodKind) -> String {
+        let mut params = vec![];
+        for (i, pt) in subr.non_default_params.iter().enumerate() {
+            let param = match (i, kind) {
+                (0, MethodKind::Instance) => "self".to_string(),
+                (0, MethodKind::Class) => "cls".to_string(),
+                _ => {
+                    let name = pt.name().map_or(format!("arg{i}"), |name| py_name(name));
+                    format!("{name}: {}", self.fmt_type(pt.typ()))
+                }
+            };
+            params.push(param);
+        }
+        let receiver = usize::from(kind != MethodKind::Static);
+        if subr.non_default_params.len() > receiver {
+            params.push("/".to_string());
+        }
+        if let Some(pt) = subr.var_params.as_ref() {
+            let name = pt.name().map_or("args".to_string(), |name| py_name(name));
+            params.push(format!("*{name}__: {}", self.fmt_type(pt.typ())));
+        }
+        for pt in subr.default_params.iter() {
+            if let ParamTy::KwWithDefault { name, ty, .. } | ParamTy::Kw { name, ty } = pt {
+                params.push(format!("{}__: {} = ...", py_name(name), self.fmt_type(ty)));
+            }
+        }
+        let ret = self.fmt_type(&subr.return_t);
+        format!("({}) -> {ret}", params.join(", "))
+    }
+
+    fn fmt_type_arg(&mut self, tp: &TyParam) -> String {
+        match <&Type>::try_from(tp) {
+            Ok(t) => self.fmt_type(t),
+            Err(_) => self.any(),
+        }
+    }
+
+    fn any(&mut self) -> String {
+        self.typing.insert("Any");
+        "Any".to_string()
+    }
+
+    fn fmt_type(&mut self, t: &Type) -> String {
+        match t {
+            Type::FreeVar(fv) if fv.is_linked() => self.fmt_type(&fv.crack()),
+            // widened
+            Type::Refinement(refine) => self.fmt_type(&refine.t),
+            Type::Ref(t) | Type::RefMut { before: t, .. } => self.fmt_type(t),
+            Type::Obj => "object".to_string(),
+            Type::Int | Type::Nat => "int".to_string(),
+            Type::Ratio | Type::Float => "float".to_string(),
+            Type::Complex => "complex".to_string(),
+            Type::Bool => "bool".to_string(),
+            Type::Str => "str".to_string(),
+            Type::NoneType => "None".to_string(),
+            Type::Type | Type::ClassType | Type::TraitType => "type".to_string(),
+            Type::Never => {
+                self.typing.insert("NoReturn");
+                "NoReturn".to_string()
+            }
+            Type::Mono(name) => match &name[..] {
+                "Int!" | "Nat!" => "int".to_string(),
+                "Float!" => "float".to_string(),
+                "Bool!" => "bool".to_string(),
+                "Str!" => "str".to_string(),
+                "Bytes" => "bytes".to_string(),
+                _ if self.is_local(t) => t.local_name().to_string(),
+                _ => self.any(),
+            },
+            Type::Poly { name, params } => match (&name[..], &params[..]) {
+                ("Array" | "Array!", [elem, ..]) => format!("list[{}]", self.fmt_type_arg(elem)),
+                ("Set" | "Set!", [elem, ..]) => format!("set[{}]", self.fmt_type_arg(elem)),
+                ("Dict" | "Dict!", [TyParam::Dict(dict)]) if dict.len() == 1 => {
+                    let (k, v) = dict.iter().next().unwrap();
+                    format!("dict[{}, {}]", self.fmt_type_arg(k), self.fmt_type_arg(v))
+                }
+                ("Tuple", [TyParam::Array(elems)]) => {
+                    let elems = elems
+                        .iter()
+                        .map(|elem| self.fmt_type_arg(elem))
+                        .collect::<Vec<_>>();
+                    if elems.is_empty() {
+                        "tuple[()]".to_string()
+                    } else {
+                        format!("tuple[{}]", elems.join(", "))
+                    }
+                }
+                ("Iterable" | "Sequence", [elem]) => {
+                    let name = if &name[..] == "Iterable" {
+                        "Iterable"
+                    } else {
+                        "Sequence"
+                    };
+                    self.typing.insert(name);
+                    format!("{name}[{}]", self.fmt_type_arg(elem))
+                }
+                _ => self.any(),
+            },
+            Type::Or(lhs, rhs) => format!("{} | {}", self.fmt_type(lhs), self.fmt_type(rhs)),
+            Type::Subr(subr) => {
+                self.typing.insert("Callable");
+                let params = subr
+                    .non_default_params
+                    .iter()
+                    .chain(subr.default_params.iter())
+                    .map(|pt| self.fmt_type(pt.typ()))
+                    .collect::<Vec<_>>();
+                let ret = self.fmt_type(&subr.return_t);
+                if subr.var_params.is_some() {
+                    format!("Callable[..., {ret}]")
+                } else {
+                    format!("Callable[[{}], {ret}]", params.join(", "))
+                }
+            }
+            _ => self.any(),
+        }
+    }
+
+    /// Defined in this module
+    fn is_local(&self, t: &Type) -> bool {
+        let qual_name = t.qual_name();
+        let local_name = t.local_name();
+        qual_name
+            .strip_suffix(&local_name[..])
+            .is_some_and(|prefix| {
+                prefix
+                    .strip_suffix(['.', ':'])
+                    .is_some_and(|module| module.trim_end_matches(':') == &self.ctx.name[..])
+            })
+    }
+}
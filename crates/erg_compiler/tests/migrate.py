@@ -0,0 +1,39 @@
+import math
+
+# Fibonacci numbers
+def fib(n: int) -> int:
+    """Returns the n-th Fibonacci number."""
+    if n <= 1:
+        return n
+    return fib(n - 1) + fib(n - 2)
+
+def greet(name: str, greeting="Hello"):
+    print(f"{greeting}, {name}!")
+
+def classify(n: int) -> str:
+    if n < 0:
+        return "negative"
+    elif n == 0:
+        return 'zero'
+    else:
+        return "positive"
+
+def collect(n: int) -> list[int]:
+    result = []
+    i = 0
+    while i < n:
+        result.append(i * i)
+        i += 1
+    return result
+
+class Point:
+    def __init__(self, x, y):
+        self.x = x
+        self.y = y
+
+squares = [x * x for x in range(10)]
+lo = 0; hi = 10  # bounds
+add = lambda a, b: a + b
+greet("Erg")
+print(fib(10), classify(-1), add(1, 2), sep=" ")
+print(collect(3), math.pi > 3, lo < hi)
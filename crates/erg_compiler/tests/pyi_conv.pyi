@@ -0,0 +1,94 @@
+import sys
+from typing import Any, Generic, Iterable, Literal, Optional, TypeVar, overload
+from typing_extensions import Self, TypeAlias
+from os import PathLike
+from dataclasses import KW_ONLY, dataclass, field
+from enum import Enum
+from typing import Callable, ClassVar, NamedTuple, Protocol
+from collections import namedtuple
+
+T = TypeVar("T")
+N = TypeVar("N", bound=float)
+Number: TypeAlias = int | float
+
+VERSION: str
+DEBUG: bool = ...
+MAX_SIZE = 100
+
+class Shape:
+    name: str
+    def __init__(self, name: str) -> None: ...
+    def area(self) -> float: ...
+    @property
+    def label(self) -> str: ...
+    @label.setter
+    def label(self, value: str) -> None: ...
+    @staticmethod
+    def unit() -> Shape: ...
+    @classmethod
+    def named(cls, name: str) -> Self: ...
+    def _private(self) -> None: ...
+    def __add__(self, other: Shape) -> Shape: ...
+
+class Circle(Shape):
+    radius: float
+    if sys.version_info >= (3, 8):
+        def scale(self, k: float, /, *, inplace: bool = False) -> Circle: ...
+    else:
+        def scale(self, k: int) -> Circle: ...
+
+class Box(Generic[T]):
+    def __init__(self, item: T) -> None: ...
+    def get(self) -> T: ...
+
+@overload
+def parse(x: int) -> int: ...
+@overload
+def parse(x: str) -> str: ...
+def biggest(xs: Iterable[N], default: Optional[N] = None) -> N: ...
+def mode(m: Literal["r", "w"] = "r", *args: int, **kwargs: Any) -> list[Number]: ...
+def open_path(p: str | PathLike[str]) -> dict[str, tuple[int, str]]: ...
+def apply(f: "Callable[[int], str]", xs: list[int]) -> set[str]: ...
+
+class Color(Enum):
+    RED = 1
+    GREEN = 2
+
+@dataclass
+class Point:
+    x: int
+    y: int = 0
+    ORIGIN: ClassVar[Point]
+
+class SupportsClose(Protocol):
+    def close(self) -> None: ...
+
+class Reader(SupportsClose, Protocol[T]):
+    mode: str
+    def read(self, n: int = -1) -> T: ...
+    def copy(self) -> Self: ...
+
+class Handler(Protocol):
+    def __call__(self, code: int, /) -> bool: ...
+
+def closing(r: SupportsClose) -> None: ...
+def read_all(r: Reader[str], on_error: Handler | None = None) -> str: ...
+
+@dataclass(frozen=True)
+class Point3D(Point):
+    z: int = 0
+    _: KW_ONLY
+    label: str
+    cache: dict[str, int] = field(init=False)
+    tags: list[str] = field(default_factory=list)
+
+@dataclass(init=False)
+class Lazy:
+    value: int
+
+class Pair(NamedTuple):
+    left: int
+    right: str = ""
+
+Version = NamedTuple("Version", [("major", int), ("minor", int)])
+Row = namedtuple("Row", "id name", defaults=[""])
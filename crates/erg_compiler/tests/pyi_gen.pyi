@@ -0,0 +1,27 @@
+from typing import Any, Protocol
+
+
+class Norm(Protocol):
+    def norm(self) -> int: ...
+
+
+class Point:
+    x: int
+    y: int
+    def __init__(self, param__: Any, /) -> None: ...
+    @staticmethod
+    def new(param__: Any, /) -> Point: ...
+    def norm(self) -> int: ...
+    def move(self, dx: int, /) -> Point: ...
+
+
+class Point3D(Point):
+    def __init__(self, param__: Any, /) -> None: ...
+    @staticmethod
+    def new(param__: Any, /) -> Point3D: ...
+
+origin: Point
+def add(x: int, /, y__: int = ...) -> int: ...
+def show__erg_proc__(x: int, /) -> None: ...
+names: list[str]
+pair: tuple[int, str]
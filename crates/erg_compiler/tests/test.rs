@@ -1,16 +1,42 @@
-use erg_common::config::ErgConfig;
-use erg_common::error::MultiErrorDisplay;
-use erg_common::io::Output;
+use erg_common::config::{ErgConfig, TargetProfile};
+use erg_common::error::{set_codes_only, ErrorDisplay, Location, MultiErrorDisplay, Suggestion};
+use erg_common::io::{Input, Output};
+use erg_common::json::Json;
+use erg_common::python_util::PythonVersion;
 use erg_common::spawn::exec_new_thread;
-use erg_common::traits::Runnable;
+use erg_common::traits::{Locational, Runnable, Stream};
+use erg_common::Str;
 
+use erg_parser::ast;
+
+use erg_compiler::artifact::Buildable;
+use erg_compiler::backend::Backend;
+use erg_compiler::build_hir::HIRBuilder;
 use erg_compiler::context::{Context, ModuleContext};
+use erg_compiler::decl_gen::DeclGenerator;
+use erg_compiler::decl_test::DeclTester;
+use erg_compiler::dump::Dumper;
 use erg_compiler::error::CompileErrors;
+use erg_compiler::fix::{apply_fixes, Fix, Fixer};
+use erg_compiler::hir::{
+    Call, ClassDef, Def, DefBody, Expr, Literal, Signature, SubrSignature, HIR,
+};
 use erg_compiler::lower::ASTLowerer;
+use erg_compiler::migrate::Migrator;
+use erg_compiler::module::{PromiseEvent, PromiseListener, SharedModuleGraph, SharedPromises};
+use erg_compiler::module_graph::GraphPrinter;
+use erg_compiler::nil_audit::NilAuditor;
+use erg_compiler::printer::print_hir;
+use erg_compiler::pyi_conv::PyiConverter;
+use erg_compiler::pyi_gen::PyiGenerator;
+use erg_compiler::visitor::{fold_children, walk_def, Fold, Visit};
+use erg_compiler::{ASTBuilder, Compiler};
 
 use erg_compiler::ty::constructors::{
-    func0, func1, func2, kw, mono, nd_func, nd_proc, or, poly, proc1, subtype_q, ty_tp, type_q,
+    and, func, func0, func1, func2, kw, mono, nd_func, nd_proc, or, poly, proc1, subtype_q, ty_tp,
+    type_q,
 };
+use erg_compiler::ty::value::ValueObj;
 use erg_compiler::ty::Type::*;
 
 fn load_file(path: &'static str) -> Result<ModuleContext, CompileErrors> {
@@ -67,6 +93,18 @@ fn _test_infer_types() -> Result<(), ()> {
     Ok(())
 }
 
+#[test]
+fn test_super_type_cache() -> Result<(), ()> {
+    exec_new_thread(_test_super_type_cache, "test_super_type_cache")
+}
+
+fn _test_super_type_cache() -> Result<(), ()> {
+    let mut module = load_file("tests/super_types.er").map_err(|errs| {
+        errs.write_all_stderr();
+    })?;
+    module.context.test_super_type_cache()
+}
+
 #[test]
 fn test_refinement_subtyping() -> Result<(), ()> {
     let context = Context::default_with_name("<module>");
@@ -95,6 +133,720 @@ fn test_intersection() -> Result<(), ()> {
     Ok(())
 }
 
+#[test]
+fn test_deep_types() -> Result<(), ()> {
+    exec_new_thread(_test_deep_types, "test_deep_types")
+}
+
+fn _test_deep_types() -> Result<(), ()> {
+    let context = Context::default_with_name("<module>");
+    context.test_deep_types()
+}
+
+#[test]
+fn test_infinite_type() -> Result<(), ()> {
+    let context = Context::default_with_name("<module>");
+    context.test_infinite_type()
+}
+
+#[test]
+fn test_migrate() -> Result<(), ()> {
+    exec_new_thread(_test_migrate, "test_migrate")
+}
+
+fn _test_migrate() -> Result<(), ()> {
+    let src = std::fs::read_to_string("tests/migrate.py").unwrap();
+    let expect = std::fs::read_to_string("tests/migrate.er").unwrap();
+    let mut migrator = Migrator::default();
+    let code = migrator.migrate(&src).map_err(|errs| {
+        errs.write_all_stderr();
+    })?;
+    assert_eq!(code, expect);
+    assert_eq!(migrator.holes(), 2);
+    load_file("tests/migrate.er").map_err(|errs| {
+        errs.write_all_stderr();
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test_decl_examples() -> Result<(), ()> {
+    exec_new_thread(_test_decl_examples, "test_decl_examples")
+}
+
+fn _test_decl_examples() -> Result<(), ()> {
+    let path = std::path::Path::new("tests/greet.d.er");
+    let src = std::fs::read_to_string(path).unwrap();
+    let mut tester = DeclTester::default();
+    let (_warns, errs) = tester.check_examples(path, src);
+    assert_eq!(tester.failed(), 1);
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs.first().unwrap().core().loc.ln_begin(), Some(21));
+    Ok(())
+}
+
+#[test]
+fn test_decl_gen() -> Result<(), ()> {
+    exec_new_thread(_test_decl_gen, "test_decl_gen")
+}
+
+fn _test_decl_gen() -> Result<(), ()> {
+    let src = std::fs::read_to_string("tests/decl_gen.er").unwrap();
+    let expect = std::fs::read_to_string("tests/decl_gen.d.er").unwrap();
+    let mut generator = DeclGenerator::default();
+    let code = generator.generate(src).map_err(|errs| {
+        errs.write_all_stderr();
+    })?;
+    assert_eq!(code, expect);
+    Ok(())
+}
+
+#[test]
+fn test_pyi_gen() -> Result<(), ()> {
+    exec_new_thread(_test_pyi_gen, "test_pyi_gen")
+}
+
+fn _test_pyi_gen() -> Result<(), ()> {
+    let src = std::fs::read_to_string("tests/pyi_gen.er").unwrap();
+    let expect = std::fs::read_to_string("tests/pyi_gen.pyi").unwrap();
+    let mut generator = PyiGenerator::default();
+    let code = generator.generate(src).map_err(|errs| {
+        errs.write_all_stderr();
+    })?;
+    assert_eq!(code, expect);
+    Ok(())
+}
+
+#[test]
+fn test_pyi_conv() -> Result<(), ()> {
+    exec_new_thread(_test_pyi_conv, "test_pyi_conv")
+}
+
+fn _test_pyi_conv() -> Result<(), ()> {
+    let src = std::fs::read_to_string("tests/pyi_conv.pyi").unwrap();
+    let expect = std::fs::read_to_string("tests/pyi_conv.d.er").unwrap();
+    let code = PyiConverter::new(PythonVersion::new(3, Some(11), Some(0))).convert(&src);
+    assert_eq!(code, expect);
+    let mut builder = HIRBuilder::default();
+    builder.build(code, "declare").map_err(|art| {
+        art.errors.write_all_stderr();
+    })?;
+    Ok(())
+}
+
+#[test]
+fn test_nil_audit() -> Result<(), ()> {
+    exec_new_thread(_test_nil_audit, "test_nil_audit")
+}
+
+fn _test_nil_audit() -> Result<(), ()> {
+    let mut cfg = ErgConfig::with_main_path("tests/nil_audit.er".into());
+    let src = cfg.input.read();
+    let mut auditor = NilAuditor::new(cfg);
+    let reports = auditor.audit(src).map_err(|errs| errs.write_all_stderr())?;
+    let summary = reports
+        .iter()
+        .map(|report| {
+            let lines = report
+                .flows
+                .iter()
+                .map(|flow| flow.loc.loc.ln_begin().unwrap())
+                .collect::<Vec<_>>();
+            (report.origin.name.to_string(), lines)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("nil_audit_py.find".to_string(), vec![8, 9]),
+            ("nil_audit_py.default".to_string(), vec![11]),
+        ]
+    );
+    assert_eq!(reports[0].flows[1].via, vec![Str::ever("lookup")]);
+    Ok(())
+}
+
+/// A backend that only extracts the names of the top-level definitions
+#[derive(Default)]
+struct DefNameExtractor {
+    names: Vec<String>,
+}
+
+impl Backend for DefNameExtractor {
+    type Output = Vec<String>;
+    type Fragment = ();
+
+    fn emit_module(&mut self, hir: HIR) -> Vec<String> {
+        for chunk in hir.module.into_iter() {
+            self.emit_expr(chunk);
+        }
+        std::mem::take(&mut self.names)
+    }
+
+    fn emit_expr(&mut self, expr: Expr) {
+        match expr {
+            Expr::Def(def) => match def.sig {
+                Signature::Subr(sig) => self.emit_function(sig, def.body),
+                Signature::Var(sig) => self.names.push(sig.ident.inspect().to_string()),
+            },
+            Expr::ClassDef(class_def) => self.emit_class(class_def),
+            _ => {}
+        }
+    }
+
+    fn emit_function(&mut self, sig: SubrSignature, _body: DefBody) {
+        self.names.push(sig.ident.inspect().to_string());
+    }
+
+    fn emit_class(&mut self, class_def: ClassDef) {
+        self.names.push(class_def.sig.ident().inspect().to_string());
+    }
+
+    fn emit_literal(&mut self, _lit: Literal) {}
+
+    fn emit_call(&mut self, _call: Call) {}
+
+    fn clear(&mut self) {
+        self.names.clear();
+    }
+}
+
+#[test]
+fn test_custom_backend() -> Result<(), ()> {
+    exec_new_thread(_test_custom_backend, "test_custom_backend")
+}
+
+fn _test_custom_backend() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let mut compiler = Compiler::new(cfg);
+    let mut backend = DefNameExtractor::default();
+    let src = "f x = x + 1\nC = Class()\ny = f 1\nprint! y\n".to_string();
+    let artifact = compiler
+        .compile_with(&mut backend, src, "exec")
+        .map_err(|eart| {
+            eart.errors.write_all_stderr();
+        })?;
+    assert_eq!(artifact.object, vec!["f", "C", "y"]);
+    Ok(())
+}
+
+#[test]
+fn test_micropython_target() -> Result<(), ()> {
+    exec_new_thread(_test_micropython_target, "test_micropython_target")
+}
+
+fn _test_micropython_target() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        target: TargetProfile::MicroPython,
+        ..Default::default()
+    };
+    let mut builder = HIRBuilder::new(cfg.clone());
+    let src = "math = pyimport \"math\"\nprint! math.pi\n".to_string();
+    builder.build(src, "exec").map_err(|eart| {
+        eart.errors.write_all_stderr();
+    })?;
+    let mut builder = HIRBuilder::new(cfg.clone());
+    let src = "dc = pyimport \"dataclasses\"\nprint! ascii(1)\n".to_string();
+    let errs = builder.build(src, "exec").unwrap_err().errors;
+    assert_eq!(errs.len(), 2);
+    let mut compiler = Compiler::new(cfg);
+    assert!(compiler.compile("print! 1".to_string(), "exec").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_type_check_limits() -> Result<(), ()> {
+    exec_new_thread(_test_type_check_limits, "test_type_check_limits")
+}
+
+fn _test_type_check_limits() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let src = "id x = x\na = id [[[1]]]\nprint! a\n";
+    let mut builder = HIRBuilder::new(cfg.clone());
+    builder.build(src.to_string(), "exec").map_err(|eart| {
+        eart.errors.write_all_stderr();
+    })?;
+    let too_complex = |cfg: ErgConfig| {
+        let mut builder = HIRBuilder::new(cfg);
+        let errs = builder.build(src.to_string(), "exec").unwrap_err().errors;
+        errs.iter()
+            .any(|err| err.core().main_message == "type checking too complex")
+    };
+    let mut depth_limited = cfg.clone();
+    depth_limited.max_type_check_depth = 2;
+    assert!(too_complex(depth_limited));
+    let mut steps_limited = cfg;
+    steps_limited.max_type_check_steps = 5;
+    assert!(too_complex(steps_limited));
+    Ok(())
+}
+
+#[test]
+fn test_property_setter() -> Result<(), ()> {
+    exec_new_thread(_test_property_setter, "test_property_setter")
+}
+
+/// `obj.x = v` in the ASTs converted from Python code (built by hand here)
+fn _test_property_setter() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let src = r#"
+C = Class { .v = Int }
+C.
+    @Property
+    x(self): Int = self.v
+    @Property
+    x!(ref! self, value: Int): NoneType = print! value
+    @Property
+    y(self): Int = self.v
+c = C.new { .v = 1 }
+c.x
+1
+c.x
+"a"
+c.y
+1
+"#;
+    let mut ast = ASTBuilder::new(cfg.copy())
+        .build(src.to_string())
+        .map_err(|iart| {
+            iart.errors.write_all_stderr();
+        })?
+        .ast;
+    let mut redefs = vec![];
+    for _ in 0..3 {
+        let expr = ast.module.ref_mut_payload().pop().unwrap();
+        let Some(ast::Expr::Accessor(attr)) = ast.module.ref_mut_payload().pop() else {
+            return Err(());
+        };
+        redefs.push(ast::Expr::ReDef(ast::ReDef::new(attr, expr)));
+    }
+    ast.module.extend(redefs.into_iter().rev());
+    let mut lowerer = ASTLowerer::new(cfg);
+    let errs = lowerer.lower(ast, "exec").unwrap_err().errors;
+    // `c.x = "a"`: type mismatch, `c.y = 1`: `y` has no setter
+    assert_eq!(errs.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_header_disables_feature() -> Result<(), ()> {
+    exec_new_thread(
+        _test_header_disables_feature,
+        "test_header_disables_feature",
+    )
+}
+
+fn _test_header_disables_feature() -> Result<(), ()> {
+    use erg_common::error::ErrorKind;
+
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let header = "#!erg 0.7 -conditional_binding";
+    let src = "x as Int or Str = 1\na = if (i: Int) := x, do: i\n";
+    // the conditional binding is parsed in the current edition, and is not desugared
+    let ast = ASTBuilder::new(cfg.copy())
+        .build_without_desugaring(src.to_string())
+        .map_err(|iart| {
+            iart.errors.write_all_stderr();
+        })?
+        .ast;
+    let mut builder = HIRBuilder::new(cfg);
+    assert!(builder
+        .cfg_mut()
+        .apply_header(&format!("{header}\n{src}"))
+        .is_none());
+    let errs = builder.check(ast, "exec").unwrap_err().errors;
+    assert_eq!(errs.len(), 1, "{errs}");
+    let err = errs.first().unwrap();
+    assert_eq!(err.core.kind, ErrorKind::SyntaxError);
+    assert_eq!(err.core.loc, Location::range(2, 16, 2, 18));
+    Ok(())
+}
+
+#[test]
+fn test_codes_only() -> Result<(), ()> {
+    exec_new_thread(_test_codes_only, "test_codes_only")
+}
+
+fn _test_codes_only() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let mut builder = HIRBuilder::new(cfg);
+    let src = "x = 1\ny: Str = x\n".to_string();
+    let errs = builder.build(src, "exec").unwrap_err().errors;
+    set_codes_only(true);
+    let shown = errs.iter().map(|err| err.show()).collect::<String>();
+    set_codes_only(false);
+    assert_eq!(shown, "<stdin>:2:1: TypeError\n");
+    Ok(())
+}
+
+#[test]
+fn test_missing_trait_members() -> Result<(), ()> {
+    exec_new_thread(_test_missing_trait_members, "test_missing_trait_members")
+}
+
+fn _test_missing_trait_members() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let mut builder = HIRBuilder::new(cfg);
+    let src = "\
+Shape = Trait { .area = (self: Self) -> Float; .name = (self: Self) -> Str }
+Square = Class { .side = Float }
+Square|<: Shape|.
+  area self = self.side * self.side
+"
+    .to_string();
+    let errs = builder.build(src, "exec").unwrap_err().errors;
+    // the missing members are reported at once
+    assert_eq!(errs.len(), 1);
+    let stubs = &errs.first().unwrap().core().suggestions[0];
+    assert_eq!(stubs.loc, Location::range(5, 0, 5, 0));
+    assert_eq!(stubs.replacement, "  name(self): Str = unreachable()\n");
+    Ok(())
+}
+
+#[test]
+fn test_fix() -> Result<(), ()> {
+    exec_new_thread(_test_fix, "test_fix")
+}
+
+fn _test_fix() -> Result<(), ()> {
+    let src = "\
+math = pyimport \"math\"
+counter = 1
+print! countr + 1
+"
+    .to_string();
+    let cfg = ErgConfig {
+        input: Input::str(src.clone()),
+        output: Output::Null,
+        ..Default::default()
+    };
+    let mut fixer = Fixer::new(cfg);
+    let fixed = fixer.eval(src).map_err(|errs| errs.write_all_stderr())?;
+    assert_eq!(fixed, "counter = 1\nprint! counter + 1\n");
+    // overlapping edits are not applied
+    let fix = |loc, replacement: &str| Fix {
+        path: None,
+        suggestion: Suggestion::new("", None, loc, replacement),
+    };
+    let fixes = vec![
+        fix(Location::range(1, 0, 1, 3), "xyz"),
+        fix(Location::range(1, 2, 1, 5), "a"),
+        fix(Location::range(2, 0, 2, 0), "."),
+    ];
+    let (fixed, applied, conflicts) = apply_fixes("abcde\nf\n", fixes);
+    assert_eq!(fixed, "xyzde\n.f\n");
+    assert_eq!((applied.len(), conflicts.len()), (2, 1));
+    Ok(())
+}
+
+#[test]
+fn test_invisible_attr() -> Result<(), ()> {
+    exec_new_thread(_test_invisible_attr, "test_invisible_attr")
+}
+
+fn _test_invisible_attr() -> Result<(), ()> {
+    let mut cfg = ErgConfig::with_main_path("tests/visibility.er".into());
+    cfg.output = Output::Null;
+    let mut builder = HIRBuilder::new(cfg);
+    let errs = builder.build_module().unwrap_err().errors;
+    // the hidden `x: {1}` is still used, so `s: Str = lib.x` is also a type error
+    assert_eq!(errs.len(), 3);
+    let err = errs.first().unwrap();
+    let hint = err.core().sub_messages[0].get_hint().unwrap();
+    assert!(hint.ends_with("(line 1 of visibility_lib.er)"), "{hint}");
+    // `x` -> `.x` in visibility_lib.er
+    let make_public = &err.core().suggestions[0];
+    assert!(make_public
+        .path
+        .as_ref()
+        .is_some_and(|path| path.ends_with("visibility_lib.er")));
+    assert_eq!(make_public.loc, Location::range(1, 0, 1, 0));
+    assert_eq!(make_public.replacement, ".");
+    // `lib.z` -> `lib.get_z()`
+    let use_public = &errs.get(2).unwrap().core().suggestions[0];
+    assert_eq!(use_public.path, None);
+    assert_eq!(use_public.loc, Location::range(5, 8, 5, 9));
+    assert_eq!(use_public.replacement, "get_z()");
+    Ok(())
+}
+
+#[test]
+fn test_promise_events() -> Result<(), ()> {
+    exec_new_thread(_test_promise_events, "test_promise_events")
+}
+
+fn _test_promise_events() -> Result<(), ()> {
+    let mut cfg = ErgConfig::with_main_path("tests/visibility.er".into());
+    cfg.output = Output::Null;
+    let mut builder = HIRBuilder::new(cfg);
+    let events = std::sync::Arc::new(std::sync::Mutex::new(vec![]));
+    let _events = events.clone();
+    let listener = PromiseListener::new(move |event| _events.lock().unwrap().push(event.clone()));
+    let shared = builder.get_context().unwrap().context.shared();
+    shared.promises.set_listener(listener);
+    let _ = builder.build_module();
+    let events = events.lock().unwrap();
+    let lib = |event: &PromiseEvent| match event {
+        PromiseEvent::Started(path) | PromiseEvent::Finished(path) => {
+            path.ends_with("visibility_lib.er")
+        }
+    };
+    assert_eq!(events.len(), 2, "{events:?}");
+    assert!(matches!(events[0], PromiseEvent::Started(_)) && lib(&events[0]));
+    assert!(matches!(events[1], PromiseEvent::Finished(_)) && lib(&events[1]));
+    Ok(())
+}
+
+#[test]
+fn test_promise_deadlock() {
+    use std::path::{Path, PathBuf};
+    use std::sync::mpsc::channel;
+
+    let promises = SharedPromises::new(SharedModuleGraph::new(), PathBuf::from("main.er"));
+    let (res_tx, res_rx) = channel();
+    let mut starts = vec![];
+    // `a.er` and `b.er` join each other
+    for (path, other) in [("a.er", "b.er"), ("b.er", "a.er")] {
+        let (start_tx, start_rx) = channel();
+        let res_tx = res_tx.clone();
+        let _promises = promises.inherit(PathBuf::from(path));
+        _promises.register_import_site(PathBuf::from(other), Location::range(1, 0, 1, 8));
+        let handle = std::thread::spawn(move || {
+            start_rx.recv().unwrap();
+            res_tx.send(_promises.join(Path::new(other))).unwrap();
+        });
+        promises.insert(PathBuf::from(path), handle);
+        starts.push(start_tx);
+    }
+    for start in starts {
+        start.send(()).unwrap();
+    }
+    let results = [res_rx.recv().unwrap(), res_rx.recv().unwrap()];
+    let deadlocks = results
+        .iter()
+        .filter_map(|res| res.as_ref().err())
+        .collect::<Vec<_>>();
+    assert_eq!(deadlocks.len(), 1, "{results:?}");
+    let mut modules = [&deadlocks[0].waiter, &deadlocks[0].target];
+    modules.sort();
+    assert_eq!(modules, [Path::new("a.er"), Path::new("b.er")]);
+    assert_eq!(deadlocks[0].loc, Location::range(1, 0, 1, 8));
+    promises.join_all();
+    assert_eq!(promises.num_threads(), 0);
+}
+
+#[test]
+fn test_promise_panic() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::path::{Path, PathBuf};
+
+    let promises = SharedPromises::new(SharedModuleGraph::new(), PathBuf::from("main.er"));
+    let handle = std::thread::spawn(|| panic!("analysis failed"));
+    promises.insert(PathBuf::from("a.er"), handle);
+    let res = catch_unwind(AssertUnwindSafe(|| promises.join(Path::new("a.er"))));
+    let payload = res.unwrap_err();
+    assert_eq!(payload.downcast_ref::<&str>(), Some(&"analysis failed"));
+    assert!(promises.is_finished(Path::new("a.er")));
+    assert_eq!(promises.num_threads(), 0);
+}
+
+#[test]
+fn test_module_panic() -> Result<(), ()> {
+    exec_new_thread(_test_module_panic, "test_module_panic")
+}
+
+fn _test_module_panic() -> Result<(), ()> {
+    use erg_common::error::ErrorKind;
+    use std::path::PathBuf;
+
+    let mut cfg = ErgConfig::with_main_path("tests/module_panic.er".into());
+    cfg.output = Output::Null;
+    let mut builder = HIRBuilder::new(cfg);
+    let shared = builder.get_context().unwrap().context.shared().clone();
+    let Err(artifact) = builder.build_module() else {
+        return Err(());
+    };
+    let errs = artifact.errors;
+    assert_eq!(errs.len(), 1, "{errs}");
+    // the panic in `module_panic_lib.er` is reported at the import site
+    let err = errs.first().unwrap();
+    assert_eq!(err.core.kind, ErrorKind::CompilerSystemError);
+    assert!(err
+        .input
+        .path()
+        .is_some_and(|path| path.ends_with("module_panic.er")));
+    assert_eq!(err.core.loc, Location::range(1, 11, 1, 29));
+    // the analysis of the sibling module is not affected
+    let sibling = PathBuf::from("tests/module_panic_sibling.er")
+        .canonicalize()
+        .unwrap();
+    assert!(shared.mod_cache.get(&sibling).is_some());
+    Ok(())
+}
+
+#[test]
+fn test_resource_stats() -> Result<(), ()> {
+    exec_new_thread(_test_resource_stats, "test_resource_stats")
+}
+
+fn _test_resource_stats() -> Result<(), ()> {
+    let mut cfg = ErgConfig::with_main_path("tests/visibility.er".into());
+    cfg.output = Output::Null;
+    let mut builder = HIRBuilder::new(cfg);
+    let shared = builder.get_context().unwrap().context.shared().clone();
+    let before = shared.stats();
+    let _ = builder.build_module();
+    let after = shared.stats();
+    // `visibility_lib.er` is cached
+    assert_eq!(after.modules, before.modules + 1, "{after}");
+    assert_eq!(after.promises, 1);
+    assert!(after.running_promises <= after.promises);
+    assert!(after.types > 0 && after.trait_impls > 0);
+    assert!(after.index_members > before.index_members);
+    // only the builtin module is left
+    shared.clear_all();
+    assert_eq!(shared.stats().modules, 1);
+    assert_eq!(shared.stats().index_members, 0);
+    Ok(())
+}
+
+#[test]
+fn test_invalidate() -> Result<(), ()> {
+    exec_new_thread(_test_invalidate, "test_invalidate")
+}
+
+fn _test_invalidate() -> Result<(), ()> {
+    use std::path::PathBuf;
+
+    let mut cfg = ErgConfig::with_main_path("tests/visibility.er".into());
+    cfg.output = Output::Null;
+    let mut builder = HIRBuilder::new(cfg.copy());
+    let shared = builder.get_context().unwrap().context.shared().clone();
+    let _ = builder.build_module();
+    let main = PathBuf::from("tests/visibility.er");
+    let lib = PathBuf::from("tests/visibility_lib.er")
+        .canonicalize()
+        .unwrap();
+    let before = shared.stats();
+    let dirty = shared.invalidate(&lib);
+    assert!(dirty.contains(&main), "{dirty:?}");
+    assert!(shared.graph.is_dirty(&main));
+    assert!(!shared.promises.is_registered(&lib));
+    assert_eq!(shared.stats().modules, before.modules - 1);
+    // `visibility_lib.er` is analyzed again
+    let mut builder = HIRBuilder::inherit(cfg, shared.clone());
+    let _ = builder.build_module();
+    assert!(shared.promises.is_registered(&lib));
+    assert_eq!(shared.stats().modules, before.modules);
+    Ok(())
+}
+
+#[test]
+fn test_reanalyze_dirty() -> Result<(), ()> {
+    exec_new_thread(_test_reanalyze_dirty, "test_reanalyze_dirty")
+}
+
+fn _test_reanalyze_dirty() -> Result<(), ()> {
+    use std::path::PathBuf;
+
+    let mut cfg = ErgConfig::with_main_path("tests/dirty.er".into());
+    cfg.output = Output::Null;
+    let mut builder = HIRBuilder::new(cfg.copy());
+    let shared = builder.get_context().unwrap().context.shared().clone();
+    let _ = builder.build_module();
+    let path = |name: &str| {
+        PathBuf::from(format!("tests/{name}.er"))
+            .canonicalize()
+            .unwrap()
+    };
+    let (mid, lib) = (path("dirty_mid"), path("dirty_lib"));
+    let mid_id = shared.mod_cache.get(&mid).unwrap().id;
+    shared.invalidate(&lib);
+    assert!(shared.graph.is_dirty(&mid));
+    // `dirty_mid.er` is analyzed again (and registered) because it depends on `dirty_lib.er`
+    let mut builder = HIRBuilder::inherit(cfg, shared.clone());
+    let _ = builder.build_module();
+    assert!(!shared.graph.is_dirty(&mid));
+    assert_ne!(shared.mod_cache.get(&mid).unwrap().id, mid_id);
+    assert!(shared.mod_cache.get(&lib).is_some());
+    Ok(())
+}
+
+#[test]
+fn test_import_suggestion() -> Result<(), ()> {
+    exec_new_thread(_test_import_suggestion, "test_import_suggestion")
+}
+
+fn _test_import_suggestion() -> Result<(), ()> {
+    let mut cfg = ErgConfig::with_main_path("tests/import_suggest.er".into());
+    cfg.output = Output::Null;
+    let mut builder = HIRBuilder::new(cfg);
+    let errs = builder.build_module().unwrap_err().errors;
+    assert_eq!(errs.len(), 1);
+    // `get_z` is defined in visibility_lib.er (imported as `lib`)
+    let import = errs
+        .first()
+        .unwrap()
+        .core()
+        .suggestions
+        .iter()
+        .find(|sugg| sugg.title.starts_with("Import"))
+        .unwrap();
+    assert_eq!(import.path, None);
+    assert_eq!(import.loc, Location::range(1, 0, 1, 0));
+    assert_eq!(import.replacement, "{get_z;} = import \"visibility_lib\"\n");
+    Ok(())
+}
+
+#[test]
+fn test_repl_undo() -> Result<(), ()> {
+    exec_new_thread(_test_repl_undo, "test_repl_undo")
+}
+
+fn _test_repl_undo() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let mut builder = HIRBuilder::new(cfg);
+    for src in ["x = 1", "f i = i + x"] {
+        builder
+            .build(src.to_string(), "eval")
+            .map_err(|eart| eart.errors.write_all_stderr())?;
+    }
+    // a failed evaluation leaves no trace
+    let src = "g() = 1\ny: Str = x".to_string();
+    assert!(builder.build(src, "eval").is_err());
+    assert!(builder.get_var_info("g").is_none());
+    assert!(builder.get_var_info("f").is_some());
+    assert!(builder.undo());
+    assert!(builder.get_var_info("f").is_none());
+    assert!(builder.get_var_info("x").is_some());
+    assert!(builder.undo());
+    assert!(builder.get_var_info("x").is_none());
+    assert!(!builder.undo());
+    Ok(())
+}
+
 /*
 #[test]
 fn test_patch() -> Result<(), ()> {
@@ -127,3 +879,359 @@ fn _test_dir() -> Result<(), ()> {
     }
     Ok(())
 }
+
+#[test]
+fn test_doc_comments() -> Result<(), ()> {
+    exec_new_thread(_test_doc_comments, "test_doc_comments")
+}
+
+fn _test_doc_comments() -> Result<(), ()> {
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let src = r#"
+'''
+adds one
+'''
+'''erg
+assert inc(1) == 2
+'''
+inc x: Int = x + 1
+'''not attached'''
+print! 1
+dec x: Int = x - 1
+C = Class { .x = Int }
+C.
+    '''returns `x`'''
+    get self = self.x
+"#;
+    let mut builder = HIRBuilder::new(cfg);
+    let hir = builder
+        .build(src.to_string(), "exec")
+        .map_err(|eart| eart.errors.write_all_stderr())?
+        .object;
+    // the nearest one first
+    let (_, inc) = builder.get_var_info("inc").ok_or(())?;
+    assert_eq!(inc.docs.len(), 2);
+    assert!(inc.docs[0].starts_with("erg\n"));
+    assert_eq!(inc.docs[1].trim(), "adds one");
+    assert!(builder.get_var_info("dec").ok_or(())?.1.docs.is_empty());
+    let Some(Expr::ClassDef(class_def)) = hir.module.iter().last() else {
+        return Err(());
+    };
+    let Some(Expr::Def(get)) = class_def.methods.iter().last() else {
+        return Err(());
+    };
+    assert_eq!(get.sig.ident().vi.docs, vec![Str::ever("returns `x`")]);
+    Ok(())
+}
+
+#[test]
+fn test_reference_index() -> Result<(), ()> {
+    exec_new_thread(_test_reference_index, "test_reference_index")
+}
+
+fn _test_reference_index() -> Result<(), ()> {
+    let module = load_file("tests/references.er").map_err(|errs| errs.write_all_stderr())?;
+    let index = module.context.index();
+    let x_def = index
+        .members()
+        .iter()
+        .find(|(_, value)| &value.name[..] == "x")
+        .map(|(def_loc, _)| def_loc.clone())
+        .ok_or(())?;
+    let path = x_def.module.clone().ok_or(())?;
+    // `x` of `y = x + 1` and `print! x, y`
+    assert_eq!(index.get_referee(&path, 2, 5), Some(x_def.clone()));
+    assert_eq!(index.get_referee(&path, 3, 8), Some(x_def.clone()));
+    // the definition itself
+    assert_eq!(index.get_referee(&path, 1, 1), Some(x_def.clone()));
+    assert_eq!(index.get_refs(&x_def).ok_or(())?.referrers.len(), 2);
+    assert!(index.get_referee(&path, 2, 9).is_none());
+    Ok(())
+}
+
+#[test]
+fn test_resolve_partial_overload() -> Result<(), ()> {
+    exec_new_thread(
+        _test_resolve_partial_overload,
+        "test_resolve_partial_overload",
+    )
+}
+
+fn _test_resolve_partial_overload() -> Result<(), ()> {
+    let module = load_file("tests/references.er").map_err(|errs| errs.write_all_stderr())?;
+    let ctx = &module.context;
+    // ((Int, Int) -> Int) and ((x: Str, sep := Str) -> Str)
+    let overloaded = and(
+        func2(Int, Int, Int),
+        func(vec![kw("x", Str)], None, vec![kw("sep", Str)], Str),
+    );
+    assert_eq!(ctx.resolve_partial_overload(&overloaded, &[], &[]), Some(0));
+    assert_eq!(
+        ctx.resolve_partial_overload(&overloaded, &[Nat], &[]),
+        Some(0)
+    );
+    assert_eq!(
+        ctx.resolve_partial_overload(&overloaded, &[Str], &[]),
+        Some(1)
+    );
+    // `sep` is passed positionally
+    assert_eq!(
+        ctx.resolve_partial_overload(&overloaded, &[Str, Str], &[]),
+        Some(1)
+    );
+    assert_eq!(
+        ctx.resolve_partial_overload(&overloaded, &[Str], &[("sep".into(), Str)]),
+        Some(1)
+    );
+    assert_eq!(
+        ctx.resolve_partial_overload(&overloaded, &[Int, Str], &[]),
+        None
+    );
+    assert_eq!(
+        ctx.resolve_partial_overload(&overloaded, &[Int], &[("sep".into(), Str)]),
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn test_hir_visitor() -> Result<(), ()> {
+    exec_new_thread(_test_hir_visitor, "test_hir_visitor")
+}
+
+fn _test_hir_visitor() -> Result<(), ()> {
+    struct DefCounter(usize);
+    impl Visit for DefCounter {
+        fn visit_def(&mut self, def: &Def) {
+            self.0 += 1;
+            walk_def(self, def);
+        }
+    }
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let src = r#"
+f x =
+    y = x + 1
+    r = { .a = y; .b = [1, 2] }
+    r.a
+C = Class { .x = Int }
+C.
+    get self = self.x
+print! f 1
+"#;
+    let mut builder = HIRBuilder::new(cfg);
+    let hir = builder
+        .build(src.to_string(), "exec")
+        .map_err(|eart| eart.errors.write_all_stderr())?
+        .object;
+    // f, y, r, a, b, C, get
+    let mut counter = DefCounter(0);
+    counter.visit_module(&hir.module);
+    assert_eq!(counter.0, 7);
+    let mut literals = 0;
+    let module = (|expr: Expr| {
+        if let Expr::Lit(_) = &expr {
+            literals += 1;
+        }
+        expr
+    })
+    .fold_module(hir.module.clone());
+    // only the top-level chunks are passed to a closure
+    assert_eq!(literals, 0);
+    assert_eq!(module.len(), hir.module.len());
+    struct LiteralCounter(usize);
+    impl Fold for LiteralCounter {
+        fn fold_expr(&mut self, expr: Expr) -> Expr {
+            if let Expr::Lit(_) = &expr {
+                self.0 += 1;
+            }
+            fold_children(self, expr)
+        }
+    }
+    let mut counter = LiteralCounter(0);
+    let module = counter.fold_module(hir.module.clone());
+    assert_eq!(counter.0, 4);
+    assert_eq!(module.to_string(), hir.module.to_string());
+    Ok(())
+}
+
+#[test]
+fn test_dump() -> Result<(), ()> {
+    exec_new_thread(_test_dump, "test_dump")
+}
+
+fn _test_dump() -> Result<(), ()> {
+    let src = "x = 1\nprint! x + 2\n";
+    let mut cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let ast = Dumper::new(cfg.copy())
+        .dump(src.to_string())
+        .map_err(|errs| errs.write_all_stderr())?;
+    assert_eq!(ast.get("format_version"), Some(&Json::Int(1)));
+    let Some(Json::Array(module)) = ast.get("module") else {
+        panic!("{ast}");
+    };
+    assert_eq!(module[0].get("node"), Some(&"Def".into()));
+    assert_eq!(module[0].get("type"), None);
+    cfg.dump_hir = true;
+    let hir = Dumper::new(cfg)
+        .dump(src.to_string())
+        .map_err(|errs| errs.write_all_stderr())?;
+    let Some(Json::Array(module)) = hir.get("module") else {
+        panic!("{hir}");
+    };
+    let Some(Json::Array(body)) = module[0].get("body") else {
+        panic!("{hir}");
+    };
+    assert_eq!(body[0].get("node"), Some(&"Literal".into()));
+    assert_eq!(body[0].get("type"), Some(&"{1}".into()));
+    assert_eq!(module[1].get("node"), Some(&"Call".into()));
+    Ok(())
+}
+
+#[test]
+fn test_module_graph() -> Result<(), ()> {
+    exec_new_thread(_test_module_graph, "test_module_graph")
+}
+
+fn _test_module_graph() -> Result<(), ()> {
+    let mut cfg = ErgConfig::with_main_path("tests/visibility.er".into());
+    cfg.output = Output::Null;
+    let mut printer = GraphPrinter::new(cfg.copy());
+    // the graph is available even if the check fails
+    let (shared, _errs) = printer.analyze(cfg.input.read());
+    let json = shared.graph.to_json(&shared.py_mod_cache);
+    let Some(Json::Array(modules)) = json.get("modules") else {
+        panic!("{json}");
+    };
+    let main = modules
+        .iter()
+        .find(|module| module.get("path") == Some(&"tests/visibility.er".into()))
+        .unwrap();
+    assert_eq!(main.get("kind"), Some(&"erg".into()));
+    let Some(Json::Array(deps)) = main.get("depends_on") else {
+        panic!("{json}");
+    };
+    assert_eq!(deps.len(), 1);
+    let Json::Str(lib) = &deps[0] else {
+        panic!("{json}");
+    };
+    assert!(lib.ends_with("visibility_lib.er"));
+    let dot = shared.graph.to_dot(&shared.py_mod_cache);
+    assert!(dot.starts_with("digraph modules {"));
+    assert!(dot.contains(&format!("\"tests/visibility.er\" -> \"{lib}\";")));
+    assert!(dot.contains(&format!("\"{lib}\" [shape=ellipse];")));
+    // Python modules are the ones registered in `py_mod_cache`
+    let mut cfg = ErgConfig::with_main_path("tests/module_graph.er".into());
+    cfg.output = Output::Null;
+    let mut printer = GraphPrinter::new(cfg.copy());
+    let (shared, _errs) = printer.analyze(cfg.input.read());
+    let json = shared.graph.to_json(&shared.py_mod_cache);
+    let Some(Json::Array(modules)) = json.get("modules") else {
+        panic!("{json}");
+    };
+    let kinds = modules
+        .iter()
+        .map(|module| module.get("kind").unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(kinds.len(), 2, "{json}");
+    assert!(kinds.contains(&&"py".into()), "{json}");
+    assert!(kinds.contains(&&"erg".into()), "{json}");
+    Ok(())
+}
+
+#[test]
+fn test_hir_printer() -> Result<(), ()> {
+    exec_new_thread(_test_hir_printer, "test_hir_printer")
+}
+
+fn _test_hir_printer() -> Result<(), ()> {
+    let src = r#"
+@Inheritable
+C = Class { .x = Int }
+C.
+    get self = self.x
+D = Inherit C
+f(x: Int, y := 2): Int = x + y * 3
+g = x -> x + 1
+for! [1, 2], i =>
+    print! i
+    print! not True
+z = if True:
+    do 1
+    do 2
+print! f(1, y:=3), g(1), z, (1, "a"), {"a": 1}, {1, 2}, { .a = 1; .b = [1; 2] }
+print! C.new({ .x = 1 }).get(), (1: Int), -f(1)
+"#;
+    let cfg = ErgConfig {
+        output: Output::Null,
+        ..Default::default()
+    };
+    let build = |src: String| {
+        HIRBuilder::new(cfg.copy())
+            .build(src, "exec")
+            .map(|art| art.object)
+            .map_err(|eart| eart.errors.write_all_stderr())
+    };
+    let hir = build(src.to_string())?;
+    let printed = print_hir(&hir);
+    assert!(
+        printed.code.contains("@Inheritable\nC = Class"),
+        "{}",
+        printed.code
+    );
+    assert!(printed.code.contains("for! [1, 2], (i) =>\n    print!(i)"));
+    // `x + y * 3` of `f`
+    let body = hir.module.iter().find_map(|chunk| match chunk {
+        Expr::Def(def) if &def.sig.ident().inspect()[..] == "f" => def.body.block.first(),
+        _ => None,
+    });
+    let body_loc = printed.printed_loc(body.unwrap().loc()).unwrap();
+    assert_eq!(printed.original_loc(body_loc), Some(body.unwrap().loc()));
+    // printing is idempotent
+    let reprinted = print_hir(&build(printed.code.clone())?);
+    assert_eq!(printed.code, reprinted.code);
+    Ok(())
+}
+
+#[test]
+fn test_match_table() -> Result<(), ()> {
+    exec_new_thread(_test_match_table, "test_match_table")
+}
+
+fn _test_match_table() -> Result<(), ()> {
+    let src = "\
+x = 3
+s = match x:
+    1 -> \"a\"
+    2 -> \"b\"
+    3 -> \"c\"
+    4 -> \"d\"
+    _ -> \"e\"
+";
+    let has_table = |minor| {
+        let cfg = ErgConfig {
+            output: Output::Null,
+            target_version: Some(PythonVersion::new(3, Some(minor), Some(0))),
+            ..ErgConfig::default()
+        };
+        let mut compiler = Compiler::new(cfg);
+        let code = compiler
+            .compile(src.to_string(), "exec")
+            .map_err(|eart| eart.errors.write_all_stderr())?
+            .object;
+        Ok::<_, ()>(code.consts.iter().any(|c| matches!(c, ValueObj::Dict(_))))
+    };
+    assert!(has_table(11)?);
+    assert!(has_table(10)?);
+    // `CONTAINS_OP` is not available
+    assert!(!has_table(8)?);
+    Ok(())
+}
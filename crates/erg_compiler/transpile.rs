@@ -5,6 +5,7 @@ use erg_common::config::ErgConfig;
 use erg_common::dict::Dict as HashMap;
 use erg_common::error::MultiErrorDisplay;
 use erg_common::log;
+use erg_common::timings;
 use erg_common::traits::{ExitStatus, Runnable, Stream};
 use erg_common::Str;
 
@@ -14,14 +15,16 @@ use erg_parser::token::TokenKind;
 use crate::artifact::{
     BuildRunnable, Buildable, CompleteArtifact, ErrorArtifact, IncompleteArtifact,
 };
+use crate::backend::Backend;
 use crate::build_hir::HIRBuilder;
-use crate::codegen::PyCodeGenerator;
+use crate::codegen::{range_index_as_slice, PyCodeGenerator};
 use crate::context::{Context, ContextProvider, ModuleContext};
 use crate::desugar_hir::HIRDesugarer;
 use crate::error::{CompileError, CompileErrors};
 use crate::hir::{
-    Accessor, Args, Array, BinOp, Block, Call, ClassDef, Def, Dict, Expr, Identifier, Lambda,
-    Literal, Params, PatchDef, ReDef, Record, Set, Signature, Tuple, UnaryOp, HIR,
+    Accessor, Args, Array, BinOp, Block, Call, ClassDef, Def, DefBody, Dict, Expr, Identifier,
+    Lambda, Literal, Params, PatchDef, ReDef, Record, Set, Signature, SubrSignature, Tuple,
+    UnaryOp, HIR,
 };
 use crate::link_hir::HIRLinker;
 use crate::module::SharedCompilerResource;
@@ -165,6 +168,22 @@ impl Runnable for Transpiler {
         artifact.warns.write_all_stderr();
         Ok(artifact.object.code)
     }
+
+    fn undo(&mut self) -> bool {
+        self.builder.undo()
+    }
+
+    fn accepted_inputs(&self) -> &[String] {
+        self.builder.accepted_inputs()
+    }
+
+    fn completion_words(&self) -> Vec<String> {
+        self.dir_names()
+    }
+
+    fn stats(&self) -> Option<String> {
+        self.builder.stats()
+    }
 }
 
 impl ContextProvider for Transpiler {
@@ -217,7 +236,10 @@ impl Transpiler {
     ) -> Result<CompleteArtifact<PyScript>, ErrorArtifact> {
         log!(info "the transpiling process has started.");
         let artifact = self.build_link_desugar(src, mode)?;
-        let script = self.script_generator.transpile(artifact.object);
+        let module = self.cfg.input.module_name();
+        let script = timings::measure(&module, "codegen", || {
+            self.script_generator.emit_module(artifact.object)
+        });
         log!(info "code:\n{}", script.code);
         log!(info "the transpiling process has completed");
         Ok(CompleteArtifact::new(script, artifact.warns))
@@ -268,6 +290,7 @@ pub struct ScriptGenerator {
     builtin_types_loaded: bool,
     builtin_control_loaded: bool,
     convertors_loaded: bool,
+    argv_loaded: bool,
     prelude: String,
 }
 
@@ -283,6 +306,7 @@ impl ScriptGenerator {
             builtin_types_loaded: false,
             builtin_control_loaded: false,
             convertors_loaded: false,
+            argv_loaded: false,
             prelude: String::new(),
         }
     }
@@ -394,6 +418,13 @@ impl ScriptGenerator {
         }
     }
 
+    fn load_argv_if_not(&mut self) {
+        if !self.argv_loaded {
+            self.prelude += "from sys import argv\n";
+            self.argv_loaded = true;
+        }
+    }
+
     fn escape_str(s: &str) -> String {
         s.replace('\n', "\\n")
             .replace('\r', "\\r")
@@ -589,6 +620,9 @@ impl ScriptGenerator {
                     "int" | "nat" | "float" | "str" => {
                         self.load_convertors_if_not();
                     }
+                    "argv" => {
+                        self.load_argv_if_not();
+                    }
                     _ => {}
                 }
                 Self::transpile_ident(ident)
@@ -616,6 +650,8 @@ impl ScriptGenerator {
                 }
                 code
             }
+            // already checked in lowering
+            Some("assert_static") => "None".to_string(),
             Some("not") => format!("(not ({}))", self.transpile_expr(call.args.remove(0))),
             Some("if" | "if!") => self.transpile_if(call),
             Some("for" | "for!") => {
@@ -642,6 +678,14 @@ impl ScriptGenerator {
                 code
             }
             Some("match" | "match!") => self.transpile_match(call),
+            // the code is spliced verbatim (checked to be a `Str` literal in lowering)
+            Some("py_inline") => match call.args.remove(0) {
+                Expr::Lit(Literal {
+                    value: ValueObj::Str(code),
+                    ..
+                }) => format!("({code})"),
+                other => format!("eval({})", self.transpile_expr(other)),
+            },
             _ => self.transpile_simple_call(call),
         }
     }
@@ -747,10 +791,13 @@ impl ScriptGenerator {
         format!("{tmp_func}()")
     }
 
-    fn transpile_simple_call(&mut self, call: Call) -> String {
+    fn transpile_simple_call(&mut self, mut call: Call) -> String {
         let is_py_api = if let Some(attr) = &call.attr_name {
             let is_py_api = attr.is_py_api();
             if let Some(name) = debind(attr) {
+                if &name[..] == "dict_as_record__" {
+                    self.load_convertors_if_not();
+                }
                 let name = demangle(&name);
                 return format!(
                     "{name}({}, {})",
@@ -758,6 +805,7 @@ impl ScriptGenerator {
                     self.transpile_args(call.args, is_py_api, false)
                 );
             }
+            range_index_as_slice(attr, &mut call.args);
             is_py_api
         } else {
             call.obj.is_py_api()
@@ -922,6 +970,8 @@ impl ScriptGenerator {
     }
 
     fn transpile_classdef(&mut self, classdef: ClassDef) -> String {
+        let non_instance_methods = classdef.non_instance_methods();
+        let properties = classdef.properties();
         let class_name = Self::transpile_ident(classdef.sig.into_ident());
         let mut code = format!("class {class_name}():\n");
         let mut init_method = format!(
@@ -948,6 +998,26 @@ impl ScriptGenerator {
             code += &format!("def new(x): return {class_name}.__call__(x)\n");
         }
         code += &self.transpile_block(classdef.methods, Discard);
+        for (kind, method) in non_instance_methods {
+            let wrapper = if kind.is_class() {
+                "classmethod"
+            } else {
+                "staticmethod"
+            };
+            let method = Self::transpile_ident(method);
+            code += &"    ".repeat(self.level + 1);
+            code += &format!("{method} = {wrapper}({method})\n");
+        }
+        for (getter, setter) in properties {
+            let getter = Self::transpile_ident(getter);
+            code += &"    ".repeat(self.level + 1);
+            if let Some(setter) = setter {
+                let setter = Self::transpile_ident(setter);
+                code += &format!("{getter} = property({getter}, {setter})\n");
+            } else {
+                code += &format!("{getter} = property({getter})\n");
+            }
+        }
         code
     }
 
@@ -984,3 +1054,36 @@ impl ScriptGenerator {
         }
     }
 }
+
+impl Backend for ScriptGenerator {
+    type Output = PyScript;
+    type Fragment = String;
+
+    fn emit_module(&mut self, hir: HIR) -> PyScript {
+        self.transpile(hir)
+    }
+
+    fn emit_expr(&mut self, expr: Expr) -> String {
+        self.transpile_expr(expr)
+    }
+
+    fn emit_function(&mut self, sig: SubrSignature, body: DefBody) -> String {
+        self.transpile_def(Def::new(Signature::Subr(sig), body))
+    }
+
+    fn emit_class(&mut self, class_def: ClassDef) -> String {
+        self.transpile_classdef(class_def)
+    }
+
+    fn emit_literal(&mut self, lit: Literal) -> String {
+        self.transpile_lit(lit)
+    }
+
+    fn emit_call(&mut self, call: Call) -> String {
+        self.transpile_call(call)
+    }
+
+    fn clear(&mut self) {
+        *self = Self::new();
+    }
+}
@@ -30,6 +30,18 @@ impl UserConstSubr {
             sig_t,
         }
     }
+
+    pub fn name(&self) -> &Str {
+        &self.name
+    }
+
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    pub fn block(&self) -> &ConstBlock {
+        &self.block
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
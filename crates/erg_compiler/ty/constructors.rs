@@ -220,6 +220,11 @@ pub fn func0(return_t: Type) -> Type {
     func(vec![], None, vec![], return_t)
 }
 
+/// `(*args: Obj) -> Dynamic`
+pub fn dynamic_func() -> Type {
+    func(vec![], Some(ParamTy::Pos(Type::Obj)), vec![], mono("Dynamic"))
+}
+
 pub fn func1(param_t: Type, return_t: Type) -> Type {
     func(vec![ParamTy::Pos(param_t)], None, vec![], return_t)
 }
@@ -197,6 +197,16 @@ impl Deserializer {
                 }
                 Ok(self.get_cached_arr(&arr))
             }
+            DataTypePrefix::Dict => {
+                let mut dict = Dict::new();
+                while DataTypePrefix::from(v[0]) != DataTypePrefix::Null {
+                    let key = self.deserialize_const(v, python_ver)?;
+                    let value = self.deserialize_const(v, python_ver)?;
+                    dict.insert(key, value);
+                }
+                v.remove(0);
+                Ok(ValueObj::Dict(dict))
+            }
             DataTypePrefix::Code => {
                 let argcount = Self::deserialize_u32(v);
                 let posonlyargcount = if python_ver.minor >= Some(8) {
@@ -254,6 +264,7 @@ impl Deserializer {
                 }))
             }
             DataTypePrefix::None => Ok(ValueObj::None),
+            DataTypePrefix::Ellipsis => Ok(ValueObj::Ellipsis),
             other => Err(DeserializeError::new(
                 0,
                 fn_name!(),
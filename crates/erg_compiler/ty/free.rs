@@ -156,6 +156,17 @@ impl Constraint {
         }
     }
 
+    /// e.g. `T: Type`, `T <: Int`
+    pub fn to_string_with_name(&self, name: &str) -> String {
+        struct Named<'a>(&'a Constraint, &'a str);
+        impl fmt::Display for Named<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.named_fmt(f, self.1, 10)
+            }
+        }
+        Named(self, name).to_string()
+    }
+
     pub fn new_type_of(t: Type) -> Self {
         if t == Type::Type {
             Self::new_sandwiched(Type::Never, Type::Obj)
@@ -382,6 +382,19 @@ impl SubrType {
             || self.return_t.contains_tvar(target)
     }
 
+    pub fn occurs(&self, target: &FreeTyVar) -> bool {
+        self.non_default_params
+            .iter()
+            .any(|pt| pt.typ().occurs(target))
+            || self
+                .var_params
+                .as_ref()
+                .map(|pt| pt.typ().occurs(target))
+                .unwrap_or(false)
+            || self.default_params.iter().any(|pt| pt.typ().occurs(target))
+            || self.return_t.occurs(target)
+    }
+
     pub fn contains_type(&self, target: &Type) -> bool {
         self.non_default_params
             .iter()
@@ -1885,6 +1898,15 @@ impl Type {
         }
     }
 
+    /// `Dynamic` is the type of values from untyped Python code, and compatible with any type.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Self::FreeVar(fv) if fv.is_linked() => fv.crack().is_dynamic(),
+            Self::Mono(name) => &name[..] == "Dynamic",
+            _ => false,
+        }
+    }
+
     pub fn is_failure(&self) -> bool {
         match self {
             Self::FreeVar(fv) if fv.is_linked() => fv.crack().is_failure(),
@@ -1903,6 +1925,15 @@ impl Type {
         }
     }
 
+    pub fn is_trait_type(&self) -> bool {
+        match self {
+            Self::FreeVar(fv) if fv.is_linked() => fv.crack().is_trait_type(),
+            Self::Refinement(refine) => refine.t.is_trait_type(),
+            Self::TraitType => true,
+            _ => false,
+        }
+    }
+
     pub fn as_free(&self) -> Option<&FreeTyVar> {
         <&FreeTyVar>::try_from(self).ok()
     }
@@ -1948,6 +1979,33 @@ impl Type {
         }
     }
 
+    /// Whether `target` appears in `self`.
+    /// Unlike `contains_tvar`, the constraints of the free variables are not inspected
+    /// (a recursive constraint such as `?T(<: Add(?T))` is allowed, but `?T == Array(?T)` is an infinite type).
+    pub fn occurs(&self, target: &FreeTyVar) -> bool {
+        match self {
+            Self::FreeVar(fv) if fv.addr_eq(target) => true,
+            Self::FreeVar(fv) if fv.is_linked() => fv.crack().occurs(target),
+            Self::Record(rec) => rec.iter().any(|(_, t)| t.occurs(target)),
+            Self::Poly { params, .. } => params.iter().any(|tp| tp.occurs(target)),
+            Self::Quantified(t) => t.occurs(target),
+            Self::Subr(subr) => subr.occurs(target),
+            Self::Refinement(refine) => refine.t.occurs(target),
+            Self::Structural(ty) => ty.occurs(target),
+            Self::Proj { lhs, .. } => lhs.occurs(target),
+            Self::ProjCall { lhs, args, .. } => {
+                lhs.occurs(target) || args.iter().any(|tp| tp.occurs(target))
+            }
+            Self::And(lhs, rhs) | Self::Or(lhs, rhs) => lhs.occurs(target) || rhs.occurs(target),
+            Self::Not(t) | Self::Ref(t) => t.occurs(target),
+            Self::RefMut { before, after } => {
+                before.occurs(target) || after.as_ref().is_some_and(|t| t.occurs(target))
+            }
+            Self::Bounded { sub, sup } => sub.occurs(target) || sup.occurs(target),
+            _ => false,
+        }
+    }
+
     pub fn contains_type(&self, target: &Type) -> bool {
         if self == target {
             // This operation can also be performed for recursive types
@@ -2729,6 +2787,24 @@ impl Type {
         }
     }
 
+    /// `(x: Int, y: Int) -> Int` => `(y: Int) -> Int`
+    pub fn without_first_param(&self) -> Option<Type> {
+        match self {
+            Self::FreeVar(fv) if fv.is_linked() => fv
+                .forced_as_ref()
+                .linked()
+                .and_then(|t| t.without_first_param()),
+            Self::Refinement(refine) => refine.t.without_first_param(),
+            Self::Subr(subr) if !subr.non_default_params.is_empty() => {
+                let mut subr = subr.clone();
+                subr.non_default_params.remove(0);
+                Some(Self::Subr(subr))
+            }
+            Self::Quantified(quant) => quant.without_first_param().map(|t| t.quantify()),
+            _ => None,
+        }
+    }
+
     pub fn var_params(&self) -> Option<&ParamTy> {
         match self {
             Self::FreeVar(fv) if fv.is_linked() => {
@@ -2804,6 +2880,16 @@ impl Type {
         }
     }
 
+    /// `x in T`, `x < 3 and y == 1`, etc.
+    pub fn is_guard_like(&self) -> bool {
+        match self {
+            Self::FreeVar(fv) if fv.is_linked() => fv.crack().is_guard_like(),
+            Self::Guard(_) => true,
+            Self::And(l, r) => l.is_guard_like() && r.is_guard_like(),
+            _ => false,
+        }
+    }
+
     pub fn mut_return_t(&mut self) -> Option<&mut Type> {
         match self {
             Self::Refinement(refine) => refine.t.mut_return_t(),
@@ -3092,7 +3178,7 @@ impl Type {
         }
     }
 
-    fn addr_eq(&self, other: &Type) -> bool {
+    pub(crate) fn addr_eq(&self, other: &Type) -> bool {
         match (self, other) {
             (Self::FreeVar(slf), _) if slf.is_linked() => slf.crack().addr_eq(other),
             (_, Self::FreeVar(otr)) if otr.is_linked() => otr.crack().addr_eq(self),
@@ -3101,11 +3187,16 @@ impl Type {
         }
     }
 
+    /// Linking `?T` to a type containing `?T` is refused, since it makes an infinite type.
+    /// Use `Context::link_checked` to report it.
     pub(crate) fn link(&self, to: &Type) {
         if self.addr_eq(to) {
             return;
         }
         match self {
+            Self::FreeVar(fv) if to.occurs(fv) => {
+                log!(err "cannot link {self} to {to} (infinite type)");
+            }
             Self::FreeVar(fv) => fv.link(to),
             Self::Refinement(refine) => refine.t.link(to),
             _ => panic!("{self} is not a free variable"),
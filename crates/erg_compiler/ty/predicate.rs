@@ -404,35 +404,68 @@ impl Predicate {
         }
     }
 
+    /// The lower and upper bounds of the variable constrained by `self`, tightened by `min` and `max`.
+    /// `None` means unbounded.
+    ///
+    /// e.g. `{I | I >= 0 and I <= 10}` => `(Some(0), Some(10))`, `{1, 3}` => `(Some(1), Some(3))`
     pub fn min_max<'a>(
         &'a self,
         min: Option<&'a TyParam>,
         max: Option<&'a TyParam>,
     ) -> (Option<&'a TyParam>, Option<&'a TyParam>) {
         match self {
-            Predicate::Equal { rhs: _, .. } => todo!(),
-            // {I | I <= 1; I <= 2}
-            Predicate::LessEqual { rhs, .. } => (
-                min,
-                max.map(|l: &TyParam| match l.cheap_cmp(rhs) {
-                    Some(c) if c.is_ge() => l,
-                    Some(_) => rhs,
-                    _ => l,
-                })
-                .or(Some(rhs)),
+            // {I | I == 1}
+            Predicate::Equal { rhs, .. } => (
+                Self::tighter(min, Some(rhs), true),
+                Self::tighter(max, Some(rhs), false),
             ),
-            // {I | I >= 1; I >= 2}
-            Predicate::GreaterEqual { rhs, .. } => (
-                min.map(|l: &TyParam| match l.cheap_cmp(rhs) {
-                    Some(c) if c.is_le() => l,
-                    Some(_) => rhs,
-                    _ => l,
-                })
-                .or(Some(rhs)),
-                max,
-            ),
-            Predicate::And(_l, _r) => todo!(),
-            _ => todo!(),
+            // {I | I <= 1 and I <= 2}
+            Predicate::LessEqual { rhs, .. } => (min, Self::tighter(max, Some(rhs), false)),
+            // {I | I >= 1 and I >= 2}
+            Predicate::GreaterEqual { rhs, .. } => (Self::tighter(min, Some(rhs), true), max),
+            Predicate::And(l, r) => {
+                let (min, max) = l.min_max(min, max);
+                r.min_max(min, max)
+            }
+            // {I | I == 1 or I == 3}
+            Predicate::Or(l, r) => {
+                let (l_min, l_max) = l.min_max(None, None);
+                let (r_min, r_max) = r.min_max(None, None);
+                (
+                    Self::tighter(min, Self::looser(l_min, r_min, true), true),
+                    Self::tighter(max, Self::looser(l_max, r_max, false), false),
+                )
+            }
+            _ => (min, max),
+        }
+    }
+
+    /// The stricter of the two bounds (`lower`: whether they are lower bounds)
+    fn tighter<'a>(
+        l: Option<&'a TyParam>,
+        r: Option<&'a TyParam>,
+        lower: bool,
+    ) -> Option<&'a TyParam> {
+        match (l, r) {
+            (Some(l), Some(r)) => match l.cheap_cmp(r) {
+                Some(ord) if ord.is_ge() == lower => Some(l),
+                Some(_) => Some(r),
+                None => Some(l),
+            },
+            (l, r) => l.or(r),
+        }
+    }
+
+    /// The weaker of the two bounds (`lower`: whether they are lower bounds)
+    fn looser<'a>(
+        l: Option<&'a TyParam>,
+        r: Option<&'a TyParam>,
+        lower: bool,
+    ) -> Option<&'a TyParam> {
+        let (l, r) = (l?, r?);
+        match l.cheap_cmp(r)? {
+            ord if ord.is_le() == lower => Some(l),
+            _ => Some(r),
         }
     }
 
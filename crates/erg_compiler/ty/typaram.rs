@@ -885,6 +885,26 @@ impl TyParam {
         Self::app("pred".into(), vec![self])
     }
 
+    /// `pred(N)` => `N - 1`, `succ(N)` => `N + 1`
+    pub fn unfold_succ_pred(&self) -> Option<Self> {
+        match self {
+            Self::App { name, args } if args.len() == 1 => {
+                let op = match &name[..] {
+                    "pred" => OpKind::Sub,
+                    "succ" => OpKind::Add,
+                    _ => return None,
+                };
+                Some(Self::bin(op, args[0].clone(), Self::value(1usize)))
+            }
+            _ => None,
+        }
+    }
+
+    /// e.g. the length of `zip(xs: [T; N], ys: [U; M])` is `min(N, M)`
+    pub fn min(self, other: Self) -> Self {
+        Self::app("min".into(), vec![self, other])
+    }
+
     pub fn qual_name(&self) -> Option<Str> {
         match self {
             Self::Type(t) => Some(t.qual_name()),
@@ -1021,6 +1041,41 @@ impl TyParam {
         }
     }
 
+    pub fn occurs(&self, target: &FreeTyVar) -> bool {
+        match self {
+            Self::FreeVar(fv) if fv.is_linked() => fv.crack().occurs(target),
+            Self::Type(t) | Self::Erased(t) => t.occurs(target),
+            Self::Proj { obj, .. } => obj.occurs(target),
+            Self::Array(ts) | Self::Tuple(ts) => ts.iter().any(|t| t.occurs(target)),
+            Self::Set(ts) => ts.iter().any(|t| t.occurs(target)),
+            Self::Dict(ts) => ts.iter().any(|(k, v)| k.occurs(target) || v.occurs(target)),
+            Self::Record(rec) => rec.iter().any(|(_, tp)| tp.occurs(target)),
+            Self::Lambda(lambda) => lambda.body.iter().any(|tp| tp.occurs(target)),
+            Self::UnaryOp { val, .. } => val.occurs(target),
+            Self::BinOp { lhs, rhs, .. } => lhs.occurs(target) || rhs.occurs(target),
+            Self::App { args, .. } => args.iter().any(|p| p.occurs(target)),
+            Self::Value(ValueObj::Type(t)) => t.typ().occurs(target),
+            _ => false,
+        }
+    }
+
+    /// Whether `target` occurs strictly inside `self` (e.g. `?N` in `?N + 1`).
+    /// Unlike `contains_tp`, free variables are compared by address, not by name.
+    pub(crate) fn occurs_tp(&self, target: &TyParam) -> bool {
+        let occurs = |tp: &TyParam| tp.addr_eq(target) || tp.occurs_tp(target);
+        match self {
+            Self::FreeVar(fv) if fv.is_linked() => fv.crack().occurs_tp(target),
+            Self::Array(ts) | Self::Tuple(ts) => ts.iter().any(occurs),
+            Self::Set(ts) => ts.iter().any(occurs),
+            Self::Dict(ts) => ts.iter().any(|(k, v)| occurs(k) || occurs(v)),
+            Self::Record(rec) => rec.iter().any(|(_, tp)| occurs(tp)),
+            Self::UnaryOp { val, .. } => occurs(val),
+            Self::BinOp { lhs, rhs, .. } => occurs(lhs) || occurs(rhs),
+            Self::App { args, .. } => args.iter().any(occurs),
+            _ => false,
+        }
+    }
+
     pub fn contains_type(&self, target: &Type) -> bool {
         match self {
             Self::FreeVar(fv) if fv.is_linked() => fv.crack().contains_type(target),
@@ -21,11 +21,12 @@ use erg_parser::ast::{ConstArgs, ConstExpr};
 
 use crate::context::eval::type_from_token_kind;
 use crate::context::Context;
+use crate::derive::DerivedTrait;
 
 use self::value_set::inner_class;
 
 use super::codeobj::CodeObj;
-use super::constructors::{array_t, dict_t, refinement, set_t, tuple_t};
+use super::constructors::{array_t, dict_t, mono, refinement, set_t, tuple_t};
 use super::typaram::TyParam;
 use super::{ConstSubr, Field, HasType, Predicate, Type};
 use super::{CONTAINER_OMIT_THRESHOLD, STR_OMIT_THRESHOLD};
@@ -63,6 +64,8 @@ pub struct ClassTypeObj {
     pub t: Type,
     pub base: Option<Box<TypeObj>>,
     pub impls: Option<Box<TypeObj>>,
+    /// trait implementations synthesized by `Derive`
+    pub derives: Vec<DerivedTrait>,
 }
 
 impl ClassTypeObj {
@@ -71,6 +74,7 @@ impl ClassTypeObj {
             t,
             base: base.map(Box::new),
             impls: impls.map(Box::new),
+            derives: vec![],
         }
     }
 }
@@ -81,6 +85,8 @@ pub struct InheritedTypeObj {
     pub sup: Box<TypeObj>,
     pub impls: Option<Box<TypeObj>>,
     pub additional: Option<Box<TypeObj>>,
+    /// trait implementations synthesized by `Derive`
+    pub derives: Vec<DerivedTrait>,
 }
 
 impl InheritedTypeObj {
@@ -90,6 +96,7 @@ impl InheritedTypeObj {
             sup: Box::new(sup),
             impls: impls.map(Box::new),
             additional: additional.map(Box::new),
+            derives: vec![],
         }
     }
 }
@@ -99,6 +106,8 @@ pub struct TraitTypeObj {
     pub t: Type,
     pub requires: Box<TypeObj>,
     pub impls: Option<Box<TypeObj>>,
+    /// module that implements the derived methods (see `Derivable`)
+    pub derive: Option<Str>,
 }
 
 impl TraitTypeObj {
@@ -107,6 +116,7 @@ impl TraitTypeObj {
             t,
             requires: Box::new(requires),
             impls: impls.map(Box::new),
+            derive: None,
         }
     }
 }
@@ -297,6 +307,36 @@ impl GenTypeObj {
         }
     }
 
+    /// decorated with `@Abstract`
+    pub fn is_abstract(&self) -> bool {
+        self.impls()
+            .is_some_and(|impls| impls.contains_intersec(&mono("AbstractType")))
+    }
+
+    pub fn derives(&self) -> &[DerivedTrait] {
+        match self {
+            Self::Class(class) => &class.derives,
+            Self::Subclass(subclass) => &subclass.derives,
+            _ => &[],
+        }
+    }
+
+    pub fn derives_mut(&mut self) -> Option<&mut Vec<DerivedTrait>> {
+        match self {
+            Self::Class(class) => Some(&mut class.derives),
+            Self::Subclass(subclass) => Some(&mut subclass.derives),
+            _ => None,
+        }
+    }
+
+    /// module that implements the derived methods of the trait
+    pub fn derive_module(&self) -> Option<&Str> {
+        match self {
+            Self::Trait(trait_) => trait_.derive.as_ref(),
+            _ => None,
+        }
+    }
+
     pub fn additional(&self) -> Option<&TypeObj> {
         match self {
             Self::Subclass(subclass) => subclass.additional.as_ref().map(|x| x.as_ref()),
@@ -996,8 +1036,19 @@ impl ValueObj {
             Self::None => {
                 vec![DataTypePrefix::None as u8]
             }
+            Self::Ellipsis => {
+                vec![DataTypePrefix::Ellipsis as u8]
+            }
             Self::Code(c) => c.into_bytes(python_ver),
-            // Dict
+            Self::Dict(dict) => {
+                let mut bytes = vec![DataTypePrefix::Dict as u8];
+                for (key, value) in dict.into_iter() {
+                    bytes.append(&mut key.into_bytes(python_ver));
+                    bytes.append(&mut value.into_bytes(python_ver));
+                }
+                bytes.push(DataTypePrefix::Null as u8);
+                bytes
+            }
             other => {
                 panic!(
                     "{}",
@@ -1276,6 +1327,18 @@ impl ValueObj {
             (Self::Str(l), Self::Str(r)) => Some(Self::from(l == r)),
             (Self::Bool(l), Self::Bool(r)) => Some(Self::from(l == r)),
             (Self::Type(l), Self::Type(r)) => Some(Self::from(l == r)),
+            (Self::None, Self::None) => Some(Self::from(true)),
+            (Self::Array(l), Self::Array(r)) | (Self::Tuple(l), Self::Tuple(r)) => {
+                if l.len() != r.len() {
+                    return Some(Self::from(false));
+                }
+                for (l, r) in l.iter().zip(r.iter()) {
+                    if !matches!(l.clone().try_eq(r.clone())?, Self::Bool(true)) {
+                        return Some(Self::from(false));
+                    }
+                }
+                Some(Self::from(true))
+            }
             // TODO:
             _ => None,
         }
@@ -1295,6 +1358,10 @@ impl ValueObj {
             (Self::Str(l), Self::Str(r)) => Some(Self::from(l != r)),
             (Self::Bool(l), Self::Bool(r)) => Some(Self::from(l != r)),
             (Self::Type(l), Self::Type(r)) => Some(Self::from(l != r)),
+            (l @ (Self::None | Self::Array(_) | Self::Tuple(_)), r) => match l.try_eq(r)? {
+                Self::Bool(eq) => Some(Self::from(!eq)),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -1325,6 +1392,130 @@ impl ValueObj {
                 let v = rec.get(attr)?;
                 Some(v.clone())
             }
+            Self::DataClass { fields, .. } => {
+                let v = fields.get(attr)?;
+                Some(v.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// The result of `str(self)`, if it can be computed at compile time
+    pub fn try_str(&self) -> Option<Str> {
+        match self {
+            Self::Str(s) => Some(s.clone()),
+            Self::Int(i) => Some(Str::from(i.to_string())),
+            Self::Nat(n) => Some(Str::from(n.to_string())),
+            Self::Float(f) if f.fract() == 0.0 && f.is_finite() => {
+                Some(Str::from(format!("{f:.1}")))
+            }
+            Self::Float(f) => Some(Str::from(f.to_string())),
+            Self::Bool(true) => Some(Str::ever("True")),
+            Self::Bool(false) => Some(Str::ever("False")),
+            Self::None => Some(Str::ever("None")),
+            _ => None,
+        }
+    }
+
+    /// Converts `index` (negative indices count from the end) into an index of a sequence of length `len`.
+    fn normalize_index(index: &Self, len: usize) -> Option<usize> {
+        let index = match index {
+            Self::Nat(n) => *n as i64,
+            Self::Int(i) => *i as i64,
+            Self::Bool(b) => *b as i64,
+            _ => return None,
+        };
+        let index = if index < 0 { index + len as i64 } else { index };
+        (0..len as i64).contains(&index).then_some(index as usize)
+    }
+
+    /// Calls the builtin method `name` of `self` at compile time (only positional arguments are supported).
+    /// Returns `None` if the method is not supported or the arguments are invalid.
+    ///
+    /// e.g. `"abc".upper() == "ABC"`, `{"a": 1}.get("b", 0) == 0`
+    pub fn try_call_method(&self, name: &str, args: &[Self]) -> Option<Self> {
+        match (self, name, args) {
+            (Self::Str(s), "upper", []) => Some(Self::Str(Str::from(s.to_uppercase()))),
+            (Self::Str(s), "lower", []) => Some(Self::Str(Str::from(s.to_lowercase()))),
+            (Self::Str(s), "strip", []) => Some(Self::Str(Str::rc(s.trim()))),
+            (Self::Str(s), "lstrip", []) => Some(Self::Str(Str::rc(s.trim_start()))),
+            (Self::Str(s), "rstrip", []) => Some(Self::Str(Str::rc(s.trim_end()))),
+            (Self::Str(s), "startswith", [Self::Str(prefix)]) => {
+                Some(Self::Bool(s.starts_with(&prefix[..])))
+            }
+            (Self::Str(s), "endswith", [Self::Str(suffix)]) => {
+                Some(Self::Bool(s.ends_with(&suffix[..])))
+            }
+            (Self::Str(s), "replace", [Self::Str(old), Self::Str(new)]) => {
+                Some(Self::Str(Str::from(s.replace(&old[..], new))))
+            }
+            (Self::Str(s), "split", [Self::Str(sep)]) if !sep.is_empty() => Some(Self::Array(
+                s.split(&sep[..]).map(|s| Self::Str(Str::rc(s))).collect(),
+            )),
+            (Self::Str(s), "find", [Self::Str(sub)]) => {
+                let idx = s.find(&sub[..]).map(|i| s[..i].chars().count() as i32);
+                Some(Self::Int(idx.unwrap_or(-1)))
+            }
+            (Self::Str(s), "count", [Self::Str(sub)]) if !sub.is_empty() => {
+                Some(Self::Nat(s.matches(&sub[..]).count() as u64))
+            }
+            (Self::Str(sep), "join", [Self::Array(arr)]) => {
+                let strs = arr
+                    .iter()
+                    .map(|elem| match elem {
+                        Self::Str(s) => Some(&s[..]),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some(Self::Str(Str::from(strs.join(sep))))
+            }
+            // only positional `{}` fields are supported
+            (Self::Str(s), "format", args) => {
+                let mut pieces = s.split("{}");
+                let mut formatted = pieces.next()?.to_string();
+                let mut args = args.iter();
+                for piece in pieces {
+                    formatted.push_str(&args.next()?.try_str()?);
+                    formatted.push_str(piece);
+                }
+                if formatted.contains(['{', '}']) || args.next().is_some() {
+                    return None;
+                }
+                Some(Self::Str(Str::from(formatted)))
+            }
+            (Self::Str(s), "__getitem__", [index]) => {
+                let idx = Self::normalize_index(index, s.chars().count())?;
+                Some(Self::Str(Str::from(s.chars().nth(idx)?.to_string())))
+            }
+            (Self::Array(arr), "__getitem__", [index]) => {
+                let idx = Self::normalize_index(index, arr.len())?;
+                arr.get(idx).cloned()
+            }
+            (Self::Tuple(tup), "__getitem__", [index]) => {
+                let idx = Self::normalize_index(index, tup.len())?;
+                tup.get(idx).cloned()
+            }
+            (Self::Dict(dict), "__getitem__", [key]) => dict.get(key).cloned(),
+            (Self::Dict(dict), "get", [key]) => Some(dict.get(key).cloned().unwrap_or(Self::None)),
+            (Self::Dict(dict), "get", [key, default]) => {
+                Some(dict.get(key).cloned().unwrap_or_else(|| default.clone()))
+            }
+            // `{Str: Int}.keys()` etc. are type-level operations (see `dict_keys`)
+            (Self::Dict(dict), "keys", []) if !dict.keys().any(|k| matches!(k, Self::Type(_))) => {
+                Some(Self::Array(dict.keys().cloned().collect()))
+            }
+            (Self::Dict(dict), "values", [])
+                if !dict.keys().any(|k| matches!(k, Self::Type(_))) =>
+            {
+                Some(Self::Array(dict.values().cloned().collect()))
+            }
+            (Self::Dict(dict), "items", []) if !dict.keys().any(|k| matches!(k, Self::Type(_))) => {
+                Some(Self::Array(
+                    dict.iter()
+                        .map(|(k, v)| Self::Tuple(vec![k.clone(), v.clone()].into()))
+                        .collect(),
+                ))
+            }
             _ => None,
         }
     }
@@ -1344,10 +1535,11 @@ impl ValueObj {
                 log!(err "as_type({})", erg_common::fmt_vec(elems));
                 None
             }
-            Self::Dict(elems) => {
-                log!(err "as_type({elems})");
-                None
-            }
+            // e.g. `{Str: Int}`, `{"name": Str, "age": Nat}`
+            Self::Dict(_) => ctx
+                .convert_value_into_type(self.clone())
+                .ok()
+                .map(TypeObj::builtin_type),
             _other => None,
         }
     }
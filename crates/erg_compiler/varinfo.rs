@@ -123,6 +123,31 @@ impl VarKind {
     }
 }
 
+/// How a method receives its receiver
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MethodKind {
+    /// `f(self, ...)`
+    Instance,
+    /// `f(...)`, compiled to `staticmethod`
+    Static,
+    /// `@ClassMethod f(cls, ...)`, compiled to `classmethod`
+    Class,
+}
+
+impl MethodKind {
+    pub const fn is_instance(&self) -> bool {
+        matches!(self, Self::Instance)
+    }
+
+    pub const fn is_static(&self) -> bool {
+        matches!(self, Self::Static)
+    }
+
+    pub const fn is_class(&self) -> bool {
+        matches!(self, Self::Class)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AbsLocation {
     pub module: Option<PathBuf>,
@@ -198,6 +223,10 @@ pub struct VarInfo {
     pub impl_of: Option<Type>,
     pub py_name: Option<Str>,
     pub def_loc: AbsLocation,
+    /// `T` of `x: Exact T`: the type of `x` is not widened beyond `T` by mutation
+    pub exact_t: Option<Type>,
+    /// the doc comments (`'''...'''`) just before the definition, the nearest one first
+    pub docs: Vec<Str>,
 }
 
 impl fmt::Display for VarInfo {
@@ -284,6 +313,8 @@ impl VarInfo {
             impl_of,
             py_name,
             def_loc,
+            exact_t: None,
+            docs: vec![],
         }
     }
 
@@ -333,4 +364,70 @@ impl VarInfo {
     pub fn is_untyped_parameter(&self) -> bool {
         self.kind.is_parameter() && self.t.is_unbound_var()
     }
+
+    /// Note that the result is meaningful only if `self` is a method.
+    pub fn method_kind(&self) -> MethodKind {
+        if self
+            .comptime_decos
+            .as_ref()
+            .is_some_and(|decos| decos.contains("ClassMethod"))
+        {
+            MethodKind::Class
+        } else if self.t.self_t().is_some() {
+            MethodKind::Instance
+        } else {
+            MethodKind::Static
+        }
+    }
+
+    /// The first parameter (`cls`) of a class method is bound when accessed.
+    pub fn into_bound_class_method(self) -> Self {
+        if !self.method_kind().is_class() {
+            return self;
+        }
+        match self.t.without_first_param() {
+            Some(t) => Self { t, ..self },
+            None => self,
+        }
+    }
+
+    /// Decorated with `@Property` (the getter `x` or the setter `x!`).
+    pub fn is_property(&self) -> bool {
+        self.comptime_decos
+            .as_ref()
+            .is_some_and(|decos| decos.contains("Property"))
+    }
+
+    /// Decorated with `@Overload` (a set of definitions dispatched by the argument types).
+    pub fn is_overloaded(&self) -> bool {
+        self.comptime_decos
+            .as_ref()
+            .is_some_and(|decos| decos.contains("Overload"))
+    }
+
+    /// A test function: a subroutine named `test_*` or decorated with `@Test`, which can be called without arguments.
+    pub fn is_test(&self, name: &str) -> bool {
+        let marked = name.starts_with("test_")
+            || self
+                .comptime_decos
+                .as_ref()
+                .is_some_and(|decos| decos.contains("Test"));
+        marked
+            && matches!(self.kind, VarKind::Defined(_))
+            && self
+                .t
+                .non_default_params()
+                .is_some_and(|params| params.is_empty())
+    }
+
+    /// `@Property x(self): T` is accessed from the instances as a value of `T`.
+    pub fn into_property_value(self) -> Self {
+        if !self.is_property() || self.t.is_procedure() {
+            return self;
+        }
+        match self.t.return_t().cloned() {
+            Some(t) => Self { t, ..self },
+            None => self,
+        }
+    }
 }
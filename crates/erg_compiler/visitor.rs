@@ -0,0 +1,300 @@
+//! Implements generic traversals of `HIR`.
+//!
+//! This is the HIR version of `erg_parser::visitor`.
+//! `Visit` walks the HIR by reference, `Fold` rebuilds it by value.
+use std::mem;
+
+use erg_common::traits::Stream;
+
+use crate::hir::{
+    Accessor, Args, Array, Block, Def, Dict, Expr, Module, Params, Record, Set, Signature, Tuple,
+};
+
+/// Walks the HIR by reference.
+///
+/// The default implementations visit all the children (see `walk_*`).
+/// If a method is overridden, call the corresponding `walk_*` function to continue walking into the children.
+pub trait Visit {
+    fn visit_module(&mut self, module: &Module) {
+        for chunk in module.iter() {
+            self.visit_expr(chunk);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    /// Called for all definitions, including record attributes.
+    fn visit_def(&mut self, def: &Def) {
+        walk_def(self, def);
+    }
+}
+
+/// Rebuilds the HIR by value.
+///
+/// `fold_expr` folds all the children by default (see `fold_children`).
+/// Record attributes are folded as `Expr::Def`, so the folder must return an `Expr::Def` for them.
+///
+/// Closures of type `FnMut(Expr) -> Expr` are also `Fold`s.
+pub trait Fold {
+    fn fold_module(&mut self, module: Module) -> Module {
+        module
+            .into_iter()
+            .map(|chunk| self.fold_expr(chunk))
+            .collect()
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_children(self, expr)
+    }
+}
+
+impl<F: FnMut(Expr) -> Expr> Fold for F {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        self(expr)
+    }
+}
+
+pub fn walk_args<V: Visit + ?Sized>(visitor: &mut V, args: &Args) {
+    for arg in args.pos_args.iter() {
+        visitor.visit_expr(&arg.expr);
+    }
+    if let Some(var_args) = &args.var_args {
+        visitor.visit_expr(&var_args.expr);
+    }
+    for arg in args.kw_args.iter() {
+        visitor.visit_expr(&arg.expr);
+    }
+}
+
+pub fn walk_accessor<V: Visit + ?Sized>(visitor: &mut V, acc: &Accessor) {
+    match acc {
+        Accessor::Ident(_) => {}
+        Accessor::Attr(attr) => visitor.visit_expr(&attr.obj),
+    }
+}
+
+fn walk_params<V: Visit + ?Sized>(visitor: &mut V, params: &Params) {
+    for default in params.defaults.iter() {
+        visitor.visit_expr(&default.default_val);
+    }
+}
+
+pub fn walk_block<V: Visit + ?Sized>(visitor: &mut V, block: &Block) {
+    for chunk in block.iter() {
+        visitor.visit_expr(chunk);
+    }
+}
+
+pub fn walk_def<V: Visit + ?Sized>(visitor: &mut V, def: &Def) {
+    if let Signature::Subr(subr) = &def.sig {
+        walk_params(visitor, &subr.params);
+    }
+    walk_block(visitor, &def.body.block);
+}
+
+pub fn walk_record<V: Visit + ?Sized>(visitor: &mut V, record: &Record) {
+    for attr in record.attrs.iter() {
+        visitor.visit_def(attr);
+    }
+}
+
+/// Visits the direct children of `expr`.
+pub fn walk_expr<V: Visit + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Lit(_) => {}
+        Expr::Accessor(acc) | Expr::Import(acc) => walk_accessor(visitor, acc),
+        Expr::Array(Array::Normal(arr)) => walk_args(visitor, &arr.elems),
+        Expr::Array(Array::WithLength(arr)) => {
+            visitor.visit_expr(&arr.elem);
+            visitor.visit_expr(&arr.len);
+        }
+        Expr::Array(Array::Comprehension(arr)) => {
+            visitor.visit_expr(&arr.elem);
+            visitor.visit_expr(&arr.guard);
+        }
+        Expr::Tuple(Tuple::Normal(tup)) => walk_args(visitor, &tup.elems),
+        Expr::Set(Set::Normal(set)) => walk_args(visitor, &set.elems),
+        Expr::Set(Set::WithLength(set)) => {
+            visitor.visit_expr(&set.elem);
+            visitor.visit_expr(&set.len);
+        }
+        Expr::Dict(Dict::Normal(dict)) => {
+            for kv in dict.kvs.iter() {
+                visitor.visit_expr(&kv.key);
+                visitor.visit_expr(&kv.value);
+            }
+        }
+        Expr::Dict(Dict::Comprehension(dict)) => {
+            visitor.visit_expr(&dict.key);
+            visitor.visit_expr(&dict.value);
+            visitor.visit_expr(&dict.guard);
+        }
+        Expr::Record(record) => walk_record(visitor, record),
+        Expr::BinOp(bin) => {
+            visitor.visit_expr(&bin.lhs);
+            visitor.visit_expr(&bin.rhs);
+        }
+        Expr::UnaryOp(unary) => visitor.visit_expr(&unary.expr),
+        Expr::Call(call) => {
+            visitor.visit_expr(&call.obj);
+            walk_args(visitor, &call.args);
+        }
+        Expr::Lambda(lambda) => {
+            walk_params(visitor, &lambda.params);
+            walk_block(visitor, &lambda.body);
+        }
+        Expr::Def(def) => visitor.visit_def(def),
+        Expr::ClassDef(class_def) => {
+            if let Some(sup) = &class_def.require_or_sup {
+                visitor.visit_expr(sup);
+            }
+            walk_block(visitor, &class_def.methods);
+        }
+        Expr::PatchDef(patch_def) => {
+            visitor.visit_expr(&patch_def.base);
+            walk_block(visitor, &patch_def.methods);
+        }
+        Expr::ReDef(redef) => {
+            walk_accessor(visitor, &redef.attr);
+            walk_block(visitor, &redef.block);
+        }
+        Expr::TypeAsc(tasc) => {
+            visitor.visit_expr(&tasc.expr);
+            visitor.visit_expr(&tasc.spec.expr);
+        }
+        Expr::Code(block) | Expr::Compound(block) => walk_block(visitor, block),
+        Expr::Dummy(dummy) => {
+            for chunk in dummy.iter() {
+                visitor.visit_expr(chunk);
+            }
+        }
+    }
+}
+
+fn fold_in_place<F: Fold + ?Sized>(folder: &mut F, expr: &mut Expr) {
+    *expr = folder.fold_expr(mem::take(expr));
+}
+
+fn fold_args<F: Fold + ?Sized>(folder: &mut F, args: &mut Args) {
+    for arg in args.pos_args.iter_mut() {
+        fold_in_place(folder, &mut arg.expr);
+    }
+    if let Some(var_args) = &mut args.var_args {
+        fold_in_place(folder, &mut var_args.expr);
+    }
+    for arg in args.kw_args.iter_mut() {
+        fold_in_place(folder, &mut arg.expr);
+    }
+}
+
+fn fold_accessor<F: Fold + ?Sized>(folder: &mut F, acc: &mut Accessor) {
+    match acc {
+        Accessor::Ident(_) => {}
+        Accessor::Attr(attr) => fold_in_place(folder, &mut attr.obj),
+    }
+}
+
+fn fold_params<F: Fold + ?Sized>(folder: &mut F, params: &mut Params) {
+    for default in params.defaults.iter_mut() {
+        fold_in_place(folder, &mut default.default_val);
+    }
+}
+
+fn fold_block<F: Fold + ?Sized>(folder: &mut F, block: &mut Block) {
+    for chunk in block.iter_mut() {
+        fold_in_place(folder, chunk);
+    }
+}
+
+fn fold_def<F: Fold + ?Sized>(folder: &mut F, def: &mut Def) {
+    if let Signature::Subr(subr) = &mut def.sig {
+        fold_params(folder, &mut subr.params);
+    }
+    fold_block(folder, &mut def.body.block);
+}
+
+fn fold_record<F: Fold + ?Sized>(folder: &mut F, record: &mut Record) {
+    let attrs = mem::take(record.attrs.ref_mut_payload());
+    for attr in attrs {
+        let Expr::Def(attr) = folder.fold_expr(Expr::Def(attr)) else {
+            unreachable!()
+        };
+        record.attrs.push(attr);
+    }
+}
+
+/// Folds the direct children of `expr`.
+pub fn fold_children<F: Fold + ?Sized>(folder: &mut F, mut expr: Expr) -> Expr {
+    match &mut expr {
+        Expr::Lit(_) => {}
+        Expr::Accessor(acc) | Expr::Import(acc) => fold_accessor(folder, acc),
+        Expr::Array(Array::Normal(arr)) => fold_args(folder, &mut arr.elems),
+        Expr::Array(Array::WithLength(arr)) => {
+            fold_in_place(folder, &mut arr.elem);
+            fold_in_place(folder, &mut arr.len);
+        }
+        Expr::Array(Array::Comprehension(arr)) => {
+            fold_in_place(folder, &mut arr.elem);
+            fold_in_place(folder, &mut arr.guard);
+        }
+        Expr::Tuple(Tuple::Normal(tup)) => fold_args(folder, &mut tup.elems),
+        Expr::Set(Set::Normal(set)) => fold_args(folder, &mut set.elems),
+        Expr::Set(Set::WithLength(set)) => {
+            fold_in_place(folder, &mut set.elem);
+            fold_in_place(folder, &mut set.len);
+        }
+        Expr::Dict(Dict::Normal(dict)) => {
+            for kv in dict.kvs.iter_mut() {
+                fold_in_place(folder, &mut kv.key);
+                fold_in_place(folder, &mut kv.value);
+            }
+        }
+        Expr::Dict(Dict::Comprehension(dict)) => {
+            fold_in_place(folder, &mut dict.key);
+            fold_in_place(folder, &mut dict.value);
+            fold_in_place(folder, &mut dict.guard);
+        }
+        Expr::Record(record) => fold_record(folder, record),
+        Expr::BinOp(bin) => {
+            fold_in_place(folder, &mut bin.lhs);
+            fold_in_place(folder, &mut bin.rhs);
+        }
+        Expr::UnaryOp(unary) => fold_in_place(folder, &mut unary.expr),
+        Expr::Call(call) => {
+            fold_in_place(folder, &mut call.obj);
+            fold_args(folder, &mut call.args);
+        }
+        Expr::Lambda(lambda) => {
+            fold_params(folder, &mut lambda.params);
+            fold_block(folder, &mut lambda.body);
+        }
+        Expr::Def(def) => fold_def(folder, def),
+        Expr::ClassDef(class_def) => {
+            if let Some(sup) = &mut class_def.require_or_sup {
+                fold_in_place(folder, sup);
+            }
+            fold_block(folder, &mut class_def.methods);
+        }
+        Expr::PatchDef(patch_def) => {
+            fold_in_place(folder, &mut patch_def.base);
+            fold_block(folder, &mut patch_def.methods);
+        }
+        Expr::ReDef(redef) => {
+            fold_accessor(folder, &mut redef.attr);
+            fold_block(folder, &mut redef.block);
+        }
+        Expr::TypeAsc(tasc) => {
+            fold_in_place(folder, &mut tasc.expr);
+            fold_in_place(folder, &mut tasc.spec.expr);
+        }
+        Expr::Code(block) | Expr::Compound(block) => fold_block(folder, block),
+        Expr::Dummy(dummy) => {
+            for chunk in dummy.iter_mut() {
+                fold_in_place(folder, chunk);
+            }
+        }
+    }
+    expr
+}
@@ -17,6 +17,7 @@ use erg_common::{
 use erg_common::{fmt_vec_split_with, Str};
 
 use crate::token::{Token, TokenKind, EQUAL};
+use crate::trivia::TriviaMap;
 
 /// Some Erg functions require additional operation by the compiler.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -32,6 +33,9 @@ pub enum OperationKind {
     Return,
     Yield,
     Cast,
+    Export,
+    PyInline,
+    StaticAssert,
 }
 
 impl OperationKind {
@@ -162,6 +166,8 @@ pub struct Args {
     pos_args: Vec<PosArg>,
     pub(crate) var_args: Option<Box<PosArg>>,
     kw_args: Vec<KwArg>,
+    /// `**kwargs`
+    pub(crate) kw_var_args: Option<Box<PosArg>>,
     // these are for ELS
     pub paren: Option<(Token, Token)>,
 }
@@ -206,6 +212,7 @@ impl Args {
             pos_args,
             var_args: var_args.map(Box::new),
             kw_args,
+            kw_var_args: None,
             paren,
         }
     }
@@ -256,10 +263,22 @@ impl Args {
         &self.pos_args[..]
     }
 
+    pub fn var_args(&self) -> Option<&PosArg> {
+        self.var_args.as_deref()
+    }
+
     pub fn kw_args(&self) -> &[KwArg] {
         &self.kw_args[..]
     }
 
+    pub fn kw_var_args(&self) -> Option<&PosArg> {
+        self.kw_var_args.as_deref()
+    }
+
+    pub fn take_kw_var_args(&mut self) -> Option<PosArg> {
+        self.kw_var_args.take().map(|x| *x)
+    }
+
     pub fn has_pos_arg(&self, pa: &PosArg) -> bool {
         self.pos_args.contains(pa)
     }
@@ -296,6 +315,10 @@ impl Args {
         self.var_args = Some(Box::new(arg));
     }
 
+    pub fn set_kw_var_args(&mut self, arg: PosArg) {
+        self.kw_var_args = Some(Box::new(arg));
+    }
+
     pub fn push_kw(&mut self, arg: KwArg) {
         self.kw_args.push(arg);
     }
@@ -319,9 +342,7 @@ impl Args {
     }
 
     pub fn nth_or_key(&self, nth: usize, key: &str) -> Option<&Expr> {
-        if !self.pos_args.is_empty() {
-            self.pos_args.get(nth).map(|a| &a.expr)
-        } else {
+        self.pos_args.get(nth).map(|a| &a.expr).or_else(|| {
             self.kw_args.iter().find_map(|a| {
                 if &a.keyword.content[..] == key {
                     Some(&a.expr)
@@ -329,7 +350,7 @@ impl Args {
                     None
                 }
             })
-        }
+        })
     }
 }
 
@@ -777,10 +798,10 @@ impl NormalDict {
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct DictComprehension {
-    l_brace: Token,
-    r_brace: Token,
+    pub l_brace: Token,
+    pub r_brace: Token,
     pub attrs: Args,
-    guards: Vec<Expr>,
+    pub guards: Vec<Expr>,
 }
 
 // TODO:
@@ -1273,12 +1294,14 @@ impl Call {
     pub fn additional_operation(&self) -> Option<OperationKind> {
         self.obj.get_name().and_then(|s| match &s[..] {
             "import" => Some(OperationKind::Import),
-            "pyimport" | "py" | "__import__" => Some(OperationKind::PyImport),
+            "pyimport" | "py" | "__import__" | "cimport" => Some(OperationKind::PyImport),
             "Del" => Some(OperationKind::Del),
             "Class" => Some(OperationKind::Class),
             "Inherit" => Some(OperationKind::Inherit),
             "Trait" => Some(OperationKind::Trait),
             "Subsume" => Some(OperationKind::Subsume),
+            "export" => Some(OperationKind::Export),
+            "assert_static" => Some(OperationKind::StaticAssert),
             _ => None,
         })
     }
@@ -2756,6 +2779,15 @@ impl TypeSpec {
     pub fn poly(acc: ConstAccessor, args: ConstArgs) -> Self {
         Self::PreDeclTy(PreDeclTypeSpec::Poly(PolyTypeSpec::new(acc, args)))
     }
+
+    /// `Exact T`
+    pub fn is_exact(&self) -> bool {
+        matches!(
+            self,
+            Self::PreDeclTy(PreDeclTypeSpec::Poly(poly))
+                if poly.acc.to_string().trim_start_matches([':', '.']) == "Exact"
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -2980,7 +3012,7 @@ impl VarName {
     /// Procedures defined in `d.er` automatically register the name without `!` as `py_name`.
     /// This method is for undoing it (e.g. pylyzer-mode)
     pub fn trim_end_proc_mark(&mut self) {
-        self.0.content = Str::rc(self.0.content.trim_end_matches('!'));
+        self.0.content = Str::intern(self.0.content.trim_end_matches('!'));
     }
 }
 
@@ -4115,6 +4147,15 @@ impl Signature {
         }
     }
 
+    /// Whether the signature is decorated with `@Overload`
+    pub fn is_overloaded(&self) -> bool {
+        self.decorators().is_some_and(|decos| {
+            decos.iter().any(|deco| {
+                matches!(deco.expr(), Expr::Accessor(Accessor::Ident(ident)) if &ident.inspect()[..] == "Overload")
+            })
+        })
+    }
+
     pub fn t_spec(&self) -> Option<&TypeSpec> {
         match self {
             Self::Var(v) => v.t_spec.as_ref().map(|t| &t.t_spec),
@@ -4250,27 +4291,52 @@ impl DefBody {
 
     pub fn def_kind(&self) -> DefKind {
         match self.block.first().unwrap() {
-            Expr::Call(call) => match call.obj.get_name().map(|n| &n[..]) {
-                Some("Class") => DefKind::Class,
-                Some("Inherit") => DefKind::Inherit,
-                Some("Trait") => DefKind::Trait,
-                Some("Subsume") => DefKind::Subsume,
-                Some("Inheritable") => {
-                    if let Some(Expr::Call(inner)) = call.args.get_left_or_key("Class") {
-                        match inner.obj.get_name().map(|n| &n[..]) {
-                            Some("Class") => DefKind::Class,
-                            Some("Inherit") => DefKind::Inherit,
-                            _ => DefKind::Other,
-                        }
-                    } else {
-                        DefKind::Other
+            Expr::Call(call) => Self::call_def_kind(call),
+            _ => DefKind::Other,
+        }
+    }
+
+    /// `Enum {...}` (which is desugared into classes only at the top level)
+    pub fn is_enum(&self) -> bool {
+        matches!(
+            self.block.first(),
+            Some(Expr::Call(call)) if call.obj.get_name().is_some_and(|name| &name[..] == "Enum")
+        )
+    }
+
+    fn call_def_kind(call: &Call) -> DefKind {
+        match call.obj.get_name().map(|n| &n[..]) {
+            Some("Class") => DefKind::Class,
+            Some("Inherit") => DefKind::Inherit,
+            Some("Trait") => DefKind::Trait,
+            Some("Subsume") => DefKind::Subsume,
+            // decorators: `Inheritable(Derive(Class(...), [Eq]))`
+            Some("Inheritable" | "Abstract" | "Derive") => {
+                if let Some(Expr::Call(inner)) = call.args.get_left_or_key("Class") {
+                    match Self::call_def_kind(inner) {
+                        kind @ (DefKind::Class | DefKind::Inherit) => kind,
+                        _ => DefKind::Other,
+                    }
+                } else {
+                    DefKind::Other
+                }
+            }
+            // `Derivable(Trait(...), "module")`
+            Some("Derivable") => {
+                if let Some(Expr::Call(inner)) = call.args.get_left_or_key("Trait") {
+                    match Self::call_def_kind(inner) {
+                        DefKind::Trait => DefKind::Trait,
+                        _ => DefKind::Other,
                     }
+                } else {
+                    DefKind::Other
                 }
-                Some("Patch") => DefKind::Patch,
-                Some("import") => DefKind::ErgImport,
-                Some("pyimport") | Some("py") | Some("__import__") => DefKind::PyImport,
-                _ => DefKind::Other,
-            },
+            }
+            Some("Patch") => DefKind::Patch,
+            Some("import") => DefKind::ErgImport,
+            Some("pyimport") | Some("py") | Some("__import__") | Some("cimport") => {
+                DefKind::PyImport
+            }
             _ => DefKind::Other,
         }
     }
@@ -4532,7 +4598,7 @@ impl Expr {
     pub fn local(name: &str, lineno: u32, col_begin: u32) -> Self {
         Self::Accessor(Accessor::local(Token::new(
             TokenKind::Symbol,
-            Str::rc(name),
+            Str::intern(name),
             lineno,
             col_begin,
         )))
@@ -4605,8 +4671,12 @@ impl Expr {
     }
 }
 
+static EMPTY_TRIVIA: TriviaMap = TriviaMap::empty();
+
+/// The trivia are boxed (and omitted if empty) to keep `Module` small,
+/// because it is returned in the `Err` variant of the parsing results.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
-pub struct Module(Block);
+pub struct Module(Block, Option<Box<TriviaMap>>);
 
 impl fmt::Display for Module {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -4643,25 +4713,38 @@ impl IntoIterator for Module {
 
 impl FromIterator<Expr> for Module {
     fn from_iter<T: IntoIterator<Item = Expr>>(iter: T) -> Self {
-        Self(iter.into_iter().collect())
+        Self(iter.into_iter().collect(), None)
     }
 }
 
 impl Module {
     pub const fn empty() -> Self {
-        Self(Block::empty())
+        Self(Block::empty(), None)
     }
     pub const fn new(payload: Vec<Expr>) -> Self {
-        Self(Block::new(payload))
+        Self(Block::new(payload), None)
     }
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
-        Self(Block::with_capacity(capacity))
+        Self(Block::with_capacity(capacity), None)
     }
 
     pub fn block(&self) -> &Block {
         &self.0
     }
+
+    /// Comments and blank lines attached to the statements
+    pub fn trivia(&self) -> &TriviaMap {
+        self.1.as_deref().unwrap_or(&EMPTY_TRIVIA)
+    }
+
+    pub fn set_trivia(&mut self, trivia: TriviaMap) {
+        self.1 = (!trivia.is_empty()).then(|| Box::new(trivia));
+    }
+
+    pub fn take_trivia(&mut self) -> TriviaMap {
+        self.1.take().map_or_else(TriviaMap::empty, |trivia| *trivia)
+    }
 }
 
 #[derive(Debug)]
@@ -1,4 +1,5 @@
 use erg_common::config::ErgConfig;
+use erg_common::timings;
 use erg_common::traits::{ExitStatus, Runnable};
 use erg_common::Str;
 
@@ -65,19 +66,36 @@ impl ASTBuilder {
         IncompleteArtifact<AST, ParserRunnerErrors>,
     > {
         let name = Str::rc(self.runner.cfg().input.unescaped_filename());
+        let module_name = self.runner.cfg().input.module_name();
+        let mut desugarer = self.desugarer();
+        let result = timings::measure(&module_name, "parse", || self.runner.parse(src));
+        // a partial AST (with syntax errors) is also desugared
+        let (module, warns, errs) = match result {
+            Ok(artifact) => (
+                Some(artifact.ast),
+                ParserRunnerErrors::convert(self.input(), artifact.warns),
+                None,
+            ),
+            Err(iart) => (iart.ast, iart.warns, Some(iart.errors)),
+        };
+        let module = module
+            .map(|module| timings::measure(&module_name, "desugar", || desugarer.desugar(module)));
+        let ast = module.map(|module| AST::new(name, module));
+        match (ast, errs) {
+            (Some(ast), None) => Ok(CompleteArtifact::new(ast, warns)),
+            (ast, errs) => Err(IncompleteArtifact::new(
+                ast,
+                warns,
+                errs.unwrap_or_default(),
+            )),
+        }
+    }
+
+    /// A `Desugarer` with the built-in passes, configured by `cfg`
+    pub fn desugarer(&self) -> Desugarer {
         let mut desugarer = Desugarer::new();
-        let artifact = self.runner.parse(src).map_err(|iart| {
-            iart.map_mod(|module| {
-                let module = desugarer.desugar(module);
-                AST::new(name.clone(), module)
-            })
-        })?;
-        let module = desugarer.desugar(artifact.ast);
-        let ast = AST::new(name, module);
-        Ok(CompleteArtifact::new(
-            ast,
-            ParserRunnerErrors::convert(self.input(), artifact.warns),
-        ))
+        desugarer.passes_mut().dump = self.runner.cfg().dump_desugar;
+        desugarer
     }
 
     pub fn build_without_desugaring(
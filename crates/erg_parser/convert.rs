@@ -403,7 +403,7 @@ impl Parser {
         Ok(param)
     }
 
-    fn convert_rhs_to_param(
+    pub(crate) fn convert_rhs_to_param(
         &mut self,
         expr: Expr,
         allow_self: bool,
@@ -4,23 +4,25 @@
 //! e.g. Literal parameters, Multi assignment
 //! 型チェックなどによる検証は行わない
 
+use std::mem;
+
 use erg_common::fresh::FreshNameGenerator;
+use erg_common::set::Set;
 use erg_common::traits::{Locational, Stream};
 use erg_common::Str;
-use erg_common::{enum_unwrap, get_hash, log, set};
+use erg_common::{get_hash, log, set};
 
 use crate::ast::{
-    Accessor, Args, Array, ArrayComprehension, ArrayTypeSpec, ArrayWithLength, BinOp, Block, Call,
-    ClassAttr, ClassAttrs, ClassDef, ConstExpr, DataPack, Def, DefBody, DefId,
-    DefaultParamSignature, Dict, Dummy, Expr, Identifier, KeyValue, KwArg, Lambda, LambdaSignature,
-    Literal, Methods, MixedRecord, Module, NonDefaultParamSignature, NormalArray, NormalDict,
-    NormalRecord, NormalSet, NormalTuple, ParamPattern, ParamRecordAttr, ParamTuplePattern, Params,
-    PatchDef, PosArg, ReDef, Record, RecordAttrOrIdent, RecordAttrs, Set as astSet,
-    SetComprehension, SetWithLength, Signature, SubrSignature, Tuple, TupleTypeSpec, TypeAppArgs,
-    TypeAppArgsKind, TypeBoundSpecs, TypeSpec, TypeSpecWithOp, UnaryOp, VarName, VarPattern,
-    VarRecordAttr, VarSignature, VisModifierSpec,
+    Accessor, Args, Array, ArrayTypeSpec, ArrayWithLength, BinOp, Block, Call, ClassAttr,
+    ClassAttrs, ConstExpr, DataPack, Def, DefBody, DefId, Dummy, Expr, Identifier, Lambda,
+    LambdaSignature, Literal, Methods, MixedRecord, Module, NonDefaultParamSignature, NormalRecord,
+    NormalSet, NormalTuple, ParamPattern, ParamRecordAttr, ParamTuplePattern, Params, PosArg,
+    Record, RecordAttrOrIdent, RecordAttrs, Set as astSet, Signature, SubrSignature, Tuple,
+    TupleTypeSpec, TypeBoundSpecs, TypeSpec, TypeSpecWithOp, VarName, VarPattern, VarRecordAttr,
+    VarSignature, VisModifierSpec,
 };
 use crate::token::{Token, TokenKind, COLON, DOT};
+use crate::visitor::{fold_args, fold_children};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum BufIndex<'i> {
@@ -29,32 +31,202 @@ enum BufIndex<'i> {
     Record(&'i Identifier),
 }
 
+/// A named desugaring pass.
+#[derive(Debug, Clone, Copy)]
+pub struct DesugarPass {
+    pub name: &'static str,
+    /// The passes that must be run before this pass (ignored if not registered)
+    pub after: &'static [&'static str],
+    /// The passes that must be run after this pass (ignored if not registered)
+    pub before: &'static [&'static str],
+    pub run: fn(&mut Desugarer, Module) -> Module,
+}
+
+impl DesugarPass {
+    pub const fn new(name: &'static str, run: fn(&mut Desugarer, Module) -> Module) -> Self {
+        Self {
+            name,
+            after: &[],
+            before: &[],
+            run,
+        }
+    }
+
+    pub const fn after(mut self, after: &'static [&'static str]) -> Self {
+        self.after = after;
+        self
+    }
+
+    pub const fn before(mut self, before: &'static [&'static str]) -> Self {
+        self.before = before;
+        self
+    }
+
+    fn must_precede(&self, other: &DesugarPass) -> bool {
+        self.before.contains(&other.name) || other.after.contains(&self.name)
+    }
+}
+
+/// The pipeline of desugaring passes.
+///
+/// The passes are run in the registration order, except that a pass is moved forward
+/// if another pass is declared to follow it.
+/// Dialects can register their own passes or disable the built-in ones by name.
+#[derive(Debug, Clone, Default)]
+pub struct DesugarPasses {
+    /// in execution order
+    passes: Vec<DesugarPass>,
+    disabled: Set<&'static str>,
+    /// dump the AST after each pass (`--dump-desugar`)
+    pub dump: bool,
+}
+
+impl DesugarPasses {
+    pub fn builtin() -> Self {
+        let mut passes = Self::default();
+        for pass in [
+            DesugarPass::new("derive", |_, module| Desugarer::desugar_derive(module)),
+            DesugarPass::new("enum", |_, module| Desugarer::desugar_enum(module)),
+            DesugarPass::new("conditional_binding", |_, module| {
+                Desugarer::desugar_conditional_binding(module)
+            }),
+            DesugarPass::new("multiple_pattern_def", |desugarer, module| {
+                desugarer.desugar_multiple_pattern_def(module)
+            }),
+            // the above passes generate patterns (`match` arms)
+            DesugarPass::new("pattern", |desugarer, module| {
+                desugarer.desugar_pattern_in_module(module)
+            })
+            .after(&["conditional_binding", "multiple_pattern_def"]),
+            DesugarPass::new("shortened_record", |_, module| {
+                Desugarer::desugar_shortened_record(module)
+            }),
+            // the pattern desugaring generates `x.0` and `x[0]`
+            DesugarPass::new("accessor", |_, module| Desugarer::desugar_acc(module))
+                .after(&["pattern"]),
+        ] {
+            passes.register(pass).unwrap();
+        }
+        passes
+    }
+
+    /// Registers `pass` just before the first pass that must follow it (or at the end).
+    /// If the ordering constraints are not satisfied there
+    /// (or a pass with the same name is already registered), `pass` is not registered.
+    pub fn register(&mut self, pass: DesugarPass) -> Result<(), String> {
+        if self.get(pass.name).is_some() {
+            return Err(format!(
+                "desugaring pass `{}` is already registered",
+                pass.name
+            ));
+        }
+        let idx = self
+            .passes
+            .iter()
+            .position(|other| pass.must_precede(other))
+            .unwrap_or(self.passes.len());
+        if let Some(other) = self.passes[idx..]
+            .iter()
+            .find(|other| other.must_precede(&pass))
+        {
+            return Err(format!(
+                "desugaring pass `{}` cannot be ordered with `{}`",
+                pass.name, other.name
+            ));
+        }
+        self.passes.insert(idx, pass);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DesugarPass> {
+        self.passes.iter().find(|pass| pass.name == name)
+    }
+
+    /// Returns `false` if `name` is not registered.
+    pub fn disable(&mut self, name: &str) -> bool {
+        let Some(pass) = self.get(name) else {
+            return false;
+        };
+        self.disabled.insert(pass.name);
+        true
+    }
+
+    /// Returns `false` if `name` is not registered.
+    pub fn enable(&mut self, name: &str) -> bool {
+        self.disabled.remove(name) || self.get(name).is_some()
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.get(name).is_some() && !self.disabled.contains(name)
+    }
+
+    /// The names of the enabled passes in execution order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.enabled().map(|pass| pass.name).collect()
+    }
+
+    fn enabled(&self) -> impl Iterator<Item = &DesugarPass> {
+        self.passes
+            .iter()
+            .filter(|pass| !self.disabled.contains(pass.name))
+    }
+
+    fn run(&self, desugarer: &mut Desugarer, mut module: Module) -> Module {
+        for pass in self.enabled() {
+            module = (pass.run)(desugarer, module);
+            log!(info "AST (after {}):\n{module}", pass.name);
+            if self.dump {
+                eprintln!("# after `{}`:\n{module}", pass.name);
+            }
+        }
+        module
+    }
+}
+
 #[derive(Debug)]
 pub struct Desugarer {
     // _desugared: Set<Str>,
     var_gen: FreshNameGenerator,
+    passes: DesugarPasses,
 }
 
 impl Desugarer {
     pub fn new() -> Desugarer {
+        Self::with_passes(DesugarPasses::builtin())
+    }
+
+    pub fn with_passes(passes: DesugarPasses) -> Desugarer {
         Self {
             // _desugared: Set::default(),
             var_gen: FreshNameGenerator::new("desugar"),
+            passes,
         }
     }
 
+    pub fn passes(&self) -> &DesugarPasses {
+        &self.passes
+    }
+
+    pub fn passes_mut(&mut self) -> &mut DesugarPasses {
+        &mut self.passes
+    }
+
     pub fn desugar(&mut self, module: Module) -> Module {
         log!(info "the desugaring process has started.");
-        let module = self.desugar_multiple_pattern_def(module);
-        let module = self.desugar_pattern_in_module(module);
-        let module = Self::desugar_shortened_record(module);
-        let module = Self::desugar_acc(module);
+        let mut module = module;
+        // the desugaring rebuilds the module, so the trivia must be carried over
+        let trivia = module.take_trivia();
+        let passes = mem::take(&mut self.passes);
+        let mut module = passes.run(self, module);
+        self.passes = passes;
+        module.set_trivia(trivia);
         log!(info "AST (desugared):\n{module}");
         log!(info "the desugaring process has completed.");
         module
     }
 
     pub fn desugar_simple_expr(expr: Expr) -> Expr {
+        let expr = Self::rec_desugar_conditional_binding(expr);
         let expr = Self::rec_desugar_shortened_record(expr);
         let expr = Self::rec_desugar_lambda_pattern(&mut Desugarer::new(), expr);
         Self::rec_desugar_acc(expr)
@@ -64,310 +236,8 @@ impl Desugarer {
         module.into_iter().map(desugar).collect()
     }
 
-    fn desugar_args(mut desugar: impl FnMut(Expr) -> Expr, args: Args) -> Args {
-        let (pos_args, var_args, kw_args, paren) = args.deconstruct();
-        let pos_args = pos_args
-            .into_iter()
-            .map(|arg| PosArg::new(desugar(arg.expr)))
-            .collect();
-        let var_args = var_args.map(|arg| PosArg::new(desugar(arg.expr)));
-        let kw_args = kw_args
-            .into_iter()
-            .map(|arg| {
-                KwArg::new(arg.keyword, arg.t_spec, desugar(arg.expr)) // TODO: t_spec
-            })
-            .collect();
-        Args::new(pos_args, var_args, kw_args, paren)
-    }
-
-    fn perform_desugar_acc(mut desugar: impl FnMut(Expr) -> Expr, acc: Accessor) -> Accessor {
-        match acc {
-            Accessor::Ident(ident) => Accessor::Ident(ident),
-            Accessor::Attr(attr) => desugar(*attr.obj).attr(attr.ident),
-            Accessor::TupleAttr(tup) => {
-                let obj = desugar(*tup.obj);
-                obj.tuple_attr(tup.index)
-            }
-            Accessor::Subscr(sub) => {
-                let obj = desugar(*sub.obj);
-                let index = desugar(*sub.index);
-                obj.subscr(index, sub.r_sqbr)
-            }
-            Accessor::TypeApp(tapp) => {
-                let obj = desugar(*tapp.obj);
-                let args = match tapp.type_args.args {
-                    TypeAppArgsKind::Args(args) => {
-                        TypeAppArgsKind::Args(Self::desugar_args(desugar, args))
-                    }
-                    other => other,
-                };
-                let type_args =
-                    TypeAppArgs::new(tapp.type_args.l_vbar, args, tapp.type_args.r_vbar);
-                obj.type_app(type_args)
-            }
-        }
-    }
-
     fn perform_desugar(mut desugar: impl FnMut(Expr) -> Expr, expr: Expr) -> Expr {
-        match expr {
-            Expr::Literal(_) => expr,
-            Expr::Record(record) => match record {
-                Record::Normal(rec) => {
-                    let mut new_attrs = vec![];
-                    for attr in rec.attrs {
-                        new_attrs.push(enum_unwrap!(desugar(Expr::Def(attr)), Expr::Def));
-                    }
-                    Expr::Record(Record::Normal(NormalRecord::new(
-                        rec.l_brace,
-                        rec.r_brace,
-                        RecordAttrs::new(new_attrs),
-                    )))
-                }
-                Record::Mixed(mixed) => {
-                    let mut new_attrs = vec![];
-                    for attr in mixed.attrs {
-                        match attr {
-                            RecordAttrOrIdent::Attr(attr) => {
-                                let attr = RecordAttrOrIdent::Attr(enum_unwrap!(
-                                    desugar(Expr::Def(attr)),
-                                    Expr::Def
-                                ));
-                                new_attrs.push(attr);
-                            }
-                            RecordAttrOrIdent::Ident(ident) => {
-                                new_attrs.push(RecordAttrOrIdent::Ident(ident));
-                            }
-                        }
-                    }
-                    Expr::Record(Record::Mixed(MixedRecord::new(
-                        mixed.l_brace,
-                        mixed.r_brace,
-                        new_attrs,
-                    )))
-                }
-            },
-            Expr::DataPack(pack) => {
-                let class = desugar(*pack.class);
-                let Expr::Record(args) = desugar(Expr::Record(pack.args)) else { unreachable!() };
-                Expr::DataPack(DataPack::new(class, pack.connector, args))
-            }
-            Expr::Array(array) => match array {
-                Array::Normal(arr) => {
-                    let (elems, ..) = arr.elems.deconstruct();
-                    let elems = elems
-                        .into_iter()
-                        .map(|elem| PosArg::new(desugar(elem.expr)))
-                        .collect();
-                    let elems = Args::pos_only(elems, None);
-                    let arr = NormalArray::new(arr.l_sqbr, arr.r_sqbr, elems);
-                    Expr::Array(Array::Normal(arr))
-                }
-                Array::WithLength(arr) => {
-                    let elem = PosArg::new(desugar(arr.elem.expr));
-                    let len = desugar(*arr.len);
-                    let arr = ArrayWithLength::new(arr.l_sqbr, arr.r_sqbr, elem, len);
-                    Expr::Array(Array::WithLength(arr))
-                }
-                Array::Comprehension(arr) => {
-                    let elem = desugar(*arr.elem);
-                    let generators = arr
-                        .generators
-                        .into_iter()
-                        .map(|(ident, gen)| (ident, desugar(gen)))
-                        .collect();
-                    let guards = arr.guards.into_iter().map(desugar).collect();
-                    let arr =
-                        ArrayComprehension::new(arr.l_sqbr, arr.r_sqbr, elem, generators, guards);
-                    Expr::Array(Array::Comprehension(arr))
-                }
-            },
-            Expr::Tuple(tuple) => match tuple {
-                Tuple::Normal(tup) => {
-                    let (elems, _, _, paren) = tup.elems.deconstruct();
-                    let elems = elems
-                        .into_iter()
-                        .map(|elem| PosArg::new(desugar(elem.expr)))
-                        .collect();
-                    let new_tup = Args::pos_only(elems, paren);
-                    let tup = NormalTuple::new(new_tup);
-                    Expr::Tuple(Tuple::Normal(tup))
-                }
-            },
-            Expr::Set(set) => match set {
-                astSet::Normal(set) => {
-                    let (elems, ..) = set.elems.deconstruct();
-                    let elems = elems
-                        .into_iter()
-                        .map(|elem| PosArg::new(desugar(elem.expr)))
-                        .collect();
-                    let elems = Args::pos_only(elems, None);
-                    let set = NormalSet::new(set.l_brace, set.r_brace, elems);
-                    Expr::Set(astSet::Normal(set))
-                }
-                astSet::WithLength(set) => {
-                    let elem = PosArg::new(desugar(set.elem.expr));
-                    let len = desugar(*set.len);
-                    let set = SetWithLength::new(set.l_brace, set.r_brace, elem, len);
-                    Expr::Set(astSet::WithLength(set))
-                }
-                astSet::Comprehension(set) => {
-                    let iter = desugar(*set.iter);
-                    let pred = desugar(*set.pred);
-                    let set = SetComprehension::new(
-                        set.l_brace,
-                        set.r_brace,
-                        set.var,
-                        set.op,
-                        iter,
-                        pred,
-                    );
-                    Expr::Set(astSet::Comprehension(set))
-                }
-            },
-            Expr::Dict(dict) => match dict {
-                Dict::Normal(dic) => {
-                    let new_kvs = dic
-                        .kvs
-                        .into_iter()
-                        .map(|elem| {
-                            let key = desugar(elem.key);
-                            let value = desugar(elem.value);
-                            KeyValue::new(key, value)
-                        })
-                        .collect();
-                    let tup = NormalDict::new(dic.l_brace, dic.r_brace, new_kvs);
-                    Expr::Dict(Dict::Normal(tup))
-                }
-                _ => todo!("dict comprehension"),
-            },
-            Expr::BinOp(binop) => {
-                let mut args = binop.args.into_iter();
-                let lhs = desugar(*args.next().unwrap());
-                let rhs = desugar(*args.next().unwrap());
-                Expr::BinOp(BinOp::new(binop.op, lhs, rhs))
-            }
-            Expr::UnaryOp(unaryop) => {
-                let mut args = unaryop.args.into_iter();
-                let expr = desugar(*args.next().unwrap());
-                Expr::UnaryOp(UnaryOp::new(unaryop.op, expr))
-            }
-            Expr::Call(call) => {
-                let obj = desugar(*call.obj);
-                let args = Self::desugar_args(desugar, call.args);
-                Expr::Call(Call::new(obj, call.attr_name, args))
-            }
-            Expr::Def(mut def) => {
-                let mut chunks = vec![];
-                for chunk in def.body.block.into_iter() {
-                    chunks.push(desugar(chunk));
-                }
-                if let Signature::Subr(mut subr) = def.sig {
-                    let mut defaults = vec![];
-                    for default in subr.params.defaults.into_iter() {
-                        let default_val = desugar(default.default_val);
-                        defaults.push(DefaultParamSignature {
-                            default_val,
-                            ..default
-                        });
-                    }
-                    subr.params.defaults = defaults;
-                    def.sig = Signature::Subr(subr);
-                }
-                let body = DefBody::new(def.body.op, Block::new(chunks), def.body.id);
-                Expr::Def(Def::new(def.sig, body))
-            }
-            Expr::ClassDef(class_def) => {
-                let Expr::Def(def) = desugar(Expr::Def(class_def.def)) else { unreachable!() };
-                let methods = class_def
-                    .methods_list
-                    .into_iter()
-                    .map(|method| enum_unwrap!(desugar(Expr::Methods(method)), Expr::Methods))
-                    .collect();
-                Expr::ClassDef(ClassDef::new(def, methods))
-            }
-            Expr::PatchDef(class_def) => {
-                let Expr::Def(def) = desugar(Expr::Def(class_def.def)) else { unreachable!() };
-                let methods = class_def
-                    .methods_list
-                    .into_iter()
-                    .map(|method| enum_unwrap!(desugar(Expr::Methods(method)), Expr::Methods))
-                    .collect();
-                Expr::PatchDef(PatchDef::new(def, methods))
-            }
-            Expr::ReDef(redef) => {
-                let expr = desugar(*redef.expr);
-                let attr = Self::perform_desugar_acc(desugar, redef.attr);
-                Expr::ReDef(ReDef::new(attr, expr))
-            }
-            Expr::Lambda(mut lambda) => {
-                let mut chunks = vec![];
-                for chunk in lambda.body.into_iter() {
-                    chunks.push(desugar(chunk));
-                }
-                let mut defaults = vec![];
-                for default in lambda.sig.params.defaults.into_iter() {
-                    let default_val = desugar(default.default_val);
-                    defaults.push(DefaultParamSignature {
-                        default_val,
-                        ..default
-                    });
-                }
-                lambda.sig.params.defaults = defaults;
-                let body = Block::new(chunks);
-                Expr::Lambda(Lambda::new(lambda.sig, lambda.op, body, lambda.id))
-            }
-            Expr::TypeAscription(tasc) => {
-                let expr = desugar(*tasc.expr);
-                let t_spec_as_expr = desugar(*tasc.t_spec.t_spec_as_expr);
-                let t_spec =
-                    TypeSpecWithOp::new(tasc.t_spec.op, tasc.t_spec.t_spec, t_spec_as_expr);
-                expr.type_asc_expr(t_spec)
-            }
-            Expr::Methods(method_defs) => {
-                let mut new_attrs = vec![];
-                for attr in method_defs.attrs.into_iter() {
-                    let mut chunks = vec![];
-                    match attr {
-                        ClassAttr::Def(def) => {
-                            for chunk in def.body.block.into_iter() {
-                                chunks.push(desugar(chunk));
-                            }
-                            let body = DefBody::new(def.body.op, Block::new(chunks), def.body.id);
-                            new_attrs.push(ClassAttr::Def(Def::new(def.sig, body)));
-                        }
-                        ClassAttr::Decl(decl) => {
-                            let expr = desugar(*decl.expr);
-                            let t_spec_as_expr = desugar(*decl.t_spec.t_spec_as_expr);
-                            let t_spec = TypeSpecWithOp::new(
-                                decl.t_spec.op,
-                                decl.t_spec.t_spec,
-                                t_spec_as_expr,
-                            );
-                            new_attrs.push(ClassAttr::Decl(expr.type_asc(t_spec)));
-                        }
-                        ClassAttr::Doc(doc) => {
-                            new_attrs.push(ClassAttr::Doc(doc));
-                        }
-                    }
-                }
-                let new_attrs = ClassAttrs::from(new_attrs);
-                Expr::Methods(Methods::new(
-                    method_defs.class,
-                    *method_defs.class_as_expr,
-                    method_defs.vis,
-                    new_attrs,
-                ))
-            }
-            Expr::Accessor(acc) => Expr::Accessor(Self::perform_desugar_acc(desugar, acc)),
-            Expr::Dummy(exprs) => {
-                let loc = exprs.loc;
-                let mut chunks = vec![];
-                for chunk in exprs.into_iter() {
-                    chunks.push(desugar(chunk));
-                }
-                Expr::Dummy(Dummy::new(loc, chunks))
-            }
-        }
+        fold_children(&mut desugar, expr)
     }
 
     /// `fib 0 = 0; fib 1 = 1; fib n = fib(n-1) + fib(n-2)`
@@ -381,6 +251,17 @@ impl Desugarer {
                         if previous.is_subr() && previous.sig.name_as_str() == def.sig.name_as_str()
                         {
                             let Some(Expr::Def(previous)) = new.pop() else { unreachable!() };
+                            // `@Overload` definitions keep their decorators,
+                            // and each branch has its own return type
+                            let overloaded =
+                                previous.sig.is_overloaded() || def.sig.is_overloaded();
+                            let decorators = if overloaded {
+                                let mut decos = previous.sig.decorators().unwrap().clone();
+                                decos.extend(def.sig.decorators().unwrap().iter().cloned());
+                                decos
+                            } else {
+                                set! {}
+                            };
                             let name = def.sig.ident().unwrap().clone();
                             let id = def.body.id;
                             let op = def.body.op.clone();
@@ -431,8 +312,9 @@ impl Desugarer {
                                 }
                                 _ => unreachable!(),
                             };
+                            let return_t_spec = if overloaded { None } else { return_t_spec };
                             let sig = Signature::Subr(SubrSignature::new(
-                                set! {},
+                                decorators,
                                 name,
                                 TypeBoundSpecs::empty(),
                                 params,
@@ -831,6 +713,300 @@ impl Desugarer {
         }
     }
 
+    /// ```erg
+    /// @Derive [Eq, Show]
+    /// C = Class { .x = Int }
+    /// ```
+    /// The decorator is applied as `C = Derive([Eq, Show])(Class { .x = Int })`,
+    /// so it is rewritten to `C = Derive(Class { .x = Int }, [Eq, Show])`.
+    /// `Derivable` (`@Derivable "module"`) is rewritten in the same way.
+    fn desugar_derive(module: Module) -> Module {
+        module
+            .into_iter()
+            .map(|chunk| match chunk {
+                Expr::Def(mut def) if def.body.block.len() == 1 => {
+                    let body = def.body.block.remove(0);
+                    def.body.block.push(Self::desugar_derive_call(body));
+                    Expr::Def(def)
+                }
+                other => other,
+            })
+            .collect()
+    }
+
+    fn desugar_derive_call(expr: Expr) -> Expr {
+        let Expr::Call(call) = expr else {
+            return expr;
+        };
+        let is_derive = |call: &Call| {
+            call.obj
+                .get_name()
+                .is_some_and(|name| &name[..] == "Derive" || &name[..] == "Derivable")
+                && call.attr_name.is_none()
+                && call.args.pos_args().len() == 1
+        };
+        match *call.obj {
+            Expr::Call(deco)
+                if is_derive(&deco) && call.attr_name.is_none() && call.args.len() == 1 =>
+            {
+                let (mut traits, ..) = deco.args.deconstruct();
+                let (mut class, ..) = call.args.deconstruct();
+                let class = PosArg::new(Self::desugar_derive_call(class.remove(0).expr));
+                let args = Args::pos_only(vec![class, traits.remove(0)], None);
+                Expr::Call(Call::new(*deco.obj, None, args))
+            }
+            // other decorators, e.g. `Inheritable(Derive([Eq])(Class {...}))`
+            obj => {
+                let args = fold_args(&mut Self::desugar_derive_call, call.args);
+                Expr::Call(Call::new(obj, call.attr_name, args))
+            }
+        }
+    }
+
+    /// ```erg
+    /// Shape = Enum {Circle(Float); Rect(Float, Float); Empty}
+    /// ```
+    /// ↓
+    /// ```erg
+    /// Circle = Class {.item0 = Float}
+    /// Circle.
+    ///     __discriminant__ = 0
+    ///     new item0: Float = Circle::__new__ {.item0 = item0}
+    /// Rect = Class {.item0 = Float; .item1 = Float}
+    /// Rect.
+    ///     __discriminant__ = 1
+    ///     new item0: Float, item1: Float = Rect::__new__ {.item0 = item0; .item1 = item1}
+    /// Empty = Class()
+    /// Empty.
+    ///     __discriminant__ = 2
+    /// Shape = Circle or Rect or Empty
+    /// ```
+    ///
+    /// Like other type definitions, `Enum`s can only be defined at the top level
+    /// (nested ones are left as they are and rejected by the lowerer).
+    fn desugar_enum(module: Module) -> Module {
+        let mut new = Module::with_capacity(module.len());
+        for chunk in module.into_iter() {
+            match chunk {
+                Expr::Def(def) if Self::is_enum_def(&def) => {
+                    new.extend(Self::desugar_enum_def(def));
+                }
+                other => new.push(other),
+            }
+        }
+        new
+    }
+
+    fn is_enum_def(def: &Def) -> bool {
+        let Some(Expr::Call(call)) = def.body.block.first() else {
+            return false;
+        };
+        def.sig.ident().is_some()
+            && def.body.block.len() == 1
+            && call.obj.get_name().is_some_and(|name| &name[..] == "Enum")
+            && call.attr_name.is_none()
+            && call.args.len() == 1
+            && match call.args.pos_args().first().map(|arg| &arg.expr) {
+                Some(Expr::Record(_)) => true,
+                // invalid variants are reported by the parser
+                Some(Expr::Set(astSet::Normal(set))) => {
+                    set.elems.pos_args().iter().all(|elem| match &elem.expr {
+                        Expr::Accessor(Accessor::Ident(_)) => true,
+                        Expr::Call(call) => {
+                            call.attr_name.is_none()
+                                && matches!(call.obj.as_ref(), Expr::Accessor(Accessor::Ident(_)))
+                        }
+                        _ => false,
+                    })
+                }
+                _ => false,
+            }
+    }
+
+    fn desugar_enum_def(def: Def) -> Vec<Expr> {
+        let enum_ident = def.sig.ident().unwrap().clone();
+        let Some(Expr::Call(call)) = def.body.block.into_iter().next() else { unreachable!() };
+        let (mut pos_args, ..) = call.args.deconstruct();
+        let variants = match pos_args.remove(0).expr {
+            // {A} or {B(Int)} (a single variant is parsed as a set)
+            Expr::Set(astSet::Normal(set)) => {
+                let (elems, ..) = set.elems.deconstruct();
+                elems
+                    .into_iter()
+                    .map(|elem| match elem.expr {
+                        Expr::Accessor(Accessor::Ident(ident)) => (ident, vec![]),
+                        Expr::Call(call) => {
+                            let Expr::Accessor(Accessor::Ident(ident)) = *call.obj else {
+                                unreachable!()
+                            };
+                            let (pos_args, ..) = call.args.deconstruct();
+                            (ident, pos_args.into_iter().map(|arg| arg.expr).collect())
+                        }
+                        _ => unreachable!(),
+                    })
+                    .collect()
+            }
+            Expr::Record(record) => Self::enum_record_variants(record),
+            _ => unreachable!(),
+        };
+        Self::gen_enum_chunks(enum_ident, def.body.op, def.body.id, variants)
+    }
+
+    fn enum_record_variants(record: Record) -> Vec<(Identifier, Vec<Expr>)> {
+        match record {
+            Record::Normal(rec) => rec
+                .attrs
+                .into_iter()
+                .map(|def| {
+                    let ident = def.sig.ident().unwrap().clone();
+                    (ident, Self::enum_variant_fields(def.body.block))
+                })
+                .collect(),
+            Record::Mixed(mixed) => mixed
+                .attrs
+                .into_iter()
+                .map(|attr| match attr {
+                    RecordAttrOrIdent::Attr(def) => {
+                        let ident = def.sig.ident().unwrap().clone();
+                        (ident, Self::enum_variant_fields(def.body.block))
+                    }
+                    RecordAttrOrIdent::Ident(ident) => (ident, vec![]),
+                })
+                .collect(),
+        }
+    }
+
+    fn gen_enum_chunks(
+        enum_ident: Identifier,
+        op: Token,
+        id: DefId,
+        variants: Vec<(Identifier, Vec<Expr>)>,
+    ) -> Vec<Expr> {
+        let line = enum_ident.ln_begin().unwrap_or(1);
+        let mut chunks = vec![];
+        let mut alias: Option<Expr> = None;
+        for (discr, (variant, fields)) in variants.into_iter().enumerate() {
+            let vline = variant.ln_begin().unwrap_or(line);
+            let vcol = variant.col_begin().unwrap_or(0);
+            let class_ident = Identifier::new(enum_ident.vis.clone(), variant.name.clone());
+            let class = Expr::local(variant.inspect(), vline, vcol);
+            let field_idents = (0..fields.len())
+                .map(|i| Identifier::public_with_line(DOT, format!("item{i}").into(), vline))
+                .collect::<Vec<_>>();
+            // Class {.item0 = T0; .item1 = T1; ...}
+            let base = if fields.is_empty() {
+                Args::empty()
+            } else {
+                let attrs = field_idents
+                    .iter()
+                    .zip(fields.iter())
+                    .map(|(ident, t)| {
+                        Def::new(
+                            Signature::new_var(ident.clone()),
+                            DefBody::new_single(t.clone()),
+                        )
+                    })
+                    .collect();
+                let record = NormalRecord::new(
+                    Token::from_str(TokenKind::LBrace, "{"),
+                    Token::from_str(TokenKind::RBrace, "}"),
+                    RecordAttrs::new(attrs),
+                );
+                Args::single(PosArg::new(Expr::Record(Record::Normal(record))))
+            };
+            let class_body = Expr::local("Class", vline, vcol).call_expr(base);
+            let class_def = Def::new(
+                Signature::new_var(class_ident),
+                DefBody::new_single(class_body),
+            );
+            chunks.push(Expr::Def(class_def));
+            // C.
+            //     __discriminant__ = i
+            //     new item0: T0, ... = C::__new__ {.item0 = item0; ...}
+            let discr_def = Def::new(
+                Signature::new_var(Identifier::public_with_line(
+                    DOT,
+                    "__discriminant__".into(),
+                    vline,
+                )),
+                DefBody::new_single(Expr::Literal(Literal::nat(discr, vline))),
+            );
+            let mut attrs = vec![ClassAttr::Def(discr_def)];
+            if !fields.is_empty() {
+                let mut params = vec![];
+                let mut attrs_of_new = vec![];
+                for (ident, t) in field_idents.iter().zip(fields) {
+                    let param_name = VarName::from_str_and_line(ident.inspect().clone(), vline);
+                    let t_spec = crate::Parser::expr_to_type_spec(t.clone())
+                        .ok()
+                        .map(|t_spec| TypeSpecWithOp::new(COLON, t_spec, t));
+                    params.push(NonDefaultParamSignature::new(
+                        ParamPattern::VarName(param_name),
+                        t_spec,
+                    ));
+                    attrs_of_new.push(Def::new(
+                        Signature::new_var(ident.clone()),
+                        DefBody::new_single(Expr::local(ident.inspect(), vline, vcol)),
+                    ));
+                }
+                let record = NormalRecord::new(
+                    Token::from_str(TokenKind::LBrace, "{"),
+                    Token::from_str(TokenKind::RBrace, "}"),
+                    RecordAttrs::new(attrs_of_new),
+                );
+                let new_body = class
+                    .clone()
+                    .attr_expr(Identifier::private_with_line("__new__".into(), vline))
+                    .call1(Expr::Record(Record::Normal(record)));
+                let sig = SubrSignature::new(
+                    set! {},
+                    Identifier::public_with_line(DOT, "new".into(), vline),
+                    TypeBoundSpecs::empty(),
+                    Params::new(params, None, vec![], None),
+                    None,
+                );
+                let new_def = Def::new(Signature::Subr(sig), DefBody::new_single(new_body));
+                attrs.push(ClassAttr::Def(new_def));
+            }
+            let t_spec = TypeSpec::mono(variant.clone());
+            let methods = Methods::new(
+                t_spec,
+                class.clone(),
+                VisModifierSpec::Public(DOT),
+                ClassAttrs::from(attrs),
+            );
+            chunks.push(Expr::Methods(methods));
+            alias = Some(match alias {
+                Some(lhs) => Expr::BinOp(BinOp::new(
+                    Token::new(TokenKind::OrOp, "or", vline, vcol),
+                    lhs,
+                    class,
+                )),
+                None => class,
+            });
+        }
+        if let Some(alias) = alias {
+            let alias_def = Def::new(
+                Signature::new_var(enum_ident),
+                DefBody::new(op, Block::new(vec![alias]), id),
+            );
+            chunks.push(Expr::Def(alias_def));
+        }
+        chunks
+    }
+
+    /// `(T0, T1, ...)` => `[T0, T1, ...]`, `()` => `[]`, `T` => `[T]`
+    fn enum_variant_fields(block: Block) -> Vec<Expr> {
+        match block.into_iter().next() {
+            Some(Expr::Tuple(Tuple::Normal(tup))) => {
+                let (pos_args, ..) = tup.elems.deconstruct();
+                pos_args.into_iter().map(|arg| arg.expr).collect()
+            }
+            Some(other) => vec![other],
+            None => vec![],
+        }
+    }
+
     /// `{x; y}` -> `{x = x; y = y}`
     fn desugar_shortened_record(module: Module) -> Module {
         Self::desugar_all_chunks(module, Self::rec_desugar_shortened_record)
@@ -1346,6 +1522,183 @@ impl Desugarer {
         todo!()
     }
 
+    fn desugar_conditional_binding(module: Module) -> Module {
+        Self::desugar_all_chunks(module, Self::rec_desugar_conditional_binding)
+    }
+
+    /// ```erg
+    /// if (i: Int) := x, do:
+    ///     i + 1
+    /// ```
+    /// ↓
+    /// ```erg
+    /// match x:
+    ///     (i: Int) -> i + 1
+    ///     _ -> None
+    /// ```
+    ///
+    /// ```erg
+    /// while! (i: Int) := f!(), do!:
+    ///     print! i
+    /// ```
+    /// ↓
+    /// ```erg
+    /// while! do!(match! f!():
+    ///     (i: Int) =>
+    ///         print! i
+    ///         True
+    ///     _ => False
+    /// ), do! None
+    /// ```
+    fn rec_desugar_conditional_binding(expr: Expr) -> Expr {
+        let Expr::Call(call) = expr else {
+            return Self::perform_desugar(Self::rec_desugar_conditional_binding, expr);
+        };
+        let is_binding =
+            |expr: &Expr| matches!(expr, Expr::Lambda(lambda) if lambda.op.is(TokenKind::Walrus));
+        let callee = match (call.obj.as_ref(), &call.attr_name) {
+            (Expr::Accessor(Accessor::Ident(ident)), None)
+                if matches!(&ident.inspect()[..], "if" | "if!")
+                    && call.args.nth_or_key(0, "cond").is_some_and(is_binding)
+                    || &ident.inspect()[..] == "while!"
+                        && call.args.nth_or_key(0, "cond!").is_some_and(is_binding) =>
+            {
+                ident.clone()
+            }
+            _ => {
+                return Self::perform_desugar(
+                    Self::rec_desugar_conditional_binding,
+                    Expr::Call(call),
+                );
+            }
+        };
+        let (line, col) = (
+            callee.ln_begin().unwrap_or(1),
+            callee.col_begin().unwrap_or(0),
+        );
+        let (name, op) = if callee.is_procedural() {
+            ("match!", Token::new(TokenKind::ProcArrow, "=>", line, col))
+        } else {
+            ("match", Token::new(TokenKind::FuncArrow, "->", line, col))
+        };
+        let (pos_args, _, kw_args, _) = call.args.deconstruct();
+        let mut pos_args = pos_args.into_iter().map(|arg| arg.expr);
+        let mut kw_args = kw_args;
+        let mut next_arg = |key: &str| {
+            pos_args.next().or_else(|| {
+                let idx = kw_args
+                    .iter()
+                    .position(|arg| &arg.keyword.inspect()[..] == key)?;
+                Some(kw_args.remove(idx).expr)
+            })
+        };
+        if &callee.inspect()[..] == "while!" {
+            let Some(Expr::Lambda(binding)) = next_arg("cond!") else {
+                unreachable!()
+            };
+            let body = next_arg("proc!");
+            return Self::desugar_while_binding(binding, body, op);
+        }
+        let Some(Expr::Lambda(binding)) = next_arg("cond") else {
+            unreachable!()
+        };
+        let then = next_arg("then");
+        let else_ = next_arg("else");
+        let scrutinee = binding.body.into_iter().next().unwrap();
+        let then_arm = Self::conditional_arm(binding.sig, then, op.clone());
+        let discard = Token::new(TokenKind::UBar, "_", line, col);
+        let discard = NonDefaultParamSignature::new(ParamPattern::Discard(discard), None);
+        let discard = LambdaSignature::new(Params::single(discard), None, TypeBoundSpecs::empty());
+        let else_arm = Self::conditional_arm(discard, else_, op);
+        let args = Args::pos_only(
+            vec![
+                PosArg::new(scrutinee),
+                PosArg::new(Expr::Lambda(then_arm)),
+                PosArg::new(Expr::Lambda(else_arm)),
+            ],
+            None,
+        );
+        let call = Call::new(Expr::local(name, line, col), None, args);
+        Self::perform_desugar(Self::rec_desugar_conditional_binding, Expr::Call(call))
+    }
+
+    fn desugar_while_binding(binding: Lambda, body: Option<Expr>, op: Token) -> Expr {
+        let (line, col) = (op.lineno, op.col_begin);
+        let bool_lit = |b: &'static str| {
+            let token = Token::new(TokenKind::BoolLit, b, line, col);
+            Expr::Literal(Literal::from(token))
+        };
+        let scrutinee = binding.body.into_iter().next().unwrap();
+        let mut then_arm = Self::conditional_arm(binding.sig, body, op.clone());
+        then_arm.body.push(bool_lit("True"));
+        let discard = Token::new(TokenKind::UBar, "_", line, col);
+        let discard = NonDefaultParamSignature::new(ParamPattern::Discard(discard), None);
+        let discard = LambdaSignature::new(Params::single(discard), None, TypeBoundSpecs::empty());
+        let id = DefId(get_hash(&(&discard, "while!")));
+        let else_arm = Lambda::new(discard, op.clone(), Block::new(vec![bool_lit("False")]), id);
+        let args = Args::pos_only(
+            vec![
+                PosArg::new(Self::rec_desugar_conditional_binding(scrutinee)),
+                PosArg::new(Self::rec_desugar_conditional_binding(Expr::Lambda(
+                    then_arm,
+                ))),
+                PosArg::new(Expr::Lambda(else_arm)),
+            ],
+            None,
+        );
+        let matcher = Call::new(Expr::local("match!", line, col), None, args);
+        let empty_sig = || {
+            LambdaSignature::new(
+                Params::new(vec![], None, vec![], None),
+                None,
+                TypeBoundSpecs::empty(),
+            )
+        };
+        let id = DefId(get_hash(&(&matcher, "cond!")));
+        let cond = Lambda::new(
+            empty_sig(),
+            op.clone(),
+            Block::new(vec![Expr::Call(matcher)]),
+            id,
+        );
+        let none = Token::new(TokenKind::NoneLit, "None", line, col);
+        let id = DefId(get_hash(&(&cond, "proc!")));
+        let proc = Lambda::new(
+            empty_sig(),
+            op,
+            Block::new(vec![Expr::Literal(Literal::from(none))]),
+            id,
+        );
+        let args = Args::pos_only(
+            vec![
+                PosArg::new(Expr::Lambda(cond)),
+                PosArg::new(Expr::Lambda(proc)),
+            ],
+            None,
+        );
+        Expr::Call(Call::new(Expr::local("while!", line, col), None, args))
+    }
+
+    /// `do: body` ==> `sig -> body`, `f` ==> `sig -> f()`, (nothing) ==> `sig -> None`
+    fn conditional_arm(sig: LambdaSignature, branch: Option<Expr>, op: Token) -> Lambda {
+        let (op, body, id) = match branch {
+            Some(Expr::Lambda(lambda)) if lambda.sig.params.is_empty() => {
+                (lambda.op, lambda.body, lambda.id)
+            }
+            Some(other) => {
+                let id = DefId(get_hash(&(&sig, &other)));
+                let call = Call::new(other, None, Args::empty());
+                (op, Block::new(vec![Expr::Call(call)]), id)
+            }
+            None => {
+                let id = DefId(get_hash(&sig));
+                let none = Token::new(TokenKind::NoneLit, "None", op.lineno, op.col_begin);
+                (op, Block::new(vec![Expr::Literal(Literal::from(none))]), id)
+            }
+        };
+        Lambda::new(sig, op, body, id)
+    }
+
     /// x[y] => x.__getitem__(y)
     /// x.0 => x.__Tuple_getitem__(0)
     fn desugar_acc(module: Module) -> Module {
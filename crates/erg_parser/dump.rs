@@ -0,0 +1,415 @@
+//! Converts `AST` to JSON (`erg dump --ast`).
+//!
+//! Each expression is an object with `"node"` (the kind of the node) and `"loc"` (the location or `null`).
+//! Patterns and type specifications are dumped as their source-like text.
+use std::fmt::Display;
+
+use erg_common::json::{Json, ToJson};
+use erg_common::traits::{Locational, Stream};
+
+use crate::ast::{
+    Accessor, Args, Array, ClassAttr, Def, Dict, Expr, Identifier, Literal, Methods, Module,
+    Params, Record, RecordAttrOrIdent, Set, Signature, Tuple, TypeSpecWithOp, AST,
+};
+
+/// The version of the JSON format of `erg dump`.
+/// This is incremented when the format is changed incompatibly.
+pub const DUMP_FORMAT_VERSION: usize = 1;
+
+pub fn loc_json<L: Locational + ?Sized>(loc: &L) -> Json {
+    let loc = loc.loc();
+    if loc.is_unknown() {
+        return Json::Null;
+    }
+    Json::object([
+        ("ln_begin", loc.ln_begin().into()),
+        ("col_begin", loc.col_begin().into()),
+        ("ln_end", loc.ln_end().into()),
+        ("col_end", loc.col_end().into()),
+    ])
+}
+
+pub fn node<L: Locational + ?Sized>(
+    kind: &'static str,
+    loc: &L,
+    fields: impl IntoIterator<Item = (&'static str, Json)>,
+) -> Json {
+    let mut object = vec![("node", kind.into()), ("loc", loc_json(loc))];
+    object.extend(fields);
+    Json::Object(object)
+}
+
+pub fn text(item: &impl Display) -> Json {
+    Json::Str(item.to_string())
+}
+
+fn t_spec_json(t_spec: &Option<TypeSpecWithOp>) -> Json {
+    t_spec
+        .as_ref()
+        .map_or(Json::Null, |t_spec| text(&t_spec.t_spec))
+}
+
+fn exprs_json<'a>(exprs: impl Iterator<Item = &'a Expr>) -> Json {
+    Json::Array(exprs.map(ToJson::to_json).collect())
+}
+
+impl ToJson for AST {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("format_version", DUMP_FORMAT_VERSION.into()),
+            ("name", text(&self.name)),
+            ("module", self.module.to_json()),
+        ])
+    }
+}
+
+impl ToJson for Module {
+    fn to_json(&self) -> Json {
+        exprs_json(self.iter())
+    }
+}
+
+impl ToJson for Literal {
+    fn to_json(&self) -> Json {
+        node(
+            "Literal",
+            self,
+            [
+                ("kind", format!("{:?}", self.token.kind).into()),
+                ("value", text(&self.token.content)),
+            ],
+        )
+    }
+}
+
+impl ToJson for Identifier {
+    fn to_json(&self) -> Json {
+        node(
+            "Identifier",
+            self,
+            [("vis", text(&self.vis)), ("name", text(self.inspect()))],
+        )
+    }
+}
+
+impl ToJson for Args {
+    fn to_json(&self) -> Json {
+        let kw_args = self
+            .kw_args()
+            .iter()
+            .map(|arg| {
+                Json::object([
+                    ("keyword", text(&arg.keyword.content)),
+                    ("value", arg.expr.to_json()),
+                ])
+            })
+            .collect();
+        Json::object([
+            (
+                "pos",
+                exprs_json(self.pos_args().iter().map(|arg| &arg.expr)),
+            ),
+            ("var", self.var_args().map(|arg| &arg.expr).to_json()),
+            ("kw", Json::Array(kw_args)),
+            ("kw_var", self.kw_var_args().map(|arg| &arg.expr).to_json()),
+        ])
+    }
+}
+
+impl ToJson for &Expr {
+    fn to_json(&self) -> Json {
+        (*self).to_json()
+    }
+}
+
+impl ToJson for Params {
+    fn to_json(&self) -> Json {
+        let defaults = self
+            .defaults
+            .iter()
+            .map(|param| {
+                Json::object([
+                    ("param", text(&param.sig)),
+                    ("default", param.default_val.to_json()),
+                ])
+            })
+            .collect();
+        Json::object([
+            (
+                "non_defaults",
+                Json::Array(self.non_defaults.iter().map(text).collect()),
+            ),
+            ("var_params", self.var_params.as_ref().map(text).into()),
+            ("defaults", Json::Array(defaults)),
+        ])
+    }
+}
+
+impl ToJson for Signature {
+    fn to_json(&self) -> Json {
+        match self {
+            Self::Var(var) => node(
+                "VarSignature",
+                var,
+                [
+                    ("pat", text(&var.pat)),
+                    ("t_spec", t_spec_json(&var.t_spec)),
+                ],
+            ),
+            Self::Subr(subr) => {
+                let decorators = subr.decorators.iter().map(|deco| deco.0.to_json());
+                node(
+                    "SubrSignature",
+                    subr,
+                    [
+                        ("decorators", Json::Array(decorators.collect())),
+                        ("ident", subr.ident.to_json()),
+                        ("bounds", text(&subr.bounds)),
+                        ("params", subr.params.to_json()),
+                        ("return_t_spec", t_spec_json(&subr.return_t_spec)),
+                    ],
+                )
+            }
+        }
+    }
+}
+
+impl ToJson for Def {
+    fn to_json(&self) -> Json {
+        node(
+            "Def",
+            self,
+            [
+                ("sig", self.sig.to_json()),
+                ("op", text(&self.body.op.content)),
+                ("body", exprs_json(self.body.block.iter())),
+            ],
+        )
+    }
+}
+
+impl ToJson for Methods {
+    fn to_json(&self) -> Json {
+        let attrs = self
+            .attrs
+            .iter()
+            .map(|attr| match attr {
+                ClassAttr::Def(def) => def.to_json(),
+                ClassAttr::Decl(decl) => Expr::TypeAscription(decl.clone()).to_json(),
+                ClassAttr::Doc(doc) => doc.to_json(),
+            })
+            .collect();
+        node(
+            "Methods",
+            self,
+            [
+                ("class", text(&self.class)),
+                ("vis", text(&self.vis)),
+                ("attrs", Json::Array(attrs)),
+            ],
+        )
+    }
+}
+
+impl ToJson for Record {
+    fn to_json(&self) -> Json {
+        let attrs = match self {
+            Self::Normal(rec) => rec.attrs.iter().map(ToJson::to_json).collect(),
+            Self::Mixed(mixed) => mixed
+                .attrs
+                .iter()
+                .map(|attr| match attr {
+                    RecordAttrOrIdent::Attr(def) => def.to_json(),
+                    RecordAttrOrIdent::Ident(ident) => ident.to_json(),
+                })
+                .collect(),
+        };
+        node("Record", self, [("attrs", Json::Array(attrs))])
+    }
+}
+
+impl ToJson for Accessor {
+    fn to_json(&self) -> Json {
+        match self {
+            Self::Ident(ident) => ident.to_json(),
+            Self::Attr(attr) => node(
+                "Attribute",
+                attr,
+                [("obj", attr.obj.to_json()), ("ident", attr.ident.to_json())],
+            ),
+            Self::TupleAttr(attr) => node(
+                "TupleAttribute",
+                attr,
+                [("obj", attr.obj.to_json()), ("index", attr.index.to_json())],
+            ),
+            Self::Subscr(sub) => node(
+                "Subscript",
+                sub,
+                [("obj", sub.obj.to_json()), ("index", sub.index.to_json())],
+            ),
+            Self::TypeApp(tapp) => node(
+                "TypeApp",
+                tapp,
+                [
+                    ("obj", tapp.obj.to_json()),
+                    ("type_args", text(&tapp.type_args.args)),
+                ],
+            ),
+        }
+    }
+}
+
+impl ToJson for Expr {
+    fn to_json(&self) -> Json {
+        match self {
+            Self::Literal(lit) => lit.to_json(),
+            Self::Accessor(acc) => acc.to_json(),
+            Self::Array(Array::Normal(arr)) => node("Array", arr, [("elems", arr.elems.to_json())]),
+            Self::Array(Array::WithLength(arr)) => node(
+                "ArrayWithLength",
+                arr,
+                [
+                    ("elem", arr.elem.expr.to_json()),
+                    ("len", arr.len.to_json()),
+                ],
+            ),
+            Self::Array(Array::Comprehension(arr)) => {
+                let generators = arr
+                    .generators
+                    .iter()
+                    .map(|(name, gen)| {
+                        Json::object([("name", name.to_json()), ("iter", gen.to_json())])
+                    })
+                    .collect();
+                node(
+                    "ArrayComprehension",
+                    arr,
+                    [
+                        ("elem", arr.elem.to_json()),
+                        ("generators", Json::Array(generators)),
+                        ("guards", exprs_json(arr.guards.iter())),
+                    ],
+                )
+            }
+            Self::Tuple(Tuple::Normal(tup)) => node("Tuple", tup, [("elems", tup.elems.to_json())]),
+            Self::Dict(Dict::Normal(dict)) => {
+                let kvs = dict
+                    .kvs
+                    .iter()
+                    .map(|kv| {
+                        Json::object([("key", kv.key.to_json()), ("value", kv.value.to_json())])
+                    })
+                    .collect();
+                node("Dict", dict, [("kvs", Json::Array(kvs))])
+            }
+            Self::Dict(Dict::Comprehension(dict)) => node(
+                "DictComprehension",
+                dict,
+                [
+                    ("attrs", dict.attrs.to_json()),
+                    ("guards", exprs_json(dict.guards.iter())),
+                ],
+            ),
+            Self::Set(Set::Normal(set)) => node("Set", set, [("elems", set.elems.to_json())]),
+            Self::Set(Set::WithLength(set)) => node(
+                "SetWithLength",
+                set,
+                [
+                    ("elem", set.elem.expr.to_json()),
+                    ("len", set.len.to_json()),
+                ],
+            ),
+            Self::Set(Set::Comprehension(set)) => node(
+                "SetComprehension",
+                set,
+                [
+                    ("var", text(&set.var.content)),
+                    ("op", text(&set.op.content)),
+                    ("iter", set.iter.to_json()),
+                    ("pred", set.pred.to_json()),
+                ],
+            ),
+            Self::Record(record) => record.to_json(),
+            Self::BinOp(bin) => node(
+                "BinOp",
+                bin,
+                [
+                    ("op", text(&bin.op.content)),
+                    ("lhs", bin.args[0].to_json()),
+                    ("rhs", bin.args[1].to_json()),
+                ],
+            ),
+            Self::UnaryOp(unary) => node(
+                "UnaryOp",
+                unary,
+                [
+                    ("op", text(&unary.op.content)),
+                    ("expr", unary.args[0].to_json()),
+                ],
+            ),
+            Self::Call(call) => node(
+                "Call",
+                call,
+                [
+                    ("obj", call.obj.to_json()),
+                    ("attr_name", call.attr_name.to_json()),
+                    ("args", call.args.to_json()),
+                ],
+            ),
+            Self::DataPack(pack) => node(
+                "DataPack",
+                pack,
+                [
+                    ("class", pack.class.to_json()),
+                    ("args", pack.args.to_json()),
+                ],
+            ),
+            Self::Lambda(lambda) => node(
+                "Lambda",
+                lambda,
+                [
+                    ("params", lambda.sig.params.to_json()),
+                    ("return_t_spec", t_spec_json(&lambda.sig.return_t_spec)),
+                    ("op", text(&lambda.op.content)),
+                    ("body", exprs_json(lambda.body.iter())),
+                ],
+            ),
+            Self::TypeAscription(tasc) => node(
+                "TypeAscription",
+                tasc,
+                [
+                    ("expr", tasc.expr.to_json()),
+                    ("op", text(&tasc.t_spec.op.content)),
+                    ("t_spec", text(&tasc.t_spec.t_spec)),
+                ],
+            ),
+            Self::Def(def) => def.to_json(),
+            Self::Methods(methods) => methods.to_json(),
+            Self::ClassDef(class_def) => node(
+                "ClassDef",
+                class_def,
+                [
+                    ("def", class_def.def.to_json()),
+                    ("methods", class_def.methods_list.to_json()),
+                ],
+            ),
+            Self::PatchDef(patch_def) => node(
+                "PatchDef",
+                patch_def,
+                [
+                    ("def", patch_def.def.to_json()),
+                    ("methods", patch_def.methods_list.to_json()),
+                ],
+            ),
+            Self::ReDef(redef) => node(
+                "ReDef",
+                redef,
+                [
+                    ("attr", redef.attr.to_json()),
+                    ("expr", redef.expr.to_json()),
+                ],
+            ),
+            Self::Dummy(dummy) => node("Dummy", dummy, [("exprs", exprs_json(dummy.exprs.iter()))]),
+        }
+    }
+}
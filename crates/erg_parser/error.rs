@@ -3,6 +3,7 @@
 //! パーサーが出すエラーを定義
 use std::fmt;
 
+use erg_common::edition::{Edition, LangOptions};
 use erg_common::error::{
     ErrorCore, ErrorDisplay, ErrorKind::*, Location, MultiErrorDisplay, SubMessage,
 };
@@ -169,6 +170,58 @@ impl LexError {
         ))
     }
 
+    pub fn invalid_header_error(errno: usize, loc: Location, reason: &str) -> Self {
+        Self::new(ErrorCore::new(
+            vec![SubMessage::only_loc(loc)],
+            switch_lang!(
+                "japanese" => format!("不正な言語ヘッダーです: {reason}"),
+                "simplified_chinese" => format!("无效的语言头: {reason}"),
+                "traditional_chinese" => format!("無效的語言頭: {reason}"),
+                "english" => format!("invalid language header: {reason}"),
+            ),
+            errno,
+            SyntaxError,
+            loc,
+        ))
+    }
+
+    /// `feature` is disabled in `edition` (or by the header)
+    pub fn unavailable_feature_error(
+        errno: usize,
+        loc: Location,
+        feature: &str,
+        edition: Edition,
+    ) -> Self {
+        let since = LangOptions::edition_of(feature).unwrap_or(Edition::CURRENT);
+        let hint = if since <= edition {
+            switch_lang!(
+                "japanese" => format!("ヘッダーから`-{feature}`を削除してください"),
+                "simplified_chinese" => format!("从头中删除`-{feature}`"),
+                "traditional_chinese" => format!("從頭中刪除`-{feature}`"),
+                "english" => format!("remove `-{feature}` from the header"),
+            )
+        } else {
+            switch_lang!(
+                "japanese" => format!("ヘッダーを`#!erg {since}`にするか、`+{feature}`を追加してください"),
+                "simplified_chinese" => format!("将头改为`#!erg {since}`，或添加`+{feature}`"),
+                "traditional_chinese" => format!("將頭改為`#!erg {since}`，或添加`+{feature}`"),
+                "english" => format!("change the header to `#!erg {since}`, or add `+{feature}` to it"),
+            )
+        };
+        Self::new(ErrorCore::new(
+            vec![SubMessage::ambiguous_new(loc, vec![], Some(hint))],
+            switch_lang!(
+                "japanese" => format!("{feature}はこのモジュール(エディション{edition})では使えません"),
+                "simplified_chinese" => format!("{feature}在此模块(版本{edition})中不可用"),
+                "traditional_chinese" => format!("{feature}在此模組(版本{edition})中不可用"),
+                "english" => format!("{feature} is not available in this module (edition {edition})"),
+            ),
+            errno,
+            SyntaxError,
+            loc,
+        ))
+    }
+
     pub fn unexpected_token<S: fmt::Display>(
         errno: usize,
         loc: Location,
@@ -327,6 +380,26 @@ impl LexError {
         Self::syntax_error(errno, loc, msg, Some(hint))
     }
 
+    pub fn invalid_enum_variant_err(errno: usize, loc: Location) -> LexError {
+        let msg = switch_lang!(
+            "japanese" => "Enumのヴァリアントの宣言が不正です",
+            "simplified_chinese" => "无效的Enum变体声明",
+            "traditional_chinese" => "無效的Enum變體聲明",
+            "english" => "invalid Enum variant declaration",
+        )
+        .to_string();
+        let name = StyledStr::new("Name", Some(HINT), Some(ATTR));
+        let call = StyledStr::new("Name(T, ...)", Some(HINT), Some(ATTR));
+        let def = StyledStr::new("Name = T", Some(HINT), Some(ATTR));
+        let hint = switch_lang!(
+            "japanese" => format!("ヴァリアントは{name}、{call}、{def}のいずれかの形で宣言してください"),
+            "simplified_chinese" => format!("变体只能声明为 {name}、{call} 或 {def}"),
+            "traditional_chinese" => format!("變體只能聲明為 {name}、{call} 或 {def}"),
+            "english" => format!("a variant must be declared as {name}, {call} or {def}"),
+        );
+        Self::syntax_error(errno, loc, msg, Some(hint))
+    }
+
     pub fn invalid_data_pack_definition(errno: usize, loc: Location, fnd: &str) -> ParseError {
         let msg = switch_lang!(
             "japanese" => "データクラスの中身が異なります",
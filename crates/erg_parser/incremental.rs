@@ -0,0 +1,294 @@
+//! Implements incremental re-lexing and re-parsing.
+//!
+//! A source code is a sequence of top-level chunks (statements starting at column 0).
+//! When the source code is edited, only the chunks containing the edited lines are lexed and parsed again,
+//! and the results are spliced into the previous token stream and AST.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use erg_common::edition::LangOptions;
+use erg_common::traits::{DequeStream, Locational, Stream};
+
+use crate::ast::{DefId, Expr, Module};
+use crate::error::{CompleteArtifact, IncompleteArtifact};
+use crate::lex::Lexer;
+use crate::token::{Token, TokenKind, TokenStream};
+use crate::Parser;
+
+use TokenKind::*;
+
+/// Lines `start..old_end` of the old source code are replaced with lines `start..new_end` of the new source code.
+/// Line numbers are 0-origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineEdit {
+    pub start: u32,
+    pub old_end: u32,
+    pub new_end: u32,
+}
+
+impl LineEdit {
+    pub const fn new(start: u32, old_end: u32, new_end: u32) -> Self {
+        Self {
+            start,
+            old_end,
+            new_end,
+        }
+    }
+
+    /// The range from line `start` to line `end` (0-origin, inclusive) of the old source code is replaced with `text`.
+    /// This corresponds to a change of LSP `textDocument/didChange`.
+    pub fn from_replacement(start: u32, end: u32, text: &str) -> Self {
+        let new_lines = text.matches('\n').count() as u32;
+        Self::new(start, end + 1, start + new_lines + 1)
+    }
+
+    pub const fn line_delta(&self) -> i64 {
+        self.new_end as i64 - self.old_end as i64
+    }
+}
+
+/// 0-origin line number of the token
+fn line_of(token: &Token) -> u32 {
+    token.lineno.saturating_sub(1)
+}
+
+fn shift_line(token: &mut Token, delta: i64) {
+    token.lineno = (token.lineno as i64 + delta).max(0) as u32;
+}
+
+/// Returns the indices of the tokens that start top-level chunks.
+/// The last element is always the index of `EOF`.
+fn chunk_starts(tokens: &TokenStream) -> Vec<usize> {
+    let mut starts = vec![];
+    let (mut indent, mut paren) = (0usize, 0usize);
+    let mut line_head = true;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            Indent => indent += 1,
+            Dedent => indent = indent.saturating_sub(1),
+            Newline => {}
+            EOF => {
+                starts.push(i);
+                break;
+            }
+            _ => {
+                if line_head && indent == 0 && paren == 0 && token.col_begin == 0 {
+                    starts.push(i);
+                }
+                match token.kind {
+                    LParen | LSqBr | LBrace => paren += 1,
+                    RParen | RSqBr | RBrace => paren = paren.saturating_sub(1),
+                    _ => {}
+                }
+            }
+        }
+        line_head = matches!(token.kind, Newline | Dedent);
+    }
+    starts
+}
+
+/// Returns the byte offset of the beginning of line `line` (0-origin)
+fn line_offset(src: &str, line: u32) -> Option<usize> {
+    if line == 0 {
+        return Some(0);
+    }
+    src.match_indices('\n')
+        .nth(line as usize - 1)
+        .map(|(idx, _)| idx + 1)
+}
+
+/// Lexes `new_src` reusing `old_tokens`, the token stream of the source code before `edit`.
+///
+/// Only the top-level chunks containing the edited lines are lexed again; the following tokens are moved.
+/// Returns `None` if the result cannot be guaranteed to be the same as lexing the whole `new_src`
+/// (e.g. the edited chunks have lexical errors or an unclosed bracket), then the caller should lex the entire source code.
+pub fn relex(old_tokens: &TokenStream, new_src: &str, edit: LineEdit) -> Option<TokenStream> {
+    if old_tokens.last().is_none_or(|t| !t.is(EOF)) {
+        return None;
+    }
+    let starts = chunk_starts(old_tokens);
+    let first = starts
+        .iter()
+        .rev()
+        .find(|&&i| line_of(&old_tokens[i]) <= edit.start && !old_tokens[i].is(EOF))
+        .copied()
+        .unwrap_or(0);
+    let next = starts
+        .iter()
+        .find(|&&i| old_tokens[i].is(EOF) || line_of(&old_tokens[i]) >= edit.old_end)
+        .copied()?;
+    let region_start = if first == 0 {
+        0
+    } else {
+        line_of(&old_tokens[first])
+    };
+    let src_start = line_offset(new_src, region_start)?;
+    let src_end = if old_tokens[next].is(EOF) {
+        new_src.len()
+    } else {
+        let line = line_of(&old_tokens[next]) as i64 + edit.line_delta();
+        line_offset(new_src, u32::try_from(line).ok()?)?
+    };
+    let region = new_src.get(src_start..src_end)?;
+    if region.trim_end_matches('\n').ends_with('\\') {
+        return None;
+    }
+    let mut relexed = Lexer::from_str(region.to_string()).lex().ok()?;
+    if !old_tokens[next].is(EOF) {
+        let eof = relexed.ref_mut_payload().pop_back()?;
+        debug_assert!(eof.is(EOF));
+        // the region must be closed by itself
+        let indents = relexed.iter().filter(|t| t.is(Indent)).count();
+        let dedents = relexed.iter().filter(|t| t.is(Dedent)).count();
+        let depth = relexed.iter().fold(0isize, |depth, t| match t.kind {
+            LParen | LSqBr | LBrace => depth + 1,
+            RParen | RSqBr | RBrace => depth - 1,
+            _ => depth,
+        });
+        if indents != dedents || depth != 0 {
+            return None;
+        }
+    }
+    let relexed = relexed.into_iter().map(|mut token| {
+        shift_line(&mut token, region_start as i64);
+        token
+    });
+    let rest = old_tokens
+        .iter()
+        .skip(next)
+        .filter(|_| !old_tokens[next].is(EOF))
+        .map(|token| {
+            let mut token = token.clone();
+            shift_line(&mut token, edit.line_delta());
+            token
+        });
+    Some(
+        old_tokens
+            .iter()
+            .take(first)
+            .cloned()
+            .chain(relexed)
+            .chain(rest)
+            .collect(),
+    )
+}
+
+/// Each incremental parse numbers `DefId`s from `generation * DEF_ID_STRIDE`,
+/// so that the `DefId`s of the reparsed chunks do not conflict with those of the reused chunks.
+/// Generation 0 is for the full parses.
+const DEF_ID_STRIDE: usize = 1 << 24;
+static GENERATION: AtomicUsize = AtomicUsize::new(1);
+
+/// Returns the indices of the chunk starts that can be a boundary of the reparsed region.
+/// A chunk following a decorator cannot be parsed separately.
+fn boundaries(tokens: &TokenStream) -> Vec<usize> {
+    let starts = chunk_starts(tokens);
+    let mut res = vec![];
+    for (n, &i) in starts.iter().enumerate() {
+        if n == 0 || !tokens[starts[n - 1]].is(AtSign) {
+            res.push(i);
+        }
+    }
+    res
+}
+
+/// Parses `new_tokens` reusing `old_module`, the AST of the token stream before `edit`.
+///
+/// `old_module` must be the result of parsing without errors (not desugared).
+/// The top-level chunks before the edited lines are reused as they are,
+/// and if the number of lines does not change, so are the chunks after them.
+/// The other chunks are parsed again. If there is no chunk that can be reused, `new_tokens` is parsed entirely.
+///
+/// The trivia and the warnings of the reused chunks are not included in the result.
+///
+/// `lang` is the language options of the new source code (see `Parser::lang_of`).
+/// An edit of the lines where the header can be (the first two lines) may change them, so the whole is parsed then.
+pub fn reparse(
+    old_module: &Module,
+    new_tokens: &TokenStream,
+    edit: LineEdit,
+    lang: &LangOptions,
+) -> Result<CompleteArtifact, IncompleteArtifact> {
+    let full_parse = || Parser::new_with_lang(new_tokens.clone(), lang.clone()).parse();
+    if new_tokens.last().is_none_or(|t| !t.is(EOF)) || edit.start < 2 {
+        return full_parse();
+    }
+    let Some(old_lines) = old_module
+        .block()
+        .iter()
+        .map(|chunk| Some((chunk.ln_begin()? - 1, chunk.ln_end()? - 1)))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return full_parse();
+    };
+    let bounds = boundaries(new_tokens);
+    // the region starts at the last boundary in the unchanged lines
+    let first = bounds
+        .iter()
+        .rev()
+        .find(|&&i| !new_tokens[i].is(EOF) && line_of(&new_tokens[i]) < edit.start)
+        .copied()
+        .unwrap_or(0);
+    let region_start = line_of(&new_tokens[first]);
+    let n_prefix = if first == 0 {
+        0
+    } else {
+        old_lines
+            .iter()
+            .take_while(|(begin, _)| *begin < region_start)
+            .count()
+    };
+    // the region ends at the first boundary after the edited lines,
+    // and no old chunk must straddle it
+    let last = if edit.line_delta() == 0 {
+        bounds.iter().copied().find(|&i| {
+            !new_tokens[i].is(EOF)
+                && line_of(&new_tokens[i]) >= edit.new_end
+                && !old_lines.iter().any(|(begin, end)| {
+                    (*begin..=*end).contains(&line_of(&new_tokens[i]))
+                        && *begin != line_of(&new_tokens[i])
+                })
+        })
+    } else {
+        None
+    };
+    let n_suffix = last.map_or(0, |i| {
+        let region_end = line_of(&new_tokens[i]);
+        old_lines
+            .iter()
+            .filter(|(begin, _)| *begin >= region_end)
+            .count()
+    });
+    if n_prefix == 0 && n_suffix == 0 {
+        return full_parse();
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed);
+    let Some(base) = generation.checked_mul(DEF_ID_STRIDE) else {
+        return full_parse();
+    };
+    let end = last.unwrap_or(new_tokens.len());
+    let mut region = new_tokens
+        .iter()
+        .skip(first)
+        .take(end - first)
+        .cloned()
+        .collect::<TokenStream>();
+    if let Some(i) = last {
+        let eof = &new_tokens[i];
+        region.push(Token::new(EOF, "", eof.lineno, 0));
+    }
+    let chunks = old_module.block();
+    let splice = |reparsed: Module| {
+        let reparsed = reparsed.block().iter().cloned();
+        let prefix = chunks.iter().take(n_prefix).cloned();
+        let suffix = chunks.iter().skip(chunks.len() - n_suffix).cloned();
+        Module::new(prefix.chain(reparsed).chain(suffix).collect::<Vec<Expr>>())
+    };
+    match Parser::with_def_id_base(region, DefId(base), lang.clone()).parse() {
+        Ok(artifact) => Ok(CompleteArtifact::new(splice(artifact.ast), artifact.warns)),
+        Err(iart) => Err(IncompleteArtifact::new(
+            iart.ast.map(splice),
+            iart.warns,
+            iart.errors,
+        )),
+    }
+}
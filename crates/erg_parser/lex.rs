@@ -1,18 +1,22 @@
 //! defines and implements `Lexer` (Tokenizer).
 use std::cmp::Ordering;
+use std::ops::Range;
 
 use erg_common::traits::ExitStatus;
 use unicode_xid::UnicodeXID;
 
-use erg_common::cache::CacheSet;
+use erg_common::cache::{get_interned, CacheSet};
 use erg_common::config::ErgConfig;
+use erg_common::error::Location;
 use erg_common::io::Input;
+use erg_common::str::ArcStr;
 use erg_common::traits::DequeStream;
 use erg_common::traits::{Locational, Runnable, Stream};
-use erg_common::{debug_power_assert, fn_name_full, normalize_newline, switch_lang};
+use erg_common::{debug_power_assert, normalize_newline, switch_lang, Str};
 
 use crate::error::{LexError, LexErrors, LexResult, LexerRunnerError, LexerRunnerErrors};
 use crate::token::{Token, TokenCategory, TokenKind, TokenStream};
+use crate::trivia::{Comment, CommentKind, RawTrivia};
 use TokenKind::*;
 
 pub trait Lexable {
@@ -165,9 +169,10 @@ impl Quote {
 #[derive(Debug)]
 pub struct Lexer /*<'a>*/ {
     str_cache: CacheSet<str>,
-    chars: Vec<char>,
+    /// The source code. Tokens are sliced from this without copying it into intermediate `String`s
+    src: ArcStr,
     indent_stack: Vec<usize>,
-    /// indicates the position in the entire source code
+    /// indicates the position (byte offset) in the entire source code
     cursor: usize,
     /// to determine the type of operators, etc.
     prev_token: Token,
@@ -175,43 +180,65 @@ pub struct Lexer /*<'a>*/ {
     lineno_token_starts: u32,
     /// 0-origin, indicates the column number in which the token appears
     col_token_starts: u32,
+    /// 0-origin, the column number of the cursor (tracked by `consume`)
+    col_cursor: u32,
     interpol_stack: Vec<Interpolation>,
+    /// comments are not tokens, but are kept for tools (see `crate::trivia`)
+    comments: Vec<Comment>,
 }
 
 impl Lexer /*<'a>*/ {
     pub fn new(mut input: Input) -> Self {
-        let normed = normalize_newline(&input.read());
-        Lexer {
-            str_cache: CacheSet::new(),
-            chars: normed.chars().collect::<Vec<char>>(),
-            indent_stack: vec![],
-            cursor: 0,
-            prev_token: Token::new(TokenKind::BOF, "", 0, 0),
-            lineno_token_starts: 0,
-            col_token_starts: 0,
-            interpol_stack: vec![Interpolation::Not],
-        }
+        Self::from_str(input.read())
     }
 
     #[allow(clippy::should_implement_trait)]
     pub fn from_str(src: String) -> Self {
-        let escaped = normalize_newline(&src);
+        let src = if src.contains('\r') {
+            normalize_newline(&src)
+        } else {
+            src
+        };
+        Self::from_arc_str(ArcStr::from(src))
+    }
+
+    /// `src` must not contain `\r` (see `erg_common::normalize_newline`).
+    pub fn from_arc_str(src: ArcStr) -> Self {
+        debug_assert!(!src.contains('\r'));
         Lexer {
             str_cache: CacheSet::new(),
-            chars: escaped.chars().collect::<Vec<char>>(),
+            src,
             indent_stack: vec![],
             cursor: 0,
             prev_token: Token::new(TokenKind::BOF, "", 0, 0),
             lineno_token_starts: 0,
             col_token_starts: 0,
+            col_cursor: 0,
             interpol_stack: vec![Interpolation::Not],
+            comments: vec![],
         }
     }
 
     pub fn lex(self) -> Result<TokenStream, LexErrors> {
+        self.lex_with_trivia().map(|(ts, _)| ts)
+    }
+
+    /// Returns the token stream and the comments and blank lines
+    pub fn lex_with_trivia(self) -> Result<(TokenStream, RawTrivia), LexErrors> {
+        let (result, trivia, errs) = self.lex_recovering();
+        if errs.is_empty() {
+            Ok((result, trivia))
+        } else {
+            Err(errs)
+        }
+    }
+
+    /// Lexes the entire source code even if there are errors.
+    /// Invalid tokens are dropped from the returned token stream.
+    pub fn lex_recovering(mut self) -> (TokenStream, RawTrivia, LexErrors) {
         let mut result = TokenStream::empty();
         let mut errs = LexErrors::empty();
-        for i in self {
+        for i in self.by_ref() {
             match i {
                 Ok(token) => result.push(token),
                 Err(err) => {
@@ -219,15 +246,43 @@ impl Lexer /*<'a>*/ {
                 }
             }
         }
-        if errs.is_empty() {
-            Ok(result)
-        } else {
-            Err(errs)
-        }
+        let blank_lines = self
+            .src
+            .split('\n')
+            .enumerate()
+            .filter(|(_, line)| line.chars().all(|c| c.is_whitespace()))
+            .map(|(i, _)| i as u32 + 1)
+            .collect();
+        let trivia = RawTrivia {
+            comments: self.comments,
+            blank_lines,
+        };
+        (result, trivia, errs)
     }
 
+    fn push_comment(&mut self, kind: CommentKind, start: usize, ln_begin: u32, col_begin: u32) {
+        let content = Str::rc(&self.src[start..self.cursor]);
+        let loc = Location::range(
+            ln_begin,
+            col_begin,
+            self.lineno_token_starts + 1,
+            self.col_cursor,
+        );
+        self.comments.push(Comment::new(kind, content, loc));
+    }
+
+    /// Emits a token whose content is not a part of the source code (e.g. a string literal with escape sequences)
     fn emit_token(&mut self, kind: TokenKind, cont: &str) -> Token {
-        let cont = self.str_cache.get(cont);
+        // the builtin names are shared among modules, and the other identifiers among this module
+        let cont = if kind == Symbol {
+            get_interned(cont).unwrap_or_else(|| self.str_cache.get(cont))
+        } else {
+            self.str_cache.get(cont)
+        };
+        self.push_token(kind, cont)
+    }
+
+    fn push_token(&mut self, kind: TokenKind, cont: Str) -> Token {
         let lineno = (self.lineno_token_starts + 2).saturating_sub(cont.lines().count() as u32);
         // cannot use String::len() for multi-byte characters
         let cont_len = cont.chars().count();
@@ -237,9 +292,35 @@ impl Lexer /*<'a>*/ {
         token
     }
 
+    /// Emits the source code from `start` to the cursor as a token
+    fn emit_token_from(&mut self, kind: TokenKind, start: usize) -> Token {
+        self.emit_slice_token(kind, start..self.cursor)
+    }
+
+    /// The content of the token refers to `self.src` (not copied)
+    fn emit_slice_token(&mut self, kind: TokenKind, range: Range<usize>) -> Token {
+        let cont = if kind == Symbol {
+            let name = &self.src[range];
+            get_interned(name).unwrap_or_else(|| self.str_cache.get(name))
+        } else {
+            Str::slice(&self.src, range)
+        };
+        self.push_token(kind, cont)
+    }
+
+    fn emit_static_token(&mut self, kind: TokenKind, cont: &'static str) -> Token {
+        self.push_token(kind, Str::ever(cont))
+    }
+
+    /// `cont` has just been consumed (except for virtual tokens like `EOF` and `Dedent`)
     #[inline]
-    fn accept(&mut self, kind: TokenKind, cont: &str) -> Option<LexResult<Token>> {
-        Some(Ok(self.emit_token(kind, cont)))
+    fn accept(&mut self, kind: TokenKind, cont: &'static str) -> Option<LexResult<Token>> {
+        let start = self.cursor.saturating_sub(cont.len());
+        if !cont.is_empty() && self.src.get(start..self.cursor) == Some(cont) {
+            Some(Ok(self.emit_token_from(kind, start)))
+        } else {
+            Some(Ok(self.emit_static_token(kind, cont)))
+        }
     }
 
     fn deny_feature(&mut self, cont: &str, feat_name: &str) -> Option<LexResult<Token>> {
@@ -333,35 +414,43 @@ impl Lexer /*<'a>*/ {
 
     /// emit_tokenで一気にcol_token_startsを移動させるのでここでは移動させない
     fn consume(&mut self) -> Option<char> {
-        let now = self.cursor;
-        self.cursor += 1;
-        self.chars.get(now).copied()
+        let ch = self.peek_cur_ch()?;
+        self.cursor += ch.len_utf8();
+        if ch == '\n' {
+            self.col_cursor = 0;
+        } else {
+            self.col_cursor += 1;
+        }
+        Some(ch)
     }
 
     fn peek_prev_prev_ch(&self) -> Option<char> {
-        self.chars.get(self.cursor.checked_sub(2)?).copied()
+        self.src[..self.cursor].chars().nth_back(1)
     }
 
     fn peek_prev_ch(&self) -> Option<char> {
-        self.chars.get(self.cursor.checked_sub(1)?).copied()
+        self.src[..self.cursor].chars().next_back()
     }
 
     #[inline]
     fn peek_cur_ch(&self) -> Option<char> {
-        self.chars.get(self.cursor).copied()
+        self.src[self.cursor..].chars().next()
     }
 
     #[inline]
     fn peek_next_ch(&self) -> Option<char> {
-        self.chars.get(self.cursor + 1).copied()
+        self.src[self.cursor..].chars().nth(1)
     }
 
     fn lex_comment(&mut self) -> LexResult<()> {
         // debug_power_assert!(self.consume(), ==, Some('#'));
-        let mut s = "".to_string();
+        let (start, ln_begin, col_begin) =
+            (self.cursor, self.lineno_token_starts + 1, self.col_cursor);
+        // the language header (`#!erg 0.7`) may follow a shebang
+        let is_shebang = col_begin == 0 && ln_begin <= 2 && self.peek_next_ch() == Some('!');
         while self.peek_cur_ch().map(|cur| cur != '\n').unwrap_or(false) {
             if Self::is_bidi(self.peek_cur_ch().unwrap()) {
-                let comment = self.emit_token(Illegal, &s);
+                let comment = self.emit_token_from(Illegal, start);
                 return Err(LexError::syntax_error(
                     line!() as usize,
                     comment.loc(),
@@ -374,13 +463,19 @@ impl Lexer /*<'a>*/ {
                     None,
                 ));
             }
-            s.push(self.consume().unwrap());
+            self.consume();
+        }
+        // `#!/usr/bin/env erg` is not a comment on the code (e.g. the document of the first definition)
+        if !is_shebang {
+            self.push_comment(CommentKind::Line, start, ln_begin, col_begin);
         }
         Ok(())
     }
 
     fn lex_multi_line_comment(&mut self) -> LexResult<()> {
-        let mut s = "".to_string();
+        let (start, ln_begin, col_begin) =
+            (self.cursor, self.lineno_token_starts + 1, self.col_cursor);
+        let mut line_start = start;
         let mut nest_level = 0;
         while let Some(c) = self.peek_cur_ch() {
             if let Some(next_c) = self.peek_next_ch() {
@@ -391,6 +486,7 @@ impl Lexer /*<'a>*/ {
                         if nest_level == 0 {
                             self.consume(); // ]
                             self.consume(); // #
+                            self.push_comment(CommentKind::Block, start, ln_begin, col_begin);
                             return Ok(());
                         }
                     }
@@ -399,13 +495,13 @@ impl Lexer /*<'a>*/ {
                 if c == '\n' {
                     self.lineno_token_starts += 1;
                     self.col_token_starts = 0;
-                    s.clear();
                     self.consume();
+                    line_start = self.cursor;
                     continue;
                 }
             }
             if Self::is_bidi(c) {
-                let comment = self.emit_token(Illegal, &s);
+                let comment = self.emit_token_from(Illegal, line_start);
                 return Err(LexError::syntax_error(
                     line!() as usize,
                     comment.loc(),
@@ -418,9 +514,9 @@ impl Lexer /*<'a>*/ {
                     None,
                 ));
             }
-            s.push(self.consume().unwrap());
+            self.consume();
         }
-        let comment = self.emit_token(Illegal, &s);
+        let comment = self.emit_token_from(Illegal, line_start);
         let hint = switch_lang!(
             "japanese" => format!("`]#`の数があと{nest_level}個必要です"),
             "simplified_chinese" => format!("需要{nest_level}个`]#`"),
@@ -448,24 +544,25 @@ impl Lexer /*<'a>*/ {
         let is_empty = is_space || is_linebreak;
         let is_toplevel = is_line_break_after && !is_empty;
         if is_toplevel {
-            let dedent = self.emit_token(Dedent, "");
+            let dedent = self.emit_static_token(Dedent, "");
             self.indent_stack.pop();
             self.col_token_starts = 0;
             return Some(Ok(dedent));
         } else if is_linebreak {
             self.consume();
-            let token = self.emit_token(Newline, "\n");
+            let token = self.emit_static_token(Newline, "\n");
             self.lineno_token_starts += 1;
             self.col_token_starts = 0;
             return Some(Ok(token));
         }
-        let mut spaces = "".to_string();
+        let spaces_start = self.cursor;
         while let Some(' ') = self.peek_cur_ch() {
-            spaces.push(self.consume().unwrap());
+            self.consume();
         }
+        let spaces_len = self.cursor - spaces_start;
         // indent in the first line: error
-        if !spaces.is_empty() && self.prev_token.is(BOF) {
-            let space = self.emit_token(Illegal, &spaces);
+        if spaces_len > 0 && self.prev_token.is(BOF) {
+            let space = self.emit_token_from(Illegal, spaces_start);
             Some(Err(LexError::syntax_error(
                 line!() as usize,
                 space.loc(),
@@ -478,19 +575,20 @@ impl Lexer /*<'a>*/ {
                 None,
             )))
         } else if self.prev_token.is(Newline) || self.prev_token.is(Dedent) {
-            self.lex_indent_dedent(spaces)
+            self.lex_indent_dedent(spaces_start)
         } else {
-            self.col_token_starts += spaces.len() as u32;
+            self.col_token_starts += spaces_len as u32;
             None
         }
     }
 
     /// The semantic correctness of the use of indent/dedent will be analyzed with `Parser`
-    fn lex_indent_dedent(&mut self, spaces: String) -> Option<LexResult<Token>> {
-        let spaces_len = spaces.len();
+    fn lex_indent_dedent(&mut self, spaces_start: usize) -> Option<LexResult<Token>> {
+        let spaces_end = self.cursor;
+        let spaces_len = spaces_end - spaces_start;
         // same as the CPython's limit
         if spaces_len > 100 {
-            let token = self.emit_token(Indent, &spaces);
+            let token = self.emit_token_from(Indent, spaces_start);
             return Some(Err(LexError::syntax_error(
                 line!() as usize,
                 token.loc(),
@@ -533,18 +631,19 @@ impl Lexer /*<'a>*/ {
             Ordering::Less => {
                 let indent_len = spaces_len.saturating_sub(sum_indent);
                 self.col_token_starts += sum_indent as u32;
-                let indent = self.emit_token(Indent, &" ".repeat(indent_len));
+                let indent = self.emit_slice_token(Indent, spaces_end - indent_len..spaces_end);
                 self.indent_stack.push(indent_len);
                 Some(Ok(indent))
             }
             Ordering::Greater => {
                 self.cursor -= spaces_len;
+                self.col_cursor -= spaces_len as u32;
                 self.indent_stack.pop();
                 if is_valid_dedent {
-                    let dedent = self.emit_token(Dedent, "");
+                    let dedent = self.emit_static_token(Dedent, "");
                     Some(Ok(dedent))
                 } else {
-                    let invalid_dedent = self.emit_token(Dedent, "");
+                    let invalid_dedent = self.emit_static_token(Dedent, "");
                     let hint = if self.peek_cur_ch() == Some('\n') {
                         Some("unnecessary spaces after linebreak".into())
                     } else { None };
@@ -568,22 +667,21 @@ impl Lexer /*<'a>*/ {
         }
     }
 
-    fn lex_exponent(&mut self, mantissa: String) -> LexResult<Token> {
-        let mut num = mantissa;
+    fn lex_exponent(&mut self, start: usize) -> LexResult<Token> {
         debug_power_assert!(self.peek_cur_ch(), ==, Some('e'));
-        num.push(self.consume().unwrap()); // e
+        self.consume(); // e
         if self.peek_cur_ch().is_some() {
-            num.push(self.consume().unwrap()); // + | -
+            self.consume(); // + | -
             while let Some(cur) = self.peek_cur_ch() {
                 if cur.is_ascii_digit() || cur == '_' {
-                    num.push(self.consume().unwrap());
+                    self.consume();
                 } else {
                     break;
                 }
             }
-            Ok(self.emit_token(RatioLit, &num))
+            Ok(self.emit_token_from(RatioLit, start))
         } else {
-            let token = self.emit_token(RatioLit, &num);
+            let token = self.emit_token_from(RatioLit, start);
             Err(LexError::syntax_error(
                 line!() as usize,
                 token.loc(),
@@ -600,25 +698,26 @@ impl Lexer /*<'a>*/ {
 
     /// `_` will be removed at compiletime
     fn lex_num(&mut self, first_ch: char) -> LexResult<Token> {
-        let mut num = first_ch.to_string();
+        let start = self.cursor - first_ch.len_utf8();
         while let Some(ch) = self.peek_cur_ch() {
             match ch {
                 // `.` may be a dot operator, don't consume
                 '.' => {
-                    return self.lex_num_dot(num);
+                    return self.lex_num_dot(start);
                 }
                 n if n.is_ascii_digit() || n == '_' => {
-                    num.push(self.consume().unwrap());
+                    self.consume();
                 }
                 c if Self::is_valid_continue_symbol_ch(c) => {
                     // exponent (e.g. 10e+3)
                     if c == 'e'
                         && (self.peek_next_ch() == Some('+') || self.peek_next_ch() == Some('-'))
                     {
-                        return self.lex_exponent(num);
+                        return self.lex_exponent(start);
                     } else {
                         // IntLit * Symbol(e.g. 3x + 1)
-                        let token = self.emit_token(Illegal, &(num + &c.to_string()));
+                        let num = &self.src[start..self.cursor];
+                        let token = self.emit_token(Illegal, &format!("{num}{c}"));
                         return Err(LexError::feature_error(
                             line!() as usize,
                             token.loc(),
@@ -631,86 +730,78 @@ impl Lexer /*<'a>*/ {
                 }
             }
         }
-        let kind = if num.starts_with('-') && !Self::is_zero(&num) {
+        Ok(self.emit_int_token(start))
+    }
+
+    fn emit_int_token(&mut self, start: usize) -> Token {
+        let num = &self.src[start..self.cursor];
+        let kind = if num.starts_with('-') && !Self::is_zero(num) {
             IntLit
         } else {
             NatLit
         };
-        Ok(self.emit_token(kind, &num))
+        self.emit_token_from(kind, start)
     }
 
     /// number '.' ~~
     /// Possibility: RatioLit or Int/NatLit call
-    fn lex_num_dot(&mut self, mut num: String) -> LexResult<Token> {
+    fn lex_num_dot(&mut self, start: usize) -> LexResult<Token> {
         match self.peek_next_ch() {
             // RatioLit
             Some(n) if n.is_ascii_digit() && !self.prev_token.is(Dot) => {
-                num.push(self.consume().unwrap());
-                self.lex_ratio(num)
+                self.consume();
+                self.lex_ratio(start)
             }
             // method call of IntLit
             // or range operator (e.g. 1..)
             Some(c) if Self::is_valid_continue_symbol_ch(c) || c == '.' => {
-                let kind = if num.starts_with('-') && !Self::is_zero(&num) {
-                    IntLit
-                } else {
-                    NatLit
-                };
-                Ok(self.emit_token(kind, &num))
+                Ok(self.emit_int_token(start))
             }
             Some('_') => {
                 self.consume();
-                let token = self.emit_token(Illegal, &(num + "_"));
+                let num = &self.src[start..self.cursor - 1];
+                let token = self.emit_token(Illegal, &format!("{num}_"));
                 Err(LexError::simple_syntax_error(0, token.loc()))
             }
             // RatioLit without zero (e.g. 3.)
             _ => {
-                num.push(self.consume().unwrap());
-                self.lex_ratio(num)
+                self.consume();
+                self.lex_ratio(start)
             }
         }
     }
 
-    /// int_part_and_point must be like `12.`
-    fn lex_ratio(&mut self, intpart_and_point: String) -> LexResult<Token> {
-        let mut num = intpart_and_point;
+    /// The source code from `start` to the cursor must be like `12.`
+    fn lex_ratio(&mut self, start: usize) -> LexResult<Token> {
         while let Some(cur) = self.peek_cur_ch() {
             if cur.is_ascii_digit() || cur == '_' {
-                num.push(self.consume().unwrap());
+                self.consume();
             } else if cur == 'e' {
-                return self.lex_exponent(num);
+                return self.lex_exponent(start);
             } else {
                 break;
             }
         }
-        Ok(self.emit_token(RatioLit, &num))
+        Ok(self.emit_token_from(RatioLit, start))
     }
 
     fn lex_symbol(&mut self, first_ch: char) -> LexResult<Token> {
-        let mut cont = first_ch.to_string();
+        let start = self.cursor - first_ch.len_utf8();
         while let Some(c) = self.peek_cur_ch() {
             if Self::is_valid_continue_symbol_ch(c) {
-                cont.push(self.consume().unwrap());
+                self.consume();
             } else {
                 break;
             }
         }
         if let Some('!') = self.peek_cur_ch() {
-            cont.push(self.consume().unwrap());
-        }
-        if cont.is_empty() {
-            let token = self.emit_token(Illegal, &self.peek_cur_ch().unwrap().to_string());
-            return Err(LexError::compiler_bug(
-                0,
-                token.loc(),
-                fn_name_full!(),
-                line!(),
-            ));
+            self.consume();
         }
+        let cont = &self.src[start..self.cursor];
         // dot: scalar product, cross: vector product
         // An alphabetical operator can also declare as a function, so checking is necessary
         // e.g. and(true, true, true) = true
-        let kind = match &cont[..] {
+        let kind = match cont {
             "and" => AndOp,
             "as" => As,
             "or" => OrOp,
@@ -730,7 +821,7 @@ impl Lexer /*<'a>*/ {
             "_" => UBar,
             _ => Symbol,
         };
-        Ok(self.emit_token(kind, &cont))
+        Ok(self.emit_token_from(kind, start))
     }
 
     fn str_line_break_error(token: Token, line: usize) -> LexError {
@@ -822,70 +913,126 @@ impl Lexer /*<'a>*/ {
         )
     }
 
+    /// Emits the string literal from `start` to the cursor.
+    /// `unescaped` is the content if the literal has escape sequences (then it differs from the source code).
+    fn emit_str_token(
+        &mut self,
+        kind: TokenKind,
+        start: usize,
+        unescaped: Option<String>,
+    ) -> Token {
+        let token = match unescaped {
+            Some(s) => self.emit_token(kind, &s),
+            None => self.emit_token_from(kind, start),
+        };
+        // the content may be shorter than the source code (escape sequences) or span multiple lines
+        self.col_token_starts = self.col_cursor;
+        token
+    }
+
+    fn str_content(&self, start: usize, unescaped: &Option<String>) -> String {
+        match unescaped {
+            Some(s) => s.clone(),
+            None => self.src[start..self.cursor].to_string(),
+        }
+    }
+
+    /// Replaces the escape sequence just consumed (`esc_len` bytes) with `unescaped_str`.
+    /// The content is copied from the source code at the first escape sequence.
+    fn unescape(
+        &self,
+        start: usize,
+        unescaped: &mut Option<String>,
+        esc_len: usize,
+        unescaped_str: &str,
+    ) {
+        unescaped
+            .get_or_insert_with(|| self.src[start..self.cursor - esc_len].to_string())
+            .push_str(unescaped_str);
+    }
+
     fn lex_single_str(&mut self) -> LexResult<Token> {
-        let mut s = "\"".to_string();
+        // the opening quote has been consumed
+        let start = self.cursor - 1;
+        // `None` while no escape sequence appears (the token refers to the source code)
+        let mut unescaped: Option<String> = None;
         while let Some(c) = self.peek_cur_ch() {
             match c {
                 '\n' => match self.interpol_stack.last().unwrap() {
                     Interpolation::SingleLine if self.interpol_stack.len() == 1 => {
-                        let token = self.emit_token(Illegal, &s);
+                        let token = self.emit_str_token(Illegal, start, unescaped);
                         return Err(Self::str_line_break_error(token, line!() as usize));
                     }
                     _ => {
-                        let token = self.emit_token(Illegal, &s);
+                        let token = self.emit_str_token(Illegal, start, unescaped);
                         return Err(Self::unclosed_interpol_error(token));
                     }
                 },
                 '"' => {
-                    s.push(self.consume().unwrap());
-                    let token = self.emit_token(StrLit, &s);
+                    let c = self.consume().unwrap();
+                    if let Some(s) = unescaped.as_mut() {
+                        s.push(c);
+                    }
+                    let token = self.emit_str_token(StrLit, start, unescaped);
                     return Ok(token);
                 }
                 _ => {
                     let c = self.consume().unwrap();
                     if c == '\\' {
                         let next_c = self.consume().unwrap();
-                        match next_c {
+                        let unescaped_str = match next_c {
                             '{' => {
-                                s.push_str("\\{");
+                                if let Some(s) = unescaped.as_mut() {
+                                    s.push_str("\\{");
+                                }
                                 self.interpol_stack.push(Interpolation::SingleLine);
-                                let token = self.emit_token(StrInterpLeft, &s);
+                                let token = self.emit_str_token(StrInterpLeft, start, unescaped);
                                 return Ok(token);
                             }
-                            '0' => s.push('\0'),
-                            'r' => s.push('\r'),
-                            'n' => s.push('\n'),
-                            '\'' => s.push('\''),
-                            '"' => s.push('"'),
-                            't' => s.push_str("    "), // tab is invalid, so changed into 4 whitespace
-                            '\\' => s.push('\\'),
+                            '0' => "\0",
+                            'r' => "\r",
+                            'n' => "\n",
+                            '\'' => "\'",
+                            '"' => "\"",
+                            't' => "    ", // tab is invalid, so changed into 4 whitespace
+                            '\\' => "\\",
                             _ => {
                                 let token = self.emit_token(Illegal, &format!("\\{next_c}"));
                                 return Err(Self::invalid_escape_error(next_c, token));
                             }
-                        }
+                        };
+                        self.unescape(start, &mut unescaped, 2, unescaped_str);
                     } else {
-                        s.push(c);
+                        if let Some(s) = unescaped.as_mut() {
+                            s.push(c);
+                        }
                         if Self::is_bidi(c) {
+                            let s = self.str_content(start, &unescaped);
                             return Err(self.invalid_unicode_character(&s));
                         }
                     }
                 }
             }
         }
-        let token = self.emit_token(Illegal, &s);
+        let token = self.emit_str_token(Illegal, start, unescaped);
         Err(Self::unclosed_string_error(token, "\"", line!() as usize))
     }
 
     fn lex_multi_line_str(&mut self, quote: Quote) -> LexResult<Token> {
-        let mut s = quote.quotes().to_string();
+        // the opening quotes have been consumed
+        let start = self.cursor - 3;
+        // `None` while no escape sequence appears (the token refers to the source code)
+        let mut unescaped: Option<String> = None;
         while let Some(c) = self.peek_cur_ch() {
             if c == quote.char() {
                 let c = self.consume().unwrap();
+                if let Some(s) = unescaped.as_mut() {
+                    s.push(c);
+                }
                 let next_c = self.peek_cur_ch();
                 let aft_next_c = self.peek_next_ch();
                 if next_c.is_none() {
-                    let token = self.emit_token(Illegal, &s);
+                    let token = self.emit_str_token(Illegal, start, unescaped);
                     return Err(Self::unclosed_string_error(
                         token,
                         quote.quotes(),
@@ -893,8 +1040,11 @@ impl Lexer /*<'a>*/ {
                     ));
                 }
                 if aft_next_c.is_none() {
-                    s.push(self.consume().unwrap());
-                    let token = self.emit_token(Illegal, &s);
+                    let c = self.consume().unwrap();
+                    if let Some(s) = unescaped.as_mut() {
+                        s.push(c);
+                    }
+                    let token = self.emit_str_token(Illegal, start, unescaped);
                     return Err(Self::unclosed_string_error(
                         token,
                         quote.quotes(),
@@ -904,57 +1054,67 @@ impl Lexer /*<'a>*/ {
                 if next_c.unwrap() == quote.char() && aft_next_c.unwrap() == quote.char() {
                     self.consume().unwrap();
                     self.consume().unwrap();
-                    s.push_str(quote.quotes());
-                    let token = self.emit_token(quote.token_kind(), &s);
+                    if let Some(s) = unescaped.as_mut() {
+                        s.push(quote.char());
+                        s.push(quote.char());
+                    }
+                    let token = self.emit_str_token(quote.token_kind(), start, unescaped);
                     return Ok(token);
                 }
                 // else unclosed_string_error
-                s.push(c);
             } else {
                 let c = self.consume().unwrap();
                 match c {
                     '\\' => {
                         let next_c = self.consume().unwrap();
-                        match next_c {
+                        let unescaped_str = match next_c {
                             '{' => {
-                                s.push_str("\\{");
+                                if let Some(s) = unescaped.as_mut() {
+                                    s.push_str("\\{");
+                                }
                                 self.interpol_stack.push(Interpolation::MultiLine(quote));
-                                let token = self.emit_token(StrInterpLeft, &s);
+                                let token = self.emit_str_token(StrInterpLeft, start, unescaped);
                                 return Ok(token);
                             }
-                            '0' => s.push('\0'),
-                            'r' => s.push('\r'),
-                            '\'' => s.push('\''),
-                            '\"' => s.push('\"'),
-                            't' => s.push_str("    "), // tab is invalid, so changed into 4 whitespace
-                            '\\' => s.push('\\'),
-                            'n' => s.push('\n'),
+                            '0' => "\0",
+                            'r' => "\r",
+                            '\'' => "\'",
+                            '\"' => "\"",
+                            't' => "    ", // tab is invalid, so changed into 4 whitespace
+                            '\\' => "\\",
+                            'n' => "\n",
                             '\n' => {
                                 self.lineno_token_starts += 1;
                                 self.col_token_starts = 0;
-                                continue;
+                                ""
                             }
                             _ => {
                                 let token = self.emit_token(Illegal, &format!("\\{next_c}"));
                                 return Err(Self::invalid_escape_error(next_c, token));
                             }
-                        }
+                        };
+                        self.unescape(start, &mut unescaped, 2, unescaped_str);
                     }
                     '\n' => {
                         self.lineno_token_starts += 1;
                         self.col_token_starts = 0;
-                        s.push('\n');
+                        if let Some(s) = unescaped.as_mut() {
+                            s.push('\n');
+                        }
                     }
                     _ => {
-                        s.push(c);
+                        if let Some(s) = unescaped.as_mut() {
+                            s.push(c);
+                        }
                         if Self::is_bidi(c) {
+                            let s = self.str_content(start, &unescaped);
                             return Err(self.invalid_unicode_character(&s));
                         }
                     }
                 }
             }
         }
-        let token = self.emit_token(Illegal, &s);
+        let token = self.emit_str_token(Illegal, start, unescaped);
         if self.interpol_stack.len() == 1 {
             Err(Self::unclosed_string_error(
                 token,
@@ -1219,7 +1379,7 @@ impl Iterator for Lexer /*<'a>*/ {
                 // prev_token can be a receiver => TupleAttribute (e.g. t.0)
                 // else: RatioLit (e.g. .0)
                 Some(c) if c.is_ascii_digit() && !self.prev_can_be_receiver() => {
-                    Some(self.lex_ratio(".".into()))
+                    Some(self.lex_ratio(self.cursor - 1))
                 }
                 _ => self.accept(Dot, "."),
             },
@@ -1334,7 +1494,15 @@ impl Iterator for Lexer /*<'a>*/ {
                 // TODO: infix/prefix
                 Some('*') => {
                     self.consume();
-                    self.accept(Pow, "**")
+                    // `f(**kwargs)`
+                    if self.prev_token.category_is(TokenCategory::LEnclosure)
+                        || self.prev_token.category_is(TokenCategory::Separator)
+                        || self.prev_token.is(Comma)
+                    {
+                        self.accept(PreDblStar, "**")
+                    } else {
+                        self.accept(Pow, "**")
+                    }
                 }
                 _ => {
                     let kind = match self.op_fix() {
@@ -1388,7 +1556,7 @@ impl Iterator for Lexer /*<'a>*/ {
             }
             Some('\\') => match self.peek_cur_ch() {
                 Some('\n') => {
-                    self.cursor += 1;
+                    self.consume();
                     self.lineno_token_starts += 1;
                     self.col_token_starts = 0;
                     self.next()
@@ -1486,7 +1654,7 @@ impl Iterator for Lexer /*<'a>*/ {
                 while let Some(c) = self.consume() {
                     if c == '`' {
                         if Self::is_definable_operator(&op[..]) {
-                            return self.accept(Symbol, &op);
+                            return Some(Ok(self.emit_token(Symbol, &op)));
                         } else {
                             let token = self.emit_token(Illegal, &op);
                             let hint = if op.contains('+') {
@@ -8,10 +8,14 @@ pub mod ast;
 pub mod build_ast;
 pub mod convert;
 pub mod desugar;
+pub mod dump;
 pub mod error;
+pub mod incremental;
 pub mod lex;
 pub mod parse;
 pub mod token;
+pub mod trivia;
 pub mod typespec;
+pub mod visitor;
 
 pub use parse::{Parser, ParserRunner};
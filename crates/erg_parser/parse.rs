@@ -5,6 +5,7 @@
 use std::mem;
 
 use erg_common::config::ErgConfig;
+use erg_common::edition::{Edition, LangOptions};
 use erg_common::error::Location;
 use erg_common::io::{Input, InputKind};
 use erg_common::set::Set as HashSet;
@@ -23,6 +24,7 @@ use crate::error::{
 };
 use crate::lex::Lexer;
 use crate::token::{Token, TokenCategory, TokenKind, TokenStream};
+use crate::trivia::{RawTrivia, TriviaMap};
 
 use TokenCategory as TC;
 use TokenKind::*;
@@ -106,16 +108,35 @@ pub struct SimpleParser {}
 
 impl Parsable for SimpleParser {
     fn parse(code: String) -> Result<CompleteArtifact, IncompleteArtifact> {
-        let ts = Lexer::from_str(code).lex()?;
-        let mut parser = Parser::new(ts);
+        let (lang, header_err) = Parser::lang_of(&code);
+        let (ts, trivia, lex_errs) = Lexer::from_str(code).lex_recovering();
+        let mut parser = Parser::new_with_lang(ts, lang);
+        if let Some(err) = header_err {
+            parser.errs.push(err);
+        }
         let mut desugarer = Desugarer::new();
-        let artifact = parser
-            .parse()
+        let artifact = attach_trivia(parser.parse_recovering(lex_errs), trivia)
             .map_err(|iart| iart.map_mod(|module| desugarer.desugar(module)))?;
         Ok(artifact.map(|module| desugarer.desugar(module)))
     }
 }
 
+/// Attaches the trivia collected by the lexer to the parsed module
+pub fn attach_trivia<Errs>(
+    result: Result<CompleteArtifact, IncompleteArtifact<Module, Errs>>,
+    trivia: RawTrivia,
+) -> Result<CompleteArtifact, IncompleteArtifact<Module, Errs>> {
+    let attach = |mut module: Module| {
+        let map = TriviaMap::new(&module, trivia);
+        module.set_trivia(map);
+        module
+    };
+    match result {
+        Ok(artifact) => Ok(artifact.map(attach)),
+        Err(iart) => Err(iart.map_mod(attach)),
+    }
+}
+
 enum ExprOrOp {
     Expr(Expr),
     Op(Token),
@@ -125,6 +146,7 @@ enum ArgKind {
     Pos(PosArg),
     Var(PosArg),
     Kw(KwArg),
+    KwVar(PosArg),
 }
 
 pub enum ArrayInner {
@@ -189,22 +211,41 @@ impl ArgsStyle {
 pub struct Parser {
     counter: DefId,
     pub(super) level: usize, // nest level (for debugging)
+    /// `true` while parsing the argument of `Enum` (the variant syntax `{A; B(Int)}` is only allowed there)
+    in_enum_variants: bool,
+    /// the edition and the features of the module (see `erg_common::edition`)
+    pub(crate) lang: LangOptions,
     tokens: TokenStream,
     warns: ParseErrors,
     pub(crate) errs: ParseErrors,
 }
 
 impl Parser {
+    /// Parses `ts` in the current edition. Use `Parser::new_with_lang` for a module that may have a header.
     pub const fn new(ts: TokenStream) -> Self {
+        Self::new_with_lang(ts, LangOptions::new(Edition::CURRENT))
+    }
+
+    pub const fn new_with_lang(ts: TokenStream, lang: LangOptions) -> Self {
         Self {
             counter: DefId(0),
             level: 0,
+            in_enum_variants: false,
+            lang,
             tokens: ts,
             warns: ParseErrors::empty(),
             errs: ParseErrors::empty(),
         }
     }
 
+    /// `DefId`s are numbered from `base`.
+    /// This is used to avoid conflicts with the `DefId`s of the reused part of an AST.
+    pub(crate) const fn with_def_id_base(ts: TokenStream, base: DefId, lang: LangOptions) -> Self {
+        let mut parser = Self::new_with_lang(ts, lang);
+        parser.counter = base;
+        parser
+    }
+
     #[inline]
     pub fn peek(&self) -> Option<&Token> {
         self.tokens.first()
@@ -243,6 +284,29 @@ impl Parser {
         self.nth(idx).map(|t| t.is(kind)).unwrap_or(false)
     }
 
+    /// Whether the parenthesized group starting at the current token is followed by `:=`.
+    /// e.g. `if (i: Int) := x, ...` is not a call with parentheses.
+    fn paren_is_followed_by_walrus(&self) -> bool {
+        let mut depth = 0usize;
+        for (idx, token) in self.tokens.iter().enumerate() {
+            match token.kind {
+                LParen => depth += 1,
+                RParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return self.nth_is(idx + 1, Walrus);
+                    }
+                }
+                Newline | EOF if depth == 0 => return false,
+                _ => {}
+            }
+            if depth == 0 {
+                return false;
+            }
+        }
+        false
+    }
+
     /// 解析を諦めて次の解析できる要素に移行する
     /// give up parsing and move to the next element that can be parsed
     fn next_expr(&mut self) {
@@ -300,6 +364,37 @@ impl Parser {
         }
     }
 
+    /// Skips the rest of the chunk that failed to be parsed (started from `start`),
+    /// and returns an error node (an empty `Dummy`) in place of it.
+    ///
+    /// The parser is resynchronized on the next line that starts at the same (or shallower) indentation as `start`.
+    /// If `in_block` is true, a dedent that closes the current block is not consumed.
+    fn recover_chunk(&mut self, start: &Token, in_block: bool) -> Expr {
+        let mut end = start.loc();
+        let mut depth = 0usize;
+        while let Some(t) = self.peek() {
+            match t.kind {
+                EOF => break,
+                Indent => depth += 1,
+                Dedent if depth == 0 => {
+                    if in_block {
+                        break;
+                    }
+                }
+                Dedent => depth -= 1,
+                Newline => {}
+                _ if depth == 0 && t.lineno > start.lineno && t.col_begin <= start.col_begin => {
+                    break;
+                }
+                _ => {
+                    end = t.loc();
+                }
+            }
+            self.skip();
+        }
+        Expr::Dummy(Dummy::new(Some(Location::concat(start, &end)), vec![]))
+    }
+
     fn unexpected_none(&self, errno: u32, caused_by: &str) -> ParseError {
         log!(err "error caused by: {caused_by}");
         ParseError::invalid_none_match(0, Location::Unknown, file!(), errno)
@@ -439,7 +534,7 @@ impl ParserRunner {
         &mut self,
         ts: TokenStream,
     ) -> Result<CompleteArtifact, IncompleteArtifact<Module, ParserRunnerErrors>> {
-        Parser::new(ts)
+        Parser::new_with_lang(ts, self.cfg.lang.clone())
             .parse()
             .map_err(|iart| iart.map_errs(|errs| ParserRunnerErrors::convert(self.input(), errs)))
     }
@@ -448,16 +543,52 @@ impl ParserRunner {
         &mut self,
         src: String,
     ) -> Result<CompleteArtifact, IncompleteArtifact<Module, ParserRunnerErrors>> {
-        let ts = Lexer::new(Input::new(InputKind::Str(src), self.cfg.input.id()))
-            .lex()
-            .map_err(|errs| ParserRunnerErrors::convert(self.input(), errs))?;
-        Parser::new(ts)
-            .parse()
+        let (lang, header_err) = Parser::lang_of(&src);
+        self.cfg.lang = lang.clone();
+        let (ts, trivia, lex_errs) =
+            Lexer::new(Input::new(InputKind::Str(src), self.cfg.input.id())).lex_recovering();
+        let mut parser = Parser::new_with_lang(ts, lang);
+        if let Some(err) = header_err {
+            parser.errs.push(err);
+        }
+        attach_trivia(parser.parse_recovering(lex_errs), trivia)
             .map_err(|iart| iart.map_errs(|errs| ParserRunnerErrors::convert(self.input(), errs)))
     }
 }
 
 impl Parser {
+    /// Returns the language options specified by the header of `src`.
+    /// If the header is invalid, the default options and the error are returned.
+    pub fn lang_of(src: &str) -> (LangOptions, Option<ParseError>) {
+        match LangOptions::parse_header(src) {
+            Some((_, Ok(lang))) => (lang, None),
+            Some((lineno, Err(reason))) => {
+                let loc = Location::Line(lineno);
+                let err = ParseError::invalid_header_error(line!() as usize, loc, &reason);
+                (LangOptions::default(), Some(err))
+            }
+            None => (LangOptions::default(), None),
+        }
+    }
+
+    /// Parses the token stream of a source code that may have failed to be lexed.
+    /// If `lex_errs` is not empty, only they are reported (the syntax errors would be their consequences),
+    /// but the partial AST is still returned.
+    pub fn parse_recovering(
+        &mut self,
+        lex_errs: ParseErrors,
+    ) -> Result<CompleteArtifact, IncompleteArtifact> {
+        let result = self.parse();
+        if lex_errs.is_empty() {
+            return result;
+        }
+        let (ast, warns) = match result {
+            Ok(artifact) => (Some(artifact.ast), artifact.warns),
+            Err(iart) => (iart.ast, iart.warns),
+        };
+        Err(IncompleteArtifact::new(ast, warns, lex_errs))
+    }
+
     pub fn parse(&mut self) -> Result<CompleteArtifact, IncompleteArtifact> {
         if self.tokens.is_empty() {
             return Ok(CompleteArtifact::new(Module::empty(), ParseErrors::empty()));
@@ -511,16 +642,22 @@ impl Parser {
                     break;
                 }
                 Some(_) => {
-                    if let Ok(expr) = self.try_reduce_chunk(true, false) {
-                        if !self.cur_is(EOF) && !self.cur_category_is(TC::Separator) {
-                            let err = self.skip_and_throw_invalid_chunk_err(
-                                caused_by!(),
-                                line!(),
-                                expr.loc(),
-                            );
-                            self.errs.push(err);
+                    let start = self.peek().unwrap().clone();
+                    match self.try_reduce_chunk(true, false) {
+                        Ok(expr) => {
+                            if !self.cur_is(EOF) && !self.cur_category_is(TC::Separator) {
+                                let err = self.skip_and_throw_invalid_chunk_err(
+                                    caused_by!(),
+                                    line!(),
+                                    expr.loc(),
+                                );
+                                self.errs.push(err);
+                            }
+                            chunks.push(expr);
+                        }
+                        Err(_) => {
+                            chunks.push(self.recover_chunk(&start, false));
                         }
-                        chunks.push(expr);
                     }
                 }
                 None => {
@@ -598,17 +735,23 @@ impl Parser {
                     break;
                 }
                 Some(_) => {
-                    if let Ok(expr) = self.try_reduce_chunk(true, false) {
-                        if !self.cur_is(Dedent) && !self.cur_category_is(TC::Separator) {
-                            let err = self.skip_and_throw_invalid_chunk_err(
-                                caused_by!(),
-                                line!(),
-                                expr.loc(),
-                            );
-                            debug_exit_info!(self);
-                            self.errs.push(err);
+                    let start = self.peek().unwrap().clone();
+                    match self.try_reduce_chunk(true, false) {
+                        Ok(expr) => {
+                            if !self.cur_is(Dedent) && !self.cur_category_is(TC::Separator) {
+                                let err = self.skip_and_throw_invalid_chunk_err(
+                                    caused_by!(),
+                                    line!(),
+                                    expr.loc(),
+                                );
+                                debug_exit_info!(self);
+                                self.errs.push(err);
+                            }
+                            block.push(expr);
+                        }
+                        Err(_) => {
+                            block.push(self.recover_chunk(&start, true));
                         }
-                        block.push(expr);
                     }
                 }
                 None => {
@@ -799,6 +942,16 @@ impl Parser {
 
     fn try_reduce_array_elems(&mut self) -> ParseResult<ArrayInner> {
         debug_call_info!(self);
+        // e.g.
+        // [
+        //     1,
+        //     2,
+        // ]
+        let multi_line = self.cur_is(Newline);
+        if multi_line {
+            self.skip();
+            expect_pop!(self, fail_next Indent);
+        }
         if self.cur_category_is(TC::REnclosure) {
             let args = Args::empty();
             debug_exit_info!(self);
@@ -838,6 +991,7 @@ impl Parser {
                 return Err(());
             }
             Some(RParen | RSqBr | RBrace | Dedent | Comma) => {}
+            Some(Newline) if multi_line => {}
             Some(_) => {
                 let elem = self
                     .try_reduce_elem()
@@ -865,6 +1019,16 @@ impl Parser {
                         debug_exit_info!(self);
                         return Err(());
                     }
+                    if multi_line {
+                        while self.cur_is(Newline) {
+                            self.skip();
+                        }
+                        // trailing comma
+                        if self.cur_is(Dedent) {
+                            self.skip();
+                            break;
+                        }
+                    }
                     elems.push_pos(
                         self.try_reduce_elem()
                             .map_err(|_| self.stack_dec(fn_name!()))?,
@@ -873,6 +1037,13 @@ impl Parser {
                 Some(RParen | RSqBr | RBrace | Dedent) => {
                     break;
                 }
+                Some(Newline) if multi_line => {
+                    while self.cur_is(Newline) {
+                        self.skip();
+                    }
+                    expect_pop!(self, fail_next Dedent);
+                    break;
+                }
                 Some(_other) => {
                     let err = self.skip_and_throw_invalid_unclosed_err(
                         caused_by!(),
@@ -952,7 +1123,7 @@ impl Parser {
         debug_call_info!(self);
         let mut lp = None;
         let rp;
-        if self.cur_is(LParen) {
+        if self.cur_is(LParen) && !self.paren_is_followed_by_walrus() {
             lp = Some(self.lpop());
         }
         let mut style = if lp.is_some() {
@@ -986,6 +1157,11 @@ impl Parser {
             ArgKind::Pos(arg) => Args::single(arg),
             ArgKind::Var(arg) => Args::new(vec![], Some(arg), vec![], None),
             ArgKind::Kw(arg) => Args::new(vec![], None, vec![arg], None),
+            ArgKind::KwVar(arg) => {
+                let mut args = Args::empty();
+                args.set_kw_var_args(arg);
+                args
+            }
         };
         loop {
             match self.peek_kind() {
@@ -1029,7 +1205,7 @@ impl Parser {
                         args.set_parens((lp.unwrap(), rp));
                         break;
                     }
-                    if !args.kw_is_empty() {
+                    if !args.kw_is_empty() && !self.cur_is(PreDblStar) {
                         args.push_kw(
                             self.try_reduce_kw_arg(in_type_args)
                                 .map_err(|_| self.stack_dec(fn_name!()))?,
@@ -1048,6 +1224,9 @@ impl Parser {
                             ArgKind::Kw(arg) => {
                                 args.push_kw(arg);
                             }
+                            ArgKind::KwVar(var) => {
+                                args.set_kw_var_args(var);
+                            }
                         }
                     }
                 }
@@ -1057,8 +1236,7 @@ impl Parser {
                         args.set_parens((lp, rp));
                     } else {
                         // e.g. f(g 1)
-                        let (pos_args, var_args, kw_args, _) = args.deconstruct();
-                        args = Args::new(pos_args, var_args, kw_args, None);
+                        args.paren = None;
                     }
                     break;
                 }
@@ -1097,7 +1275,7 @@ impl Parser {
                     break;
                 }
                 Some(_) if style.is_colon() => {
-                    if !args.kw_is_empty() {
+                    if !args.kw_is_empty() && !self.cur_is(PreDblStar) {
                         args.push_kw(
                             self.try_reduce_kw_arg(in_type_args)
                                 .map_err(|_| self.stack_dec(fn_name!()))?,
@@ -1116,6 +1294,9 @@ impl Parser {
                             ArgKind::Kw(arg) => {
                                 args.push_kw(arg);
                             }
+                            ArgKind::KwVar(var) => {
+                                args.set_kw_var_args(var);
+                            }
                         }
                     }
                 }
@@ -1249,10 +1430,26 @@ impl Parser {
                 debug_exit_info!(self);
                 Ok(ArgKind::Var(PosArg::new(expr)))
             }
+            Some(PreDblStar) => {
+                self.skip();
+                let expr = self
+                    .try_reduce_expr(false, in_type_args, false, false)
+                    .map_err(|_| self.stack_dec(fn_name!()))?;
+                debug_exit_info!(self);
+                Ok(ArgKind::KwVar(PosArg::new(expr)))
+            }
             Some(_) => {
                 let expr = self
                     .try_reduce_expr(false, in_type_args, false, false)
                     .map_err(|_| self.stack_dec(fn_name!()))?;
+                // conditional binding (e.g. `if (i: Int) := x, do: ...`)
+                if self.cur_is(Walrus) && !in_type_args {
+                    let binding = self
+                        .try_reduce_binding(expr)
+                        .map_err(|_| self.stack_dec(fn_name!()))?;
+                    debug_exit_info!(self);
+                    return Ok(ArgKind::Pos(PosArg::new(Expr::Lambda(binding))));
+                }
                 debug_exit_info!(self);
                 Ok(ArgKind::Pos(PosArg::new(expr)))
             }
@@ -1264,6 +1461,33 @@ impl Parser {
         }
     }
 
+    /// `pattern := expr` is represented as a lambda whose operator is `:=` and whose body is `expr`.
+    /// It is desugared into `match` by `Desugarer::desugar_conditional_binding`.
+    fn try_reduce_binding(&mut self, pattern: Expr) -> ParseResult<Lambda> {
+        debug_call_info!(self);
+        let op = self.lpop();
+        debug_power_assert!(op.is(Walrus));
+        if !self.lang.is_enabled("conditional_binding") {
+            let err = ParseError::unavailable_feature_error(
+                line!() as usize,
+                op.loc(),
+                "conditional_binding",
+                self.lang.edition,
+            );
+            self.errs.push(err);
+        }
+        let param = self
+            .convert_rhs_to_param(pattern, false)
+            .map_err(|_| self.stack_dec(fn_name!()))?;
+        let sig = LambdaSignature::new(Params::single(param), None, TypeBoundSpecs::empty());
+        let expr = self
+            .try_reduce_expr(false, false, false, false)
+            .map_err(|_| self.stack_dec(fn_name!()))?;
+        self.counter.inc();
+        debug_exit_info!(self);
+        Ok(Lambda::new(sig, op, Block::new(vec![expr]), self.counter))
+    }
+
     fn try_reduce_kw_arg(&mut self, in_type_args: bool) -> ParseResult<KwArg> {
         debug_call_info!(self);
         match self.peek() {
@@ -1539,6 +1763,14 @@ impl Parser {
                     let op = self.lpop();
                     let is_multiline_block = self.cur_is(Newline);
                     let lhs = enum_unwrap!(stack.pop(), Some:(ExprOrOp::Expr:(_)));
+                    // `obj.x = v` (an assignment to a property)
+                    if let Expr::Accessor(attr @ Accessor::Attr(_)) = lhs {
+                        let expr = self
+                            .try_reduce_expr(true, false, false, false)
+                            .map_err(|_| self.stack_dec(fn_name!()))?;
+                        debug_exit_info!(self);
+                        return Ok(Expr::ReDef(ReDef::new(attr, expr)));
+                    }
                     let sig = self
                         .convert_rhs_to_sig(lhs)
                         .map_err(|_| self.stack_dec(fn_name!()))?;
@@ -2122,6 +2354,18 @@ impl Parser {
                     return Err(());
                 }
             }
+            // `{.name; .age} := r`
+            pattern @ (Expr::Tuple(_) | Expr::Record(_) | Expr::Array(_)) => {
+                let binding = self
+                    .try_reduce_binding(pattern)
+                    .map_err(|_| self.stack_dec(fn_name!()))?;
+                let first_elem = ArgKind::Pos(PosArg::new(Expr::Lambda(binding)));
+                let tuple = self
+                    .try_reduce_nonempty_tuple(first_elem, self.nth_is(1, Newline))
+                    .map_err(|_| self.stack_dec(fn_name!()))?;
+                debug_exit_info!(self);
+                return Ok(tuple);
+            }
             other => {
                 let caused_by = caused_by!();
                 log!(err "error caused by: {caused_by}");
@@ -2337,6 +2581,14 @@ impl Parser {
                 if let Expr::Tuple(Tuple::Normal(tup)) = &mut expr {
                     tup.elems.paren = Some((lparen, rparen));
                 }
+                // `(i: Int) := x`
+                if self.cur_is(Walrus) {
+                    let binding = self
+                        .try_reduce_binding(expr)
+                        .map_err(|_| self.stack_dec(fn_name!()))?;
+                    debug_exit_info!(self);
+                    return Ok(Expr::Lambda(binding));
+                }
                 debug_exit_info!(self);
                 Ok(expr)
             }
@@ -2424,7 +2676,18 @@ impl Parser {
         let mut call_or_acc = self
             .try_reduce_acc_chain(acc, in_type_args)
             .map_err(|_| self.stack_dec(fn_name!()))?;
-        while let Some(res) = self.opt_reduce_args(in_type_args) {
+        // the variants of `Enum {A; B(Int)}` are parsed specially
+        let mut is_enum = matches!(
+            &call_or_acc,
+            Expr::Accessor(Accessor::Ident(ident)) if &ident.inspect()[..] == "Enum"
+        );
+        loop {
+            self.in_enum_variants = mem::take(&mut is_enum);
+            let res = self.opt_reduce_args(in_type_args);
+            self.in_enum_variants = false;
+            let Some(res) = res else {
+                break;
+            };
             let args = res.map_err(|_| self.stack_dec(fn_name!()))?;
             let call = call_or_acc.call(args);
             call_or_acc = Expr::Call(call);
@@ -2507,6 +2770,30 @@ impl Parser {
                             );
                             obj = obj.attr_expr(ident);
                         }
+                        // e.g. `R::.name` ==> `lens(R, "name")`
+                        Dot if self.cur_is(Symbol) => {
+                            let field = self.lpop();
+                            let lens = Token::new(Symbol, "lens", vis.lineno, vis.col_begin);
+                            let lens = Identifier::private_from_token(lens);
+                            let field = Token::new(
+                                StrLit,
+                                format!("\"{}\"", field.content),
+                                field.lineno,
+                                field.col_begin,
+                            );
+                            let args = Args::pos_only(
+                                vec![
+                                    PosArg::new(obj),
+                                    PosArg::new(Expr::Literal(Literal::from(field))),
+                                ],
+                                None,
+                            );
+                            obj = Expr::Call(Call::new(
+                                Expr::Accessor(Accessor::Ident(lens)),
+                                None,
+                                args,
+                            ));
+                        }
                         LBrace => {
                             self.restore(token);
                             let args = self
@@ -2638,6 +2925,8 @@ impl Parser {
     /// Set, Dict, Record
     fn try_reduce_brace_container(&mut self) -> ParseResult<BraceContainer> {
         debug_call_info!(self);
+        // nested braces are not variants
+        let in_enum_variants = mem::take(&mut self.in_enum_variants);
         let l_brace = expect_pop!(self, fail_next LBrace);
         if self.cur_is(Newline) {
             self.skip();
@@ -2648,6 +2937,13 @@ impl Parser {
         match self.peek_kind() {
             Some(RBrace) => {
                 let r_brace = self.lpop();
+                if in_enum_variants {
+                    let loc = Location::concat(&l_brace, &r_brace);
+                    let err = ParseError::invalid_enum_variant_err(line!() as usize, loc);
+                    self.errs.push(err);
+                    debug_exit_info!(self);
+                    return Err(());
+                }
                 let arg = Args::empty();
                 let set = NormalSet::new(l_brace, r_brace, arg);
                 debug_exit_info!(self);
@@ -2746,7 +3042,18 @@ impl Parser {
             Expr::Def(def) => {
                 let attr = RecordAttrOrIdent::Attr(def);
                 let record = self
-                    .try_reduce_record(l_brace, attr)
+                    .try_reduce_record(l_brace, attr, in_enum_variants)
+                    .map_err(|_| self.stack_dec(fn_name!()))?;
+                debug_exit_info!(self);
+                Ok(BraceContainer::Record(record))
+            }
+            // e.g. {A; B(Int); C(Str, Int)} (variants of `Enum`)
+            Expr::Call(call)
+                if in_enum_variants && self.cur_is(Semi) && Self::is_variant_call(&call) =>
+            {
+                let attr = RecordAttrOrIdent::Attr(Self::variant_call_into_def(call));
+                let record = self
+                    .try_reduce_record(l_brace, attr, in_enum_variants)
                     .map_err(|_| self.stack_dec(fn_name!()))?;
                 debug_exit_info!(self);
                 Ok(BraceContainer::Record(record))
@@ -2772,7 +3079,7 @@ impl Parser {
                 };
                 let attr = RecordAttrOrIdent::Ident(ident);
                 let record = self
-                    .try_reduce_record(l_brace, attr)
+                    .try_reduce_record(l_brace, attr, in_enum_variants)
                     .map_err(|_| self.stack_dec(fn_name!()))?;
                 debug_exit_info!(self);
                 Ok(BraceContainer::Record(record))
@@ -2785,19 +3092,35 @@ impl Parser {
                 Ok(res)
             }
             other => {
-                match self.peek() {
+                let set = match self.peek() {
                     Some(r_brace) if r_brace.is(RBrace) => {
                         let arg = Args::new(vec![PosArg::new(other)], None, vec![], None);
                         let r_brace = self.lpop();
-                        return Ok(BraceContainer::Set(Set::Normal(NormalSet::new(
-                            l_brace, r_brace, arg,
-                        ))));
+                        Set::Normal(NormalSet::new(l_brace, r_brace, arg))
+                    }
+                    _ => self
+                        .try_reduce_set(l_brace, other)
+                        .map_err(|_| self.stack_dec(fn_name!()))?,
+                };
+                // {A} or {B(Int)} (a single variant is parsed as a set)
+                if in_enum_variants {
+                    let invalid = match &set {
+                        Set::Normal(set) => set
+                            .elems
+                            .pos_args()
+                            .iter()
+                            .find(|arg| !Self::is_variant(&arg.expr))
+                            .map(|arg| arg.loc()),
+                        // e.g. {A; 1}
+                        other => Some(other.loc()),
+                    };
+                    if let Some(loc) = invalid {
+                        let err = ParseError::invalid_enum_variant_err(line!() as usize, loc);
+                        self.errs.push(err);
+                        debug_exit_info!(self);
+                        return Err(());
                     }
-                    _ => {}
                 }
-                let set = self
-                    .try_reduce_set(l_brace, other)
-                    .map_err(|_| self.stack_dec(fn_name!()))?;
                 debug_exit_info!(self);
                 Ok(BraceContainer::Set(set))
             }
@@ -2812,6 +3135,7 @@ impl Parser {
         &mut self,
         l_brace: Token,
         first_attr: RecordAttrOrIdent,
+        in_enum_variants: bool,
     ) -> ParseResult<Record> {
         debug_call_info!(self);
         let mut attrs = vec![first_attr];
@@ -2892,13 +3216,20 @@ impl Parser {
                             };
                             attrs.push(RecordAttrOrIdent::Ident(ident));
                         }
+                        Expr::Call(call) if in_enum_variants && Self::is_variant_call(&call) => {
+                            attrs.push(RecordAttrOrIdent::Attr(Self::variant_call_into_def(call)));
+                        }
                         other => {
                             let caused_by = caused_by!();
                             log!(err "error caused by: {caused_by}");
-                            let err = ParseError::invalid_record_element_err(
-                                line!() as usize,
-                                other.loc(),
-                            );
+                            let err = if in_enum_variants {
+                                ParseError::invalid_enum_variant_err(line!() as usize, other.loc())
+                            } else {
+                                ParseError::invalid_record_element_err(
+                                    line!() as usize,
+                                    other.loc(),
+                                )
+                            };
                             self.errs.push(err);
                             self.next_expr();
                             debug_exit_info!(self);
@@ -2915,6 +3246,30 @@ impl Parser {
         }
     }
 
+    /// `A` or `B(Int, Str)`
+    fn is_variant(expr: &Expr) -> bool {
+        match expr {
+            Expr::Accessor(Accessor::Ident(_)) => true,
+            Expr::Call(call) => Self::is_variant_call(call),
+            _ => false,
+        }
+    }
+
+    /// `B(Int, Str)` (a local name applied to positional arguments only)
+    fn is_variant_call(call: &Call) -> bool {
+        matches!(call.obj.as_ref(), Expr::Accessor(Accessor::Ident(_)))
+            && call.attr_name.is_none()
+            && call.args.kw_is_empty()
+            && call.args.var_args.is_none()
+    }
+
+    /// `B(Int, Str)` => `B = (Int, Str)`
+    fn variant_call_into_def(call: Call) -> Def {
+        let Expr::Accessor(Accessor::Ident(ident)) = *call.obj else { unreachable!() };
+        let tuple = Expr::Tuple(Tuple::Normal(NormalTuple::new(call.args)));
+        Def::new(Signature::new_var(ident), DefBody::new_single(tuple))
+    }
+
     fn try_reduce_normal_dict_or_set_comp(
         &mut self,
         l_brace: Token,
@@ -3118,7 +3473,7 @@ impl Parser {
                                 }
                             }
                         },
-                        ArgKind::Var(var) => {
+                        ArgKind::Var(var) | ArgKind::KwVar(var) => {
                             let err = ParseError::simple_syntax_error(line!() as usize, var.loc());
                             self.errs.push(err);
                             debug_exit_info!(self);
@@ -3170,6 +3525,11 @@ impl Parser {
             ArgKind::Pos(pos) => Args::single(pos),
             ArgKind::Var(var) => Args::new(vec![], Some(var), vec![], None),
             ArgKind::Kw(kw) => Args::new(vec![], None, vec![kw], None),
+            ArgKind::KwVar(var) => {
+                let mut args = Args::empty();
+                args.set_kw_var_args(var);
+                args
+            }
         };
         #[allow(clippy::while_let_loop)]
         loop {
@@ -3231,6 +3591,9 @@ impl Parser {
                         ArgKind::Kw(arg) => {
                             args.push_kw(arg);
                         }
+                        ArgKind::KwVar(var) => {
+                            args.set_kw_var_args(var);
+                        }
                     }
                 }
                 Some(_other) => {
@@ -1,13 +1,21 @@
 use erg_common::config::ErgConfig;
 use erg_common::consts::DEBUG_MODE;
+use erg_common::edition::LangOptions;
 use erg_common::error::MultiErrorDisplay;
 use erg_common::io::Input;
 use erg_common::spawn::exec_new_thread;
-use erg_common::traits::{Runnable, Stream};
+use erg_common::traits::{DequeStream, Locational, Runnable, Stream};
 
+use erg_parser::ast::{Def, Expr, Literal, Module};
+use erg_parser::desugar::{DesugarPass, DesugarPasses, Desugarer};
 use erg_parser::error::{ErrorArtifact, ParseWarnings, ParserRunnerErrors};
+use erg_parser::incremental::{relex, reparse, LineEdit};
 use erg_parser::lex::Lexer;
-use erg_parser::ParserRunner;
+use erg_parser::parse::{Parsable, SimpleParser};
+use erg_parser::token::TokenStream;
+use erg_parser::trivia::{Comment, CommentKind};
+use erg_parser::visitor::{fold_children, walk_def, Fold, Visit};
+use erg_parser::{Parser, ParserRunner};
 
 #[test]
 fn parse_args() -> Result<(), ()> {
@@ -54,6 +62,232 @@ fn parse_test2_advanced_syntax() -> Result<(), ()> {
     expect_success("tests/test2_advanced_syntax.er", 0)
 }
 
+#[test]
+fn parse_shebang() {
+    let code = "#!/usr/bin/env erg\n# the answer\nx = 42\n".to_string();
+    let module = ParserRunner::new(ErgConfig::default())
+        .parse(code)
+        .unwrap()
+        .ast;
+    let x = module.block().first().unwrap();
+    let texts = module
+        .trivia()
+        .leading_comments(x)
+        .iter()
+        .map(|c| c.text())
+        .collect::<Vec<_>>();
+    assert_eq!(texts, ["the answer"]);
+}
+
+#[test]
+fn parse_trivia() {
+    let code = std::fs::read_to_string("tests/trivia.er").unwrap();
+    let module = ParserRunner::new(ErgConfig::default())
+        .parse(code)
+        .unwrap()
+        .ast;
+    let trivia = module.trivia();
+    let stmts = module.block().iter().collect::<Vec<_>>();
+    let [x, f, _, methods] = stmts[..] else {
+        panic!("unexpected module: {module}");
+    };
+    fn texts(comments: &[Comment]) -> Vec<&str> {
+        comments.iter().map(|c| c.text()).collect()
+    }
+    assert_eq!(texts(trivia.leading_comments(x)), ["the answer"]);
+    assert_eq!(trivia.trailing_comment(x).unwrap().text(), "trailing");
+    let f_trivia = trivia.get(f).unwrap();
+    assert_eq!(f_trivia.leading[0].kind, CommentKind::Block);
+    assert_eq!(f_trivia.blank_lines_before, 2);
+    let Expr::Def(f) = f else { panic!() };
+    assert_eq!(
+        texts(trivia.leading_comments(f.body.block.first().unwrap())),
+        ["inside"]
+    );
+    let Expr::Methods(methods) = methods else {
+        panic!()
+    };
+    let get = methods.attrs.iter().next().unwrap();
+    assert_eq!(texts(trivia.leading_comments(get)), ["the value"]);
+    assert_eq!(texts(trivia.dangling_comments()), ["end"]);
+    assert_eq!(trivia.comments().len(), 6);
+}
+
+#[test]
+fn parse_recovery() {
+    let code = std::fs::read_to_string("tests/recovery.er").unwrap();
+    let iart = ParserRunner::new(ErgConfig::default())
+        .parse(code)
+        .unwrap_err();
+    // only the lexer error (unclosed string literal) is reported
+    assert_eq!(iart.errors.len(), 1);
+    let module = iart.ast.unwrap();
+    let stmts = module.block().iter().collect::<Vec<_>>();
+    let [_x, f, _g, _h, s, print] = stmts[..] else {
+        panic!("unexpected module: {module}");
+    };
+    // the invalid lines are replaced with error nodes
+    assert!(matches!(s, Expr::Dummy(dummy) if dummy.exprs.is_empty()));
+    assert!(matches!(print, Expr::Call(_)));
+    let Expr::Def(f) = f else { panic!() };
+    let body = f.body.block.iter().collect::<Vec<_>>();
+    assert_eq!(body.len(), 3);
+    assert!(matches!(body[0], Expr::Dummy(_)));
+}
+
+#[test]
+fn parse_incremental() {
+    let old =
+        "x = 1\nf a =\n    y = a + 1\n    y * 2\n\n@Override\ng b = (\n    b,\n)\nprint! f x\n";
+    // (start, end (inclusive), replacement)
+    let edits = [
+        (0, 0, "x = 2"),
+        (2, 2, "    y = a - 1"),
+        (2, 3, "    a"),
+        (3, 3, "    z = y\n    z * 3"),
+        (4, 4, "h = 1\n"),
+        (5, 5, "@Deprecated"),
+        (7, 7, "    b, 1"),
+        (9, 9, "print! g x"),
+        (1, 1, "f a = ("),
+    ];
+    let old_tokens = Lexer::from_str(old.to_string()).lex().unwrap();
+    let old_module = Parser::new(old_tokens.clone()).parse().unwrap().ast;
+    for (start, end, text) in edits {
+        let mut lines = old.lines().map(|l| l.to_string()).collect::<Vec<_>>();
+        lines.splice(start..=end, text.split('\n').map(|l| l.to_string()));
+        let new = lines.join("\n") + "\n";
+        let edit = LineEdit::from_replacement(start as u32, end as u32, text);
+        let summary = |tokens: &TokenStream| {
+            tokens
+                .iter()
+                .map(|t| (t.kind, t.content.to_string(), t.lineno, t.col_begin))
+                .collect::<Vec<_>>()
+        };
+        let full_tokens = Lexer::from_str(new.clone()).lex();
+        let Some(tokens) = relex(&old_tokens, &new, edit) else {
+            // the edit is not closed in the chunk
+            assert_eq!(text, "f a = (");
+            continue;
+        };
+        let full_tokens = full_tokens.unwrap();
+        assert_eq!(summary(&tokens), summary(&full_tokens), "{new}");
+        let full = Parser::new(full_tokens).parse().unwrap().ast;
+        let module = reparse(&old_module, &tokens, edit, &LangOptions::default())
+            .unwrap()
+            .ast;
+        assert_eq!(module.to_string(), full.to_string(), "{new}");
+        let lines = |module: &Module| {
+            module
+                .block()
+                .iter()
+                .map(|chunk| (chunk.ln_begin(), chunk.ln_end()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lines(&module), lines(&full), "{new}");
+    }
+}
+
+#[test]
+fn parse_header_disables_feature() {
+    let old = "#!erg 0.7 -conditional_binding\nx = 1\ny = 2\n";
+    let binding = "a = if (i: Int) := x, do: i";
+    let new = old.replace("y = 2", binding);
+    let iart = SimpleParser::parse(new.clone()).unwrap_err();
+    assert_eq!(iart.errors.len(), 1);
+    // the header applies to the reparsed chunks
+    let (lang, header_err) = Parser::lang_of(&new);
+    assert!(header_err.is_none() && !lang.is_enabled("conditional_binding"));
+    let old_tokens = Lexer::from_str(old.to_string()).lex().unwrap();
+    let old_module = Parser::new_with_lang(old_tokens.clone(), lang.clone())
+        .parse()
+        .unwrap()
+        .ast;
+    let edit = LineEdit::from_replacement(2, 2, binding);
+    let tokens = relex(&old_tokens, &new, edit).unwrap();
+    let iart = reparse(&old_module, &tokens, edit, &lang).unwrap_err();
+    assert_eq!(iart.errors.len(), 1);
+    assert!(reparse(&old_module, &tokens, edit, &LangOptions::default()).is_ok());
+    // an invalid header is reported, and the default options are used
+    let iart = SimpleParser::parse(new.replace("-conditional_binding", "-foo")).unwrap_err();
+    assert_eq!(iart.errors.len(), 1);
+}
+
+#[test]
+fn parse_visitor() {
+    struct DefCounter(usize);
+    impl Visit for DefCounter {
+        fn visit_def(&mut self, def: &Def) {
+            self.0 += 1;
+            walk_def(self, def);
+        }
+    }
+    let code = "f x =\n    y = x + 1\n    { a = y; b = [1, 2] }\nC.\n    g self = 1\nprint! f 1\n";
+    let module = Parser::new(Lexer::from_str(code.to_string()).lex().unwrap())
+        .parse()
+        .unwrap()
+        .ast;
+    // f, y, a, b, g
+    let mut counter = DefCounter(0);
+    counter.visit_module(&module);
+    assert_eq!(counter.0, 5);
+    // replaces all the literals with `0`
+    fn zero(expr: Expr) -> Expr {
+        match expr {
+            Expr::Literal(lit) => Expr::Literal(Literal::nat(0, lit.ln_begin().unwrap())),
+            expr => fold_children(&mut zero, expr),
+        }
+    }
+    let folded = (zero as fn(Expr) -> Expr).fold_module(module.clone());
+    assert_eq!(folded.len(), module.len());
+    assert_eq!(
+        folded.to_string().matches('0').count(),
+        module.to_string().matches('1').count() + module.to_string().matches('2').count()
+    );
+    assert!(!folded.to_string().contains('1'));
+}
+
+#[test]
+fn parse_desugar_passes() {
+    let code = "xs = [1, 2]\nprint! xs[0]\n";
+    let parse = || {
+        Parser::new(Lexer::from_str(code.to_string()).lex().unwrap())
+            .parse()
+            .unwrap()
+            .ast
+    };
+    let mut desugarer = Desugarer::new();
+    assert!(desugarer
+        .desugar(parse())
+        .to_string()
+        .contains("__getitem__"));
+    assert!(desugarer.passes_mut().disable("accessor"));
+    assert!(!desugarer.passes().is_enabled("accessor"));
+    assert!(!desugarer
+        .desugar(parse())
+        .to_string()
+        .contains("__getitem__"));
+    // a pass of a dialect that generates patterns
+    let mut passes = DesugarPasses::builtin();
+    let dialect = DesugarPass::new("dialect", |_, module| module).before(&["pattern"]);
+    assert!(passes.register(dialect.after(&["accessor"])).is_err());
+    passes.register(dialect).unwrap();
+    assert!(passes.register(dialect).is_err());
+    assert_eq!(
+        passes.names(),
+        [
+            "derive",
+            "enum",
+            "conditional_binding",
+            "multiple_pattern_def",
+            "dialect",
+            "pattern",
+            "shortened_record",
+            "accessor"
+        ]
+    );
+}
+
 #[test]
 fn parse_stack() -> Result<(), ()> {
     expect_failure("tests/stack.er", 0, 2)
@@ -69,14 +303,20 @@ fn parse_invalid_chunk() -> Result<(), ()> {
     expect_failure("tests/invalid_chunk.er", 0, 62)
 }
 
+// Only the first error of a broken line is reported; the parser resynchronizes on the next line.
 #[test]
 fn parse_invalid_collections() -> Result<(), ()> {
-    expect_failure("tests/invalid_collections.er", 0, 29)
+    expect_failure("tests/invalid_collections.er", 0, 21)
 }
 
 #[test]
 fn parse_invalid_class_definition() -> Result<(), ()> {
-    expect_failure("tests/invalid_class_definition.er", 0, 7)
+    expect_failure("tests/invalid_class_definition.er", 0, 6)
+}
+
+#[test]
+fn parse_invalid_enum() -> Result<(), ()> {
+    expect_failure("tests/invalid_enum.er", 0, 5)
 }
 
 #[test]
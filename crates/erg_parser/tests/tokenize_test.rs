@@ -460,3 +460,29 @@ fn tesop_te_prec() {
     assert_eq!(LParen.precedence(), Some(0));
     assert_eq!(Illegal.precedence(), None);
 }
+
+#[test]
+fn test_lexer_token_spans() {
+    let src = "x = 12 + \"ab\" # cmt\nあ = \"c\\nd\" * 3.5\n";
+    let tokens = Lexer::from_str(src.to_string())
+        .map(|res| res.unwrap())
+        .collect::<Vec<_>>();
+    for tok in tokens.iter() {
+        match tok.kind {
+            // symbols are interned, escaped strings are unescaped
+            Symbol => assert_eq!(tok.span(), None),
+            StrLit if tok.content.contains('\n') => assert_eq!(tok.span(), None),
+            NatLit | RatioLit | StrLit | Plus | Star | Assign => {
+                let span = tok.span().unwrap();
+                assert_eq!(&src[span], &tok.content[..]);
+            }
+            _ => {}
+        }
+    }
+    let lit = tokens.iter().find(|tok| tok.is(RatioLit)).unwrap();
+    assert_eq!((lit.lineno, lit.col_begin), (2, 13));
+    let star = tokens.iter().find(|tok| tok.is(Star)).unwrap();
+    assert_eq!((star.lineno, star.col_begin), (2, 11));
+    let escaped = tokens.iter().filter(|tok| tok.is(StrLit)).nth(1).unwrap();
+    assert_eq!(&escaped.content[..], "\"c\nd\"");
+}
@@ -4,6 +4,7 @@
 use std::collections::VecDeque;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::ops::Range;
 
 use erg_common::error::Location;
 use erg_common::impl_displayable_deque_stream_for_wrapper;
@@ -432,14 +433,19 @@ impl Token {
 
     #[inline]
     pub fn symbol(cont: &str) -> Self {
-        Self::from_str(TokenKind::Symbol, cont)
+        Token {
+            kind: TokenKind::Symbol,
+            content: Str::intern(cont),
+            lineno: 0,
+            col_begin: 0,
+        }
     }
 
     #[inline]
     pub fn symbol_with_line(cont: &str, lineno: u32) -> Self {
         Token {
             kind: TokenKind::Symbol,
-            content: Str::rc(cont),
+            content: Str::intern(cont),
             lineno,
             col_begin: 0,
         }
@@ -463,6 +469,12 @@ impl Token {
         }
     }
 
+    /// The byte range of the token in the source code.
+    /// `None` if the content is not a slice of the source (e.g. symbols, escaped strings and desugared tokens).
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.content.slice_range()
+    }
+
     pub fn deep_eq(&self, other: &Self) -> bool {
         self.kind == other.kind
             && self.content == other.content
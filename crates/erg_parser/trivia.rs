@@ -0,0 +1,265 @@
+//! Comments and blank lines ("trivia").
+//!
+//! Trivia is not a part of the syntax, so the parser does not see it,
+//! but tools that rewrite the source (formatters, code actions, etc.) or read it (doc generators) need it.
+//! The lexer collects the trivia as [`RawTrivia`], and the parser attaches it to the statements of the module ([`TriviaMap`]).
+use std::cmp::Reverse;
+
+use erg_common::error::Location;
+use erg_common::traits::Locational;
+use erg_common::Str;
+
+use crate::ast::{Block, ClassAttr, Expr, Module, Record};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommentKind {
+    /// `# ...`
+    Line,
+    /// `#[ ... ]#`
+    Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Comment {
+    pub kind: CommentKind,
+    /// the source text including `#`, `#[` and `]#`
+    pub content: Str,
+    pub loc: Location,
+}
+
+impl Locational for Comment {
+    fn loc(&self) -> Location {
+        self.loc
+    }
+}
+
+impl Comment {
+    pub const fn new(kind: CommentKind, content: Str, loc: Location) -> Self {
+        Self { kind, content, loc }
+    }
+
+    /// The content without the comment markers
+    pub fn text(&self) -> &str {
+        match self.kind {
+            CommentKind::Line => self.content.trim_start_matches('#').trim(),
+            CommentKind::Block => self
+                .content
+                .trim_start_matches("#[")
+                .trim_end_matches("]#")
+                .trim(),
+        }
+    }
+}
+
+/// Trivia collected by the lexer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RawTrivia {
+    pub comments: Vec<Comment>,
+    /// 1-origin line numbers of the lines that consist of whitespaces only
+    pub blank_lines: Vec<u32>,
+}
+
+/// Trivia attached to a statement
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Trivia {
+    /// comments in the lines just before the statement (or before the statement in the same line)
+    pub leading: Vec<Comment>,
+    /// a comment after the statement in the last line of it
+    pub trailing: Option<Comment>,
+    /// the number of blank lines before the statement (and the leading comments)
+    pub blank_lines_before: u32,
+}
+
+impl Trivia {
+    pub fn is_empty(&self) -> bool {
+        self.leading.is_empty() && self.trailing.is_none() && self.blank_lines_before == 0
+    }
+}
+
+/// Trivia of the statements (including the nested ones) of a module, keyed by the start position of the statement.
+/// Comments that belong to no statement (e.g. at the end of the module) are `dangling`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct TriviaMap {
+    /// sorted by the key
+    entries: Vec<((u32, u32), Trivia)>,
+    dangling: Vec<Comment>,
+}
+
+fn start(loc: &impl Locational) -> Option<(u32, u32)> {
+    Some((loc.ln_begin()?, loc.col_begin()?))
+}
+
+fn end(loc: &impl Locational) -> Option<(u32, u32)> {
+    Some((loc.ln_end()?, loc.col_end()?))
+}
+
+impl TriviaMap {
+    pub const fn empty() -> Self {
+        Self {
+            entries: vec![],
+            dangling: vec![],
+        }
+    }
+
+    pub fn new(module: &Module, raw: RawTrivia) -> Self {
+        let mut stmts = vec![];
+        collect_block(module.block(), &mut stmts);
+        // desugared or partially located statements cannot own trivia
+        stmts.retain(|loc| start(loc).is_some() && end(loc).is_some());
+        // outer statements come first if they start at the same position
+        stmts.sort_by_key(start);
+        stmts.dedup_by_key(|loc| start(loc));
+        let mut map = Self::empty();
+        for comment in raw.comments {
+            let (Some((ln, col)), Some(comment_end)) = (start(&comment), end(&comment)) else {
+                continue;
+            };
+            // the outermost statement among the ones starting last and ending before the comment in the same line
+            let owner = stmts
+                .iter()
+                .filter(|stmt| {
+                    stmt.ln_end() == Some(ln) && stmt.col_end().is_some_and(|c| c <= col)
+                })
+                .max_by_key(|stmt| (stmt.ln_begin(), Reverse(stmt.col_begin())));
+            if let Some(owner) = owner {
+                let trivia = map.entry(start(owner).unwrap());
+                if trivia.trailing.is_none() {
+                    trivia.trailing = Some(comment);
+                    continue;
+                }
+            }
+            match stmts.iter().find(|stmt| start(*stmt) > Some(comment_end)) {
+                Some(next) => map.entry(start(next).unwrap()).leading.push(comment),
+                None => map.dangling.push(comment),
+            }
+        }
+        for stmt in stmts.iter() {
+            let key = start(stmt).unwrap();
+            let first_line = map
+                .get_by_key(key)
+                .and_then(|trivia| trivia.leading.first())
+                .and_then(|comment| comment.ln_begin())
+                .unwrap_or(key.0);
+            let blank_lines = (1..first_line)
+                .rev()
+                .take_while(|ln| raw.blank_lines.binary_search(ln).is_ok())
+                .count() as u32;
+            if blank_lines > 0 {
+                map.entry(key).blank_lines_before = blank_lines;
+            }
+        }
+        map
+    }
+
+    fn entry(&mut self, key: (u32, u32)) -> &mut Trivia {
+        let idx = match self.entries.binary_search_by_key(&key, |(k, _)| *k) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                self.entries.insert(idx, (key, Trivia::default()));
+                idx
+            }
+        };
+        &mut self.entries[idx].1
+    }
+
+    fn get_by_key(&self, key: (u32, u32)) -> Option<&Trivia> {
+        let idx = self.entries.binary_search_by_key(&key, |(k, _)| *k).ok()?;
+        Some(&self.entries[idx].1)
+    }
+
+    /// Returns the trivia of the statement (`None` if `stmt` is not a statement or has no trivia)
+    pub fn get(&self, stmt: &impl Locational) -> Option<&Trivia> {
+        self.get_by_key(start(stmt)?)
+    }
+
+    pub fn leading_comments(&self, stmt: &impl Locational) -> &[Comment] {
+        self.get(stmt).map_or(&[], |trivia| &trivia.leading[..])
+    }
+
+    pub fn trailing_comment(&self, stmt: &impl Locational) -> Option<&Comment> {
+        self.get(stmt)?.trailing.as_ref()
+    }
+
+    pub fn dangling_comments(&self) -> &[Comment] {
+        &self.dangling
+    }
+
+    /// All comments in the order of appearance
+    pub fn comments(&self) -> Vec<&Comment> {
+        let mut comments = self
+            .entries
+            .iter()
+            .flat_map(|(_, trivia)| trivia.leading.iter().chain(trivia.trailing.iter()))
+            .chain(self.dangling.iter())
+            .collect::<Vec<_>>();
+        comments.sort_by_key(|comment| start(*comment));
+        comments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.dangling.is_empty()
+    }
+}
+
+fn collect_block(block: &Block, stmts: &mut Vec<Location>) {
+    for expr in block.iter() {
+        stmts.push(expr.loc());
+        collect_expr(expr, stmts);
+    }
+}
+
+fn collect_class_attrs<'a>(attrs: impl Iterator<Item = &'a ClassAttr>, stmts: &mut Vec<Location>) {
+    for attr in attrs {
+        stmts.push(attr.loc());
+        if let ClassAttr::Def(def) = attr {
+            collect_block(&def.body.block, stmts);
+        }
+    }
+}
+
+/// Collects the statements in the blocks of `expr`
+fn collect_expr(expr: &Expr, stmts: &mut Vec<Location>) {
+    match expr {
+        Expr::Def(def) => collect_block(&def.body.block, stmts),
+        Expr::Lambda(lambda) => collect_block(&lambda.body, stmts),
+        Expr::Methods(methods) => collect_class_attrs(methods.attrs.iter(), stmts),
+        Expr::ClassDef(class_def) => {
+            collect_block(&class_def.def.body.block, stmts);
+            for methods in class_def.methods_list.iter() {
+                collect_class_attrs(methods.attrs.iter(), stmts);
+            }
+        }
+        Expr::PatchDef(patch_def) => {
+            collect_block(&patch_def.def.body.block, stmts);
+            for methods in patch_def.methods_list.iter() {
+                collect_class_attrs(methods.attrs.iter(), stmts);
+            }
+        }
+        Expr::Record(Record::Normal(record)) => {
+            for def in record.attrs.iter() {
+                stmts.push(def.loc());
+                collect_block(&def.body.block, stmts);
+            }
+        }
+        Expr::Call(call) => {
+            collect_expr(&call.obj, stmts);
+            for arg in call.args.pos_args().iter() {
+                collect_expr(&arg.expr, stmts);
+            }
+            if let Some(arg) = call.args.var_args.as_ref() {
+                collect_expr(&arg.expr, stmts);
+            }
+            for arg in call.args.kw_args().iter() {
+                collect_expr(&arg.expr, stmts);
+            }
+        }
+        Expr::BinOp(bin) => {
+            collect_expr(&bin.args[0], stmts);
+            collect_expr(&bin.args[1], stmts);
+        }
+        Expr::UnaryOp(unary) => collect_expr(&unary.args[0], stmts),
+        Expr::TypeAscription(tasc) => collect_expr(&tasc.expr, stmts),
+        Expr::Dummy(dummy) => collect_block(&dummy.exprs, stmts),
+        _ => {}
+    }
+}
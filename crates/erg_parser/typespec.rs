@@ -124,6 +124,12 @@ impl Parser {
             }
             Expr::Call(call) => {
                 let obj = Self::validate_const_expr(*call.obj)?;
+                // `x.f(y)` is represented as `(x.f)(y)`
+                let obj = if let Some(attr_name) = call.attr_name {
+                    ConstExpr::Accessor(ConstAccessor::Attr(ConstAttribute::new(obj, attr_name)))
+                } else {
+                    obj
+                };
                 let ConstExpr::Accessor(acc) = obj else {
                     return Err(ParseError::feature_error(
                         line!() as usize,
@@ -189,9 +195,12 @@ impl Parser {
     }
 
     fn validate_const_def(def: Def) -> Result<ConstDef, ParseError> {
+        let Some(ident) = def.sig.ident().cloned() else {
+            return Err(ParseError::simple_syntax_error(line!() as usize, def.loc()));
+        };
         let block = Self::validate_const_block(def.body.block)?;
         let body = ConstDefBody::new(def.body.op, block, def.body.id);
-        Ok(ConstDef::new(def.sig.ident().unwrap().clone(), body))
+        Ok(ConstDef::new(ident, body))
     }
 
     fn accessor_to_type_spec(accessor: Accessor) -> Result<TypeSpec, ParseError> {
@@ -380,7 +389,11 @@ impl Parser {
             Dict::Normal(dic) => {
                 let mut kvs = vec![];
                 for kv in dic.kvs.into_iter() {
-                    let key = Self::expr_to_type_spec(kv.key)?;
+                    let key = match kv.key {
+                        // typed dict (e.g. `{"name": Str, "age": Nat}`): the key `"name"` means `{"name"}`
+                        Expr::Literal(lit) => TypeSpec::enum_t_spec(vec![lit]),
+                        other => Self::expr_to_type_spec(other)?,
+                    };
                     let value = Self::expr_to_type_spec(kv.value)?;
                     kvs.push((key, value));
                 }
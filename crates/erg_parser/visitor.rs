@@ -0,0 +1,563 @@
+//! Implements generic traversals of `AST`.
+//!
+//! `Visit` walks the AST by reference, `Fold` rebuilds it by value.
+//! Both have default implementations that descend into every child expression,
+//! so a pass only needs to override the cases it is interested in.
+//!
+//! ```
+//! use erg_parser::ast::{Expr, Literal};
+//! use erg_parser::visitor::{walk_expr, Visit};
+//!
+//! #[derive(Default)]
+//! struct LiteralCounter(usize);
+//!
+//! impl Visit for LiteralCounter {
+//!     fn visit_expr(&mut self, expr: &Expr) {
+//!         if let Expr::Literal(_) = expr {
+//!             self.0 += 1;
+//!         }
+//!         walk_expr(self, expr);
+//!     }
+//! }
+//! ```
+use erg_common::enum_unwrap;
+use erg_common::traits::Stream;
+
+use crate::ast::{
+    Accessor, Args, Array, ArrayComprehension, ArrayWithLength, BinOp, Block, Call, ClassAttr,
+    ClassAttrs, ClassDef, DataPack, Def, DefBody, DefaultParamSignature, Dict, DictComprehension,
+    Dummy, Expr, KeyValue, KwArg, Lambda, Methods, MixedRecord, Module, NormalArray, NormalDict,
+    NormalRecord, NormalSet, NormalTuple, Params, PatchDef, PosArg, ReDef, Record,
+    RecordAttrOrIdent, RecordAttrs, Set, SetComprehension, SetWithLength, Signature, Tuple,
+    TypeAppArgs, TypeAppArgsKind, TypeSpecWithOp, UnaryOp,
+};
+
+/// Walks the AST by reference.
+///
+/// The default implementations visit all the children (see `walk_*`).
+/// If a method is overridden, call the corresponding `walk_*` function to continue walking into the children.
+pub trait Visit {
+    fn visit_module(&mut self, module: &Module) {
+        for chunk in module.iter() {
+            self.visit_expr(chunk);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr);
+    }
+
+    /// Called for all definitions, including record attributes and methods.
+    fn visit_def(&mut self, def: &Def) {
+        walk_def(self, def);
+    }
+}
+
+/// Rebuilds the AST by value.
+///
+/// `fold_expr` folds all the children by default (see `fold_children`).
+/// Definitions that are not `Expr`s (record attributes, methods) are folded as `Expr::Def`/`Expr::Methods`,
+/// so the folder must return the same kind of expression for them.
+///
+/// Closures of type `FnMut(Expr) -> Expr` are also `Fold`s.
+pub trait Fold {
+    fn fold_module(&mut self, module: Module) -> Module {
+        module
+            .into_iter()
+            .map(|chunk| self.fold_expr(chunk))
+            .collect()
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        fold_children(self, expr)
+    }
+}
+
+impl<F: FnMut(Expr) -> Expr> Fold for F {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        self(expr)
+    }
+}
+
+pub fn walk_args<V: Visit + ?Sized>(visitor: &mut V, args: &Args) {
+    for arg in args.pos_args() {
+        visitor.visit_expr(&arg.expr);
+    }
+    if let Some(var_args) = args.var_args() {
+        visitor.visit_expr(&var_args.expr);
+    }
+    for arg in args.kw_args() {
+        visitor.visit_expr(&arg.expr);
+    }
+    if let Some(kw_var_args) = args.kw_var_args() {
+        visitor.visit_expr(&kw_var_args.expr);
+    }
+}
+
+pub fn walk_accessor<V: Visit + ?Sized>(visitor: &mut V, acc: &Accessor) {
+    match acc {
+        Accessor::Ident(_) => {}
+        Accessor::Attr(attr) => visitor.visit_expr(&attr.obj),
+        Accessor::TupleAttr(tup) => visitor.visit_expr(&tup.obj),
+        Accessor::Subscr(sub) => {
+            visitor.visit_expr(&sub.obj);
+            visitor.visit_expr(&sub.index);
+        }
+        Accessor::TypeApp(tapp) => {
+            visitor.visit_expr(&tapp.obj);
+            if let TypeAppArgsKind::Args(args) = &tapp.type_args.args {
+                walk_args(visitor, args);
+            }
+        }
+    }
+}
+
+fn walk_params<V: Visit + ?Sized>(visitor: &mut V, params: &Params) {
+    for default in params.defaults.iter() {
+        visitor.visit_expr(&default.default_val);
+    }
+}
+
+pub fn walk_block<V: Visit + ?Sized>(visitor: &mut V, block: &Block) {
+    for chunk in block.iter() {
+        visitor.visit_expr(chunk);
+    }
+}
+
+pub fn walk_def<V: Visit + ?Sized>(visitor: &mut V, def: &Def) {
+    if let Signature::Subr(subr) = &def.sig {
+        walk_params(visitor, &subr.params);
+    }
+    walk_block(visitor, &def.body.block);
+}
+
+pub fn walk_methods<V: Visit + ?Sized>(visitor: &mut V, methods: &Methods) {
+    for attr in methods.attrs.iter() {
+        match attr {
+            ClassAttr::Def(def) => visitor.visit_def(def),
+            ClassAttr::Decl(decl) => {
+                visitor.visit_expr(&decl.expr);
+                visitor.visit_expr(&decl.t_spec.t_spec_as_expr);
+            }
+            ClassAttr::Doc(_) => {}
+        }
+    }
+}
+
+pub fn walk_record<V: Visit + ?Sized>(visitor: &mut V, record: &Record) {
+    match record {
+        Record::Normal(rec) => {
+            for attr in rec.attrs.iter() {
+                visitor.visit_def(attr);
+            }
+        }
+        Record::Mixed(mixed) => {
+            for attr in mixed.attrs.iter() {
+                if let RecordAttrOrIdent::Attr(attr) = attr {
+                    visitor.visit_def(attr);
+                }
+            }
+        }
+    }
+}
+
+/// Visits the direct children of `expr`.
+pub fn walk_expr<V: Visit + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::Literal(_) => {}
+        Expr::Accessor(acc) => walk_accessor(visitor, acc),
+        Expr::Array(Array::Normal(arr)) => walk_args(visitor, &arr.elems),
+        Expr::Array(Array::WithLength(arr)) => {
+            visitor.visit_expr(&arr.elem.expr);
+            visitor.visit_expr(&arr.len);
+        }
+        Expr::Array(Array::Comprehension(arr)) => {
+            visitor.visit_expr(&arr.elem);
+            for (_, gen) in arr.generators.iter() {
+                visitor.visit_expr(gen);
+            }
+            for guard in arr.guards.iter() {
+                visitor.visit_expr(guard);
+            }
+        }
+        Expr::Tuple(Tuple::Normal(tup)) => walk_args(visitor, &tup.elems),
+        Expr::Dict(Dict::Normal(dict)) => {
+            for kv in dict.kvs.iter() {
+                visitor.visit_expr(&kv.key);
+                visitor.visit_expr(&kv.value);
+            }
+        }
+        Expr::Dict(Dict::Comprehension(dict)) => {
+            walk_args(visitor, &dict.attrs);
+            for guard in dict.guards.iter() {
+                visitor.visit_expr(guard);
+            }
+        }
+        Expr::Set(Set::Normal(set)) => walk_args(visitor, &set.elems),
+        Expr::Set(Set::WithLength(set)) => {
+            visitor.visit_expr(&set.elem.expr);
+            visitor.visit_expr(&set.len);
+        }
+        Expr::Set(Set::Comprehension(set)) => {
+            visitor.visit_expr(&set.iter);
+            visitor.visit_expr(&set.pred);
+        }
+        Expr::Record(record) => walk_record(visitor, record),
+        Expr::BinOp(bin) => {
+            visitor.visit_expr(&bin.args[0]);
+            visitor.visit_expr(&bin.args[1]);
+        }
+        Expr::UnaryOp(unary) => visitor.visit_expr(&unary.args[0]),
+        Expr::Call(call) => {
+            visitor.visit_expr(&call.obj);
+            walk_args(visitor, &call.args);
+        }
+        Expr::DataPack(pack) => {
+            visitor.visit_expr(&pack.class);
+            walk_record(visitor, &pack.args);
+        }
+        Expr::Lambda(lambda) => {
+            walk_params(visitor, &lambda.sig.params);
+            walk_block(visitor, &lambda.body);
+        }
+        Expr::TypeAscription(tasc) => {
+            visitor.visit_expr(&tasc.expr);
+            visitor.visit_expr(&tasc.t_spec.t_spec_as_expr);
+        }
+        Expr::Def(def) => visitor.visit_def(def),
+        Expr::Methods(methods) => walk_methods(visitor, methods),
+        Expr::ClassDef(class_def) => {
+            visitor.visit_def(&class_def.def);
+            for methods in class_def.methods_list.iter() {
+                walk_methods(visitor, methods);
+            }
+        }
+        Expr::PatchDef(patch_def) => {
+            visitor.visit_def(&patch_def.def);
+            for methods in patch_def.methods_list.iter() {
+                walk_methods(visitor, methods);
+            }
+        }
+        Expr::ReDef(redef) => {
+            walk_accessor(visitor, &redef.attr);
+            visitor.visit_expr(&redef.expr);
+        }
+        Expr::Dummy(dummy) => walk_block(visitor, &dummy.exprs),
+    }
+}
+
+pub fn fold_args<F: Fold + ?Sized>(folder: &mut F, mut args: Args) -> Args {
+    let kw_var_args = args.take_kw_var_args();
+    let (pos_args, var_args, kw_args, paren) = args.deconstruct();
+    let pos_args = pos_args
+        .into_iter()
+        .map(|arg| PosArg::new(folder.fold_expr(arg.expr)))
+        .collect();
+    let var_args = var_args.map(|arg| PosArg::new(folder.fold_expr(arg.expr)));
+    let kw_args = kw_args
+        .into_iter()
+        .map(|arg| {
+            KwArg::new(arg.keyword, arg.t_spec, folder.fold_expr(arg.expr)) // TODO: t_spec
+        })
+        .collect();
+    let mut args = Args::new(pos_args, var_args, kw_args, paren);
+    if let Some(kw_var) = kw_var_args {
+        args.set_kw_var_args(PosArg::new(folder.fold_expr(kw_var.expr)));
+    }
+    args
+}
+
+pub fn fold_accessor<F: Fold + ?Sized>(folder: &mut F, acc: Accessor) -> Accessor {
+    match acc {
+        Accessor::Ident(ident) => Accessor::Ident(ident),
+        Accessor::Attr(attr) => folder.fold_expr(*attr.obj).attr(attr.ident),
+        Accessor::TupleAttr(tup) => {
+            let obj = folder.fold_expr(*tup.obj);
+            obj.tuple_attr(tup.index)
+        }
+        Accessor::Subscr(sub) => {
+            let obj = folder.fold_expr(*sub.obj);
+            let index = folder.fold_expr(*sub.index);
+            obj.subscr(index, sub.r_sqbr)
+        }
+        Accessor::TypeApp(tapp) => {
+            let obj = folder.fold_expr(*tapp.obj);
+            let args = match tapp.type_args.args {
+                TypeAppArgsKind::Args(args) => TypeAppArgsKind::Args(fold_args(folder, args)),
+                other => other,
+            };
+            let type_args = TypeAppArgs::new(tapp.type_args.l_vbar, args, tapp.type_args.r_vbar);
+            obj.type_app(type_args)
+        }
+    }
+}
+
+pub fn fold_children<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    match expr {
+        Expr::Literal(_) => expr,
+        Expr::Record(record) => match record {
+            Record::Normal(rec) => {
+                let mut new_attrs = vec![];
+                for attr in rec.attrs {
+                    new_attrs.push(enum_unwrap!(folder.fold_expr(Expr::Def(attr)), Expr::Def));
+                }
+                Expr::Record(Record::Normal(NormalRecord::new(
+                    rec.l_brace,
+                    rec.r_brace,
+                    RecordAttrs::new(new_attrs),
+                )))
+            }
+            Record::Mixed(mixed) => {
+                let mut new_attrs = vec![];
+                for attr in mixed.attrs {
+                    match attr {
+                        RecordAttrOrIdent::Attr(attr) => {
+                            let attr = RecordAttrOrIdent::Attr(enum_unwrap!(
+                                folder.fold_expr(Expr::Def(attr)),
+                                Expr::Def
+                            ));
+                            new_attrs.push(attr);
+                        }
+                        RecordAttrOrIdent::Ident(ident) => {
+                            new_attrs.push(RecordAttrOrIdent::Ident(ident));
+                        }
+                    }
+                }
+                Expr::Record(Record::Mixed(MixedRecord::new(
+                    mixed.l_brace,
+                    mixed.r_brace,
+                    new_attrs,
+                )))
+            }
+        },
+        Expr::DataPack(pack) => {
+            let class = folder.fold_expr(*pack.class);
+            let Expr::Record(args) = folder.fold_expr(Expr::Record(pack.args)) else {
+                unreachable!()
+            };
+            Expr::DataPack(DataPack::new(class, pack.connector, args))
+        }
+        Expr::Array(array) => match array {
+            Array::Normal(arr) => {
+                let (elems, ..) = arr.elems.deconstruct();
+                let elems = elems
+                    .into_iter()
+                    .map(|elem| PosArg::new(folder.fold_expr(elem.expr)))
+                    .collect();
+                let elems = Args::pos_only(elems, None);
+                let arr = NormalArray::new(arr.l_sqbr, arr.r_sqbr, elems);
+                Expr::Array(Array::Normal(arr))
+            }
+            Array::WithLength(arr) => {
+                let elem = PosArg::new(folder.fold_expr(arr.elem.expr));
+                let len = folder.fold_expr(*arr.len);
+                let arr = ArrayWithLength::new(arr.l_sqbr, arr.r_sqbr, elem, len);
+                Expr::Array(Array::WithLength(arr))
+            }
+            Array::Comprehension(arr) => {
+                let elem = folder.fold_expr(*arr.elem);
+                let generators = arr
+                    .generators
+                    .into_iter()
+                    .map(|(ident, gen)| (ident, folder.fold_expr(gen)))
+                    .collect();
+                let guards = arr
+                    .guards
+                    .into_iter()
+                    .map(|guard| folder.fold_expr(guard))
+                    .collect();
+                let arr = ArrayComprehension::new(arr.l_sqbr, arr.r_sqbr, elem, generators, guards);
+                Expr::Array(Array::Comprehension(arr))
+            }
+        },
+        Expr::Tuple(tuple) => match tuple {
+            Tuple::Normal(tup) => {
+                let (elems, _, _, paren) = tup.elems.deconstruct();
+                let elems = elems
+                    .into_iter()
+                    .map(|elem| PosArg::new(folder.fold_expr(elem.expr)))
+                    .collect();
+                let new_tup = Args::pos_only(elems, paren);
+                let tup = NormalTuple::new(new_tup);
+                Expr::Tuple(Tuple::Normal(tup))
+            }
+        },
+        Expr::Set(set) => match set {
+            Set::Normal(set) => {
+                let (elems, ..) = set.elems.deconstruct();
+                let elems = elems
+                    .into_iter()
+                    .map(|elem| PosArg::new(folder.fold_expr(elem.expr)))
+                    .collect();
+                let elems = Args::pos_only(elems, None);
+                let set = NormalSet::new(set.l_brace, set.r_brace, elems);
+                Expr::Set(Set::Normal(set))
+            }
+            Set::WithLength(set) => {
+                let elem = PosArg::new(folder.fold_expr(set.elem.expr));
+                let len = folder.fold_expr(*set.len);
+                let set = SetWithLength::new(set.l_brace, set.r_brace, elem, len);
+                Expr::Set(Set::WithLength(set))
+            }
+            Set::Comprehension(set) => {
+                let iter = folder.fold_expr(*set.iter);
+                let pred = folder.fold_expr(*set.pred);
+                let set =
+                    SetComprehension::new(set.l_brace, set.r_brace, set.var, set.op, iter, pred);
+                Expr::Set(Set::Comprehension(set))
+            }
+        },
+        Expr::Dict(dict) => match dict {
+            Dict::Normal(dic) => {
+                let new_kvs = dic
+                    .kvs
+                    .into_iter()
+                    .map(|elem| {
+                        let key = folder.fold_expr(elem.key);
+                        let value = folder.fold_expr(elem.value);
+                        KeyValue::new(key, value)
+                    })
+                    .collect();
+                let tup = NormalDict::new(dic.l_brace, dic.r_brace, new_kvs);
+                Expr::Dict(Dict::Normal(tup))
+            }
+            Dict::Comprehension(dic) => {
+                let attrs = fold_args(folder, dic.attrs);
+                let guards = dic
+                    .guards
+                    .into_iter()
+                    .map(|guard| folder.fold_expr(guard))
+                    .collect();
+                let dic = DictComprehension::new(dic.l_brace, dic.r_brace, attrs, guards);
+                Expr::Dict(Dict::Comprehension(dic))
+            }
+        },
+        Expr::BinOp(binop) => {
+            let mut args = binop.args.into_iter();
+            let lhs = folder.fold_expr(*args.next().unwrap());
+            let rhs = folder.fold_expr(*args.next().unwrap());
+            Expr::BinOp(BinOp::new(binop.op, lhs, rhs))
+        }
+        Expr::UnaryOp(unaryop) => {
+            let mut args = unaryop.args.into_iter();
+            let expr = folder.fold_expr(*args.next().unwrap());
+            Expr::UnaryOp(UnaryOp::new(unaryop.op, expr))
+        }
+        Expr::Call(call) => {
+            let obj = folder.fold_expr(*call.obj);
+            let args = fold_args(folder, call.args);
+            Expr::Call(Call::new(obj, call.attr_name, args))
+        }
+        Expr::Def(mut def) => {
+            let mut chunks = vec![];
+            for chunk in def.body.block.into_iter() {
+                chunks.push(folder.fold_expr(chunk));
+            }
+            if let Signature::Subr(mut subr) = def.sig {
+                let mut defaults = vec![];
+                for default in subr.params.defaults.into_iter() {
+                    let default_val = folder.fold_expr(default.default_val);
+                    defaults.push(DefaultParamSignature {
+                        default_val,
+                        ..default
+                    });
+                }
+                subr.params.defaults = defaults;
+                def.sig = Signature::Subr(subr);
+            }
+            let body = DefBody::new(def.body.op, Block::new(chunks), def.body.id);
+            Expr::Def(Def::new(def.sig, body))
+        }
+        Expr::ClassDef(class_def) => {
+            let Expr::Def(def) = folder.fold_expr(Expr::Def(class_def.def)) else {
+                unreachable!()
+            };
+            let methods = class_def
+                .methods_list
+                .into_iter()
+                .map(|method| enum_unwrap!(folder.fold_expr(Expr::Methods(method)), Expr::Methods))
+                .collect();
+            Expr::ClassDef(ClassDef::new(def, methods))
+        }
+        Expr::PatchDef(class_def) => {
+            let Expr::Def(def) = folder.fold_expr(Expr::Def(class_def.def)) else {
+                unreachable!()
+            };
+            let methods = class_def
+                .methods_list
+                .into_iter()
+                .map(|method| enum_unwrap!(folder.fold_expr(Expr::Methods(method)), Expr::Methods))
+                .collect();
+            Expr::PatchDef(PatchDef::new(def, methods))
+        }
+        Expr::ReDef(redef) => {
+            let expr = folder.fold_expr(*redef.expr);
+            let attr = fold_accessor(folder, redef.attr);
+            Expr::ReDef(ReDef::new(attr, expr))
+        }
+        Expr::Lambda(mut lambda) => {
+            let mut chunks = vec![];
+            for chunk in lambda.body.into_iter() {
+                chunks.push(folder.fold_expr(chunk));
+            }
+            let mut defaults = vec![];
+            for default in lambda.sig.params.defaults.into_iter() {
+                let default_val = folder.fold_expr(default.default_val);
+                defaults.push(DefaultParamSignature {
+                    default_val,
+                    ..default
+                });
+            }
+            lambda.sig.params.defaults = defaults;
+            let body = Block::new(chunks);
+            Expr::Lambda(Lambda::new(lambda.sig, lambda.op, body, lambda.id))
+        }
+        Expr::TypeAscription(tasc) => {
+            let expr = folder.fold_expr(*tasc.expr);
+            let t_spec_as_expr = folder.fold_expr(*tasc.t_spec.t_spec_as_expr);
+            let t_spec = TypeSpecWithOp::new(tasc.t_spec.op, tasc.t_spec.t_spec, t_spec_as_expr);
+            expr.type_asc_expr(t_spec)
+        }
+        Expr::Methods(method_defs) => {
+            let mut new_attrs = vec![];
+            for attr in method_defs.attrs.into_iter() {
+                let mut chunks = vec![];
+                match attr {
+                    ClassAttr::Def(def) => {
+                        for chunk in def.body.block.into_iter() {
+                            chunks.push(folder.fold_expr(chunk));
+                        }
+                        let body = DefBody::new(def.body.op, Block::new(chunks), def.body.id);
+                        new_attrs.push(ClassAttr::Def(Def::new(def.sig, body)));
+                    }
+                    ClassAttr::Decl(decl) => {
+                        let expr = folder.fold_expr(*decl.expr);
+                        let t_spec_as_expr = folder.fold_expr(*decl.t_spec.t_spec_as_expr);
+                        let t_spec =
+                            TypeSpecWithOp::new(decl.t_spec.op, decl.t_spec.t_spec, t_spec_as_expr);
+                        new_attrs.push(ClassAttr::Decl(expr.type_asc(t_spec)));
+                    }
+                    ClassAttr::Doc(doc) => {
+                        new_attrs.push(ClassAttr::Doc(doc));
+                    }
+                }
+            }
+            let new_attrs = ClassAttrs::from(new_attrs);
+            Expr::Methods(Methods::new(
+                method_defs.class,
+                *method_defs.class_as_expr,
+                method_defs.vis,
+                new_attrs,
+            ))
+        }
+        Expr::Accessor(acc) => Expr::Accessor(fold_accessor(folder, acc)),
+        Expr::Dummy(exprs) => {
+            let loc = exprs.loc;
+            let mut chunks = vec![];
+            for chunk in exprs.into_iter() {
+                chunks.push(folder.fold_expr(chunk));
+            }
+            Expr::Dummy(Dummy::new(loc, chunks))
+        }
+    }
+}
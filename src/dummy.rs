@@ -33,6 +33,14 @@ enum Inst {
     Initialize = 0x04,
     /// Informs that the connection is to be / should be terminated.
     Exit = 0x05,
+    /// Send from client to server. Queries the Erg type, fields, and pretty form of a live object.
+    /// The server replies with the result as JSON.
+    Inspect = 0x06,
+    /// Send from server to client. Represents an exception raised by the evaluated code (the module is kept).
+    Error = 0x07,
+    /// Send from client to server. Discards the module (the next evaluation starts with a fresh namespace).
+    /// The server replies with the same instruction.
+    Reset = 0x08,
     /// Informs that it is not a supported instruction.
     Unknown = 0x00,
 }
@@ -45,6 +53,9 @@ impl From<u8> for Inst {
             0x03 => Inst::Exception,
             0x04 => Inst::Initialize,
             0x05 => Inst::Exit,
+            0x06 => Inst::Inspect,
+            0x07 => Inst::Error,
+            0x08 => Inst::Reset,
             _ => Inst::Unknown,
         }
     }
@@ -273,6 +284,21 @@ impl Runnable for DummyVM {
 
     fn initialize(&mut self) {
         self.compiler.initialize();
+        if let Some(stream) = &mut self.stream {
+            // the prelude needs to be loaded again into the new namespace
+            self.compiler.initialize_generator();
+            if let Err(err) = stream.send_msg(&Message::new(Inst::Reset, None)) {
+                eprintln!("Sending error: {err}");
+                return;
+            }
+            match stream.recv_msg() {
+                Result::Ok(msg) if msg.inst == Inst::Reset => {}
+                Result::Ok(msg) => {
+                    eprintln!("Receiving unexpected inst {:?} from server", msg.inst)
+                }
+                Result::Err(err) => eprintln!("Received an error: {err}"),
+            }
+        }
     }
 
     fn clear(&mut self) {
@@ -342,14 +368,20 @@ impl Runnable for DummyVM {
                         );
                         return Err(EvalErrors::from(EvalError::system_exit()));
                     }
+                    // The variables that failed to be defined at runtime must not be visible to the type checker
                     Inst::Initialize => {
                         self.compiler.initialize_generator();
+                        self.compiler.undo();
+                        String::from_utf8(msg.data.unwrap_or_default())
+                    }
+                    Inst::Error => {
+                        self.compiler.undo();
                         String::from_utf8(msg.data.unwrap_or_default())
                     }
                     Inst::Print => String::from_utf8(msg.data.unwrap_or_default()),
                     Inst::Exit => err_handle!("Receiving inst {:?} from server", msg.inst),
                     // `load` can only be sent from the client to the server
-                    Inst::Load | Inst::Unknown => {
+                    Inst::Load | Inst::Inspect | Inst::Reset | Inst::Unknown => {
                         err_handle!("Receiving unexpected inst {:?} from server", msg.inst)
                     }
                 };
@@ -384,6 +416,24 @@ impl Runnable for DummyVM {
         }
         Ok(res)
     }
+
+    /// Only the type environment is rolled back;
+    /// the objects already created on the Python side remain.
+    fn undo(&mut self) -> bool {
+        self.compiler.undo()
+    }
+
+    fn accepted_inputs(&self) -> &[String] {
+        self.compiler.accepted_inputs()
+    }
+
+    fn completion_words(&self) -> Vec<String> {
+        self.compiler.completion_words()
+    }
+
+    fn stats(&self) -> Option<String> {
+        self.compiler.stats()
+    }
 }
 
 impl DummyVM {
@@ -396,4 +446,30 @@ impl DummyVM {
     pub fn eval(&mut self, src: String) -> Result<String, EvalErrors> {
         Runnable::eval(self, src)
     }
+
+    /// Queries the Erg type, fields, and pretty form of a live object (e.g. `x`, `x.y`).
+    /// The result is JSON: `{"type": "C", "pretty": "C {x = 1}", "fields": [{"name": "x", "type": "Nat", "pretty": "1"}]}`
+    /// (or `{"error": "..."}`).
+    /// Returns `None` if the VM is not running in REPL mode.
+    pub fn inspect(&mut self, name: &str) -> Option<String> {
+        let stream = self.stream.as_mut()?;
+        let msg = Message::new(Inst::Inspect, Some(name.as_bytes().to_vec()));
+        if let Err(err) = stream.send_msg(&msg) {
+            eprintln!("Sending error: {err}");
+            return None;
+        }
+        match stream.recv_msg() {
+            Result::Ok(msg) if msg.inst == Inst::Inspect => {
+                String::from_utf8(msg.data.unwrap_or_default()).ok()
+            }
+            Result::Ok(msg) => {
+                eprintln!("Receiving unexpected inst {:?} from server", msg.inst);
+                None
+            }
+            Result::Err(err) => {
+                eprintln!("Received an error: {err}");
+                None
+            }
+        }
+    }
 }
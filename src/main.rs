@@ -11,7 +11,15 @@ use erg_parser::lex::LexerRunner;
 use erg_parser::ParserRunner;
 
 use erg_compiler::build_hir::HIRBuilder;
+use erg_compiler::decl_gen::DeclGenerator;
+use erg_compiler::decl_test::DeclTester;
+use erg_compiler::dump::Dumper;
+use erg_compiler::fix::Fixer;
 use erg_compiler::lower::ASTLowerer;
+use erg_compiler::migrate::Migrator;
+use erg_compiler::module_graph::GraphPrinter;
+use erg_compiler::nil_audit::NilAuditor;
+use erg_compiler::pyi_gen::PyiGenerator;
 use erg_compiler::transpile::Transpiler;
 use erg_compiler::ty::deserialize::Deserializer;
 use erg_compiler::Compiler;
@@ -28,6 +36,29 @@ fn run() {
         FullCheck => HIRBuilder::run(cfg),
         Compile => Compiler::run(cfg),
         Transpile => Transpiler::run(cfg),
+        Migrate => Migrator::run(cfg),
+        TypeAt => {
+            #[cfg(feature = "els")]
+            {
+                els::run_type_at(cfg)
+            }
+            #[cfg(not(feature = "els"))]
+            {
+                eprintln!("This version of the build does not support type-at mode");
+                ExitStatus::ERR1
+            }
+        }
+        Test if cfg.test_decls => DeclTester::run(cfg),
+        Audit => NilAuditor::run(cfg),
+        Declare => DeclGenerator::run(cfg),
+        Stub => PyiGenerator::run(cfg),
+        Fix => Fixer::run(cfg),
+        Dump => Dumper::run(cfg),
+        Graph => GraphPrinter::run(cfg),
+        Test => {
+            eprintln!("`erg test` currently supports only `--decls`");
+            ExitStatus::ERR1
+        }
         Execute => DummyVM::run(cfg),
         Read => Deserializer::run(cfg),
         LanguageServer => {
@@ -3,6 +3,8 @@ import socket
 import sys
 import importlib
 import io
+import json
+import marshal
 import traceback
 
 class INST:
@@ -18,6 +20,15 @@ class INST:
     INITIALIZE = 0x04
     # Informs that the connection is to be / should be terminated.
     EXIT = 0x05
+    # Send from client to server. Queries the Erg type, fields, and pretty form of a live object (the data is its name).
+    # The server replies with the result as JSON (see _erg_inspect.py).
+    INSPECT = 0x06
+    # Send from server to client. Represents an exception raised by the evaluated code.
+    # Unlike INITIALIZE, the module (and the objects created so far) is kept.
+    ERROR = 0x07
+    # Send from client to server. Discards the module (the next LOAD imports it again with a fresh namespace).
+    # The server replies with the same instruction.
+    RESET = 0x08
 
 class MessageStream:
     def __init__(self, socket):
@@ -55,9 +66,49 @@ already_loaded = False
 ctx = {'importlib': importlib}
 client_stream = MessageStream(client_socket)
 
+# The .pyc file only contains the code of the last input
+def load_code(path):
+    with open(path, 'rb') as f:
+        # skip the header (magic number, flags, timestamp, and size)
+        return marshal.loads(f.read()[16:])
+
+def erg_inspect():
+    try:
+        # the std path is added by the prelude of the module
+        import _erg_inspect
+        return _erg_inspect
+    except ImportError:
+        return None
+
+def displayhook(value):
+    insp = erg_inspect()
+    if insp is None:
+        sys.__displayhook__(value)
+    else:
+        insp.displayhook(value)
+
+sys.displayhook = displayhook
+
+# e.g. x => x or ::x_L1 (private variables are mangled with the definition location)
+def resolve_name(name):
+    module = vars(ctx['__MODULE__'.split('.')[0]])
+    for part in '__MODULE__'.split('.')[1:]:
+        module = vars(module[part])
+    head, *attrs = name.replace('!', '__erg_proc__').split('.')
+    if head in module:
+        obj = module[head]
+    else:
+        mangled = [key for key in module if key == '::' + head or key.startswith('::' + head + '_L')]
+        if not mangled:
+            raise NameError(f'{head} is not defined')
+        obj = module[mangled[-1]]
+    for attr in attrs:
+        obj = getattr(obj, attr)
+    return obj
+
 while True:
     try:
-        inst, _ = client_stream.recv_msg()
+        inst, data = client_stream.recv_msg()
     except ConnectionResetError: # when the server was crashed
         break
     if inst == INST.EXIT: # when the server was closed successfully
@@ -71,8 +122,11 @@ while True:
         buf = []
         try:
             if already_loaded:
+                # Only the new code is executed in the namespace of the module,
+                # so the objects created by the previous inputs are kept as they are
                 # __MODULE__ will be replaced with module name
-                res = str(exec('importlib.reload(__MODULE__)', ctx))
+                module = sys.modules['__MODULE__']
+                res = str(exec(load_code(module.__file__), vars(module)))
             else:
                 res = str(exec('import __MODULE__', ctx))
             already_loaded = True
@@ -86,13 +140,27 @@ while True:
                 excs = traceback.format_exception_only(e.__class__, e)
             exc = ''.join(excs).rstrip()
             traceback.clear_frames(e.__traceback__)
-            resp_inst = INST.INITIALIZE
+            # If the first import fails, the module is discarded (the prelude needs to be loaded again)
+            resp_inst = INST.ERROR if already_loaded else INST.INITIALIZE
         out = sys.stdout.getvalue()[:-1]
         if out and exc or res:
             out += '\n'
         res = out + exc + res
         buf.append(res)
         client_stream.send_msg(resp_inst, ''.join(buf))
+    elif inst == INST.RESET:
+        sys.modules.pop('__MODULE__', None)
+        already_loaded = False
+        client_stream.send_msg(INST.RESET)
+    elif inst == INST.INSPECT:
+        insp = erg_inspect()
+        try:
+            if insp is None:
+                raise ImportError('_erg_inspect is not available')
+            res = insp.inspect(resolve_name(data))
+        except Exception as e:
+            res = json.dumps({'error': ''.join(traceback.format_exception_only(e.__class__, e)).rstrip()})
+        client_stream.send_msg(INST.INSPECT, res)
     else:
         client_stream.send_msg(INST.UNKNOWN)
 
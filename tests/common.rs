@@ -115,7 +115,16 @@ pub(crate) fn expect_repl_failure(
 }
 
 pub(crate) fn expect_end_with(file_path: &'static str, code: i32) -> Result<(), ()> {
-    match exec_file(file_path) {
+    end_with(exec_file(file_path), code)
+}
+
+/// Executes the file with `--runtime-checks`
+pub(crate) fn expect_end_with_runtime_checks(file_path: &'static str, code: i32) -> Result<(), ()> {
+    end_with(exec_file_with_runtime_checks(file_path), code)
+}
+
+fn end_with(result: Result<ExitStatus, CompileErrors>, code: i32) -> Result<(), ()> {
+    match result {
         Ok(stat) if stat.succeed() => {
             println!("err: should end with {code}, but end with 0");
             Err(())
@@ -143,7 +152,24 @@ pub(crate) fn expect_failure(
     num_warns: usize,
     num_errs: usize,
 ) -> Result<(), ()> {
-    match exec_file(file_path) {
+    failure(exec_file(file_path), num_warns, num_errs)
+}
+
+/// Executes the file with `--strict`
+pub(crate) fn expect_failure_strict(
+    file_path: &'static str,
+    num_warns: usize,
+    num_errs: usize,
+) -> Result<(), ()> {
+    failure(exec_file_strict(file_path), num_warns, num_errs)
+}
+
+fn failure(
+    result: Result<ExitStatus, CompileErrors>,
+    num_warns: usize,
+    num_errs: usize,
+) -> Result<(), ()> {
+    match result {
         Ok(stat) if stat.succeed() => {
             println!("err: should fail, but end with 0");
             Err(())
@@ -197,8 +223,16 @@ fn set_cfg(mut cfg: ErgConfig) -> ErgConfig {
 /// The test is intend to run only on 3.11 for fast execution.
 /// To execute on other versions, change the version and magic number.
 fn _exec_file(file_path: &'static str) -> Result<ExitStatus, CompileErrors> {
+    _exec_file_with(file_path, |_| {})
+}
+
+fn _exec_file_with(
+    file_path: &'static str,
+    configure: fn(&mut ErgConfig),
+) -> Result<ExitStatus, CompileErrors> {
     println!("{DEBUG_MAIN}[test] exec {file_path}{RESET}");
     let mut cfg = ErgConfig::with_main_path(PathBuf::from(file_path));
+    configure(&mut cfg);
     cfg.output = if DEBUG_MODE {
         Output::stdout()
     } else {
@@ -231,6 +265,22 @@ pub(crate) fn exec_file(file_path: &'static str) -> Result<ExitStatus, CompileEr
     exec_new_thread(move || _exec_file(file_path), file_path)
 }
 
+pub(crate) fn exec_file_with_runtime_checks(
+    file_path: &'static str,
+) -> Result<ExitStatus, CompileErrors> {
+    exec_new_thread(
+        move || _exec_file_with(file_path, |cfg| cfg.runtime_checks = true),
+        file_path,
+    )
+}
+
+pub(crate) fn exec_file_strict(file_path: &'static str) -> Result<ExitStatus, CompileErrors> {
+    exec_new_thread(
+        move || _exec_file_with(file_path, |cfg| cfg.strict = true),
+        file_path,
+    )
+}
+
 pub(crate) fn exec_repl(
     name: &'static str,
     lines: Vec<String>,
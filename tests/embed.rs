@@ -17,6 +17,70 @@ fn test_vm_embedding() -> Result<(), ()> {
     Ok(())
 }
 
+#[test]
+fn test_vm_eval_side_effect_and_rollback() -> Result<(), ()> {
+    let mut vm = DummyVM::default();
+    let out = vm.eval("print! \"side effect\"".into()).map_err(|es| {
+        es.write_all_stderr();
+    })?;
+    assert_eq!(out.matches("side effect").count(), 1, "{out}");
+    // the previous input must not be executed again
+    let out = vm.eval("x = 1".into()).map_err(|es| {
+        es.write_all_stderr();
+    })?;
+    assert!(!out.contains("side effect"), "{out}");
+    let out = vm.eval("y = 1 // 0".into()).map_err(|es| {
+        es.write_all_stderr();
+    })?;
+    assert!(out.contains("ZeroDivisionError"), "{out}");
+    // `y` failed to be defined at runtime, so it is rolled back
+    vm.eval("print! y".into()).expect_err("should err");
+    let out = vm.eval("print! x".into()).map_err(|es| {
+        es.write_all_stderr();
+    })?;
+    assert_eq!(out, "1");
+    Ok(())
+}
+
+#[test]
+fn test_vm_inspect() -> Result<(), ()> {
+    let mut vm = DummyVM::default();
+    let src =
+        "C = Class {x = Int; .y = Str}\nc = C.new {x = 1; .y = \"a\"}\nr = {i = 1; s = [\"b\"]}";
+    vm.eval(src.into()).map_err(|es| {
+        es.write_all_stderr();
+    })?;
+    let res = vm.inspect("c").ok_or(())?;
+    assert!(res.contains(r#""type": "C""#), "{res}");
+    assert!(
+        res.contains(r#""pretty": "C {x = 1; .y = \"a\"}""#),
+        "{res}"
+    );
+    assert!(
+        res.contains(r#"{"name": "x", "type": "Nat", "pretty": "1"}"#),
+        "{res}"
+    );
+    let res = vm.inspect("r").ok_or(())?;
+    assert!(res.contains(r#""pretty": "{i = 1; s = [\"b\"]}""#), "{res}");
+    let shown = vm.eval("c".into()).map_err(|es| {
+        es.write_all_stderr();
+    })?;
+    assert_eq!(shown, "C {x = 1; .y = \"a\"}");
+    let src = "Shape = Enum {Circle(Float); Empty}\nIntArr = Inherit Array(Int, 3)";
+    vm.eval(src.into()).map_err(|es| {
+        es.write_all_stderr();
+    })?;
+    let shown = vm
+        .eval("(Circle.new(1.0), Empty.new(), IntArr.new([1, 2, 3]))".into())
+        .map_err(|es| {
+            es.write_all_stderr();
+        })?;
+    assert_eq!(shown, "(Circle(1.0), Empty, IntArr [1, 2, 3])");
+    let res = vm.inspect("undefined").ok_or(())?;
+    assert!(res.contains(r#""error": "NameError"#), "{res}");
+    Ok(())
+}
+
 #[test]
 fn test_transpiler_embedding() -> Result<(), ()> {
     let mut trans = Transpiler::default();
@@ -195,6 +195,58 @@ fn exec_repl_invalid_def_after_the_at_sign() -> Result<(), ()> {
     )
 }
 
+#[test]
+#[ignore]
+fn exec_repl_runtime_error() -> Result<(), ()> {
+    expect_repl_failure(
+        "repl_runtime_error",
+        ["x = 1", "y = 1 // 0", "print! x", "print! y", "exit()"]
+            .into_iter()
+            .map(|x| x.to_string())
+            .collect(),
+        1,
+    )
+}
+
+#[test]
+#[ignore]
+fn exec_repl_del_and_reset() -> Result<(), ()> {
+    expect_repl_failure(
+        "repl_del_and_reset",
+        [
+            "x = 1",
+            ":del x",
+            "print! x",
+            "x = \"a\"",
+            "assert x == \"a\"",
+            "y = 1",
+            ":reset",
+            "print! y",
+            "exit()",
+        ]
+        .into_iter()
+        .map(|x| x.to_string())
+        .collect(),
+        2,
+    )
+}
+
+#[test]
+#[ignore]
+fn exec_repl_load() -> Result<(), ()> {
+    expect_repl_success(
+        "repl_load",
+        [
+            ":load tests/should_ok/addition.er",
+            "assert add(2, 3) == 5",
+            "exit()",
+        ]
+        .into_iter()
+        .map(|x| x.to_string())
+        .collect(),
+    )
+}
+
 #[test]
 #[ignore]
 fn exec_repl_server_mock_test() -> Result<(), ()> {
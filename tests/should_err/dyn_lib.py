@@ -0,0 +1,9 @@
+def parse(s):
+    return int(s)
+
+
+def echo(x):
+    return x
+
+
+config = {"name": "erg", "port": 8080}
@@ -0,0 +1,32 @@
+import sys
+from dataclasses import dataclass
+from typing import NamedTuple, Protocol, overload
+
+VERSION: str
+MAX_SIZE = 100
+
+class Shape:
+    def __init__(self, name: str) -> None: ...
+    def area(self) -> float: ...
+
+if sys.version_info >= (3, 0):
+    @overload
+    def parse(x: int) -> int: ...
+    @overload
+    def parse(x: str) -> str: ...
+else:
+    def parse(x: object) -> object: ...
+
+class SupportsClose(Protocol):
+    def close(self) -> None: ...
+
+def closing(x: SupportsClose) -> None: ...
+
+@dataclass
+class Config:
+    path: str
+    retries: int = 3
+
+class Pair(NamedTuple):
+    key: str
+    value: int
@@ -0,0 +1,2 @@
+def greet(self):
+    return f"Hello, {self.name}!"
@@ -1,7 +1,15 @@
 mod common;
-use common::{expect_compile_success, expect_end_with, expect_failure, expect_success};
+use common::{
+    expect_compile_success, expect_end_with, expect_end_with_runtime_checks, expect_failure,
+    expect_failure_strict, expect_success,
+};
 use erg_common::python_util::{module_exists, opt_which_python};
 
+#[test]
+fn exec_abstract() -> Result<(), ()> {
+    expect_success("tests/should_ok/abstract.er", 2)
+}
+
 #[test]
 fn exec_addition_ok() -> Result<(), ()> {
     expect_success("tests/should_ok/addition.er", 0)
@@ -12,11 +20,36 @@ fn exec_advanced_type_spec() -> Result<(), ()> {
     expect_success("tests/should_ok/advanced_type_spec.er", 5)
 }
 
+#[test]
+fn exec_assert_static() -> Result<(), ()> {
+    expect_success("tests/should_ok/assert_static.er", 0)
+}
+
+#[test]
+fn exec_argv() -> Result<(), ()> {
+    expect_success("tests/should_ok/argv.er", 0)
+}
+
 #[test]
 fn exec_array() -> Result<(), ()> {
     expect_success("tests/should_ok/array.er", 0)
 }
 
+#[test]
+fn exec_array_length() -> Result<(), ()> {
+    expect_success("tests/should_ok/array_length.er", 0)
+}
+
+#[test]
+fn exec_attr_lookup() -> Result<(), ()> {
+    expect_success("tests/should_ok/attr_lookup.er", 0)
+}
+
+#[test]
+fn exec_cimport() -> Result<(), ()> {
+    expect_success("tests/should_ok/cimport.er", 0)
+}
+
 #[test]
 fn exec_class() -> Result<(), ()> {
     expect_success("examples/class.er", 0)
@@ -42,6 +75,21 @@ fn exec_comment() -> Result<(), ()> {
     expect_success("tests/should_ok/comment.er", 0)
 }
 
+#[test]
+fn exec_conditional_binding() -> Result<(), ()> {
+    expect_success("tests/should_ok/conditional_binding.er", 0)
+}
+
+#[test]
+fn exec_const_eval() -> Result<(), ()> {
+    expect_success("tests/should_ok/const_eval.er", 0)
+}
+
+#[test]
+fn exec_const_method() -> Result<(), ()> {
+    expect_success("tests/should_ok/const_method.er", 0)
+}
+
 #[test]
 fn exec_control() -> Result<(), ()> {
     expect_success("examples/control.er", 2)
@@ -57,11 +105,57 @@ fn exec_dependent() -> Result<(), ()> {
     expect_success("tests/should_ok/dependent.er", 0)
 }
 
+#[test]
+fn exec_derive() -> Result<(), ()> {
+    expect_success("tests/should_ok/derive.er", 0)
+}
+
+#[test]
+fn exec_derivable() -> Result<(), ()> {
+    expect_success("tests/should_ok/derivable.er", 0)
+}
+
 #[test]
 fn exec_dict() -> Result<(), ()> {
     expect_success("examples/dict.er", 0)
 }
 
+#[test]
+fn exec_dynamic() -> Result<(), ()> {
+    expect_success("tests/should_ok/dynamic.er", 0)
+}
+
+#[test]
+fn exec_edition() -> Result<(), ()> {
+    expect_success("tests/should_ok/edition.er", 0)
+}
+
+#[test]
+fn exec_effect_policy_package() -> Result<(), ()> {
+    expect_success("tests/should_ok/effect_policy_pkg/src/plugins/math.er", 0)?;
+    expect_success("tests/should_ok/effect_policy_pkg/src/sandbox.er", 0)
+}
+
+#[test]
+fn exec_ellipsis() -> Result<(), ()> {
+    expect_success("tests/should_ok/ellipsis.er", 0)
+}
+
+#[test]
+fn exec_enum() -> Result<(), ()> {
+    expect_success("tests/should_ok/enum.er", 0)
+}
+
+#[test]
+fn exec_exact() -> Result<(), ()> {
+    expect_success("tests/should_ok/exact.er", 0)
+}
+
+#[test]
+fn exec_export() -> Result<(), ()> {
+    expect_success("tests/should_ok/export/export.er", 0)
+}
+
 #[test]
 fn exec_external() -> Result<(), ()> {
     let py_command = opt_which_python().unwrap();
@@ -77,6 +171,11 @@ fn exec_fib() -> Result<(), ()> {
     expect_success("examples/fib.er", 0)
 }
 
+#[test]
+fn exec_forward_ref() -> Result<(), ()> {
+    expect_success("tests/should_ok/forward_ref.er", 0)
+}
+
 #[test]
 fn exec_helloworld() -> Result<(), ()> {
     // HACK: When running the test with Windows, the exit code is 1 (the cause is unknown)
@@ -108,11 +207,26 @@ fn exec_import_cyclic() -> Result<(), ()> {
     expect_success("tests/should_ok/cyclic/import.er", 0)
 }
 
+#[test]
+fn exec_import_cyclic_interface() -> Result<(), ()> {
+    expect_success("tests/should_ok/cyclic_interface/import.er", 0)
+}
+
+#[test]
+fn exec_index_proof() -> Result<(), ()> {
+    expect_success("tests/should_ok/index_proof.er", 0)
+}
+
 #[test]
 fn exec_inherit() -> Result<(), ()> {
     expect_success("tests/should_ok/inherit.er", 0)
 }
 
+#[test]
+fn exec_inherit_poly() -> Result<(), ()> {
+    expect_success("tests/should_ok/inherit_poly.er", 0)
+}
+
 #[test]
 fn exec_infer_class() -> Result<(), ()> {
     expect_success("tests/should_ok/infer_class.er", 0)
@@ -128,11 +242,31 @@ fn exec_int() -> Result<(), ()> {
     expect_success("tests/should_ok/int.er", 0)
 }
 
+#[test]
+fn exec_int_refinement() -> Result<(), ()> {
+    expect_success("tests/should_ok/int_refinement.er", 0)
+}
+
 #[test]
 fn exec_interpolation() -> Result<(), ()> {
     expect_success("tests/should_ok/interpolation.er", 0)
 }
 
+#[test]
+fn exec_into() -> Result<(), ()> {
+    expect_success("tests/should_ok/into.er", 0)
+}
+
+#[test]
+fn exec_kwargs_unpack() -> Result<(), ()> {
+    expect_compile_success("tests/should_ok/kwargs_unpack.er", 0)
+}
+
+#[test]
+fn exec_lens() -> Result<(), ()> {
+    expect_success("tests/should_ok/lens.er", 0)
+}
+
 #[test]
 fn exec_long() -> Result<(), ()> {
     expect_success("tests/should_ok/long.er", 257)
@@ -153,6 +287,16 @@ fn exec_map() -> Result<(), ()> {
     expect_success("tests/should_ok/map.er", 0)
 }
 
+#[test]
+fn exec_match_literal() -> Result<(), ()> {
+    expect_success("tests/should_ok/match_literal.er", 0)
+}
+
+#[test]
+fn exec_method_kind() -> Result<(), ()> {
+    expect_success("tests/should_ok/method_kind.er", 0)
+}
+
 #[test]
 fn exec_mut() -> Result<(), ()> {
     expect_success("examples/mut.er", 0)
@@ -173,6 +317,16 @@ fn exec_nested() -> Result<(), ()> {
     expect_success("tests/should_ok/nested.er", 3)
 }
 
+#[test]
+fn exec_overload() -> Result<(), ()> {
+    expect_success("tests/should_ok/overload.er", 0)
+}
+
+#[test]
+fn exec_partial() -> Result<(), ()> {
+    expect_success("tests/should_ok/partial.er", 0)
+}
+
 #[test]
 fn exec_patch() -> Result<(), ()> {
     expect_success("examples/patch.er", 0)
@@ -183,6 +337,32 @@ fn exec_pattern() -> Result<(), ()> {
     expect_success("tests/should_ok/pattern.er", 0)
 }
 
+#[test]
+fn exec_prelude_package() -> Result<(), ()> {
+    expect_success("tests/should_ok/prelude_pkg/src/main.er", 0)
+}
+
+#[test]
+fn exec_property() -> Result<(), ()> {
+    expect_success("tests/should_ok/property.er", 0)
+}
+
+#[test]
+fn exec_py_compat() -> Result<(), ()> {
+    expect_success("tests/should_ok/py_compat.er", 0)
+}
+
+#[test]
+fn exec_py_compat_package() -> Result<(), ()> {
+    expect_success("tests/should_ok/py_compat_pkg/src/legacy.er", 0)?;
+    expect_success("tests/should_ok/py_compat_pkg/src/compat/legacy.er", 0)
+}
+
+#[test]
+fn exec_py_inline() -> Result<(), ()> {
+    expect_success("tests/should_ok/py_inline.er", 0)
+}
+
 #[test]
 fn exec_pyimport_test() -> Result<(), ()> {
     expect_success("tests/should_ok/pyimport.er", 2)
@@ -193,6 +373,11 @@ fn exec_quantified() -> Result<(), ()> {
     expect_success("examples/quantified.er", 1)
 }
 
+#[test]
+fn exec_quantity() -> Result<(), ()> {
+    expect_success("tests/should_ok/quantity.er", 0)
+}
+
 #[test]
 fn exec_raw_ident() -> Result<(), ()> {
     expect_success("examples/raw_ident.er", 1)
@@ -203,11 +388,22 @@ fn exec_rec() -> Result<(), ()> {
     expect_success("tests/should_ok/rec.er", 0)
 }
 
+/// The runtime value of the alias is not supported yet, so this is a compile-only test
+#[test]
+fn exec_rec_alias() -> Result<(), ()> {
+    expect_compile_success("tests/should_ok/rec_alias.er", 0)
+}
+
 #[test]
 fn exec_record() -> Result<(), ()> {
     expect_success("examples/record.er", 0)
 }
 
+#[test]
+fn exec_record_types() -> Result<(), ()> {
+    expect_success("tests/should_ok/record_types.er", 0)
+}
+
 #[test]
 fn exec_refinement() -> Result<(), ()> {
     expect_success("tests/should_ok/refinement.er", 0)
@@ -218,6 +414,21 @@ fn exec_return() -> Result<(), ()> {
     expect_success("tests/should_ok/return.er", 0)
 }
 
+#[test]
+fn exec_shadowed_type_params() -> Result<(), ()> {
+    expect_success("tests/should_ok/shadowed_type_params.er", 0)
+}
+
+#[test]
+fn exec_slice() -> Result<(), ()> {
+    expect_success("tests/should_ok/slice.er", 0)
+}
+
+#[test]
+fn exec_spread_args() -> Result<(), ()> {
+    expect_success("tests/should_ok/spread_args.er", 0)
+}
+
 #[test]
 fn exec_structural_example() -> Result<(), ()> {
     expect_success("examples/structural.er", 0)
@@ -243,6 +454,16 @@ fn exec_tuple() -> Result<(), ()> {
     expect_success("examples/tuple.er", 0)
 }
 
+#[test]
+fn exec_typed_dict() -> Result<(), ()> {
+    expect_success("tests/should_ok/typed_dict.er", 0)
+}
+
+#[test]
+fn exec_unit() -> Result<(), ()> {
+    expect_success("tests/should_ok/unit.er", 0)
+}
+
 #[test]
 fn exec_unit_test() -> Result<(), ()> {
     expect_success("examples/unit_test.er", 0)
@@ -258,6 +479,12 @@ fn exec_unused_import() -> Result<(), ()> {
     expect_success("tests/should_ok/many_import/unused_import.er", 2)
 }
 
+#[test]
+fn exec_unused_result() -> Result<(), ()> {
+    // 4 warns: discarded results
+    expect_success("tests/should_ok/unused_result.er", 4)
+}
+
 #[test]
 fn exec_use_py() -> Result<(), ()> {
     expect_success("examples/use_py.er", 0)
@@ -273,11 +500,21 @@ fn exec_with() -> Result<(), ()> {
     expect_success("examples/with.er", 0)
 }
 
+#[test]
+fn exec_abstract_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/abstract.er", 0, 4)
+}
+
 #[test]
 fn exec_addition_err() -> Result<(), ()> {
     expect_failure("tests/should_err/addition.er", 3, 9)
 }
 
+#[test]
+fn exec_assert_static_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/assert_static.er", 0, 3)
+}
+
 #[test]
 fn exec_args() -> Result<(), ()> {
     expect_failure("tests/should_err/args.er", 0, 16)
@@ -288,6 +525,11 @@ fn exec_array_err() -> Result<(), ()> {
     expect_failure("examples/array.er", 0, 1)
 }
 
+#[test]
+fn exec_array_length_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/array_length.er", 0, 5)
+}
+
 #[test]
 fn exec_as() -> Result<(), ()> {
     expect_failure("tests/should_err/as.er", 0, 6)
@@ -298,6 +540,11 @@ fn exec_assert_cast() -> Result<(), ()> {
     expect_failure("examples/assert_cast.er", 0, 3)
 }
 
+#[test]
+fn exec_cimport_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/cimport.er", 0, 4)
+}
+
 #[test]
 fn exec_class_attr_err() -> Result<(), ()> {
     expect_failure("tests/should_err/class_attr.er", 1, 1)
@@ -308,11 +555,66 @@ fn exec_collection_err() -> Result<(), ()> {
     expect_failure("tests/should_err/collection.er", 0, 4)
 }
 
+#[test]
+fn exec_conditional_binding_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/conditional_binding.er", 0, 4)
+}
+
+#[test]
+fn exec_const_eval_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/const_eval.er", 0, 4)
+}
+
+#[test]
+fn exec_cyclic_def_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/cyclic_def.er", 0, 3)
+}
+
 #[test]
 fn exec_dependent_err() -> Result<(), ()> {
     expect_failure("tests/should_err/dependent.er", 0, 5)
 }
 
+#[test]
+fn exec_derive_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/derive.er", 0, 8)
+}
+
+#[test]
+fn exec_edition_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/edition.er", 0, 1)
+}
+
+#[test]
+fn exec_edition_disabled_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/edition_disabled.er", 0, 1)
+}
+
+#[test]
+fn exec_effect_policy_package_err() -> Result<(), ()> {
+    expect_failure(
+        "tests/should_err/effect_policy_pkg/src/plugins/log.er",
+        0,
+        3,
+    )?;
+    expect_failure("tests/should_err/effect_policy_pkg/src/sandbox.er", 0, 4)
+}
+
+#[test]
+fn exec_enum_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/enum.er", 0, 4)
+}
+
+#[test]
+fn exec_exact_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/exact.er", 0, 2)
+}
+
+#[test]
+fn exec_export_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/export.er", 0, 2)
+}
+
 #[test]
 fn exec_err_import() -> Result<(), ()> {
     expect_failure("tests/should_err/err_import.er", 0, 9)
@@ -339,11 +641,31 @@ fn exec_import_cyclic_err() -> Result<(), ()> {
     expect_failure("tests/should_err/cyclic/import.er", 0, 1)
 }
 
+#[test]
+fn exec_index_proof_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/index_proof.er", 0, 5)
+}
+
+#[test]
+fn exec_inherit_poly_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/inherit_poly.er", 0, 7)
+}
+
 #[test]
 fn exec_infer_union_array() -> Result<(), ()> {
     expect_failure("tests/should_err/infer_union_array.er", 2, 1)
 }
 
+#[test]
+fn exec_int_refinement_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/int_refinement.er", 0, 3)
+}
+
+#[test]
+fn exec_into_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/into.er", 0, 2)
+}
+
 #[test]
 fn exec_invalid_interpol() -> Result<(), ()> {
     expect_failure("tests/should_err/invalid_interpol.er", 0, 2)
@@ -354,11 +676,46 @@ fn exec_invalid_param() -> Result<(), ()> {
     expect_failure("tests/should_err/invalid_param.er", 0, 3)
 }
 
+#[test]
+fn exec_kwargs_unpack_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/kwargs_unpack.er", 0, 6)
+}
+
+#[test]
+fn exec_lens_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/lens.er", 0, 5)
+}
+
 #[test]
 fn exec_move_check() -> Result<(), ()> {
     expect_failure("examples/move_check.er", 1, 1)
 }
 
+#[test]
+fn exec_prelude_package_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/prelude_pkg/main.er", 0, 3)
+}
+
+#[test]
+fn exec_py_inline_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/py_inline.er", 0, 3)
+}
+
+#[test]
+fn exec_py_inline_effect() -> Result<(), ()> {
+    expect_failure("tests/should_err/py_inline_effect.er", 0, 1)
+}
+
+#[test]
+fn exec_pyi_stub() -> Result<(), ()> {
+    expect_failure("tests/should_err/pyi_stub.er", 0, 7)
+}
+
+#[test]
+fn exec_runtime_check() -> Result<(), ()> {
+    expect_end_with_runtime_checks("tests/should_err/runtime_check.er", 1)
+}
+
 #[test]
 fn exec_pyimport() -> Result<(), ()> {
     if cfg!(unix) {
@@ -378,6 +735,21 @@ fn exec_side_effect() -> Result<(), ()> {
     expect_failure("examples/side_effect.er", 5, 4)
 }
 
+#[test]
+fn exec_slice_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/slice.er", 0, 4)
+}
+
+#[test]
+fn exec_spread_args_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/spread_args.er", 0, 5)
+}
+
+#[test]
+fn exec_strict_err() -> Result<(), ()> {
+    expect_failure_strict("tests/should_err/strict.er", 0, 6)
+}
+
 #[test]
 fn exec_structural_err() -> Result<(), ()> {
     expect_failure("tests/should_err/structural.er", 1, 9)
@@ -388,16 +760,36 @@ fn exec_subtyping_err() -> Result<(), ()> {
     expect_failure("tests/should_err/subtyping.er", 0, 17)
 }
 
+#[test]
+fn exec_trait_impl_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/trait_impl.er", 0, 1)
+}
+
 #[test]
 fn exec_tuple_err() -> Result<(), ()> {
     expect_failure("tests/should_err/tuple.er", 0, 1)
 }
 
+#[test]
+fn exec_typed_dict_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/typed_dict.er", 0, 4)
+}
+
+#[test]
+fn exec_unit_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/unit.er", 0, 7)
+}
+
 #[test]
 fn exec_callable() -> Result<(), ()> {
     expect_failure("tests/should_err/callable.er", 0, 6)
 }
 
+#[test]
+fn exec_method_kind_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/method_kind.er", 0, 3)
+}
+
 #[test]
 fn exec_move() -> Result<(), ()> {
     expect_failure("tests/should_err/move.er", 1, 2)
@@ -423,11 +815,46 @@ fn exec_mut_dict_err() -> Result<(), ()> {
     expect_failure("tests/should_err/mut_dict.er", 0, 3)
 }
 
+#[test]
+fn exec_overload_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/overload.er", 0, 2)
+}
+
+#[test]
+fn exec_partial_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/partial.er", 0, 4)
+}
+
+#[test]
+fn exec_property_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/property.er", 0, 3)
+}
+
+#[test]
+fn exec_py_compat_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/py_compat.er", 0, 2)
+}
+
 #[test]
 fn exec_quantified_err() -> Result<(), ()> {
     expect_failure("tests/should_err/quantified.er", 0, 3)
 }
 
+#[test]
+fn exec_quantity_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/quantity.er", 0, 3)
+}
+
+#[test]
+fn exec_rec_alias_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/rec_alias.er", 0, 3)
+}
+
+#[test]
+fn exec_record_types_err() -> Result<(), ()> {
+    expect_failure("tests/should_err/record_types.er", 0, 5)
+}
+
 #[test]
 fn exec_refinement_err() -> Result<(), ()> {
     expect_failure("tests/should_err/refinement.er", 0, 8)